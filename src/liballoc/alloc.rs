@@ -179,11 +179,9 @@ unsafe fn exchange_malloc(size: usize, align: usize) -> *mut u8 {
         align as *mut u8
     } else {
         let layout = Layout::from_size_align_unchecked(size, align);
-        let ptr = alloc(layout);
-        if !ptr.is_null() {
-            ptr
-        } else {
-            handle_alloc_error(layout)
+        match retry_on_oom(layout, || NonNull::new(alloc(layout)).ok_or(AllocErr)) {
+            Ok(ptr) => ptr.as_ptr(),
+            Err(_) => handle_alloc_error(layout),
         }
     }
 }
@@ -224,6 +222,196 @@ pub fn handle_alloc_error(layout: Layout) -> ! {
     unsafe { oom_impl(layout) }
 }
 
+/// What the hook registered with [`set_oom_hook`] asks for after an
+/// allocation failed.
+///
+/// [`set_oom_hook`]: fn.set_oom_hook.html
+#[unstable(feature = "oom_hook", issue = "0")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OomAction {
+    /// Memory was freed, and the allocation should be attempted again.
+    Retry,
+    /// Nothing more can be done; give up and call [`handle_alloc_error`].
+    ///
+    /// [`handle_alloc_error`]: fn.handle_alloc_error.html
+    Abort,
+}
+
+/// The number of times one allocation is retried at the request of the OOM
+/// hook before [`handle_alloc_error`] is called regardless.
+///
+/// [`handle_alloc_error`]: fn.handle_alloc_error.html
+#[unstable(feature = "oom_hook", issue = "0")]
+pub const MAX_OOM_RETRIES: u32 = 3;
+
+/// Registers a hook to call when an infallible allocation fails, replacing
+/// any that was previously registered.
+///
+/// The hook is called with the layout of the failed allocation and the
+/// number of retries made for it so far. It can free memory, for example by
+/// dropping caches, and return [`OomAction::Retry`] to have the allocation
+/// attempted again, up to [`MAX_OOM_RETRIES`] times. Once it returns
+/// [`OomAction::Abort`], or the retries run out, the failure goes on to
+/// [`handle_alloc_error`] as before.
+///
+/// Fallible allocations, such as `Vec::try_reserve` and the [`Alloc`] methods
+/// themselves, report their failure to the caller without calling the hook.
+///
+/// The hook runs in the middle of the failed allocation, so it must not
+/// panic, and it may run on several threads at once. It may allocate, but an
+/// allocation failing inside the hook goes straight to `handle_alloc_error`
+/// instead of calling the hook again.
+///
+/// The OOM hook is a global resource. On targets without pointer-sized
+/// atomics it is never called.
+///
+/// [`OomAction::Retry`]: enum.OomAction.html#variant.Retry
+/// [`OomAction::Abort`]: enum.OomAction.html#variant.Abort
+/// [`MAX_OOM_RETRIES`]: constant.MAX_OOM_RETRIES.html
+/// [`handle_alloc_error`]: fn.handle_alloc_error.html
+/// [`Alloc`]: trait.Alloc.html
+#[unstable(feature = "oom_hook", issue = "0")]
+pub fn set_oom_hook(hook: fn(Layout, u32) -> OomAction) {
+    oom_hook::set(Some(hook))
+}
+
+/// Unregisters the current OOM hook, returning it if there was one.
+///
+/// Failed allocations don't call the hook from then on, though calls already
+/// running carry on.
+///
+/// *See also the function [`set_oom_hook`].*
+///
+/// [`set_oom_hook`]: fn.set_oom_hook.html
+#[unstable(feature = "oom_hook", issue = "0")]
+pub fn take_oom_hook() -> Option<fn(Layout, u32) -> OomAction> {
+    oom_hook::set(None)
+}
+
+/// Runs `alloc` and, while it fails and the OOM hook asks for it, runs it
+/// again. This is for allocations which call `handle_alloc_error` when this
+/// returns an error.
+#[inline]
+pub(crate) fn retry_on_oom<T, E>(layout: Layout, mut alloc: impl FnMut() -> Result<T, E>)
+                                 -> Result<T, E> {
+    let mut result = alloc();
+    let mut retries = 0;
+    while result.is_err() && retries < MAX_OOM_RETRIES {
+        let action = match oom_hook::call(layout, retries) {
+            Some(action) => action,
+            None => break,
+        };
+        if action == OomAction::Abort {
+            break;
+        }
+        result = alloc();
+        retries += 1;
+    }
+    result
+}
+
+#[cfg(target_has_atomic = "ptr")]
+mod oom_hook {
+    use core::alloc::Layout;
+    use core::mem;
+    use core::ptr;
+    use core::sync::atomic::{AtomicPtr, Ordering};
+    use super::OomAction;
+
+    static HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+    /// Replaces the hook, returning the previous one.
+    pub fn set(hook: Option<fn(Layout, u32) -> OomAction>)
+               -> Option<fn(Layout, u32) -> OomAction> {
+        let new = match hook {
+            Some(hook) => hook as *mut (),
+            None => ptr::null_mut(),
+        };
+        let old = HOOK.swap(new, Ordering::SeqCst);
+        if old.is_null() {
+            None
+        } else {
+            Some(unsafe { mem::transmute(old) })
+        }
+    }
+
+    /// Calls the hook, unless there is none or this thread is already
+    /// running it.
+    pub fn call(layout: Layout, retries: u32) -> Option<OomAction> {
+        let hook = HOOK.load(Ordering::SeqCst);
+        if hook.is_null() {
+            return None
+        }
+        let hook: fn(Layout, u32) -> OomAction = unsafe { mem::transmute(hook) };
+        let _running = running::enter()?;
+        Some(hook(layout, retries))
+    }
+
+    #[cfg(target_thread_local)]
+    mod running {
+        use core::cell::Cell;
+
+        #[thread_local]
+        static RUNNING: Cell<bool> = Cell::new(false);
+
+        pub struct Running(());
+
+        pub fn enter() -> Option<Running> {
+            if RUNNING.replace(true) {
+                None
+            } else {
+                Some(Running(()))
+            }
+        }
+
+        impl Drop for Running {
+            fn drop(&mut self) {
+                RUNNING.set(false);
+            }
+        }
+    }
+
+    // Without thread locals, only one thread at a time can run the hook, and
+    // allocations failing on the others meanwhile go without it.
+    #[cfg(not(target_thread_local))]
+    mod running {
+        use core::sync::atomic::{AtomicBool, Ordering};
+
+        static RUNNING: AtomicBool = AtomicBool::new(false);
+
+        pub struct Running(());
+
+        pub fn enter() -> Option<Running> {
+            if RUNNING.swap(true, Ordering::SeqCst) {
+                None
+            } else {
+                Some(Running(()))
+            }
+        }
+
+        impl Drop for Running {
+            fn drop(&mut self) {
+                RUNNING.store(false, Ordering::SeqCst);
+            }
+        }
+    }
+}
+
+#[cfg(not(target_has_atomic = "ptr"))]
+mod oom_hook {
+    use core::alloc::Layout;
+    use super::OomAction;
+
+    pub fn set(_hook: Option<fn(Layout, u32) -> OomAction>)
+               -> Option<fn(Layout, u32) -> OomAction> {
+        None
+    }
+
+    pub fn call(_layout: Layout, _retries: u32) -> Option<OomAction> {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     extern crate test;
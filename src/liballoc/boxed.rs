@@ -71,6 +71,7 @@ use core::ops::{
     CoerceUnsized, DispatchFromDyn, Deref, DerefMut, Receiver, Generator, GeneratorState
 };
 use core::ptr::{self, NonNull, Unique};
+use core::slice;
 use core::task::{Waker, Poll};
 
 use crate::vec::Vec;
@@ -272,6 +273,64 @@ impl<T: ?Sized> Box<T> {
     }
 }
 
+impl<T> Box<[T]> {
+    /// Constructs a boxed slice from a pointer to its first element and its
+    /// length.
+    ///
+    /// The only valid arguments are the ones returned by
+    /// [`Box::into_raw_parts`] for another boxed slice. The resulting `Box`
+    /// owns the elements and the allocation again, as with [`Box::from_raw`].
+    ///
+    /// [`Box::into_raw_parts`]: struct.Box.html#method.into_raw_parts
+    /// [`Box::from_raw`]: struct.Box.html#method.from_raw
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(box_into_raw_parts)]
+    /// let x: Box<[u8]> = vec![1, 2, 3].into_boxed_slice();
+    /// let (ptr, len) = Box::into_raw_parts(x);
+    /// let x = unsafe { Box::from_raw_parts(ptr, len) };
+    /// assert_eq!(*x, [1, 2, 3]);
+    /// ```
+    #[unstable(feature = "box_into_raw_parts", issue = "0")]
+    #[inline]
+    pub unsafe fn from_raw_parts(ptr: *mut T, len: usize) -> Self {
+        Box::from_raw(slice::from_raw_parts_mut(ptr, len))
+    }
+
+    /// Consumes the boxed slice, returning a pointer to its first element
+    /// and its length.
+    ///
+    /// This is [`Box::into_raw`] with the fat pointer taken apart, which is
+    /// the form C code expects a buffer in. The pointer is non-null and
+    /// properly aligned even when the slice is empty. The caller becomes
+    /// responsible for the memory; to release it, rebuild the `Box` with
+    /// [`Box::from_raw_parts`].
+    ///
+    /// Note: this is an associated function, which means that you have
+    /// to call it as `Box::into_raw_parts(b)` instead of `b.into_raw_parts()`.
+    ///
+    /// [`Box::into_raw`]: struct.Box.html#method.into_raw
+    /// [`Box::from_raw_parts`]: struct.Box.html#method.from_raw_parts
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(box_into_raw_parts)]
+    /// let x: Box<[u8]> = vec![1, 2, 3].into_boxed_slice();
+    /// let (ptr, len) = Box::into_raw_parts(x);
+    /// assert_eq!(len, 3);
+    /// # unsafe { Box::from_raw_parts(ptr, len); }
+    /// ```
+    #[unstable(feature = "box_into_raw_parts", issue = "0")]
+    #[inline]
+    pub fn into_raw_parts(b: Self) -> (*mut T, usize) {
+        let len = b.len();
+        (Box::into_raw(b) as *mut T, len)
+    }
+}
+
 #[stable(feature = "rust1", since = "1.0.0")]
 unsafe impl<#[may_dangle] T: ?Sized> Drop for Box<T> {
     fn drop(&mut self) {
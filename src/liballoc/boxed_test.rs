@@ -110,6 +110,25 @@ fn raw_trait() {
     }
 }
 
+#[test]
+fn raw_slice_parts() {
+    let x: Box<[u32]> = Box::new([17, 18, 19]);
+    let (p, len) = Box::into_raw_parts(x);
+    assert_eq!(len, 3);
+    unsafe {
+        *p = 20;
+        let y = Box::from_raw_parts(p, len);
+        assert_eq!(*y, [20, 18, 19]);
+    }
+
+    let x: Box<[u32]> = Box::new([]);
+    let (p, len) = Box::into_raw_parts(x);
+    assert!(!p.is_null());
+    assert_eq!(len, 0);
+    let y = unsafe { Box::from_raw_parts(p, len) };
+    assert!(y.is_empty());
+}
+
 #[test]
 fn f64_slice() {
     let slice: &[f64] = &[-1.0, 0.0, 1.0, f64::INFINITY];
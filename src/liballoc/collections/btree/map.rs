@@ -5,7 +5,7 @@ use core::hash::{Hash, Hasher};
 use core::iter::{FromIterator, Peekable, FusedIterator};
 use core::marker::PhantomData;
 use core::ops::Bound::{Excluded, Included, Unbounded};
-use core::ops::{Index, RangeBounds};
+use core::ops::{Bound, Index, RangeBounds};
 use core::{fmt, intrinsics, mem, ptr};
 
 use super::node::{self, Handle, NodeRef, marker, InsertResult::*, ForceResult::*};
@@ -502,6 +502,55 @@ struct MergeIter<K, V, I: Iterator<Item = (K, V)>> {
     right: Peekable<I>,
 }
 
+// An iterator over the longest run of `next` followed by elements of `iter` whose keys are
+// strictly increasing. The element which ended the run, if any, is left in `stray`.
+struct SortedRun<K, V, I: Iterator<Item = (K, V)>> {
+    next: Option<(K, V)>,
+    iter: I,
+    stray: Option<(K, V)>,
+}
+
+/// A cursor over a `BTreeMap`.
+///
+/// A `Cursor` is like an iterator, except that it can freely seek back-and-forth.
+///
+/// Cursors always point to an element in the map, or to a "ghost" non-element which sits
+/// between the last element and the first one. Moving forward from the ghost gives the first
+/// element, and moving backward from it gives the last one.
+///
+/// This `struct` is created by the [`lower_bound`] and [`upper_bound`] methods on
+/// [`BTreeMap`]. See their documentation for more.
+///
+/// [`lower_bound`]: struct.BTreeMap.html#method.lower_bound
+/// [`upper_bound`]: struct.BTreeMap.html#method.upper_bound
+/// [`BTreeMap`]: struct.BTreeMap.html
+#[unstable(feature = "btree_cursors", issue = "0")]
+pub struct Cursor<'a, K: 'a, V: 'a> {
+    current: Option<Handle<NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal>, marker::KV>>,
+    root: NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal>,
+}
+
+/// A cursor over a `BTreeMap` with editing operations.
+///
+/// A `CursorMut` moves like a [`Cursor`], and can also remove the element it points to or
+/// insert new ones in front of it, without looking the position up again.
+///
+/// This `struct` is created by the [`lower_bound_mut`] and [`upper_bound_mut`] methods on
+/// [`BTreeMap`]. See their documentation for more.
+///
+/// [`Cursor`]: struct.Cursor.html
+/// [`lower_bound_mut`]: struct.BTreeMap.html#method.lower_bound_mut
+/// [`upper_bound_mut`]: struct.BTreeMap.html#method.upper_bound_mut
+/// [`BTreeMap`]: struct.BTreeMap.html
+#[unstable(feature = "btree_cursors", issue = "0")]
+pub struct CursorMut<'a, K: 'a, V: 'a> {
+    current: Option<Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>, marker::KV>>,
+    map: *mut BTreeMap<K, V>,
+
+    // Be invariant in `K` and `V`
+    _marker: PhantomData<&'a mut (K, V)>,
+}
+
 impl<K: Ord, V> BTreeMap<K, V> {
     /// Makes a new empty BTreeMap with a reasonable choice for B.
     ///
@@ -782,6 +831,66 @@ impl<K: Ord, V> BTreeMap<K, V> {
         self.fix_right_edge();
     }
 
+    /// Moves all elements from `iter` into the map, expecting them to be sorted by key.
+    ///
+    /// As long as the keys are strictly increasing and greater than every key already in the
+    /// map, elements are added to the right edge of the tree directly, without searching for
+    /// their place. Should the iterator turn out not to be sorted, the remaining elements are
+    /// inserted one by one, so the result is the same as [`extend`]'s either way; only the
+    /// speed depends on the order. If a key occurs more than once, the last value wins.
+    ///
+    /// [`extend`]: #impl-Extend%3C(K%2C%20V)%3E
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// #![feature(btree_append_sorted)]
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::new();
+    /// map.insert(1, "a");
+    ///
+    /// map.append_sorted(vec![(2, "b"), (3, "c"), (4, "d")]);
+    ///
+    /// assert_eq!(map.len(), 4);
+    /// assert_eq!(map[&3], "c");
+    /// ```
+    #[unstable(feature = "btree_append_sorted", issue = "0")]
+    pub fn append_sorted<I>(&mut self, iter: I)
+        where I: IntoIterator<Item = (K, V)>
+    {
+        let mut iter = iter.into_iter();
+        let first = match iter.next() {
+            Some(first) => first,
+            None => return,
+        };
+
+        let above_last = match self.iter().next_back() {
+            Some((last, _)) => *last < first.0,
+            None => true,
+        };
+        if !above_last {
+            self.insert(first.0, first.1);
+            self.extend(iter);
+            return;
+        }
+
+        let mut run = SortedRun {
+            next: Some(first),
+            iter: &mut iter,
+            stray: None,
+        };
+        self.from_sorted_iter(&mut run);
+        self.fix_right_edge();
+
+        if let Some((key, value)) = run.stray {
+            self.insert(key, value);
+        }
+        self.extend(iter);
+    }
+
     /// Constructs a double-ended iterator over a sub-range of elements in the map.
     /// The simplest way is to use the range syntax `min..max`, thus `range(min..max)` will
     /// yield elements from min (inclusive) to max (exclusive).
@@ -867,6 +976,153 @@ impl<K: Ord, V> BTreeMap<K, V> {
         }
     }
 
+    /// Returns a [`Cursor`] pointing at the first element that is above the given bound.
+    ///
+    /// If no such element exists then a cursor pointing at the "ghost" non-element is
+    /// returned. Passing `Bound::Unbounded` will return a cursor pointing at the first element
+    /// of the map.
+    ///
+    /// [`Cursor`]: struct.Cursor.html
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// #![feature(btree_cursors)]
+    /// use std::collections::BTreeMap;
+    /// use std::ops::Bound;
+    ///
+    /// let mut map = BTreeMap::new();
+    /// map.insert(1, "a");
+    /// map.insert(2, "b");
+    /// map.insert(3, "c");
+    /// map.insert(4, "d");
+    /// let cursor = map.lower_bound(Bound::Excluded(&2));
+    /// assert_eq!(cursor.key(), Some(&3));
+    /// ```
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn lower_bound<Q: ?Sized>(&self, bound: Bound<&Q>) -> Cursor<'_, K, V>
+        where K: Borrow<Q>, Q: Ord
+    {
+        let root = self.root.as_ref();
+        Cursor {
+            current: lower_bound_kv(root, bound),
+            root,
+        }
+    }
+
+    /// Returns a [`CursorMut`] pointing at the first element that is above the given bound.
+    ///
+    /// If no such element exists then a cursor pointing at the "ghost" non-element is
+    /// returned. Passing `Bound::Unbounded` will return a cursor pointing at the first element
+    /// of the map.
+    ///
+    /// [`CursorMut`]: struct.CursorMut.html
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// #![feature(btree_cursors)]
+    /// use std::collections::BTreeMap;
+    /// use std::ops::Bound;
+    ///
+    /// let mut map = BTreeMap::new();
+    /// map.insert(1, "a");
+    /// map.insert(2, "b");
+    /// map.insert(3, "c");
+    /// map.insert(4, "d");
+    /// let mut cursor = map.lower_bound_mut(Bound::Included(&2));
+    /// assert_eq!(cursor.remove_current(), Some((2, "b")));
+    /// assert_eq!(cursor.key(), Some(&3));
+    /// ```
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn lower_bound_mut<Q: ?Sized>(&mut self, bound: Bound<&Q>) -> CursorMut<'_, K, V>
+        where K: Borrow<Q>, Q: Ord
+    {
+        let map = self as *mut BTreeMap<K, V>;
+        CursorMut {
+            current: lower_bound_kv(self.root.as_mut(), bound),
+            map,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns a [`Cursor`] pointing at the last element that is below the given bound.
+    ///
+    /// If no such element exists then a cursor pointing at the "ghost" non-element is
+    /// returned. Passing `Bound::Unbounded` will return a cursor pointing at the last element
+    /// of the map.
+    ///
+    /// [`Cursor`]: struct.Cursor.html
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// #![feature(btree_cursors)]
+    /// use std::collections::BTreeMap;
+    /// use std::ops::Bound;
+    ///
+    /// let mut map = BTreeMap::new();
+    /// map.insert(1, "a");
+    /// map.insert(2, "b");
+    /// map.insert(3, "c");
+    /// map.insert(4, "d");
+    /// let cursor = map.upper_bound(Bound::Excluded(&3));
+    /// assert_eq!(cursor.key(), Some(&2));
+    /// ```
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn upper_bound<Q: ?Sized>(&self, bound: Bound<&Q>) -> Cursor<'_, K, V>
+        where K: Borrow<Q>, Q: Ord
+    {
+        let root = self.root.as_ref();
+        Cursor {
+            current: upper_bound_kv(root, bound),
+            root,
+        }
+    }
+
+    /// Returns a [`CursorMut`] pointing at the last element that is below the given bound.
+    ///
+    /// If no such element exists then a cursor pointing at the "ghost" non-element is
+    /// returned. Passing `Bound::Unbounded` will return a cursor pointing at the last element
+    /// of the map.
+    ///
+    /// [`CursorMut`]: struct.CursorMut.html
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// #![feature(btree_cursors)]
+    /// use std::collections::BTreeMap;
+    /// use std::ops::Bound;
+    ///
+    /// let mut map = BTreeMap::new();
+    /// map.insert(1, "a");
+    /// map.insert(3, "c");
+    /// let mut cursor = map.upper_bound_mut(Bound::Unbounded);
+    /// cursor.insert_before(2, "b");
+    /// assert_eq!(cursor.key(), Some(&3));
+    /// assert_eq!(map[&2], "b");
+    /// ```
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn upper_bound_mut<Q: ?Sized>(&mut self, bound: Bound<&Q>) -> CursorMut<'_, K, V>
+        where K: Borrow<Q>, Q: Ord
+    {
+        let map = self as *mut BTreeMap<K, V>;
+        CursorMut {
+            current: upper_bound_kv(self.root.as_mut(), bound),
+            map,
+            _marker: PhantomData,
+        }
+    }
+
     /// Gets the given key's corresponding entry in the map for in-place manipulation.
     ///
     /// # Examples
@@ -1826,6 +2082,120 @@ fn last_leaf_edge<BorrowType, K, V>
     }
 }
 
+/// Finds the first key/value pair to the right of `edge`, ascending as far as needed.
+fn next_kv_from_edge<BorrowType, K, V>
+    (mut edge: Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::Edge>)
+     -> Option<Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>> {
+    loop {
+        match edge.right_kv() {
+            Ok(kv) => return Some(kv),
+            Err(last_edge) => match last_edge.into_node().ascend() {
+                Ok(parent_edge) => edge = parent_edge.forget_node_type(),
+                Err(_) => return None,
+            }
+        }
+    }
+}
+
+/// Finds the first key/value pair to the left of `edge`, ascending as far as needed.
+fn prev_kv_from_edge<BorrowType, K, V>
+    (mut edge: Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::Edge>)
+     -> Option<Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>> {
+    loop {
+        match edge.left_kv() {
+            Ok(kv) => return Some(kv),
+            Err(first_edge) => match first_edge.into_node().ascend() {
+                Ok(parent_edge) => edge = parent_edge.forget_node_type(),
+                Err(_) => return None,
+            }
+        }
+    }
+}
+
+fn first_kv<BorrowType, K, V>(root: NodeRef<BorrowType, K, V, marker::LeafOrInternal>)
+     -> Option<Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>> {
+    next_kv_from_edge(first_leaf_edge(root).forget_node_type())
+}
+
+fn last_kv<BorrowType, K, V>(root: NodeRef<BorrowType, K, V, marker::LeafOrInternal>)
+     -> Option<Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>> {
+    prev_kv_from_edge(last_leaf_edge(root).forget_node_type())
+}
+
+/// Finds the key/value pair following `kv` in the tree.
+fn next_kv<BorrowType, K, V>
+    (kv: Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>)
+     -> Option<Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>> {
+    let edge = match kv.right_edge().force() {
+        Leaf(leaf_edge) => leaf_edge.forget_node_type(),
+        Internal(internal_edge) => first_leaf_edge(internal_edge.descend()).forget_node_type(),
+    };
+    next_kv_from_edge(edge)
+}
+
+/// Finds the key/value pair preceding `kv` in the tree.
+fn prev_kv<BorrowType, K, V>
+    (kv: Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>)
+     -> Option<Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>> {
+    let edge = match kv.left_edge().force() {
+        Leaf(leaf_edge) => leaf_edge.forget_node_type(),
+        Internal(internal_edge) => last_leaf_edge(internal_edge.descend()).forget_node_type(),
+    };
+    prev_kv_from_edge(edge)
+}
+
+/// Finds the first key/value pair whose key is above `bound`.
+fn lower_bound_kv<BorrowType, K, V, Q: ?Sized>(
+    mut node: NodeRef<BorrowType, K, V, marker::LeafOrInternal>,
+    bound: Bound<&Q>
+) -> Option<Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>>
+        where Q: Ord, K: Borrow<Q>
+{
+    loop {
+        let idx = match bound {
+            Included(key) => match search::search_linear(&node, key) {
+                (i, true) => return Some(Handle::new_kv(node, i)),
+                (i, false) => i,
+            },
+            Excluded(key) => match search::search_linear(&node, key) {
+                (i, true) => return next_kv(Handle::new_kv(node, i)),
+                (i, false) => i,
+            },
+            Unbounded => 0,
+        };
+        match Handle::new_edge(node, idx).force() {
+            Leaf(leaf_edge) => return next_kv_from_edge(leaf_edge.forget_node_type()),
+            Internal(internal_edge) => node = internal_edge.descend(),
+        }
+    }
+}
+
+/// Finds the last key/value pair whose key is below `bound`.
+fn upper_bound_kv<BorrowType, K, V, Q: ?Sized>(
+    mut node: NodeRef<BorrowType, K, V, marker::LeafOrInternal>,
+    bound: Bound<&Q>
+) -> Option<Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>>
+        where Q: Ord, K: Borrow<Q>
+{
+    loop {
+        let idx = match bound {
+            Included(key) => match search::search_linear(&node, key) {
+                (i, true) => return Some(Handle::new_kv(node, i)),
+                (i, false) => i,
+            },
+            Excluded(key) => match search::search_linear(&node, key) {
+                (i, true) => return prev_kv(Handle::new_kv(node, i)),
+                (i, false) => i,
+            },
+            Unbounded => node.len(),
+        };
+        match Handle::new_edge(node, idx).force() {
+            Leaf(leaf_edge) => return prev_kv_from_edge(leaf_edge.forget_node_type()),
+            Internal(internal_edge) => node = internal_edge.descend(),
+        }
+    }
+}
+
 fn range_search<BorrowType, K, V, Q: ?Sized, R: RangeBounds<Q>>(
     root1: NodeRef<BorrowType, K, V, marker::LeafOrInternal>,
     root2: NodeRef<BorrowType, K, V, marker::LeafOrInternal>,
@@ -2264,6 +2634,11 @@ impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn insert(self, value: V) -> &'a mut V {
+        self.insert_kv(value).1
+    }
+
+    /// Like `insert`, but also hands out the key, which the tree must not see changed.
+    fn insert_kv(self, value: V) -> (&'a mut K, &'a mut V) {
         *self.length += 1;
 
         let out_ptr;
@@ -2273,7 +2648,7 @@ impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
         let mut ins_edge;
 
         let mut cur_parent = match self.handle.insert(self.key, value) {
-            (Fit(handle), _) => return handle.into_kv_mut().1,
+            (Fit(handle), _) => return handle.into_kv_mut(),
             (Split(left, k, v, right), ptr) => {
                 ins_k = k;
                 ins_v = v;
@@ -2287,7 +2662,7 @@ impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
             match cur_parent {
                 Ok(parent) => {
                     match parent.insert(ins_k, ins_v, ins_edge) {
-                        Fit(_) => return unsafe { &mut *out_ptr },
+                        Fit(_) => return unsafe { (&mut *out_ptr.0, &mut *out_ptr.1) },
                         Split(left, k, v, right) => {
                             ins_k = k;
                             ins_v = v;
@@ -2298,7 +2673,7 @@ impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
                 }
                 Err(root) => {
                     root.push_level().push(ins_k, ins_v, ins_edge);
-                    return unsafe { &mut *out_ptr };
+                    return unsafe { (&mut *out_ptr.0, &mut *out_ptr.1) };
                 }
             }
         }
@@ -2514,6 +2889,266 @@ impl<'a, K: Ord, V> OccupiedEntry<'a, K, V> {
     }
 }
 
+#[unstable(feature = "btree_cursors", issue = "0")]
+impl<K, V> Clone for Cursor<'_, K, V> {
+    fn clone(&self) -> Self {
+        Cursor {
+            current: self.current,
+            root: self.root,
+        }
+    }
+}
+
+#[unstable(feature = "btree_cursors", issue = "0")]
+impl<K: Debug, V: Debug> Debug for Cursor<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Cursor")
+         .field(&self.key_value())
+         .finish()
+    }
+}
+
+#[unstable(feature = "btree_cursors", issue = "0")]
+impl<K: Debug, V: Debug> Debug for CursorMut<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CursorMut")
+         .field(&self.key_value())
+         .finish()
+    }
+}
+
+impl<'a, K, V> Cursor<'a, K, V> {
+    /// Moves the cursor to the next element of the `BTreeMap`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the first element of the map. If it is pointing to the last element then this
+    /// will move it to the "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn move_next(&mut self) {
+        self.current = match self.current {
+            Some(kv) => next_kv(kv),
+            None => first_kv(self.root),
+        };
+    }
+
+    /// Moves the cursor to the previous element of the `BTreeMap`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the last element of the map. If it is pointing to the first element then this
+    /// will move it to the "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn move_prev(&mut self) {
+        self.current = match self.current {
+            Some(kv) => prev_kv(kv),
+            None => last_kv(self.root),
+        };
+    }
+
+    /// Returns a reference to the key of the element that the cursor is currently
+    /// pointing to, or `None` if it is pointing to the "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn key(&self) -> Option<&'a K> {
+        self.key_value().map(|(k, _)| k)
+    }
+
+    /// Returns a reference to the value of the element that the cursor is currently
+    /// pointing to, or `None` if it is pointing to the "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn value(&self) -> Option<&'a V> {
+        self.key_value().map(|(_, v)| v)
+    }
+
+    /// Returns references to the key and value of the element that the cursor is
+    /// currently pointing to, or `None` if it is pointing to the "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn key_value(&self) -> Option<(&'a K, &'a V)> {
+        self.current.map(|kv| kv.into_kv())
+    }
+
+    /// Returns the element that the next call to `move_next` would point to, without
+    /// moving the cursor.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn peek_next(&self) -> Option<(&'a K, &'a V)> {
+        let mut next = self.clone();
+        next.move_next();
+        next.key_value()
+    }
+
+    /// Returns the element that the next call to `move_prev` would point to, without
+    /// moving the cursor.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn peek_prev(&self) -> Option<(&'a K, &'a V)> {
+        let mut prev = self.clone();
+        prev.move_prev();
+        prev.key_value()
+    }
+}
+
+impl<'a, K, V> CursorMut<'a, K, V> {
+    // Only to be used once `current` has been given up: the two can't both be live.
+    unsafe fn root(&mut self) -> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal> {
+        (*self.map).root.as_mut()
+    }
+
+    /// Moves the cursor to the next element of the `BTreeMap`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the first element of the map. If it is pointing to the last element then this
+    /// will move it to the "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn move_next(&mut self) {
+        self.current = match self.current.take() {
+            Some(kv) => next_kv(kv),
+            None => first_kv(unsafe { self.root() }),
+        };
+    }
+
+    /// Moves the cursor to the previous element of the `BTreeMap`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the last element of the map. If it is pointing to the first element then this
+    /// will move it to the "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn move_prev(&mut self) {
+        self.current = match self.current.take() {
+            Some(kv) => prev_kv(kv),
+            None => last_kv(unsafe { self.root() }),
+        };
+    }
+
+    /// Returns a reference to the key of the element that the cursor is currently
+    /// pointing to, or `None` if it is pointing to the "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn key(&self) -> Option<&K> {
+        self.key_value().map(|(k, _)| k)
+    }
+
+    /// Returns a reference to the value of the element that the cursor is currently
+    /// pointing to, or `None` if it is pointing to the "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn value(&self) -> Option<&V> {
+        self.key_value().map(|(_, v)| v)
+    }
+
+    /// Returns a mutable reference to the value of the element that the cursor is
+    /// currently pointing to, or `None` if it is pointing to the "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn value_mut(&mut self) -> Option<&mut V> {
+        self.current.as_mut().map(|kv| kv.kv_mut().1)
+    }
+
+    /// Returns references to the key and value of the element that the cursor is
+    /// currently pointing to, or `None` if it is pointing to the "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn key_value(&self) -> Option<(&K, &V)> {
+        self.current.as_ref().map(|kv| kv.reborrow().into_kv())
+    }
+
+    /// Returns the element that the next call to `move_next` would point to, without
+    /// moving the cursor.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn peek_next(&mut self) -> Option<(&K, &mut V)> {
+        let next = match self.current {
+            Some(ref mut kv) => next_kv(unsafe { kv.reborrow_mut() }),
+            None => return first_kv(unsafe { self.root() }).map(|kv| {
+                let (k, v) = kv.into_kv_mut();
+                (&*k, v)
+            }),
+        };
+        next.map(|kv| {
+            let (k, v) = kv.into_kv_mut();
+            (&*k, v)
+        })
+    }
+
+    /// Returns the element that the next call to `move_prev` would point to, without
+    /// moving the cursor.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn peek_prev(&mut self) -> Option<(&K, &mut V)> {
+        let prev = match self.current {
+            Some(ref mut kv) => prev_kv(unsafe { kv.reborrow_mut() }),
+            None => return last_kv(unsafe { self.root() }).map(|kv| {
+                let (k, v) = kv.into_kv_mut();
+                (&*k, v)
+            }),
+        };
+        prev.map(|kv| {
+            let (k, v) = kv.into_kv_mut();
+            (&*k, v)
+        })
+    }
+
+    /// Returns a read-only cursor pointing to the same element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn as_cursor(&self) -> Cursor<'_, K, V> {
+        Cursor {
+            current: self.current.as_ref().map(|kv| kv.reborrow()),
+            root: unsafe { (*self.map).root.as_ref() },
+        }
+    }
+}
+
+impl<'a, K: Ord, V> CursorMut<'a, K, V> {
+    /// Inserts a new element into the `BTreeMap` right before the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new element is
+    /// inserted at the end of the map. Either way the cursor keeps pointing at the
+    /// same element (or non-element) as before.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` does not sort strictly between the keys of the previous element and
+    /// the current one, as the map would no longer be sorted otherwise.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn insert_before(&mut self, key: K, value: V) {
+        if let Some(current) = self.key() {
+            assert!(key < *current, "key must be ordered below the current element");
+        }
+        if let Some((prev, _)) = self.peek_prev() {
+            assert!(key > *prev, "key must be ordered above the previous element");
+        }
+
+        let handle = match self.current.take() {
+            Some(kv) => match kv.left_edge().force() {
+                Leaf(leaf_edge) => leaf_edge,
+                Internal(internal_edge) => last_leaf_edge(internal_edge.descend()),
+            },
+            None => unsafe {
+                (*self.map).ensure_root_is_owned();
+                last_leaf_edge(self.root())
+            },
+        };
+        let entry = VacantEntry {
+            key,
+            handle,
+            length: unsafe { &mut (*self.map).length },
+            _marker: PhantomData,
+        };
+        let key = entry.insert_kv(value).0 as *const K;
+
+        // Inserting may have split the current element's node, so find it again.
+        self.current = lower_bound_kv(unsafe { self.root() }, Excluded(unsafe { &*key }));
+    }
+
+    /// Removes the current element from the `BTreeMap` and returns it.
+    ///
+    /// The cursor is moved to point to the next element of the map. If it is pointing
+    /// at the "ghost" non-element then nothing is removed and `None` is returned.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn remove_current(&mut self) -> Option<(K, V)> {
+        let entry = OccupiedEntry {
+            handle: self.current.take()?,
+            length: unsafe { &mut (*self.map).length },
+            _marker: PhantomData,
+        };
+        let (key, value) = entry.remove_kv();
+
+        // Removing may have rebalanced the tree, so find our place again.
+        self.current = lower_bound_kv(unsafe { self.root() }, Excluded(&key));
+        Some((key, value))
+    }
+}
+
 enum UnderflowResult<'a, K, V> {
     AtRoot,
     EmptyParent(NodeRef<marker::Mut<'a>, K, V, marker::Internal>),
@@ -2553,6 +3188,22 @@ fn handle_underfull_node<'a, K, V>(node: NodeRef<marker::Mut<'a>, K, V, marker::
     }
 }
 
+impl<K: Ord, V, I: Iterator<Item = (K, V)>> Iterator for SortedRun<K, V, I> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        let current = self.next.take()?;
+        if let Some(next) = self.iter.next() {
+            if current.0 < next.0 {
+                self.next = Some(next);
+            } else {
+                self.stray = Some(next);
+            }
+        }
+        Some(current)
+    }
+}
+
 impl<K: Ord, V, I: Iterator<Item = (K, V)>> Iterator for MergeIter<K, V, I> {
     type Item = (K, V);
 
@@ -893,6 +893,23 @@ impl<Node, Type> Handle<Node, Type> {
     }
 }
 
+impl<BorrowType, K, V, NodeType, HandleType>
+        Handle<NodeRef<BorrowType, K, V, NodeType>, HandleType> {
+
+    /// Removes any static information about whether the node this handle points into is a
+    /// `Leaf` or an `Internal` node.
+    pub fn forget_node_type(self)
+            -> Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, HandleType> {
+
+        // We can't use Handle::new_kv or Handle::new_edge because we don't know our type
+        Handle {
+            node: self.node.forget_type(),
+            idx: self.idx,
+            _marker: PhantomData
+        }
+    }
+}
+
 impl<BorrowType, K, V, NodeType> Handle<NodeRef<BorrowType, K, V, NodeType>, marker::KV> {
     /// Creates a new handle to a key/value pair in `node`. `idx` must be less than `node.len()`.
     pub fn new_kv(node: NodeRef<BorrowType, K, V, NodeType>, idx: usize) -> Self {
@@ -1006,8 +1023,8 @@ impl<'a, K, V> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge
     /// this edge. This method assumes that there is enough space in the node for the new
     /// pair to fit.
     ///
-    /// The returned pointer points to the inserted value.
-    fn insert_fit(&mut self, key: K, val: V) -> *mut V {
+    /// The returned pointers point to the inserted key and value.
+    fn insert_fit(&mut self, key: K, val: V) -> (*mut K, *mut V) {
         // Necessary for correctness, but in a private module
         debug_assert!(self.node.len() < CAPACITY);
         debug_assert!(!self.node.is_shared_root());
@@ -1018,16 +1035,19 @@ impl<'a, K, V> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge
 
             (*self.node.as_leaf_mut()).len += 1;
 
-            self.node.vals_mut().get_unchecked_mut(self.idx)
+            (self.node.keys_mut().get_unchecked_mut(self.idx) as *mut K,
+             self.node.vals_mut().get_unchecked_mut(self.idx) as *mut V)
         }
     }
 
     /// Inserts a new key/value pair between the key/value pairs to the right and left of
     /// this edge. This method splits the node if there isn't enough room.
     ///
-    /// The returned pointer points to the inserted value.
+    /// The returned pointers point to the inserted key and value. Since splitting a node's
+    /// parent never moves the node's own contents, they stay valid until the tree is next
+    /// modified.
     pub fn insert(mut self, key: K, val: V)
-            -> (InsertResult<'a, K, V, marker::Leaf>, *mut V) {
+            -> (InsertResult<'a, K, V, marker::Leaf>, (*mut K, *mut V)) {
 
         if self.node.len() < CAPACITY {
             let ptr = self.insert_fit(key, val);
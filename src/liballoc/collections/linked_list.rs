@@ -737,48 +737,125 @@ impl<T> LinkedList<T> {
         second_part
     }
 
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all elements `e` such that `f(&e)` returns `false`.
+    /// This method operates in place and preserves the order of the retained
+    /// elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(linked_list_retain)]
+    /// use std::collections::LinkedList;
+    ///
+    /// let mut list: LinkedList<u32> = (1..=6).collect();
+    /// list.retain(|&x| x % 2 == 0);
+    /// assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![2, 4, 6]);
+    /// ```
+    #[unstable(feature = "linked_list_retain", reason = "recently added", issue = "0")]
+    pub fn retain<F>(&mut self, mut f: F)
+        where F: FnMut(&T) -> bool
+    {
+        self.extract_if(|x| !f(x));
+    }
+
+    /// Retains only the elements specified by the predicate, passing a mutable reference to it.
+    ///
+    /// In other words, remove all elements `e` such that `f(&mut e)` returns `false`.
+    /// This method operates in place and preserves the order of the retained
+    /// elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(retain_mut)]
+    /// use std::collections::LinkedList;
+    ///
+    /// let mut list: LinkedList<u32> = (1..=6).collect();
+    /// list.retain_mut(|x| if *x % 2 == 0 {
+    ///     *x *= 10;
+    ///     true
+    /// } else {
+    ///     false
+    /// });
+    /// assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![20, 40, 60]);
+    /// ```
+    #[unstable(feature = "retain_mut", reason = "recently added", issue = "0")]
+    pub fn retain_mut<F>(&mut self, mut f: F)
+        where F: FnMut(&mut T) -> bool
+    {
+        self.extract_if(|x| !f(x));
+    }
+
     /// Creates an iterator which uses a closure to determine if an element should be removed.
     ///
     /// If the closure returns true, then the element is removed and yielded.
     /// If the closure returns false, the element will remain in the list and will not be yielded
     /// by the iterator.
     ///
-    /// Note that `drain_filter` lets you mutate every element in the filter closure, regardless of
+    /// Note that `extract_if` lets you mutate every element in the filter closure, regardless of
     /// whether you choose to keep or remove it.
     ///
+    /// The work is done lazily: if the returned iterator is dropped before it
+    /// is exhausted, the elements it hasn't looked at yet are run through the
+    /// closure then, and removed or kept accordingly.
+    ///
+    /// # Panics and leaks
+    ///
+    /// If the closure panics, the element it was called on and all the ones
+    /// after it are kept, and the closure isn't called again.
+    ///
+    /// Each element is unlinked as it is yielded, so leaking the iterator
+    /// (with [`mem::forget`], say) simply leaves the unvisited elements in
+    /// the list.
+    ///
+    /// [`mem::forget`]: ../../std/mem/fn.forget.html
+    ///
     /// # Examples
     ///
     /// Splitting a list into evens and odds, reusing the original list:
     ///
     /// ```
-    /// #![feature(drain_filter)]
+    /// #![feature(extract_if)]
     /// use std::collections::LinkedList;
     ///
     /// let mut numbers: LinkedList<u32> = LinkedList::new();
     /// numbers.extend(&[1, 2, 3, 4, 5, 6, 8, 9, 11, 13, 14, 15]);
     ///
-    /// let evens = numbers.drain_filter(|x| *x % 2 == 0).collect::<LinkedList<_>>();
+    /// let evens = numbers.extract_if(|x| *x % 2 == 0).collect::<LinkedList<_>>();
     /// let odds = numbers;
     ///
     /// assert_eq!(evens.into_iter().collect::<Vec<_>>(), vec![2, 4, 6, 8, 14]);
     /// assert_eq!(odds.into_iter().collect::<Vec<_>>(), vec![1, 3, 5, 9, 11, 13, 15]);
     /// ```
-    #[unstable(feature = "drain_filter", reason = "recently added", issue = "43244")]
-    pub fn drain_filter<F>(&mut self, filter: F) -> DrainFilter<'_, T, F>
+    #[unstable(feature = "extract_if", reason = "recently added", issue = "43244")]
+    pub fn extract_if<F>(&mut self, filter: F) -> ExtractIf<'_, T, F>
         where F: FnMut(&mut T) -> bool
     {
         // avoid borrow issues.
         let it = self.head;
         let old_len = self.len;
 
-        DrainFilter {
+        ExtractIf {
             list: self,
             it: it,
             pred: filter,
             idx: 0,
             old_len: old_len,
+            panic_flag: false,
         }
     }
+
+    /// The former name of [`extract_if`].
+    ///
+    /// [`extract_if`]: #method.extract_if
+    #[unstable(feature = "drain_filter", reason = "recently added", issue = "43244")]
+    pub fn drain_filter<F>(&mut self, filter: F) -> ExtractIf<'_, T, F>
+        where F: FnMut(&mut T) -> bool
+    {
+        self.extract_if(filter)
+    }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -969,9 +1046,9 @@ impl<T> IterMut<'_, T> {
     }
 }
 
-/// An iterator produced by calling `drain_filter` on LinkedList.
-#[unstable(feature = "drain_filter", reason = "recently added", issue = "43244")]
-pub struct DrainFilter<'a, T: 'a, F: 'a>
+/// An iterator produced by calling `extract_if` on LinkedList.
+#[unstable(feature = "extract_if", reason = "recently added", issue = "43244")]
+pub struct ExtractIf<'a, T: 'a, F: 'a>
     where F: FnMut(&mut T) -> bool,
 {
     list: &'a mut LinkedList<T>,
@@ -979,10 +1056,12 @@ pub struct DrainFilter<'a, T: 'a, F: 'a>
     pred: F,
     idx: usize,
     old_len: usize,
+    // Set while `pred` runs, so that `drop` doesn't call it again after it panicked.
+    panic_flag: bool,
 }
 
-#[unstable(feature = "drain_filter", reason = "recently added", issue = "43244")]
-impl<T, F> Iterator for DrainFilter<'_, T, F>
+#[unstable(feature = "extract_if", reason = "recently added", issue = "43244")]
+impl<T, F> Iterator for ExtractIf<'_, T, F>
     where F: FnMut(&mut T) -> bool,
 {
     type Item = T;
@@ -993,7 +1072,10 @@ impl<T, F> Iterator for DrainFilter<'_, T, F>
                 self.it = node.as_ref().next;
                 self.idx += 1;
 
-                if (self.pred)(&mut node.as_mut().element) {
+                self.panic_flag = true;
+                let extract = (self.pred)(&mut node.as_mut().element);
+                self.panic_flag = false;
+                if extract {
                     self.list.unlink_node(node);
                     return Some(Box::from_raw(node.as_ptr()).element);
                 }
@@ -1008,21 +1090,23 @@ impl<T, F> Iterator for DrainFilter<'_, T, F>
     }
 }
 
-#[unstable(feature = "drain_filter", reason = "recently added", issue = "43244")]
-impl<T, F> Drop for DrainFilter<'_, T, F>
+#[unstable(feature = "extract_if", reason = "recently added", issue = "43244")]
+impl<T, F> Drop for ExtractIf<'_, T, F>
     where F: FnMut(&mut T) -> bool,
 {
     fn drop(&mut self) {
-        self.for_each(drop);
+        if !self.panic_flag {
+            self.for_each(drop);
+        }
     }
 }
 
-#[unstable(feature = "drain_filter", reason = "recently added", issue = "43244")]
-impl<T: fmt::Debug, F> fmt::Debug for DrainFilter<'_, T, F>
+#[unstable(feature = "extract_if", reason = "recently added", issue = "43244")]
+impl<T: fmt::Debug, F> fmt::Debug for ExtractIf<'_, T, F>
     where F: FnMut(&mut T) -> bool
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_tuple("DrainFilter")
+        f.debug_tuple("ExtractIf")
          .field(&self.list)
          .finish()
     }
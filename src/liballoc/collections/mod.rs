@@ -41,32 +41,48 @@ pub use linked_list::LinkedList;
 #[doc(no_inline)]
 pub use vec_deque::VecDeque;
 
-use crate::alloc::{AllocErr, LayoutErr};
+use core::fmt;
 
-/// Augments `AllocErr` with a CapacityOverflow variant.
+use crate::alloc::{Layout, LayoutErr};
+
+/// The error type for `try_reserve` methods.
+///
+/// It tells apart a request that could never be met, because the size it
+/// works out to doesn't fit in memory at all, from one that was valid but
+/// which the allocator turned down.
 #[derive(Clone, PartialEq, Eq, Debug)]
 #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
-pub enum CollectionAllocErr {
+pub enum TryReserveError {
     /// Error due to the computed capacity exceeding the collection's maximum
     /// (usually `isize::MAX` bytes).
     CapacityOverflow,
-    /// Error due to the allocator (see the `AllocErr` type's docs).
-    AllocErr,
+    /// The memory allocator returned an error.
+    AllocError {
+        /// The layout of the allocation request that failed.
+        layout: Layout,
+    },
 }
 
 #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
-impl From<AllocErr> for CollectionAllocErr {
-    #[inline]
-    fn from(AllocErr: AllocErr) -> Self {
-        CollectionAllocErr::AllocErr
+impl fmt::Display for TryReserveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("memory allocation failed")?;
+        match *self {
+            TryReserveError::CapacityOverflow => {
+                f.write_str(" because the computed capacity exceeded the collection's maximum")
+            }
+            TryReserveError::AllocError { .. } => {
+                f.write_str(" because the memory allocator returned an error")
+            }
+        }
     }
 }
 
 #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
-impl From<LayoutErr> for CollectionAllocErr {
+impl From<LayoutErr> for TryReserveError {
     #[inline]
     fn from(_: LayoutErr) -> Self {
-        CollectionAllocErr::CapacityOverflow
+        TryReserveError::CapacityOverflow
     }
 }
 
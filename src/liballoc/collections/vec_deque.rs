@@ -17,7 +17,7 @@ use core::ptr::{self, NonNull};
 use core::slice;
 use core::hash::{Hash, Hasher};
 
-use crate::collections::CollectionAllocErr;
+use crate::collections::TryReserveError;
 use crate::raw_vec::RawVec;
 use crate::vec::Vec;
 
@@ -572,10 +572,10 @@ impl<T> VecDeque<T> {
     ///
     /// ```
     /// #![feature(try_reserve)]
-    /// use std::collections::CollectionAllocErr;
+    /// use std::collections::TryReserveError;
     /// use std::collections::VecDeque;
     ///
-    /// fn process_data(data: &[u32]) -> Result<VecDeque<u32>, CollectionAllocErr> {
+    /// fn process_data(data: &[u32]) -> Result<VecDeque<u32>, TryReserveError> {
     ///     let mut output = VecDeque::new();
     ///
     ///     // Pre-reserve the memory, exiting if we can't
@@ -591,7 +591,7 @@ impl<T> VecDeque<T> {
     /// # process_data(&[1, 2, 3]).expect("why is the test harness OOMing on 12 bytes?");
     /// ```
     #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
-    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), CollectionAllocErr>  {
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError>  {
         self.try_reserve(additional)
     }
 
@@ -610,10 +610,10 @@ impl<T> VecDeque<T> {
     ///
     /// ```
     /// #![feature(try_reserve)]
-    /// use std::collections::CollectionAllocErr;
+    /// use std::collections::TryReserveError;
     /// use std::collections::VecDeque;
     ///
-    /// fn process_data(data: &[u32]) -> Result<VecDeque<u32>, CollectionAllocErr> {
+    /// fn process_data(data: &[u32]) -> Result<VecDeque<u32>, TryReserveError> {
     ///     let mut output = VecDeque::new();
     ///
     ///     // Pre-reserve the memory, exiting if we can't
@@ -629,12 +629,12 @@ impl<T> VecDeque<T> {
     /// # process_data(&[1, 2, 3]).expect("why is the test harness OOMing on 12 bytes?");
     /// ```
     #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
-    pub fn try_reserve(&mut self, additional: usize) -> Result<(), CollectionAllocErr> {
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
         let old_cap = self.cap();
         let used_cap = self.len() + 1;
         let new_cap = used_cap.checked_add(additional)
             .and_then(|needed_cap| needed_cap.checked_next_power_of_two())
-            .ok_or(CollectionAllocErr::CapacityOverflow)?;
+            .ok_or(TryReserveError::CapacityOverflow)?;
 
         if new_cap > old_cap {
             self.buf.try_reserve_exact(used_cap, new_cap - used_cap)?;
@@ -1849,11 +1849,40 @@ impl<T> VecDeque<T> {
     #[stable(feature = "vec_deque_retain", since = "1.4.0")]
     pub fn retain<F>(&mut self, mut f: F)
         where F: FnMut(&T) -> bool
+    {
+        self.retain_mut(|x| f(x));
+    }
+
+    /// Retains only the elements specified by the predicate, passing a mutable reference to it.
+    ///
+    /// In other words, remove all elements `e` such that `f(&mut e)` returns false.
+    /// This method operates in place and preserves the order of the retained
+    /// elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(retain_mut)]
+    /// use std::collections::VecDeque;
+    ///
+    /// let mut buf = VecDeque::new();
+    /// buf.extend(1..5);
+    /// buf.retain_mut(|x| if *x % 2 == 0 {
+    ///     *x += 1;
+    ///     true
+    /// } else {
+    ///     false
+    /// });
+    /// assert_eq!(buf, [3, 5]);
+    /// ```
+    #[unstable(feature = "retain_mut", reason = "recently added", issue = "0")]
+    pub fn retain_mut<F>(&mut self, mut f: F)
+        where F: FnMut(&mut T) -> bool
     {
         let len = self.len();
         let mut del = 0;
         for i in 0..len {
-            if !f(&self[i]) {
+            if !f(&mut self[i]) {
                 del += 1;
             } else if del > 0 {
                 self.swap(i - del, i);
@@ -1864,6 +1893,61 @@ impl<T> VecDeque<T> {
         }
     }
 
+    /// Creates an iterator which uses a closure to determine if an element should be removed.
+    ///
+    /// If the closure returns true, then the element is removed and yielded.
+    /// If the closure returns false, the element will remain in the deque and will not be
+    /// yielded by the iterator. The closure is called on the elements front to back, and
+    /// may mutate them whether or not they are removed.
+    ///
+    /// The work is done lazily: if the returned iterator is dropped before it
+    /// is exhausted, the elements it hasn't looked at yet are run through the
+    /// closure then, and removed or kept accordingly.
+    ///
+    /// # Panics and leaks
+    ///
+    /// If the closure panics, the element it was called on and all the ones
+    /// after it are kept, and the closure isn't called again. The elements
+    /// removed so far are not put back.
+    ///
+    /// While the iterator is alive the deque's length is set to zero, so if
+    /// the iterator is leaked (with [`mem::forget`], say) the deque may lose
+    /// any number of its elements. They are leaked, never double-dropped.
+    ///
+    /// [`mem::forget`]: ../../std/mem/fn.forget.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(extract_if)]
+    /// use std::collections::VecDeque;
+    ///
+    /// let mut numbers: VecDeque<_> = (1..=10).collect();
+    ///
+    /// let evens = numbers.extract_if(|x| *x % 2 == 0).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(evens, [2, 4, 6, 8, 10]);
+    /// assert_eq!(numbers, [1, 3, 5, 7, 9]);
+    /// ```
+    #[unstable(feature = "extract_if", reason = "recently added", issue = "43244")]
+    pub fn extract_if<F>(&mut self, filter: F) -> ExtractIf<'_, T, F>
+        where F: FnMut(&mut T) -> bool
+    {
+        let old_len = self.len();
+
+        // Guard against us getting leaked (leak amplification)
+        self.head = self.tail;
+
+        ExtractIf {
+            deque: self,
+            idx: 0,
+            del: 0,
+            old_len,
+            pred: filter,
+            panic_flag: false,
+        }
+    }
+
     // This may panic or abort
     #[inline]
     fn grow_if_necessary(&mut self) {
@@ -2465,6 +2549,127 @@ impl<T> ExactSizeIterator for Drain<'_, T> {}
 #[stable(feature = "fused", since = "1.26.0")]
 impl<T> FusedIterator for Drain<'_, T> {}
 
+/// An iterator produced by calling `extract_if` on `VecDeque`.
+///
+/// This `struct` is created by the [`extract_if`] method on [`VecDeque`]. See its
+/// documentation for more.
+///
+/// [`extract_if`]: struct.VecDeque.html#method.extract_if
+/// [`VecDeque`]: struct.VecDeque.html
+#[unstable(feature = "extract_if", reason = "recently added", issue = "43244")]
+pub struct ExtractIf<'a, T: 'a, F: 'a>
+    where F: FnMut(&mut T) -> bool,
+{
+    deque: &'a mut VecDeque<T>,
+    /// The logical index of the element the next call to `next` will look at.
+    idx: usize,
+    /// The number of elements removed so far.
+    del: usize,
+    /// The length of `deque` before the iterator was created.
+    old_len: usize,
+    pred: F,
+    /// Set while `pred` runs, so that `drop` doesn't call it again after it panicked.
+    panic_flag: bool,
+}
+
+impl<T, F> ExtractIf<'_, T, F>
+    where F: FnMut(&mut T) -> bool,
+{
+    /// Returns a pointer to the element at logical index `idx`.
+    fn elem(&self, idx: usize) -> *mut T {
+        unsafe { self.deque.ptr().add(self.deque.wrap_add(self.deque.tail, idx)) }
+    }
+}
+
+#[unstable(feature = "extract_if", reason = "recently added", issue = "43244")]
+impl<T, F> Iterator for ExtractIf<'_, T, F>
+    where F: FnMut(&mut T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        unsafe {
+            while self.idx != self.old_len {
+                let i = self.idx;
+                let elem = self.elem(i);
+                self.panic_flag = true;
+                let extract = (self.pred)(&mut *elem);
+                self.panic_flag = false;
+                // Only move past the element once `pred` has returned, so that
+                // it is kept if `pred` panics.
+                self.idx += 1;
+                if extract {
+                    self.del += 1;
+                    return Some(ptr::read(elem));
+                } else if self.del > 0 {
+                    // The deque has length 0 while we're working, so nothing
+                    // gets dropped twice if `pred` panics.
+                    ptr::copy_nonoverlapping(elem, self.elem(i - self.del), 1);
+                }
+            }
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.old_len - self.idx))
+    }
+}
+
+#[unstable(feature = "extract_if", reason = "recently added", issue = "43244")]
+impl<T, F> Drop for ExtractIf<'_, T, F>
+    where F: FnMut(&mut T) -> bool,
+{
+    fn drop(&mut self) {
+        struct BackshiftOnDrop<'a, 'b, T: 'a, F: 'a>
+            where F: FnMut(&mut T) -> bool,
+        {
+            drain: &'b mut ExtractIf<'a, T, F>,
+        }
+
+        impl<T, F> Drop for BackshiftOnDrop<'_, '_, T, F>
+            where F: FnMut(&mut T) -> bool,
+        {
+            fn drop(&mut self) {
+                let drain = &mut *self.drain;
+                unsafe {
+                    // If `pred` panicked, the elements it didn't get to are
+                    // still in place behind the gap left by the removed ones.
+                    // Close the gap and keep them.
+                    if drain.del > 0 {
+                        for i in drain.idx..drain.old_len {
+                            ptr::copy_nonoverlapping(drain.elem(i), drain.elem(i - drain.del), 1);
+                        }
+                    }
+                    let len = drain.old_len - drain.del;
+                    drain.deque.head = drain.deque.wrap_add(drain.deque.tail, len);
+                }
+            }
+        }
+
+        let backshift = BackshiftOnDrop { drain: self };
+
+        // Run the remaining elements through `pred` unless it already
+        // panicked; the guard puts the deque back together either way.
+        if !backshift.drain.panic_flag {
+            backshift.drain.for_each(drop);
+        }
+    }
+}
+
+#[unstable(feature = "extract_if", reason = "recently added", issue = "43244")]
+impl<T: fmt::Debug, F> fmt::Debug for ExtractIf<'_, T, F>
+    where F: FnMut(&mut T) -> bool,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExtractIf")
+         .field("idx", &self.idx)
+         .field("del", &self.del)
+         .field("old_len", &self.old_len)
+         .finish()
+    }
+}
+
 #[stable(feature = "rust1", since = "1.0.0")]
 impl<A: PartialEq> PartialEq for VecDeque<A> {
     fn eq(&self, other: &VecDeque<A>) -> bool {
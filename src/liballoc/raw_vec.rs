@@ -7,8 +7,8 @@ use core::ops::Drop;
 use core::ptr::{self, NonNull, Unique};
 use core::slice;
 
-use crate::alloc::{Alloc, Layout, Global, handle_alloc_error};
-use crate::collections::CollectionAllocErr::{self, *};
+use crate::alloc::{Alloc, Layout, Global, handle_alloc_error, retry_on_oom};
+use crate::collections::TryReserveError::{self, *};
 use crate::boxed::Box;
 
 /// A low-level utility for more ergonomically allocating, reallocating, and deallocating
@@ -89,11 +89,11 @@ impl<T, A: Alloc> RawVec<T, A> {
             } else {
                 let align = mem::align_of::<T>();
                 let layout = Layout::from_size_align(alloc_size, align).unwrap();
-                let result = if zeroed {
+                let result = retry_on_oom(layout, || if zeroed {
                     a.alloc_zeroed(layout)
                 } else {
                     a.alloc(layout)
-                };
+                });
                 match result {
                     Ok(ptr) => ptr.cast(),
                     Err(_) => handle_alloc_error(layout),
@@ -306,23 +306,23 @@ impl<T, A: Alloc> RawVec<T, A> {
                     let new_cap = 2 * self.cap;
                     let new_size = new_cap * elem_size;
                     alloc_guard(new_size).unwrap_or_else(|_| capacity_overflow());
-                    let ptr_res = self.a.realloc(NonNull::from(self.ptr).cast(),
-                                                 cur,
-                                                 new_size);
+                    let new_layout = Layout::from_size_align_unchecked(new_size, cur.align());
+                    let ptr_res = retry_on_oom(new_layout, || {
+                        self.a.realloc(NonNull::from(self.ptr).cast(), cur, new_size)
+                    });
                     match ptr_res {
                         Ok(ptr) => (new_cap, ptr.cast().into()),
-                        Err(_) => handle_alloc_error(
-                            Layout::from_size_align_unchecked(new_size, cur.align())
-                        ),
+                        Err(_) => handle_alloc_error(new_layout),
                     }
                 }
                 None => {
                     // skip to 4 because tiny Vec's are dumb; but not if that
                     // would cause overflow
                     let new_cap = if elem_size > (!0) / 8 { 1 } else { 4 };
-                    match self.a.alloc_array::<T>(new_cap) {
+                    let layout = Layout::array::<T>(new_cap).unwrap();
+                    match retry_on_oom(layout, || self.a.alloc_array::<T>(new_cap)) {
                         Ok(ptr) => (new_cap, ptr.into()),
-                        Err(_) => handle_alloc_error(Layout::array::<T>(new_cap).unwrap()),
+                        Err(_) => handle_alloc_error(layout),
                     }
                 }
             };
@@ -382,7 +382,7 @@ impl<T, A: Alloc> RawVec<T, A> {
 
     /// The same as `reserve_exact`, but returns on errors instead of panicking or aborting.
     pub fn try_reserve_exact(&mut self, used_cap: usize, needed_extra_cap: usize)
-           -> Result<(), CollectionAllocErr> {
+           -> Result<(), TryReserveError> {
 
         self.reserve_internal(used_cap, needed_extra_cap, Fallible, Exact)
     }
@@ -410,7 +410,7 @@ impl<T, A: Alloc> RawVec<T, A> {
     pub fn reserve_exact(&mut self, used_cap: usize, needed_extra_cap: usize) {
         match self.reserve_internal(used_cap, needed_extra_cap, Infallible, Exact) {
             Err(CapacityOverflow) => capacity_overflow(),
-            Err(AllocErr) => unreachable!(),
+            Err(AllocError { .. }) => unreachable!(),
             Ok(()) => { /* yay */ }
          }
      }
@@ -419,7 +419,7 @@ impl<T, A: Alloc> RawVec<T, A> {
     /// needed_extra_cap` elements. This logic is used in amortized reserve methods.
     /// Returns `(new_capacity, new_alloc_size)`.
     fn amortized_new_size(&self, used_cap: usize, needed_extra_cap: usize)
-        -> Result<usize, CollectionAllocErr> {
+        -> Result<usize, TryReserveError> {
 
         // Nothing we can really do about these checks :(
         let required_cap = used_cap.checked_add(needed_extra_cap).ok_or(CapacityOverflow)?;
@@ -431,7 +431,7 @@ impl<T, A: Alloc> RawVec<T, A> {
 
     /// The same as `reserve`, but returns on errors instead of panicking or aborting.
     pub fn try_reserve(&mut self, used_cap: usize, needed_extra_cap: usize)
-        -> Result<(), CollectionAllocErr> {
+        -> Result<(), TryReserveError> {
         self.reserve_internal(used_cap, needed_extra_cap, Fallible, Amortized)
     }
 
@@ -490,7 +490,7 @@ impl<T, A: Alloc> RawVec<T, A> {
     pub fn reserve(&mut self, used_cap: usize, needed_extra_cap: usize) {
         match self.reserve_internal(used_cap, needed_extra_cap, Infallible, Amortized) {
             Err(CapacityOverflow) => capacity_overflow(),
-            Err(AllocErr) => unreachable!(),
+            Err(AllocError { .. }) => unreachable!(),
             Ok(()) => { /* yay */ }
         }
     }
@@ -601,13 +601,13 @@ impl<T, A: Alloc> RawVec<T, A> {
                 let new_size = elem_size * amount;
                 let align = mem::align_of::<T>();
                 let old_layout = Layout::from_size_align_unchecked(old_size, align);
-                match self.a.realloc(NonNull::from(self.ptr).cast(),
-                                     old_layout,
-                                     new_size) {
+                let new_layout = Layout::from_size_align_unchecked(new_size, align);
+                let ptr_res = retry_on_oom(new_layout, || {
+                    self.a.realloc(NonNull::from(self.ptr).cast(), old_layout, new_size)
+                });
+                match ptr_res {
                     Ok(p) => self.ptr = p.cast().into(),
-                    Err(_) => handle_alloc_error(
-                        Layout::from_size_align_unchecked(new_size, align)
-                    ),
+                    Err(_) => handle_alloc_error(new_layout),
                 }
             }
             self.cap = amount;
@@ -636,7 +636,7 @@ impl<T, A: Alloc> RawVec<T, A> {
         needed_extra_cap: usize,
         fallibility: Fallibility,
         strategy: ReserveStrategy,
-    ) -> Result<(), CollectionAllocErr> {
+    ) -> Result<(), TryReserveError> {
         unsafe {
             use crate::alloc::AllocErr;
 
@@ -660,12 +660,19 @@ impl<T, A: Alloc> RawVec<T, A> {
 
             alloc_guard(new_layout.size())?;
 
-            let res = match self.current_layout() {
+            let current_layout = self.current_layout();
+            let ptr = self.ptr;
+            let a = &mut self.a;
+            let mut attempt = || match current_layout {
                 Some(layout) => {
                     debug_assert!(new_layout.align() == layout.align());
-                    self.a.realloc(NonNull::from(self.ptr).cast(), layout, new_layout.size())
+                    a.realloc(NonNull::from(ptr).cast(), layout, new_layout.size())
                 }
-                None => self.a.alloc(new_layout),
+                None => a.alloc(new_layout),
+            };
+            let res = match fallibility {
+                Infallible => retry_on_oom(new_layout, &mut attempt),
+                Fallible => attempt(),
             };
 
             match (&res, fallibility) {
@@ -673,7 +680,7 @@ impl<T, A: Alloc> RawVec<T, A> {
                 _ => {}
             }
 
-            self.ptr = res?.cast().into();
+            self.ptr = res.map_err(|_| AllocError { layout: new_layout })?.cast().into();
             self.cap = new_cap;
 
             Ok(())
@@ -731,7 +738,7 @@ unsafe impl<#[may_dangle] T, A: Alloc> Drop for RawVec<T, A> {
 // all 4GB in user-space. e.g., PAE or x32
 
 #[inline]
-fn alloc_guard(alloc_size: usize) -> Result<(), CollectionAllocErr> {
+fn alloc_guard(alloc_size: usize) -> Result<(), TryReserveError> {
     if mem::size_of::<usize>() < 8 && alloc_size > core::isize::MAX as usize {
         Err(CapacityOverflow)
     } else {
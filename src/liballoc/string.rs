@@ -53,10 +53,11 @@ use core::iter::{FromIterator, FusedIterator};
 use core::ops::{self, Add, AddAssign, Index, IndexMut, RangeBounds};
 use core::ops::Bound::{Excluded, Included, Unbounded};
 use core::ptr;
+use core::slice;
 use core::str::{pattern::Pattern, lossy};
 
 use crate::borrow::{Cow, ToOwned};
-use crate::collections::CollectionAllocErr;
+use crate::collections::TryReserveError;
 use crate::boxed::Box;
 use crate::str::{self, from_boxed_utf8_unchecked, FromStr, Utf8Error, Chars};
 use crate::vec::Vec;
@@ -584,6 +585,99 @@ impl String {
         Cow::Owned(res)
     }
 
+    /// Converts a vector of bytes to a `String`, replacing invalid UTF-8
+    /// sequences with [`U+FFFD REPLACEMENT CHARACTER`][U+FFFD].
+    ///
+    /// This is the owned counterpart of [`from_utf8_lossy`]. The conversion
+    /// reuses the vector's buffer: if the bytes are valid UTF-8 nothing is
+    /// copied at all, and otherwise only the bytes from the first invalid
+    /// sequence on are moved, growing the buffer if the replacement
+    /// characters don't fit in its spare capacity.
+    ///
+    /// [U+FFFD]: ../char/constant.REPLACEMENT_CHARACTER.html
+    /// [`from_utf8_lossy`]: #method.from_utf8_lossy
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// #![feature(string_from_utf8_lossy_owned)]
+    /// // some bytes, in a vector
+    /// let sparkle_heart = vec![240, 159, 146, 150];
+    ///
+    /// let sparkle_heart = String::from_utf8_lossy_owned(sparkle_heart);
+    ///
+    /// assert_eq!("💖", sparkle_heart);
+    /// ```
+    ///
+    /// Incorrect bytes:
+    ///
+    /// ```
+    /// #![feature(string_from_utf8_lossy_owned)]
+    /// // some invalid bytes
+    /// let input = b"Hello \xF0\x90\x80World".to_vec();
+    /// let output = String::from_utf8_lossy_owned(input);
+    ///
+    /// assert_eq!("Hello �World", output);
+    /// ```
+    #[unstable(feature = "string_from_utf8_lossy_owned", issue = "0")]
+    pub fn from_utf8_lossy_owned(mut v: Vec<u8>) -> String {
+        const REPLACEMENT: &str = "\u{FFFD}";
+
+        // A broken sequence is never longer than the replacement character,
+        // so the string can only grow. Work out by how much, and where the
+        // first broken sequence is.
+        let mut first_broken = None;
+        let mut grow = 0;
+        let mut pos = 0;
+        for lossy::Utf8LossyChunk { valid, broken } in lossy::Utf8Lossy::from_bytes(&v).chunks() {
+            pos += valid.len();
+            if !broken.is_empty() {
+                first_broken.get_or_insert(pos);
+                grow += REPLACEMENT.len() - broken.len();
+                pos += broken.len();
+            }
+        }
+        let start = match first_broken {
+            Some(start) => start,
+            None => return unsafe { String::from_utf8_unchecked(v) },
+        };
+
+        let old_len = v.len();
+        let new_len = old_len + grow;
+        v.reserve(grow);
+        unsafe {
+            // Move everything from the first broken sequence on to the end of
+            // the new string, then rewrite it front to back. Every byte ends up
+            // at or before the place it was moved to, so nothing is
+            // overwritten before it has been read.
+            let ptr = v.as_mut_ptr();
+            ptr::copy(ptr.add(start), ptr.add(start + grow), old_len - start);
+            let mut read = start + grow;
+            let mut write = start;
+            while read < new_len {
+                let (valid, broken) = {
+                    let rest = slice::from_raw_parts(ptr.add(read), new_len - read);
+                    let chunk = lossy::Utf8Lossy::from_bytes(rest).chunks().next().unwrap();
+                    (chunk.valid.len(), chunk.broken.len())
+                };
+                ptr::copy(ptr.add(read), ptr.add(write), valid);
+                read += valid;
+                write += valid;
+                if broken != 0 {
+                    ptr::copy_nonoverlapping(REPLACEMENT.as_ptr(), ptr.add(write),
+                                             REPLACEMENT.len());
+                    read += broken;
+                    write += REPLACEMENT.len();
+                }
+            }
+            debug_assert_eq!(write, new_len);
+            v.set_len(new_len);
+            String::from_utf8_unchecked(v)
+        }
+    }
+
     /// Decode a UTF-16 encoded vector `v` into a `String`, returning [`Err`]
     /// if `v` contains any invalid data.
     ///
@@ -697,6 +791,37 @@ impl String {
         String { vec: Vec::from_raw_parts(buf, length, capacity) }
     }
 
+    /// Decomposes a `String` into its raw components.
+    ///
+    /// Returns the raw pointer to the underlying data, the length of
+    /// the string (in bytes), and the allocated capacity of the data
+    /// (in bytes). These are the same arguments in the same order as
+    /// the arguments to [`from_raw_parts`].
+    ///
+    /// After calling this function, the caller is responsible for the
+    /// memory previously managed by the `String`. The only way to do
+    /// this is to convert the raw pointer, length, and capacity back
+    /// into a `String` with the [`from_raw_parts`] function, allowing
+    /// the destructor to perform the cleanup.
+    ///
+    /// [`from_raw_parts`]: #method.from_raw_parts
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(vec_into_raw_parts)]
+    /// let s = String::from("hello");
+    ///
+    /// let (ptr, len, cap) = s.into_raw_parts();
+    ///
+    /// let rebuilt = unsafe { String::from_raw_parts(ptr, len, cap) };
+    /// assert_eq!(rebuilt, "hello");
+    /// ```
+    #[unstable(feature = "vec_into_raw_parts", reason = "new API", issue = "0")]
+    pub fn into_raw_parts(self) -> (*mut u8, usize, usize) {
+        self.vec.into_raw_parts()
+    }
+
     /// Converts a vector of bytes to a `String` without checking that the
     /// string contains valid UTF-8.
     ///
@@ -937,9 +1062,9 @@ impl String {
     ///
     /// ```
     /// #![feature(try_reserve)]
-    /// use std::collections::CollectionAllocErr;
+    /// use std::collections::TryReserveError;
     ///
-    /// fn process_data(data: &str) -> Result<String, CollectionAllocErr> {
+    /// fn process_data(data: &str) -> Result<String, TryReserveError> {
     ///     let mut output = String::new();
     ///
     ///     // Pre-reserve the memory, exiting if we can't
@@ -953,7 +1078,7 @@ impl String {
     /// # process_data("rust").expect("why is the test harness OOMing on 4 bytes?");
     /// ```
     #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
-    pub fn try_reserve(&mut self, additional: usize) -> Result<(), CollectionAllocErr> {
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
         self.vec.try_reserve(additional)
     }
 
@@ -975,9 +1100,9 @@ impl String {
     ///
     /// ```
     /// #![feature(try_reserve)]
-    /// use std::collections::CollectionAllocErr;
+    /// use std::collections::TryReserveError;
     ///
-    /// fn process_data(data: &str) -> Result<String, CollectionAllocErr> {
+    /// fn process_data(data: &str) -> Result<String, TryReserveError> {
     ///     let mut output = String::new();
     ///
     ///     // Pre-reserve the memory, exiting if we can't
@@ -991,7 +1116,7 @@ impl String {
     /// # process_data("rust").expect("why is the test harness OOMing on 4 bytes?");
     /// ```
     #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
-    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), CollectionAllocErr>  {
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError>  {
         self.vec.try_reserve_exact(additional)
     }
 
@@ -19,11 +19,12 @@ use core::pin::Pin;
 use core::ptr::{self, NonNull};
 use core::marker::{Unpin, Unsize, PhantomData};
 use core::hash::{Hash, Hasher};
+use core::iter;
 use core::{isize, usize};
 use core::convert::From;
 use core::slice::from_raw_parts_mut;
 
-use crate::alloc::{Global, Alloc, Layout, box_free, handle_alloc_error};
+use crate::alloc::{Global, Alloc, Layout, box_free, handle_alloc_error, retry_on_oom};
 use crate::boxed::Box;
 use crate::rc::is_dangling;
 use crate::string::String;
@@ -582,20 +583,23 @@ impl<T: ?Sized> Arc<T> {
 
 impl<T: ?Sized> Arc<T> {
     // Allocates an `ArcInner<T>` with sufficient space for an unsized value
-    unsafe fn allocate_for_ptr(ptr: *const T) -> *mut ArcInner<T> {
-        // Calculate layout using the given value.
-        // Previously, layout was calculated on the expression
-        // `&*(ptr as *const ArcInner<T>)`, but this created a misaligned
-        // reference (see #54908).
+    // whose layout is `value_layout`.
+    //
+    // `mem_to_inner` is given the start of the allocation and must return the
+    // (possibly fat) pointer to the `ArcInner<T>` there.
+    unsafe fn allocate_for_layout(
+        value_layout: Layout,
+        mem_to_inner: impl FnOnce(*mut u8) -> *mut ArcInner<T>,
+    ) -> *mut ArcInner<T> {
         let layout = Layout::new::<ArcInner<()>>()
-            .extend(Layout::for_value(&*ptr)).unwrap().0
+            .extend(value_layout).unwrap().0
             .pad_to_align().unwrap();
 
-        let mem = Global.alloc(layout)
+        let mem = retry_on_oom(layout, || Global.alloc(layout))
             .unwrap_or_else(|_| handle_alloc_error(layout));
 
         // Initialize the ArcInner
-        let inner = set_data_ptr(ptr as *mut T, mem.as_ptr() as *mut u8) as *mut ArcInner<T>;
+        let inner = mem_to_inner(mem.as_ptr());
         debug_assert_eq!(Layout::for_value(&*inner), layout);
 
         ptr::write(&mut (*inner).strong, atomic::AtomicUsize::new(1));
@@ -604,6 +608,18 @@ impl<T: ?Sized> Arc<T> {
         inner
     }
 
+    // Allocates an `ArcInner<T>` with sufficient space for an unsized value
+    unsafe fn allocate_for_ptr(ptr: *const T) -> *mut ArcInner<T> {
+        // Calculate layout using the given value.
+        // Previously, layout was calculated on the expression
+        // `&*(ptr as *const ArcInner<T>)`, but this created a misaligned
+        // reference (see #54908).
+        Self::allocate_for_layout(
+            Layout::for_value(&*ptr),
+            |mem| set_data_ptr(ptr as *mut T, mem) as *mut ArcInner<T>,
+        )
+    }
+
     fn from_box(v: Box<T>) -> Arc<T> {
         unsafe {
             let box_unique = Box::into_unique(v);
@@ -650,17 +666,21 @@ impl<T> Arc<[T]> {
 
         Arc { ptr: NonNull::new_unchecked(ptr), phantom: PhantomData }
     }
-}
 
-// Specialization trait used for From<&[T]>
-trait ArcFromSlice<T> {
-    fn from_slice(slice: &[T]) -> Self;
-}
+    // Allocates an `ArcInner<[T]>` with room for `len` elements.
+    unsafe fn allocate_for_slice(len: usize) -> *mut ArcInner<[T]> {
+        Self::allocate_for_layout(
+            Layout::array::<T>(len).unwrap(),
+            |mem| from_raw_parts_mut(mem as *mut T, len) as *mut [T] as *mut ArcInner<[T]>,
+        )
+    }
 
-impl<T: Clone> ArcFromSlice<T> for Arc<[T]> {
-    #[inline]
-    default fn from_slice(v: &[T]) -> Self {
-        // Panic guard while cloning T elements.
+    // Constructs an `Arc<[T]>` from an iterator known to yield exactly
+    // `len` items.
+    //
+    // Unsafe because behavior is undefined should `len` be wrong.
+    unsafe fn from_iter_exact(iter: impl Iterator<Item = T>, len: usize) -> Arc<[T]> {
+        // Panic guard while producing T elements.
         // In the event of a panic, elements that have been written
         // into the new ArcInner will be dropped, then the memory freed.
         struct Guard<T> {
@@ -681,36 +701,88 @@ impl<T: Clone> ArcFromSlice<T> for Arc<[T]> {
             }
         }
 
-        unsafe {
-            let v_ptr = v as *const [T];
-            let ptr = Self::allocate_for_ptr(v_ptr);
+        let ptr = Self::allocate_for_slice(len);
 
-            let mem = ptr as *mut _ as *mut u8;
-            let layout = Layout::for_value(&*ptr);
+        let mem = ptr as *mut _ as *mut u8;
+        let layout = Layout::for_value(&*ptr);
 
-            // Pointer to first element
-            let elems = &mut (*ptr).data as *mut [T] as *mut T;
+        // Pointer to first element
+        let elems = &mut (*ptr).data as *mut [T] as *mut T;
 
-            let mut guard = Guard{
-                mem: NonNull::new_unchecked(mem),
-                elems: elems,
-                layout: layout,
-                n_elems: 0,
-            };
+        let mut guard = Guard {
+            mem: NonNull::new_unchecked(mem),
+            elems,
+            layout,
+            n_elems: 0,
+        };
+
+        for (i, item) in iter.enumerate() {
+            ptr::write(elems.add(i), item);
+            guard.n_elems += 1;
+        }
 
-            for (i, item) in v.iter().enumerate() {
-                ptr::write(elems.add(i), item.clone());
-                guard.n_elems += 1;
+        // All clear. Forget the guard so it doesn't free the new ArcInner.
+        mem::forget(guard);
+
+        Arc { ptr: NonNull::new_unchecked(ptr), phantom: PhantomData }
+    }
+
+    // Moves the elements of `v` into a new `Arc<[T]>`.
+    //
+    // The counts have to go in front of the elements, so the vector's buffer
+    // can only be kept if it is aligned for them and its spare capacity has
+    // room for them. In that case it is shrunk to the size of the `ArcInner`
+    // and the elements are moved up behind the counts; otherwise they're
+    // copied into a new allocation.
+    fn from_vec(mut v: Vec<T>) -> Arc<[T]> {
+        unsafe {
+            let len = v.len();
+            let cap = v.capacity();
+            let (layout, offset) = Layout::new::<ArcInner<()>>()
+                .extend(Layout::array::<T>(len).unwrap()).unwrap();
+            let layout = layout.pad_to_align().unwrap();
+
+            if mem::size_of::<T>() == 0 || mem::align_of::<T>() != layout.align() ||
+                cap * mem::size_of::<T>() < layout.size() {
+                let arc = Arc::copy_from_slice(&v);
+
+                // Allow the Vec to free its memory, but not destroy its contents
+                v.set_len(0);
+
+                return arc;
             }
 
-            // All clear. Forget the guard so it doesn't free the new ArcInner.
-            mem::forget(guard);
+            let (ptr, _, _) = v.into_raw_parts();
+            let old_layout = Layout::array::<T>(cap).unwrap();
+            let mem = retry_on_oom(layout, || {
+                Global.realloc(NonNull::new_unchecked(ptr).cast(), old_layout, layout.size())
+            }).unwrap_or_else(|_| handle_alloc_error(layout))
+                .as_ptr();
+            ptr::copy(mem as *const T, mem.add(offset) as *mut T, len);
 
-            Arc { ptr: NonNull::new_unchecked(ptr), phantom: PhantomData }
+            let inner = from_raw_parts_mut(mem as *mut T, len) as *mut [T] as *mut ArcInner<[T]>;
+            debug_assert_eq!(Layout::for_value(&*inner), layout);
+
+            ptr::write(&mut (*inner).strong, atomic::AtomicUsize::new(1));
+            ptr::write(&mut (*inner).weak, atomic::AtomicUsize::new(1));
+
+            Arc { ptr: NonNull::new_unchecked(inner), phantom: PhantomData }
         }
     }
 }
 
+// Specialization trait used for From<&[T]>
+trait ArcFromSlice<T> {
+    fn from_slice(slice: &[T]) -> Self;
+}
+
+impl<T: Clone> ArcFromSlice<T> for Arc<[T]> {
+    #[inline]
+    default fn from_slice(v: &[T]) -> Self {
+        unsafe { Self::from_iter_exact(v.iter().cloned(), v.len()) }
+    }
+}
+
 impl<T: Copy> ArcFromSlice<T> for Arc<[T]> {
     #[inline]
     fn from_slice(v: &[T]) -> Self {
@@ -1634,14 +1706,61 @@ impl<T: ?Sized> From<Box<T>> for Arc<T> {
 #[stable(feature = "shared_from_slice", since = "1.21.0")]
 impl<T> From<Vec<T>> for Arc<[T]> {
     #[inline]
-    fn from(mut v: Vec<T>) -> Arc<[T]> {
-        unsafe {
-            let arc = Arc::copy_from_slice(&v);
+    fn from(v: Vec<T>) -> Arc<[T]> {
+        Arc::from_vec(v)
+    }
+}
+
+#[stable(feature = "shared_from_iter", since = "1.34.0")]
+impl<T> iter::FromIterator<T> for Arc<[T]> {
+    /// Takes each element in the `Iterator` and collects it into an `Arc<[T]>`.
+    ///
+    /// # Performance characteristics
+    ///
+    /// In general, the elements are first collected into a `Vec<T>`, which
+    /// is then turned into an `Arc<[T]>`; see `From<Vec<T>>` for when that
+    /// reuses the vector's allocation.
+    ///
+    /// When the iterator implements `TrustedLen`, so that its exact length
+    /// is known up front, the elements are written straight into the new
+    /// `Arc<[T]>` instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// let evens: Arc<[u8]> = (0..10).filter(|&x| x % 2 == 0).collect();
+    /// assert_eq!(&*evens, [0, 2, 4, 6, 8]);
+    ///
+    /// let squares: Arc<[u32]> = (0..5).map(|i| i * i).collect();
+    /// assert_eq!(&*squares, [0, 1, 4, 9, 16]);
+    /// ```
+    fn from_iter<I: iter::IntoIterator<Item = T>>(iter: I) -> Self {
+        ArcFromIter::from_iter(iter.into_iter())
+    }
+}
+
+// Specialization trait used for collecting into `Arc<[T]>`.
+trait ArcFromIter<T, I> {
+    fn from_iter(iter: I) -> Self;
+}
 
-            // Allow the Vec to free its memory, but not destroy its contents
-            v.set_len(0);
+impl<T, I: Iterator<Item = T>> ArcFromIter<T, I> for Arc<[T]> {
+    default fn from_iter(iter: I) -> Self {
+        iter.collect::<Vec<T>>().into()
+    }
+}
 
-            arc
+impl<T, I: iter::TrustedLen<Item = T>> ArcFromIter<T, I> for Arc<[T]> {
+    default fn from_iter(iter: I) -> Self {
+        match iter.size_hint() {
+            (low, Some(high)) => {
+                debug_assert_eq!(low, high, "TrustedLen iterator's size hint is not exact");
+                unsafe { Arc::from_iter_exact(iter, low) }
+            }
+            // TrustedLen's contract is that a missing upper bound means more
+            // than `usize::MAX` elements, which could never fit in memory.
+            (_, None) => panic!("capacity overflow"),
         }
     }
 }
@@ -2106,6 +2225,45 @@ mod tests {
         assert_eq!(&r[..], [1, 2, 3]);
     }
 
+    #[test]
+    fn test_from_vec_spare_capacity() {
+        use std::string::{String, ToString};
+        use std::vec::Vec;
+
+        let mut v: Vec<String> = Vec::with_capacity(8);
+        v.extend((0..5).map(|i| i.to_string()));
+        let r: Arc<[String]> = Arc::from(v);
+        assert_eq!(&r[..], ["0", "1", "2", "3", "4"]);
+
+        let v = vec!["a".to_string()];
+        let r: Arc<[String]> = Arc::from(v);
+        assert_eq!(&r[..], ["a"]);
+
+        let mut v = Vec::with_capacity(16);
+        v.extend_from_slice(b"bytes");
+        let r: Arc<[u8]> = Arc::from(v);
+        assert_eq!(&r[..], b"bytes");
+
+        let r: Arc<[String]> = Arc::from(Vec::new());
+        assert!(r.is_empty());
+    }
+
+    #[test]
+    fn test_from_iter() {
+        use std::iter::FromIterator;
+
+        // Not TrustedLen: collected into a Vec first.
+        let r: Arc<[u32]> = (0..10).filter(|x| x % 3 == 0).collect();
+        assert_eq!(&r[..], [0, 3, 6, 9]);
+
+        // TrustedLen: written straight into the allocation.
+        let r: Arc<[u32]> = (0..4).map(|x| x * 2).collect();
+        assert_eq!(&r[..], [0, 2, 4, 6]);
+
+        let r = Arc::<[Box<u32>]>::from_iter((0..3).map(Box::new));
+        assert_eq!(*r[2], 2);
+    }
+
     #[test]
     fn test_downcast() {
         use std::any::Any;
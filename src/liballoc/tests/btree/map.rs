@@ -633,6 +633,179 @@ create_append_test!(test_append_181, 181);
 create_append_test!(test_append_239, 239);
 create_append_test!(test_append_1700, 1700);
 
+macro_rules! create_append_sorted_test {
+    ($name:ident, $len:expr) => {
+        #[test]
+        fn $name() {
+            let mut a = BTreeMap::new();
+            for i in 0..8 {
+                a.insert(i, i);
+            }
+
+            a.append_sorted((8..$len).map(|i| (i, 2*i)));
+
+            assert_eq!(a.len(), $len);
+            for i in 0..$len {
+                if i < 8 {
+                    assert_eq!(a[&i], i);
+                } else {
+                    assert_eq!(a[&i], 2*i);
+                }
+            }
+
+            assert_eq!(a.remove(&($len-1)), Some(2*($len-1)));
+            assert_eq!(a.insert($len-1, 20), None);
+        }
+    };
+}
+
+// Same shapes as the `append` tests above.
+create_append_sorted_test!(test_append_sorted_9, 9);
+create_append_sorted_test!(test_append_sorted_14, 14);
+create_append_sorted_test!(test_append_sorted_145, 145);
+create_append_sorted_test!(test_append_sorted_1700, 1700);
+
+#[test]
+fn test_append_sorted_unsorted() {
+    let data = rand_data(300);
+
+    // Not sorted at all, and partly below the existing keys.
+    let mut map = BTreeMap::new();
+    map.insert(data[0].0, data[0].1);
+    map.append_sorted(data.clone());
+    assert!(map.into_iter().eq(BTreeMap::from_iter(data.clone())));
+
+    // Sorted for a while, then not.
+    let mut sorted = data.clone();
+    sorted.sort();
+    sorted.extend(data.iter().cloned().map(|(k, v)| (k, v + 1)));
+    let mut map = BTreeMap::new();
+    map.append_sorted(sorted.clone());
+    assert!(map.into_iter().eq(BTreeMap::from_iter(sorted)));
+}
+
+#[test]
+fn test_append_sorted_duplicates() {
+    let mut map = BTreeMap::new();
+    map.append_sorted(vec![(1, 'a'), (2, 'b'), (2, 'c'), (3, 'd')]);
+    assert_eq!(map.len(), 3);
+    assert_eq!(map[&2], 'c');
+    assert_eq!(map[&3], 'd');
+}
+
+#[test]
+fn test_cursor() {
+    let map = BTreeMap::from_iter((0..100).map(|i| (i * 2, i)));
+
+    let mut cur = map.lower_bound(Included(&10));
+    assert_eq!(cur.key_value(), Some((&10, &5)));
+    assert_eq!(cur.peek_prev(), Some((&8, &4)));
+    assert_eq!(cur.peek_next(), Some((&12, &6)));
+    cur.move_next();
+    assert_eq!(cur.key(), Some(&12));
+    cur.move_prev();
+    cur.move_prev();
+    assert_eq!(cur.key(), Some(&8));
+
+    assert_eq!(map.lower_bound(Excluded(&10)).key(), Some(&12));
+    assert_eq!(map.lower_bound(Included(&11)).key(), Some(&12));
+    assert_eq!(map.lower_bound(Unbounded::<&i32>).key(), Some(&0));
+    assert_eq!(map.upper_bound(Included(&10)).key(), Some(&10));
+    assert_eq!(map.upper_bound(Excluded(&10)).key(), Some(&8));
+    assert_eq!(map.upper_bound(Included(&11)).key(), Some(&10));
+    assert_eq!(map.upper_bound(Unbounded::<&i32>).key(), Some(&198));
+
+    // Walking off either end reaches the ghost, and walking on wraps around.
+    let mut cur = map.upper_bound(Unbounded::<&i32>);
+    cur.move_next();
+    assert_eq!(cur.key(), None);
+    assert_eq!(cur.peek_next(), Some((&0, &0)));
+    assert_eq!(cur.peek_prev(), Some((&198, &99)));
+    cur.move_next();
+    assert_eq!(cur.key(), Some(&0));
+    assert_eq!(map.lower_bound(Excluded(&198)).key(), None);
+    assert_eq!(map.upper_bound(Excluded(&0)).key(), None);
+
+    // A full walk visits every element in order, whichever level it's stored at.
+    let mut cur = map.lower_bound(Unbounded::<&i32>);
+    let mut keys = vec![];
+    while let Some(&k) = cur.key() {
+        keys.push(k);
+        cur.move_next();
+    }
+    assert!(keys.into_iter().eq((0..100).map(|i| i * 2)));
+
+    let mut cur = map.upper_bound(Unbounded::<&i32>);
+    let mut keys = vec![];
+    while let Some(&k) = cur.key() {
+        keys.push(k);
+        cur.move_prev();
+    }
+    assert!(keys.into_iter().eq((0..100).rev().map(|i| i * 2)));
+
+    let empty = BTreeMap::<i32, i32>::new();
+    let mut cur = empty.lower_bound(Unbounded::<&i32>);
+    assert_eq!(cur.key(), None);
+    cur.move_next();
+    assert_eq!(cur.key(), None);
+}
+
+#[test]
+fn test_cursor_mut() {
+    let mut map = BTreeMap::from_iter((0..200).map(|i| (i * 2, i)));
+
+    // Remove every multiple of 4 in a single pass.
+    {
+        let mut cur = map.lower_bound_mut(Unbounded::<&i32>);
+        while let Some(&k) = cur.key() {
+            if k % 4 == 0 {
+                assert_eq!(cur.remove_current(), Some((k, k / 2)));
+            } else {
+                *cur.value_mut().unwrap() += 1000;
+                cur.move_next();
+            }
+        }
+    }
+    assert_eq!(map.len(), 100);
+    assert!(map.iter().map(|(&k, &v)| (k, v))
+               .eq((0..100).map(|i| (i * 4 + 2, i * 2 + 1 + 1000))));
+
+    // Put back the odd numbers in front of each element.
+    {
+        let mut cur = map.lower_bound_mut(Unbounded::<&i32>);
+        while let Some(&k) = cur.key() {
+            cur.insert_before(k - 1, 0);
+            assert_eq!(cur.key(), Some(&k));
+            assert_eq!(cur.peek_prev().map(|(&k, &mut v)| (k, v)), Some((k - 1, 0)));
+            cur.move_next();
+        }
+        // At the ghost, insertion appends.
+        cur.insert_before(1000, 0);
+        assert_eq!(cur.key(), None);
+    }
+    assert_eq!(map.len(), 201);
+    assert!(map.keys().cloned().eq((0..100).flat_map(|i| vec![i * 4 + 1, i * 4 + 2])
+                                            .chain(Some(1000))));
+
+    // Draining through the cursor leaves an empty map behind.
+    {
+        let mut cur = map.upper_bound_mut(Unbounded::<&i32>);
+        cur.move_next();
+        cur.move_next();
+        while cur.remove_current().is_some() {}
+        assert_eq!(cur.key(), None);
+    }
+    assert!(map.is_empty());
+}
+
+#[test]
+#[should_panic]
+fn test_cursor_mut_insert_before_unordered() {
+    let mut map = BTreeMap::from_iter((0..10).map(|i| (i * 2, i)));
+    let mut cur = map.lower_bound_mut(Included(&4));
+    cur.insert_before(1, 0);
+}
+
 fn rand_data(len: usize) -> Vec<(u32, u32)> {
     let mut rng = DeterministicRng::new();
     Vec::from_iter((0..len).map(|_| (rng.next(), rng.next())))
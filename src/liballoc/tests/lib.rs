@@ -1,12 +1,21 @@
 #![feature(allocator_api)]
 #![feature(box_syntax)]
+#![feature(btree_append_sorted)]
+#![feature(btree_cursors)]
 #![feature(drain_filter)]
 #![feature(exact_size_is_empty)]
+#![feature(extract_if)]
+#![feature(linked_list_retain)]
 #![feature(pattern)]
 #![feature(repeat_generic_slice)]
+#![feature(retain_mut)]
 #![feature(slice_sort_by_cached_key)]
+#![feature(split_inclusive)]
+#![feature(str_split_once)]
+#![feature(string_from_utf8_lossy_owned)]
 #![feature(try_reserve)]
 #![feature(unboxed_closures)]
+#![feature(vec_into_raw_parts)]
 #![feature(vecdeque_rotate)]
 
 extern crate core;
@@ -546,3 +546,30 @@ fn drain_filter_complex() {
         assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![1, 3, 5, 7, 9, 11, 13, 15, 17, 19]);
     }
 }
+
+#[test]
+fn extract_if_unconsumed() {
+    let mut list: LinkedList<u32> = (1..=4).collect();
+    let drain = list.extract_if(|&mut x| x % 2 != 0);
+    drop(drain);
+    assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![2, 4]);
+}
+
+#[test]
+fn test_retain() {
+    let mut list: LinkedList<u32> = (1..=6).collect();
+    list.retain(|&x| x > 3);
+    assert_eq!(list.len(), 3);
+    assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![4, 5, 6]);
+}
+
+#[test]
+fn test_retain_mut() {
+    let mut list: LinkedList<u32> = (1..=6).collect();
+    list.retain_mut(|x| {
+        *x *= 10;
+        *x % 20 == 0
+    });
+    assert_eq!(list.len(), 3);
+    assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![20, 40, 60]);
+}
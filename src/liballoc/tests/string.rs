@@ -1,7 +1,7 @@
 #![cfg(not(miri))]
 
 use std::borrow::Cow;
-use std::collections::CollectionAllocErr::*;
+use std::collections::TryReserveError::*;
 use std::mem::size_of;
 use std::{usize, isize};
 
@@ -93,6 +93,51 @@ fn test_from_utf8_lossy() {
                String::from("\u{FFFD}\u{FFFD}\u{FFFD}foo\u{FFFD}\u{FFFD}\u{FFFD}bar").into_cow());
 }
 
+#[test]
+fn test_into_raw_parts() {
+    let mut s = String::with_capacity(10);
+    s.push_str("héllo");
+    let (ptr, len, cap) = s.into_raw_parts();
+    assert_eq!((len, cap), (6, 10));
+    let s = unsafe { String::from_raw_parts(ptr, len, cap) };
+    assert_eq!(s, "héllo");
+}
+
+#[test]
+fn test_from_utf8_lossy_owned() {
+    let xs = b"hello".to_vec();
+    let ptr = xs.as_ptr();
+    let ys = String::from_utf8_lossy_owned(xs);
+    assert_eq!(ys, "hello");
+    assert_eq!(ys.as_ptr(), ptr);
+
+    let inputs: &[&[u8]] = &[
+        b"",
+        "ศไทย中华Việt Nam".as_bytes(),
+        b"Hello\xC2 There\xFF Goodbye",
+        b"Hello\xC0\x80 There\xE6\x83 Goodbye",
+        b"\xF5foo\xF5\x80bar",
+        b"\xF1foo\xF1\x80bar\xF1\x80\x80baz",
+        b"\xF4foo\xF4\x80bar\xF4\xBFbaz",
+        b"\xF0\x80\x80\x80foo\xF0\x90\x80\x80bar",
+        b"\xED\xA0\x80foo\xED\xBF\xBFbar",
+        b"\xFF\xFF\xFF",
+        b"abc\xF0\x90\x80",
+    ];
+    for &xs in inputs {
+        assert_eq!(String::from_utf8_lossy_owned(xs.to_vec()), String::from_utf8_lossy(xs));
+        assert_eq!(xs.to_vec().into_string_lossy(), String::from_utf8_lossy(xs));
+
+        // With enough spare capacity the buffer is reused.
+        let mut v = Vec::with_capacity(xs.len() * 3);
+        v.extend_from_slice(xs);
+        let ptr = v.as_ptr();
+        let s = String::from_utf8_lossy_owned(v);
+        assert_eq!(s, String::from_utf8_lossy(xs));
+        assert_eq!(s.as_ptr(), ptr);
+    }
+}
+
 #[test]
 fn test_from_utf16() {
     let pairs = [(String::from("𐍅𐌿𐌻𐍆𐌹𐌻𐌰\n"),
@@ -567,11 +612,11 @@ fn test_try_reserve() {
             } else { panic!("usize::MAX should trigger an overflow!") }
         } else {
             // Check isize::MAX + 1 is an OOM
-            if let Err(AllocErr) = empty_string.try_reserve(MAX_CAP + 1) {
+            if let Err(AllocError { .. }) = empty_string.try_reserve(MAX_CAP + 1) {
             } else { panic!("isize::MAX + 1 should trigger an OOM!") }
 
             // Check usize::MAX is an OOM
-            if let Err(AllocErr) = empty_string.try_reserve(MAX_USIZE) {
+            if let Err(AllocError { .. }) = empty_string.try_reserve(MAX_USIZE) {
             } else { panic!("usize::MAX should trigger an OOM!") }
         }
     }
@@ -591,7 +636,7 @@ fn test_try_reserve() {
             if let Err(CapacityOverflow) = ten_bytes.try_reserve(MAX_CAP - 9) {
             } else { panic!("isize::MAX + 1 should trigger an overflow!"); }
         } else {
-            if let Err(AllocErr) = ten_bytes.try_reserve(MAX_CAP - 9) {
+            if let Err(AllocError { .. }) = ten_bytes.try_reserve(MAX_CAP - 9) {
             } else { panic!("isize::MAX + 1 should trigger an OOM!") }
         }
         // Should always overflow in the add-to-len
@@ -629,10 +674,10 @@ fn test_try_reserve_exact() {
             if let Err(CapacityOverflow) = empty_string.try_reserve_exact(MAX_USIZE) {
             } else { panic!("usize::MAX should trigger an overflow!") }
         } else {
-            if let Err(AllocErr) = empty_string.try_reserve_exact(MAX_CAP + 1) {
+            if let Err(AllocError { .. }) = empty_string.try_reserve_exact(MAX_CAP + 1) {
             } else { panic!("isize::MAX + 1 should trigger an OOM!") }
 
-            if let Err(AllocErr) = empty_string.try_reserve_exact(MAX_USIZE) {
+            if let Err(AllocError { .. }) = empty_string.try_reserve_exact(MAX_USIZE) {
             } else { panic!("usize::MAX should trigger an OOM!") }
         }
     }
@@ -651,7 +696,7 @@ fn test_try_reserve_exact() {
             if let Err(CapacityOverflow) = ten_bytes.try_reserve_exact(MAX_CAP - 9) {
             } else { panic!("isize::MAX + 1 should trigger an overflow!"); }
         } else {
-            if let Err(AllocErr) = ten_bytes.try_reserve_exact(MAX_CAP - 9) {
+            if let Err(AllocError { .. }) = ten_bytes.try_reserve_exact(MAX_CAP - 9) {
             } else { panic!("isize::MAX + 1 should trigger an OOM!") }
         }
         if let Err(CapacityOverflow) = ten_bytes.try_reserve_exact(MAX_USIZE) {
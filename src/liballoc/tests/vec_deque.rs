@@ -1,6 +1,6 @@
 use std::fmt::Debug;
 use std::collections::{VecDeque, vec_deque::Drain};
-use std::collections::CollectionAllocErr::*;
+use std::collections::TryReserveError::*;
 use std::mem::size_of;
 use std::{usize, isize};
 
@@ -1034,6 +1034,59 @@ fn test_retain() {
     assert_eq!(&v[..], &[2, 4]);
 }
 
+#[test]
+fn test_retain_mut() {
+    let mut buf = VecDeque::new();
+    buf.extend(1..5);
+    buf.retain_mut(|x| {
+        *x *= 10;
+        *x % 20 == 0
+    });
+    assert_eq!(buf, [20, 40]);
+}
+
+#[test]
+fn test_extract_if() {
+    // Make the contents wrap around the end of the buffer.
+    let mut buf = VecDeque::with_capacity(15);
+    buf.extend(0..10);
+    for _ in 0..8 {
+        let x = buf.pop_front().unwrap();
+        buf.push_back(x);
+    }
+    assert!(!buf.as_slices().1.is_empty());
+
+    let removed = buf.extract_if(|x| *x % 3 == 0).collect::<Vec<_>>();
+    assert_eq!(removed, [9, 0, 3, 6]);
+    assert_eq!(buf, [8, 1, 2, 4, 5, 7]);
+
+    let drain = buf.extract_if(|x| *x > 4);
+    drop(drain);
+    assert_eq!(buf, [1, 2, 4]);
+
+    buf.push_back(10);
+    assert_eq!(buf, [1, 2, 4, 10]);
+}
+
+#[test]
+fn test_extract_if_pred_panic() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::rc::Rc;
+
+    let drops = Rc::new(());
+    let mut buf = (0..8).map(|i| (i, drops.clone())).collect::<VecDeque<_>>();
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        for _ in buf.extract_if(|&mut (i, _)| if i == 5 { panic!() } else { i % 2 == 0 }) {}
+    }));
+    assert!(result.is_err());
+
+    assert_eq!(buf.iter().map(|&(i, _)| i).collect::<Vec<_>>(), [1, 3, 5, 6, 7]);
+    assert_eq!(Rc::strong_count(&drops), 6);
+    drop(buf);
+    assert_eq!(Rc::strong_count(&drops), 1);
+}
+
 #[test]
 fn test_extend_ref() {
     let mut v = VecDeque::new();
@@ -1171,7 +1224,7 @@ fn test_try_reserve() {
             // VecDeque starts with capacity 7, always adds 1 to the capacity
             // and also rounds the number to next power of 2 so this is the
             // furthest we can go without triggering CapacityOverflow
-            if let Err(AllocErr) = empty_bytes.try_reserve(MAX_CAP) {
+            if let Err(AllocError { .. }) = empty_bytes.try_reserve(MAX_CAP) {
             } else { panic!("isize::MAX + 1 should trigger an OOM!") }
         }
     }
@@ -1191,7 +1244,7 @@ fn test_try_reserve() {
             if let Err(CapacityOverflow) = ten_bytes.try_reserve(MAX_CAP - 9) {
             } else { panic!("isize::MAX + 1 should trigger an overflow!"); }
         } else {
-            if let Err(AllocErr) = ten_bytes.try_reserve(MAX_CAP - 9) {
+            if let Err(AllocError { .. }) = ten_bytes.try_reserve(MAX_CAP - 9) {
             } else { panic!("isize::MAX + 1 should trigger an OOM!") }
         }
         // Should always overflow in the add-to-len
@@ -1214,7 +1267,7 @@ fn test_try_reserve() {
             if let Err(CapacityOverflow) = ten_u32s.try_reserve(MAX_CAP/4 - 9) {
             } else { panic!("isize::MAX + 1 should trigger an overflow!"); }
         } else {
-            if let Err(AllocErr) = ten_u32s.try_reserve(MAX_CAP/4 - 9) {
+            if let Err(AllocError { .. }) = ten_u32s.try_reserve(MAX_CAP/4 - 9) {
             } else { panic!("isize::MAX + 1 should trigger an OOM!") }
         }
         // Should fail in the mul-by-size
@@ -1259,7 +1312,7 @@ fn test_try_reserve_exact() {
             // VecDeque starts with capacity 7, always adds 1 to the capacity
             // and also rounds the number to next power of 2 so this is the
             // furthest we can go without triggering CapacityOverflow
-            if let Err(AllocErr) = empty_bytes.try_reserve_exact(MAX_CAP) {
+            if let Err(AllocError { .. }) = empty_bytes.try_reserve_exact(MAX_CAP) {
             } else { panic!("isize::MAX + 1 should trigger an OOM!") }
         }
     }
@@ -1278,7 +1331,7 @@ fn test_try_reserve_exact() {
             if let Err(CapacityOverflow) = ten_bytes.try_reserve_exact(MAX_CAP - 9) {
             } else { panic!("isize::MAX + 1 should trigger an overflow!"); }
         } else {
-            if let Err(AllocErr) = ten_bytes.try_reserve_exact(MAX_CAP - 9) {
+            if let Err(AllocError { .. }) = ten_bytes.try_reserve_exact(MAX_CAP - 9) {
             } else { panic!("isize::MAX + 1 should trigger an OOM!") }
         }
         if let Err(CapacityOverflow) = ten_bytes.try_reserve_exact(MAX_USIZE) {
@@ -1299,7 +1352,7 @@ fn test_try_reserve_exact() {
             if let Err(CapacityOverflow) = ten_u32s.try_reserve_exact(MAX_CAP/4 - 9) {
             } else { panic!("isize::MAX + 1 should trigger an overflow!"); }
         } else {
-            if let Err(AllocErr) = ten_u32s.try_reserve_exact(MAX_CAP/4 - 9) {
+            if let Err(AllocError { .. }) = ten_u32s.try_reserve_exact(MAX_CAP/4 - 9) {
             } else { panic!("isize::MAX + 1 should trigger an OOM!") }
         }
         if let Err(CapacityOverflow) = ten_u32s.try_reserve_exact(MAX_USIZE - 20) {
@@ -72,3 +72,47 @@ pub fn spin_loop() {
         asm!("yield" ::: "memory" : "volatile");
     }
 }
+
+/// A function that is opaque to the optimizer, to allow benchmarks to
+/// pretend to use outputs to assist in avoiding dead-code
+/// elimination.
+///
+/// The value is returned unchanged, but the optimizer has to assume that
+/// its computation matters and that the result may be anything. This is a
+/// best-effort barrier: on some targets it is only an `#[inline(never)]`
+/// call, and it must never be relied on for the correctness of a program.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(bench_black_box)]
+/// use std::hint::black_box;
+///
+/// fn sum(n: u64) -> u64 {
+///     (0..n).sum()
+/// }
+///
+/// // Without `black_box`, the sum could be computed at compile time.
+/// assert_eq!(black_box(sum(black_box(100))), 4950);
+/// ```
+#[cfg(not(any(target_arch = "asmjs", target_arch = "wasm32")))]
+#[inline]
+#[unstable(feature = "bench_black_box", issue = "0")]
+pub fn black_box<T>(dummy: T) -> T {
+    // we need to "use" the argument in some way LLVM can't
+    // introspect.
+    unsafe { asm!("" : : "r"(&dummy)) }
+    dummy
+}
+
+/// A function that is opaque to the optimizer, to allow benchmarks to
+/// pretend to use outputs to assist in avoiding dead-code
+/// elimination.
+///
+/// On this target it is only an `#[inline(never)]` call.
+#[cfg(any(target_arch = "asmjs", target_arch = "wasm32"))]
+#[inline(never)]
+#[unstable(feature = "bench_black_box", issue = "0")]
+pub fn black_box<T>(dummy: T) -> T {
+    dummy
+}
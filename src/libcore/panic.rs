@@ -34,8 +34,10 @@ use fmt;
 #[derive(Debug)]
 pub struct PanicInfo<'a> {
     payload: &'a (dyn Any + Send),
+    payload_str: Option<&'a str>,
     message: Option<&'a fmt::Arguments<'a>>,
     location: Location<'a>,
+    thread_name: Option<&'a str>,
 }
 
 impl<'a> PanicInfo<'a> {
@@ -49,7 +51,13 @@ impl<'a> PanicInfo<'a> {
                                 location: Location<'a>)
                                 -> Self {
         struct NoPayload;
-        PanicInfo { payload: &NoPayload, location, message }
+        PanicInfo {
+            payload: &NoPayload,
+            payload_str: None,
+            message,
+            location,
+            thread_name: None,
+        }
     }
 
     #[doc(hidden)]
@@ -58,6 +66,20 @@ impl<'a> PanicInfo<'a> {
         self.payload = info;
     }
 
+    /// Records the textual form of a payload whose type libcore can't name,
+    /// such as `String`, for `payload_as_str`.
+    #[doc(hidden)]
+    #[inline]
+    pub fn set_payload_str(&mut self, s: &'a str) {
+        self.payload_str = Some(s);
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn set_thread_name(&mut self, name: &'a str) {
+        self.thread_name = Some(name);
+    }
+
     /// Returns the payload associated with the panic.
     ///
     /// This will commonly, but not always, be a `&'static str` or [`String`].
@@ -80,6 +102,50 @@ impl<'a> PanicInfo<'a> {
         self.payload
     }
 
+    /// Returns the payload associated with the panic, if it is a string.
+    ///
+    /// This returns the payload if it is of type `&'static str` or [`String`],
+    /// which covers the payloads of `panic!` and the formatted `panic!` of
+    /// `std`.
+    ///
+    /// [`String`]: ../../std/string/struct.String.html
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// #![feature(panic_info_payload_str)]
+    /// use std::panic;
+    ///
+    /// panic::set_hook(Box::new(|panic_info| {
+    ///     if let Some(s) = panic_info.payload_as_str() {
+    ///         println!("panic occurred: {:?}", s);
+    ///     } else {
+    ///         println!("panic occurred");
+    ///     }
+    /// }));
+    ///
+    /// panic!("Normal {}", "panic");
+    /// ```
+    #[unstable(feature = "panic_info_payload_str", issue = "0")]
+    pub fn payload_as_str(&self) -> Option<&str> {
+        if let Some(s) = self.payload_str {
+            Some(s)
+        } else if let Some(s) = self.payload.downcast_ref::<&'static str>() {
+            Some(s)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the name of the thread that panicked, if it has one.
+    ///
+    /// Unnamed threads, and panics outside of `std` which don't know about
+    /// threads, return `None`. The default hook prints these as `<unnamed>`.
+    #[unstable(feature = "panic_info_thread", issue = "0")]
+    pub fn thread_name(&self) -> Option<&str> {
+        self.thread_name
+    }
+
     /// If the `panic!` macro from the `core` crate (not from `std`)
     /// was used with a formatting string and some additional arguments,
     /// returns that message ready to be used for example with [`fmt::write`]
@@ -37,6 +37,102 @@ fn repeat_byte(b: u8) -> usize {
 
 /// Returns the first index matching the byte `x` in `text`.
 pub fn memchr(x: u8, text: &[u8]) -> Option<usize> {
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"),
+              target_feature = "sse2", not(miri)))]
+    {
+        if text.len() >= sse2::VECTOR_SIZE {
+            return unsafe { sse2::memchr(x, text) };
+        }
+    }
+    memchr_fallback(x, text)
+}
+
+/// Returns the last index matching the byte `x` in `text`.
+pub fn memrchr(x: u8, text: &[u8]) -> Option<usize> {
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"),
+              target_feature = "sse2", not(miri)))]
+    {
+        if text.len() >= sse2::VECTOR_SIZE {
+            return unsafe { sse2::memrchr(x, text) };
+        }
+    }
+    memrchr_fallback(x, text)
+}
+
+/// Searches 16 bytes at a time with SSE2, which every x86_64 CPU has.
+///
+/// Both functions need `text` to be at least `VECTOR_SIZE` bytes long. The
+/// last, partial vector is handled by loading the `VECTOR_SIZE` bytes at the
+/// end of `text`, which overlap bytes that were already found not to match,
+/// so any match in it is the one being looked for.
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"),
+          target_feature = "sse2", not(miri)))]
+mod sse2 {
+    #[cfg(target_arch = "x86")]
+    use arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use arch::x86_64::*;
+
+    pub const VECTOR_SIZE: usize = 16;
+
+    /// Returns a mask with bit `i` set if byte `i` of the vector at `ptr` is
+    /// equal to the bytes of `needle`.
+    #[inline(always)]
+    unsafe fn matches(ptr: *const u8, needle: __m128i) -> u32 {
+        let chunk = _mm_loadu_si128(ptr as *const __m128i);
+        _mm_movemask_epi8(_mm_cmpeq_epi8(chunk, needle)) as u32
+    }
+
+    pub unsafe fn memchr(x: u8, text: &[u8]) -> Option<usize> {
+        let len = text.len();
+        let ptr = text.as_ptr();
+        let needle = _mm_set1_epi8(x as i8);
+
+        let mut offset = 0;
+        while offset + VECTOR_SIZE <= len {
+            let mask = matches(ptr.add(offset), needle);
+            if mask != 0 {
+                return Some(offset + mask.trailing_zeros() as usize);
+            }
+            offset += VECTOR_SIZE;
+        }
+
+        if offset < len {
+            let start = len - VECTOR_SIZE;
+            let mask = matches(ptr.add(start), needle);
+            if mask != 0 {
+                return Some(start + mask.trailing_zeros() as usize);
+            }
+        }
+        None
+    }
+
+    pub unsafe fn memrchr(x: u8, text: &[u8]) -> Option<usize> {
+        let ptr = text.as_ptr();
+        let needle = _mm_set1_epi8(x as i8);
+
+        let mut end = text.len();
+        while end >= VECTOR_SIZE {
+            let start = end - VECTOR_SIZE;
+            let mask = matches(ptr.add(start), needle);
+            if mask != 0 {
+                return Some(start + 31 - mask.leading_zeros() as usize);
+            }
+            end = start;
+        }
+
+        if end > 0 {
+            let mask = matches(ptr, needle);
+            if mask != 0 {
+                return Some(31 - mask.leading_zeros() as usize);
+            }
+        }
+        None
+    }
+}
+
+/// Returns the first index matching the byte `x` in `text`, without SIMD.
+fn memchr_fallback(x: u8, text: &[u8]) -> Option<usize> {
     // Scan for a single byte value by reading two `usize` words at a time.
     //
     // Split `text` in three parts
@@ -80,8 +176,8 @@ pub fn memchr(x: u8, text: &[u8]) -> Option<usize> {
     text[offset..].iter().position(|elt| *elt == x).map(|i| offset + i)
 }
 
-/// Returns the last index matching the byte `x` in `text`.
-pub fn memrchr(x: u8, text: &[u8]) -> Option<usize> {
+/// Returns the last index matching the byte `x` in `text`, without SIMD.
+fn memrchr_fallback(x: u8, text: &[u8]) -> Option<usize> {
     // Scan for a single byte value by reading two `usize` words at a time.
     //
     // Split `text` in three parts:
@@ -1158,6 +1158,35 @@ pub mod memchr {
         assert_eq!(None, memrchr(b'a', b"xyz"));
     }
 
+    #[test]
+    fn each_position() {
+        let mut data = [1u8; 67];
+        for pos in 0..data.len() {
+            data[pos] = 2;
+            assert_eq!(Some(pos), memchr(2, &data));
+            assert_eq!(Some(pos), memrchr(2, &data));
+            for len in 0..pos {
+                assert_eq!(None, memchr(2, &data[..len]));
+                assert_eq!(None, memrchr(2, &data[..len]));
+            }
+            data[pos] = 1;
+        }
+    }
+
+    #[test]
+    fn first_and_last_of_many() {
+        let mut data = [0u8; 67];
+        for (pos, byte) in data.iter_mut().enumerate() {
+            *byte = (pos % 20) as u8;
+        }
+        for needle in 0..20 {
+            let first = needle as usize;
+            let last = 60 + first - if first >= 7 { 20 } else { 0 };
+            assert_eq!(Some(first), memchr(needle, &data));
+            assert_eq!(Some(last), memrchr(needle, &data));
+        }
+    }
+
     #[test]
     fn each_alignment_reversed() {
         let mut data = [1u8; 64];
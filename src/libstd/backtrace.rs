@@ -0,0 +1,636 @@
+//! Support for capturing a stack backtrace of an OS thread
+//!
+//! This module contains the support necessary to capture a stack backtrace of
+//! a running OS thread from the OS thread itself. The `Backtrace` type
+//! captures the frames of the stack when it's created and resolves their
+//! symbols only when they're first looked at, so capturing is relatively
+//! cheap while printing is not.
+//!
+//! # Accuracy
+//!
+//! Backtraces are attempted to be as accurate as possible, but no guarantees
+//! are provided about the exact accuracy of a backtrace. Instruction pointers,
+//! symbol names, filenames, line numbers, etc, may all be incorrect when
+//! reported. Accuracy is attempted on a best-effort basis, however, and bugs
+//! are always welcome to indicate areas of improvement!
+//!
+//! For most platforms a backtrace with a filename/line number requires that
+//! programs be compiled with debug information. Without debug information
+//! filenames/line numbers will not be reported.
+//!
+//! # Platform support
+//!
+//! Not all platforms that libstd compiles for support capturing backtraces.
+//! Some platforms simply do nothing when capturing a backtrace. To check
+//! whether the platform supports capturing backtraces you can consult the
+//! `BacktraceStatus` enum as a result of `Backtrace::status`.
+//!
+//! Like above with accuracy platform support is done on a best effort basis.
+//! Sometimes libraries may not be available at runtime or something may go
+//! wrong which would cause a backtrace to not be captured. Please feel free to
+//! report issues with platforms where a backtrace cannot be captured though!
+//!
+//! # Environment Variables
+//!
+//! The `Backtrace::capture` function may not actually capture a backtrace by
+//! default. Its behavior is governed by two environment variables:
+//!
+//! * `RUST_LIB_BACKTRACE` - if this is set to `0` then `Backtrace::capture`
+//!   will never capture a backtrace. Any other value this is set to will
+//!   enable `Backtrace::capture`.
+//!
+//! * `RUST_BACKTRACE` - if `RUST_LIB_BACKTRACE` is not set, then this variable
+//!   is consulted with the same rules of `RUST_LIB_BACKTRACE`.
+//!
+//! * If neither of the above env vars are set, then `Backtrace::capture` will
+//!   be disabled.
+//!
+//! Capturing a backtrace can be a quite expensive runtime operation, so the
+//! environment variables allow either forcibly disabling this runtime
+//! performance hit or allow selectively enabling it in some programs.
+//!
+//! Note that the `Backtrace::force_capture` function can be used to ignore
+//! these environment variables. Also note that the state of environment
+//! variables is cached once the first backtrace is created, so altering
+//! `RUST_LIB_BACKTRACE` or `RUST_BACKTRACE` at runtime may not actually change
+//! how backtraces are captured.
+
+#![unstable(feature = "backtrace", issue = "53487")]
+
+use cell::UnsafeCell;
+use env;
+use ffi::c_void;
+use fmt;
+use io;
+use path::{Path, PathBuf};
+use sync::Once;
+use sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "backtrace")]
+use rustc_demangle::demangle;
+#[cfg(feature = "backtrace")]
+use str;
+#[cfg(feature = "backtrace")]
+use sys_common::backtrace as sys_backtrace;
+
+/// A captured OS thread stack backtrace.
+///
+/// This type represents a stack backtrace for an OS thread captured at a
+/// previous point in time. In some instances the `Backtrace` type may
+/// internally be empty due to configuration. For more information see
+/// `Backtrace::capture`.
+pub struct Backtrace {
+    inner: Inner,
+}
+
+/// The current status of a backtrace, indicating whether it was captured or
+/// whether it is empty for some other reason.
+#[non_exhaustive]
+#[derive(Debug, PartialEq, Eq)]
+pub enum BacktraceStatus {
+    /// Capturing a backtrace is not supported, likely because it's not
+    /// implemented for the current platform.
+    Unsupported,
+    /// Capturing a backtrace has been disabled through either the
+    /// `RUST_LIB_BACKTRACE` or `RUST_BACKTRACE` environment variables.
+    Disabled,
+    /// A backtrace has been captured and the `Backtrace` should print
+    /// reasonable information when rendered.
+    Captured,
+}
+
+enum Inner {
+    Unsupported,
+    Disabled,
+    Captured(LazilyResolvedCapture),
+}
+
+struct Capture {
+    actual_start: usize,
+    resolved: bool,
+    frames: Vec<BacktraceFrame>,
+}
+
+/// A single frame of a captured backtrace.
+pub struct BacktraceFrame {
+    ip: usize,
+    #[allow(dead_code)]
+    symbol_addr: usize,
+    #[allow(dead_code)]
+    inline_context: u32,
+    symbols: Vec<BacktraceSymbol>,
+}
+
+/// A symbol resolved for a `BacktraceFrame`.
+///
+/// A frame may resolve to more than one symbol when functions have been
+/// inlined into it.
+pub struct BacktraceSymbol {
+    name: Option<String>,
+    filename: Option<PathBuf>,
+    lineno: Option<u32>,
+}
+
+struct LazilyResolvedCapture {
+    sync: Once,
+    capture: UnsafeCell<Capture>,
+}
+
+// The capture is only mutated inside `sync.call_once`, and only read once
+// that has completed.
+unsafe impl Sync for LazilyResolvedCapture {}
+
+impl BacktraceFrame {
+    /// Returns the instruction pointer of this frame.
+    pub fn ip(&self) -> *mut c_void {
+        self.ip as *mut c_void
+    }
+
+    /// Returns the symbols this frame resolved to, innermost inlined function
+    /// first. This is empty if no symbol information could be found.
+    pub fn symbols(&self) -> &[BacktraceSymbol] {
+        &self.symbols
+    }
+}
+
+impl BacktraceSymbol {
+    /// Returns the demangled name of this symbol, without the trailing hash,
+    /// if it's known.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_ref().map(|s| &s[..])
+    }
+
+    /// Returns the source file this symbol was defined in, if debug
+    /// information for it is available.
+    pub fn filename(&self) -> Option<&Path> {
+        self.filename.as_ref().map(|p| p.as_path())
+    }
+
+    /// Returns the line number in `filename` this symbol is at, if debug
+    /// information for it is available.
+    pub fn lineno(&self) -> Option<u32> {
+        self.lineno
+    }
+}
+
+impl fmt::Debug for Backtrace {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let capture = match &self.inner {
+            Inner::Unsupported => return fmt.write_str("<unsupported>"),
+            Inner::Disabled => return fmt.write_str("<disabled>"),
+            Inner::Captured(c) => c.force(),
+        };
+
+        let frames = &capture.frames[capture.actual_start..];
+        fmt.debug_list()
+            .entries(frames.iter().flat_map(|frame| frame.symbols.iter()))
+            .finish()
+    }
+}
+
+impl fmt::Debug for BacktraceFrame {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("BacktraceFrame")
+            .field("ip", &self.ip())
+            .field("symbols", &self.symbols)
+            .finish()
+    }
+}
+
+impl fmt::Debug for BacktraceSymbol {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{{ fn: ")?;
+        match &self.name {
+            Some(name) => write!(fmt, "{:?}", name)?,
+            None => write!(fmt, "<unknown>")?,
+        }
+        if let Some(filename) = &self.filename {
+            write!(fmt, ", file: {:?}", filename)?;
+        }
+        if let Some(lineno) = self.lineno {
+            write!(fmt, ", line: {}", lineno)?;
+        }
+        write!(fmt, " }}")
+    }
+}
+
+impl fmt::Display for Backtrace {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let capture = match &self.inner {
+            Inner::Unsupported => return fmt.write_str("unsupported backtrace"),
+            Inner::Disabled => return fmt.write_str("disabled backtrace"),
+            Inner::Captured(c) => c.force(),
+        };
+
+        writeln!(fmt, "stack backtrace:")?;
+        let frames = &capture.frames[capture.actual_start..];
+        for (idx, frame) in frames.iter().enumerate() {
+            if frame.symbols.is_empty() {
+                writeln!(fmt, "  {:2}: {:?} - <unknown>", idx, frame.ip())?;
+                continue;
+            }
+            for (i, symbol) in frame.symbols.iter().enumerate() {
+                if i == 0 {
+                    write!(fmt, "  {:2}: ", idx)?;
+                } else {
+                    write!(fmt, "      ")?;
+                }
+                match &symbol.name {
+                    Some(name) => writeln!(fmt, "{}", name)?,
+                    None => writeln!(fmt, "<unknown>")?,
+                }
+                if let (Some(filename), Some(lineno)) = (&symbol.filename, symbol.lineno) {
+                    writeln!(fmt, "             at {}:{}", filename.display(), lineno)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Backtrace {
+    /// Returns whether backtrace captures are enabled through environment
+    /// variables.
+    fn enabled() -> bool {
+        // Cache the result of reading the environment variables to make
+        // backtrace captures speedy, because otherwise reading environment
+        // variables every time can be somewhat slow.
+        static ENABLED: AtomicUsize = AtomicUsize::new(0);
+        match ENABLED.load(Ordering::SeqCst) {
+            0 => {}
+            1 => return false,
+            _ => return true,
+        }
+        let enabled = match env::var_os("RUST_LIB_BACKTRACE") {
+            Some(s) => s != "0",
+            None => match env::var_os("RUST_BACKTRACE") {
+                Some(s) => s != "0",
+                None => false,
+            },
+        };
+        ENABLED.store(enabled as usize + 1, Ordering::SeqCst);
+        enabled
+    }
+
+    /// Captures a stack backtrace of the current thread.
+    ///
+    /// This function will capture a stack backtrace of the current OS thread
+    /// of execution, returning a `Backtrace` type which can be later used to
+    /// print the entire stack trace or render it to a string.
+    ///
+    /// This function will be a noop if the `RUST_BACKTRACE` or
+    /// `RUST_LIB_BACKTRACE` backtrace variables are both not set. If either
+    /// environment variable is set and enabled then this function will
+    /// actually capture a backtrace. Capturing a backtrace can be both memory
+    /// intensive and slow, so these environment variables allow liberally
+    /// using `Backtrace::capture` and only incurring a slowdown when the
+    /// environment variables are set.
+    ///
+    /// To forcibly capture a backtrace regardless of environment variables,
+    /// use the `Backtrace::force_capture` function.
+    #[inline(never)] // want to make sure there's a frame here to remove
+    pub fn capture() -> Backtrace {
+        if !Backtrace::enabled() {
+            return Backtrace { inner: Inner::Disabled };
+        }
+        Backtrace::create(Backtrace::capture as usize)
+    }
+
+    /// Forcibly captures a full backtrace, regardless of environment variable
+    /// configuration.
+    ///
+    /// This function behaves the same as `capture` except that it ignores the
+    /// values of the `RUST_BACKTRACE` and `RUST_LIB_BACKTRACE` environment
+    /// variables, always capturing a backtrace.
+    #[inline(never)] // want to make sure there's a frame here to remove
+    pub fn force_capture() -> Backtrace {
+        Backtrace::create(Backtrace::force_capture as usize)
+    }
+
+    // Capture a backtrace which starts just before the function addressed by
+    // `ip`
+    #[cfg(feature = "backtrace")]
+    fn create(ip: usize) -> Backtrace {
+        // There are issues currently linking libbacktrace into tests, see
+        // `sys_common::backtrace::print`.
+        if cfg!(test) {
+            return Backtrace { inner: Inner::Unsupported };
+        }
+
+        let frames = match sys_backtrace::capture() {
+            Ok(frames) => frames,
+            Err(_) => return Backtrace { inner: Inner::Unsupported },
+        };
+        let frames = frames.iter().map(|frame| BacktraceFrame {
+            ip: frame.exact_position as usize,
+            symbol_addr: frame.symbol_addr as usize,
+            inline_context: frame.inline_context,
+            symbols: Vec::new(),
+        }).collect::<Vec<_>>();
+
+        // If no frames came out assume that this is an unsupported platform
+        // since the unwinder isn't able to produce anything.
+        if frames.is_empty() {
+            return Backtrace { inner: Inner::Unsupported };
+        }
+
+        // Skip everything up to and including the frame of `capture` or
+        // `force_capture` itself. Where the enclosing function isn't known
+        // nothing is skipped.
+        let actual_start = frames.iter()
+            .position(|frame| frame.symbol_addr == ip)
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        Backtrace {
+            inner: Inner::Captured(LazilyResolvedCapture::new(Capture {
+                actual_start,
+                resolved: false,
+                frames,
+            })),
+        }
+    }
+
+    #[cfg(not(feature = "backtrace"))]
+    fn create(_ip: usize) -> Backtrace {
+        Backtrace { inner: Inner::Unsupported }
+    }
+
+    /// Returns the status of this backtrace, indicating whether this backtrace
+    /// request was unsupported, disabled, or a stack trace was actually
+    /// captured.
+    pub fn status(&self) -> BacktraceStatus {
+        match self.inner {
+            Inner::Unsupported => BacktraceStatus::Unsupported,
+            Inner::Disabled => BacktraceStatus::Disabled,
+            Inner::Captured(_) => BacktraceStatus::Captured,
+        }
+    }
+
+    /// Returns the frames of this backtrace, resolving their symbols first if
+    /// that hasn't happened yet. This is empty unless the status is
+    /// `BacktraceStatus::Captured`.
+    pub fn frames(&self) -> &[BacktraceFrame] {
+        match &self.inner {
+            Inner::Captured(c) => {
+                let capture = c.force();
+                &capture.frames[capture.actual_start..]
+            }
+            _ => &[],
+        }
+    }
+}
+
+impl LazilyResolvedCapture {
+    fn new(capture: Capture) -> LazilyResolvedCapture {
+        LazilyResolvedCapture {
+            sync: Once::new(),
+            capture: UnsafeCell::new(capture),
+        }
+    }
+
+    fn force(&self) -> &Capture {
+        self.sync.call_once(|| {
+            // Safety: This exclusive reference can't overlap with any others
+            // `Once` guarantees callers will block until this closure returns
+            // `Once` also guarantees only a single caller will enter this
+            // closure
+            unsafe { &mut *self.capture.get() }.resolve();
+        });
+
+        // Safety: This shared reference can't overlap with the exclusive
+        // reference above
+        unsafe { &*self.capture.get() }
+    }
+}
+
+impl Capture {
+    #[cfg(feature = "backtrace")]
+    fn resolve(&mut self) {
+        // If we're already resolved, nothing to do!
+        if self.resolved {
+            return;
+        }
+        self.resolved = true;
+
+        let frames = &mut self.frames;
+        let _ = sys_backtrace::resolve(|context| {
+            for frame in frames.iter_mut() {
+                let raw = sys_backtrace::Frame {
+                    exact_position: frame.ip as *const u8,
+                    symbol_addr: frame.symbol_addr as *const u8,
+                    inline_context: frame.inline_context,
+                };
+                frame.symbols = resolve_frame(raw, context);
+            }
+        });
+    }
+
+    #[cfg(not(feature = "backtrace"))]
+    fn resolve(&mut self) {
+        self.resolved = true;
+    }
+}
+
+/// Looks up the symbols of one frame, innermost inlined function first.
+#[cfg(feature = "backtrace")]
+fn resolve_frame(frame: sys_backtrace::Frame,
+                 context: &sys_backtrace::BacktraceContext) -> Vec<BacktraceSymbol> {
+    let mut name = None;
+    let _ = sys_backtrace::resolve_symname(frame, |symname| {
+        name = symname.map(|s| format!("{:#}", demangle(s)));
+        Ok(())
+    }, context);
+
+    let mut symbols = Vec::new();
+    let _ = sys_backtrace::foreach_symbol_fileline(frame, |file, line| {
+        symbols.push(BacktraceSymbol {
+            name: name.take(),
+            filename: str::from_utf8(file).ok().map(PathBuf::from),
+            lineno: Some(line),
+        });
+        Ok(())
+    }, context);
+
+    if symbols.is_empty() && name.is_some() {
+        symbols.push(BacktraceSymbol {
+            name,
+            filename: None,
+            lineno: None,
+        });
+    }
+    symbols
+}
+
+/// Resolves an instruction pointer to the symbols it belongs to.
+///
+/// This uses the same machinery as resolving the frames of a `Backtrace`, so
+/// that profilers which record raw instruction pointers, for example from
+/// `BacktraceFrame::ip`, can symbolicate them later in the same process. The
+/// symbols of the innermost inlined function come first. The result is empty
+/// if nothing is known about `ip`, or if this platform can't resolve
+/// symbols.
+pub fn resolve(ip: *mut c_void) -> Vec<BacktraceSymbol> {
+    resolve_ip(ip as usize)
+}
+
+#[cfg(feature = "backtrace")]
+fn resolve_ip(ip: usize) -> Vec<BacktraceSymbol> {
+    // See `Backtrace::create`.
+    if cfg!(test) {
+        return Vec::new();
+    }
+
+    let frame = sys_backtrace::Frame {
+        exact_position: ip as *const u8,
+        symbol_addr: ip as *const u8,
+        inline_context: 0,
+    };
+    sys_backtrace::resolve(|context| resolve_frame(frame, context)).unwrap_or_default()
+}
+
+#[cfg(not(feature = "backtrace"))]
+fn resolve_ip(_ip: usize) -> Vec<BacktraceSymbol> {
+    Vec::new()
+}
+
+/// An executable or shared library loaded into the current process.
+///
+/// Instruction pointers recorded in this process can be symbolicated offline
+/// by finding the module whose range contains them and looking up their
+/// offset from the module's base address in the file at its path.
+#[derive(Debug, Clone)]
+pub struct Module {
+    path: PathBuf,
+    base: usize,
+    size: usize,
+}
+
+impl Module {
+    /// Returns the path the module was loaded from.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the lowest address the module is mapped at.
+    pub fn base_address(&self) -> *mut c_void {
+        self.base as *mut c_void
+    }
+
+    /// Returns the number of bytes from the base address to the end of the
+    /// module's last mapped segment.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+}
+
+/// Lists the executable and shared libraries currently loaded into the
+/// process.
+///
+/// # Errors
+///
+/// Returns an error if this platform can't list its modules.
+pub fn modules() -> io::Result<Vec<Module>> {
+    list_modules()
+}
+
+#[cfg(feature = "backtrace")]
+fn list_modules() -> io::Result<Vec<Module>> {
+    Ok(sys_backtrace::modules()?.into_iter().map(|module| Module {
+        path: module.path,
+        base: module.base,
+        size: module.size,
+    }).collect())
+}
+
+#[cfg(not(feature = "backtrace"))]
+fn list_modules() -> io::Result<Vec<Module>> {
+    Err(io::Error::new(io::ErrorKind::Other,
+                       "listing modules is not supported without backtrace support"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generated() -> Backtrace {
+        let frame = |ip, symbols| BacktraceFrame {
+            ip,
+            symbol_addr: ip,
+            inline_context: 0,
+            symbols,
+        };
+        let symbol = |name: &str, file: &str, line| BacktraceSymbol {
+            name: Some(name.to_string()),
+            filename: Some(PathBuf::from(file)),
+            lineno: Some(line),
+        };
+        Backtrace {
+            inner: Inner::Captured(LazilyResolvedCapture::new(Capture {
+                actual_start: 1,
+                resolved: true,
+                frames: vec![
+                    frame(0x1000, vec![symbol("std::backtrace::Backtrace::create",
+                                              "rust/backtrace.rs", 10)]),
+                    frame(0x2000, vec![
+                        symbol("std::inlined", "rust/inlined.rs", 20),
+                        symbol("std::outer", "rust/outer.rs", 30),
+                    ]),
+                    frame(0x3000, vec![]),
+                ],
+            })),
+        }
+    }
+
+    #[test]
+    fn test_status() {
+        assert_eq!(Backtrace { inner: Inner::Disabled }.status(),
+                   BacktraceStatus::Disabled);
+        assert_eq!(Backtrace { inner: Inner::Unsupported }.status(),
+                   BacktraceStatus::Unsupported);
+        assert_eq!(generated().status(), BacktraceStatus::Captured);
+        assert!(Backtrace { inner: Inner::Disabled }.frames().is_empty());
+    }
+
+    #[test]
+    fn test_frames() {
+        let bt = generated();
+        let frames = bt.frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].ip() as usize, 0x2000);
+        let symbols = frames[0].symbols();
+        assert_eq!(symbols.len(), 2);
+        assert_eq!(symbols[0].name(), Some("std::inlined"));
+        assert_eq!(symbols[1].filename(), Some(Path::new("rust/outer.rs")));
+        assert_eq!(symbols[1].lineno(), Some(30));
+        assert!(frames[1].symbols().is_empty());
+    }
+
+    #[test]
+    fn test_debug() {
+        let bt = generated();
+        let expected = "[\
+            { fn: \"std::inlined\", file: \"rust/inlined.rs\", line: 20 }, \
+            { fn: \"std::outer\", file: \"rust/outer.rs\", line: 30 }\
+        ]";
+        assert_eq!(format!("{:?}", bt), expected);
+        assert_eq!(format!("{:?}", Backtrace { inner: Inner::Disabled }), "<disabled>");
+    }
+
+    #[test]
+    fn test_display() {
+        let bt = generated();
+        let display = format!("{}", bt);
+        assert!(display.starts_with("stack backtrace:\n   0: std::inlined\n"));
+        assert!(display.contains("             at rust/outer.rs:30\n"));
+        assert!(!display.contains("Backtrace::create"));
+        assert_eq!(format!("{}", Backtrace { inner: Inner::Unsupported }),
+                   "unsupported backtrace");
+    }
+
+    #[test]
+    fn test_module_accessors() {
+        let module = Module { path: PathBuf::from("/lib/libfoo.so"), base: 0x1000, size: 0x20 };
+        assert_eq!(module.path(), Path::new("/lib/libfoo.so"));
+        assert_eq!(module.base_address() as usize, 0x1000);
+        assert_eq!(module.size(), 0x20);
+    }
+}
@@ -36,7 +36,9 @@ fn main() {
         // res_init and friends require -lresolv on macOS/iOS.
         // See #41582 and http://blog.achernya.com/2013/03/os-x-has-silly-libsystem.html
         println!("cargo:rustc-link-lib=resolv");
-    } else if target.contains("apple-ios") {
+    } else if target.contains("apple-ios") ||
+              target.contains("apple-tvos") ||
+              target.contains("apple-watchos") {
         println!("cargo:rustc-link-lib=System");
         println!("cargo:rustc-link-lib=objc");
         println!("cargo:rustc-link-lib=framework=Security");
@@ -46,6 +48,8 @@ fn main() {
         println!("cargo:rustc-link-lib=advapi32");
         println!("cargo:rustc-link-lib=ws2_32");
         println!("cargo:rustc-link-lib=userenv");
+    } else if target.contains("nto") {
+        println!("cargo:rustc-link-lib=socket");
     } else if target.contains("fuchsia") {
         println!("cargo:rustc-link-lib=zircon");
         println!("cargo:rustc-link-lib=fdio");
@@ -2,7 +2,7 @@ use self::Entry::*;
 use self::VacantEntryState::*;
 
 use intrinsics::unlikely;
-use collections::CollectionAllocErr;
+use collections::TryReserveError;
 use cell::Cell;
 use borrow::Borrow;
 use cmp::max;
@@ -36,7 +36,7 @@ impl DefaultResizePolicy {
     /// provide that capacity, accounting for maximum loading. The raw capacity
     /// is always zero or a power of two.
     #[inline]
-    fn try_raw_capacity(&self, len: usize) -> Result<usize, CollectionAllocErr> {
+    fn try_raw_capacity(&self, len: usize) -> Result<usize, TryReserveError> {
         if len == 0 {
             Ok(0)
         } else {
@@ -46,7 +46,7 @@ impl DefaultResizePolicy {
             let mut raw_cap = len.checked_mul(11)
                 .map(|l| l / 10)
                 .and_then(|l| l.checked_next_power_of_two())
-                .ok_or(CollectionAllocErr::CapacityOverflow)?;
+                .ok_or(TryReserveError::CapacityOverflow)?;
 
             raw_cap = max(MIN_NONZERO_RAW_CAPACITY, raw_cap);
             Ok(raw_cap)
@@ -847,8 +847,8 @@ impl<K, V, S> HashMap<K, V, S>
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn reserve(&mut self, additional: usize) {
         match self.reserve_internal(additional, Infallible) {
-            Err(CollectionAllocErr::CapacityOverflow) => panic!("capacity overflow"),
-            Err(CollectionAllocErr::AllocErr) => unreachable!(),
+            Err(TryReserveError::CapacityOverflow) => panic!("capacity overflow"),
+            Err(TryReserveError::AllocError { .. }) => unreachable!(),
             Ok(()) => { /* yay */ }
         }
     }
@@ -871,19 +871,19 @@ impl<K, V, S> HashMap<K, V, S>
     /// map.try_reserve(10).expect("why is the test harness OOMing on 10 bytes?");
     /// ```
     #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
-    pub fn try_reserve(&mut self, additional: usize) -> Result<(), CollectionAllocErr> {
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
         self.reserve_internal(additional, Fallible)
     }
 
     #[inline]
     fn reserve_internal(&mut self, additional: usize, fallibility: Fallibility)
-        -> Result<(), CollectionAllocErr> {
+        -> Result<(), TryReserveError> {
 
         let remaining = self.capacity() - self.len(); // this can't overflow
         if remaining < additional {
             let min_cap = self.len()
                 .checked_add(additional)
-                .ok_or(CollectionAllocErr::CapacityOverflow)?;
+                .ok_or(TryReserveError::CapacityOverflow)?;
             let raw_cap = self.resize_policy.try_raw_capacity(min_cap)?;
             self.try_resize(raw_cap, fallibility)?;
         } else if self.table.tag() && remaining <= self.len() {
@@ -906,7 +906,7 @@ impl<K, V, S> HashMap<K, V, S>
         &mut self,
         new_raw_cap: usize,
         fallibility: Fallibility,
-    ) -> Result<(), CollectionAllocErr> {
+    ) -> Result<(), TryReserveError> {
         assert!(self.table.size() <= new_raw_cap);
         assert!(new_raw_cap.is_power_of_two() || new_raw_cap == 0);
 
@@ -1530,6 +1530,69 @@ impl<K, V, S> HashMap<K, V, S>
             debug_assert!(elems_left == 0 || bucket.index() != start_index);
         }
     }
+
+    /// Creates an iterator which uses a closure to determine if a key-value
+    /// pair should be removed.
+    ///
+    /// If the closure returns `true`, the pair is removed from the map and
+    /// yielded. If it returns `false`, the pair stays in the map and is not
+    /// yielded. The closure gets a mutable reference to the value either way.
+    ///
+    /// The work is done lazily: if the returned iterator is dropped before it
+    /// is exhausted, the pairs it hasn't looked at yet are run through the
+    /// closure then, and removed or kept accordingly. Use [`retain`] with a
+    /// negated predicate if you don't need the removed pairs.
+    ///
+    /// If the closure panics, the pairs it hasn't returned `true` for are
+    /// kept and it is not called again. Leaking the iterator (with
+    /// [`mem::forget`], say) leaves the pairs it hasn't visited in the map.
+    ///
+    /// [`retain`]: #method.retain
+    /// [`mem::forget`]: ../../std/mem/fn.forget.html
+    ///
+    /// # Examples
+    ///
+    /// Splitting a map into even and odd keys, reusing the original map:
+    ///
+    /// ```
+    /// #![feature(extract_if)]
+    /// use std::collections::HashMap;
+    ///
+    /// let mut map: HashMap<i32, i32> = (0..8).map(|x| (x, x)).collect();
+    /// let extracted: HashMap<i32, i32> = map.extract_if(|k, _v| k % 2 == 0).collect();
+    ///
+    /// let mut evens = extracted.keys().cloned().collect::<Vec<_>>();
+    /// let mut odds = map.keys().cloned().collect::<Vec<_>>();
+    /// evens.sort();
+    /// odds.sort();
+    ///
+    /// assert_eq!(evens, vec![0, 2, 4, 6]);
+    /// assert_eq!(odds, vec![1, 3, 5, 7]);
+    /// ```
+    #[unstable(feature = "extract_if", reason = "recently added", issue = "43244")]
+    pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<K, V, F>
+        where F: FnMut(&K, &mut V) -> bool
+    {
+        ExtractIf {
+            inner: self.extract_if_inner(),
+            pred,
+        }
+    }
+
+    pub(super) fn extract_if_inner(&mut self) -> ExtractIfInner<K, V> {
+        let elems_left = self.table.size();
+        let bucket = if elems_left == 0 {
+            None
+        } else {
+            // Go backwards from just before a bucket which is either empty or
+            // holds an element in its ideal position, as `retain` does: every
+            // element shifted back by a removal then has been visited already.
+            let mut bucket = Bucket::head_bucket(&mut self.table);
+            bucket.prev();
+            Some(bucket)
+        };
+        ExtractIfInner { bucket, elems_left }
+    }
 }
 
 impl<K, V, S> HashMap<K, V, S>
@@ -1782,6 +1845,68 @@ pub struct Drain<'a, K: 'a, V: 'a> {
     pub(super) inner: table::Drain<'a, K, V>,
 }
 
+/// A draining, filtering iterator over the entries of a `HashMap`.
+///
+/// This `struct` is created by the [`extract_if`] method on [`HashMap`]. See its
+/// documentation for more.
+///
+/// [`extract_if`]: struct.HashMap.html#method.extract_if
+/// [`HashMap`]: struct.HashMap.html
+#[unstable(feature = "extract_if", reason = "recently added", issue = "43244")]
+pub struct ExtractIf<'a, K: 'a, V: 'a, F>
+    where F: FnMut(&K, &mut V) -> bool
+{
+    inner: ExtractIfInner<'a, K, V>,
+    pred: F,
+}
+
+/// The part of `ExtractIf` which doesn't depend on the predicate, shared with
+/// `HashSet`'s.
+pub(super) struct ExtractIfInner<'a, K: 'a, V: 'a> {
+    // `None` once every element has been visited, or if the predicate panicked.
+    bucket: Option<Bucket<K, V, &'a mut RawTable<K, V>>>,
+    elems_left: usize,
+}
+
+impl<'a, K, V> ExtractIfInner<'a, K, V> {
+    pub(super) fn next<F>(&mut self, pred: &mut F) -> Option<(K, V)>
+        where F: FnMut(&K, &mut V) -> bool
+    {
+        while self.elems_left != 0 {
+            // Taken out while `pred` runs, so that nothing is visited again
+            // if it panics.
+            let bucket = self.bucket.take()?;
+            let (mut bucket, item) = match bucket.peek() {
+                Full(mut full) => {
+                    self.elems_left -= 1;
+                    let should_remove = {
+                        let (k, v) = full.read_mut();
+                        pred(k, v)
+                    };
+                    if should_remove {
+                        let prev_raw = full.raw();
+                        let (k, v, t) = pop_internal(full);
+                        (Bucket::new_from(prev_raw, t), Some((k, v)))
+                    } else {
+                        (full.into_bucket(), None)
+                    }
+                }
+                Empty(b) => (b.into_bucket(), None),
+            };
+            bucket.prev();  // reverse iteration
+            self.bucket = Some(bucket);
+            if item.is_some() {
+                return item;
+            }
+        }
+        None
+    }
+
+    pub(super) fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.elems_left))
+    }
+}
+
 /// A mutable iterator over the values of a `HashMap`.
 ///
 /// This `struct` is created by the [`values_mut`] method on [`HashMap`]. See its
@@ -2238,6 +2363,24 @@ impl<'a, K, V, S> RawVacantEntryMut<'a, K, V, S> {
         };
         b.into_mut_refs()
     }
+
+    /// Sets the value of the entry with a custom hasher function, and returns
+    /// a mutable reference to it.
+    ///
+    /// This is for maps whose keys aren't hashed by the map's own
+    /// `BuildHasher` (because `S` can't hash `K` at all, say): `hash` must
+    /// be `hasher(&key)`, and `hasher` must agree with the hashes of every
+    /// other key already in the map. Hashes are stored in the table next to
+    /// their keys, so `hasher` is never called to rehash existing entries.
+    #[inline]
+    #[unstable(feature = "hash_raw_entry", issue = "56167")]
+    pub fn insert_with_hasher<H>(self, hash: u64, key: K, value: V, hasher: H)
+                                 -> (&'a mut K, &'a mut V)
+        where H: Fn(&K) -> u64,
+    {
+        debug_assert_eq!(hash, hasher(&key));
+        self.insert_hashed_nocheck(hash, key, value)
+    }
 }
 
 #[unstable(feature = "hash_raw_entry", issue = "56167")]
@@ -2641,6 +2784,40 @@ impl<'a, K, V> fmt::Debug for Drain<'a, K, V>
     }
 }
 
+#[unstable(feature = "extract_if", reason = "recently added", issue = "43244")]
+impl<'a, K, V, F> Iterator for ExtractIf<'a, K, V, F>
+    where F: FnMut(&K, &mut V) -> bool
+{
+    type Item = (K, V);
+
+    #[inline]
+    fn next(&mut self) -> Option<(K, V)> {
+        self.inner.next(&mut self.pred)
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[unstable(feature = "extract_if", reason = "recently added", issue = "43244")]
+impl<'a, K, V, F> Drop for ExtractIf<'a, K, V, F>
+    where F: FnMut(&K, &mut V) -> bool
+{
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
+#[unstable(feature = "extract_if", reason = "recently added", issue = "43244")]
+impl<'a, K, V, F> fmt::Debug for ExtractIf<'a, K, V, F>
+    where F: FnMut(&K, &mut V) -> bool
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad("ExtractIf { .. }")
+    }
+}
+
 impl<'a, K, V> Entry<'a, K, V> {
     #[stable(feature = "rust1", since = "1.0.0")]
     /// Ensures a value is in the entry by inserting the default if empty, and returns
@@ -3259,6 +3436,140 @@ impl fmt::Debug for RandomState {
     }
 }
 
+/// A [`HashMap`] using [`FastState`] instead of the default [`RandomState`].
+///
+/// See [`FastHasher`] for when this is appropriate, and when it is not.
+///
+/// [`HashMap`]: struct.HashMap.html
+/// [`FastState`]: struct.FastState.html
+/// [`RandomState`]: struct.RandomState.html
+/// [`FastHasher`]: struct.FastHasher.html
+///
+/// # Examples
+///
+/// ```
+/// #![feature(hash_map_fast_hasher)]
+/// use std::collections::hash_map::FastHashMap;
+///
+/// let mut map = FastHashMap::default();
+/// map.insert(1, "a");
+/// assert_eq!(map[&1], "a");
+/// ```
+#[unstable(feature = "hash_map_fast_hasher", issue = "0")]
+pub type FastHashMap<K, V> = HashMap<K, V, FastState>;
+
+/// A [`BuildHasher`] for [`FastHasher`]s.
+///
+/// Unlike [`RandomState`] this carries no keys: every `FastState` builds
+/// identical hashers, and maps using it iterate in the same order from one
+/// run of the program to the next.
+///
+/// [`BuildHasher`]: ../../hash/trait.BuildHasher.html
+/// [`FastHasher`]: struct.FastHasher.html
+/// [`RandomState`]: struct.RandomState.html
+#[derive(Clone, Copy, Debug, Default)]
+#[unstable(feature = "hash_map_fast_hasher", issue = "0")]
+pub struct FastState;
+
+#[unstable(feature = "hash_map_fast_hasher", issue = "0")]
+impl BuildHasher for FastState {
+    type Hasher = FastHasher;
+    #[inline]
+    fn build_hasher(&self) -> FastHasher {
+        FastHasher::default()
+    }
+}
+
+/// A fast, non-cryptographic [`Hasher`].
+///
+/// Each word of input is folded in with a rotate, an xor and a multiply,
+/// which is several times cheaper than SipHash for the integer and short
+/// string keys typical of compiler-style workloads. The high half of the
+/// result is then folded into the low half, which is what picks a bucket.
+///
+/// **This hasher is not resistant to HashDoS.** It is unkeyed, so anyone who
+/// can choose the keys of a map can cheaply choose ones which all collide
+/// and turn every operation into a linear scan. Only use it for keys which
+/// don't come from an untrusted source; [`RandomState`] remains the default
+/// for that reason.
+///
+/// The algorithm is not specified, and so it and its hashes should not be
+/// relied upon over releases.
+///
+/// [`Hasher`]: ../../hash/trait.Hasher.html
+/// [`RandomState`]: struct.RandomState.html
+#[derive(Clone, Debug, Default)]
+#[unstable(feature = "hash_map_fast_hasher", issue = "0")]
+pub struct FastHasher {
+    hash: u64,
+}
+
+// An odd constant with well spread bits, so the multiply mixes the low bits
+// of the input into the high bits of the hash.
+const FAST_SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+impl FastHasher {
+    #[inline]
+    fn add_to_hash(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(FAST_SEED);
+    }
+}
+
+#[unstable(feature = "hash_map_fast_hasher", issue = "0")]
+impl Hasher for FastHasher {
+    #[inline]
+    fn write(&mut self, mut bytes: &[u8]) {
+        while bytes.len() >= 8 {
+            let mut word = [0; 8];
+            word.copy_from_slice(&bytes[..8]);
+            self.add_to_hash(u64::from_ne_bytes(word));
+            bytes = &bytes[8..];
+        }
+        if bytes.len() >= 4 {
+            let mut word = [0; 4];
+            word.copy_from_slice(&bytes[..4]);
+            self.add_to_hash(u32::from_ne_bytes(word) as u64);
+            bytes = &bytes[4..];
+        }
+        for &byte in bytes {
+            self.add_to_hash(byte as u64);
+        }
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.add_to_hash(i as u64);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.add_to_hash(i as u64);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.add_to_hash(i as u64);
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.add_to_hash(i);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.add_to_hash(i as u64);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        // The multiply only carries upwards, so the low bits of `hash` depend
+        // only on the low bits of the input, and keys which differ only
+        // higher up, like aligned pointers, would share a few buckets.
+        self.hash ^ (self.hash >> 32)
+    }
+}
+
 impl<K, S, Q: ?Sized> super::Recover<Q> for HashMap<K, (), S>
     where K: Eq + Hash + Borrow<Q>,
           S: BuildHasher,
@@ -3337,10 +3648,52 @@ mod test_map {
     use super::RandomState;
     use cell::RefCell;
     use rand::{thread_rng, Rng};
-    use realstd::collections::CollectionAllocErr::*;
+    use realstd::collections::TryReserveError::*;
     use realstd::mem::size_of;
     use realstd::usize;
 
+    #[test]
+    fn test_fast_hasher() {
+        use super::{FastHashMap, FastState};
+        use hash::{BuildHasher, Hash, Hasher};
+
+        fn hash<T: Hash + ?Sized>(t: &T) -> u64 {
+            let mut hasher = FastState.build_hasher();
+            t.hash(&mut hasher);
+            hasher.finish()
+        }
+        // Unkeyed, so equal for every hasher...
+        assert_eq!(hash(&5u32), hash(&5u32));
+        assert_eq!(hash("hello world"), hash("hello world"));
+        // ...but still telling keys apart.
+        assert!(hash(&5u32) != hash(&6u32));
+        assert!(hash("abcdefghi") != hash("abcdefghj"));
+
+        let mut map = FastHashMap::default();
+        for i in 0..1000 {
+            assert!(map.insert(i, i * 2).is_none());
+        }
+        assert_eq!(map.len(), 1000);
+        for i in 0..1000 {
+            assert_eq!(map[&i], i * 2);
+        }
+    }
+
+    #[test]
+    fn test_fast_hasher_high_bit_keys() {
+        use super::FastHashMap;
+
+        // Keys differing only above their low bits still spread out over the
+        // table rather than piling up in long probe sequences.
+        let mut map = FastHashMap::default();
+        for i in 0..1000u64 {
+            map.insert(i << 10, i);
+        }
+        for i in 0..1000u64 {
+            assert!(map.search(&(i << 10)).unwrap().displacement() < 16);
+        }
+    }
+
     #[test]
     fn test_zero_capacities() {
         type HM = HashMap<i32, i32>;
@@ -4201,6 +4554,51 @@ mod test_map {
         assert_eq!(map[&6], 60);
     }
 
+    #[test]
+    fn test_extract_if() {
+        let mut map: HashMap<i32, i32> = (0..100).map(|x|(x, x*10)).collect();
+
+        let mut removed = map.extract_if(|&k, v| {
+            *v += 1;
+            k % 2 == 0
+        }).collect::<Vec<_>>();
+        removed.sort();
+        assert_eq!(removed, (0..50).map(|x| (x * 2, x * 20 + 1)).collect::<Vec<_>>());
+        assert_eq!(map.len(), 50);
+        assert_eq!(map[&1], 11);
+        assert_eq!(map[&99], 991);
+
+        // Dropping the iterator early finishes the job.
+        drop(map.extract_if(|&k, _| k > 50));
+        assert_eq!(map.len(), 25);
+        assert!(map.keys().all(|&k| k < 50));
+
+        let mut empty: HashMap<i32, i32> = HashMap::new();
+        assert_eq!(empty.extract_if(|_, _| true).count(), 0);
+    }
+
+    #[test]
+    fn test_extract_if_pred_panic() {
+        use panic::{catch_unwind, AssertUnwindSafe};
+
+        let mut map: HashMap<i32, i32> = (0..100).map(|x|(x, x)).collect();
+        let mut calls = 0;
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            for _ in map.extract_if(|_, _| {
+                calls += 1;
+                if calls == 10 { panic!() }
+                true
+            }) {}
+        }));
+        assert!(result.is_err());
+        // The predicate isn't called again once it panicked.
+        assert_eq!(calls, 10);
+        assert_eq!(map.len(), 91);
+        for (k, v) in &map {
+            assert_eq!(map[k], *v);
+        }
+    }
+
     #[test]
     fn test_adaptive() {
         const TEST_LEN: usize = 5000;
@@ -4244,7 +4642,7 @@ mod test_map {
             if let Err(CapacityOverflow) = empty_bytes.try_reserve(max_no_ovf) {
             } else { panic!("isize::MAX + 1 should trigger a CapacityOverflow!") }
         } else {
-            if let Err(AllocErr) = empty_bytes.try_reserve(max_no_ovf) {
+            if let Err(AllocError { .. }) = empty_bytes.try_reserve(max_no_ovf) {
             } else { panic!("isize::MAX + 1 should trigger an OOM!") }
         }
     }
@@ -4321,6 +4719,27 @@ mod test_map {
         assert_eq!(map.raw_entry().from_key(&10).unwrap(), (&10, &1000));
         assert_eq!(map.len(), 6);
 
+        // Nonexistent key (insert with hasher)
+        let hash11 = compute_hash(&map, 11);
+        let builder = map.hasher().clone();
+        let hasher = |k: &i32| {
+            use core::hash::{BuildHasher, Hash, Hasher};
+
+            let mut hasher = builder.build_hasher();
+            k.hash(&mut hasher);
+            hasher.finish()
+        };
+        match map.raw_entry_mut().from_hash(hash11, |k| *k == 11) {
+            Occupied(_) => unreachable!(),
+            Vacant(view) => {
+                assert_eq!(view.insert_with_hasher(hash11, 11, 1100, hasher),
+                           (&mut 11, &mut 1100));
+            }
+        }
+        assert_eq!(map.raw_entry().from_key(&11).unwrap(), (&11, &1100));
+        assert_eq!(map.remove(&11), Some(1100));
+        assert_eq!(map.len(), 6);
+
         // Ensure all lookup methods produce equivalent results.
         for k in 0..12 {
             let hash = compute_hash(&map, k);
@@ -771,6 +771,54 @@ impl<T, S> HashSet<T, S>
     {
         self.map.retain(|k, _| f(k));
     }
+
+    /// Creates an iterator which uses a closure to determine if a value
+    /// should be removed.
+    ///
+    /// If the closure returns `true`, the value is removed from the set and
+    /// yielded. If it returns `false`, the value stays in the set and is not
+    /// yielded.
+    ///
+    /// The work is done lazily: if the returned iterator is dropped before it
+    /// is exhausted, the values it hasn't looked at yet are run through the
+    /// closure then, and removed or kept accordingly. Use [`retain`] with a
+    /// negated predicate if you don't need the removed values.
+    ///
+    /// If the closure panics, the values it hasn't returned `true` for are
+    /// kept and it is not called again. Leaking the iterator (with
+    /// [`mem::forget`], say) leaves the values it hasn't visited in the set.
+    ///
+    /// [`retain`]: #method.retain
+    /// [`mem::forget`]: ../../std/mem/fn.forget.html
+    ///
+    /// # Examples
+    ///
+    /// Splitting a set into even and odd values, reusing the original set:
+    ///
+    /// ```
+    /// #![feature(extract_if)]
+    /// use std::collections::HashSet;
+    ///
+    /// let mut set: HashSet<i32> = (0..8).collect();
+    /// let extracted: HashSet<i32> = set.extract_if(|v| v % 2 == 0).collect();
+    ///
+    /// let mut evens = extracted.into_iter().collect::<Vec<_>>();
+    /// let mut odds = set.into_iter().collect::<Vec<_>>();
+    /// evens.sort();
+    /// odds.sort();
+    ///
+    /// assert_eq!(evens, vec![0, 2, 4, 6]);
+    /// assert_eq!(odds, vec![1, 3, 5, 7]);
+    /// ```
+    #[unstable(feature = "extract_if", reason = "recently added", issue = "43244")]
+    pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<T, F>
+        where F: FnMut(&T) -> bool
+    {
+        ExtractIf {
+            inner: self.map.extract_if_inner(),
+            pred,
+        }
+    }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -1011,6 +1059,21 @@ pub struct Drain<'a, K: 'a> {
     iter: map::Drain<'a, K, ()>,
 }
 
+/// A draining, filtering iterator over the items of a `HashSet`.
+///
+/// This `struct` is created by the [`extract_if`] method on [`HashSet`].
+/// See its documentation for more.
+///
+/// [`HashSet`]: struct.HashSet.html
+/// [`extract_if`]: struct.HashSet.html#method.extract_if
+#[unstable(feature = "extract_if", reason = "recently added", issue = "43244")]
+pub struct ExtractIf<'a, K: 'a, F>
+    where F: FnMut(&K) -> bool
+{
+    inner: map::ExtractIfInner<'a, K, ()>,
+    pred: F,
+}
+
 /// A lazy iterator producing elements in the intersection of `HashSet`s.
 ///
 /// This `struct` is created by the [`intersection`] method on [`HashSet`].
@@ -1206,6 +1269,39 @@ impl<'a, K: fmt::Debug> fmt::Debug for Drain<'a, K> {
     }
 }
 
+#[unstable(feature = "extract_if", reason = "recently added", issue = "43244")]
+impl<'a, K, F> Iterator for ExtractIf<'a, K, F>
+    where F: FnMut(&K) -> bool
+{
+    type Item = K;
+
+    fn next(&mut self) -> Option<K> {
+        let pred = &mut self.pred;
+        self.inner.next(&mut |k, _| pred(k)).map(|(k, _)| k)
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[unstable(feature = "extract_if", reason = "recently added", issue = "43244")]
+impl<'a, K, F> Drop for ExtractIf<'a, K, F>
+    where F: FnMut(&K) -> bool
+{
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
+#[unstable(feature = "extract_if", reason = "recently added", issue = "43244")]
+impl<'a, K, F> fmt::Debug for ExtractIf<'a, K, F>
+    where F: FnMut(&K) -> bool
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad("ExtractIf { .. }")
+    }
+}
+
 #[stable(feature = "rust1", since = "1.0.0")]
 impl<'a, T, S> Clone for Intersection<'a, T, S> {
     fn clone(&self) -> Intersection<'a, T, S> {
@@ -1823,4 +1919,19 @@ mod test_set {
         assert!(set.contains(&4));
         assert!(set.contains(&6));
     }
+
+    #[test]
+    fn test_extract_if() {
+        let mut set: HashSet<i32> = (0..8).collect();
+        let mut removed = set.extract_if(|&k| k % 2 == 0).collect::<Vec<_>>();
+        removed.sort();
+        assert_eq!(removed, [0, 2, 4, 6]);
+        assert_eq!(set.len(), 4);
+        assert!(set.iter().all(|&k| k % 2 == 1));
+
+        drop(set.extract_if(|&k| k > 4));
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&1));
+        assert!(set.contains(&3));
+    }
 }
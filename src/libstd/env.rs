@@ -13,13 +13,20 @@
 
 #![stable(feature = "env", since = "1.0.0")]
 
+use collections::btree_map::{self, BTreeMap};
 use error::Error;
 use ffi::{OsStr, OsString};
 use fmt;
 use io;
+use marker::PhantomData;
+use mem;
 use path::{Path, PathBuf};
+use slice;
+use sync::Once;
 use sys;
 use sys::os as os_imp;
+use sys_common::mutex::Mutex;
+use vec;
 
 /// Returns the current working directory as a [`PathBuf`].
 ///
@@ -175,6 +182,256 @@ impl fmt::Debug for VarsOs {
     }
 }
 
+/// A snapshot of the environment variables of this process, which can be
+/// iterated over without allocating for each variable.
+///
+/// This structure is created by the [`std::env::vars_os_ref`] function. See
+/// its documentation for more.
+///
+/// [`std::env::vars_os_ref`]: fn.vars_os_ref.html
+#[unstable(feature = "vars_os_ref", issue = "0")]
+pub struct VarsOsRef { inner: os_imp::EnvSnapshot }
+
+/// An iterator over the (variable, value) pairs of a [`VarsOsRef`].
+///
+/// [`VarsOsRef`]: struct.VarsOsRef.html
+#[unstable(feature = "vars_os_ref", issue = "0")]
+pub struct VarsOsRefIter<'a> { inner: os_imp::EnvSnapshotIter<'a> }
+
+/// Returns a snapshot of the environment variables of the current process,
+/// whose (variable, value) pairs can be borrowed as OS strings.
+///
+/// Like [`vars_os`], this takes a snapshot of the process's environment
+/// variables at the time of this invocation. Where [`vars_os`] allocates a
+/// pair of `OsString`s for every variable, on Unix this copies the whole
+/// environment into a single buffer while holding the environment lock,
+/// which the pairs then borrow from. Other platforms fall back to owned
+/// strings behind the same interface.
+///
+/// [`vars_os`]: fn.vars_os.html
+///
+/// # Examples
+///
+/// ```
+/// #![feature(vars_os_ref)]
+/// use std::env;
+///
+/// let vars = env::vars_os_ref();
+/// for (key, value) in &vars {
+///     println!("{:?}: {:?}", key, value);
+/// }
+/// ```
+#[unstable(feature = "vars_os_ref", issue = "0")]
+pub fn vars_os_ref() -> VarsOsRef {
+    VarsOsRef { inner: os_imp::env_snapshot() }
+}
+
+#[unstable(feature = "vars_os_ref", issue = "0")]
+impl VarsOsRef {
+    /// Returns an iterator over the (variable, value) pairs of the snapshot.
+    pub fn iter(&self) -> VarsOsRefIter {
+        VarsOsRefIter { inner: self.inner.iter() }
+    }
+}
+
+#[unstable(feature = "vars_os_ref", issue = "0")]
+impl<'a> IntoIterator for &'a VarsOsRef {
+    type Item = (&'a OsStr, &'a OsStr);
+    type IntoIter = VarsOsRefIter<'a>;
+    fn into_iter(self) -> VarsOsRefIter<'a> { self.iter() }
+}
+
+#[unstable(feature = "vars_os_ref", issue = "0")]
+impl<'a> Iterator for VarsOsRefIter<'a> {
+    type Item = (&'a OsStr, &'a OsStr);
+    fn next(&mut self) -> Option<(&'a OsStr, &'a OsStr)> { self.inner.next() }
+    fn size_hint(&self) -> (usize, Option<usize>) { self.inner.size_hint() }
+}
+
+#[unstable(feature = "vars_os_ref", issue = "0")]
+impl fmt::Debug for VarsOsRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+#[unstable(feature = "vars_os_ref", issue = "0")]
+impl<'a> fmt::Debug for VarsOsRefIter<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad("VarsOsRefIter { .. }")
+    }
+}
+
+/// An owned, immutable copy of the environment variables of this process,
+/// taken all at once.
+///
+/// Variables are looked up by name the way the platform does it, so on
+/// Windows, where the case of a variable's name doesn't matter, `PATH` finds
+/// a variable named `Path`.
+///
+/// This structure is created by the [`std::env::snapshot`] function. See its
+/// documentation for more.
+///
+/// [`std::env::snapshot`]: fn.snapshot.html
+#[unstable(feature = "env_snapshot", issue = "0")]
+#[derive(Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    // Each variable's own name is kept alongside its value, as the key may
+    // not preserve it.
+    vars: BTreeMap<sys::process::EnvKey, (OsString, OsString)>,
+}
+
+/// An iterator over the (variable, value) pairs of a [`Snapshot`], in order
+/// of the variables' names.
+///
+/// [`Snapshot`]: struct.Snapshot.html
+#[unstable(feature = "env_snapshot", issue = "0")]
+#[derive(Clone)]
+pub struct SnapshotIter<'a> {
+    inner: btree_map::Values<'a, sys::process::EnvKey, (OsString, OsString)>,
+}
+
+/// An owning iterator over the (variable, value) pairs of a [`Snapshot`], in
+/// order of the variables' names.
+///
+/// [`Snapshot`]: struct.Snapshot.html
+#[unstable(feature = "env_snapshot", issue = "0")]
+pub struct SnapshotIntoIter {
+    inner: btree_map::IntoIter<sys::process::EnvKey, (OsString, OsString)>,
+}
+
+/// Returns a copy of the environment variables of the current process, taken
+/// atomically.
+///
+/// [`var_os`] and [`vars_os`] each hold the environment lock only while they
+/// read, so a series of lookups can see the environment part way through
+/// changes made by other threads. The snapshot is copied out under the lock
+/// in one go, so its variables are all from the same moment, and later
+/// changes to the environment don't affect it.
+///
+/// The lock only covers changes made through this module. C code which calls
+/// `setenv` directly while another thread reads the environment is still
+/// racing with it.
+///
+/// [`var_os`]: fn.var_os.html
+/// [`vars_os`]: fn.vars_os.html
+///
+/// # Examples
+///
+/// ```
+/// #![feature(env_snapshot)]
+/// use std::env;
+///
+/// let env = env::snapshot();
+/// if let (Some(home), Some(user)) = (env.get("HOME"), env.get("USER")) {
+///     println!("{:?} lives in {:?}", user, home);
+/// }
+/// ```
+#[unstable(feature = "env_snapshot", issue = "0")]
+pub fn snapshot() -> Snapshot {
+    let vars = os_imp::env_snapshot().iter()
+        .map(|(key, value)| {
+            let key = key.to_os_string();
+            (sys::process::EnvKey::from(key.clone()), (key, value.to_os_string()))
+        })
+        .collect();
+    Snapshot { vars }
+}
+
+#[unstable(feature = "env_snapshot", issue = "0")]
+impl Snapshot {
+    /// Returns the value of the variable `key` in the snapshot, if it was set.
+    pub fn get<K: AsRef<OsStr>>(&self, key: K) -> Option<&OsStr> {
+        let key = sys::process::EnvKey::from(key.as_ref().to_os_string());
+        self.vars.get(&key).map(|&(_, ref value)| &value[..])
+    }
+
+    /// Returns whether the variable `key` was set in the snapshot.
+    pub fn contains_key<K: AsRef<OsStr>>(&self, key: K) -> bool {
+        self.vars.contains_key(&sys::process::EnvKey::from(key.as_ref().to_os_string()))
+    }
+
+    /// Returns the number of variables in the snapshot.
+    pub fn len(&self) -> usize {
+        self.vars.len()
+    }
+
+    /// Returns whether the snapshot has no variables.
+    pub fn is_empty(&self) -> bool {
+        self.vars.is_empty()
+    }
+
+    /// Returns an iterator over the (variable, value) pairs of the snapshot.
+    pub fn iter(&self) -> SnapshotIter {
+        SnapshotIter { inner: self.vars.values() }
+    }
+}
+
+#[unstable(feature = "env_snapshot", issue = "0")]
+impl<'a> IntoIterator for &'a Snapshot {
+    type Item = (&'a OsStr, &'a OsStr);
+    type IntoIter = SnapshotIter<'a>;
+    fn into_iter(self) -> SnapshotIter<'a> { self.iter() }
+}
+
+#[unstable(feature = "env_snapshot", issue = "0")]
+impl IntoIterator for Snapshot {
+    type Item = (OsString, OsString);
+    type IntoIter = SnapshotIntoIter;
+    fn into_iter(self) -> SnapshotIntoIter {
+        SnapshotIntoIter { inner: self.vars.into_iter() }
+    }
+}
+
+#[unstable(feature = "env_snapshot", issue = "0")]
+impl<'a> Iterator for SnapshotIter<'a> {
+    type Item = (&'a OsStr, &'a OsStr);
+    fn next(&mut self) -> Option<(&'a OsStr, &'a OsStr)> {
+        self.inner.next().map(|&(ref key, ref value)| (&key[..], &value[..]))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) { self.inner.size_hint() }
+}
+
+#[unstable(feature = "env_snapshot", issue = "0")]
+impl<'a> ExactSizeIterator for SnapshotIter<'a> {
+    fn len(&self) -> usize { self.inner.len() }
+}
+
+#[unstable(feature = "env_snapshot", issue = "0")]
+impl Iterator for SnapshotIntoIter {
+    type Item = (OsString, OsString);
+    fn next(&mut self) -> Option<(OsString, OsString)> {
+        self.inner.next().map(|(_, var)| var)
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) { self.inner.size_hint() }
+}
+
+#[unstable(feature = "env_snapshot", issue = "0")]
+impl ExactSizeIterator for SnapshotIntoIter {
+    fn len(&self) -> usize { self.inner.len() }
+}
+
+#[unstable(feature = "env_snapshot", issue = "0")]
+impl fmt::Debug for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+#[unstable(feature = "env_snapshot", issue = "0")]
+impl<'a> fmt::Debug for SnapshotIter<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad("SnapshotIter { .. }")
+    }
+}
+
+#[unstable(feature = "env_snapshot", issue = "0")]
+impl fmt::Debug for SnapshotIntoIter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad("SnapshotIntoIter { .. }")
+    }
+}
+
 /// Fetches the environment variable `key` from the current process.
 ///
 /// # Errors
@@ -544,7 +801,9 @@ pub fn home_dir() -> Option<PathBuf> {
 /// Returns the value of the `TMPDIR` environment variable if it is
 /// set, otherwise for non-Android it returns `/tmp`. If Android, since there
 /// is no global temporary folder (it is usually allocated per-app), it returns
-/// `/data/local/tmp`.
+/// `/data/local/tmp`. On macOS the fallback is the per-user temporary
+/// directory (`_CS_DARWIN_USER_TEMP_DIR`), which is also writable from within
+/// the app sandbox, and `/tmp` only if that can't be determined.
 ///
 /// # Windows
 ///
@@ -641,6 +900,61 @@ pub fn current_exe() -> io::Result<PathBuf> {
     os_imp::current_exe()
 }
 
+/// Returns the full filesystem path of the current running executable, or
+/// failing that, a guess from the first argument.
+///
+/// This is [`current_exe`] where the platform can answer. Where it can't,
+/// because there's no `/proc/self/exe` or equivalent, the first argument is
+/// taken to name the executable the way a shell would have run it: a path
+/// with more than one component is resolved against the current directory,
+/// and a bare name is looked up in the directories of `PATH`.
+///
+/// The guess is only as good as the first argument, which the parent process
+/// chose; it may name some other file, or none. The same security caveats as
+/// for [`current_exe`] apply, and more so.
+///
+/// [`current_exe`]: fn.current_exe.html
+///
+/// # Examples
+///
+/// ```
+/// #![feature(current_exe_lossy)]
+/// use std::env;
+///
+/// match env::current_exe_lossy() {
+///     Ok(exe_path) => println!("Path of this executable is probably: {}",
+///                              exe_path.display()),
+///     Err(e) => println!("failed to find the executable: {}", e),
+/// };
+/// ```
+#[unstable(feature = "current_exe_lossy", issue = "0")]
+pub fn current_exe_lossy() -> io::Result<PathBuf> {
+    let err = match current_exe() {
+        Ok(path) => return Ok(path),
+        Err(err) => err,
+    };
+    let arg0 = match arg0() {
+        Some(ref arg0) if !arg0.is_empty() => PathBuf::from(arg0),
+        _ => return Err(err),
+    };
+    if arg0.components().count() > 1 {
+        return Ok(current_dir()?.join(arg0));
+    }
+    if let Some(paths) = var_os("PATH") {
+        for dir in split_paths(&paths) {
+            let candidate = dir.join(&arg0);
+            if candidate.is_file() {
+                return if candidate.is_absolute() {
+                    Ok(candidate)
+                } else {
+                    Ok(current_dir()?.join(candidate))
+                };
+            }
+        }
+    }
+    Err(err)
+}
+
 /// An iterator over the arguments of a process, yielding a [`String`] value for
 /// each argument.
 ///
@@ -669,7 +983,19 @@ pub struct Args { inner: ArgsOs }
 /// [`OsString`]: ../ffi/struct.OsString.html
 /// [`std::env::args_os`]: ./fn.args_os.html
 #[stable(feature = "env", since = "1.0.0")]
-pub struct ArgsOs { inner: sys::args::Args }
+pub struct ArgsOs { inner: ArgsInner }
+
+enum ArgsInner {
+    Sys(sys::args::Args),
+    /// A copy of the arguments given to `set_args`.
+    Set(vec::IntoIter<OsString>),
+}
+
+// The arguments `set_args` put in place of the platform's, if any.
+// We never call `ARGS_LOCK.init()`, so it is UB to attempt to
+// acquire this mutex reentrantly!
+static ARGS_LOCK: Mutex = Mutex::new();
+static mut ARGS_SET: Option<Vec<OsString>> = None;
 
 /// Returns the arguments which this program was started with (normally passed
 /// via the command line).
@@ -724,7 +1050,99 @@ pub fn args() -> Args {
 /// ```
 #[stable(feature = "env", since = "1.0.0")]
 pub fn args_os() -> ArgsOs {
-    ArgsOs { inner: sys::args::args() }
+    let set = unsafe {
+        let _guard = ARGS_LOCK.lock();
+        ARGS_SET.clone()
+    };
+    let inner = match set {
+        Some(args) => ArgsInner::Set(args.into_iter()),
+        None => ArgsInner::Sys(sys::args::args()),
+    };
+    ArgsOs { inner }
+}
+
+impl ArgsInner {
+    fn get(&self, index: usize) -> Option<OsString> {
+        match *self {
+            ArgsInner::Sys(ref args) => args.get(index),
+            ArgsInner::Set(ref args) => args.as_slice().get(index).cloned(),
+        }
+    }
+}
+
+impl Iterator for ArgsInner {
+    type Item = OsString;
+    fn next(&mut self) -> Option<OsString> {
+        match *self {
+            ArgsInner::Sys(ref mut args) => args.next(),
+            ArgsInner::Set(ref mut args) => args.next(),
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+    fn nth(&mut self, n: usize) -> Option<OsString> {
+        match *self {
+            ArgsInner::Sys(ref mut args) => args.nth(n),
+            ArgsInner::Set(ref mut args) => args.nth(n),
+        }
+    }
+    fn last(mut self) -> Option<OsString> { self.next_back() }
+    fn count(self) -> usize { self.len() }
+}
+
+impl ExactSizeIterator for ArgsInner {
+    fn len(&self) -> usize {
+        match *self {
+            ArgsInner::Sys(ref args) => args.len(),
+            ArgsInner::Set(ref args) => args.len(),
+        }
+    }
+}
+
+impl DoubleEndedIterator for ArgsInner {
+    fn next_back(&mut self) -> Option<OsString> {
+        match *self {
+            ArgsInner::Sys(ref mut args) => args.next_back(),
+            ArgsInner::Set(ref mut args) => args.next_back(),
+        }
+    }
+}
+
+impl fmt::Debug for ArgsInner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ArgsInner::Sys(ref args) => fmt::Debug::fmt(&args.inner_debug(), f),
+            ArgsInner::Set(ref args) => fmt::Debug::fmt(args.as_slice(), f),
+        }
+    }
+}
+
+impl Args {
+    /// Returns the argument `index` places after the next one the iterator
+    /// would yield, without advancing it.
+    ///
+    /// On most Unix platforms this reads the argument straight out of `argv`,
+    /// so looking one up doesn't copy the ones before it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the argument is not valid unicode, like iterating does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(args_get)]
+    /// use std::env;
+    ///
+    /// let args = env::args();
+    /// assert_eq!(args.get(0), env::args().next());
+    /// ```
+    #[unstable(feature = "args_get", issue = "0")]
+    pub fn get(&self, index: usize) -> Option<String> {
+        self.inner.get(index).map(|s| s.into_string().unwrap())
+    }
 }
 
 #[stable(feature = "env_unimpl_send_sync", since = "1.26.0")]
@@ -740,6 +1158,13 @@ impl Iterator for Args {
         self.inner.next().map(|s| s.into_string().unwrap())
     }
     fn size_hint(&self) -> (usize, Option<usize>) { self.inner.size_hint() }
+    fn nth(&mut self, n: usize) -> Option<String> {
+        self.inner.nth(n).map(|s| s.into_string().unwrap())
+    }
+    fn last(self) -> Option<String> {
+        self.inner.last().map(|s| s.into_string().unwrap())
+    }
+    fn count(self) -> usize { self.inner.count() }
 }
 
 #[stable(feature = "env", since = "1.0.0")]
@@ -759,11 +1184,35 @@ impl DoubleEndedIterator for Args {
 impl fmt::Debug for Args {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("Args")
-            .field("inner", &self.inner.inner.inner_debug())
+            .field("inner", &self.inner.inner)
             .finish()
     }
 }
 
+impl ArgsOs {
+    /// Returns the argument `index` places after the next one the iterator
+    /// would yield, without advancing it.
+    ///
+    /// On most Unix platforms this reads the argument straight out of `argv`,
+    /// so looking one up doesn't copy the ones before it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(args_get)]
+    /// use std::env;
+    ///
+    /// let mut args = env::args_os();
+    /// let second = args.get(1);
+    /// args.next();
+    /// assert_eq!(args.get(0), second);
+    /// ```
+    #[unstable(feature = "args_get", issue = "0")]
+    pub fn get(&self, index: usize) -> Option<OsString> {
+        self.inner.get(index)
+    }
+}
+
 #[stable(feature = "env_unimpl_send_sync", since = "1.26.0")]
 impl !Send for ArgsOs {}
 
@@ -775,6 +1224,9 @@ impl Iterator for ArgsOs {
     type Item = OsString;
     fn next(&mut self) -> Option<OsString> { self.inner.next() }
     fn size_hint(&self) -> (usize, Option<usize>) { self.inner.size_hint() }
+    fn nth(&mut self, n: usize) -> Option<OsString> { self.inner.nth(n) }
+    fn last(self) -> Option<OsString> { self.inner.last() }
+    fn count(self) -> usize { self.inner.count() }
 }
 
 #[stable(feature = "env", since = "1.0.0")]
@@ -792,11 +1244,194 @@ impl DoubleEndedIterator for ArgsOs {
 impl fmt::Debug for ArgsOs {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("ArgsOs")
-            .field("inner", &self.inner.inner_debug())
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// An iterator over the arguments of a process, yielding a borrowed
+/// [`OsStr`] for each argument.
+///
+/// This struct is created by the [`std::env::args_ref`] function. See its
+/// documentation for more.
+///
+/// [`OsStr`]: ../ffi/struct.OsStr.html
+/// [`std::env::args_ref`]: ./fn.args_ref.html
+#[unstable(feature = "args_ref", issue = "0")]
+#[derive(Clone)]
+pub struct ArgsRef { inner: slice::Iter<'static, OsString> }
+
+/// Returns the arguments which this program was started with (normally passed
+/// via the command line), without copying them.
+///
+/// The arguments are read from the system once, on the first call, and kept
+/// for the rest of the process. This and every later call then only borrow
+/// them, where [`args_os`] allocates every argument anew on each call.
+///
+/// The first element is traditionally the path of the executable, but it can be
+/// set to arbitrary text, and it may not even exist, so this property should
+/// not be relied upon for security purposes.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(args_ref)]
+/// use std::env;
+///
+/// // Prints each argument on a separate line
+/// for argument in env::args_ref() {
+///     println!("{:?}", argument);
+/// }
+/// ```
+///
+/// [`args_os`]: ./fn.args_os.html
+#[unstable(feature = "args_ref", issue = "0")]
+pub fn args_ref() -> ArgsRef {
+    static INIT: Once = Once::new();
+    static mut ARGS: &'static [OsString] = &[];
+
+    unsafe {
+        INIT.call_once(|| {
+            let args: Vec<OsString> = sys::args::args().collect();
+            ARGS = Box::leak(args.into_boxed_slice());
+        });
+        ArgsRef { inner: ARGS.iter() }
+    }
+}
+
+/// Returns the first argument this program was started with, which is
+/// traditionally the name it was run as.
+///
+/// This reads only that argument, where [`args_os`] would make an iterator
+/// over all of them. As with [`args_os`], it can be set to arbitrary text, so
+/// it's fine for usage messages but shouldn't be relied upon for security
+/// purposes. Returns `None` if the program was started without arguments.
+///
+/// [`args_os`]: ./fn.args_os.html
+///
+/// # Examples
+///
+/// ```
+/// #![feature(arg0)]
+/// use std::env;
+///
+/// let name = env::arg0().unwrap_or_else(|| "prog".into());
+/// println!("usage: {} [OPTIONS]", name.to_string_lossy());
+/// ```
+#[unstable(feature = "arg0", issue = "0")]
+pub fn arg0() -> Option<OsString> {
+    args_os().get(0)
+}
+
+/// Returns the first argument this program was started with, borrowed from
+/// the copy of the arguments kept by [`args_ref`].
+///
+/// See [`arg0`] for more.
+///
+/// [`args_ref`]: ./fn.args_ref.html
+/// [`arg0`]: ./fn.arg0.html
+#[unstable(feature = "arg0", issue = "0")]
+pub fn arg0_ref() -> Option<&'static OsStr> {
+    args_ref().next()
+}
+
+#[unstable(feature = "args_ref", issue = "0")]
+impl Iterator for ArgsRef {
+    type Item = &'static OsStr;
+    fn next(&mut self) -> Option<&'static OsStr> { self.inner.next().map(|s| &s[..]) }
+    fn size_hint(&self) -> (usize, Option<usize>) { self.inner.size_hint() }
+}
+
+#[unstable(feature = "args_ref", issue = "0")]
+impl ExactSizeIterator for ArgsRef {
+    fn len(&self) -> usize { self.inner.len() }
+}
+
+#[unstable(feature = "args_ref", issue = "0")]
+impl DoubleEndedIterator for ArgsRef {
+    fn next_back(&mut self) -> Option<&'static OsStr> {
+        self.inner.next_back().map(|s| &s[..])
+    }
+}
+
+#[unstable(feature = "args_ref", issue = "0")]
+impl fmt::Debug for ArgsRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ArgsRef")
+            .field("inner", &self.inner.as_slice())
             .finish()
     }
 }
 
+/// Puts back the arguments replaced by [`set_args`] when dropped.
+///
+/// [`set_args`]: fn.set_args.html
+#[unstable(feature = "set_args", issue = "0")]
+#[must_use = "the arguments are restored as soon as the guard is dropped"]
+pub struct ArgsGuard {
+    old: Option<Vec<OsString>>,
+    _not_send_or_sync: PhantomData<*mut ()>,
+}
+
+/// Replaces the arguments returned by [`args`] and [`args_os`] until the
+/// returned guard is dropped.
+///
+/// This is meant for test harnesses and programs which re-run their own
+/// `main` in process. The arguments before the replacement are put back when
+/// the guard is dropped. An iterator made in the meantime keeps its own copy
+/// of the replacement.
+///
+/// Only the arguments these functions return are replaced. [`args_ref`]
+/// keeps the ones it saw first, and C code, or
+/// `std::os::unix::env::raw_args`, still sees the `argv` the process started
+/// with.
+///
+/// # Safety
+///
+/// Guards must be dropped in the reverse order of the calls which returned
+/// them, or the wrong arguments are put back.
+///
+/// [`args`]: fn.args.html
+/// [`args_os`]: fn.args_os.html
+/// [`args_ref`]: fn.args_ref.html
+///
+/// # Examples
+///
+/// ```
+/// #![feature(set_args)]
+/// use std::env;
+///
+/// let guard = unsafe { env::set_args(vec!["prog".into(), "--help".into()]) };
+/// assert_eq!(env::args().nth(1).unwrap(), "--help");
+/// drop(guard);
+/// ```
+#[unstable(feature = "set_args", issue = "0")]
+pub unsafe fn set_args(args: Vec<OsString>) -> ArgsGuard {
+    let _guard = ARGS_LOCK.lock();
+    ArgsGuard {
+        old: mem::replace(&mut ARGS_SET, Some(args)),
+        _not_send_or_sync: PhantomData,
+    }
+}
+
+#[unstable(feature = "set_args", issue = "0")]
+impl Drop for ArgsGuard {
+    fn drop(&mut self) {
+        // The replacement is freed once the lock is released.
+        let _set = unsafe {
+            let _guard = ARGS_LOCK.lock();
+            mem::replace(&mut ARGS_SET, self.old.take())
+        };
+    }
+}
+
+#[unstable(feature = "set_args", issue = "0")]
+impl fmt::Debug for ArgsGuard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad("ArgsGuard { .. }")
+    }
+}
+
 /// Constants associated with the current target
 #[stable(feature = "env", since = "1.0.0")]
 pub mod consts {
@@ -974,6 +1609,75 @@ mod tests {
 
     use path::Path;
 
+    #[test]
+    #[cfg_attr(target_os = "emscripten", ignore)]
+    fn vars_os_ref_matches_vars_os() {
+        set_var("TEST_VARS_OS_REF", "a=b");
+        let vars = vars_os_ref();
+        let borrowed: Vec<(OsString, OsString)> = vars.iter()
+            .map(|(key, value)| (key.to_os_string(), value.to_os_string()))
+            .collect();
+        assert!(borrowed.contains(&("TEST_VARS_OS_REF".into(), "a=b".into())));
+    }
+
+    #[test]
+    #[cfg_attr(target_os = "emscripten", ignore)]
+    fn snapshot_is_unaffected_by_later_changes() {
+        set_var("TEST_ENV_SNAPSHOT", "before");
+        let env = snapshot();
+        remove_var("TEST_ENV_SNAPSHOT");
+        assert_eq!(env.get("TEST_ENV_SNAPSHOT"), Some(OsStr::new("before")));
+        assert!(!snapshot().contains_key("TEST_ENV_SNAPSHOT"));
+        assert_eq!(env.iter().count(), env.len());
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn snapshot_ignores_case_on_windows() {
+        set_var("TEST_ENV_SNAPSHOT_CASE", "value");
+        let env = snapshot();
+        remove_var("TEST_ENV_SNAPSHOT_CASE");
+        assert_eq!(env.get("test_env_snapshot_case"), Some(OsStr::new("value")));
+        assert!(env.contains_key("Test_Env_Snapshot_Case"));
+        // The name itself is kept as it was set.
+        assert!(env.iter().any(|(key, _)| key == "TEST_ENV_SNAPSHOT_CASE"));
+    }
+
+    #[test]
+    fn args_ref_matches_args_os() {
+        let borrowed: Vec<OsString> = args_ref().map(|arg| arg.to_os_string()).collect();
+        assert_eq!(borrowed, args_os().collect::<Vec<_>>());
+        assert_eq!(args_ref().len(), borrowed.len());
+    }
+
+    #[test]
+    fn current_exe_lossy_prefers_current_exe() {
+        if let Ok(path) = current_exe() {
+            assert_eq!(current_exe_lossy().unwrap(), path);
+        }
+    }
+
+    #[test]
+    fn arg0_is_first_arg() {
+        assert_eq!(arg0(), args_os().next());
+        assert_eq!(arg0_ref(), args_ref().next());
+    }
+
+    #[test]
+    fn args_os_random_access() {
+        let all: Vec<OsString> = args_os().collect();
+        let mut args = args_os();
+        for (i, arg) in all.iter().enumerate() {
+            assert_eq!(args.get(i).as_ref(), Some(arg));
+        }
+        assert_eq!(args.get(all.len()), None);
+        assert_eq!(args_os().count(), all.len());
+        assert_eq!(args_os().last().as_ref(), all.last());
+        assert_eq!(args_os().nth(1).as_ref(), all.get(1));
+        assert_eq!(args.nth(all.len()), None);
+        assert_eq!(args.len(), 0);
+    }
+
     #[test]
     #[cfg_attr(target_os = "emscripten", ignore)]
     fn test_self_exe_path() {
@@ -14,6 +14,7 @@
 // reconsider what crate these items belong in.
 
 use alloc::{AllocErr, LayoutErr, CannotReallocInPlace};
+use collections::TryReserveError;
 use any::TypeId;
 use borrow::Cow;
 use cell;
@@ -429,6 +430,16 @@ impl Error for AllocErr {
     }
 }
 
+#[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+impl Error for TryReserveError {
+    fn description(&self) -> &str {
+        match *self {
+            TryReserveError::CapacityOverflow => "capacity overflow",
+            TryReserveError::AllocError { .. } => "memory allocation failed",
+        }
+    }
+}
+
 #[unstable(feature = "allocator_api",
            reason = "the precise API and guarantees it provides may be tweaked.",
            issue = "32838")]
@@ -267,6 +267,89 @@ impl FromBytesWithNulError {
     }
 }
 
+/// An error indicating that no nul byte was present.
+///
+/// A slice used to create a [`CStr`] with
+/// [`from_bytes_until_nul`][`CStr::from_bytes_until_nul`] must contain at
+/// least one nul byte.
+///
+/// [`CStr`]: struct.CStr.html
+/// [`CStr::from_bytes_until_nul`]: struct.CStr.html#method.from_bytes_until_nul
+///
+/// # Examples
+///
+/// ```
+/// #![feature(cstr_from_bytes_until_nul)]
+/// use std::ffi::{CStr, FromBytesUntilNulError};
+///
+/// let _: FromBytesUntilNulError = CStr::from_bytes_until_nul(b"foo").unwrap_err();
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[unstable(feature = "cstr_from_bytes_until_nul", issue = "0")]
+pub struct FromBytesUntilNulError(());
+
+/// An error indicating that a nul byte was not in the expected position.
+///
+/// The vector used to create a [`CString`] with
+/// [`from_vec_with_nul`][`CString::from_vec_with_nul`] must have one and
+/// only one nul byte, at its end. The error gives the vector back.
+///
+/// [`CString`]: struct.CString.html
+/// [`CString::from_vec_with_nul`]: struct.CString.html#method.from_vec_with_nul
+///
+/// # Examples
+///
+/// ```
+/// #![feature(cstring_from_vec_with_nul)]
+/// use std::ffi::{CString, FromVecWithNulError};
+///
+/// let _: FromVecWithNulError = CString::from_vec_with_nul(b"f\0oo".to_vec()).unwrap_err();
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[unstable(feature = "cstring_from_vec_with_nul", issue = "0")]
+pub struct FromVecWithNulError {
+    kind: FromBytesWithNulErrorKind,
+    bytes: Vec<u8>,
+}
+
+#[unstable(feature = "cstring_from_vec_with_nul", issue = "0")]
+impl FromVecWithNulError {
+    /// Returns the bytes that were attempted to convert to a [`CString`].
+    ///
+    /// [`CString`]: struct.CString.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(cstring_from_vec_with_nul)]
+    /// use std::ffi::CString;
+    ///
+    /// let err = CString::from_vec_with_nul(b"foo".to_vec()).unwrap_err();
+    /// assert_eq!(err.as_bytes(), b"foo");
+    /// ```
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Consumes this error, returning the bytes that were attempted to
+    /// convert to a [`CString`].
+    ///
+    /// [`CString`]: struct.CString.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(cstring_from_vec_with_nul)]
+    /// use std::ffi::CString;
+    ///
+    /// let err = CString::from_vec_with_nul(b"f\0oo".to_vec()).unwrap_err();
+    /// assert_eq!(err.into_bytes(), b"f\0oo");
+    /// ```
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
 /// An error indicating invalid UTF-8 when converting a [`CString`] into a [`String`].
 ///
 /// `CString` is just a wrapper over a buffer of bytes with a nul
@@ -355,6 +438,73 @@ impl CString {
         CString { inner: v.into_boxed_slice() }
     }
 
+    /// Creates a C-compatible string by consuming a byte vector which
+    /// already ends with its nul terminator, without checking for interior
+    /// 0 bytes or for the terminator.
+    ///
+    /// Unlike [`from_vec_unchecked`], this doesn't append a nul byte.
+    ///
+    /// [`from_vec_unchecked`]: #method.from_vec_unchecked
+    ///
+    /// # Safety
+    ///
+    /// The vector must end with a nul byte, and contain no other.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(cstring_from_vec_with_nul)]
+    /// use std::ffi::CString;
+    ///
+    /// let c_string = unsafe { CString::from_vec_with_nul_unchecked(b"foo\0".to_vec()) };
+    /// assert_eq!(c_string.as_bytes(), b"foo");
+    /// ```
+    #[unstable(feature = "cstring_from_vec_with_nul", issue = "0")]
+    pub unsafe fn from_vec_with_nul_unchecked(v: Vec<u8>) -> CString {
+        CString { inner: v.into_boxed_slice() }
+    }
+
+    /// Creates a C-compatible string by consuming a byte vector which
+    /// already ends with its nul terminator.
+    ///
+    /// This is [`CStr::from_bytes_with_nul`] for owned data: the vector is
+    /// checked to end with a nul byte and contain no other, and then used as
+    /// it is, so a buffer filled in by C code needn't be copied or have its
+    /// terminator removed and put back.
+    ///
+    /// [`CStr::from_bytes_with_nul`]: struct.CStr.html#method.from_bytes_with_nul
+    ///
+    /// # Errors
+    ///
+    /// If the vector has an interior nul byte, or doesn't end with one, a
+    /// [`FromVecWithNulError`] holding it is returned.
+    ///
+    /// [`FromVecWithNulError`]: struct.FromVecWithNulError.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(cstring_from_vec_with_nul)]
+    /// use std::ffi::CString;
+    ///
+    /// let c_string = CString::from_vec_with_nul(b"foo\0".to_vec()).unwrap();
+    /// assert_eq!(c_string.as_bytes(), b"foo");
+    ///
+    /// assert!(CString::from_vec_with_nul(b"foo".to_vec()).is_err());
+    /// assert!(CString::from_vec_with_nul(b"f\0oo\0".to_vec()).is_err());
+    /// ```
+    #[unstable(feature = "cstring_from_vec_with_nul", issue = "0")]
+    pub fn from_vec_with_nul(v: Vec<u8>) -> Result<CString, FromVecWithNulError> {
+        let kind = match memchr::memchr(0, &v) {
+            Some(nul_pos) if nul_pos + 1 == v.len() => {
+                return Ok(unsafe { CString::from_vec_with_nul_unchecked(v) });
+            }
+            Some(nul_pos) => FromBytesWithNulErrorKind::InteriorNul(nul_pos),
+            None => FromBytesWithNulErrorKind::NotNulTerminated,
+        };
+        Err(FromVecWithNulError { kind, bytes: v })
+    }
+
     /// Retakes ownership of a `CString` that was transferred to C via [`into_raw`].
     ///
     /// Additionally, the length of the string will be recalculated from the pointer.
@@ -887,6 +1037,43 @@ impl fmt::Display for FromBytesWithNulError {
     }
 }
 
+#[unstable(feature = "cstr_from_bytes_until_nul", issue = "0")]
+impl Error for FromBytesUntilNulError {
+    fn description(&self) -> &str {
+        "data provided does not contain a nul"
+    }
+}
+
+#[unstable(feature = "cstr_from_bytes_until_nul", issue = "0")]
+impl fmt::Display for FromBytesUntilNulError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.description().fmt(f)
+    }
+}
+
+#[unstable(feature = "cstring_from_vec_with_nul", issue = "0")]
+impl Error for FromVecWithNulError {
+    fn description(&self) -> &str {
+        match self.kind {
+            FromBytesWithNulErrorKind::InteriorNul(..) =>
+                "data provided contains an interior nul byte",
+            FromBytesWithNulErrorKind::NotNulTerminated =>
+                "data provided is not nul terminated",
+        }
+    }
+}
+
+#[unstable(feature = "cstring_from_vec_with_nul", issue = "0")]
+impl fmt::Display for FromVecWithNulError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.description())?;
+        if let FromBytesWithNulErrorKind::InteriorNul(pos) = self.kind {
+            write!(f, " at byte pos {}", pos)?;
+        }
+        Ok(())
+    }
+}
+
 impl IntoStringError {
     /// Consumes this error, returning original [`CString`] which generated the
     /// error.
@@ -1011,6 +1198,45 @@ impl CStr {
         }
     }
 
+    /// Creates a C string wrapper from a byte slice which contains a nul
+    /// byte somewhere.
+    ///
+    /// The C string ends at the first nul byte; anything after it is
+    /// ignored. This is how C code reads a string out of a fixed-size buffer,
+    /// and unlike [`from_bytes_with_nul`] it doesn't mind the slice being
+    /// longer than the string.
+    ///
+    /// [`from_bytes_with_nul`]: #method.from_bytes_with_nul
+    ///
+    /// # Errors
+    ///
+    /// If there is no nul byte in the slice, a [`FromBytesUntilNulError`] is
+    /// returned.
+    ///
+    /// [`FromBytesUntilNulError`]: struct.FromBytesUntilNulError.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(cstr_from_bytes_until_nul)]
+    /// use std::ffi::CStr;
+    ///
+    /// let mut buffer = [0u8; 16];
+    /// buffer[..5].copy_from_slice(b"hello");
+    ///
+    /// let c_str = CStr::from_bytes_until_nul(&buffer).unwrap();
+    /// assert_eq!(c_str.to_str(), Ok("hello"));
+    ///
+    /// assert!(CStr::from_bytes_until_nul(b"hello").is_err());
+    /// ```
+    #[unstable(feature = "cstr_from_bytes_until_nul", issue = "0")]
+    pub fn from_bytes_until_nul(bytes: &[u8]) -> Result<&CStr, FromBytesUntilNulError> {
+        match memchr::memchr(0, bytes) {
+            Some(nul_pos) => Ok(unsafe { CStr::from_bytes_with_nul_unchecked(&bytes[..=nul_pos]) }),
+            None => Err(FromBytesUntilNulError(())),
+        }
+    }
+
     /// Unsafely creates a C string wrapper from a byte slice.
     ///
     /// This function will cast the provided `bytes` to a `CStr` wrapper without
@@ -1429,6 +1655,30 @@ mod tests {
         assert!(cstr.is_err());
     }
 
+    #[test]
+    fn from_bytes_until_nul() {
+        let cstr = CStr::from_bytes_until_nul(b"123\0456\0");
+        assert_eq!(cstr.map(CStr::to_bytes_with_nul), Ok(&b"123\0"[..]));
+        let cstr = CStr::from_bytes_until_nul(b"\0");
+        assert_eq!(cstr.map(CStr::to_bytes), Ok(&b""[..]));
+        assert!(CStr::from_bytes_until_nul(b"123").is_err());
+        assert!(CStr::from_bytes_until_nul(b"").is_err());
+    }
+
+    #[test]
+    fn from_vec_with_nul() {
+        let cstring = CString::from_vec_with_nul(b"123\0".to_vec()).unwrap();
+        assert_eq!(cstring.as_bytes_with_nul(), b"123\0");
+
+        let err = CString::from_vec_with_nul(b"123".to_vec()).unwrap_err();
+        assert_eq!(err.to_string(), "data provided is not nul terminated");
+        assert_eq!(err.into_bytes(), b"123");
+
+        let err = CString::from_vec_with_nul(b"1\023\0".to_vec()).unwrap_err();
+        assert_eq!(err.to_string(), "data provided contains an interior nul byte at byte pos 1");
+        assert_eq!(err.as_bytes(), b"1\023\0");
+    }
+
     #[test]
     fn into_boxed() {
         let orig: &[u8] = b"Hello, world!\0";
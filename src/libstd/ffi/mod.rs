@@ -159,9 +159,15 @@
 pub use self::c_str::{CString, CStr, NulError, IntoStringError};
 #[stable(feature = "cstr_from_bytes", since = "1.10.0")]
 pub use self::c_str::{FromBytesWithNulError};
+#[unstable(feature = "cstr_from_bytes_until_nul", issue = "0")]
+pub use self::c_str::FromBytesUntilNulError;
+#[unstable(feature = "cstring_from_vec_with_nul", issue = "0")]
+pub use self::c_str::FromVecWithNulError;
 
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::os_str::{OsString, OsStr};
+#[unstable(feature = "os_str_string_ops", issue = "0")]
+pub use self::os_str::{OsStrPattern, OsStrSplit};
 
 #[stable(feature = "raw_os", since = "1.1.0")]
 pub use core::ffi::c_void;
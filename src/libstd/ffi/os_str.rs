@@ -1,9 +1,12 @@
 use borrow::{Borrow, Cow};
 use fmt;
-use ops;
+use ops::{self, Bound, RangeBounds};
 use cmp;
+use collections::TryReserveError;
 use hash::{Hash, Hasher};
+use iter::FusedIterator;
 use rc::Rc;
+use str;
 use sync::Arc;
 
 use sys::os_str::{Buf, Slice};
@@ -326,6 +329,113 @@ impl OsString {
         self.inner.shrink_to(min_capacity)
     }
 
+    /// Tries to reserve capacity for at least `additional` more length units
+    /// in the given `OsString`, returning an error instead of aborting if the
+    /// allocation fails.
+    ///
+    /// The string may reserve more space to avoid frequent reallocations.
+    /// After calling `try_reserve`, capacity will be greater than or equal to
+    /// `self.len() + additional`. Does nothing if capacity is already
+    /// sufficient.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    /// use std::ffi::OsString;
+    ///
+    /// let mut s = OsString::new();
+    /// s.try_reserve(10).expect("why is the test harness OOMing on 10 bytes?");
+    /// assert!(s.capacity() >= 10);
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.inner.try_reserve(additional)
+    }
+
+    /// Tries to reserve the minimum capacity for exactly `additional` more
+    /// length units in the given `OsString`, returning an error instead of
+    /// aborting if the allocation fails.
+    ///
+    /// Note that the allocator may give the string more space than it
+    /// requests. Therefore, capacity can not be relied upon to be precisely
+    /// minimal. Prefer `try_reserve` if future insertions are expected.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    /// use std::ffi::OsString;
+    ///
+    /// let mut s = OsString::new();
+    /// s.try_reserve_exact(10).expect("why is the test harness OOMing on 10 bytes?");
+    /// assert!(s.capacity() >= 10);
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.inner.try_reserve_exact(additional)
+    }
+
+    /// Converts bytes to an `OsString` without checking that they are valid
+    /// in the platform's [encoding][encoded].
+    ///
+    /// # Safety
+    ///
+    /// The same as for [`OsStr`'s version][from_bytes]: the bytes
+    /// must have come from [`OsStr::as_encoded_bytes`] or
+    /// [`into_encoded_bytes`] on the same platform, possibly split next to
+    /// valid UTF-8 text or concatenated.
+    ///
+    /// [encoded]: struct.OsStr.html#method.as_encoded_bytes
+    /// [from_bytes]: struct.OsStr.html#method.from_encoded_bytes_unchecked
+    /// [`OsStr::as_encoded_bytes`]: struct.OsStr.html#method.as_encoded_bytes
+    /// [`into_encoded_bytes`]: #method.into_encoded_bytes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(os_str_bytes)]
+    /// use std::ffi::OsString;
+    ///
+    /// let os_string = OsString::from("hello");
+    /// let bytes = os_string.into_encoded_bytes();
+    /// let os_string = unsafe { OsString::from_encoded_bytes_unchecked(bytes) };
+    /// assert_eq!(os_string, "hello");
+    /// ```
+    #[unstable(feature = "os_str_bytes", issue = "0")]
+    pub unsafe fn from_encoded_bytes_unchecked(bytes: Vec<u8>) -> OsString {
+        OsString { inner: Buf::from_encoded_bytes_unchecked(bytes) }
+    }
+
+    /// Converts the `OsString` into its [encoded bytes][encoded], without
+    /// copying.
+    ///
+    /// [encoded]: struct.OsStr.html#method.as_encoded_bytes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(os_str_bytes)]
+    /// use std::ffi::OsString;
+    ///
+    /// let os_string = OsString::from("hello");
+    /// assert_eq!(os_string.into_encoded_bytes(), b"hello");
+    /// ```
+    #[unstable(feature = "os_str_bytes", issue = "0")]
+    pub fn into_encoded_bytes(self) -> Vec<u8> {
+        self.inner.into_encoded_bytes()
+    }
+
     /// Converts this `OsString` into a boxed [`OsStr`].
     ///
     /// [`OsStr`]: struct.OsStr.html
@@ -636,6 +746,364 @@ impl OsStr {
         self.inner.inner.len()
     }
 
+    /// Returns the bytes this `OsStr` is stored as.
+    ///
+    /// The encoding is a platform-specific superset of UTF-8:
+    ///
+    /// * On Unix-like systems, and others whose strings are bytes, it is just
+    ///   the bytes the operating system uses.
+    /// * On Windows it is [WTF-8], which extends UTF-8 to cover the unpaired
+    ///   surrogates that can appear in UTF-16 strings.
+    ///
+    /// Either way, text which is valid UTF-8 is stored as itself, and the
+    /// encoding is self-synchronizing: searching the bytes for a valid UTF-8
+    /// string only finds whole matches. Beyond that, the bytes should be
+    /// treated as opaque. They are only guaranteed to be understood by
+    /// [`from_encoded_bytes_unchecked`] on the same platform and with the same
+    /// Rust version, so don't store them or send them to another machine;
+    /// use [`to_str`], or the platform's `OsStrExt`, for that.
+    ///
+    /// [WTF-8]: https://simonsapin.github.io/wtf-8/
+    /// [`from_encoded_bytes_unchecked`]: #method.from_encoded_bytes_unchecked
+    /// [`to_str`]: #method.to_str
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(os_str_bytes)]
+    /// use std::ffi::OsStr;
+    ///
+    /// let os_str = OsStr::new("--name=value");
+    /// let bytes = os_str.as_encoded_bytes();
+    /// let i = bytes.iter().position(|&b| b == b'=').unwrap();
+    /// // Splitting next to `=`, which is valid UTF-8, is allowed.
+    /// let (name, value) = unsafe {
+    ///     (OsStr::from_encoded_bytes_unchecked(&bytes[..i]),
+    ///      OsStr::from_encoded_bytes_unchecked(&bytes[i + 1..]))
+    /// };
+    /// assert_eq!(name, "--name");
+    /// assert_eq!(value, "value");
+    /// ```
+    #[unstable(feature = "os_str_bytes", issue = "0")]
+    #[inline]
+    pub fn as_encoded_bytes(&self) -> &[u8] {
+        self.bytes()
+    }
+
+    /// Converts bytes to an `OsStr` without checking that they are valid in
+    /// the platform's [encoding][encoded].
+    ///
+    /// # Safety
+    ///
+    /// The bytes must have been returned by [`as_encoded_bytes`] or
+    /// [`OsString::into_encoded_bytes`] on the same platform and with the
+    /// same Rust version. They may have been split into pieces immediately
+    /// before or after a valid non-empty UTF-8 substring, and pieces may have
+    /// been concatenated.
+    ///
+    /// [encoded]: #method.as_encoded_bytes
+    /// [`as_encoded_bytes`]: #method.as_encoded_bytes
+    /// [`OsString::into_encoded_bytes`]: struct.OsString.html#method.into_encoded_bytes
+    ///
+    /// # Examples
+    ///
+    /// See [`as_encoded_bytes`].
+    #[unstable(feature = "os_str_bytes", issue = "0")]
+    #[inline]
+    pub unsafe fn from_encoded_bytes_unchecked(bytes: &[u8]) -> &OsStr {
+        &*(bytes as *const [u8] as *const OsStr)
+    }
+
+    /// Returns `true` if the given string is a prefix of this `OsStr`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(os_str_string_ops)]
+    /// use std::ffi::OsStr;
+    ///
+    /// let arg = OsStr::new("--color=auto");
+    /// assert!(arg.starts_with("--"));
+    /// assert!(!arg.starts_with("-c"));
+    /// ```
+    #[unstable(feature = "os_str_string_ops", issue = "0")]
+    pub fn starts_with<P: OsStrPattern>(&self, prefix: P) -> bool {
+        prefix.__with_os_str(|p| p.map_or(false, |p| self.inner.starts_with(&p.inner)))
+    }
+
+    /// Returns `true` if the given string is a suffix of this `OsStr`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(os_str_string_ops)]
+    /// use std::ffi::OsStr;
+    ///
+    /// let name = OsStr::new("archive.tar.gz");
+    /// assert!(name.ends_with(".gz"));
+    /// assert!(!name.ends_with(".tar"));
+    /// ```
+    #[unstable(feature = "os_str_string_ops", issue = "0")]
+    pub fn ends_with<P: OsStrPattern>(&self, suffix: P) -> bool {
+        suffix.__with_os_str(|p| p.map_or(false, |p| self.inner.ends_with(&p.inner)))
+    }
+
+    /// Returns this `OsStr` with the given prefix removed, or `None` if it
+    /// doesn't start with it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(os_str_string_ops)]
+    /// use std::ffi::OsStr;
+    ///
+    /// let arg = OsStr::new("--verbose");
+    /// assert_eq!(arg.strip_prefix("--"), Some(OsStr::new("verbose")));
+    /// assert_eq!(arg.strip_prefix("-v"), None);
+    /// ```
+    #[unstable(feature = "os_str_string_ops", issue = "0")]
+    pub fn strip_prefix<P: OsStrPattern>(&self, prefix: P) -> Option<&OsStr> {
+        prefix.__with_os_str(|prefix| {
+            let prefix = prefix?;
+            if self.inner.starts_with(&prefix.inner) {
+                self.get(prefix.len()..)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Returns this `OsStr` with the given suffix removed, or `None` if it
+    /// doesn't end with it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(os_str_string_ops)]
+    /// use std::ffi::OsStr;
+    ///
+    /// let name = OsStr::new("archive.tar.gz");
+    /// assert_eq!(name.strip_suffix(".gz"), Some(OsStr::new("archive.tar")));
+    /// assert_eq!(name.strip_suffix(".zip"), None);
+    /// ```
+    #[unstable(feature = "os_str_string_ops", issue = "0")]
+    pub fn strip_suffix<P: OsStrPattern>(&self, suffix: P) -> Option<&OsStr> {
+        suffix.__with_os_str(|suffix| {
+            let suffix = suffix?;
+            if self.inner.ends_with(&suffix.inner) {
+                self.get(..self.len() - suffix.len())
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Returns this `OsStr` with every prefix matching the pattern removed,
+    /// repeatedly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(os_str_string_ops)]
+    /// use std::ffi::OsStr;
+    ///
+    /// assert_eq!(OsStr::new("--verbose").trim_start_matches('-'), "verbose");
+    /// assert_eq!(OsStr::new("xxyx").trim_start_matches("x"), "yx");
+    /// ```
+    #[unstable(feature = "os_str_string_ops", issue = "0")]
+    pub fn trim_start_matches<P: OsStrPattern>(&self, pat: P) -> &OsStr {
+        pat.__with_os_str(|pat| {
+            let mut rest = self;
+            if let Some(pat) = pat.filter(|pat| !pat.is_empty()) {
+                while rest.inner.starts_with(&pat.inner) {
+                    rest = rest.get(pat.len()..).unwrap();
+                }
+            }
+            rest
+        })
+    }
+
+    /// Returns this `OsStr` with every suffix matching the pattern removed,
+    /// repeatedly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(os_str_string_ops)]
+    /// use std::ffi::OsStr;
+    ///
+    /// assert_eq!(OsStr::new("dir///").trim_end_matches('/'), "dir");
+    /// ```
+    #[unstable(feature = "os_str_string_ops", issue = "0")]
+    pub fn trim_end_matches<P: OsStrPattern>(&self, pat: P) -> &OsStr {
+        pat.__with_os_str(|pat| {
+            let mut rest = self;
+            if let Some(pat) = pat.filter(|pat| !pat.is_empty()) {
+                while rest.inner.ends_with(&pat.inner) {
+                    rest = rest.get(..rest.len() - pat.len()).unwrap();
+                }
+            }
+            rest
+        })
+    }
+
+    /// Returns this `OsStr` with every prefix and suffix matching the
+    /// pattern removed, repeatedly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(os_str_string_ops)]
+    /// use std::ffi::OsStr;
+    ///
+    /// assert_eq!(OsStr::new("\"quoted\"").trim_matches(b'"'), "quoted");
+    /// ```
+    #[unstable(feature = "os_str_string_ops", issue = "0")]
+    pub fn trim_matches<P: OsStrPattern>(&self, pat: P) -> &OsStr {
+        self.trim_start_matches(pat).trim_end_matches(pat)
+    }
+
+    /// Returns the index of the first occurrence of the given string in this
+    /// `OsStr`, or `None` if there is none.
+    ///
+    /// Like the one returned by [`len`], the index is a position in the
+    /// underlying storage rather than a number of characters. It is meant to
+    /// be passed to [`get`].
+    ///
+    /// [`len`]: #method.len
+    /// [`get`]: #method.get
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(os_str_string_ops)]
+    /// use std::ffi::OsStr;
+    ///
+    /// let arg = OsStr::new("--color=auto");
+    /// let i = arg.find("=").unwrap();
+    /// assert_eq!(arg.get(..i), Some(OsStr::new("--color")));
+    /// assert_eq!(arg.find("never"), None);
+    /// ```
+    #[unstable(feature = "os_str_string_ops", issue = "0")]
+    pub fn find<P: OsStrPattern>(&self, needle: P) -> Option<usize> {
+        needle.__with_os_str(|needle| self.inner.find(&needle?.inner))
+    }
+
+    /// Splits this `OsStr` around the first occurrence of the given
+    /// delimiter, returning the parts before and after it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(os_str_string_ops)]
+    /// use std::ffi::OsStr;
+    ///
+    /// let arg = OsStr::new("--color=auto");
+    /// assert_eq!(arg.split_once("="), Some((OsStr::new("--color"), OsStr::new("auto"))));
+    /// assert_eq!(arg.split_once(":"), None);
+    /// ```
+    #[unstable(feature = "os_str_string_ops", issue = "0")]
+    pub fn split_once<P: OsStrPattern>(&self, delimiter: P) -> Option<(&OsStr, &OsStr)> {
+        delimiter.__with_os_str(|delimiter| self.split_once_os_str(delimiter?))
+    }
+
+    fn split_once_os_str(&self, delimiter: &OsStr) -> Option<(&OsStr, &OsStr)> {
+        let i = self.inner.find(&delimiter.inner)?;
+        Some((self.get(..i)?, self.get(i + delimiter.len()..)?))
+    }
+
+    /// Returns an iterator over the parts of this `OsStr` separated by
+    /// matches of the pattern.
+    ///
+    /// Like [`str::split`], separators next to each other or at either end
+    /// produce empty parts. An empty pattern doesn't split the string at
+    /// all.
+    ///
+    /// [`str::split`]: ../primitive.str.html#method.split
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(os_str_string_ops)]
+    /// use std::ffi::OsStr;
+    ///
+    /// let path = OsStr::new("/usr/bin::/bin");
+    /// let parts: Vec<_> = path.split(':').collect();
+    /// assert_eq!(parts, ["/usr/bin", "", "/bin"]);
+    /// ```
+    #[unstable(feature = "os_str_string_ops", issue = "0")]
+    pub fn split<P: OsStrPattern>(&self, pat: P) -> OsStrSplit<P> {
+        OsStrSplit { rest: Some(self), pat }
+    }
+
+    /// Returns a subslice of this `OsStr`, or `None` if the range is out of
+    /// bounds or doesn't fall on character boundaries.
+    ///
+    /// The indices are positions in the underlying storage, as returned by
+    /// [`len`] and [`find`]; what counts as a character boundary is
+    /// platform-specific. Slicing at the ends of a match, or of a prefix or
+    /// suffix, always works.
+    ///
+    /// [`len`]: #method.len
+    /// [`find`]: #method.find
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(os_str_string_ops)]
+    /// use std::ffi::OsStr;
+    ///
+    /// let os_str = OsStr::new("foo.txt");
+    /// assert_eq!(os_str.get(4..), Some(OsStr::new("txt")));
+    /// assert_eq!(os_str.get(..10), None);
+    /// ```
+    #[unstable(feature = "os_str_string_ops", issue = "0")]
+    pub fn get<R: RangeBounds<usize>>(&self, range: R) -> Option<&OsStr> {
+        let begin = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n.checked_add(1)?,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n.checked_add(1)?,
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => self.len(),
+        };
+        self.inner.get(begin, end).map(OsStr::from_inner)
+    }
+
+    /// Returns a copy of this `OsStr` where each ASCII character is mapped to
+    /// its lowercase equivalent. Other characters are unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(os_str_string_ops)]
+    /// use std::ffi::OsStr;
+    ///
+    /// assert_eq!(OsStr::new("README.Md").to_ascii_lowercase(), "readme.md");
+    /// ```
+    #[unstable(feature = "os_str_string_ops", issue = "0")]
+    pub fn to_ascii_lowercase(&self) -> OsString {
+        OsString { inner: self.inner.to_ascii_lowercase() }
+    }
+
+    /// Returns a copy of this `OsStr` where each ASCII character is mapped to
+    /// its uppercase equivalent. Other characters are unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(os_str_string_ops)]
+    /// use std::ffi::OsStr;
+    ///
+    /// assert_eq!(OsStr::new("Path").to_ascii_uppercase(), "PATH");
+    /// ```
+    #[unstable(feature = "os_str_string_ops", issue = "0")]
+    pub fn to_ascii_uppercase(&self) -> OsString {
+        OsString { inner: self.inner.to_ascii_uppercase() }
+    }
+
     /// Converts a [`Box`]`<OsStr>` into an [`OsString`] without copying or allocating.
     ///
     /// [`Box`]: ../boxed/struct.Box.html
@@ -648,13 +1116,125 @@ impl OsStr {
 
     /// Gets the underlying byte representation.
     ///
-    /// Note: it is *crucial* that this API is private, to avoid
-    /// revealing the internal, platform-specific encodings.
+    /// This is what `as_encoded_bytes` returns; what can be done with it is
+    /// limited by the contract described there.
     fn bytes(&self) -> &[u8] {
         unsafe { &*(&self.inner as *const _ as *const [u8]) }
     }
 }
 
+mod private {
+    pub trait Sealed {}
+}
+
+/// A pattern that the [`OsStr`] search methods, such as [`find`] and
+/// [`split`], accept.
+///
+/// It is implemented for `char`, `&str`, `&String`, `&OsStr` and
+/// `&OsString`, which match themselves, and for `u8`, which matches an ASCII
+/// byte. A byte outside the ASCII range never matches: in the encoding some
+/// platforms use for `OsStr`, it could match part of a character.
+///
+/// This trait is sealed, and can't be implemented outside the standard
+/// library.
+///
+/// [`OsStr`]: struct.OsStr.html
+/// [`find`]: struct.OsStr.html#method.find
+/// [`split`]: struct.OsStr.html#method.split
+#[unstable(feature = "os_str_string_ops", issue = "0")]
+pub trait OsStrPattern: Copy + private::Sealed {
+    /// Calls `f` with the pattern as an `OsStr`, or with `None` if nothing
+    /// can match it.
+    #[doc(hidden)]
+    fn __with_os_str<R, F: FnOnce(Option<&OsStr>) -> R>(self, f: F) -> R;
+}
+
+impl private::Sealed for char {}
+#[unstable(feature = "os_str_string_ops", issue = "0")]
+impl OsStrPattern for char {
+    fn __with_os_str<R, F: FnOnce(Option<&OsStr>) -> R>(self, f: F) -> R {
+        let mut buf = [0; 4];
+        f(Some(OsStr::new(&*self.encode_utf8(&mut buf))))
+    }
+}
+
+impl private::Sealed for u8 {}
+#[unstable(feature = "os_str_string_ops", issue = "0")]
+impl OsStrPattern for u8 {
+    fn __with_os_str<R, F: FnOnce(Option<&OsStr>) -> R>(self, f: F) -> R {
+        // Only ASCII bytes make a valid single-byte `str`.
+        f(str::from_utf8(&[self]).ok().map(OsStr::new))
+    }
+}
+
+impl<'a> private::Sealed for &'a str {}
+#[unstable(feature = "os_str_string_ops", issue = "0")]
+impl<'a> OsStrPattern for &'a str {
+    fn __with_os_str<R, F: FnOnce(Option<&OsStr>) -> R>(self, f: F) -> R {
+        f(Some(OsStr::new(self)))
+    }
+}
+
+impl<'a> private::Sealed for &'a String {}
+#[unstable(feature = "os_str_string_ops", issue = "0")]
+impl<'a> OsStrPattern for &'a String {
+    fn __with_os_str<R, F: FnOnce(Option<&OsStr>) -> R>(self, f: F) -> R {
+        f(Some(OsStr::new(self)))
+    }
+}
+
+impl<'a> private::Sealed for &'a OsStr {}
+#[unstable(feature = "os_str_string_ops", issue = "0")]
+impl<'a> OsStrPattern for &'a OsStr {
+    fn __with_os_str<R, F: FnOnce(Option<&OsStr>) -> R>(self, f: F) -> R {
+        f(Some(self))
+    }
+}
+
+impl<'a> private::Sealed for &'a OsString {}
+#[unstable(feature = "os_str_string_ops", issue = "0")]
+impl<'a> OsStrPattern for &'a OsString {
+    fn __with_os_str<R, F: FnOnce(Option<&OsStr>) -> R>(self, f: F) -> R {
+        f(Some(self))
+    }
+}
+
+/// An iterator over the parts of an [`OsStr`] separated by a pattern.
+///
+/// This struct is created by the [`split`] method on [`OsStr`]. See its
+/// documentation for more.
+///
+/// [`OsStr`]: struct.OsStr.html
+/// [`split`]: struct.OsStr.html#method.split
+#[derive(Clone, Debug)]
+#[unstable(feature = "os_str_string_ops", issue = "0")]
+pub struct OsStrSplit<'a, P> {
+    rest: Option<&'a OsStr>,
+    pat: P,
+}
+
+#[unstable(feature = "os_str_string_ops", issue = "0")]
+impl<'a, P: OsStrPattern> Iterator for OsStrSplit<'a, P> {
+    type Item = &'a OsStr;
+
+    fn next(&mut self) -> Option<&'a OsStr> {
+        let rest = self.rest?;
+        let split = self.pat.__with_os_str(|pat| {
+            rest.split_once_os_str(pat.filter(|pat| !pat.is_empty())?)
+        });
+        match split {
+            Some((part, rest)) => {
+                self.rest = Some(rest);
+                Some(part)
+            }
+            None => self.rest.take(),
+        }
+    }
+}
+
+#[unstable(feature = "os_str_string_ops", issue = "0")]
+impl<'a, P: OsStrPattern> FusedIterator for OsStrSplit<'a, P> {}
+
 #[stable(feature = "box_from_os_str", since = "1.17.0")]
 impl<'a> From<&'a OsStr> for Box<OsStr> {
     fn from(s: &'a OsStr) -> Box<OsStr> {
@@ -1132,4 +1712,126 @@ mod tests {
         assert_eq!(&*rc2, os_str);
         assert_eq!(&*arc2, os_str);
     }
+
+    #[test]
+    fn test_os_str_encoded_bytes() {
+        let os_str = OsStr::new("héllo=wörld");
+        let bytes = os_str.as_encoded_bytes();
+        assert_eq!(bytes, "héllo=wörld".as_bytes());
+
+        let i = bytes.iter().position(|&b| b == b'=').unwrap();
+        let (left, right) = unsafe {
+            (OsStr::from_encoded_bytes_unchecked(&bytes[..i]),
+             OsStr::from_encoded_bytes_unchecked(&bytes[i + 1..]))
+        };
+        assert_eq!(left, "héllo");
+        assert_eq!(right, "wörld");
+
+        let os_string = os_str.to_os_string();
+        let vec = os_string.into_encoded_bytes();
+        assert_eq!(vec, bytes);
+        let os_string = unsafe { OsString::from_encoded_bytes_unchecked(vec) };
+        assert_eq!(os_string, os_str);
+    }
+
+    #[test]
+    fn test_os_string_try_reserve() {
+        let mut os_string = OsString::from("abc");
+        os_string.try_reserve(10).unwrap();
+        assert!(os_string.capacity() >= 13);
+        os_string.try_reserve_exact(20).unwrap();
+        assert!(os_string.capacity() >= 23);
+        assert!(os_string.try_reserve(usize::max_value()).is_err());
+        assert_eq!(os_string, "abc");
+    }
+
+    #[test]
+    fn test_os_str_affixes() {
+        let os_str = OsStr::new("--name=value");
+        assert!(os_str.starts_with(""));
+        assert!(os_str.starts_with("--"));
+        assert!(os_str.starts_with(os_str));
+        assert!(!os_str.starts_with("--name=value="));
+        assert!(os_str.ends_with("value"));
+        assert!(!os_str.ends_with("name"));
+
+        assert_eq!(os_str.strip_prefix("--"), Some(OsStr::new("name=value")));
+        assert_eq!(os_str.strip_prefix(os_str), Some(OsStr::new("")));
+        assert_eq!(os_str.strip_prefix("-n"), None);
+        assert_eq!(os_str.strip_suffix("=value"), Some(OsStr::new("--name")));
+        assert_eq!(os_str.strip_suffix("="), None);
+    }
+
+    #[test]
+    fn test_os_str_find() {
+        let os_str = OsStr::new("a=b=c");
+        assert_eq!(os_str.find("="), Some(1));
+        assert_eq!(os_str.find("b=c"), Some(2));
+        assert_eq!(os_str.find(""), Some(0));
+        assert_eq!(os_str.find("c="), None);
+        assert_eq!(OsStr::new("").find("a"), None);
+
+        assert_eq!(os_str.split_once("="), Some((OsStr::new("a"), OsStr::new("b=c"))));
+        assert_eq!(os_str.split_once("a=b=c"), Some((OsStr::new(""), OsStr::new(""))));
+        assert_eq!(os_str.split_once(":"), None);
+    }
+
+    #[test]
+    fn test_os_str_patterns() {
+        let os_str = OsStr::new("--näme=välue");
+        assert!(os_str.starts_with('-'));
+        assert!(os_str.starts_with(b'-'));
+        assert!(os_str.ends_with(&String::from("lue")));
+        assert!(os_str.ends_with(&OsString::from("välue")));
+        assert_eq!(os_str.find('ä'), Some(3));
+        assert_eq!(os_str.find(b'='), Some(7));
+        // A non-ASCII byte could only match part of a character.
+        assert_eq!(os_str.find(0xa4u8), None);
+        assert!(!os_str.starts_with(0xffu8));
+        assert_eq!(os_str.split_once('='), Some((OsStr::new("--näme"), OsStr::new("välue"))));
+        assert_eq!(os_str.strip_prefix(b'-'), Some(OsStr::new("-näme=välue")));
+    }
+
+    #[test]
+    fn test_os_str_trim_matches() {
+        let os_str = OsStr::new("--x--");
+        assert_eq!(os_str.trim_start_matches('-'), "x--");
+        assert_eq!(os_str.trim_end_matches("-"), "--x");
+        assert_eq!(os_str.trim_matches(b'-'), "x");
+        assert_eq!(os_str.trim_matches("--"), "x");
+        assert_eq!(os_str.trim_matches("---"), "--x--");
+        assert_eq!(os_str.trim_matches(""), "--x--");
+        assert_eq!(OsStr::new("---").trim_matches('-'), "");
+    }
+
+    #[test]
+    fn test_os_str_split() {
+        let parts: Vec<_> = OsStr::new("a,b,,c,").split(',').collect();
+        assert_eq!(parts, ["a", "b", "", "c", ""]);
+        let parts: Vec<_> = OsStr::new("").split(',').collect();
+        assert_eq!(parts, [""]);
+        let parts: Vec<_> = OsStr::new("a->b").split("->").collect();
+        assert_eq!(parts, ["a", "b"]);
+        let parts: Vec<_> = OsStr::new("ab").split("").collect();
+        assert_eq!(parts, ["ab"]);
+    }
+
+    #[test]
+    fn test_os_str_get() {
+        let os_str = OsStr::new("héllo");
+        assert_eq!(os_str.get(..), Some(os_str));
+        assert_eq!(os_str.get(0..1), Some(OsStr::new("h")));
+        assert_eq!(os_str.get(1..=2), Some(OsStr::new("é")));
+        assert_eq!(os_str.get(3..), Some(OsStr::new("llo")));
+        assert_eq!(os_str.get(3..1), None);
+        assert_eq!(os_str.get(..7), None);
+        assert_eq!(os_str.get(..=usize::max_value()), None);
+    }
+
+    #[test]
+    fn test_os_str_ascii_case() {
+        let os_str = OsStr::new("MiXeD CaSe é");
+        assert_eq!(os_str.to_ascii_lowercase(), "mixed case é");
+        assert_eq!(os_str.to_ascii_uppercase(), "MIXED CASE é");
+    }
 }
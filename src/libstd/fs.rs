@@ -8,11 +8,14 @@
 #![stable(feature = "rust1", since = "1.0.0")]
 
 use fmt;
-use ffi::OsString;
-use io::{self, SeekFrom, Seek, Read, Initializer, Write};
+use ffi::{OsStr, OsString};
+use io::{self, SeekFrom, Seek, Read, ReadAt, Initializer, Write, WriteAt};
 use path::{Path, PathBuf};
+use process;
+use sync::atomic::{AtomicUsize, Ordering};
 use sys::fs as fs_imp;
 use sys_common::{AsInnerMut, FromInner, AsInner, IntoInner};
+use sys_common::fs::Progress;
 use time::SystemTime;
 
 /// A reference to an open file on the filesystem.
@@ -211,6 +214,114 @@ pub struct DirBuilder {
     recursive: bool,
 }
 
+/// An open directory, which other files can be opened and managed relative
+/// to.
+///
+/// Paths given to the methods of `Dir` are looked up starting from the
+/// directory itself, rather than from the current directory, so they keep
+/// referring to the same place however the directory is renamed or moved,
+/// and nothing can swap one of its parent directories for a symbolic link
+/// in between two operations. This makes it possible to work in a
+/// directory which other users can write to without racing them.
+///
+/// It isn't a sandbox, though: absolute paths, `..` and symbolic links in
+/// the paths given still lead outside of the directory.
+///
+/// # Platform-specific behavior
+///
+/// This uses `openat` and the related functions on Unix, and paths relative
+/// to a directory descriptor on WASI. It isn't supported on Windows yet,
+/// where [`Dir::open`] returns an error.
+/// Note that, this [may change in the future][changes].
+///
+/// [`Dir::open`]: #method.open
+/// [changes]: ../io/index.html#platform-specific-behavior
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_dir)]
+/// use std::fs::{Dir, OpenOptions};
+/// use std::io::{self, Write};
+///
+/// fn main() -> io::Result<()> {
+///     let dir = Dir::open("/var/lib/app")?;
+///     let mut file = dir.open_at("state.new", OpenOptions::new().write(true).create_new(true))?;
+///     file.write_all(b"new state")?;
+///     file.sync_all()?;
+///     dir.rename_at("state.new", &dir, "state")?;
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "fs_dir", issue = "0")]
+pub struct Dir(fs_imp::DirHandle);
+
+/// Options for replacing the contents of a file atomically, with
+/// [`write_atomic`].
+///
+/// [`write_atomic`]: fn.write_atomic.html
+///
+/// # Examples
+///
+/// Replace a file's contents, leaving the new file with the default
+/// permissions rather than those of the old one:
+///
+/// ```no_run
+/// #![feature(fs_write_atomic)]
+/// use std::fs::AtomicWriteOptions;
+///
+/// fn main() -> std::io::Result<()> {
+///     AtomicWriteOptions::new()
+///         .preserve_permissions(false)
+///         .write("settings.toml", "theme = \"dark\"\n")?;
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "fs_write_atomic", issue = "0")]
+#[derive(Clone, Debug)]
+pub struct AtomicWriteOptions {
+    preserve_permissions: bool,
+}
+
+/// Options for copying files and directory trees, with [`copy_with`] and
+/// [`copy_dir_all`].
+///
+/// By default, an existing destination is overwritten, the permissions of
+/// the source are copied and symlinks are followed, as with [`copy`]. The
+/// times, the owner and the extended attributes of the source aren't
+/// copied unless asked for.
+///
+/// [`copy_with`]: fn.copy_with.html
+/// [`copy_dir_all`]: fn.copy_dir_all.html
+/// [`copy`]: fn.copy.html
+///
+/// # Examples
+///
+/// Make a backup of a directory which looks as much like the original as
+/// the current user can make it:
+///
+/// ```no_run
+/// #![feature(fs_copy_options)]
+/// use std::fs::{self, CopyOptions};
+///
+/// fn main() -> std::io::Result<()> {
+///     let mut options = CopyOptions::new();
+///     options.preserve_times(true).follow_symlinks(false);
+///     fs::copy_dir_all("site", "site.bak", &options)?;
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "fs_copy_options", issue = "0")]
+#[derive(Clone, Debug)]
+pub struct CopyOptions {
+    overwrite: bool,
+    preserve_permissions: bool,
+    preserve_times: bool,
+    preserve_ownership: bool,
+    preserve_xattrs: bool,
+    follow_symlinks: bool,
+}
+
 /// How large a buffer to pre-allocate before reading the entire file.
 fn initial_buffer_size(file: &File) -> usize {
     // Allocate one extra byte so the buffer doesn't need to grow before the
@@ -329,6 +440,53 @@ pub fn write<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Result
     File::create(path)?.write_all(contents.as_ref())
 }
 
+/// Replaces the entire contents of a file atomically, creating it if it
+/// doesn't exist.
+///
+/// Unlike [`write`], other processes see either the old contents or the new
+/// ones, never a mixture of the two or a truncated file, even if the program
+/// or the system crashes partway through.
+///
+/// The contents are written to a new file in the same directory, which is
+/// synced to disk and then renamed over `path`, and finally the directory
+/// is synced with [`sync_dir`] so that the rename survives a crash too. The
+/// new file gets the
+/// permissions of the one it replaces; see [`AtomicWriteOptions`] to change
+/// that.
+///
+/// As the file is replaced rather than rewritten, other links to the old
+/// file keep the old contents, and if `path` is a symbolic link, the link is
+/// replaced instead of the file it points to. The owner of the old file
+/// isn't kept either. If the process dies before the rename, the
+/// temporary file, named after `path` with a leading `.` and a `.tmp`
+/// suffix, is left behind.
+///
+/// [`write`]: fn.write.html
+/// [`sync_dir`]: fn.sync_dir.html
+/// [`AtomicWriteOptions`]: struct.AtomicWriteOptions.html
+///
+/// # Errors
+///
+/// This function will return an error if `path` has no file name, or if
+/// creating, writing, syncing or renaming the new file fails. The new file
+/// is removed if anything fails before it's renamed.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_write_atomic)]
+/// use std::fs;
+///
+/// fn main() -> std::io::Result<()> {
+///     fs::write_atomic("state.json", b"{\"generation\": 2}")?;
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "fs_write_atomic", issue = "0")]
+pub fn write_atomic<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Result<()> {
+    AtomicWriteOptions::new().write(path, contents)
+}
+
 impl File {
     /// Attempts to open a file in read-only mode.
     ///
@@ -385,6 +543,12 @@ impl File {
     /// This function will attempt to ensure that all in-core data reaches the
     /// filesystem before returning.
     ///
+    /// Syncing a file doesn't make changes to the directory containing it,
+    /// such as the file having been created or renamed, durable; use
+    /// [`sync_dir`] for that.
+    ///
+    /// [`sync_dir`]: fn.sync_dir.html
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -470,6 +634,53 @@ impl File {
         self.inner.truncate(size)
     }
 
+    /// Allocates disk space for the first `len` bytes of the file, extending
+    /// it with 0s to `len` bytes if it's shorter. The file is never shrunk.
+    ///
+    /// Writes within that range then can't fail for lack of space, so a
+    /// writer which knows how much it's going to write can find out that the
+    /// disk is full before it starts, rather than partway through.
+    ///
+    /// Like [`set_len`], this doesn't move the file's cursor.
+    ///
+    /// [`set_len`]: #method.set_len
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to `posix_fallocate` on Linux and
+    /// FreeBSD, `fallocate` on Android, `fcntl` with `F_PREALLOCATE` on macOS
+    /// and iOS, and `SetFileInformationByHandle` with `FileAllocationInfo` on
+    /// Windows. It returns an error on other platforms, and some filesystems
+    /// don't support it either.
+    /// Note that, this [may change in the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file is not opened for
+    /// writing, or if there isn't enough space for `len` bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_allocate)]
+    /// use std::fs::File;
+    /// use std::io::Write;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let data = vec![0x55; 1 << 20];
+    ///     let mut f = File::create("foo.bin")?;
+    ///     f.allocate(data.len() as u64)?;
+    ///     f.write_all(&data)?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_allocate", issue = "0")]
+    pub fn allocate(&self, len: u64) -> io::Result<()> {
+        self.inner.allocate(len)
+    }
+
     /// Queries metadata about the underlying file.
     ///
     /// # Examples
@@ -570,6 +781,133 @@ impl File {
     pub fn set_permissions(&self, perm: Permissions) -> io::Result<()> {
         self.inner.set_permissions(perm.0)
     }
+
+    /// Locks the file for exclusive use, waiting until any other locks on
+    /// it are released.
+    ///
+    /// While it's held, no other shared or exclusive lock can be taken on
+    /// the file. The lock belongs to this `File` and any made from it by
+    /// [`try_clone`], and is released by [`unlock`] or once all of them are
+    /// closed. Other `File`s opened for the same file, even in this process,
+    /// take their own locks, which conflict with this one.
+    ///
+    /// Whether locking a file which is already locked through this `File`
+    /// replaces that lock, waits, or fails is unspecified, as is unlocking
+    /// after doing so.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to `flock` with `LOCK_EX` on
+    /// Unix and `LockFileEx` with `LOCKFILE_EXCLUSIVE_LOCK` on Windows.
+    /// Locks on Unix are advisory: they only stop other attempts to lock the
+    /// file. Locks on Windows are mandatory: other handles can't read or
+    /// write a file while it's exclusively locked, or write it while it has
+    /// a shared lock, and a lock may not be released straight away when the
+    /// file is closed. On platforms without `flock`, this returns an error.
+    /// Note that, this [may change in the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    /// [`try_clone`]: #method.try_clone
+    /// [`unlock`]: #method.unlock
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let file = File::create("foo.lock")?;
+    ///     file.lock_exclusive()?;
+    ///     // ...
+    ///     file.unlock()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn lock_exclusive(&self) -> io::Result<()> {
+        self.inner.lock(true, true).map(|_| ())
+    }
+
+    /// Locks the file for shared use, waiting until any exclusive lock on it
+    /// is released.
+    ///
+    /// Any number of shared locks can be held on a file at once, but not
+    /// alongside an exclusive lock. Otherwise this is like
+    /// [`lock_exclusive`].
+    ///
+    /// [`lock_exclusive`]: #method.lock_exclusive
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let file = File::open("foo.db")?;
+    ///     file.lock_shared()?;
+    ///     // ...
+    ///     file.unlock()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.inner.lock(false, true).map(|_| ())
+    }
+
+    /// Tries to lock the file for exclusive use, without waiting.
+    ///
+    /// Returns `Ok(false)` if another lock is held on the file. Otherwise
+    /// this is like [`lock_exclusive`].
+    ///
+    /// [`lock_exclusive`]: #method.lock_exclusive
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let file = File::create("foo.lock")?;
+    ///     if !file.try_lock()? {
+    ///         println!("someone else is using it");
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn try_lock(&self) -> io::Result<bool> {
+        self.inner.lock(true, false)
+    }
+
+    /// Tries to lock the file for shared use, without waiting.
+    ///
+    /// Returns `Ok(false)` if an exclusive lock is held on the file.
+    /// Otherwise this is like [`lock_shared`].
+    ///
+    /// [`lock_shared`]: #method.lock_shared
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        self.inner.lock(false, false)
+    }
+
+    /// Releases the lock held through this `File`.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to `flock` with `LOCK_UN` on Unix
+    /// and `UnlockFile` on Windows. Unlocking a file which isn't locked
+    /// succeeds on Unix, but fails on Windows.
+    /// Note that, this [may change in the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn unlock(&self) -> io::Result<()> {
+        self.inner.unlock()
+    }
 }
 
 impl AsInner<fs_imp::File> for File {
@@ -642,6 +980,32 @@ impl<'a> Seek for &'a File {
     }
 }
 
+/// Reading at an offset leaves the file's cursor where it was on Unix, but
+/// moves it to the end of what was read on Windows, as with
+/// `std::os::windows::fs::FileExt::seek_read`.
+#[unstable(feature = "positioned_io", issue = "0")]
+impl ReadAt for File {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        self.inner.read_at(buf, offset)
+    }
+    fn read_vectored_at(&self, bufs: &mut [&mut [u8]], offset: u64) -> io::Result<usize> {
+        self.inner.read_vectored_at(bufs, offset)
+    }
+}
+/// Writing at an offset leaves the file's cursor where it was on Unix, but
+/// moves it to the end of what was written on Windows, as with
+/// `std::os::windows::fs::FileExt::seek_write`. On Linux, writing at an
+/// offset to a file opened for appending appends instead.
+#[unstable(feature = "positioned_io", issue = "0")]
+impl WriteAt for File {
+    fn write_at(&self, buf: &[u8], offset: u64) -> io::Result<usize> {
+        self.inner.write_at(buf, offset)
+    }
+    fn write_vectored_at(&self, bufs: &[&[u8]], offset: u64) -> io::Result<usize> {
+        self.inner.write_vectored_at(bufs, offset)
+    }
+}
+
 impl OpenOptions {
     /// Creates a blank new set of options ready for configuration.
     ///
@@ -872,6 +1236,10 @@ impl OpenOptions {
     }
 }
 
+impl AsInner<fs_imp::OpenOptions> for OpenOptions {
+    fn as_inner(&self) -> &fs_imp::OpenOptions { &self.0 }
+}
+
 impl AsInnerMut<fs_imp::OpenOptions> for OpenOptions {
     fn as_inner_mut(&mut self) -> &mut fs_imp::OpenOptions { &mut self.0 }
 }
@@ -1047,12 +1415,14 @@ impl Metadata {
     /// Returns the creation time listed in this metadata.
     ///
     /// The returned value corresponds to the `birthtime` field of `stat` on
-    /// Unix platforms and the `ftCreationTime` field on Windows platforms.
+    /// Unix platforms, the `stx_btime` field of `statx` on Linux and the
+    /// `ftCreationTime` field on Windows platforms.
     ///
     /// # Errors
     ///
     /// This field may not be available on all platforms, and will return an
-    /// `Err` on platforms where it is not available.
+    /// `Err` on platforms where it is not available. On Linux it needs
+    /// Linux 4.11 or later, and a filesystem which records it.
     ///
     /// # Examples
     ///
@@ -1095,6 +1465,10 @@ impl AsInner<fs_imp::FileAttr> for Metadata {
     fn as_inner(&self) -> &fs_imp::FileAttr { &self.0 }
 }
 
+impl FromInner<fs_imp::FileAttr> for Metadata {
+    fn from_inner(attr: fs_imp::FileAttr) -> Metadata { Metadata(attr) }
+}
+
 impl Permissions {
     /// Returns `true` if these permissions describe a readonly (unwritable) file.
     ///
@@ -1249,6 +1623,47 @@ impl AsInner<fs_imp::FilePermissions> for Permissions {
     fn as_inner(&self) -> &fs_imp::FilePermissions { &self.0 }
 }
 
+impl ReadDir {
+    /// Sets roughly how many bytes of entries are fetched from the operating
+    /// system at a time, returning the same iterator.
+    ///
+    /// Every fetch is a system call, so for directories with a great many
+    /// entries a larger buffer can make reading them much faster, at the cost
+    /// of the memory it takes. The buffer is never made too small to hold an
+    /// entry, and a new size applies from the next fetch.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This currently only has an effect on Linux, where entries are read
+    /// with `getdents64` into a buffer of 64 KiB by default. Elsewhere the
+    /// size is ignored.
+    /// Note that, this [may change in the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(read_dir_buffer_size)]
+    /// use std::fs;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let mut count = 0;
+    ///     for entry in fs::read_dir("/var/spool/huge")?.with_buffer_size(1 << 20) {
+    ///         entry?;
+    ///         count += 1;
+    ///     }
+    ///     println!("{} entries", count);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "read_dir_buffer_size", issue = "0")]
+    pub fn with_buffer_size(mut self, size: usize) -> ReadDir {
+        self.0.set_buffer_size(size);
+        self
+    }
+}
+
 #[stable(feature = "rust1", since = "1.0.0")]
 impl Iterator for ReadDir {
     type Item = io::Result<DirEntry>;
@@ -1392,8 +1807,410 @@ impl fmt::Debug for DirEntry {
     }
 }
 
-impl AsInner<fs_imp::DirEntry> for DirEntry {
-    fn as_inner(&self) -> &fs_imp::DirEntry { &self.0 }
+impl AsInner<fs_imp::DirEntry> for DirEntry {
+    fn as_inner(&self) -> &fs_imp::DirEntry { &self.0 }
+}
+
+impl AtomicWriteOptions {
+    /// Creates a blank new set of options, which preserve permissions.
+    #[unstable(feature = "fs_write_atomic", issue = "0")]
+    pub fn new() -> AtomicWriteOptions {
+        AtomicWriteOptions { preserve_permissions: true }
+    }
+
+    /// Sets whether the new file gets the permissions of the file it
+    /// replaces.
+    ///
+    /// Otherwise, or if there's no file to replace, it gets the default
+    /// permissions for a new file.
+    #[unstable(feature = "fs_write_atomic", issue = "0")]
+    pub fn preserve_permissions(&mut self, preserve: bool) -> &mut AtomicWriteOptions {
+        self.preserve_permissions = preserve;
+        self
+    }
+
+    /// Replaces the entire contents of the file at `path` atomically with
+    /// these options.
+    ///
+    /// See [`write_atomic`] for how.
+    ///
+    /// [`write_atomic`]: fn.write_atomic.html
+    #[unstable(feature = "fs_write_atomic", issue = "0")]
+    pub fn write<P: AsRef<Path>, C: AsRef<[u8]>>(&self, path: P, contents: C)
+                                                 -> io::Result<()> {
+        self._write(path.as_ref(), contents.as_ref())
+    }
+
+    fn _write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        let name = match path.file_name() {
+            Some(name) => name,
+            None => return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                              "path has no file name")),
+        };
+        let dir = match path.parent() {
+            Some(dir) if dir != Path::new("") => dir,
+            _ => Path::new("."),
+        };
+        let permissions = if self.preserve_permissions {
+            match metadata(path) {
+                Ok(meta) => Some(meta.permissions()),
+                Err(ref e) if e.kind() == io::ErrorKind::NotFound => None,
+                Err(e) => return Err(e),
+            }
+        } else {
+            None
+        };
+
+        let (file, temp) = create_temp_beside(dir, name)?;
+        let res = fill_temp(file, contents, permissions).and_then(|()| rename(&temp, path));
+        if res.is_err() {
+            let _ = remove_file(&temp);
+        }
+        res?;
+        fs_imp::sync_dir(dir)
+    }
+}
+
+impl CopyOptions {
+    /// Creates a blank new set of options, which overwrite, preserve
+    /// permissions and follow symlinks, but preserve nothing else.
+    #[unstable(feature = "fs_copy_options", issue = "0")]
+    pub fn new() -> CopyOptions {
+        CopyOptions {
+            overwrite: true,
+            preserve_permissions: true,
+            preserve_times: false,
+            preserve_ownership: false,
+            preserve_xattrs: false,
+            follow_symlinks: true,
+        }
+    }
+
+    /// Sets whether an existing file or symlink at the destination is
+    /// replaced.
+    ///
+    /// Otherwise, copying fails with an error of the kind
+    /// [`ErrorKind::AlreadyExists`] if there's one. When copying a directory
+    /// tree, overwriting also lets an existing directory be copied into,
+    /// but a directory is never replaced by anything else.
+    ///
+    /// [`ErrorKind::AlreadyExists`]: ../io/enum.ErrorKind.html#variant.AlreadyExists
+    #[unstable(feature = "fs_copy_options", issue = "0")]
+    pub fn overwrite(&mut self, overwrite: bool) -> &mut CopyOptions {
+        self.overwrite = overwrite;
+        self
+    }
+
+    /// Sets whether the copy gets the permissions of the source.
+    ///
+    /// Otherwise it keeps the permissions of the file it replaces, or gets
+    /// the default permissions for a new file.
+    #[unstable(feature = "fs_copy_options", issue = "0")]
+    pub fn preserve_permissions(&mut self, preserve: bool) -> &mut CopyOptions {
+        self.preserve_permissions = preserve;
+        self
+    }
+
+    /// Sets whether the copy gets the last access and modification times of
+    /// the source.
+    ///
+    /// The times are only as precise as the platform can set them, which
+    /// can be less precise than it reports them.
+    #[unstable(feature = "fs_copy_options", issue = "0")]
+    pub fn preserve_times(&mut self, preserve: bool) -> &mut CopyOptions {
+        self.preserve_times = preserve;
+        self
+    }
+
+    /// Sets whether the copy gets the owner and group of the source.
+    ///
+    /// Changing the owner usually needs privileges, so copying fails without
+    /// them. This isn't supported on Windows.
+    #[unstable(feature = "fs_copy_options", issue = "0")]
+    pub fn preserve_ownership(&mut self, preserve: bool) -> &mut CopyOptions {
+        self.preserve_ownership = preserve;
+        self
+    }
+
+    /// Sets whether the copy gets the extended attributes of the source.
+    ///
+    /// A source on a filesystem without extended attributes has none to
+    /// copy. This is only supported on Linux, Android, macOS and iOS.
+    #[unstable(feature = "fs_copy_options", issue = "0")]
+    pub fn preserve_xattrs(&mut self, preserve: bool) -> &mut CopyOptions {
+        self.preserve_xattrs = preserve;
+        self
+    }
+
+    /// Sets whether a symlink is copied as what it points to.
+    ///
+    /// Otherwise, a new symlink is made with the same target, which is kept
+    /// as it is even if it's relative. Only the owner of a symlink is
+    /// preserved, if asked for.
+    #[unstable(feature = "fs_copy_options", issue = "0")]
+    pub fn follow_symlinks(&mut self, follow: bool) -> &mut CopyOptions {
+        self.follow_symlinks = follow;
+        self
+    }
+
+    fn source_attr(&self, path: &Path) -> io::Result<fs_imp::FileAttr> {
+        if self.follow_symlinks {
+            fs_imp::stat(path)
+        } else {
+            fs_imp::lstat(path)
+        }
+    }
+
+    // Copies anything but a directory, which `copy_dir` handles itself.
+    fn copy_entry(&self, from: &Path, to: &Path, attr: &fs_imp::FileAttr) -> io::Result<u64> {
+        if attr.file_type().is_symlink() {
+            self.copy_symlink(from, to, attr)
+        } else {
+            self.copy_file(from, to, attr)
+        }
+    }
+
+    fn copy_file(&self, from: &Path, to: &Path, attr: &fs_imp::FileAttr) -> io::Result<u64> {
+        // `copy` copies the permissions, so find out what they'd otherwise
+        // be first, making the file if need be.
+        let mut opts = OpenOptions::new();
+        opts.write(true);
+        if self.overwrite {
+            opts.create(true);
+        } else {
+            opts.create_new(true);
+        }
+        let permissions = opts.open(to)?.metadata()?.permissions();
+        let copied = fs_imp::copy(from, to)?;
+        self.copy_attributes(from, to, attr, permissions)?;
+        Ok(copied)
+    }
+
+    fn copy_symlink(&self, from: &Path, to: &Path, attr: &fs_imp::FileAttr) -> io::Result<u64> {
+        if self.overwrite {
+            match remove_file(to) {
+                Ok(()) => {}
+                Err(ref e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+        fs_imp::copy_symlink(from, to)?;
+        if self.preserve_ownership {
+            fs_imp::copy_ownership(attr, to, false)?;
+        }
+        Ok(0)
+    }
+
+    // `ancestors` identifies the directories being copied from and to on the
+    // way down to here, which is only kept up when following symlinks, as
+    // otherwise none of them can be met again.
+    fn copy_dir(&self, from: &Path, to: &Path, attr: &fs_imp::FileAttr,
+                ancestors: &mut Vec<(u64, u64)>) -> io::Result<u64> {
+        match create_dir(to) {
+            Ok(()) => {}
+            Err(ref e) if e.kind() == io::ErrorKind::AlreadyExists && self.overwrite => {
+                if !symlink_metadata(to)?.is_dir() {
+                    return Err(io::Error::new(io::ErrorKind::AlreadyExists,
+                                              "the destination is not a directory"));
+                }
+            }
+            Err(e) => return Err(e),
+        }
+        let permissions = symlink_metadata(to)?.permissions();
+        if self.follow_symlinks {
+            ancestors.push(fs_imp::file_id(to)?);
+        }
+
+        let mut copied = 0;
+        for entry in read_dir(from)? {
+            let entry = entry?;
+            let path = entry.path();
+            let attr = self.source_attr(&path)?;
+            let to = to.join(entry.file_name());
+            if !attr.file_type().is_dir() {
+                copied += self.copy_entry(&path, &to, &attr)?;
+            } else if self.follow_symlinks {
+                let id = fs_imp::file_id(&path)?;
+                if ancestors.contains(&id) {
+                    return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                              "a symlink leads back to a directory being copied"));
+                }
+                ancestors.push(id);
+                copied += self.copy_dir(&path, &to, &attr, ancestors)?;
+                ancestors.pop();
+            } else {
+                copied += self.copy_dir(&path, &to, &attr, ancestors)?;
+            }
+        }
+        if self.follow_symlinks {
+            ancestors.pop();
+        }
+        // Only now, in case the permissions of `from` don't let us write to
+        // `to`, or the times would be changed by writing to it.
+        self.copy_attributes(from, to, attr, permissions)?;
+        Ok(copied)
+    }
+
+    // Ownership goes first, as changing it can clear the setuid and setgid
+    // bits, and extended attributes before permissions which could stop us
+    // setting them.
+    fn copy_attributes(&self, from: &Path, to: &Path, attr: &fs_imp::FileAttr,
+                       permissions: Permissions) -> io::Result<()> {
+        if self.preserve_ownership {
+            fs_imp::copy_ownership(attr, to, true)?;
+        }
+        if self.preserve_xattrs {
+            fs_imp::copy_xattrs(from, to)?;
+        }
+        if self.preserve_permissions {
+            fs_imp::set_perm(to, attr.perm())?;
+        } else {
+            fs_imp::set_perm(to, permissions.0)?;
+        }
+        if self.preserve_times {
+            fs_imp::set_times(to, attr.accessed()?, attr.modified()?)?;
+        }
+        Ok(())
+    }
+}
+
+// Creates a new file in `dir` with a name which no one else is using.
+fn create_temp_beside(dir: &Path, name: &OsStr) -> io::Result<(File, PathBuf)> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let mut opts = OpenOptions::new();
+    opts.write(true).create_new(true);
+    let mut attempts = 0;
+    loop {
+        let mut temp_name = OsString::from(".");
+        temp_name.push(name);
+        temp_name.push(format!(".{}.{}.tmp", process::id(),
+                               COUNTER.fetch_add(1, Ordering::Relaxed)));
+        let temp = dir.join(temp_name);
+        match opts.open(&temp) {
+            Ok(file) => return Ok((file, temp)),
+            // Left behind by an earlier process with the same id.
+            Err(ref e) if e.kind() == io::ErrorKind::AlreadyExists && attempts < 100 => {
+                attempts += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+// Takes the file by value so that it's closed before it's renamed, which
+// Windows needs.
+fn fill_temp(mut file: File, contents: &[u8], permissions: Option<Permissions>)
+             -> io::Result<()> {
+    file.write_all(contents)?;
+    if let Some(permissions) = permissions {
+        file.set_permissions(permissions)?;
+    }
+    file.sync_all()
+}
+
+impl Dir {
+    /// Opens the directory at `path`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `path` doesn't exist, isn't a
+    /// directory or can't be read.
+    #[unstable(feature = "fs_dir", issue = "0")]
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Dir> {
+        fs_imp::DirHandle::open(path.as_ref()).map(Dir)
+    }
+
+    /// Opens the file at `path` within this directory, with the options
+    /// given.
+    ///
+    /// This is [`OpenOptions::open`], but relative to this directory.
+    ///
+    /// [`OpenOptions::open`]: struct.OpenOptions.html#method.open
+    #[unstable(feature = "fs_dir", issue = "0")]
+    pub fn open_at<P: AsRef<Path>>(&self, path: P, opts: &OpenOptions) -> io::Result<File> {
+        self.0.open_at(path.as_ref(), &opts.0).map(|inner| File { inner })
+    }
+
+    /// Opens the directory at `path` within this directory.
+    #[unstable(feature = "fs_dir", issue = "0")]
+    pub fn open_dir_at<P: AsRef<Path>>(&self, path: P) -> io::Result<Dir> {
+        self.0.open_dir_at(path.as_ref()).map(Dir)
+    }
+
+    /// Returns an iterator over the entries of the directory at `path`
+    /// within this directory. Use `"."` for this directory itself.
+    ///
+    /// This is [`read_dir`], but relative to this directory. The paths of
+    /// the entries are joined onto the path this directory was opened
+    /// with, so they're only right as long as it hasn't been moved.
+    ///
+    /// [`read_dir`]: fn.read_dir.html
+    #[unstable(feature = "fs_dir", issue = "0")]
+    pub fn read_dir_at<P: AsRef<Path>>(&self, path: P) -> io::Result<ReadDir> {
+        self.0.read_dir_at(path.as_ref()).map(ReadDir)
+    }
+
+    /// Queries the metadata of the file at `path` within this directory,
+    /// following symbolic links.
+    ///
+    /// This is [`metadata`], but relative to this directory.
+    ///
+    /// [`metadata`]: fn.metadata.html
+    #[unstable(feature = "fs_dir", issue = "0")]
+    pub fn metadata_at<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
+        self.0.metadata_at(path.as_ref(), true).map(Metadata)
+    }
+
+    /// Queries the metadata of the file at `path` within this directory,
+    /// without following a symbolic link at the end of it.
+    ///
+    /// This is [`symlink_metadata`], but relative to this directory.
+    ///
+    /// [`symlink_metadata`]: fn.symlink_metadata.html
+    #[unstable(feature = "fs_dir", issue = "0")]
+    pub fn symlink_metadata_at<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
+        self.0.metadata_at(path.as_ref(), false).map(Metadata)
+    }
+
+    /// Removes the file at `path` within this directory.
+    ///
+    /// This is [`remove_file`], but relative to this directory.
+    ///
+    /// [`remove_file`]: fn.remove_file.html
+    #[unstable(feature = "fs_dir", issue = "0")]
+    pub fn remove_file_at<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.0.remove_file_at(path.as_ref())
+    }
+
+    /// Renames the file at `from` within this directory to `to` within
+    /// `to_dir`, which may be this directory again.
+    ///
+    /// This is [`rename`], but relative to directories, and it has the same
+    /// limits, such as both directories having to be on the same
+    /// filesystem.
+    ///
+    /// [`rename`]: fn.rename.html
+    #[unstable(feature = "fs_dir", issue = "0")]
+    pub fn rename_at<P: AsRef<Path>, Q: AsRef<Path>>(&self, from: P, to_dir: &Dir, to: Q)
+                                                     -> io::Result<()> {
+        self.0.rename_at(from.as_ref(), &to_dir.0, to.as_ref())
+    }
+}
+
+#[unstable(feature = "fs_dir", issue = "0")]
+impl fmt::Debug for Dir {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl AsInner<fs_imp::DirHandle> for Dir {
+    fn as_inner(&self) -> &fs_imp::DirHandle { &self.0 }
+}
+
+impl IntoInner<fs_imp::DirHandle> for Dir {
+    fn into_inner(self) -> fs_imp::DirHandle { self.0 }
 }
 
 /// Removes a file from the filesystem.
@@ -1505,6 +2322,55 @@ pub fn symlink_metadata<P: AsRef<Path>>(path: P) -> io::Result<Metadata> {
     fs_imp::lstat(path.as_ref()).map(Metadata)
 }
 
+/// Returns whether a file exists at `path`, following symbolic links.
+///
+/// Unlike [`Path::exists`], this only returns `Ok(false)` when the file
+/// definitely doesn't exist, and returns an error when that can't be
+/// determined, such as when a directory leading to it can't be searched.
+/// Broken symbolic links return `Ok(false)`.
+///
+/// Note that the file may be created or removed as soon as this returns, so
+/// the answer shouldn't be used to decide whether, say, creating the file
+/// will succeed; do that and handle the error instead.
+///
+/// [`Path::exists`]: ../path/struct.Path.html#method.exists
+///
+/// # Platform-specific behavior
+///
+/// This function currently corresponds to the `stat` function on Unix and
+/// the `CreateFile` function on Windows, where files which are open without
+/// sharing, and so can't be opened, are also reported to exist.
+/// Note that, this [may change in the future][changes].
+///
+/// [changes]: ../io/index.html#platform-specific-behavior
+///
+/// # Errors
+///
+/// This function will return an error in the following situations, but is not
+/// limited to just these cases:
+///
+/// * The user lacks permissions to search a directory leading to `path`.
+/// * A component of `path` which should be a directory is a regular file.
+/// * There are too many levels of symbolic links in `path`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// #![feature(fs_try_exists)]
+/// use std::fs;
+///
+/// fn main() -> std::io::Result<()> {
+///     if !fs::try_exists("/etc/app/config.toml")? {
+///         println!("using the default configuration");
+///     }
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "fs_try_exists", issue = "0")]
+pub fn try_exists<P: AsRef<Path>>(path: P) -> io::Result<bool> {
+    fs_imp::try_exists(path.as_ref())
+}
+
 /// Rename a file or directory to a new name, replacing the original file if
 /// `to` already exists.
 ///
@@ -1570,6 +2436,8 @@ pub fn rename<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<()>
 /// This function currently corresponds to the `open` function in Unix
 /// with `O_RDONLY` for `from` and `O_WRONLY`, `O_CREAT`, and `O_TRUNC` for `to`.
 /// `O_CLOEXEC` is set for returned file descriptors.
+/// On Linux, holes in a sparse `from` are left as holes in `to`, where the
+/// filesystems allow it.
 /// On Windows, this function currently corresponds to `CopyFileEx`. Alternate
 /// NTFS streams are copied but only the size of the main stream is returned by
 /// this function.
@@ -1602,6 +2470,199 @@ pub fn copy<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<u64> {
     fs_imp::copy(from.as_ref(), to.as_ref())
 }
 
+/// Copies the contents of one file to another like [`copy`], calling
+/// `progress` as it goes.
+///
+/// `progress` is called with the number of bytes copied so far and the size
+/// of `from` when the copy started: each time at least `chunk_size` more
+/// bytes have been copied since it was last called, and once more when the
+/// copy is done. Where the copy is done by the operating system in chunks
+/// of a size we can choose, they're `chunk_size` bytes, so a smaller
+/// `chunk_size` means more frequent calls but can make the copy slower.
+///
+/// If `progress` returns an error, the copy is stopped and the error is
+/// returned. Whatever had been copied is left in `to`.
+///
+/// This uses the same means of copying as [`copy`], which calls this with a
+/// `progress` that does nothing.
+///
+/// [`copy`]: fn.copy.html
+///
+/// # Errors
+///
+/// As well as in the cases [`copy`] fails, this function will return an
+/// error if `chunk_size` is zero, or if `progress` does.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(copy_with_progress)]
+/// use std::fs;
+///
+/// fn main() -> std::io::Result<()> {
+///     fs::copy_with_progress("big.iso", "copy.iso", 1 << 20, |copied, total| {
+///         println!("{} of {} bytes", copied, total);
+///         Ok(())
+///     })?;
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "copy_with_progress", issue = "0")]
+pub fn copy_with_progress<P, Q, F>(from: P, to: Q, chunk_size: u64, mut progress: F)
+                                   -> io::Result<u64>
+    where P: AsRef<Path>, Q: AsRef<Path>, F: FnMut(u64, u64) -> io::Result<()>
+{
+    if chunk_size == 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "chunk size is zero"))
+    }
+    let mut progress = Progress::new(&mut progress, chunk_size);
+    fs_imp::copy_with_progress(from.as_ref(), to.as_ref(), &mut progress)
+}
+
+/// Copies a file with the given options, returning the number of bytes
+/// copied.
+///
+/// This is [`copy`] with more control over what happens: whether an
+/// existing `to` is replaced, which of the attributes of `from` are given to
+/// the copy, and whether a symlink at `from` is copied as a symlink. See
+/// [`CopyOptions`] for the details, and [`copy_dir_all`] for copying
+/// directories.
+///
+/// The contents are copied as by [`copy`], and then the attributes are set
+/// one by one, so if copying fails part of the way, `to` can be left with
+/// some of them. Copying a symlink copies no bytes.
+///
+/// [`copy`]: fn.copy.html
+/// [`CopyOptions`]: struct.CopyOptions.html
+/// [`copy_dir_all`]: fn.copy_dir_all.html
+///
+/// # Platform-specific behavior
+///
+/// On Windows, the modification time of `from` is always given to the copy,
+/// as [`copy`] does.
+/// Note that, this [may change in the future][changes].
+///
+/// [changes]: ../io/index.html#platform-specific-behavior
+///
+/// # Errors
+///
+/// As well as in the cases [`copy`] fails, this function will return an
+/// error if:
+///
+/// * `from` is a directory.
+/// * `to` exists and the options don't allow overwriting it.
+/// * An attribute can't be preserved, such as the owner without the
+///   privileges to change it, or one the platform doesn't support.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_copy_options)]
+/// use std::fs::{self, CopyOptions};
+///
+/// fn main() -> std::io::Result<()> {
+///     let mut options = CopyOptions::new();
+///     options.overwrite(false).preserve_times(true);
+///     fs::copy_with("foo.txt", "bar.txt", &options)?;
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "fs_copy_options", issue = "0")]
+pub fn copy_with<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q, options: &CopyOptions)
+                                                 -> io::Result<u64> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    let attr = options.source_attr(from)?;
+    if attr.file_type().is_dir() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "the source path is a directory"))
+    }
+    options.copy_entry(from, to, &attr)
+}
+
+/// Recursively copies a directory and all of its contents with the given
+/// options, returning the total number of bytes copied.
+///
+/// `to` is created if it doesn't exist. Each entry of `from` is copied to
+/// the entry of the same name in `to` as by [`copy_with`], or by this
+/// function for a directory, and then the attributes of `from` are given to
+/// `to`. See [`CopyOptions`] for which.
+///
+/// The copy isn't a snapshot: changes made to `from` while it's being
+/// copied may or may not show up in `to`. If copying fails part of the way,
+/// whatever had been copied is left in `to`.
+///
+/// [`copy_with`]: fn.copy_with.html
+/// [`CopyOptions`]: struct.CopyOptions.html
+///
+/// # Errors
+///
+/// This function will return an error in the following situations, but is not
+/// limited to just these cases:
+///
+/// * `from` is not a directory.
+/// * `to` exists and is not a directory.
+/// * Copying any of the contents fails, as for [`copy_with`]. This includes
+///   entries which are neither files, directories nor symlinks, such as
+///   sockets.
+/// * `to` is `from` or is inside it, which is found before anything is
+///   copied.
+/// * The options follow symlinks, and `from` contains a symlink to a
+///   directory which contains the symlink or `to`. The copy stops at the
+///   symlink, as it would otherwise go on forever.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_copy_options)]
+/// use std::fs::{self, CopyOptions};
+///
+/// fn main() -> std::io::Result<()> {
+///     fs::copy_dir_all("assets", "dist/assets", &CopyOptions::new())?;
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "fs_copy_options", issue = "0")]
+pub fn copy_dir_all<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q, options: &CopyOptions)
+                                                    -> io::Result<u64> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    let attr = options.source_attr(from)?;
+    if !attr.file_type().is_dir() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                  "the source path is not a directory"))
+    }
+    let id = fs_imp::file_id(from)?;
+    check_not_inside(id, to)?;
+    options.copy_dir(from, to, &attr, &mut vec![id])
+}
+
+// Fails if `to` is the directory identified by `from` or is inside it,
+// looking at the directories above the nearest part of `to` which exists.
+fn check_not_inside(from: (u64, u64), to: &Path) -> io::Result<()> {
+    let mut dir = to;
+    loop {
+        match fs_imp::file_id(dir) {
+            Ok(_) => break,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        dir = match dir.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            Some(_) if dir != Path::new(".") => Path::new("."),
+            _ => return Ok(()),
+        };
+    }
+    // Once symlinks and `..` are resolved, the ancestors of the path are
+    // where `to` really is. Where that can't be done, as on WASI, the ones it
+    // names are the best there is.
+    let dir = canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+    for dir in dir.ancestors().filter(|dir| !dir.as_os_str().is_empty()) {
+        if fs_imp::file_id(dir)? == from {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "cannot copy a directory into itself"))
+        }
+    }
+    Ok(())
+}
+
 /// Creates a new hard link on the filesystem.
 ///
 /// The `dst` path will be a link pointing to the `src` path. Note that systems
@@ -1897,6 +2958,52 @@ pub fn remove_dir_all<P: AsRef<Path>>(path: P) -> io::Result<()> {
     fs_imp::remove_dir_all(path.as_ref())
 }
 
+/// Makes changes to the entries of a directory durable.
+///
+/// Files created in, renamed into or removed from a directory may not
+/// survive a crash until the directory itself is synced, even if the files
+/// themselves have been synced with [`File::sync_all`]. This is needed, for
+/// example, after renaming a new version of a file over the old one.
+///
+/// [`File::sync_all`]: struct.File.html#method.sync_all
+///
+/// # Platform-specific behavior
+///
+/// This function currently opens the directory and calls `fsync` on Unix,
+/// treating `EINVAL` from filesystems which can't sync directories as
+/// success. On Windows it only checks that the directory exists, as
+/// directory changes are committed along with the operations that make
+/// them.
+/// Note that, this [may change in the future][changes].
+///
+/// [changes]: ../io/index.html#platform-specific-behavior
+///
+/// # Errors
+///
+/// This function will return an error if `path` doesn't exist, isn't a
+/// directory or can't be opened, or if syncing fails.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_sync_dir)]
+/// use std::fs::{self, File};
+/// use std::io::Write;
+///
+/// fn main() -> std::io::Result<()> {
+///     let mut f = File::create("data/log.new")?;
+///     f.write_all(b"entry")?;
+///     f.sync_all()?;
+///     fs::rename("data/log.new", "data/log")?;
+///     fs::sync_dir("data")?;
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "fs_sync_dir", issue = "0")]
+pub fn sync_dir<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    fs_imp::sync_dir(path.as_ref())
+}
+
 /// Returns an iterator over the entries within a directory.
 ///
 /// The iterator will yield instances of [`io::Result`]`<`[`DirEntry`]`>`.
@@ -2090,7 +3197,7 @@ mod tests {
     use io::prelude::*;
 
     use fs::{self, File, OpenOptions};
-    use io::{ErrorKind, SeekFrom};
+    use io::{self, ErrorKind, SeekFrom};
     use path::Path;
     use rand::{rngs::StdRng, FromEntropy, RngCore};
     use str;
@@ -2322,6 +3429,45 @@ mod tests {
         check!(fs::remove_file(&filename));
     }
 
+    #[test]
+    fn file_test_io_positioned() {
+        use io::{ReadAt, WriteAt};
+
+        let tmpdir = tmpdir();
+        let filename = tmpdir.join("positioned");
+        let f = check!(OpenOptions::new().create_new(true).write(true).read(true)
+                                         .open(&filename));
+
+        check!(f.write_all_at(b"world", 6));
+        check!(f.write_all_at(b"hello ", 0));
+        // Vectored calls may stop after the first buffer on some platforms.
+        match check!(f.write_vectored_at(&[&b"!"[..], &b"?"[..]], 11)) {
+            1 => check!(f.write_all_at(b"?", 12)),
+            n => assert_eq!(n, 2),
+        }
+        assert_eq!(check!(fs::read(&filename)), b"hello world!?");
+
+        let mut buf = [0; 5];
+        check!(f.read_exact_at(&mut buf, 6));
+        assert_eq!(&buf, b"world");
+        let (mut a, mut b) = ([0; 3], [0; 3]);
+        match check!(f.read_vectored_at(&mut [&mut a[..], &mut b[..]], 8)) {
+            3 => {}
+            n => {
+                assert_eq!(n, 5);
+                assert_eq!(&b[..2], b"!?");
+            }
+        }
+        assert_eq!(&a, b"rld");
+        assert_eq!(check!(f.read_at(&mut buf, 13)), 0);
+        let err = f.read_exact_at(&mut buf, 10).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+        if cfg!(unix) {
+            assert_eq!(check!((&f).seek(SeekFrom::Current(0))), 0);
+        }
+    }
+
     #[test]
     #[cfg(unix)]
     fn file_test_io_read_write_at() {
@@ -2674,6 +3820,30 @@ mod tests {
         }
     }
 
+    #[test]
+    // paths over MAX_PATH need the verbatim prefix on Windows, which std
+    // should add transparently.
+    fn long_path_roundtrip() {
+        let tmpdir = tmpdir();
+        let component = "a".repeat(100);
+        let mut dir = tmpdir.join("long");
+        for _ in 0..4 {
+            dir.push(&component);
+        }
+        check!(fs::create_dir_all(&dir));
+        assert!(dir.is_dir());
+
+        let file = dir.join("f1");
+        check!(check!(File::create(&file)).write(b"foo"));
+        assert_eq!(check!(fs::read(&file)), b"foo");
+        let canonical = check!(fs::canonicalize(&file));
+        assert_eq!(check!(fs::read(&canonical)), b"foo");
+        check!(fs::rename(&file, dir.join("f2")));
+        check!(fs::copy(dir.join("f2"), dir.join("f3")));
+        check!(fs::remove_dir_all(tmpdir.join("long")));
+        assert!(!dir.exists());
+    }
+
     #[test]
     fn unicode_path_is_dir() {
         assert!(Path::new(".").is_dir());
@@ -2749,6 +3919,23 @@ mod tests {
                    check!(out.metadata()).permissions());
     }
 
+    #[test]
+    fn copy_sparse_file() {
+        let tmpdir = tmpdir();
+        let input = tmpdir.join("in");
+        let out = tmpdir.join("out");
+        let len = 3 << 20;
+
+        {
+            let mut f = check!(File::create(&input));
+            check!(f.set_len(len));
+            check!(f.seek(SeekFrom::Start(1 << 20)));
+            check!(f.write_all(&[0x5a; 4096]));
+        }
+        assert_eq!(check!(fs::copy(&input, &out)), len);
+        assert_eq!(check!(fs::read(&input)), check!(fs::read(&out)));
+    }
+
     #[test]
     fn copy_file_dst_dir() {
         let tmpdir = tmpdir();
@@ -2826,6 +4013,242 @@ mod tests {
         assert_eq!(check!(out_path.metadata()).len(), copied_len);
     }
 
+    #[test]
+    fn copy_file_large() {
+        let tmp = tmpdir();
+        let in_path = tmp.join("in.bin");
+        let out_path = tmp.join("out.bin");
+        let data: Vec<u8> = (0..3 * 1024 * 1024 + 7).map(|i| (i % 251) as u8).collect();
+        check!(check!(File::create(&in_path)).write_all(&data));
+        assert_eq!(check!(fs::copy(&in_path, &out_path)), data.len() as u64);
+        let mut v = Vec::new();
+        check!(check!(File::open(&out_path)).read_to_end(&mut v));
+        assert!(v == data);
+    }
+
+    #[test]
+    fn copy_with_progress_reports() {
+        let tmp = tmpdir();
+        let in_path = tmp.join("in.bin");
+        let out_path = tmp.join("out.bin");
+        let data = vec![7u8; 100 * 1024 + 3];
+        check!(check!(File::create(&in_path)).write_all(&data));
+
+        let mut calls = Vec::new();
+        let copied = check!(fs::copy_with_progress(&in_path, &out_path, 16 * 1024, |c, t| {
+            calls.push((c, t));
+            Ok(())
+        }));
+        assert_eq!(copied, data.len() as u64);
+        assert_eq!(calls.last(), Some(&(copied, copied)));
+        for pair in calls.windows(2) {
+            assert!(pair[0].0 < pair[1].0);
+        }
+        assert!(calls.iter().all(|&(_, total)| total == data.len() as u64));
+        let mut v = Vec::new();
+        check!(check!(File::open(&out_path)).read_to_end(&mut v));
+        assert!(v == data);
+
+        let err = fs::copy_with_progress(&in_path, &out_path, 1, |_, _| {
+            Err(io::Error::new(ErrorKind::Other, "cancelled"))
+        }).unwrap_err();
+        assert_eq!(err.to_string(), "cancelled");
+
+        let err = fs::copy_with_progress(&in_path, &out_path, 0, |_, _| Ok(())).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn copy_with_options() {
+        use fs::CopyOptions;
+        use time::Duration;
+
+        let tmpdir = tmpdir();
+        let in_path = tmpdir.join("in.txt");
+        let out_path = tmpdir.join("out.txt");
+        check!(fs::write(&in_path, b"carrot"));
+        check!(fs::write(&out_path, b"lettuce"));
+        let mut options = CopyOptions::new();
+
+        options.overwrite(false);
+        let err = fs::copy_with(&in_path, &out_path, &options).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+        assert_eq!(check!(fs::read(&out_path)), b"lettuce");
+
+        // Long enough that the copy would otherwise get a later time.
+        thread::sleep(Duration::from_millis(100));
+        options.overwrite(true).preserve_times(true);
+        assert_eq!(check!(fs::copy_with(&in_path, &out_path, &options)), 6);
+        assert_eq!(check!(fs::read(&out_path)), b"carrot");
+        // Allowing for platforms which set times less precisely.
+        let from = check!(check!(fs::metadata(&in_path)).modified());
+        let to = check!(check!(fs::metadata(&out_path)).modified());
+        let diff = from.duration_since(to).unwrap_or_else(|e| e.duration());
+        assert!(diff < Duration::from_millis(1), "modified {:?} but copied {:?}", from, to);
+
+        let err = fs::copy_with(tmpdir.path(), tmpdir.join("dir"), &options).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn copy_with_permissions() {
+        use fs::CopyOptions;
+        use os::unix::fs::PermissionsExt;
+
+        let tmpdir = tmpdir();
+        let in_path = tmpdir.join("in.txt");
+        let out_path = tmpdir.join("out.txt");
+        check!(fs::write(&in_path, b"carrot"));
+        check!(fs::write(&out_path, b"lettuce"));
+        check!(fs::set_permissions(&in_path, fs::Permissions::from_mode(0o600)));
+        check!(fs::set_permissions(&out_path, fs::Permissions::from_mode(0o640)));
+
+        check!(fs::copy_with(&in_path, &out_path, CopyOptions::new().preserve_permissions(false)));
+        assert_eq!(check!(fs::metadata(&out_path)).permissions().mode() & 0o777, 0o640);
+
+        check!(fs::copy_with(&in_path, &out_path, &CopyOptions::new()));
+        assert_eq!(check!(fs::metadata(&out_path)).permissions().mode() & 0o777, 0o600);
+    }
+
+    #[test]
+    fn copy_dir_all() {
+        use fs::CopyOptions;
+
+        let tmpdir = tmpdir();
+        let from = tmpdir.join("from");
+        let to = tmpdir.join("to");
+        check!(fs::create_dir_all(from.join("a/b")));
+        check!(fs::write(from.join("top.txt"), b"top"));
+        check!(fs::write(from.join("a/b/deep.txt"), b"deep"));
+
+        assert_eq!(check!(fs::copy_dir_all(&from, &to, &CopyOptions::new())), 7);
+        assert_eq!(check!(fs::read(to.join("top.txt"))), b"top");
+        assert_eq!(check!(fs::read(to.join("a/b/deep.txt"))), b"deep");
+
+        // Copying into an existing directory needs overwriting allowed.
+        check!(fs::write(from.join("top.txt"), b"new"));
+        let err = fs::copy_dir_all(&from, &to, CopyOptions::new().overwrite(false)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+        check!(fs::copy_dir_all(&from, &to, &CopyOptions::new()));
+        assert_eq!(check!(fs::read(to.join("top.txt"))), b"new");
+
+        let err = fs::copy_dir_all(from.join("top.txt"), &to, &CopyOptions::new()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn copy_dir_all_into_itself() {
+        use fs::CopyOptions;
+
+        let tmpdir = tmpdir();
+        let a = tmpdir.join("a");
+        check!(fs::create_dir(&a));
+        check!(fs::write(a.join("file"), b"foo"));
+
+        let err = fs::copy_dir_all(&a, a.join("sub"), &CopyOptions::new()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert!(!a.join("sub").exists());
+        let err = fs::copy_dir_all(&a, a.join("sub/deeper"), &CopyOptions::new()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert!(!a.join("sub").exists());
+        let err = fs::copy_dir_all(&a, &a, &CopyOptions::new()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn copy_dir_all_symlinks() {
+        use fs::CopyOptions;
+
+        let tmpdir = tmpdir();
+        if !got_symlink_permission(&tmpdir) { return };
+        let from = tmpdir.join("from");
+        check!(fs::create_dir(&from));
+        check!(fs::write(from.join("file"), b"foo"));
+        check!(symlink_file("file", from.join("link")));
+
+        let to = tmpdir.join("links");
+        check!(fs::copy_dir_all(&from, &to, CopyOptions::new().follow_symlinks(false)));
+        assert!(check!(fs::symlink_metadata(to.join("link"))).file_type().is_symlink());
+        assert_eq!(check!(fs::read_link(to.join("link"))), Path::new("file"));
+
+        let to = tmpdir.join("files");
+        check!(fs::copy_dir_all(&from, &to, &CopyOptions::new()));
+        assert!(check!(fs::symlink_metadata(to.join("link"))).file_type().is_file());
+        assert_eq!(check!(fs::read(to.join("link"))), b"foo");
+
+        check!(symlink_dir("..", from.join("loop")));
+        let err = fs::copy_dir_all(&from, tmpdir.join("loop"), &CopyOptions::new()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    #[cfg(any(windows,
+              target_os = "linux",
+              target_os = "android",
+              target_os = "macos",
+              target_os = "ios",
+              target_os = "freebsd"))]
+    fn file_allocate() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("allocated");
+        let mut file = check!(File::create(&path));
+        check!(file.write_all(b"foo"));
+
+        check!(file.allocate(1 << 20));
+        assert_eq!(check!(file.metadata()).len(), 1 << 20);
+        assert_eq!(check!(file.seek(SeekFrom::Current(0))), 3);
+
+        check!(file.allocate(10));
+        assert_eq!(check!(file.metadata()).len(), 1 << 20);
+
+        let mut contents = Vec::new();
+        check!(check!(File::open(&path)).read_to_end(&mut contents));
+        assert_eq!(&contents[..3], b"foo");
+        assert!(contents[3..].iter().all(|&b| b == 0));
+
+        let read_only = check!(File::open(&path));
+        assert!(read_only.allocate(2 << 20).is_err());
+    }
+
+    #[test]
+    #[cfg(any(windows,
+              target_os = "linux",
+              target_os = "android",
+              target_os = "macos",
+              target_os = "ios",
+              target_os = "freebsd",
+              target_os = "dragonfly",
+              target_os = "netbsd",
+              target_os = "openbsd"))]
+    fn file_lock() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("lock");
+        let a = check!(File::create(&path));
+        let b = check!(File::open(&path));
+
+        check!(a.lock_exclusive());
+        assert!(!check!(b.try_lock()));
+        assert!(!check!(b.try_lock_shared()));
+        check!(a.unlock());
+
+        check!(a.lock_shared());
+        assert!(check!(b.try_lock_shared()));
+        check!(b.unlock());
+        assert!(!check!(b.try_lock()));
+        check!(a.unlock());
+
+        // A lock is released when the file is closed, although Windows may
+        // take a while to do so.
+        assert!(check!(b.try_lock()));
+        if cfg!(windows) {
+            check!(b.unlock());
+        }
+        drop(b);
+        assert!(check!(a.try_lock()));
+        check!(a.unlock());
+    }
+
     #[test]
     fn symlinks_work() {
         let tmpdir = tmpdir();
@@ -3328,6 +4751,153 @@ mod tests {
         assert!(d.exists());
     }
 
+    #[test]
+    fn read_dir_buffer_size() {
+        let tmpdir = tmpdir();
+        let mut names = Vec::new();
+        for i in 0..500 {
+            // Long names, so that few fit in a small buffer.
+            let name = format!("{:0>100}", i);
+            check!(File::create(tmpdir.join(&name)));
+            names.push(name);
+        }
+        names.sort();
+
+        for &size in &[0, 1, 300, 4096, 1 << 20] {
+            let mut found = check!(fs::read_dir(tmpdir.path()))
+                .with_buffer_size(size)
+                .map(|entry| {
+                    let entry = check!(entry);
+                    assert_eq!(entry.path(), tmpdir.join(entry.file_name()));
+                    assert!(check!(entry.file_type()).is_file());
+                    entry.file_name().into_string().unwrap()
+                })
+                .collect::<Vec<_>>();
+            found.sort();
+            assert!(found == names, "buffer size {}", size);
+        }
+    }
+
+    #[test]
+    #[cfg(any(target_os = "linux",
+              target_os = "android",
+              target_os = "macos",
+              target_os = "ios",
+              target_os = "freebsd"))]
+    fn dir_at() {
+        use fs::Dir;
+
+        let tmpdir = tmpdir();
+        let sub = tmpdir.join("sub");
+        check!(fs::create_dir(&sub));
+        let dir = check!(Dir::open(&sub));
+
+        let mut file = check!(dir.open_at("a", OpenOptions::new().write(true).create_new(true)));
+        check!(file.write_all(b"hello"));
+        drop(file);
+        assert!(dir.open_at("a", OpenOptions::new().write(true).create_new(true)).is_err());
+        assert_eq!(check!(dir.metadata_at("a")).len(), 5);
+        assert!(check!(dir.symlink_metadata_at("a")).is_file());
+
+        // Moving the directory doesn't change what the handle refers to.
+        let moved = tmpdir.join("moved");
+        check!(fs::rename(&sub, &moved));
+        check!(fs::create_dir(&sub));
+        let mut contents = String::new();
+        check!(check!(dir.open_at("a", OpenOptions::new().read(true)))
+               .read_to_string(&mut contents));
+        assert_eq!(contents, "hello");
+
+        check!(fs::create_dir(moved.join("inner")));
+        let inner = check!(dir.open_dir_at("inner"));
+        check!(dir.rename_at("a", &inner, "b"));
+        assert!(dir.metadata_at("a").is_err());
+        assert!(moved.join("inner/b").is_file());
+
+        let names = check!(dir.read_dir_at("."))
+            .map(|entry| check!(entry).file_name())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["inner"]);
+        let names = check!(inner.read_dir_at("."))
+            .map(|entry| check!(entry).file_name())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["b"]);
+
+        check!(inner.remove_file_at("b"));
+        assert!(!moved.join("inner/b").exists());
+        assert!(inner.remove_file_at("b").is_err());
+    }
+
+    #[test]
+    fn try_exists() {
+        let tmpdir = tmpdir();
+        let file = tmpdir.join("file");
+        check!(fs::write(&file, b""));
+
+        assert_eq!(check!(fs::try_exists(&file)), true);
+        assert_eq!(check!(fs::try_exists(tmpdir.path())), true);
+        assert_eq!(check!(fs::try_exists(tmpdir.join("missing"))), false);
+        // Not `false`, as no file could exist there.
+        if cfg!(unix) {
+            assert!(fs::try_exists(file.join("child")).is_err());
+        }
+
+        if got_symlink_permission(&tmpdir) {
+            let link = tmpdir.join("link");
+            check!(symlink_file(tmpdir.join("missing"), &link));
+            assert_eq!(check!(fs::try_exists(&link)), false);
+        }
+    }
+
+    #[test]
+    fn sync_dir() {
+        let tmpdir = tmpdir();
+        check!(fs::write(tmpdir.join("file"), b"contents"));
+        check!(fs::sync_dir(tmpdir.path()));
+        assert!(fs::sync_dir(tmpdir.join("file")).is_err());
+        assert!(fs::sync_dir(tmpdir.join("missing")).is_err());
+    }
+
+    #[test]
+    fn write_atomic() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("atomic");
+
+        check!(fs::write_atomic(&path, b"first"));
+        assert_eq!(check!(fs::read(&path)), b"first");
+        check!(fs::write_atomic(&path, "second"));
+        assert_eq!(check!(fs::read(&path)), b"second");
+
+        // Only the file itself is left in the directory.
+        let names = check!(fs::read_dir(tmpdir.path()))
+            .map(|entry| check!(entry).file_name())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["atomic"]);
+
+        let err = fs::write_atomic(tmpdir.join(".."), b"").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert!(fs::write_atomic(tmpdir.join("missing/atomic"), b"").is_err());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn write_atomic_permissions() {
+        use fs::AtomicWriteOptions;
+        use os::unix::fs::PermissionsExt;
+
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("atomic");
+        check!(fs::write(&path, b"old"));
+        check!(fs::set_permissions(&path, fs::Permissions::from_mode(0o640)));
+
+        check!(fs::write_atomic(&path, b"new"));
+        assert_eq!(check!(fs::metadata(&path)).permissions().mode() & 0o777, 0o640);
+
+        check!(AtomicWriteOptions::new().preserve_permissions(false).write(&path, b"newer"));
+        assert_eq!(check!(fs::read(&path)), b"newer");
+        assert!(check!(fs::metadata(&path)).permissions().mode() & 0o777 != 0o640);
+    }
+
     #[test]
     fn metadata_access_times() {
         let tmpdir = tmpdir();
@@ -3347,4 +4917,28 @@ mod tests {
             check!(b.created());
         }
     }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn metadata_created_linux() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("a");
+        let file = check!(File::create(&path));
+
+        let by_path = check!(fs::metadata(&path));
+        let by_file = check!(file.metadata());
+        let entry = check!(check!(fs::read_dir(tmpdir.path())).next().unwrap());
+        let by_entry = check!(entry.metadata());
+
+        // The kernel or the filesystem may not have it, but if it does, all
+        // of the ways of getting the metadata should find it.
+        match by_path.created() {
+            Ok(created) => {
+                assert_eq!(check!(by_file.created()), created);
+                assert_eq!(check!(by_entry.created()), created);
+                assert!(created <= check!(by_path.modified()));
+            }
+            Err(e) => assert_eq!(e.kind(), ErrorKind::Other),
+        }
+    }
 }
@@ -276,6 +276,8 @@ pub use self::cursor::Cursor;
 pub use self::error::{Result, Error, ErrorKind};
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::util::{copy, sink, Sink, empty, Empty, repeat, Repeat};
+#[unstable(feature = "positioned_io", issue = "0")]
+pub use self::positioned::{ReadAt, WriteAt};
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::stdio::{stdin, stdout, stderr, Stdin, Stdout, Stderr};
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -292,6 +294,7 @@ mod cursor;
 mod error;
 mod impls;
 mod lazy;
+mod positioned;
 mod util;
 mod stdio;
 
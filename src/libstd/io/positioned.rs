@@ -0,0 +1,240 @@
+use io::{self, ErrorKind};
+
+/// The `ReadAt` trait allows for reading bytes from a source at any offset,
+/// without a cursor.
+///
+/// Unlike with [`Read`], reading takes `&self`, so several threads can read
+/// different parts of a source at once without coordinating a shared
+/// position. This is how a database reads pages of a [`File`], for example,
+/// and code written against this trait works the same way on every
+/// platform.
+///
+/// Whether reading at an offset moves the cursor used by [`Read`] and
+/// [`Seek`] on the same source depends on the implementation; see theirs.
+///
+/// [`Read`]: trait.Read.html
+/// [`Seek`]: trait.Seek.html
+/// [`File`]: ../fs/struct.File.html
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(positioned_io)]
+/// use std::fs::File;
+/// use std::io::{self, ReadAt};
+///
+/// fn main() -> io::Result<()> {
+///     let file = File::open("pages.db")?;
+///     let mut page = [0; 4096];
+///     file.read_exact_at(&mut page, 3 * 4096)?;
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "positioned_io", issue = "0")]
+pub trait ReadAt {
+    /// Reads some bytes starting at the given offset into `buf`, returning
+    /// how many were read.
+    ///
+    /// The offset is relative to the start of the source. As with
+    /// [`Read::read`], fewer bytes than `buf` can hold may be read, and `0`
+    /// means that `offset` is at or past the end.
+    ///
+    /// [`Read::read`]: trait.Read.html#tymethod.read
+    #[unstable(feature = "positioned_io", issue = "0")]
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize>;
+
+    /// Like [`read_at`], except that it reads into a slice of buffers,
+    /// filling each before moving on to the next, and returns the total
+    /// number of bytes read.
+    ///
+    /// The default implementation reads into the first non-empty buffer
+    /// with [`read_at`].
+    ///
+    /// [`read_at`]: #tymethod.read_at
+    #[unstable(feature = "positioned_io", issue = "0")]
+    fn read_vectored_at(&self, bufs: &mut [&mut [u8]], offset: u64) -> io::Result<usize> {
+        match bufs.iter_mut().find(|buf| !buf.is_empty()) {
+            Some(buf) => self.read_at(buf, offset),
+            None => Ok(0),
+        }
+    }
+
+    /// Reads exactly enough bytes starting at the given offset to fill
+    /// `buf`.
+    ///
+    /// This is to [`read_at`] as [`Read::read_exact`] is to `read`: errors
+    /// of the kind [`ErrorKind::Interrupted`] are ignored, and if the end is
+    /// reached before `buf` is full, an error of the kind
+    /// [`ErrorKind::UnexpectedEof`] is returned. The contents of `buf` are
+    /// unspecified if an error is returned.
+    ///
+    /// [`read_at`]: #tymethod.read_at
+    /// [`Read::read_exact`]: trait.Read.html#method.read_exact
+    /// [`ErrorKind::Interrupted`]: enum.ErrorKind.html#variant.Interrupted
+    /// [`ErrorKind::UnexpectedEof`]: enum.ErrorKind.html#variant.UnexpectedEof
+    #[unstable(feature = "positioned_io", issue = "0")]
+    fn read_exact_at(&self, mut buf: &mut [u8], mut offset: u64) -> io::Result<()> {
+        while !buf.is_empty() {
+            match self.read_at(buf, offset) {
+                Ok(0) => break,
+                Ok(n) => {
+                    let tmp = buf;
+                    buf = &mut tmp[n..];
+                    offset += n as u64;
+                }
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        if !buf.is_empty() {
+            Err(io::Error::new(ErrorKind::UnexpectedEof,
+                               "failed to fill whole buffer"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The `WriteAt` trait allows for writing bytes to a sink at any offset,
+/// without a cursor.
+///
+/// This is the counterpart of [`ReadAt`] for writing; see it for more.
+///
+/// [`ReadAt`]: trait.ReadAt.html
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(positioned_io)]
+/// use std::fs::OpenOptions;
+/// use std::io::{self, WriteAt};
+///
+/// fn main() -> io::Result<()> {
+///     let file = OpenOptions::new().write(true).open("pages.db")?;
+///     let header = [1, 0, 0, 0];
+///     let page = [0; 4092];
+///     file.write_all_at(&header, 3 * 4096)?;
+///     file.write_vectored_at(&[&header[..], &page[..]], 5 * 4096)?;
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "positioned_io", issue = "0")]
+pub trait WriteAt {
+    /// Writes some bytes of `buf` starting at the given offset, returning
+    /// how many were written.
+    ///
+    /// The offset is relative to the start of the sink, and writing past
+    /// the end extends it. As with [`Write::write`], fewer bytes than are
+    /// in `buf` may be written.
+    ///
+    /// [`Write::write`]: trait.Write.html#tymethod.write
+    #[unstable(feature = "positioned_io", issue = "0")]
+    fn write_at(&self, buf: &[u8], offset: u64) -> io::Result<usize>;
+
+    /// Like [`write_at`], except that it writes from a slice of buffers, one
+    /// after another, and returns the total number of bytes written.
+    ///
+    /// The default implementation writes the first non-empty buffer with
+    /// [`write_at`].
+    ///
+    /// [`write_at`]: #tymethod.write_at
+    #[unstable(feature = "positioned_io", issue = "0")]
+    fn write_vectored_at(&self, bufs: &[&[u8]], offset: u64) -> io::Result<usize> {
+        match bufs.iter().find(|buf| !buf.is_empty()) {
+            Some(buf) => self.write_at(buf, offset),
+            None => Ok(0),
+        }
+    }
+
+    /// Writes all of `buf` starting at the given offset.
+    ///
+    /// This is to [`write_at`] as [`Write::write_all`] is to `write`: errors
+    /// of the kind [`ErrorKind::Interrupted`] are ignored, and if nothing
+    /// can be written, an error of the kind [`ErrorKind::WriteZero`] is
+    /// returned.
+    ///
+    /// [`write_at`]: #tymethod.write_at
+    /// [`Write::write_all`]: trait.Write.html#method.write_all
+    /// [`ErrorKind::Interrupted`]: enum.ErrorKind.html#variant.Interrupted
+    /// [`ErrorKind::WriteZero`]: enum.ErrorKind.html#variant.WriteZero
+    #[unstable(feature = "positioned_io", issue = "0")]
+    fn write_all_at(&self, mut buf: &[u8], mut offset: u64) -> io::Result<()> {
+        while !buf.is_empty() {
+            match self.write_at(buf, offset) {
+                Ok(0) => return Err(io::Error::new(ErrorKind::WriteZero,
+                                                   "failed to write whole buffer")),
+                Ok(n) => {
+                    buf = &buf[n..];
+                    offset += n as u64
+                }
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cell::RefCell;
+    use cmp;
+    use io::{self, ErrorKind, ReadAt, WriteAt};
+
+    // Reads and writes at most two bytes at a time, to exercise the loops.
+    struct Dribble(RefCell<Vec<u8>>);
+
+    impl ReadAt for Dribble {
+        fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+            let data = self.0.borrow();
+            let start = cmp::min(offset as usize, data.len());
+            let n = cmp::min(cmp::min(buf.len(), 2), data.len() - start);
+            buf[..n].copy_from_slice(&data[start..start + n]);
+            Ok(n)
+        }
+    }
+
+    impl WriteAt for Dribble {
+        fn write_at(&self, buf: &[u8], offset: u64) -> io::Result<usize> {
+            let mut data = self.0.borrow_mut();
+            let n = cmp::min(buf.len(), 2);
+            let end = offset as usize + n;
+            if data.len() < end {
+                data.resize(end, 0);
+            }
+            data[offset as usize..end].copy_from_slice(&buf[..n]);
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn read_exact_at() {
+        let d = Dribble(RefCell::new(b"hello world".to_vec()));
+        let mut buf = [0; 5];
+        d.read_exact_at(&mut buf, 6).unwrap();
+        assert_eq!(&buf, b"world");
+        let err = d.read_exact_at(&mut buf, 8).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        d.read_exact_at(&mut [], 100).unwrap();
+    }
+
+    #[test]
+    fn write_all_at() {
+        let d = Dribble(RefCell::new(Vec::new()));
+        d.write_all_at(b"world", 6).unwrap();
+        d.write_all_at(b"hello ", 0).unwrap();
+        assert_eq!(*d.0.borrow(), b"hello world");
+    }
+
+    #[test]
+    fn vectored_defaults() {
+        let d = Dribble(RefCell::new(Vec::new()));
+        assert_eq!(d.write_vectored_at(&[&b""[..], &b"abc"[..], &b"def"[..]], 0).unwrap(), 2);
+        assert_eq!(d.write_vectored_at(&[&b""[..], &b""[..]], 0).unwrap(), 0);
+        assert_eq!(*d.0.borrow(), b"ab");
+
+        let (mut a, mut b) = ([0; 0], [0; 4]);
+        assert_eq!(d.read_vectored_at(&mut [&mut a[..], &mut b[..]], 0).unwrap(), 2);
+        assert_eq!(&b[..2], b"ab");
+    }
+}
@@ -292,6 +292,8 @@
 #![feature(doc_alias)]
 #![feature(doc_keyword)]
 #![feature(panic_info_message)]
+#![feature(panic_info_payload_str)]
+#![feature(panic_info_thread)]
 #![feature(non_exhaustive)]
 #![feature(alloc_layout_extra)]
 #![feature(maybe_uninit)]
@@ -441,6 +443,7 @@ pub mod f64;
 #[macro_use]
 pub mod thread;
 pub mod ascii;
+pub mod backtrace;
 pub mod collections;
 pub mod env;
 pub mod error;
@@ -459,11 +462,7 @@ pub mod time;
 #[unstable(feature = "futures_api",
            reason = "futures in libcore are unstable",
            issue = "50547")]
-pub mod task {
-    //! Types and Traits for working with asynchronous tasks.
-    #[doc(inline)]
-    pub use core::task::*;
-}
+pub mod task;
 
 #[unstable(feature = "futures_api",
            reason = "futures in libcore are unstable",
@@ -351,6 +351,71 @@ macro_rules! await {
     } }
 }
 
+/// Registers a function to run before `main`.
+///
+/// The function is called by the runtime after it has been set up, so
+/// `std::env::args` and the rest of the standard library are available, but
+/// before `main` starts. This suits setup that libraries would otherwise do
+/// lazily or through platform specific constructor sections, such as
+/// configuring logging from the environment.
+///
+/// The function must be named by an identifier and have the signature
+/// `fn()`. An optional priority orders the hooks of a program: lower
+/// priorities run first, and the default priority is 0. Hooks with the same
+/// priority run in an unspecified order.
+///
+/// A panic in a hook is reported like a panic in `main`: the process exits
+/// with the same status, without running any later hooks or `main` itself.
+///
+/// Hooks only run in executables whose `main` is a Rust `main` function.
+/// Using this macro on platforms where the registrations can't be collected
+/// is a compile error.
+///
+/// A hook is only collected if the linker includes the object file it's in.
+/// Linkers only take the object files out of a library which provide a
+/// symbol the program uses, so a hook in a library module that nothing else
+/// refers to may be dropped, and never run.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(rt_init_hooks)]
+/// # #[cfg(any(target_os = "linux", target_os = "macos", windows))]
+/// # mod hook {
+/// use std::sync::atomic::{AtomicBool, Ordering};
+///
+/// static READY: AtomicBool = AtomicBool::new(false);
+///
+/// fn setup() {
+///     READY.store(true, Ordering::SeqCst);
+/// }
+///
+/// std::register_init!(setup, -10);
+/// # }
+/// ```
+#[macro_export]
+#[unstable(feature = "rt_init_hooks", issue = "0")]
+#[cfg_attr(stage0, allow_internal_unstable)]
+#[cfg_attr(not(stage0), allow_internal_unstable(rt, rt_init_hooks))]
+macro_rules! register_init {
+    ($f:ident) => {
+        $crate::register_init!($f, 0);
+    };
+    ($f:ident, $priority:expr) => {
+        // A module shares the function's name without clashing with it, since
+        // the two live in different namespaces.
+        #[allow(non_snake_case)]
+        mod $f {
+            $crate::__rust_init_hook! {
+                static HOOK: $crate::rt::InitHook = $crate::rt::InitHook {
+                    f: Some(super::$f),
+                    priority: $priority,
+                };
+            }
+        }
+    };
+}
+
 /// A macro to select an event from a number of receivers.
 ///
 /// This macro is used to wait for the first event to occur on a number of
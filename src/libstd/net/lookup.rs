@@ -0,0 +1,97 @@
+//! Reverse lookups of socket addresses into host and service names.
+
+use io;
+use net::{IpAddr, SocketAddr};
+use sys_common::net as net_imp;
+
+/// A flag changing how [`lookup_name_info`] looks up a socket address.
+///
+/// These correspond to the `NI_*` flags of `getnameinfo`.
+///
+/// [`lookup_name_info`]: fn.lookup_name_info.html
+#[unstable(feature = "net_reverse_lookup", issue = "0")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NameInfoFlag {
+    /// Returns only the first label of the host name if the host is in the
+    /// local domain.
+    NoFqdn,
+    /// Returns the numeric form of the address without looking it up.
+    NumericHost,
+    /// Returns the port number in decimal instead of looking up its service
+    /// name.
+    NumericService,
+    /// Fails if the host has no name, instead of returning the numeric form
+    /// of the address.
+    NameRequired,
+    /// Looks up the service used over datagrams (UDP) on the port, for the
+    /// few ports where it differs from the one used over streams (TCP).
+    Datagram,
+}
+
+/// Looks up the name of the host at the given address, as with a `PTR`
+/// query.
+///
+/// This fails if the host has no name, rather than returning the address.
+/// See [`lookup_name_info`] for the details of how the lookup is made.
+///
+/// [`lookup_name_info`]: fn.lookup_name_info.html
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(net_reverse_lookup)]
+/// use std::net::{self, Ipv4Addr};
+///
+/// let name = net::lookup_addr(Ipv4Addr::LOCALHOST.into()).expect("no name for localhost");
+/// println!("127.0.0.1 is {}", name);
+/// ```
+#[unstable(feature = "net_reverse_lookup", issue = "0")]
+pub fn lookup_addr(addr: IpAddr) -> io::Result<String> {
+    let (host, _) = lookup_name_info(SocketAddr::new(addr, 0), &[NameInfoFlag::NameRequired])?;
+    Ok(host)
+}
+
+/// Looks up the name of the service commonly found on the port of the given
+/// address, such as `"http"` for port 80.
+///
+/// The host part of the address isn't looked up. Ports without a known
+/// service come back as their number in decimal.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(net_reverse_lookup)]
+/// use std::net::{self, SocketAddr};
+///
+/// let addr: SocketAddr = "127.0.0.1:443".parse().unwrap();
+/// println!("{}", net::lookup_service(addr).unwrap());
+/// ```
+#[unstable(feature = "net_reverse_lookup", issue = "0")]
+pub fn lookup_service(addr: SocketAddr) -> io::Result<String> {
+    let (_, service) = lookup_name_info(addr, &[NameInfoFlag::NumericHost])?;
+    Ok(service)
+}
+
+/// Looks up the host and service names of a socket address using the
+/// system resolver, which is `getnameinfo` on Unix and Windows.
+///
+/// Unless `flags` says otherwise, a host without a name comes back as the
+/// numeric form of its address, and a port without a known service comes
+/// back as its number.
+///
+/// Names are returned as the resolver gives them. In particular, host names
+/// using international characters come back in their ASCII (punycode) form,
+/// without being decoded. This function is safe to call from several threads
+/// at once. It blocks until the resolver answers or gives up, which depends
+/// on how the system's resolver is configured.
+///
+/// # Errors
+///
+/// Fails with the error reported by the resolver, and with
+/// `ErrorKind::InvalidData` if a name isn't valid UTF-8. Platforms without a
+/// resolver always fail.
+#[unstable(feature = "net_reverse_lookup", issue = "0")]
+pub fn lookup_name_info(addr: SocketAddr, flags: &[NameInfoFlag])
+                        -> io::Result<(String, String)> {
+    net_imp::lookup_name_info(&addr, flags)
+}
@@ -40,9 +40,12 @@ pub use self::tcp::{TcpStream, TcpListener, Incoming};
 pub use self::udp::UdpSocket;
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::parser::AddrParseError;
+#[unstable(feature = "net_reverse_lookup", issue = "0")]
+pub use self::lookup::{lookup_addr, lookup_name_info, lookup_service, NameInfoFlag};
 
 mod ip;
 mod addr;
+mod lookup;
 mod tcp;
 mod udp;
 mod parser;
@@ -4,3 +4,4 @@
 
 pub mod raw;
 pub mod fs;
+pub mod properties;
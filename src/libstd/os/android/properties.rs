@@ -0,0 +1,50 @@
+//! Android system properties.
+//!
+//! System properties are global key/value pairs describing the device and
+//! its configuration, the same ones the `getprop` tool prints.
+
+#![unstable(feature = "android_system_properties", issue = "0")]
+
+use ffi::{CString, OsStr, OsString};
+use os::unix::ffi::{OsStrExt, OsStringExt};
+use sys;
+
+/// Reads the system property `name`, such as `"ro.product.model"` or a
+/// vendor property.
+///
+/// Returns `None` if the property isn't set. An empty property can't be told
+/// apart from a missing one and also reads as `None`.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(android_system_properties)]
+/// use std::os::android::properties;
+///
+/// if let Some(model) = properties::get("ro.product.model") {
+///     println!("running on a {:?}", model);
+/// }
+/// ```
+pub fn get<K: AsRef<OsStr>>(name: K) -> Option<OsString> {
+    let name = CString::new(name.as_ref().as_bytes()).ok()?;
+    sys::android::system_property(&name).map(OsString::from_vec)
+}
+
+/// Returns the API level of the device, such as 28 for Android 9.
+///
+/// This is read from `ro.build.version.sdk` once and cached. It returns 0 if
+/// the level can't be determined, which only happens on broken systems.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(android_system_properties)]
+/// use std::os::android::properties;
+///
+/// if properties::api_level() >= 26 {
+///     // use something only available on Android 8 and later
+/// }
+/// ```
+pub fn api_level() -> u32 {
+    sys::android::api_level()
+}
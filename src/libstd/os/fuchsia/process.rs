@@ -0,0 +1,60 @@
+//! Fuchsia-specific extensions to primitives in the `std::process` module.
+
+#![unstable(feature = "fuchsia_process_ext", issue = "0")]
+
+use ffi::OsStr;
+use process;
+use sys_common::AsInnerMut;
+
+/// A raw Zircon handle.
+pub type RawHandle = u32;
+
+/// Fuchsia-specific extensions to the [`process::Command`] builder.
+///
+/// Children are started with `fdio_spawn_etc`, which lets the parent hand
+/// them startup handles and namespace entries directly instead of going
+/// through file descriptors.
+///
+/// [`process::Command`]: ../../../../std/process/struct.Command.html
+pub trait CommandExt {
+    /// Passes `handle` to the child as a startup handle with the given
+    /// `id` (a `PA_HND` value).
+    ///
+    /// Ownership of the handle moves to the `Command`; it is transferred to
+    /// the first child spawned from it, or closed if the `Command` is dropped
+    /// without spawning.
+    ///
+    /// # Safety
+    ///
+    /// `handle` must be a valid handle owned by the caller, and must not be
+    /// used or closed by the caller afterwards.
+    unsafe fn add_handle(&mut self, id: u32, handle: RawHandle) -> &mut process::Command;
+
+    /// Installs `handle`, a directory channel, at `prefix` in the child's
+    /// namespace.
+    ///
+    /// Once any namespace entry has been added the child no longer gets a
+    /// copy of the parent's namespace, only the entries given here.
+    /// Ownership of the handle is the same as for [`add_handle`].
+    ///
+    /// # Safety
+    ///
+    /// The same requirements as for [`add_handle`] apply.
+    ///
+    /// [`add_handle`]: #tymethod.add_handle
+    unsafe fn add_namespace_entry<P: AsRef<OsStr>>(&mut self, prefix: P, handle: RawHandle)
+                                                  -> &mut process::Command;
+}
+
+impl CommandExt for process::Command {
+    unsafe fn add_handle(&mut self, id: u32, handle: RawHandle) -> &mut process::Command {
+        self.as_inner_mut().add_handle(id, handle);
+        self
+    }
+
+    unsafe fn add_namespace_entry<P: AsRef<OsStr>>(&mut self, prefix: P, handle: RawHandle)
+                                                  -> &mut process::Command {
+        self.as_inner_mut().add_namespace_entry(prefix.as_ref(), handle);
+        self
+    }
+}
@@ -1,4 +1,6 @@
 //! iOS-specific definitions
+//!
+//! tvOS and watchOS share the iOS ABI, so these definitions apply to them too.
 
 #![stable(feature = "raw_ext", since = "1.1.0")]
 
@@ -0,0 +1,127 @@
+//! Linux-specific extensions to primitives in the `std::env` module.
+//!
+//! The kernel passes every process an auxiliary vector of `(type, value)`
+//! pairs describing its environment: the page size, the CPU's capabilities,
+//! some random bytes, and so on. The runtime finds it next to the arguments
+//! when the program starts, so it can be read here without going through
+//! `/proc/self/auxv`.
+
+#![unstable(feature = "linux_auxv", issue = "0")]
+
+use fmt;
+use slice;
+use sys;
+
+/// The type of the entry ending the auxiliary vector, which [`auxv`] doesn't
+/// yield.
+///
+/// [`auxv`]: fn.auxv.html
+pub const AT_NULL: usize = 0;
+/// The type of the entry holding the system page size.
+pub const AT_PAGESZ: usize = 6;
+/// The type of the entry holding the architecture dependent CPU capability
+/// bits.
+pub const AT_HWCAP: usize = 16;
+/// The type of the entry holding the address of 16 random bytes.
+pub const AT_RANDOM: usize = 25;
+
+/// An iterator over the entries of the auxiliary vector, yielding a
+/// `(type, value)` pair for each.
+///
+/// This struct is created by the [`auxv`] function. See its documentation for
+/// more.
+///
+/// [`auxv`]: fn.auxv.html
+#[derive(Clone)]
+pub struct Auxv {
+    inner: slice::Iter<'static, [usize; 2]>,
+}
+
+/// Returns an iterator over the auxiliary vector the process was started
+/// with.
+///
+/// The vector is empty when the runtime didn't start the program through the
+/// standard `main`, or couldn't find it.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(linux_auxv)]
+/// use std::os::linux::env;
+///
+/// for (key, value) in env::auxv() {
+///     println!("{}: {:#x}", key, value);
+/// }
+/// ```
+pub fn auxv() -> Auxv {
+    Auxv { inner: sys::args::auxv().iter() }
+}
+
+/// Returns the value of the first auxiliary vector entry of type `key`, if
+/// there is one.
+pub fn getauxval(key: usize) -> Option<usize> {
+    auxv().find(|&(k, _)| k == key).map(|(_, value)| value)
+}
+
+/// Returns the CPU capability bits, the `AT_HWCAP` entry.
+pub fn hwcap() -> Option<usize> {
+    getauxval(AT_HWCAP)
+}
+
+/// Returns the system page size, the `AT_PAGESZ` entry.
+pub fn page_size() -> Option<usize> {
+    getauxval(AT_PAGESZ)
+}
+
+/// Returns the 16 random bytes the kernel provides each process, the
+/// `AT_RANDOM` entry.
+///
+/// The C library may already have used them, for instance to seed its stack
+/// protector, so they aren't a secret and shouldn't be used as key material.
+pub fn random_bytes() -> Option<&'static [u8; 16]> {
+    match getauxval(AT_RANDOM) {
+        Some(addr) if addr != 0 => Some(unsafe { &*(addr as *const [u8; 16]) }),
+        _ => None,
+    }
+}
+
+impl Iterator for Auxv {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<(usize, usize)> {
+        self.inner.next().map(|entry| (entry[0], entry[1]))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Auxv {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl fmt::Debug for Auxv {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn getauxval_matches_auxv() {
+        for (key, _) in auxv() {
+            assert!(key != AT_NULL);
+            assert!(getauxval(key).is_some());
+        }
+        assert_eq!(auxv().count(), auxv().len());
+        if let Some(size) = page_size() {
+            assert!(size.is_power_of_two());
+        }
+    }
+}
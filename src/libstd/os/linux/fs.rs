@@ -2,8 +2,11 @@
 
 use libc;
 
-use fs::Metadata;
-use sys_common::AsInner;
+use fs::{File, Metadata, OpenOptions};
+use io;
+use path::Path;
+use sys::fs as fs_imp;
+use sys_common::{AsInner, FromInner};
 
 #[allow(deprecated)]
 use os::linux::raw;
@@ -323,8 +326,92 @@ pub trait MetadataExt {
     /// ```
     #[stable(feature = "metadata_ext2", since = "1.8.0")]
     fn st_blocks(&self) -> u64;
+    /// Returns the id of the mount the file is on, as in the first field of
+    /// `/proc/self/mountinfo`.
+    ///
+    /// This comes from `statx`, so it's `None` before Linux 5.8.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(linux_statx)]
+    /// use std::fs;
+    /// use std::io;
+    /// use std::os::linux::fs::MetadataExt;
+    ///
+    /// fn main() -> io::Result<()> {
+    ///     let meta = fs::metadata("some_file")?;
+    ///     if let Some(id) = meta.st_mnt_id() {
+    ///         println!("{}", id);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "linux_statx", issue = "0")]
+    fn st_mnt_id(&self) -> Option<u64> { None }
+    /// Returns the file's `STATX_ATTR_*` flags, such as
+    /// [`STATX_ATTR_IMMUTABLE`].
+    ///
+    /// Only the flags in [`st_attributes_mask`] are meaningful; the others
+    /// are always clear. This comes from `statx`, so it's `None` before
+    /// Linux 4.11.
+    ///
+    /// [`STATX_ATTR_IMMUTABLE`]: constant.STATX_ATTR_IMMUTABLE.html
+    /// [`st_attributes_mask`]: #method.st_attributes_mask
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(linux_statx)]
+    /// use std::fs;
+    /// use std::io;
+    /// use std::os::linux::fs::{MetadataExt, STATX_ATTR_IMMUTABLE};
+    ///
+    /// fn main() -> io::Result<()> {
+    ///     let meta = fs::metadata("some_file")?;
+    ///     if meta.st_attributes().unwrap_or(0) & STATX_ATTR_IMMUTABLE != 0 {
+    ///         println!("some_file is immutable");
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "linux_statx", issue = "0")]
+    fn st_attributes(&self) -> Option<u64> { None }
+    /// Returns which of the `STATX_ATTR_*` flags the filesystem supports.
+    ///
+    /// This comes from `statx`, so it's `None` before Linux 4.11.
+    #[unstable(feature = "linux_statx", issue = "0")]
+    fn st_attributes_mask(&self) -> Option<u64> { None }
 }
 
+/// The file is compressed by the filesystem.
+#[unstable(feature = "linux_statx", issue = "0")]
+pub const STATX_ATTR_COMPRESSED: u64 = 0x0000_0004;
+/// The file can't be changed, deleted, renamed or linked to.
+#[unstable(feature = "linux_statx", issue = "0")]
+pub const STATX_ATTR_IMMUTABLE: u64 = 0x0000_0010;
+/// The file can only be opened for appending.
+#[unstable(feature = "linux_statx", issue = "0")]
+pub const STATX_ATTR_APPEND: u64 = 0x0000_0020;
+/// The file isn't a candidate for backup by `dump`.
+#[unstable(feature = "linux_statx", issue = "0")]
+pub const STATX_ATTR_NODUMP: u64 = 0x0000_0040;
+/// The file needs a key to be decrypted.
+#[unstable(feature = "linux_statx", issue = "0")]
+pub const STATX_ATTR_ENCRYPTED: u64 = 0x0000_0800;
+/// The directory is an automount trigger.
+#[unstable(feature = "linux_statx", issue = "0")]
+pub const STATX_ATTR_AUTOMOUNT: u64 = 0x0000_1000;
+/// The file is the root of a mount.
+#[unstable(feature = "linux_statx", issue = "0")]
+pub const STATX_ATTR_MOUNT_ROOT: u64 = 0x0000_2000;
+/// The file has fs-verity enabled.
+#[unstable(feature = "linux_statx", issue = "0")]
+pub const STATX_ATTR_VERITY: u64 = 0x0010_0000;
+/// The file is accessed with DAX, bypassing the page cache.
+#[unstable(feature = "linux_statx", issue = "0")]
+pub const STATX_ATTR_DAX: u64 = 0x0020_0000;
+
 #[stable(feature = "metadata_ext", since = "1.1.0")]
 impl MetadataExt for Metadata {
     #[allow(deprecated)]
@@ -382,4 +469,91 @@ impl MetadataExt for Metadata {
     fn st_blocks(&self) -> u64 {
         self.as_inner().as_inner().st_blocks as u64
     }
+    fn st_mnt_id(&self) -> Option<u64> {
+        self.as_inner().mount_id()
+    }
+    fn st_attributes(&self) -> Option<u64> {
+        self.as_inner().attributes()
+    }
+    fn st_attributes_mask(&self) -> Option<u64> {
+        self.as_inner().attributes_mask()
+    }
+}
+
+/// Linux-specific extensions to [`fs::OpenOptions`].
+///
+/// [`fs::OpenOptions`]: ../../../../std/fs/struct.OpenOptions.html
+#[unstable(feature = "linux_tmpfile", issue = "0")]
+pub trait OpenOptionsExt {
+    /// Opens a new file with no name, in the filesystem which holds the
+    /// directory `dir`, using `O_TMPFILE`.
+    ///
+    /// The file is deleted when it's closed, unless it's been given a name
+    /// with [`FileExt::link_at`] first. Writing it and then linking it into
+    /// place means that other processes never see it half-written, and
+    /// there's no temporary name which could collide with another.
+    ///
+    /// The options must allow writing. The options for creating and
+    /// truncating files are ignored, as the file is always new, but the
+    /// mode and custom flags from [`os::unix::fs::OpenOptionsExt`] apply.
+    ///
+    /// This needs Linux 3.11 or later, and a filesystem which supports it.
+    /// Otherwise it fails, usually with `EOPNOTSUPP` or `EISDIR`.
+    ///
+    /// [`FileExt::link_at`]: trait.FileExt.html#tymethod.link_at
+    /// [`os::unix::fs::OpenOptionsExt`]: ../../unix/fs/trait.OpenOptionsExt.html
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(linux_tmpfile)]
+    /// use std::fs::OpenOptions;
+    /// use std::io::{self, Write};
+    /// use std::os::linux::fs::{FileExt, OpenOptionsExt};
+    ///
+    /// fn main() -> io::Result<()> {
+    ///     let mut file = OpenOptions::new().write(true).tmpfile("/var/lib/app")?;
+    ///     file.write_all(b"new state")?;
+    ///     file.sync_all()?;
+    ///     file.link_at("/var/lib/app/state")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "linux_tmpfile", issue = "0")]
+    fn tmpfile<P: AsRef<Path>>(&self, dir: P) -> io::Result<File>;
+}
+
+#[unstable(feature = "linux_tmpfile", issue = "0")]
+impl OpenOptionsExt for OpenOptions {
+    fn tmpfile<P: AsRef<Path>>(&self, dir: P) -> io::Result<File> {
+        fs_imp::File::open_tmpfile(dir.as_ref(), self.as_inner()).map(File::from_inner)
+    }
+}
+
+/// Linux-specific extensions to [`fs::File`].
+///
+/// [`fs::File`]: ../../../../std/fs/struct.File.html
+#[unstable(feature = "linux_tmpfile", issue = "0")]
+pub trait FileExt {
+    /// Gives the file a name at `path`, which mustn't exist yet, using
+    /// `linkat`.
+    ///
+    /// This is how a file opened with [`OpenOptionsExt::tmpfile`] is put in
+    /// place, but it works on any open file which can be linked to. It must
+    /// be on the same filesystem as `path`.
+    ///
+    /// Linking through the file descriptor itself needs the
+    /// `CAP_DAC_READ_SEARCH` capability. Without it, this links through
+    /// `/proc/self/fd` instead, which needs `/proc` to be mounted.
+    ///
+    /// [`OpenOptionsExt::tmpfile`]: trait.OpenOptionsExt.html#tymethod.tmpfile
+    #[unstable(feature = "linux_tmpfile", issue = "0")]
+    fn link_at<P: AsRef<Path>>(&self, path: P) -> io::Result<()>;
+}
+
+#[unstable(feature = "linux_tmpfile", issue = "0")]
+impl FileExt for File {
+    fn link_at<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.as_inner().link_at(path.as_ref())
+    }
 }
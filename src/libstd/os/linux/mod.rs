@@ -4,3 +4,5 @@
 
 pub mod raw;
 pub mod fs;
+pub mod env;
+pub mod process;
@@ -0,0 +1,205 @@
+//! Linux-specific extensions to primitives in the `std::process` module.
+//!
+//! A pidfd is a file descriptor referring to a process. Unlike its pid, which
+//! the kernel may give to another process once the first one has been
+//! reaped, a pidfd goes on referring to the same process for as long as it's
+//! open, so signals sent through it can't reach the wrong process. It becomes
+//! readable when the process exits, and can be passed to other processes
+//! like any other file descriptor. pidfds need Linux 5.3 or later.
+
+#![unstable(feature = "linux_pidfd", issue = "0")]
+
+use fmt;
+use io;
+use libc;
+use os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use path::Path;
+use process;
+use sys::fd::FileDesc;
+use sys::process::pidfd;
+use sys_common::{AsInner, AsInnerMut};
+
+/// A file descriptor referring to a process.
+///
+/// The descriptor is closed when this is dropped. See the [module-level
+/// documentation](index.html) for more.
+#[repr(transparent)]
+pub struct PidFd {
+    inner: FileDesc,
+}
+
+impl PidFd {
+    /// Sends `signal` to the process.
+    ///
+    /// This fails with `ESRCH` once the process has exited, rather than
+    /// signalling whichever process has its pid by then.
+    pub fn send_signal(&self, signal: i32) -> io::Result<()> {
+        pidfd::send_signal(&self.inner, signal)
+    }
+
+    /// Sends `SIGKILL` to the process.
+    pub fn kill(&self) -> io::Result<()> {
+        self.send_signal(libc::SIGKILL)
+    }
+
+    fn from_ref(inner: &FileDesc) -> &PidFd {
+        // `PidFd` is a transparent wrapper around `FileDesc`.
+        unsafe { &*(inner as *const FileDesc as *const PidFd) }
+    }
+}
+
+impl AsRawFd for PidFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.raw()
+    }
+}
+
+impl FromRawFd for PidFd {
+    unsafe fn from_raw_fd(fd: RawFd) -> PidFd {
+        PidFd { inner: FileDesc::new(fd) }
+    }
+}
+
+impl IntoRawFd for PidFd {
+    fn into_raw_fd(self) -> RawFd {
+        self.inner.into_raw()
+    }
+}
+
+impl fmt::Debug for PidFd {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PidFd").field("fd", &self.inner.raw()).finish()
+    }
+}
+
+/// Linux-specific extensions to the [`process::Command`] builder.
+///
+/// [`process::Command`]: ../../../../std/process/struct.Command.html
+pub trait CommandExt {
+    /// Sets whether a pidfd is opened for the child when it's spawned.
+    ///
+    /// The child is created with `clone3`, which creates the pidfd along
+    /// with it, so the pidfd refers to the child even if it exits straight
+    /// away. Spawning fails if the pidfd can't be created, as on kernels
+    /// before 5.3. Defaults to `false`.
+    ///
+    /// As `clone3` bypasses the C library's `fork` handling, a command with
+    /// a pidfd can't have [`before_exec`] closures; spawning it fails with
+    /// [`InvalidInput`].
+    ///
+    /// [`before_exec`]: ../../unix/process/trait.CommandExt.html#tymethod.before_exec
+    /// [`InvalidInput`]: ../../../../std/io/enum.ErrorKind.html#variant.InvalidInput
+    fn create_pidfd(&mut self, val: bool) -> &mut process::Command;
+
+    /// Starts the child in new namespaces, one for each of the `CLONE_NEW*`
+    /// flags in `flags`, as `unshare` does.
+    ///
+    /// The child is created with `clone3`, so with `CLONE_NEWPID` the child
+    /// itself is the first process in the new pid namespace, which needs
+    /// Linux 5.3 or later. Spawning fails with [`InvalidInput`] if `flags`
+    /// holds anything other than namespace flags, or if the command has
+    /// [`before_exec`] closures, for the same reason as with [`create_pidfd`].
+    /// Most namespaces can only be created by root, unless `CLONE_NEWUSER`
+    /// is given too.
+    ///
+    /// [`InvalidInput`]: ../../../../std/io/enum.ErrorKind.html#variant.InvalidInput
+    /// [`before_exec`]: ../../unix/process/trait.CommandExt.html#tymethod.before_exec
+    /// [`create_pidfd`]: #tymethod.create_pidfd
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(linux_namespaces, rustc_private)]
+    /// extern crate libc;
+    ///
+    /// use std::os::linux::process::CommandExt;
+    /// use std::process::Command;
+    ///
+    /// // Run `hostname` in its own user and UTS namespaces, where it may
+    /// // change the host name without affecting anything else.
+    /// let status = Command::new("hostname")
+    ///     .arg("sandbox")
+    ///     .unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWUTS)
+    ///     .status()
+    ///     .unwrap();
+    /// ```
+    #[unstable(feature = "linux_namespaces", issue = "0")]
+    fn unshare(&mut self, flags: i32) -> &mut process::Command;
+
+    /// Changes the root directory of the child to `dir` before it runs the
+    /// program.
+    ///
+    /// This happens before the uid and gid are changed, and before any
+    /// [`current_dir`], which is then relative to the new root. Without one
+    /// the child starts in the new root itself. The program is looked up
+    /// within the new root.
+    ///
+    /// [`current_dir`]: ../../../../std/process/struct.Command.html#method.current_dir
+    #[unstable(feature = "linux_namespaces", issue = "0")]
+    fn chroot<P: AsRef<Path>>(&mut self, dir: P) -> &mut process::Command;
+}
+
+impl CommandExt for process::Command {
+    fn create_pidfd(&mut self, val: bool) -> &mut process::Command {
+        self.as_inner_mut().create_pidfd(val);
+        self
+    }
+
+    fn unshare(&mut self, flags: i32) -> &mut process::Command {
+        self.as_inner_mut().unshare(flags);
+        self
+    }
+
+    fn chroot<P: AsRef<Path>>(&mut self, dir: P) -> &mut process::Command {
+        self.as_inner_mut().chroot(dir.as_ref().as_ref());
+        self
+    }
+}
+
+/// Linux-specific extensions to [`process::Child`].
+///
+/// [`process::Child`]: ../../../../std/process/struct.Child.html
+pub trait ChildExt {
+    /// Returns the child's pidfd.
+    ///
+    /// This fails if the child wasn't spawned with
+    /// [`CommandExt::create_pidfd`], or if the pidfd has been taken with
+    /// [`take_pidfd`].
+    ///
+    /// [`CommandExt::create_pidfd`]: trait.CommandExt.html#tymethod.create_pidfd
+    /// [`take_pidfd`]: #tymethod.take_pidfd
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(linux_pidfd)]
+    /// use std::os::linux::process::{ChildExt, CommandExt};
+    /// use std::process::Command;
+    ///
+    /// let mut child = Command::new("sleep").arg("100").create_pidfd(true).spawn().unwrap();
+    /// child.pidfd().unwrap().kill().unwrap();
+    /// child.wait().unwrap();
+    /// ```
+    fn pidfd(&self) -> io::Result<&PidFd>;
+
+    /// Takes the child's pidfd, so that it outlives the `Child`.
+    ///
+    /// This fails in the same cases as [`pidfd`].
+    ///
+    /// [`pidfd`]: #tymethod.pidfd
+    fn take_pidfd(&mut self) -> io::Result<PidFd>;
+}
+
+fn no_pidfd() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "no pidfd was created for this child")
+}
+
+impl ChildExt for process::Child {
+    fn pidfd(&self) -> io::Result<&PidFd> {
+        self.as_inner().pidfd().map(PidFd::from_ref).ok_or_else(no_pidfd)
+    }
+
+    fn take_pidfd(&mut self) -> io::Result<PidFd> {
+        self.as_inner_mut().take_pidfd().map(|inner| PidFd { inner }).ok_or_else(no_pidfd)
+    }
+}
@@ -0,0 +1,36 @@
+//! macOS-specific locations.
+
+#![unstable(feature = "macos_user_dirs", issue = "0")]
+
+use io;
+use path::PathBuf;
+use sys;
+
+/// Returns the per-user temporary directory, `_CS_DARWIN_USER_TEMP_DIR`.
+///
+/// Unlike `/tmp` this is private to the user, and in a sandboxed app it's a
+/// location inside the container the sandbox allows writing to. It's what
+/// [`env::temp_dir`] falls back to when `TMPDIR` isn't set.
+///
+/// [`env::temp_dir`]: ../../../std/env/fn.temp_dir.html
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(macos_user_dirs)]
+/// use std::os::macos::env::user_temp_dir;
+///
+/// println!("temporary files go in {:?}", user_temp_dir().unwrap());
+/// ```
+pub fn user_temp_dir() -> io::Result<PathBuf> {
+    sys::os::darwin_user_dir(sys::os::_CS_DARWIN_USER_TEMP_DIR)
+}
+
+/// Returns the per-user cache directory, `_CS_DARWIN_USER_CACHE_DIR`.
+///
+/// Like [`user_temp_dir`], this is inside the container of a sandboxed app.
+///
+/// [`user_temp_dir`]: fn.user_temp_dir.html
+pub fn user_cache_dir() -> io::Result<PathBuf> {
+    sys::os::darwin_user_dir(sys::os::_CS_DARWIN_USER_CACHE_DIR)
+}
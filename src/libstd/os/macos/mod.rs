@@ -3,4 +3,6 @@
 #![stable(feature = "raw_ext", since = "1.1.0")]
 
 pub mod raw;
+pub mod env;
 pub mod fs;
+pub mod time;
@@ -0,0 +1,51 @@
+//! Access to the mach absolute time clock.
+
+#![unstable(feature = "macos_mach_time", issue = "0")]
+
+use libc;
+use sys;
+
+/// The ratio converting [`mach_absolute_time`] ticks to nanoseconds, as
+/// returned by the `mach_timebase_info` function.
+///
+/// [`mach_absolute_time`]: fn.mach_absolute_time.html
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MachTimebaseInfo {
+    /// The numerator of the ratio.
+    pub numer: u32,
+    /// The denominator of the ratio.
+    pub denom: u32,
+}
+
+/// Returns the current value of the clock [`Instant`] is based on, in
+/// mach ticks.
+///
+/// This is what timestamps in system traces (such as `kdebug` and signposts)
+/// are recorded in, so it allows correlating them with events in Rust code.
+///
+/// [`Instant`]: ../../../std/time/struct.Instant.html
+pub fn mach_absolute_time() -> u64 {
+    unsafe { libc::mach_absolute_time() }
+}
+
+/// Returns the timebase of [`mach_absolute_time`].
+///
+/// The result is queried once and cached, the same value [`Instant`] uses.
+///
+/// [`mach_absolute_time`]: fn.mach_absolute_time.html
+/// [`Instant`]: ../../../std/time/struct.Instant.html
+///
+/// # Examples
+///
+/// ```
+/// #![feature(macos_mach_time)]
+/// use std::os::macos::time::{mach_absolute_time, mach_timebase_info};
+///
+/// let info = mach_timebase_info();
+/// let nanos = mach_absolute_time() as u128 * info.numer as u128 / info.denom as u128;
+/// println!("up for {}ns", nanos);
+/// ```
+pub fn mach_timebase_info() -> MachTimebaseInfo {
+    let (numer, denom) = sys::time::mach_timebase();
+    MachTimebaseInfo { numer, denom }
+}
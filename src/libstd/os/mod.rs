@@ -35,6 +35,10 @@ cfg_if! {
         #[cfg(any(target_os = "linux", target_os = "l4re"))]
         pub mod linux;
 
+        #[cfg(target_os = "wasi")]
+        #[unstable(feature = "wasi_ext", issue = "0")]
+        pub use sys::ext as wasi;
+
     }
 }
 
@@ -43,7 +47,7 @@ cfg_if! {
 #[cfg(target_os = "dragonfly")]  pub mod dragonfly;
 #[cfg(target_os = "freebsd")]    pub mod freebsd;
 #[cfg(target_os = "haiku")]      pub mod haiku;
-#[cfg(target_os = "ios")]        pub mod ios;
+#[cfg(any(target_os = "ios", target_os = "tvos", target_os = "watchos"))] pub mod ios;
 #[cfg(target_os = "macos")]      pub mod macos;
 #[cfg(target_os = "netbsd")]     pub mod netbsd;
 #[cfg(target_os = "openbsd")]    pub mod openbsd;
@@ -51,6 +55,9 @@ cfg_if! {
 #[cfg(target_os = "emscripten")] pub mod emscripten;
 #[cfg(target_os = "fuchsia")]    pub mod fuchsia;
 #[cfg(target_os = "hermit")]     pub mod hermit;
+#[cfg(target_os = "vxworks")]    pub mod vxworks;
+#[cfg(target_os = "espidf")]     pub mod espidf;
+#[cfg(target_os = "nto")]        pub mod nto;
 #[cfg(all(target_vendor = "fortanix", target_env = "sgx"))] pub mod fortanix_sgx;
 
 pub mod raw;
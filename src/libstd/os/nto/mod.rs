@@ -0,0 +1,6 @@
+//! QNX Neutrino-specific definitions
+
+#![stable(feature = "raw_ext", since = "1.1.0")]
+
+pub mod raw;
+pub mod fs;
@@ -3,6 +3,7 @@
 #![stable(feature = "std_panic", since = "1.9.0")]
 
 use any::Any;
+use backtrace::Backtrace;
 use cell::UnsafeCell;
 use fmt;
 use future::Future;
@@ -11,6 +12,7 @@ use ops::{Deref, DerefMut};
 use panicking;
 use ptr::{Unique, NonNull};
 use rc::Rc;
+use result;
 use sync::{Arc, Mutex, RwLock, atomic};
 use task::{Waker, Poll};
 use thread::Result;
@@ -18,6 +20,9 @@ use thread::Result;
 #[stable(feature = "panic_hooks", since = "1.10.0")]
 pub use panicking::{take_hook, set_hook};
 
+#[unstable(feature = "panic_double_panic_policy", issue = "0")]
+pub use panicking::{set_double_panic_policy, double_panic_policy, DoublePanicPolicy};
+
 #[stable(feature = "panic_hooks", since = "1.10.0")]
 pub use core::panic::{PanicInfo, Location};
 
@@ -389,6 +394,143 @@ pub fn catch_unwind<F: FnOnce() -> R + UnwindSafe, R>(f: F) -> Result<R> {
     }
 }
 
+/// Invokes a closure like [`catch_unwind`], returning what is known about the
+/// panic if one occurs.
+///
+/// Where [`catch_unwind`] returns only the payload of the panic, this also
+/// returns its message, source location and a backtrace in a
+/// [`CaughtPanic`]. While the closure runs the default panic hook is not
+/// called for panics on this thread, so nothing is printed to standard error.
+/// A custom hook installed with [`set_hook`] still runs.
+///
+/// The backtrace is captured with [`Backtrace::capture`], so it is subject to
+/// the `RUST_BACKTRACE` and `RUST_LIB_BACKTRACE` environment variables.
+///
+/// [`catch_unwind`]: ./fn.catch_unwind.html
+/// [`CaughtPanic`]: ./struct.CaughtPanic.html
+/// [`set_hook`]: ./fn.set_hook.html
+/// [`Backtrace::capture`]: ../backtrace/struct.Backtrace.html#method.capture
+///
+/// # Notes
+///
+/// Like [`catch_unwind`], this only catches unwinding panics. If the panic
+/// strategy is to abort, the process is aborted without the default hook
+/// having printed anything.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(catch_unwind_with_info)]
+/// use std::panic;
+///
+/// let result = panic::catch_unwind_with_info(|| {
+///     panic!("oh no!");
+/// });
+/// let caught = result.unwrap_err();
+/// assert_eq!(caught.message(), Some("oh no!"));
+/// assert!(caught.location().is_some());
+/// ```
+#[unstable(feature = "catch_unwind_with_info", issue = "0")]
+pub fn catch_unwind_with_info<F, R>(f: F) -> result::Result<R, CaughtPanic>
+    where F: FnOnce() -> R + UnwindSafe
+{
+    let prev = panicking::begin_capture();
+    let result = unsafe { panicking::try(f) };
+    let captured = panicking::end_capture(prev);
+    result.map_err(|payload| CaughtPanic { payload, captured })
+}
+
+/// A panic caught by [`catch_unwind_with_info`].
+///
+/// [`catch_unwind_with_info`]: ./fn.catch_unwind_with_info.html
+#[unstable(feature = "catch_unwind_with_info", issue = "0")]
+pub struct CaughtPanic {
+    payload: Box<dyn Any + Send>,
+    // `None` if the panic didn't go through the panic hook machinery, as with
+    // `resume_unwind`.
+    captured: Option<panicking::CapturedPanic>,
+}
+
+impl CaughtPanic {
+    /// Returns the payload of the panic, as [`catch_unwind`] would have.
+    ///
+    /// [`catch_unwind`]: ./fn.catch_unwind.html
+    #[unstable(feature = "catch_unwind_with_info", issue = "0")]
+    pub fn payload(&self) -> &(dyn Any + Send) {
+        &*self.payload
+    }
+
+    /// Consumes the `CaughtPanic`, returning the payload of the panic, for
+    /// example to continue unwinding with [`resume_unwind`].
+    ///
+    /// [`resume_unwind`]: ./fn.resume_unwind.html
+    #[unstable(feature = "catch_unwind_with_info", issue = "0")]
+    pub fn into_payload(self) -> Box<dyn Any + Send> {
+        self.payload
+    }
+
+    /// Returns the message of the panic, if its payload is a string.
+    #[unstable(feature = "catch_unwind_with_info", issue = "0")]
+    pub fn message(&self) -> Option<&str> {
+        if let Some(message) = self.captured.as_ref().and_then(|c| c.message.as_ref()) {
+            Some(message)
+        } else if let Some(s) = self.payload.downcast_ref::<&'static str>() {
+            Some(s)
+        } else if let Some(s) = self.payload.downcast_ref::<String>() {
+            Some(s)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the location the panic originated from, if known.
+    ///
+    /// This is `None` for panics started with [`resume_unwind`], which don't
+    /// go through the panic hook.
+    ///
+    /// [`resume_unwind`]: ./fn.resume_unwind.html
+    #[unstable(feature = "catch_unwind_with_info", issue = "0")]
+    pub fn location(&self) -> Option<Location> {
+        self.captured.as_ref().map(|c| {
+            let (ref file, line, col) = c.location;
+            Location::internal_constructor(file, line, col)
+        })
+    }
+
+    /// Returns the backtrace captured when the panic started, if the panic went
+    /// through the panic hook.
+    #[unstable(feature = "catch_unwind_with_info", issue = "0")]
+    pub fn backtrace(&self) -> Option<&Backtrace> {
+        self.captured.as_ref().map(|c| &c.backtrace)
+    }
+}
+
+#[unstable(feature = "catch_unwind_with_info", issue = "0")]
+impl fmt::Debug for CaughtPanic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CaughtPanic")
+            .field("message", &self.message())
+            .field("location", &self.location())
+            .field("backtrace", &self.backtrace())
+            .finish()
+    }
+}
+
+#[unstable(feature = "catch_unwind_with_info", issue = "0")]
+impl fmt::Display for CaughtPanic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("panicked")?;
+        match self.message() {
+            Some(message) => write!(f, " at '{}'", message)?,
+            None => f.write_str(" at 'Box<Any>'")?,
+        }
+        if let Some(location) = self.location() {
+            write!(f, ", {}", location)?;
+        }
+        Ok(())
+    }
+}
+
 /// Triggers a panic without invoking the panic hook.
 ///
 /// This is designed to be used in conjunction with [`catch_unwind`] to, for
@@ -420,3 +562,206 @@ pub fn catch_unwind<F: FnOnce() -> R + UnwindSafe, R>(f: F) -> Result<R> {
 pub fn resume_unwind(payload: Box<dyn Any + Send>) -> ! {
     panicking::update_count_then_panic(payload)
 }
+
+/// The configuration for whether and how the default panic hook will capture
+/// and display the backtrace.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[unstable(feature = "panic_backtrace_config", issue = "0")]
+#[non_exhaustive]
+pub enum BacktraceStyle {
+    /// Prints a terser backtrace which ideally only contains relevant
+    /// information.
+    Short,
+    /// Prints a backtrace with all possible information.
+    Full,
+    /// Disable collecting and displaying backtraces.
+    Off,
+}
+
+/// Configures whether the default panic hook will capture and display a
+/// backtrace.
+///
+/// This takes precedence over the `RUST_BACKTRACE` environment variable, which
+/// is otherwise consulted the first time a panic needs to know. It has no
+/// effect if libstd was built without backtrace support.
+#[unstable(feature = "panic_backtrace_config", issue = "0")]
+pub fn set_backtrace_style(style: BacktraceStyle) {
+    #[cfg(feature = "backtrace")]
+    {
+        use sys_common::backtrace::{self, PrintFormat};
+
+        backtrace::set_log_enabled(match style {
+            BacktraceStyle::Short => Some(PrintFormat::Short),
+            BacktraceStyle::Full => Some(PrintFormat::Full),
+            BacktraceStyle::Off => None,
+        });
+    }
+    #[cfg(not(feature = "backtrace"))]
+    let _ = style;
+}
+
+/// Checks whether the default panic hook will capture and display a
+/// backtrace.
+///
+/// This returns the style set by `set_backtrace_style`, or else the one
+/// requested through `RUST_BACKTRACE`. It returns `None` if libstd was built
+/// without backtrace support.
+#[unstable(feature = "panic_backtrace_config", issue = "0")]
+pub fn get_backtrace_style() -> Option<BacktraceStyle> {
+    #[cfg(feature = "backtrace")]
+    {
+        use sys_common::backtrace::{self, PrintFormat};
+
+        Some(match backtrace::log_enabled() {
+            Some(PrintFormat::Short) => BacktraceStyle::Short,
+            Some(PrintFormat::Full) => BacktraceStyle::Full,
+            None => BacktraceStyle::Off,
+        })
+    }
+    #[cfg(not(feature = "backtrace"))]
+    None
+}
+
+/// A machine-readable rendering of a [`PanicInfo`].
+///
+/// Its `Display` implementation writes the panic as a single line JSON object
+/// with `thread`, `message`, `file`, `line` and `column` keys, so that crash
+/// reporters installed with [`set_hook`] don't have to parse the text the
+/// default hook prints. Keys whose value isn't known are `null`.
+///
+/// [`PanicInfo`]: struct.PanicInfo.html
+/// [`set_hook`]: fn.set_hook.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// #![feature(panic_structured_info)]
+/// use std::panic::{self, StructuredPanicInfo};
+///
+/// panic::set_hook(Box::new(|info| {
+///     eprintln!("{}", StructuredPanicInfo::new(info));
+/// }));
+///
+/// panic!("Normal panic");
+/// ```
+#[unstable(feature = "panic_structured_info", issue = "0")]
+#[derive(Debug)]
+pub struct StructuredPanicInfo<'a> {
+    info: &'a PanicInfo<'a>,
+}
+
+impl<'a> StructuredPanicInfo<'a> {
+    /// Wraps `info` for structured formatting.
+    #[unstable(feature = "panic_structured_info", issue = "0")]
+    pub fn new(info: &'a PanicInfo<'a>) -> StructuredPanicInfo<'a> {
+        StructuredPanicInfo { info }
+    }
+}
+
+#[unstable(feature = "panic_structured_info", issue = "0")]
+impl fmt::Display for StructuredPanicInfo<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("{\"thread\":")?;
+        json_string(f, self.info.thread_name())?;
+        f.write_str(",\"message\":")?;
+        if let Some(s) = self.info.payload_as_str() {
+            json_string(f, Some(s))?;
+        } else if let Some(message) = self.info.message() {
+            f.write_str("\"")?;
+            fmt::write(&mut JsonEscape(f), *message)?;
+            f.write_str("\"")?;
+        } else {
+            f.write_str("null")?;
+        }
+        match self.info.location() {
+            Some(location) => {
+                f.write_str(",\"file\":")?;
+                json_string(f, Some(location.file()))?;
+                write!(f, ",\"line\":{},\"column\":{}", location.line(), location.column())?;
+            }
+            None => f.write_str(",\"file\":null,\"line\":null,\"column\":null")?,
+        }
+        f.write_str("}")
+    }
+}
+
+fn json_string(f: &mut fmt::Formatter, s: Option<&str>) -> fmt::Result {
+    match s {
+        Some(s) => {
+            f.write_str("\"")?;
+            fmt::Write::write_str(&mut JsonEscape(f), s)?;
+            f.write_str("\"")
+        }
+        None => f.write_str("null"),
+    }
+}
+
+/// Escapes everything written through it for use inside a JSON string.
+struct JsonEscape<'a, 'b: 'a>(&'a mut fmt::Formatter<'b>);
+
+impl fmt::Write for JsonEscape<'_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            match c {
+                '"' => self.0.write_str("\\\"")?,
+                '\\' => self.0.write_str("\\\\")?,
+                '\n' => self.0.write_str("\\n")?,
+                '\r' => self.0.write_str("\\r")?,
+                '\t' => self.0.write_str("\\t")?,
+                c if c < ' ' => write!(self.0, "\\u{:04x}", c as u32)?,
+                c => fmt::Write::write_char(self.0, c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn structured_str_payload() {
+        let payload: &'static str = "oh \"no\"\n";
+        let mut info = PanicInfo::internal_constructor(
+            None,
+            Location::internal_constructor("src/main.rs", 2, 5),
+        );
+        info.set_payload(&payload);
+        info.set_thread_name("main");
+        assert_eq!(info.payload_as_str(), Some("oh \"no\"\n"));
+        assert_eq!(StructuredPanicInfo::new(&info).to_string(),
+                   "{\"thread\":\"main\",\"message\":\"oh \\\"no\\\"\\n\",\
+                    \"file\":\"src/main.rs\",\"line\":2,\"column\":5}");
+    }
+
+    #[test]
+    fn structured_fmt_message() {
+        fn structured(args: fmt::Arguments) -> String {
+            let info = PanicInfo::internal_constructor(
+                Some(&args),
+                Location::internal_constructor("lib.rs", 10, 1),
+            );
+            assert_eq!(info.payload_as_str(), None);
+            assert_eq!(info.thread_name(), None);
+            StructuredPanicInfo::new(&info).to_string()
+        }
+
+        assert_eq!(structured(format_args!("{}\\{}\u{1}", 1, 2)),
+                   "{\"thread\":null,\"message\":\"1\\\\2\\u0001\",\
+                    \"file\":\"lib.rs\",\"line\":10,\"column\":1}");
+    }
+
+    #[test]
+    fn structured_no_message() {
+        let payload = 3u32;
+        let mut info = PanicInfo::internal_constructor(
+            None,
+            Location::internal_constructor("lib.rs", 1, 1),
+        );
+        info.set_payload(&payload);
+        assert_eq!(StructuredPanicInfo::new(&info).to_string(),
+                   "{\"thread\":null,\"message\":null,\
+                    \"file\":\"lib.rs\",\"line\":1,\"column\":1}");
+    }
+}
@@ -12,6 +12,7 @@ use core::panic::BoxMeUp;
 use io::prelude::*;
 
 use any::Any;
+use backtrace::Backtrace;
 use cell::RefCell;
 use core::panic::{PanicInfo, Location};
 use fmt;
@@ -23,6 +24,7 @@ use sys::stdio::panic_output;
 use sys_common::rwlock::RWLock;
 use sys_common::thread_info;
 use sys_common::util;
+use sync::atomic::{AtomicUsize, Ordering};
 use thread;
 
 thread_local! {
@@ -31,6 +33,49 @@ thread_local! {
     }
 }
 
+/// What `catch_unwind_with_info` learns about a panic from the hook machinery.
+pub struct CapturedPanic {
+    pub message: Option<String>,
+    pub location: (String, u32, u32),
+    pub backtrace: Backtrace,
+}
+
+thread_local! {
+    // `Some` while a `catch_unwind_with_info` is running on this thread, with
+    // the details of the latest panic once there has been one.
+    static CAPTURE: RefCell<Option<Option<CapturedPanic>>> = RefCell::new(None);
+}
+
+/// Starts recording panics on this thread instead of printing them with the
+/// default hook, returning the state to hand back to `end_capture`.
+pub fn begin_capture() -> Option<Option<CapturedPanic>> {
+    CAPTURE.with(|c| c.replace(Some(None)))
+}
+
+/// Stops the recording started by the matching `begin_capture`, returning the
+/// latest panic recorded since.
+pub fn end_capture(prev: Option<Option<CapturedPanic>>) -> Option<CapturedPanic> {
+    CAPTURE.with(|c| c.replace(prev)).and_then(|captured| captured)
+}
+
+fn capturing() -> bool {
+    CAPTURE.try_with(|c| c.borrow().is_some()).unwrap_or(false)
+}
+
+fn capture_panic(info: &PanicInfo) {
+    let location = info.location().unwrap(); // The current implementation always returns Some
+    let captured = CapturedPanic {
+        message: info.payload_as_str().map(|s| s.to_string()),
+        location: (location.file().to_string(), location.line(), location.column()),
+        backtrace: Backtrace::capture(),
+    };
+    let _ = CAPTURE.try_with(|c| {
+        if let Some(slot) = c.borrow_mut().as_mut() {
+            *slot = Some(captured);
+        }
+    });
+}
+
 // Binary interface to the panic runtime that the standard library depends on.
 //
 // The standard library is tagged with `#![needs_panic_runtime]` (introduced in
@@ -157,6 +202,79 @@ pub fn take_hook() -> Box<dyn Fn(&PanicInfo) + 'static + Sync + Send> {
     }
 }
 
+/// What happens when a thread panics while it is already unwinding from an
+/// earlier panic, usually because a destructor run during unwinding panicked.
+///
+/// *See also the function [`set_double_panic_policy`].*
+///
+/// [`set_double_panic_policy`]: ./fn.set_double_panic_policy.html
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[unstable(feature = "panic_double_panic_policy", issue = "0")]
+#[non_exhaustive]
+pub enum DoublePanicPolicy {
+    /// Abort the process after running the panic hook. This is the default.
+    Abort,
+    /// Run the panic hook, then abandon the earlier panic and unwind with the
+    /// new one instead.
+    ///
+    /// The payload of the earlier panic is leaked, and an enclosing
+    /// `catch_unwind` sees only the new payload. This is best effort: panic
+    /// runtimes that abort still abort, and a panic raised while the panic
+    /// hook itself is running always aborts.
+    Unwind,
+}
+
+static DOUBLE_PANIC_POLICY: AtomicUsize = AtomicUsize::new(0);
+
+/// Chooses what happens when a thread panics while it is already panicking.
+///
+/// By default the process is aborted, since the unwinder can't run the
+/// remaining cleanup for both panics at once. Applications which would rather
+/// log such failures and keep unwinding can select
+/// [`DoublePanicPolicy::Unwind`].
+///
+/// The policy is a global resource.
+///
+/// [`DoublePanicPolicy::Unwind`]: ./enum.DoublePanicPolicy.html#variant.Unwind
+///
+/// # Examples
+///
+/// ```
+/// #![feature(panic_double_panic_policy)]
+/// use std::panic::{self, DoublePanicPolicy};
+///
+/// struct Bomb;
+///
+/// impl Drop for Bomb {
+///     fn drop(&mut self) {
+///         panic!("panic in drop");
+///     }
+/// }
+///
+/// panic::set_double_panic_policy(DoublePanicPolicy::Unwind);
+///
+/// let result = panic::catch_unwind(|| {
+///     let _bomb = Bomb;
+///     panic!("first panic");
+/// });
+/// assert!(result.is_err());
+/// ```
+#[unstable(feature = "panic_double_panic_policy", issue = "0")]
+pub fn set_double_panic_policy(policy: DoublePanicPolicy) {
+    DOUBLE_PANIC_POLICY.store(policy as usize, Ordering::SeqCst);
+}
+
+/// Returns the policy set by [`set_double_panic_policy`].
+///
+/// [`set_double_panic_policy`]: ./fn.set_double_panic_policy.html
+#[unstable(feature = "panic_double_panic_policy", issue = "0")]
+pub fn double_panic_policy() -> DoublePanicPolicy {
+    match DOUBLE_PANIC_POLICY.load(Ordering::SeqCst) {
+        0 => DoublePanicPolicy::Abort,
+        _ => DoublePanicPolicy::Unwind,
+    }
+}
+
 fn default_hook(info: &PanicInfo) {
     #[cfg(feature = "backtrace")]
     use sys_common::backtrace;
@@ -176,15 +294,8 @@ fn default_hook(info: &PanicInfo) {
 
     let location = info.location().unwrap();  // The current implementation always returns Some
 
-    let msg = match info.payload().downcast_ref::<&'static str>() {
-        Some(s) => *s,
-        None => match info.payload().downcast_ref::<String>() {
-            Some(s) => &s[..],
-            None => "Box<Any>",
-        }
-    };
-    let thread = thread_info::current_thread();
-    let name = thread.as_ref().and_then(|t| t.name()).unwrap_or("<unnamed>");
+    let msg = info.payload_as_str().unwrap_or("Box<Any>");
+    let name = info.thread_name().unwrap_or("<unnamed>");
 
     let write = |err: &mut dyn (::io::Write)| {
         let _ = writeln!(err, "thread '{}' panicked at '{}', {}",
@@ -192,7 +303,7 @@ fn default_hook(info: &PanicInfo) {
 
         #[cfg(feature = "backtrace")]
         {
-            use sync::atomic::{AtomicBool, Ordering};
+            use sync::atomic::AtomicBool;
 
             static FIRST_PANIC: AtomicBool = AtomicBool::new(true);
 
@@ -463,22 +574,36 @@ fn rust_panic_with_hook(payload: &mut dyn BoxMeUp,
     }
 
     unsafe {
+        let thread = thread_info::current_thread();
         let mut info = PanicInfo::internal_constructor(
             message,
             Location::internal_constructor(file, line, col),
         );
+        if let Some(name) = thread.as_ref().and_then(|t| t.name()) {
+            info.set_thread_name(name);
+        }
+        let capturing = capturing();
         HOOK_LOCK.read();
         match HOOK {
+            // Inside `catch_unwind_with_info` the panic is handed back to the
+            // caller instead of being printed by the default hook.
+            Hook::Default if capturing => {
+                set_payload(&mut info, payload.get());
+                capture_panic(&info);
+            }
             // Some platforms know that printing to stderr won't ever actually
             // print anything, and if that's the case we can skip the default
             // hook.
             Hook::Default if panic_output().is_none() => {}
             Hook::Default => {
-                info.set_payload(payload.get());
+                set_payload(&mut info, payload.get());
                 default_hook(&info);
             }
             Hook::Custom(ptr) => {
-                info.set_payload(payload.get());
+                set_payload(&mut info, payload.get());
+                if capturing {
+                    capture_panic(&info);
+                }
                 (*ptr)(&info);
             }
         };
@@ -487,17 +612,34 @@ fn rust_panic_with_hook(payload: &mut dyn BoxMeUp,
 
     if panics > 1 {
         // If a thread panics while it's already unwinding then we
-        // have limited options. Currently our preference is to
-        // just abort. In the future we may consider resuming
-        // unwinding or otherwise exiting the thread cleanly.
+        // have limited options. Unless the application asked us to
+        // carry on with the new panic, our preference is to just
+        // abort.
+        if double_panic_policy() == DoublePanicPolicy::Abort {
+            util::dumb_print(format_args!("thread panicked while panicking. \
+                                           aborting.\n"));
+            unsafe { intrinsics::abort() }
+        }
+
+        // The earlier panic will never reach a `catch_unwind`, so stop
+        // counting it.
         util::dumb_print(format_args!("thread panicked while panicking. \
-                                       aborting.\n"));
-        unsafe { intrinsics::abort() }
+                                       abandoning the earlier panic.\n"));
+        update_panic_count(-1);
     }
 
     rust_panic(payload)
 }
 
+/// Hands the payload to the hook, along with its text if it is a `String`,
+/// which libcore's `PanicInfo::payload_as_str` can't recognize by itself.
+fn set_payload<'a>(info: &mut PanicInfo<'a>, payload: &'a (dyn Any + Send)) {
+    info.set_payload(payload);
+    if let Some(s) = payload.downcast_ref::<String>() {
+        info.set_payload_str(s);
+    }
+}
+
 /// Shim around rust_panic. Called by resume_unwind.
 pub fn update_count_then_panic(msg: Box<dyn Any + Send>) -> ! {
     update_panic_count(1);
@@ -282,6 +282,55 @@ pub fn is_separator(c: char) -> bool {
 #[stable(feature = "rust1", since = "1.0.0")]
 pub const MAIN_SEPARATOR: char = ::sys::path::MAIN_SEP;
 
+/// Makes a path absolute without accessing the filesystem.
+///
+/// A relative path is joined onto the [current directory], and the result
+/// is normalized the way the platform does, without resolving symbolic
+/// links or checking that the path exists. Unlike [`fs::canonicalize`],
+/// this works for paths which don't exist yet, but the result may refer to
+/// a different file than `path` if a symbolic link is followed by `..`.
+///
+/// [current directory]: ../env/fn.current_dir.html
+/// [`fs::canonicalize`]: ../fs/fn.canonicalize.html
+///
+/// # Platform-specific behavior
+///
+/// On Unix the path is normalized with [`Path::normalize_lexically`]. On
+/// Windows this currently corresponds to `GetFullPathNameW`, which also
+/// strips trailing dots and spaces from components and maps reserved names
+/// such as `NUL` to device paths, and leaves verbatim `\\?\` paths alone.
+/// Note that, this [may change in the future][changes].
+///
+/// [`Path::normalize_lexically`]: struct.Path.html#method.normalize_lexically
+/// [changes]: ../io/index.html#platform-specific-behavior
+///
+/// # Errors
+///
+/// This function will return an error if `path` is empty, or if it's
+/// relative and the current directory can't be determined.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(path_absolute)]
+/// use std::path;
+///
+/// fn main() -> std::io::Result<()> {
+///     let path = path::absolute("target/../Cargo.toml")?;
+///     assert_eq!(path, std::env::current_dir()?.join("Cargo.toml"));
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "path_absolute", issue = "0")]
+pub fn absolute<P: AsRef<Path>>(path: P) -> io::Result<PathBuf> {
+    let path = path.as_ref();
+    if path.as_os_str().is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                  "cannot make an empty path absolute"));
+    }
+    ::sys::path::absolute(path)
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // Misc helpers
 ////////////////////////////////////////////////////////////////////////////////
@@ -357,6 +406,24 @@ fn split_file_at_dot(file: &OsStr) -> (Option<&OsStr>, Option<&OsStr>) {
     }
 }
 
+// Like `split_file_at_dot`, but splits at the first `.` which isn't the
+// leading one, so that every extension ends up in the second half.
+fn split_file_at_first_dot(file: &OsStr) -> (&OsStr, Option<&OsStr>) {
+    let slice = os_str_as_u8_slice(file);
+    if slice == b".." {
+        return (file, None);
+    }
+
+    // See `split_file_at_dot` for why the conversions are safe.
+    let i = match slice.iter().skip(1).position(|b| *b == b'.') {
+        Some(i) => i + 1,
+        None => return (file, None),
+    };
+    unsafe {
+        (u8_slice_as_os_str(&slice[..i]), Some(u8_slice_as_os_str(&slice[i + 1..])))
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // The core iterators
 ////////////////////////////////////////////////////////////////////////////////
@@ -1348,6 +1415,51 @@ impl PathBuf {
         true
     }
 
+    /// Appends `extension` to [`self.file_name`], after a `.`.
+    ///
+    /// Returns `false` and does nothing if [`self.file_name`] is [`None`],
+    /// returns `true` and adds the extension otherwise. Unlike
+    /// [`set_extension`], any extension the file name already has is kept.
+    /// An empty `extension` leaves the file name as it is.
+    ///
+    /// [`self.file_name`]: struct.PathBuf.html#method.file_name
+    /// [`set_extension`]: struct.PathBuf.html#method.set_extension
+    /// [`None`]: ../../std/option/enum.Option.html#variant.None
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(path_add_extension)]
+    /// use std::path::{Path, PathBuf};
+    ///
+    /// let mut p = PathBuf::from("/backups/archive.tar");
+    ///
+    /// p.add_extension("gz");
+    /// assert_eq!(Path::new("/backups/archive.tar.gz"), p.as_path());
+    ///
+    /// p.add_extension("");
+    /// assert_eq!(Path::new("/backups/archive.tar.gz"), p.as_path());
+    /// ```
+    #[unstable(feature = "path_add_extension", issue = "0")]
+    pub fn add_extension<S: AsRef<OsStr>>(&mut self, extension: S) -> bool {
+        self._add_extension(extension.as_ref())
+    }
+
+    fn _add_extension(&mut self, extension: &OsStr) -> bool {
+        let mut file_name = match self.file_name() {
+            Some(file_name) => file_name.to_os_string(),
+            None => return false,
+        };
+
+        if !os_str_as_u8_slice(extension).is_empty() {
+            file_name.push(".");
+            file_name.push(extension);
+            self.set_file_name(&file_name);
+        }
+
+        true
+    }
+
     /// Consumes the `PathBuf`, yielding its internal [`OsString`] storage.
     ///
     /// [`OsString`]: ../ffi/struct.OsString.html
@@ -1682,6 +1794,24 @@ pub struct Path {
 #[stable(since = "1.7.0", feature = "strip_prefix")]
 pub struct StripPrefixError(());
 
+/// An error returned from [`Path::try_join`][`try_join`] if the path would
+/// not have stayed inside the base path.
+///
+/// This `struct` is created by the [`try_join`] method on [`Path`].
+/// See its documentation for more.
+///
+/// [`try_join`]: struct.Path.html#method.try_join
+/// [`Path`]: struct.Path.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[unstable(feature = "path_try_join", issue = "0")]
+pub struct TryJoinError(TryJoinErrorKind);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TryJoinErrorKind {
+    Absolute,
+    Escapes,
+}
+
 impl Path {
     // The following (private!) function allows construction of a path from a u8
     // slice, which is only safe when it is known to follow the OsStr encoding.
@@ -2085,6 +2215,38 @@ impl Path {
         self.file_name().map(split_file_at_dot).and_then(|(before, after)| before.or(after))
     }
 
+    /// Extracts the prefix of [`self.file_name`], the part before all of its
+    /// extensions.
+    ///
+    /// The prefix is:
+    ///
+    /// * [`None`], if there is no file name;
+    /// * The entire file name if there is no embedded `.`;
+    /// * The entire file name if the file name begins with `.` and has no other `.`s within;
+    /// * Otherwise, the portion of the file name before the first `.` which
+    ///   isn't its first character
+    ///
+    /// This differs from [`file_stem`], which only strips the last extension.
+    ///
+    /// [`self.file_name`]: struct.Path.html#method.file_name
+    /// [`file_stem`]: struct.Path.html#method.file_stem
+    /// [`None`]: ../../std/option/enum.Option.html#variant.None
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(path_file_prefix)]
+    /// use std::path::Path;
+    ///
+    /// assert_eq!("archive", Path::new("archive.tar.gz").file_prefix().unwrap());
+    /// assert_eq!("archive.tar", Path::new("archive.tar.gz").file_stem().unwrap());
+    /// assert_eq!(".config", Path::new(".config.toml").file_prefix().unwrap());
+    /// ```
+    #[unstable(feature = "path_file_prefix", issue = "0")]
+    pub fn file_prefix(&self) -> Option<&OsStr> {
+        self.file_name().map(|name| split_file_at_first_dot(name).0)
+    }
+
     /// Extracts the extension of [`self.file_name`], if possible.
     ///
     /// The extension is:
@@ -2111,6 +2273,37 @@ impl Path {
         self.file_name().map(split_file_at_dot).and_then(|(before, after)| before.and(after))
     }
 
+    /// Extracts all the extensions of [`self.file_name`], if possible.
+    ///
+    /// The full extension is:
+    ///
+    /// * [`None`], if there is no file name;
+    /// * [`None`], if there is no embedded `.`;
+    /// * [`None`], if the file name begins with `.` and has no other `.`s within;
+    /// * Otherwise, the portion of the file name after the first `.` which
+    ///   isn't its first character
+    ///
+    /// It is what's left of the file name after [`file_prefix`] and a `.`.
+    ///
+    /// [`self.file_name`]: struct.Path.html#method.file_name
+    /// [`file_prefix`]: struct.Path.html#method.file_prefix
+    /// [`None`]: ../../std/option/enum.Option.html#variant.None
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(path_file_prefix)]
+    /// use std::path::Path;
+    ///
+    /// assert_eq!("tar.gz", Path::new("archive.tar.gz").full_extension().unwrap());
+    /// assert_eq!("gz", Path::new("archive.tar.gz").extension().unwrap());
+    /// assert_eq!(None, Path::new(".bashrc").full_extension());
+    /// ```
+    #[unstable(feature = "path_file_prefix", issue = "0")]
+    pub fn full_extension(&self) -> Option<&OsStr> {
+        self.file_name().and_then(|name| split_file_at_first_dot(name).1)
+    }
+
     /// Creates an owned [`PathBuf`] with `path` adjoined to `self`.
     ///
     /// See [`PathBuf::push`] for more details on what it means to adjoin a path.
@@ -2136,6 +2329,58 @@ impl Path {
         buf
     }
 
+    /// Creates an owned [`PathBuf`] with `path` adjoined to `self`, if the
+    /// result stays inside `self`.
+    ///
+    /// This is [`join`] for paths which come from somewhere untrusted, such
+    /// as the names of files in an archive being unpacked into `self`. It
+    /// fails instead of joining if `path`:
+    ///
+    /// * is absolute, or has a root or a prefix, any of which would make
+    ///   [`join`] replace `self` instead of extending it;
+    /// * has more `..` components than the ones before them can cancel out,
+    ///   so that it would point outside of `self`.
+    ///
+    /// Only the components of `path` are looked at, not the file system, so
+    /// a symbolic link inside `self` can still lead elsewhere.
+    ///
+    /// [`PathBuf`]: struct.PathBuf.html
+    /// [`join`]: #method.join
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(path_try_join)]
+    /// use std::path::{Path, PathBuf};
+    ///
+    /// let base = Path::new("/srv/uploads");
+    /// assert_eq!(base.try_join("a/../b.txt").unwrap(), PathBuf::from("/srv/uploads/a/../b.txt"));
+    /// assert!(base.try_join("/etc/passwd").is_err());
+    /// assert!(base.try_join("../../etc/passwd").is_err());
+    /// ```
+    #[unstable(feature = "path_try_join", issue = "0")]
+    pub fn try_join<P: AsRef<Path>>(&self, path: P) -> Result<PathBuf, TryJoinError> {
+        self._try_join(path.as_ref())
+    }
+
+    fn _try_join(&self, path: &Path) -> Result<PathBuf, TryJoinError> {
+        let mut depth = 0usize;
+        for component in path.components() {
+            match component {
+                Component::Prefix(..) | Component::RootDir => {
+                    return Err(TryJoinError(TryJoinErrorKind::Absolute));
+                }
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    depth = depth.checked_sub(1)
+                        .ok_or(TryJoinError(TryJoinErrorKind::Escapes))?;
+                }
+                Component::Normal(_) => depth += 1,
+            }
+        }
+        Ok(self._join(path))
+    }
+
     /// Creates an owned [`PathBuf`] like `self` but with the given file name.
     ///
     /// See [`PathBuf::set_file_name`] for more details.
@@ -2191,6 +2436,31 @@ impl Path {
         buf
     }
 
+    /// Creates an owned [`PathBuf`] like `self` but with the given extension
+    /// added after the ones it already has.
+    ///
+    /// See [`PathBuf::add_extension`] for more details.
+    ///
+    /// [`PathBuf`]: struct.PathBuf.html
+    /// [`PathBuf::add_extension`]: struct.PathBuf.html#method.add_extension
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(path_add_extension)]
+    /// use std::path::{Path, PathBuf};
+    ///
+    /// let path = Path::new("archive.tar");
+    /// assert_eq!(path.with_added_extension("gz"), PathBuf::from("archive.tar.gz"));
+    /// assert_eq!(path.with_extension("gz"), PathBuf::from("archive.gz"));
+    /// ```
+    #[unstable(feature = "path_add_extension", issue = "0")]
+    pub fn with_added_extension<S: AsRef<OsStr>>(&self, extension: S) -> PathBuf {
+        let mut buf = self.to_path_buf();
+        buf.add_extension(extension);
+        buf
+    }
+
     /// Produces an iterator over the [`Component`]s of the path.
     ///
     /// When parsing the path, there is a small amount of normalization:
@@ -2264,6 +2534,72 @@ impl Path {
         Iter { inner: self.components() }
     }
 
+    /// Normalizes the path without accessing the filesystem, by removing
+    /// `.` components and resolving `..` components against the ones before
+    /// them.
+    ///
+    /// `..` components at the start of a relative path are kept, and those
+    /// directly after the root are dropped, as the parent of the root is the
+    /// root itself. Verbatim paths on Windows are returned as they are, as
+    /// `.` and `..` aren't special in them. A path which normalizes to
+    /// nothing becomes `.`.
+    ///
+    /// This doesn't resolve symbolic links, so if `a` is a symbolic link to
+    /// a directory, `a/..` normalizes to `.` although the filesystem would
+    /// take it to mean the parent of the directory `a` points to. Use
+    /// [`canonicalize`] when that matters and the path exists.
+    ///
+    /// [`canonicalize`]: #method.canonicalize
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(path_normalize_lexically)]
+    /// use std::path::Path;
+    ///
+    /// assert_eq!(Path::new("/usr/./lib/../bin").normalize_lexically(), Path::new("/usr/bin"));
+    /// assert_eq!(Path::new("/../etc").normalize_lexically(), Path::new("/etc"));
+    /// assert_eq!(Path::new("../a/./b/..").normalize_lexically(), Path::new("../a"));
+    /// assert_eq!(Path::new("a/..").normalize_lexically(), Path::new("."));
+    /// ```
+    #[unstable(feature = "path_normalize_lexically", issue = "0")]
+    pub fn normalize_lexically(&self) -> PathBuf {
+        let components = self.components();
+        if components.prefix_verbatim() {
+            return self.to_path_buf();
+        }
+
+        let mut normalized = PathBuf::new();
+        // The number of normal components at the end of `normalized`, which
+        // a `..` can remove.
+        let mut depth = 0;
+        for component in components {
+            match component {
+                Component::Prefix(..) | Component::RootDir => {
+                    normalized.push(component.as_os_str());
+                }
+                Component::CurDir => {}
+                Component::ParentDir if depth > 0 => {
+                    normalized.pop();
+                    depth -= 1;
+                }
+                Component::ParentDir => {
+                    if !normalized.has_root() {
+                        normalized.push("..");
+                    }
+                }
+                Component::Normal(name) => {
+                    normalized.push(name);
+                    depth += 1;
+                }
+            }
+        }
+        if normalized.as_os_str().is_empty() && !self.as_os_str().is_empty() {
+            normalized.push(".");
+        }
+        normalized
+    }
+
     /// Returns an object that implements [`Display`] for safely printing paths
     /// that may contain non-Unicode data.
     ///
@@ -2411,14 +2747,42 @@ impl Path {
     /// # See Also
     ///
     /// This is a convenience function that coerces errors to false. If you want to
-    /// check errors, call [fs::metadata].
+    /// check errors, call [fs::metadata] or [`try_exists`].
     ///
     /// [fs::metadata]: ../../std/fs/fn.metadata.html
+    /// [`try_exists`]: #method.try_exists
     #[stable(feature = "path_ext", since = "1.5.0")]
     pub fn exists(&self) -> bool {
         fs::metadata(self).is_ok()
     }
 
+    /// Returns `Ok(true)` if the path points at an existing entity, and
+    /// `Ok(false)` only if it definitely doesn't.
+    ///
+    /// This function will traverse symbolic links, so broken symbolic links
+    /// return `Ok(false)`. Unlike [`exists`], errors such as lacking
+    /// permission to search a directory leading to the path are returned
+    /// rather than treated as the path not existing.
+    ///
+    /// This is an alias to [`fs::try_exists`].
+    ///
+    /// [`exists`]: #method.exists
+    /// [`fs::try_exists`]: ../fs/fn.try_exists.html
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(fs_try_exists)]
+    /// use std::path::Path;
+    ///
+    /// assert_eq!(Path::new("does_not_exist.txt").try_exists().unwrap(), false);
+    /// assert!(Path::new("/root/secret_file.txt").try_exists().is_err());
+    /// ```
+    #[unstable(feature = "fs_try_exists", issue = "0")]
+    pub fn try_exists(&self) -> io::Result<bool> {
+        fs::try_exists(self)
+    }
+
     /// Returns `true` if the path exists on disk and is pointing at a regular file.
     ///
     /// This function will traverse symbolic links to query information about the
@@ -2736,6 +3100,23 @@ impl Error for StripPrefixError {
     fn description(&self) -> &str { "prefix not found" }
 }
 
+#[unstable(feature = "path_try_join", issue = "0")]
+impl fmt::Display for TryJoinError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.description().fmt(f)
+    }
+}
+
+#[unstable(feature = "path_try_join", issue = "0")]
+impl Error for TryJoinError {
+    fn description(&self) -> &str {
+        match self.0 {
+            TryJoinErrorKind::Absolute => "joined path is absolute",
+            TryJoinErrorKind::Escapes => "joined path leaves the base path",
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3710,6 +4091,32 @@ mod tests {
            );
     }
 
+    #[test]
+    pub fn test_prefix_full_ext() {
+        macro_rules! tpe(
+            ($path:expr, $prefix:expr, $full_ext:expr) => ( {
+                let path = Path::new($path);
+                assert!(path.file_prefix() == $prefix.map(OsStr::new) &&
+                        path.full_extension() == $full_ext.map(OsStr::new),
+                        "splitting {:?}: Expected {:?}/{:?}, got {:?}/{:?}",
+                        $path, $prefix, $full_ext,
+                        path.file_prefix(), path.full_extension());
+            });
+        );
+
+        tpe!("foo", Some("foo"), None::<&str>);
+        tpe!("foo.", Some("foo"), Some(""));
+        tpe!(".foo", Some(".foo"), None::<&str>);
+        tpe!(".foo.txt", Some(".foo"), Some("txt"));
+        tpe!("foo.txt", Some("foo"), Some("txt"));
+        tpe!("foo.bar.txt", Some("foo"), Some("bar.txt"));
+        tpe!("dir.d/foo.tar.gz", Some("foo"), Some("tar.gz"));
+        tpe!("foo..txt", Some("foo"), Some(".txt"));
+        tpe!(".", None::<&str>, None::<&str>);
+        tpe!("..", None::<&str>, None::<&str>);
+        tpe!("", None::<&str>, None::<&str>);
+    }
+
     #[test]
     pub fn test_push() {
         macro_rules! tp(
@@ -3926,6 +4333,51 @@ mod tests {
         tfe!("/", "foo", "/", false);
     }
 
+    #[test]
+    pub fn test_add_extension() {
+        macro_rules! tae(
+                ($path:expr, $ext:expr, $expected:expr, $output:expr) => ( {
+                let mut p = PathBuf::from($path);
+                let output = p.add_extension($ext);
+                assert!(p.to_str() == Some($expected) && output == $output,
+                        "adding extension {:?} to {:?}: Expected {:?}/{:?}, got {:?}/{:?}",
+                        $ext, $path, $expected, $output,
+                        p.to_str().unwrap(), output);
+                assert_eq!(Path::new($path).with_added_extension($ext).to_str(), Some($expected));
+            });
+        );
+
+        tae!("foo", "txt", "foo.txt", true);
+        tae!("foo.bar", "txt", "foo.bar.txt", true);
+        tae!(".test", "txt", ".test.txt", true);
+        tae!("foo.txt", "", "foo.txt", true);
+        tae!("", "foo", "", false);
+        tae!(".", "foo", ".", false);
+        tae!("foo/", "bar", "foo.bar", true);
+        tae!("..", "foo", "..", false);
+        tae!("/", "foo", "/", false);
+    }
+
+    #[test]
+    pub fn test_try_join() {
+        let base = Path::new("base");
+        assert_eq!(base.try_join("a/b").unwrap(), Path::new("base/a/b"));
+        assert_eq!(base.try_join("").unwrap(), Path::new("base/"));
+        assert_eq!(base.try_join("./a/../b/..").unwrap(), Path::new("base/./a/../b/.."));
+        assert!(base.try_join("..").is_err());
+        assert!(base.try_join("a/../..").is_err());
+        assert!(base.try_join("a/../../base/a").is_err());
+
+        let err = base.try_join("/a").unwrap_err();
+        assert!(err != base.try_join("..").unwrap_err());
+        assert_eq!(err.to_string(), "joined path is absolute");
+        if cfg!(windows) {
+            assert!(base.try_join(r"\a").is_err());
+            assert!(base.try_join(r"C:a").is_err());
+            assert!(base.try_join(r"C:\a").is_err());
+        }
+    }
+
     #[test]
     fn test_eq_receivers() {
         use borrow::Cow;
@@ -4140,6 +4592,51 @@ mod tests {
         assert!(path_buf.into_os_string().capacity() >= 15);
     }
 
+    #[test]
+    fn normalize_lexically() {
+        macro_rules! n {
+            ($path:expr, $expected:expr) => (
+                assert_eq!(Path::new($path).normalize_lexically(), Path::new($expected),
+                           "normalizing {:?}", $path)
+            )
+        }
+
+        n!("", "");
+        n!(".", ".");
+        n!("a/..", ".");
+        n!("./a/./b/.", "a/b");
+        n!("a/b/../c", "a/c");
+        n!("a/../../b", "../b");
+        n!("../../a/..", "../..");
+        n!("/", "/");
+        n!("/..", "/");
+        n!("/../a/b/../../..", "/");
+        n!("/a//b/", "/a/b");
+
+        if cfg!(windows) {
+            n!(r"C:\..", r"C:\");
+            n!(r"C:\a\..\b", r"C:\b");
+            n!(r"C:a\..\..", r"C:..");
+            n!(r"\\server\share\..\..", r"\\server\share\");
+            n!(r"\\?\C:\..", r"\\?\C:\..");
+        }
+    }
+
+    #[test]
+    fn absolute() {
+        use env;
+
+        let cwd = env::current_dir().unwrap();
+        assert_eq!(super::absolute("a/../b/.").unwrap(), cwd.join("b"));
+        assert_eq!(super::absolute(".").unwrap(), cwd);
+        assert!(super::absolute("").is_err());
+        if cfg!(unix) {
+            assert_eq!(super::absolute("/a/./b/../c").unwrap(), Path::new("/a/c"));
+        } else if cfg!(windows) {
+            assert_eq!(super::absolute(r"C:\a\.\b\..\c").unwrap(), Path::new(r"C:\a\c"));
+        }
+    }
+
     #[test]
     fn display_format_flags() {
         assert_eq!(format!("a{:#<5}b", Path::new("").display()), "a#####b");
@@ -108,15 +108,20 @@
 
 use io::prelude::*;
 
-use ffi::OsStr;
+use error::Error;
+use ffi::{OsStr, OsString};
 use fmt;
 use fs;
 use io::{self, Initializer};
 use path::Path;
 use str;
-use sys::pipe::{read2, AnonPipe};
+use sync::atomic::{AtomicBool, Ordering};
+use sys::pipe::{read2, read2_with, AnonPipe};
+use ops::Deref;
 use sys::process as imp;
+use sys_common::process::split_spawn_error;
 use sys_common::{AsInner, AsInnerMut, FromInner, IntoInner};
+use time::{Duration, Instant};
 
 /// Representation of a running or exited child process.
 ///
@@ -177,6 +182,10 @@ impl AsInner<imp::Process> for Child {
     fn as_inner(&self) -> &imp::Process { &self.handle }
 }
 
+impl AsInnerMut<imp::Process> for Child {
+    fn as_inner_mut(&mut self) -> &mut imp::Process { &mut self.handle }
+}
+
 impl FromInner<(imp::Process, imp::StdioPipes)> for Child {
     fn from_inner((handle, io): (imp::Process, imp::StdioPipes)) -> Child {
         Child {
@@ -230,6 +239,34 @@ impl Write for ChildStdin {
     }
 }
 
+impl ChildStdin {
+    /// Creates a new handle to the same pipe.
+    ///
+    /// Writes through either handle go to the child's stdin, and the child
+    /// only sees its end of the stream once both are dropped.
+    #[unstable(feature = "child_stdio_ext", issue = "0")]
+    pub fn try_clone(&self) -> io::Result<ChildStdin> {
+        self.inner.try_clone().map(|inner| ChildStdin { inner })
+    }
+
+    /// Moves the pipe into or out of non-blocking mode.
+    ///
+    /// In non-blocking mode, writing to a full pipe fails with
+    /// [`ErrorKind::WouldBlock`] instead of waiting, so that the pipe can be
+    /// driven by an event loop polling its raw descriptor. The mode is
+    /// shared with any clones made with [`try_clone`].
+    ///
+    /// This isn't supported on Windows, where it fails unless `nonblocking`
+    /// is `false`.
+    ///
+    /// [`ErrorKind::WouldBlock`]: ../io/enum.ErrorKind.html#variant.WouldBlock
+    /// [`try_clone`]: #method.try_clone
+    #[unstable(feature = "child_stdio_ext", issue = "0")]
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.inner.set_nonblocking(nonblocking)
+    }
+}
+
 impl AsInner<AnonPipe> for ChildStdin {
     fn as_inner(&self) -> &AnonPipe { &self.inner }
 }
@@ -277,6 +314,34 @@ impl Read for ChildStdout {
     }
 }
 
+impl ChildStdout {
+    /// Creates a new handle to the same pipe.
+    ///
+    /// Reads through either handle take from the same data, so each byte the
+    /// child writes is returned by only one of them.
+    #[unstable(feature = "child_stdio_ext", issue = "0")]
+    pub fn try_clone(&self) -> io::Result<ChildStdout> {
+        self.inner.try_clone().map(|inner| ChildStdout { inner })
+    }
+
+    /// Moves the pipe into or out of non-blocking mode.
+    ///
+    /// In non-blocking mode, reading from an empty pipe fails with
+    /// [`ErrorKind::WouldBlock`] instead of waiting, so that the pipe can be
+    /// driven by an event loop polling its raw descriptor. The mode is
+    /// shared with any clones made with [`try_clone`].
+    ///
+    /// This isn't supported on Windows, where it fails unless `nonblocking`
+    /// is `false`.
+    ///
+    /// [`ErrorKind::WouldBlock`]: ../io/enum.ErrorKind.html#variant.WouldBlock
+    /// [`try_clone`]: #method.try_clone
+    #[unstable(feature = "child_stdio_ext", issue = "0")]
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.inner.set_nonblocking(nonblocking)
+    }
+}
+
 impl AsInner<AnonPipe> for ChildStdout {
     fn as_inner(&self) -> &AnonPipe { &self.inner }
 }
@@ -324,6 +389,34 @@ impl Read for ChildStderr {
     }
 }
 
+impl ChildStderr {
+    /// Creates a new handle to the same pipe.
+    ///
+    /// Reads through either handle take from the same data, so each byte the
+    /// child writes is returned by only one of them.
+    #[unstable(feature = "child_stdio_ext", issue = "0")]
+    pub fn try_clone(&self) -> io::Result<ChildStderr> {
+        self.inner.try_clone().map(|inner| ChildStderr { inner })
+    }
+
+    /// Moves the pipe into or out of non-blocking mode.
+    ///
+    /// In non-blocking mode, reading from an empty pipe fails with
+    /// [`ErrorKind::WouldBlock`] instead of waiting, so that the pipe can be
+    /// driven by an event loop polling its raw descriptor. The mode is
+    /// shared with any clones made with [`try_clone`].
+    ///
+    /// This isn't supported on Windows, where it fails unless `nonblocking`
+    /// is `false`.
+    ///
+    /// [`ErrorKind::WouldBlock`]: ../io/enum.ErrorKind.html#variant.WouldBlock
+    /// [`try_clone`]: #method.try_clone
+    #[unstable(feature = "child_stdio_ext", issue = "0")]
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.inner.set_nonblocking(nonblocking)
+    }
+}
+
 impl AsInner<AnonPipe> for ChildStderr {
     fn as_inner(&self) -> &AnonPipe { &self.inner }
 }
@@ -739,7 +832,41 @@ impl Command {
     /// ```
     #[stable(feature = "process", since = "1.0.0")]
     pub fn spawn(&mut self) -> io::Result<Child> {
-        self.inner.spawn(imp::Stdio::Inherit, true).map(Child::from_inner)
+        self.spawn_with_context().map_err(SpawnError::into_io_error)
+    }
+
+    /// Executes the command as a child process like [`spawn`], but on
+    /// failure returns a [`SpawnError`] saying which program couldn't be
+    /// started and at which stage of the child's setup it failed.
+    ///
+    /// [`spawn`]: #method.spawn
+    /// [`SpawnError`]: struct.SpawnError.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(spawn_error)]
+    /// use std::process::Command;
+    ///
+    /// let err = Command::new("does-not-exist").spawn_with_context().unwrap_err();
+    /// println!("{}", err);
+    /// assert_eq!(err.program(), "does-not-exist");
+    /// ```
+    #[unstable(feature = "spawn_error", issue = "0")]
+    pub fn spawn_with_context(&mut self) -> Result<Child, SpawnError> {
+        self.spawn_inner(imp::Stdio::Inherit, true)
+    }
+
+    fn spawn_inner(&mut self, default: imp::Stdio, needs_stdin: bool)
+                   -> Result<Child, SpawnError> {
+        match self.inner.spawn(default, needs_stdin) {
+            Ok(inner) => Ok(Child::from_inner(inner)),
+            Err(e) => {
+                let (stage, error) = split_spawn_error(e);
+                let program = self.inner.get_program().to_os_string();
+                Err(SpawnError { error, stage, program })
+            }
+        }
     }
 
     /// Executes the command as a child process, waiting for it to finish and
@@ -768,10 +895,160 @@ impl Command {
     /// ```
     #[stable(feature = "process", since = "1.0.0")]
     pub fn output(&mut self) -> io::Result<Output> {
-        self.inner.spawn(imp::Stdio::MakePipe, false).map(Child::from_inner)
+        self.spawn_inner(imp::Stdio::MakePipe, false).map_err(SpawnError::into_io_error)
             .and_then(|p| p.wait_with_output())
     }
 
+    /// Executes the command as a child process, waiting for it to finish and
+    /// collecting its stdout and stderr together, in the order it wrote
+    /// them.
+    ///
+    /// This is like [`output`], except that the child's stderr goes to the
+    /// same pipe as its stdout, as with `2>&1` in a shell. All of the
+    /// output is in the `stdout` field of the returned [`Output`], and
+    /// `stderr` is empty. If the command's stdout has been redirected,
+    /// stderr goes to the same place and nothing is captured.
+    ///
+    /// [`output`]: #method.output
+    /// [`Output`]: struct.Output.html
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(process_combined_output)]
+    /// use std::process::Command;
+    ///
+    /// let output = Command::new("make").combined_output().expect("failed to run make");
+    /// if !output.status.success() {
+    ///     println!("{}", String::from_utf8_lossy(&output.stdout));
+    /// }
+    /// ```
+    #[unstable(feature = "process_combined_output", issue = "0")]
+    pub fn combined_output(&mut self) -> io::Result<Output> {
+        self.inner.merge_stderr(true);
+        let child = self.spawn_inner(imp::Stdio::MakePipe, false);
+        self.inner.merge_stderr(false);
+        child.map_err(SpawnError::into_io_error).and_then(|p| p.wait_with_output())
+    }
+
+    /// Executes the command as a child process, passing its output to `f`
+    /// as it's read, and returning its exit status once it's finished.
+    ///
+    /// Stdout and stderr are captured by default, as with [`output`], and
+    /// read at the same time, so the child can't block on one while we
+    /// wait for the other. `f` is called with each chunk as it arrives,
+    /// along with the stream it came from. The chunks are whatever each
+    /// read returned: they needn't end at a line break, and a write by the
+    /// child may be split over several of them.
+    ///
+    /// If `f` returns an error, reading stops and the pipes are closed, so
+    /// that a child still writing gets `EPIPE` (or is killed by `SIGPIPE`)
+    /// rather than blocking. The child is then waited for and `f`'s error is
+    /// returned.
+    ///
+    /// [`output`]: #method.output
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(process_output_with)]
+    /// use std::io::{self, Write};
+    /// use std::process::{Command, OutputStream};
+    ///
+    /// // Show the build's output as it goes, while keeping its errors.
+    /// let mut errors = Vec::new();
+    /// let status = Command::new("make")
+    ///     .output_with(|stream, chunk| {
+    ///         if stream == OutputStream::Stderr {
+    ///             errors.extend_from_slice(chunk);
+    ///         }
+    ///         io::stdout().write_all(chunk)
+    ///     })
+    ///     .expect("failed to run make");
+    /// ```
+    #[unstable(feature = "process_output_with", issue = "0")]
+    pub fn output_with<F>(&mut self, mut f: F) -> io::Result<ExitStatus>
+        where F: FnMut(OutputStream, &[u8]) -> io::Result<()>
+    {
+        let (status, res) = self.output_inner(&mut f)?;
+        res.map(|()| status)
+    }
+
+    /// Executes the command as a child process, waiting for it to finish and
+    /// collecting at most `max_bytes` of each of its stdout and stderr.
+    ///
+    /// This is [`output`] for children which can't be trusted to keep their
+    /// output to a reasonable size. If either stream goes over the limit,
+    /// reading stops there and the child is treated as [`output_with`]
+    /// treats it on an error. The error returned then wraps an
+    /// [`OutputLimitExceeded`] holding the output up to the limit and the
+    /// child's exit status.
+    ///
+    /// [`output`]: #method.output
+    /// [`output_with`]: #method.output_with
+    /// [`OutputLimitExceeded`]: struct.OutputLimitExceeded.html
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(process_output_with)]
+    /// use std::process::{Command, OutputLimitExceeded};
+    ///
+    /// match Command::new("generate").output_limited(1 << 20) {
+    ///     Ok(output) => println!("{} bytes", output.stdout.len()),
+    ///     Err(e) => match e.get_ref().and_then(|e| e.downcast_ref::<OutputLimitExceeded>()) {
+    ///         Some(_) => println!("generate wrote too much"),
+    ///         None => println!("failed to run generate: {}", e),
+    ///     },
+    /// }
+    /// ```
+    #[unstable(feature = "process_output_with", issue = "0")]
+    pub fn output_limited(&mut self, max_bytes: usize) -> io::Result<Output> {
+        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+        let mut exceeded = None;
+        let (status, res) = self.output_inner(&mut |stream, chunk| {
+            let dst = match stream {
+                OutputStream::Stdout => &mut stdout,
+                OutputStream::Stderr => &mut stderr,
+            };
+            let room = max_bytes - dst.len();
+            if chunk.len() > room {
+                dst.extend_from_slice(&chunk[..room]);
+                exceeded = Some(stream);
+                return Err(io::Error::new(io::ErrorKind::Other, "output limit exceeded"))
+            }
+            dst.extend_from_slice(chunk);
+            Ok(())
+        })?;
+        let output = Output { status, stdout, stderr };
+        if let Some(stream) = exceeded {
+            return Err(io::Error::new(io::ErrorKind::Other,
+                                      OutputLimitExceeded { output, stream, limit: max_bytes }))
+        }
+        res.map(|()| output)
+    }
+
+    // Returns the result of reading the output separately from the status,
+    // which the child is always waited for to get.
+    fn output_inner(&mut self, f: &mut dyn FnMut(OutputStream, &[u8]) -> io::Result<()>)
+                    -> io::Result<(ExitStatus, io::Result<()>)> {
+        let mut child = self.spawn_inner(imp::Stdio::MakePipe, false)
+            .map_err(SpawnError::into_io_error)?;
+        drop(child.stdin.take());
+        let res = match (child.stdout.take(), child.stderr.take()) {
+            (None, None) => Ok(()),
+            (Some(out), None) => read_chunks(out, OutputStream::Stdout, f),
+            (None, Some(err)) => read_chunks(err, OutputStream::Stderr, f),
+            (Some(out), Some(err)) => {
+                read2_with(out.inner, err.inner, &mut |i, chunk| {
+                    f(if i == 0 { OutputStream::Stdout } else { OutputStream::Stderr }, chunk)
+                })
+            }
+        };
+        let status = child.wait()?;
+        Ok((status, res))
+    }
+
     /// Executes a command as a child process, waiting for it to finish and
     /// collecting its exit status.
     ///
@@ -793,8 +1070,104 @@ impl Command {
     /// ```
     #[stable(feature = "process", since = "1.0.0")]
     pub fn status(&mut self) -> io::Result<ExitStatus> {
-        self.inner.spawn(imp::Stdio::Inherit, true).map(Child::from_inner)
-                  .and_then(|mut p| p.wait())
+        self.spawn_inner(imp::Stdio::Inherit, true).map_err(SpawnError::into_io_error)
+            .and_then(|mut p| p.wait())
+    }
+
+    /// Returns the path to the program that was given to [`Command::new`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(command_access)]
+    /// use std::process::Command;
+    ///
+    /// let cmd = Command::new("echo");
+    /// assert_eq!(cmd.get_program(), "echo");
+    /// ```
+    ///
+    /// [`Command::new`]: #method.new
+    #[unstable(feature = "command_access", issue = "0")]
+    pub fn get_program(&self) -> &OsStr {
+        self.inner.get_program()
+    }
+
+    /// Returns an iterator of the arguments that will be passed to the
+    /// program.
+    ///
+    /// This does not include the path to the program as the first argument,
+    /// nor any replacement for it set on Unix with `arg0`. On Windows, the
+    /// arguments given with `raw_arg` are included, as given.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(command_access)]
+    /// use std::ffi::OsStr;
+    /// use std::process::Command;
+    ///
+    /// let mut cmd = Command::new("echo");
+    /// cmd.arg("first").arg("second");
+    /// let args: Vec<&OsStr> = cmd.get_args().collect();
+    /// assert_eq!(args, &["first", "second"]);
+    /// ```
+    #[unstable(feature = "command_access", issue = "0")]
+    pub fn get_args(&self) -> CommandArgs {
+        CommandArgs { inner: self.inner.get_args() }
+    }
+
+    /// Returns an iterator of the changes that will be made to the
+    /// environment when the program is spawned.
+    ///
+    /// Each element is a tuple of the variable's name and `Some(value)` if
+    /// it's set with [`env`] or [`envs`], or `None` if it's removed with
+    /// [`env_remove`]. The current environment is not included, and a
+    /// call to [`env_clear`] isn't shown, except in that it discards the
+    /// changes made before it. On Windows, names are upper-cased.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(command_access)]
+    /// use std::ffi::OsStr;
+    /// use std::process::Command;
+    ///
+    /// let mut cmd = Command::new("ls");
+    /// cmd.env("TERM", "dumb").env_remove("TZ");
+    /// let envs: Vec<(&OsStr, Option<&OsStr>)> = cmd.get_envs().collect();
+    /// assert_eq!(envs, &[
+    ///     (OsStr::new("TERM"), Some(OsStr::new("dumb"))),
+    ///     (OsStr::new("TZ"), None)
+    /// ]);
+    /// ```
+    ///
+    /// [`env`]: #method.env
+    /// [`envs`]: #method.envs
+    /// [`env_remove`]: #method.env_remove
+    /// [`env_clear`]: #method.env_clear
+    #[unstable(feature = "command_access", issue = "0")]
+    pub fn get_envs(&self) -> CommandEnvs {
+        CommandEnvs { inner: self.inner.get_envs() }
+    }
+
+    /// Returns the working directory for the child process, or `None` if it
+    /// will inherit ours.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(command_access)]
+    /// use std::path::Path;
+    /// use std::process::Command;
+    ///
+    /// let mut cmd = Command::new("ls");
+    /// assert_eq!(cmd.get_current_dir(), None);
+    /// cmd.current_dir("/bin");
+    /// assert_eq!(cmd.get_current_dir(), Some(Path::new("/bin")));
+    /// ```
+    #[unstable(feature = "command_access", issue = "0")]
+    pub fn get_current_dir(&self) -> Option<&Path> {
+        self.inner.get_current_dir()
     }
 }
 
@@ -808,6 +1181,44 @@ impl fmt::Debug for Command {
     }
 }
 
+/// Formats the command as a line of shell which would run it, for error
+/// messages and logs.
+///
+/// On Unix, this is a POSIX shell line: arguments are single-quoted where
+/// the shell would otherwise change them, a working directory set with
+/// [`current_dir`] is shown as a `cd` first, and changes to the environment
+/// are shown as assignments before the program, or with `env` when
+/// variables are removed or the environment is cleared. A replacement for
+/// `argv[0]` isn't shown.
+///
+/// On Windows, the program and arguments are shown exactly as they'll be
+/// quoted on the command line, after `cd /d` and `set` commands for the
+/// working directory and environment. A call to [`env_clear`] isn't shown.
+///
+/// In both cases, non-UTF-8 data is lossily converted using the UTF-8
+/// replacement character.
+///
+/// # Examples
+///
+/// ```
+/// use std::process::Command;
+///
+/// let mut cmd = Command::new("grep");
+/// cmd.arg("-n").arg("two words");
+/// if cfg!(unix) {
+///     assert_eq!(cmd.to_string(), "grep -n 'two words'");
+/// }
+/// ```
+///
+/// [`current_dir`]: #method.current_dir
+/// [`env_clear`]: #method.env_clear
+#[unstable(feature = "command_display", issue = "0")]
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.inner, f)
+    }
+}
+
 impl AsInner<imp::Command> for Command {
     fn as_inner(&self) -> &imp::Command { &self.inner }
 }
@@ -816,6 +1227,170 @@ impl AsInnerMut<imp::Command> for Command {
     fn as_inner_mut(&mut self) -> &mut imp::Command { &mut self.inner }
 }
 
+/// The stage of starting a child process which failed, as reported by a
+/// [`SpawnError`].
+///
+/// [`SpawnError`]: struct.SpawnError.html
+#[unstable(feature = "spawn_error", issue = "0")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SpawnStage {
+    /// Setting up the child's stdio or other file descriptors.
+    Stdio,
+    /// Changing the child's user or group.
+    User,
+    /// Changing the child's working directory.
+    Chdir,
+    /// Running the closures registered with `before_exec`.
+    PreExec,
+    /// Moving the child into a new session or process group.
+    Session,
+    /// Executing the program itself, for instance because it wasn't found.
+    Exec,
+    /// Anything else, including failures in the parent and on platforms
+    /// which can't tell the stages apart.
+    Other,
+}
+
+/// An iterator over the arguments of a [`Command`], returned by
+/// [`Command::get_args`].
+///
+/// [`Command`]: struct.Command.html
+/// [`Command::get_args`]: struct.Command.html#method.get_args
+#[unstable(feature = "command_access", issue = "0")]
+#[derive(Debug)]
+pub struct CommandArgs<'a> {
+    inner: imp::CommandArgs<'a>,
+}
+
+#[unstable(feature = "command_access", issue = "0")]
+impl<'a> Iterator for CommandArgs<'a> {
+    type Item = &'a OsStr;
+    fn next(&mut self) -> Option<&'a OsStr> {
+        self.inner.next()
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[unstable(feature = "command_access", issue = "0")]
+impl<'a> ExactSizeIterator for CommandArgs<'a> {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+/// An iterator over the environment changes of a [`Command`], returned by
+/// [`Command::get_envs`].
+///
+/// [`Command`]: struct.Command.html
+/// [`Command::get_envs`]: struct.Command.html#method.get_envs
+#[unstable(feature = "command_access", issue = "0")]
+#[derive(Debug)]
+pub struct CommandEnvs<'a> {
+    inner: imp::CommandEnvs<'a>,
+}
+
+#[unstable(feature = "command_access", issue = "0")]
+impl<'a> Iterator for CommandEnvs<'a> {
+    type Item = (&'a OsStr, Option<&'a OsStr>);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[unstable(feature = "command_access", issue = "0")]
+impl<'a> ExactSizeIterator for CommandEnvs<'a> {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+/// An error starting a child process, returned by
+/// [`Command::spawn_with_context`].
+///
+/// It dereferences to the underlying [`io::Error`], and its `Display`
+/// output names the program and the stage which failed.
+///
+/// [`Command::spawn_with_context`]: struct.Command.html#method.spawn_with_context
+/// [`io::Error`]: ../io/struct.Error.html
+#[unstable(feature = "spawn_error", issue = "0")]
+#[derive(Debug)]
+pub struct SpawnError {
+    error: io::Error,
+    stage: SpawnStage,
+    program: OsString,
+}
+
+impl SpawnError {
+    /// Returns the stage at which starting the child failed.
+    #[unstable(feature = "spawn_error", issue = "0")]
+    pub fn stage(&self) -> SpawnStage {
+        self.stage
+    }
+
+    /// Returns the program which was being started.
+    #[unstable(feature = "spawn_error", issue = "0")]
+    pub fn program(&self) -> &OsStr {
+        &self.program
+    }
+
+    /// Returns the underlying error, without the context.
+    #[unstable(feature = "spawn_error", issue = "0")]
+    pub fn into_io_error(self) -> io::Error {
+        self.error
+    }
+}
+
+#[unstable(feature = "spawn_error", issue = "0")]
+impl Deref for SpawnError {
+    type Target = io::Error;
+
+    fn deref(&self) -> &io::Error {
+        &self.error
+    }
+}
+
+#[unstable(feature = "spawn_error", issue = "0")]
+impl fmt::Display for SpawnError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let stage = match self.stage {
+            SpawnStage::Stdio => " while setting up its file descriptors",
+            SpawnStage::User => " while changing its user",
+            SpawnStage::Chdir => " while changing its working directory",
+            SpawnStage::PreExec => " while running its pre-exec closures",
+            SpawnStage::Session => " while changing its session or process group",
+            SpawnStage::Exec => " while executing it",
+            SpawnStage::Other => "",
+        };
+        write!(f, "failed to spawn {:?}{}: {}", self.program, stage, self.error)
+    }
+}
+
+#[unstable(feature = "spawn_error", issue = "0")]
+impl Error for SpawnError {
+    fn description(&self) -> &str {
+        self.error.description()
+    }
+
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Keeps the context, so `?` in a function returning `io::Result` still
+/// says what failed; the kind is the underlying error's.
+#[unstable(feature = "spawn_error", issue = "0")]
+impl From<SpawnError> for io::Error {
+    fn from(err: SpawnError) -> io::Error {
+        io::Error::new(err.error.kind(), err)
+    }
+}
+
 /// The output of a finished process.
 ///
 /// This is returned in a Result by either the [`output`] method of a
@@ -866,6 +1441,88 @@ impl fmt::Debug for Output {
     }
 }
 
+/// The stream a chunk of output came from, passed to the callback of
+/// [`Command::output_with`].
+///
+/// [`Command::output_with`]: struct.Command.html#method.output_with
+#[unstable(feature = "process_output_with", issue = "0")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputStream {
+    /// The child's stdout.
+    Stdout,
+    /// The child's stderr.
+    Stderr,
+}
+
+/// The error wrapped by the [`io::Error`] returned from
+/// [`Command::output_limited`] when the child writes too much.
+///
+/// [`io::Error`]: ../io/struct.Error.html
+/// [`Command::output_limited`]: struct.Command.html#method.output_limited
+#[unstable(feature = "process_output_with", issue = "0")]
+#[derive(Debug)]
+pub struct OutputLimitExceeded {
+    output: Output,
+    stream: OutputStream,
+    limit: usize,
+}
+
+impl OutputLimitExceeded {
+    /// Returns the output read before the limit was reached, and the
+    /// child's exit status.
+    #[unstable(feature = "process_output_with", issue = "0")]
+    pub fn output(&self) -> &Output {
+        &self.output
+    }
+
+    /// Returns the output read before the limit was reached, and the
+    /// child's exit status.
+    #[unstable(feature = "process_output_with", issue = "0")]
+    pub fn into_output(self) -> Output {
+        self.output
+    }
+
+    /// Returns the stream which went over the limit.
+    #[unstable(feature = "process_output_with", issue = "0")]
+    pub fn stream(&self) -> OutputStream {
+        self.stream
+    }
+}
+
+#[unstable(feature = "process_output_with", issue = "0")]
+impl fmt::Display for OutputLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let stream = match self.stream {
+            OutputStream::Stdout => "stdout",
+            OutputStream::Stderr => "stderr",
+        };
+        write!(f, "child process wrote more than {} bytes to {}", self.limit, stream)
+    }
+}
+
+#[unstable(feature = "process_output_with", issue = "0")]
+impl Error for OutputLimitExceeded {
+    fn description(&self) -> &str {
+        "child process output limit exceeded"
+    }
+}
+
+// Reads `r` to the end, passing each chunk to `f`.
+fn read_chunks<R: Read>(mut r: R,
+                        stream: OutputStream,
+                        f: &mut dyn FnMut(OutputStream, &[u8]) -> io::Result<()>)
+                        -> io::Result<()> {
+    let mut buf = [0; 8 * 1024];
+    loop {
+        match r.read(&mut buf) {
+            Ok(0) => return Ok(()),
+            Ok(n) => f(stream, &buf[..n])?,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
+
 /// Describes what to do with a standard I/O stream for a child process when
 /// passed to the [`stdin`], [`stdout`], and [`stderr`] methods of [`Command`].
 ///
@@ -1242,6 +1899,16 @@ impl ExitCode {
     /// return the same codes (but will also `eprintln!` the error).
     #[unstable(feature = "process_exitcode_placeholder", issue = "48711")]
     pub const FAILURE: ExitCode = ExitCode(imp::ExitCode::FAILURE);
+
+    /// Shuts the process down with this exit code.
+    ///
+    /// This is the same as calling [`shutdown`] with `self`.
+    ///
+    /// [`shutdown`]: fn.shutdown.html
+    #[unstable(feature = "process_shutdown", issue = "0")]
+    pub fn exit_process(self) -> ! {
+        shutdown(self)
+    }
 }
 
 impl Child {
@@ -1366,6 +2033,57 @@ impl Child {
         Ok(self.handle.try_wait()?.map(ExitStatus))
     }
 
+    /// Waits for the child to exit for at most `timeout`, returning its exit
+    /// status if it did.
+    ///
+    /// This is [`wait_deadline`] with a deadline `timeout` from now. See its
+    /// documentation for more.
+    ///
+    /// [`wait_deadline`]: #method.wait_deadline
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(child_wait_timeout)]
+    /// use std::process::Command;
+    /// use std::time::Duration;
+    ///
+    /// let mut child = Command::new("sleep").arg("10").spawn().unwrap();
+    ///
+    /// if child.wait_timeout(Duration::from_secs(1)).unwrap().is_none() {
+    ///     child.kill().unwrap();
+    ///     child.wait().unwrap();
+    /// }
+    /// ```
+    #[unstable(feature = "child_wait_timeout", issue = "0")]
+    pub fn wait_timeout(&mut self, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.wait_deadline(deadline),
+            None => self.wait().map(Some),
+        }
+    }
+
+    /// Waits for the child to exit until `deadline` at the latest, returning
+    /// its exit status if it did.
+    ///
+    /// If the child has exited, then `Ok(Some(status))` is returned, and on
+    /// Unix the process ID is reaped, as with [`wait`]. If it's still running
+    /// at `deadline`, then `Ok(None)` is returned. A deadline in the past
+    /// checks once, like [`try_wait`]. Like [`wait`], this closes the child's
+    /// stdin first, if it's been captured.
+    ///
+    /// On Linux this blocks on a pidfd where the kernel has them, on macOS
+    /// and FreeBSD on a kqueue, and on Windows on the process handle. Other
+    /// platforms check the child at increasing intervals.
+    ///
+    /// [`wait`]: #method.wait
+    /// [`try_wait`]: #method.try_wait
+    #[unstable(feature = "child_wait_timeout", issue = "0")]
+    pub fn wait_deadline(&mut self, deadline: Instant) -> io::Result<Option<ExitStatus>> {
+        drop(self.stdin.take());
+        Ok(self.handle.wait_deadline(deadline)?.map(ExitStatus))
+    }
+
     /// Simultaneously waits for the child to exit and collect all remaining
     /// output on the stdout/stderr handles, returning an `Output`
     /// instance.
@@ -1546,6 +2264,74 @@ pub fn abort() -> ! {
     unsafe { ::sys::abort_internal() };
 }
 
+/// Registers a closure to run when the process shuts down.
+///
+/// Registered closures run in reverse order of registration, so teardown
+/// registered after setting something up runs before the teardown of what it
+/// depended on. They run when [`shutdown`] or [`exit`] is called, or when
+/// `main` returns, in each case before the standard output is flushed.
+///
+/// The closures run one at a time on the thread which shuts the process
+/// down, and only the first thread to do so runs them:
+///
+/// * Registering from one of them fails, as shutdown has begun.
+/// * A panic in one of them is reported as usual, and then the rest still
+///   run.
+/// * Calling [`exit`] from one of them skips the rest and exits straight
+///   away. Calling [`shutdown`] aborts the process.
+///
+/// Returns `Err` if shutdown has already begun, in which case `f` will never
+/// run.
+///
+/// [`shutdown`]: fn.shutdown.html
+/// [`exit`]: fn.exit.html
+///
+/// # Examples
+///
+/// ```
+/// #![feature(process_shutdown)]
+/// use std::process;
+///
+/// process::on_shutdown(|| println!("closing the database")).unwrap();
+/// process::on_shutdown(|| println!("no longer accepting connections")).unwrap();
+/// ```
+#[unstable(feature = "process_shutdown", issue = "0")]
+pub fn on_shutdown<F: FnOnce() + Send + 'static>(f: F) -> Result<(), ()> {
+    ::sys_common::on_shutdown(f)
+}
+
+/// Shuts the current process down with the specified exit code.
+///
+/// This runs the closures registered with [`on_shutdown`] in reverse order of
+/// registration, then the runtime's own cleanup, which flushes the standard
+/// output, and finally exits the process. As with [`exit`], destructors on
+/// the stack of this and every other thread don't run.
+///
+/// Only one shutdown can be in progress: calling this function again, from a
+/// registered closure or from another thread, aborts the process.
+///
+/// [`on_shutdown`]: fn.on_shutdown.html
+/// [`exit`]: fn.exit.html
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(process_exitcode_placeholder, process_shutdown)]
+/// use std::process::{self, ExitCode};
+///
+/// process::on_shutdown(|| eprintln!("shutting down")).unwrap();
+/// process::shutdown(ExitCode::SUCCESS);
+/// ```
+#[unstable(feature = "process_shutdown", issue = "0")]
+pub fn shutdown(code: ExitCode) -> ! {
+    static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+
+    if SHUTTING_DOWN.swap(true, Ordering::SeqCst) {
+        rtabort!("process::shutdown called while the process was already shutting down");
+    }
+    exit(code.0.as_i32())
+}
+
 /// Returns the OS-assigned process identifier associated with this process.
 ///
 /// # Examples
@@ -1625,9 +2411,9 @@ impl Termination for ExitCode {
 mod tests {
     use io::prelude::*;
 
-    use io::ErrorKind;
+    use io::{self, ErrorKind};
     use str;
-    use super::{Command, Output, Stdio};
+    use super::{Command, Output, OutputLimitExceeded, OutputStream, SpawnStage, Stdio};
 
     // FIXME(#10380) these tests should not all be ignored on android.
 
@@ -1764,6 +2550,87 @@ mod tests {
         assert!(Command::new("/bin/ls").uid(0).gid(0).spawn().is_err());
     }
 
+    #[test]
+    #[cfg_attr(any(target_os = "android", target_os = "fuchsia"), ignore)]
+    #[cfg(unix)]
+    fn send_signal_works() {
+        use os::unix::prelude::*;
+        use os::unix::process::ChildExt;
+        use libc;
+
+        let mut p = Command::new("sleep").arg("100").spawn().unwrap();
+        p.terminate().unwrap();
+        assert_eq!(p.wait().unwrap().signal(), Some(libc::SIGTERM));
+        assert_eq!(p.send_signal(libc::SIGTERM).unwrap_err().kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    #[cfg_attr(any(target_os = "android", target_os = "fuchsia"), ignore)]
+    #[cfg(unix)]
+    fn wait_state_change_works() {
+        use os::unix::prelude::*;
+        use os::unix::process::ChildExt;
+        use libc;
+
+        let mut p = Command::new("sleep").arg("100").spawn().unwrap();
+        p.send_signal(libc::SIGSTOP).unwrap();
+        let status = p.wait_state_change().unwrap();
+        assert_eq!(status.stopped_signal(), Some(libc::SIGSTOP));
+        assert_eq!((status.code(), status.signal()), (None, None));
+
+        p.send_signal(libc::SIGCONT).unwrap();
+        assert!(p.wait_state_change().unwrap().continued());
+
+        p.kill().unwrap();
+        let status = p.wait_state_change().unwrap();
+        assert_eq!(status.signal(), Some(libc::SIGKILL));
+        assert!(!status.core_dumped());
+        assert_eq!(p.wait().unwrap(), status);
+    }
+
+    #[test]
+    #[cfg_attr(target_os = "android", ignore)]
+    #[cfg(unix)]
+    fn on_exit_works() {
+        use os::unix::process::ChildExt;
+        use sync::mpsc;
+
+        let rx = Command::new("sh").arg("-c").arg("exit 3").spawn().unwrap().exit_receiver();
+        assert_eq!(rx.recv().unwrap().unwrap().code(), Some(3));
+
+        let (tx, rx) = mpsc::channel();
+        for i in 0..4 {
+            let tx = tx.clone();
+            let child = Command::new("sh").arg("-c").arg(format!("exit {}", i)).spawn().unwrap();
+            child.on_exit(move |status| tx.send(status.unwrap().code()).unwrap());
+        }
+        let mut codes = rx.iter().take(4).collect::<Vec<_>>();
+        codes.sort();
+        assert_eq!(codes, [Some(0), Some(1), Some(2), Some(3)]);
+    }
+
+    #[test]
+    #[cfg_attr(target_os = "android", ignore)]
+    #[cfg(unix)]
+    fn groups_works() {
+        use os::unix::prelude::*;
+        use libc;
+
+        // Only root can change its groups.
+        if unsafe { libc::getuid() != 0 } {
+            assert!(Command::new("/bin/sh").arg("-c").arg("true")
+                .groups(&[0]).spawn().is_err());
+            return
+        }
+        let output = Command::new("id").arg("-G").gid(0).groups(&[1, 2]).output().unwrap();
+        assert!(output.status.success());
+        let mut groups: Vec<String> = String::from_utf8(output.stdout).unwrap()
+            .split_whitespace().map(String::from).collect();
+        groups.sort();
+        groups.dedup();
+        assert_eq!(groups, ["0", "1", "2"]);
+    }
+
     #[test]
     #[cfg_attr(target_os = "android", ignore)]
     fn test_process_status() {
@@ -1790,6 +2657,135 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg_attr(target_os = "android", ignore)]
+    fn test_child_stdio_try_clone() {
+        let mut p = if cfg!(target_os = "windows") {
+            // Already in order, so this copies the input.
+            Command::new("sort").stdin(Stdio::piped()).stdout(Stdio::piped()).spawn().unwrap()
+        } else {
+            Command::new("cat").stdin(Stdio::piped()).stdout(Stdio::piped()).spawn().unwrap()
+        };
+        let mut stdin = p.stdin.take().unwrap();
+        let mut clone = stdin.try_clone().unwrap();
+        stdin.write_all(b"a\n").unwrap();
+        clone.write_all(b"b\n").unwrap();
+        drop(stdin);
+        drop(clone);
+        let mut out = String::new();
+        p.stdout.take().unwrap().try_clone().unwrap().read_to_string(&mut out).unwrap();
+        assert!(p.wait().unwrap().success());
+        assert_eq!(out.lines().map(str::trim).collect::<Vec<_>>(), ["a", "b"]);
+    }
+
+    #[test]
+    #[cfg_attr(target_os = "android", ignore)]
+    #[cfg(unix)]
+    fn test_child_stdout_nonblocking() {
+        let mut p = Command::new("cat")
+            .stdin(Stdio::piped()).stdout(Stdio::piped()).spawn().unwrap();
+        let mut stdout = p.stdout.take().unwrap();
+        stdout.set_nonblocking(true).unwrap();
+        let mut buf = [0; 8];
+        assert_eq!(stdout.read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+
+        p.stdin.take().unwrap().write_all(b"hello").unwrap();
+        stdout.set_nonblocking(false).unwrap();
+        let mut out = String::new();
+        stdout.read_to_string(&mut out).unwrap();
+        assert_eq!(out, "hello");
+        assert!(p.wait().unwrap().success());
+    }
+
+    #[test]
+    fn test_command_access() {
+        use ffi::OsStr;
+        use path::Path;
+
+        let mut cmd = Command::new("prog");
+        assert_eq!(cmd.get_program(), "prog");
+        assert_eq!(cmd.get_args().len(), 0);
+        assert_eq!(cmd.get_envs().len(), 0);
+        assert_eq!(cmd.get_current_dir(), None);
+
+        cmd.arg("a").args(&["b", "c"]).current_dir("dir");
+        cmd.env("SET", "1").env_remove("REMOVED");
+        assert_eq!(cmd.get_args().collect::<Vec<_>>(), ["a", "b", "c"]);
+        assert_eq!(cmd.get_current_dir(), Some(Path::new("dir")));
+        let envs = cmd.get_envs().collect::<Vec<_>>();
+        assert_eq!(envs, [(OsStr::new("REMOVED"), None),
+                          (OsStr::new("SET"), Some(OsStr::new("1")))]);
+
+        // Clearing the environment forgets the earlier changes.
+        cmd.env_clear().env("AFTER", "2");
+        let envs = cmd.get_envs().collect::<Vec<_>>();
+        assert_eq!(envs, [(OsStr::new("AFTER"), Some(OsStr::new("2")))]);
+    }
+
+    #[test]
+    fn test_command_display() {
+        let mut cmd = Command::new("prog");
+        cmd.arg("plain-arg=1").arg("two words").arg("it's").arg("");
+        if cfg!(target_os = "windows") {
+            assert_eq!(cmd.to_string(), r#""prog" plain-arg=1 "two words" it's """#);
+        } else {
+            assert_eq!(cmd.to_string(), r#"prog plain-arg=1 'two words' 'it'\''s' ''"#);
+        }
+
+        cmd.env("A", "1 2").current_dir("/some dir");
+        if cfg!(target_os = "windows") {
+            assert_eq!(cmd.to_string(), concat!(r#"cd /d "/some dir" && set "A=1 2" && "#,
+                                                r#""prog" plain-arg=1 "two words" it's """#));
+        } else {
+            assert_eq!(cmd.to_string(), concat!(r#"cd '/some dir' && A='1 2' "#,
+                                                r#"prog plain-arg=1 'two words' 'it'\''s' ''"#));
+        }
+
+        let mut cmd = Command::new("a=b");
+        cmd.env("C", "3");
+        if !cfg!(target_os = "windows") {
+            assert_eq!(cmd.to_string(), "C=3 'a=b'");
+            cmd.env_remove("B").arg("x");
+            assert_eq!(cmd.to_string(), r#"env -u B C=3 sh -c 'exec "$0" "$@"' 'a=b' x"#);
+            cmd.env_clear().env("D", "");
+            assert_eq!(cmd.to_string(), r#"env -i D= sh -c 'exec "$0" "$@"' 'a=b' x"#);
+        }
+
+        let mut cmd = Command::new("prog");
+        cmd.env("A", "1").env_remove("Z");
+        if !cfg!(target_os = "windows") {
+            assert_eq!(cmd.to_string(), "env -u Z A=1 prog");
+        }
+    }
+
+    #[test]
+    fn test_spawn_error_context() {
+        let program = "/no-binary-by-this-name-should-exist";
+        let err = Command::new(program).spawn_with_context().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert_eq!(err.program(), program);
+        assert!(err.to_string().contains(program));
+
+        // `spawn` returns the error without the context.
+        let err = Command::new(program).spawn().unwrap_err();
+        assert!(err.raw_os_error().is_some());
+    }
+
+    #[test]
+    #[cfg_attr(any(windows, target_os = "android", target_os = "fuchsia"), ignore)]
+    fn test_spawn_error_stage() {
+        let err = Command::new("/no-binary-by-this-name-should-exist")
+            .spawn_with_context().unwrap_err();
+        assert_eq!(err.stage(), SpawnStage::Exec);
+
+        // posix_spawn can't tell a missing directory from a missing program.
+        let err = Command::new("/bin/sh")
+            .current_dir("/no-directory-by-this-name-should-exist")
+            .spawn_with_context().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert!(err.stage() == SpawnStage::Chdir || err.stage() == SpawnStage::Other);
+    }
+
     #[test]
     #[cfg_attr(target_os = "android", ignore)]
     fn test_process_output_output() {
@@ -1806,6 +2802,91 @@ mod tests {
         assert_eq!(stderr, Vec::new());
     }
 
+    #[test]
+    #[cfg_attr(target_os = "android", ignore)]
+    fn test_process_combined_output() {
+        let mut cmd = if cfg!(target_os = "windows") {
+            let mut cmd = Command::new("cmd");
+            cmd.args(&["/C", "echo a& echo b 1>&2& echo c"]);
+            cmd
+        } else {
+            let mut cmd = Command::new("sh");
+            cmd.args(&["-c", "echo a; echo b >&2; echo c"]);
+            cmd
+        };
+        let Output { status, stdout, stderr } = cmd.combined_output().unwrap();
+        assert!(status.success());
+        // cmd keeps the space before the redirection.
+        let stdout = String::from_utf8(stdout).unwrap();
+        let lines: Vec<&str> = stdout.lines().map(str::trim).collect();
+        assert_eq!(lines, ["a", "b", "c"]);
+        assert!(stderr.is_empty());
+
+        // The command isn't left merging stderr for the next spawn.
+        let output = cmd.output().unwrap();
+        assert!(!output.stderr.is_empty());
+    }
+
+    #[test]
+    #[cfg_attr(target_os = "android", ignore)]
+    fn test_process_output_with() {
+        let mut cmd = if cfg!(target_os = "windows") {
+            let mut cmd = Command::new("cmd");
+            cmd.args(&["/C", "echo out& echo err 1>&2"]);
+            cmd
+        } else {
+            let mut cmd = Command::new("sh");
+            cmd.args(&["-c", "echo out; echo err >&2"]);
+            cmd
+        };
+        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+        let status = cmd.output_with(|stream, chunk| {
+            match stream {
+                OutputStream::Stdout => stdout.extend_from_slice(chunk),
+                OutputStream::Stderr => stderr.extend_from_slice(chunk),
+            }
+            Ok(())
+        }).unwrap();
+        assert!(status.success());
+        assert_eq!(String::from_utf8(stdout).unwrap().trim(), "out");
+        assert_eq!(String::from_utf8(stderr).unwrap().trim(), "err");
+
+        let err = cmd.output_with(|_, _| Err(io::Error::new(ErrorKind::Other, "stop")))
+            .unwrap_err();
+        assert_eq!(err.to_string(), "stop");
+    }
+
+    #[test]
+    #[cfg_attr(target_os = "android", ignore)]
+    fn test_process_output_limited() {
+        // `yes` writes forever, so this only returns because reading stops.
+        let mut cmd = if cfg!(target_os = "windows") {
+            let mut cmd = Command::new("cmd");
+            cmd.args(&["/C", "for /L %i in (1,1,1000) do @echo y"]);
+            cmd
+        } else {
+            Command::new("yes")
+        };
+        let err = cmd.output_limited(1000).unwrap_err();
+        let err = err.into_inner().unwrap().downcast::<OutputLimitExceeded>().unwrap();
+        assert_eq!(err.stream(), OutputStream::Stdout);
+        let output = err.into_output();
+        // cmd carries on when its writes fail, but `yes` gets `SIGPIPE`.
+        if !cfg!(target_os = "windows") {
+            assert!(!output.status.success());
+        }
+        assert_eq!(output.stdout.len(), 1000);
+        assert!(output.stdout.starts_with(b"y"));
+
+        let output = if cfg!(target_os = "windows") {
+            Command::new("cmd").args(&["/C", "echo hello"]).output_limited(1000).unwrap()
+        } else {
+            Command::new("echo").arg("hello").output_limited(1000).unwrap()
+        };
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8(output.stdout).unwrap().trim(), "hello");
+    }
+
     #[test]
     #[cfg_attr(target_os = "android", ignore)]
     fn test_process_output_error() {
@@ -1844,6 +2925,29 @@ mod tests {
         assert!(prog.wait().unwrap().code() == Some(1));
     }
 
+    #[test]
+    #[cfg_attr(target_os = "android", ignore)]
+    fn test_wait_timeout() {
+        use time::Duration;
+
+        let mut prog = if cfg!(target_os = "windows") {
+            Command::new("cmd").args(&["/C", "ping -n 11 127.0.0.1 > nul"]).spawn().unwrap()
+        } else {
+            Command::new("sleep").arg("10").spawn().unwrap()
+        };
+        assert!(prog.wait_timeout(Duration::from_millis(50)).unwrap().is_none());
+        prog.kill().unwrap();
+        assert!(prog.wait_timeout(Duration::from_secs(10)).unwrap().is_some());
+
+        let mut prog = if cfg!(target_os = "windows") {
+            Command::new("cmd").args(&["/C", "exit 1"]).spawn().unwrap()
+        } else {
+            Command::new("false").spawn().unwrap()
+        };
+        let status = prog.wait_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(status.and_then(|s| s.code()), Some(1));
+    }
+
     #[test]
     #[cfg_attr(target_os = "android", ignore)]
     fn test_wait_with_output_once() {
@@ -2037,6 +3141,64 @@ mod tests {
         assert!(events > 0);
     }
 
+    #[test]
+    #[cfg(windows)]
+    fn test_job_object_kill_tree() {
+        use os::windows::process::{ChildExt, CommandExt, JobLimits};
+
+        // A child that doesn't get to run to completion, with a grandchild
+        // that would outlive it if it weren't for the job.
+        let mut limits = JobLimits::new();
+        limits.kill_on_close(true);
+        let mut child = Command::new("cmd")
+            .args(&["/c", "ping -n 60 127.0.0.1 & ping -n 60 127.0.0.1"])
+            .stdout(Stdio::null())
+            .job_object(limits)
+            .spawn().unwrap();
+        child.kill_tree().unwrap();
+        assert!(!child.wait().unwrap().success());
+
+        let mut plain = Command::new("cmd").arg("/c").arg("exit").spawn().unwrap();
+        assert_eq!(plain.kill_tree().unwrap_err().kind(), ErrorKind::InvalidInput);
+        plain.wait().unwrap();
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn test_raw_arg_reaches_cmd_verbatim() {
+        use os::windows::process::CommandExt;
+
+        // cmd strips the outermost quotes and runs the rest as is, which
+        // only works if they aren't escaped the way `arg` would.
+        let output = Command::new("cmd")
+            .arg("/C")
+            .raw_arg(r#""echo "a b" & exit 3""#)
+            .output().unwrap();
+        assert_eq!(output.status.code(), Some(3));
+        assert_eq!(str::from_utf8(&output.stdout).unwrap().trim(), r#""a b""#);
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn test_kill_on_drop() {
+        use os::windows::process::CommandExt;
+        use time::{Duration, Instant};
+
+        // The pipe only reaches its end once cmd and ping have both been
+        // terminated; left alone, ping would run for a minute.
+        let mut child = Command::new("cmd")
+            .args(&["/c", "ping -n 60 127.0.0.1"])
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn().unwrap();
+        let mut stdout = child.stdout.take().unwrap();
+        let start = Instant::now();
+        drop(child);
+        let mut out = Vec::new();
+        stdout.read_to_end(&mut out).unwrap();
+        assert!(start.elapsed() < Duration::from_secs(30));
+    }
+
     #[test]
     fn test_command_implements_send() {
         fn take_send_type<T: Send>(_: T) {}
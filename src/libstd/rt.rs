@@ -16,6 +16,36 @@
 // Re-export some of our utilities which are expected by other crates.
 pub use panicking::{begin_panic, begin_panic_fmt, update_panic_count};
 
+/// A function registered with `register_init!`, laid out the way the runtime
+/// reads it back out of the linker section the registrations are gathered in.
+///
+/// It's aligned to its own size, so that whatever padding a linker puts
+/// between registrations to align them is a whole number of entries.
+#[repr(C, align(16))]
+#[unstable(feature = "rt_init_hooks", issue = "0")]
+pub struct InitHook {
+    /// The function to call, or `None` for the placeholders and padding the
+    /// runtime skips.
+    pub f: Option<fn()>,
+    /// Hooks run in ascending order of priority.
+    pub priority: i32,
+}
+
+/// Runs the functions registered with `register_init!`, lowest priority
+/// first. Hooks with the same priority run in an unspecified order.
+#[cfg(not(test))]
+fn run_init_hooks() {
+    use sys_common;
+
+    let mut hooks = sys_common::init_hooks::hooks().iter()
+        .filter_map(|hook| hook.f.map(|f| (hook.priority, f)))
+        .collect::<Vec<_>>();
+    hooks.sort_by_key(|&(priority, _)| priority);
+    for (_, f) in hooks {
+        f();
+    }
+}
+
 // To reduce the generated code of the new `lang_start`, this function is doing
 // the real work.
 #[cfg(not(test))]
@@ -43,13 +73,21 @@ fn lang_start_internal(main: &(dyn Fn() -> i32 + Sync + ::panic::RefUnwindSafe),
         // Store our args if necessary in a squirreled away location
         sys::args::init(argc, argv);
 
-        // Let's run some code!
+        // Let's run some code! Init hooks run first, and a panic in one of
+        // them is reported just like a panic in `main`, which then never
+        // runs.
         #[cfg(feature = "backtrace")]
         let exit_code = panic::catch_unwind(|| {
-            ::sys_common::backtrace::__rust_begin_short_backtrace(move || main())
+            ::sys_common::backtrace::__rust_begin_short_backtrace(move || {
+                run_init_hooks();
+                main()
+            })
         });
         #[cfg(not(feature = "backtrace"))]
-        let exit_code = panic::catch_unwind(move || main());
+        let exit_code = panic::catch_unwind(move || {
+            run_init_hooks();
+            main()
+        });
 
         sys_common::cleanup();
         exit_code.unwrap_or(101) as isize
@@ -3,7 +3,7 @@ use ffi::CStr;
 use intrinsics;
 use io;
 use libc;
-use sys_common::backtrace::Frame;
+use sys_common::backtrace::{Frame, Module};
 use unwind as uw;
 
 pub struct BacktraceContext;
@@ -112,3 +112,7 @@ struct Dl_info {
 extern "C" {
     fn dladdr(addr: *const libc::c_void, info: *mut Dl_info) -> libc::c_int;
 }
+
+pub fn modules() -> io::Result<Vec<Module>> {
+    Err(io::Error::new(io::ErrorKind::Other, "Not implemented"))
+}
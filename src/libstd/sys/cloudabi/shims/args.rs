@@ -6,6 +6,10 @@ impl Args {
     pub fn inner_debug(&self) -> &[OsString] {
         &[]
     }
+
+    pub fn get(&self, _i: usize) -> Option<OsString> {
+        None
+    }
 }
 
 impl Iterator for Args {
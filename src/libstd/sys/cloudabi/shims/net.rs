@@ -1,6 +1,6 @@
 use fmt;
 use io;
-use net::{Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr};
+use net::{Ipv4Addr, Ipv6Addr, NameInfoFlag, Shutdown, SocketAddr};
 use time::Duration;
 use sys::{unsupported, Void};
 use convert::TryFrom;
@@ -303,3 +303,8 @@ impl<'a> TryFrom<(&'a str, u16)> for LookupHost {
         unsupported()
     }
 }
+
+pub fn lookup_name_info(_addr: &SocketAddr, _flags: &[NameInfoFlag])
+                        -> io::Result<(String, String)> {
+    unsupported()
+}
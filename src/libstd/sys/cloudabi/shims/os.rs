@@ -20,6 +20,13 @@ pub fn env() -> Env {
     iter::empty()
 }
 
+pub use sys_common::env_snapshot::{EnvSnapshot, EnvSnapshotIter};
+
+/// Returns a snapshot of the environment variables of the current process.
+pub fn env_snapshot() -> EnvSnapshot {
+    EnvSnapshot::new(env())
+}
+
 pub fn getenv(_: &OsStr) -> io::Result<Option<OsString>> {
     Ok(None)
 }
@@ -12,6 +12,14 @@ impl AnonPipe {
         match self.0 {}
     }
 
+    pub fn try_clone(&self) -> io::Result<AnonPipe> {
+        match self.0 {}
+    }
+
+    pub fn set_nonblocking(&self, _nonblocking: bool) -> io::Result<()> {
+        match self.0 {}
+    }
+
     pub fn diverge(&self) -> ! {
         match self.0 {}
     }
@@ -20,3 +28,11 @@ impl AnonPipe {
 pub fn read2(p1: AnonPipe, _v1: &mut Vec<u8>, _p2: AnonPipe, _v2: &mut Vec<u8>) -> io::Result<()> {
     match p1.0 {}
 }
+
+pub fn read2_with(
+    p1: AnonPipe,
+    _p2: AnonPipe,
+    _f: &mut dyn FnMut(usize, &[u8]) -> io::Result<()>,
+) -> io::Result<()> {
+    match p1.0 {}
+}
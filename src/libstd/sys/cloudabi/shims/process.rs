@@ -1,17 +1,56 @@
-use ffi::OsStr;
+use ffi::{OsStr, OsString};
 use fmt;
 use io;
+use path::Path;
+use slice;
 use sys::fs::File;
 use sys::pipe::AnonPipe;
 use sys::{unsupported, Void};
-use sys_common::process::{CommandEnv, DefaultEnvKey};
+use sys_common::process::{self, CommandEnv, DefaultEnvKey};
+use time::Instant;
 
 ////////////////////////////////////////////////////////////////////////////////
 // Command
 ////////////////////////////////////////////////////////////////////////////////
 
+// Nothing can be spawned, but what the command was given is kept so that
+// it can still be inspected.
 pub struct Command {
+    program: OsString,
+    args: Vec<OsString>,
     env: CommandEnv<DefaultEnvKey>,
+    cwd: Option<OsString>,
+}
+
+pub type CommandEnvs<'a> = process::CommandEnvs<'a, DefaultEnvKey>;
+
+/// How environment variable names are compared, and kept in order.
+pub type EnvKey = DefaultEnvKey;
+
+pub struct CommandArgs<'a> {
+    iter: slice::Iter<'a, OsString>,
+}
+
+impl<'a> Iterator for CommandArgs<'a> {
+    type Item = &'a OsStr;
+    fn next(&mut self) -> Option<&'a OsStr> {
+        self.iter.next().map(|arg| &**arg)
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for CommandArgs<'a> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<'a> fmt::Debug for CommandArgs<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter.clone()).finish()
+    }
 }
 
 // passed back to std::process with the pipes connected to the child, if any
@@ -29,19 +68,42 @@ pub enum Stdio {
 }
 
 impl Command {
-    pub fn new(_program: &OsStr) -> Command {
+    pub fn new(program: &OsStr) -> Command {
         Command {
+            program: program.to_os_string(),
+            args: Vec::new(),
             env: Default::default(),
+            cwd: None,
         }
     }
 
-    pub fn arg(&mut self, _arg: &OsStr) {}
+    pub fn arg(&mut self, arg: &OsStr) {
+        self.args.push(arg.to_os_string());
+    }
 
     pub fn env_mut(&mut self) -> &mut CommandEnv<DefaultEnvKey> {
         &mut self.env
     }
 
-    pub fn cwd(&mut self, _dir: &OsStr) {}
+    pub fn cwd(&mut self, dir: &OsStr) {
+        self.cwd = Some(dir.to_os_string());
+    }
+
+    pub fn get_program(&self) -> &OsStr {
+        &self.program
+    }
+
+    pub fn get_args(&self) -> CommandArgs {
+        CommandArgs { iter: self.args.iter() }
+    }
+
+    pub fn get_envs(&self) -> CommandEnvs {
+        self.env.iter()
+    }
+
+    pub fn get_current_dir(&self) -> Option<&Path> {
+        self.cwd.as_ref().map(|cwd| Path::new(cwd))
+    }
 
     pub fn stdin(&mut self, _stdin: Stdio) {}
 
@@ -49,6 +111,8 @@ impl Command {
 
     pub fn stderr(&mut self, _stderr: Stdio) {}
 
+    pub fn merge_stderr(&mut self, _merge: bool) {}
+
     pub fn spawn(
         &mut self,
         _default: Stdio,
@@ -76,6 +140,13 @@ impl fmt::Debug for Command {
     }
 }
 
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cwd = self.cwd.as_ref().map(|cwd| &**cwd);
+        process::fmt_shell_command(f, &self.program, self.get_args(), &self.env, cwd)
+    }
+}
+
 pub struct ExitStatus(Void);
 
 impl ExitStatus {
@@ -146,4 +217,8 @@ impl Process {
     pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
         match self.0 {}
     }
+
+    pub fn wait_deadline(&mut self, _deadline: Instant) -> io::Result<Option<ExitStatus>> {
+        match self.0 {}
+    }
 }
@@ -0,0 +1,49 @@
+use io;
+use sys::fd::FileDesc;
+use sys::unsupported;
+
+////////////////////////////////////////////////////////////////////////////////
+// Anonymous pipes
+////////////////////////////////////////////////////////////////////////////////
+
+// The VFS has no pipe driver. Pipes only ever connect us to child processes,
+// which don't exist here either, so nothing is lost.
+pub struct AnonPipe(FileDesc);
+
+pub fn anon_pipe() -> io::Result<(AnonPipe, AnonPipe)> {
+    unsupported()
+}
+
+impl AnonPipe {
+    pub fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+
+    pub fn write(&self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    pub fn try_clone(&self) -> io::Result<AnonPipe> {
+        self.0.duplicate().map(AnonPipe)
+    }
+
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.0.set_nonblocking(nonblocking)
+    }
+
+    pub fn fd(&self) -> &FileDesc { &self.0 }
+    pub fn into_fd(self) -> FileDesc { self.0 }
+}
+
+pub fn read2(_p1: AnonPipe,
+             _v1: &mut Vec<u8>,
+             _p2: AnonPipe,
+             _v2: &mut Vec<u8>) -> io::Result<()> {
+    unsupported()
+}
+
+pub fn read2_with(_p1: AnonPipe,
+                 _p2: AnonPipe,
+                 _f: &mut dyn FnMut(usize, &[u8]) -> io::Result<()>) -> io::Result<()> {
+    unsupported()
+}
@@ -0,0 +1,8 @@
+pub use self::process_common::{Command, CommandArgs, CommandEnvs, ExitStatus, ExitCode};
+pub use self::process_common::{EnvKey, Stdio, StdioPipes};
+pub use self::process_inner::Process;
+
+#[path = "../../unix/process/process_common.rs"]
+mod process_common;
+#[path = "process_unsupported.rs"]
+mod process_inner;
@@ -0,0 +1,61 @@
+use io;
+use libc::c_int;
+use sys::process::process_common::*;
+use sys::{unsupported, unsupported_err};
+use time::Instant;
+
+////////////////////////////////////////////////////////////////////////////////
+// Command
+////////////////////////////////////////////////////////////////////////////////
+
+// The whole firmware is a single program, there's nothing to spawn.
+impl Command {
+    pub fn spawn(&mut self, _default: Stdio, _needs_stdin: bool)
+                 -> io::Result<(Process, StdioPipes)> {
+        unsupported()
+    }
+
+    pub fn exec(&mut self, _default: Stdio) -> io::Error {
+        unsupported_err()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Processes
+////////////////////////////////////////////////////////////////////////////////
+
+pub enum Process {}
+
+impl Process {
+    pub fn id(&self) -> u32 {
+        match *self {}
+    }
+
+    pub fn kill(&mut self) -> io::Result<()> {
+        match *self {}
+    }
+
+    pub fn send_signal(&mut self, _signal: c_int) -> io::Result<()> {
+        match *self {}
+    }
+
+    pub fn wait(&mut self) -> io::Result<ExitStatus> {
+        match *self {}
+    }
+
+    pub fn on_exit(self, _f: Box<dyn FnOnce(io::Result<ExitStatus>) + Send>) {
+        match self {}
+    }
+
+    pub fn wait_state_change(&mut self) -> io::Result<ExitStatus> {
+        match *self {}
+    }
+
+    pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
+        match *self {}
+    }
+
+    pub fn wait_deadline(&mut self, _deadline: Instant) -> io::Result<Option<ExitStatus>> {
+        match *self {}
+    }
+}
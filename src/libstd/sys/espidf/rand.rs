@@ -0,0 +1,19 @@
+use mem;
+use slice;
+
+extern {
+    // Reads the hardware RNG, which is only truly random while the radio
+    // (or the bootloader's entropy source) is running, but is always good
+    // enough for hash map keys.
+    fn esp_fill_random(buf: *mut u8, len: usize);
+}
+
+pub fn hashmap_random_keys() -> (u64, u64) {
+    let mut v = (0, 0);
+    unsafe {
+        let view = slice::from_raw_parts_mut(&mut v as *mut _ as *mut u8,
+                                             mem::size_of_val(&v));
+        esp_fill_random(view.as_mut_ptr(), view.len());
+    }
+    return v
+}
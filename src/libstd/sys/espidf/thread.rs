@@ -0,0 +1,164 @@
+//! Threads as FreeRTOS tasks.
+//!
+//! A FreeRTOS task can't be joined and must delete itself rather than return
+//! from its entry point. Each thread therefore gets a binary semaphore which
+//! the task gives right before deleting itself; joining takes it. Whichever
+//! of the task and the `Thread` handle lets go of the semaphore last deletes
+//! it, so detaching is free.
+
+use boxed::FnBox;
+use cmp;
+use ffi::CStr;
+use io;
+use libc::{self, c_char, c_int, c_uint, c_void};
+use ptr;
+use sync::Arc;
+use time::Duration;
+
+use sys_common::thread::*;
+
+// Task stacks are carved out of a small internal RAM and never grow, so this
+// is a lot less than elsewhere. `RUST_MIN_STACK` still applies.
+pub const DEFAULT_MIN_STACK_SIZE: usize = 8 * 1024;
+
+const MIN_TASK_STACK_SIZE: usize = 2 * 1024;
+
+type TaskHandle_t = *mut c_void;
+type QueueHandle_t = *mut c_void;
+type BaseType_t = c_int;
+type UBaseType_t = c_uint;
+type TickType_t = u32;
+
+const pdPASS: BaseType_t = 1;
+const tskNO_AFFINITY: BaseType_t = 0x7fff_ffff;
+const portMAX_DELAY: TickType_t = 0xffff_ffff;
+const queueQUEUE_TYPE_BINARY_SEMAPHORE: u8 = 3;
+const queueSEND_TO_BACK: BaseType_t = 0;
+
+extern {
+    // ESP-IDF measures task stacks in bytes, not words.
+    fn xTaskCreatePinnedToCore(entry: extern fn(*mut c_void), name: *const c_char,
+                               stack_depth: u32, arg: *mut c_void,
+                               priority: UBaseType_t, handle: *mut TaskHandle_t,
+                               core_id: BaseType_t) -> BaseType_t;
+    fn vTaskDelete(task: TaskHandle_t);
+    fn vTaskDelay(ticks: TickType_t);
+    fn uxTaskPriorityGet(task: TaskHandle_t) -> UBaseType_t;
+
+    // The semaphore API is all macros over these.
+    fn xQueueGenericCreate(len: UBaseType_t, item_size: UBaseType_t,
+                           queue_type: u8) -> QueueHandle_t;
+    fn xQueueGenericSend(queue: QueueHandle_t, item: *const c_void,
+                         wait: TickType_t, position: BaseType_t) -> BaseType_t;
+    fn xQueueSemaphoreTake(queue: QueueHandle_t, wait: TickType_t) -> BaseType_t;
+    fn vQueueDelete(queue: QueueHandle_t);
+}
+
+/// Given by the task once its closure has run.
+struct Exited(QueueHandle_t);
+
+unsafe impl Send for Exited {}
+unsafe impl Sync for Exited {}
+
+impl Drop for Exited {
+    fn drop(&mut self) {
+        unsafe { vQueueDelete(self.0) }
+    }
+}
+
+struct Packet {
+    main: Box<dyn FnBox()>,
+    exited: Arc<Exited>,
+}
+
+pub struct Thread {
+    id: TaskHandle_t,
+    exited: Arc<Exited>,
+}
+
+// The task handle is only ever used as an identifier.
+unsafe impl Send for Thread {}
+unsafe impl Sync for Thread {}
+
+impl Thread {
+    // unsafe: see thread::Builder::spawn_unchecked for safety requirements
+    pub unsafe fn new(stack: usize, p: Box<dyn FnBox()>)
+                          -> io::Result<Thread> {
+        let sem = xQueueGenericCreate(1, 0, queueQUEUE_TYPE_BINARY_SEMAPHORE);
+        if sem.is_null() {
+            return Err(io::Error::from_raw_os_error(libc::ENOMEM))
+        }
+        let exited = Arc::new(Exited(sem));
+
+        let stack_size = cmp::max(stack, MIN_TASK_STACK_SIZE);
+        let packet = Box::into_raw(Box::new(Packet {
+            main: p,
+            exited: exited.clone(),
+        }));
+
+        // Run at our own priority on whichever core is free, which is what
+        // a pthread with default attributes gets too.
+        let mut id = ptr::null_mut();
+        let ret = xTaskCreatePinnedToCore(task_start, b"rust\0".as_ptr() as *const c_char,
+                                          stack_size as u32, packet as *mut c_void,
+                                          uxTaskPriorityGet(ptr::null_mut()),
+                                          &mut id, tskNO_AFFINITY);
+        return if ret != pdPASS {
+            drop(Box::from_raw(packet));
+            Err(io::Error::from_raw_os_error(libc::ENOMEM))
+        } else {
+            Ok(Thread { id, exited })
+        };
+
+        extern fn task_start(packet: *mut c_void) {
+            unsafe {
+                let Packet { main, exited } = *Box::from_raw(packet as *mut Packet);
+                start_thread(Box::into_raw(Box::new(main)) as *mut u8);
+                xQueueGenericSend(exited.0, ptr::null(), 0, queueSEND_TO_BACK);
+                drop(exited);
+                vTaskDelete(ptr::null_mut());
+            }
+        }
+    }
+
+    pub fn yield_now() {
+        // A zero tick delay is FreeRTOS' documented way to yield.
+        unsafe { vTaskDelay(0) }
+    }
+
+    pub fn set_name(_name: &CStr) {
+        // Task names are fixed when the task is created.
+    }
+
+    pub fn sleep(dur: Duration) {
+        // Round up to whole microseconds so we never sleep short.
+        let mut micros = dur.as_secs()
+            .saturating_mul(1_000_000)
+            .saturating_add((dur.subsec_nanos() as u64 + 999) / 1_000);
+
+        while micros > 0 {
+            let amt = cmp::min(micros, libc::useconds_t::max_value() as u64);
+            unsafe {
+                libc::usleep(amt as libc::useconds_t);
+            }
+            micros -= amt;
+        }
+    }
+
+    pub fn join(self) {
+        let ret = unsafe { xQueueSemaphoreTake(self.exited.0, portMAX_DELAY) };
+        assert!(ret == pdPASS, "failed to join thread");
+    }
+
+    pub fn id(&self) -> TaskHandle_t { self.id }
+
+    pub fn into_id(self) -> TaskHandle_t { self.id }
+}
+
+#[cfg_attr(test, allow(dead_code))]
+pub mod guard {
+    use ops::Range;
+    pub type Guard = Range<usize>;
+    pub unsafe fn current() -> Option<Guard> { None }
+    pub unsafe fn init() -> Option<Guard> { None }
+}
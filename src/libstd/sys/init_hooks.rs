@@ -0,0 +1,144 @@
+//! The linker sections the functions registered with `register_init!` are
+//! gathered in, see `sys_common::init_hooks`.
+//!
+//! How the bounds of a section are found depends on the object format:
+//!
+//! * ELF linkers define `__start_<name>` and `__stop_<name>` for any section
+//!   whose name is a valid C identifier.
+//! * Mach-O linkers define `section$start$<segment>$<section>` and
+//!   `section$end$<segment>$<section>`.
+//! * PE linkers sort sections named `<name>$<suffix>` by their suffix before
+//!   merging them, so statics in `$a` and `$z` bracket the entries in `$m`.
+//!   Linkers may pad between the groups with zeroes to align them, which,
+//!   since `InitHook` is aligned to its size, read as whole entries without
+//!   a function.
+//!
+//! Elsewhere there is no array to read, and `register_init!` is a compile
+//! error instead of a registration that silently never runs.
+
+#![allow(dead_code)] // not used in libstd's own tests
+
+/// Wraps `$item`, the static built by `register_init!`, in the attributes
+/// placing it in the section found by `section`.
+#[macro_export]
+#[doc(hidden)]
+#[unstable(feature = "rt_init_hooks", issue = "0")]
+macro_rules! __rust_init_hook {
+    ($item:item) => {
+        #[cfg(not(any(windows,
+                      target_os = "macos",
+                      target_os = "ios",
+                      target_os = "linux",
+                      target_os = "android",
+                      target_os = "freebsd",
+                      target_os = "dragonfly",
+                      target_os = "netbsd",
+                      target_os = "openbsd",
+                      target_os = "solaris",
+                      target_os = "fuchsia",
+                      target_os = "haiku",
+                      target_os = "redox")))]
+        compile_error!("`register_init!` is not supported on this platform");
+
+        #[used]
+        #[cfg_attr(any(target_os = "macos", target_os = "ios"),
+                   link_section = "__DATA,__rust_init")]
+        #[cfg_attr(windows, link_section = ".rust_init$m")]
+        #[cfg_attr(not(any(windows, target_os = "macos", target_os = "ios")),
+                   link_section = "rust_init_hooks")]
+        $item
+    };
+}
+
+/// Returns the first entry of the section the hooks are gathered in and the
+/// end of it, or `None` where there's no such section.
+pub use self::imp::section;
+
+#[cfg(any(target_os = "linux",
+          target_os = "android",
+          target_os = "freebsd",
+          target_os = "dragonfly",
+          target_os = "netbsd",
+          target_os = "openbsd",
+          target_os = "solaris",
+          target_os = "fuchsia",
+          target_os = "haiku",
+          target_os = "redox"))]
+mod imp {
+    use rt::InitHook;
+
+    extern {
+        static __start_rust_init_hooks: InitHook;
+        static __stop_rust_init_hooks: InitHook;
+    }
+
+    // Makes sure the section exists, and with it the symbols above, even when
+    // nothing registers a hook.
+    #[used]
+    #[link_section = "rust_init_hooks"]
+    static NO_HOOK: InitHook = InitHook { f: None, priority: 0 };
+
+    pub fn section() -> Option<(&'static InitHook, &'static InitHook)> {
+        unsafe { Some((&__start_rust_init_hooks, &__stop_rust_init_hooks)) }
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+mod imp {
+    use rt::InitHook;
+
+    extern {
+        #[link_name = "\x01section$start$__DATA$__rust_init"]
+        static START: InitHook;
+        #[link_name = "\x01section$end$__DATA$__rust_init"]
+        static END: InitHook;
+    }
+
+    // Makes sure the section exists, and with it the symbols above, even when
+    // nothing registers a hook.
+    #[used]
+    #[link_section = "__DATA,__rust_init"]
+    static NO_HOOK: InitHook = InitHook { f: None, priority: 0 };
+
+    pub fn section() -> Option<(&'static InitHook, &'static InitHook)> {
+        unsafe { Some((&START, &END)) }
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use rt::InitHook;
+
+    #[used]
+    #[link_section = ".rust_init$a"]
+    static START: InitHook = InitHook { f: None, priority: 0 };
+
+    #[used]
+    #[link_section = ".rust_init$z"]
+    static END: InitHook = InitHook { f: None, priority: 0 };
+
+    pub fn section() -> Option<(&'static InitHook, &'static InitHook)> {
+        Some((&START, &END))
+    }
+}
+
+#[cfg(not(any(windows,
+              target_os = "macos",
+              target_os = "ios",
+              target_os = "linux",
+              target_os = "android",
+              target_os = "freebsd",
+              target_os = "dragonfly",
+              target_os = "netbsd",
+              target_os = "openbsd",
+              target_os = "solaris",
+              target_os = "fuchsia",
+              target_os = "haiku",
+              target_os = "redox")))]
+mod imp {
+    use rt::InitHook;
+
+    pub fn section() -> Option<(&'static InitHook, &'static InitHook)> {
+        None
+    }
+}
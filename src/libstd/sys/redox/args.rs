@@ -29,6 +29,10 @@ impl Args {
     pub fn inner_debug(&self) -> &[OsString] {
         self.iter.as_slice()
     }
+
+    pub fn get(&self, i: usize) -> Option<OsString> {
+        self.iter.as_slice().get(i).cloned()
+    }
 }
 
 impl Iterator for Args {
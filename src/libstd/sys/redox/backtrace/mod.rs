@@ -1,5 +1,8 @@
 /// See sys/unix/backtrace/mod.rs for an explanation of the method used here.
 
+use io;
+use sys_common::backtrace::Module;
+
 pub use self::tracing::unwind_backtrace;
 pub use self::printing::{foreach_symbol_fileline, resolve_symname};
 
@@ -30,3 +33,7 @@ pub mod gnu {
 }
 
 pub struct BacktraceContext;
+
+pub fn modules() -> io::Result<Vec<Module>> {
+    Err(io::Error::new(io::ErrorKind::Other, "Not implemented"))
+}
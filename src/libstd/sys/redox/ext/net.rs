@@ -2,11 +2,13 @@
 
 //! Unix-specific networking functionality
 
+use ffi::OsStr;
 use fmt;
 use io::{self, Error, ErrorKind, Initializer};
 use net::Shutdown;
+use os::unix::ffi::OsStrExt;
 use os::unix::io::{RawFd, AsRawFd, FromRawFd, IntoRawFd};
-use path::Path;
+use path::{Path, PathBuf};
 use time::Duration;
 use sys::{cvt, fd::FileDesc, syscall};
 
@@ -28,9 +30,26 @@ use sys::{cvt, fd::FileDesc, syscall};
 /// ```
 #[derive(Clone)]
 #[stable(feature = "unix_socket_redox", since = "1.29")]
-pub struct SocketAddr(());
+pub struct SocketAddr(Option<PathBuf>);
 
 impl SocketAddr {
+    /// Looks up the address a `chan:` handle was opened with.
+    ///
+    /// Redox reports it as the full scheme path, e.g. `chan:/tmp/sock`, and
+    /// anonymous channels (such as those from `UnixStream::pair`) have an
+    /// empty path.
+    fn from_fd(fd: &FileDesc) -> io::Result<SocketAddr> {
+        let mut buf = [0; 4096];
+        let count = cvt(syscall::fpath(fd.raw(), &mut buf))?;
+        let path = &buf[..count];
+        let path = if path.starts_with(b"chan:") { &path[5..] } else { path };
+        if path.is_empty() {
+            Ok(SocketAddr(None))
+        } else {
+            Ok(SocketAddr(Some(PathBuf::from(OsStr::from_bytes(path)))))
+        }
+    }
+
     /// Returns the contents of this address if it is a `pathname` address.
     ///
     /// # Examples
@@ -57,7 +76,7 @@ impl SocketAddr {
     /// ```
     #[stable(feature = "unix_socket_redox", since = "1.29")]
     pub fn as_pathname(&self) -> Option<&Path> {
-        None
+        self.0.as_ref().map(|p| &**p)
     }
 
     /// Returns `true` if the address is unnamed.
@@ -85,13 +104,16 @@ impl SocketAddr {
     /// ```
     #[stable(feature = "unix_socket_redox", since = "1.29")]
     pub fn is_unnamed(&self) -> bool {
-        false
+        self.0.is_none()
     }
 }
 #[stable(feature = "unix_socket_redox", since = "1.29")]
 impl fmt::Debug for SocketAddr {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        write!(fmt, "SocketAddr")
+        match self.0 {
+            None => write!(fmt, "(unnamed)"),
+            Some(ref path) => write!(fmt, "{:?} (pathname)", path),
+        }
     }
 }
 
@@ -110,7 +132,19 @@ impl fmt::Debug for SocketAddr {
 /// println!("{}", response);
 /// ```
 #[stable(feature = "unix_socket_redox", since = "1.29")]
-pub struct UnixStream(FileDesc);
+pub struct UnixStream(FileDesc, End);
+
+// Which end of a channel a stream is, which the handle itself can't tell
+// us. Only listeners have names on Redox, so the end which connected is
+// unnamed and its peer has the channel's path, and the other way around for
+// the end which was accepted.
+#[derive(Clone, Copy)]
+enum End {
+    Connected,
+    Accepted,
+    // Made from a raw descriptor, so it could be either.
+    Unknown,
+}
 
 #[stable(feature = "unix_socket_redox", since = "1.29")]
 impl fmt::Debug for UnixStream {
@@ -147,8 +181,7 @@ impl UnixStream {
     pub fn connect<P: AsRef<Path>>(path: P) -> io::Result<UnixStream> {
         if let Some(s) = path.as_ref().to_str() {
             cvt(syscall::open(format!("chan:{}", s), syscall::O_CLOEXEC))
-                .map(FileDesc::new)
-                .map(UnixStream)
+                .map(|fd| UnixStream(FileDesc::new(fd), End::Connected))
         } else {
             Err(Error::new(
                 ErrorKind::Other,
@@ -180,7 +213,7 @@ impl UnixStream {
             .map(FileDesc::new)?;
         let client = server.duplicate_path(b"connect")?;
         let stream = server.duplicate_path(b"listen")?;
-        Ok((UnixStream(client), UnixStream(stream)))
+        Ok((UnixStream(client, End::Connected), UnixStream(stream, End::Accepted)))
     }
 
     /// Creates a new independently owned handle to the underlying socket.
@@ -200,7 +233,7 @@ impl UnixStream {
     /// ```
     #[stable(feature = "unix_socket_redox", since = "1.29")]
     pub fn try_clone(&self) -> io::Result<UnixStream> {
-        self.0.duplicate().map(UnixStream)
+        self.0.duplicate().map(|fd| UnixStream(fd, self.1))
     }
 
     /// Returns the socket address of the local half of this connection.
@@ -215,7 +248,10 @@ impl UnixStream {
     /// ```
     #[stable(feature = "unix_socket_redox", since = "1.29")]
     pub fn local_addr(&self) -> io::Result<SocketAddr> {
-        Err(Error::new(ErrorKind::Other, "UnixStream::local_addr unimplemented on redox"))
+        match self.1 {
+            End::Connected => Ok(SocketAddr(None)),
+            End::Accepted | End::Unknown => SocketAddr::from_fd(&self.0),
+        }
     }
 
     /// Returns the socket address of the remote half of this connection.
@@ -228,9 +264,18 @@ impl UnixStream {
     /// let socket = UnixStream::connect("/tmp/sock").unwrap();
     /// let addr = socket.peer_addr().expect("Couldn't get peer address");
     /// ```
+    ///
+    /// # Platform specific
+    /// Redox can't tell which end of a connection a stream made with
+    /// `from_raw_fd` is, so this returns an error for one.
     #[stable(feature = "unix_socket_redox", since = "1.29")]
     pub fn peer_addr(&self) -> io::Result<SocketAddr> {
-        Err(Error::new(ErrorKind::Other, "UnixStream::peer_addr unimplemented on redox"))
+        match self.1 {
+            End::Connected => SocketAddr::from_fd(&self.0),
+            End::Accepted => Ok(SocketAddr(None)),
+            End::Unknown => Err(Error::new(ErrorKind::Other,
+                                           "UnixStream::peer_addr unknown for a raw fd on redox")),
+        }
     }
 
     /// Sets the read timeout for the socket.
@@ -268,7 +313,8 @@ impl UnixStream {
     /// assert_eq!(err.kind(), io::ErrorKind::InvalidInput)
     /// ```
     #[stable(feature = "unix_socket_redox", since = "1.29")]
-    pub fn set_read_timeout(&self, _timeout: Option<Duration>) -> io::Result<()> {
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        check_timeout(timeout)?;
         Err(Error::new(ErrorKind::Other, "UnixStream::set_read_timeout unimplemented on redox"))
     }
 
@@ -307,7 +353,8 @@ impl UnixStream {
     /// assert_eq!(err.kind(), io::ErrorKind::InvalidInput)
     /// ```
     #[stable(feature = "unix_socket_redox", since = "1.29")]
-    pub fn set_write_timeout(&self, _timeout: Option<Duration>) -> io::Result<()> {
+    pub fn set_write_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        check_timeout(timeout)?;
         Err(Error::new(ErrorKind::Other, "UnixStream::set_write_timeout unimplemented on redox"))
     }
 
@@ -403,6 +450,17 @@ impl UnixStream {
     }
 }
 
+// Zero timeouts are rejected up front like on every other platform, even
+// though Redox can't apply the others yet.
+fn check_timeout(timeout: Option<Duration>) -> io::Result<()> {
+    if timeout == Some(Duration::new(0, 0)) {
+        Err(Error::new(ErrorKind::InvalidInput,
+                       "cannot set a 0 duration timeout"))
+    } else {
+        Ok(())
+    }
+}
+
 #[stable(feature = "unix_socket_redox", since = "1.29")]
 impl io::Read for UnixStream {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
@@ -459,7 +517,7 @@ impl AsRawFd for UnixStream {
 #[stable(feature = "unix_socket_redox", since = "1.29")]
 impl FromRawFd for UnixStream {
     unsafe fn from_raw_fd(fd: RawFd) -> UnixStream {
-        UnixStream(FileDesc::new(fd))
+        UnixStream(FileDesc::new(fd), End::Unknown)
     }
 }
 
@@ -565,7 +623,8 @@ impl UnixListener {
     /// ```
     #[stable(feature = "unix_socket_redox", since = "1.29")]
     pub fn accept(&self) -> io::Result<(UnixStream, SocketAddr)> {
-        self.0.duplicate_path(b"listen").map(|fd| (UnixStream(fd), SocketAddr(())))
+        self.0.duplicate_path(b"listen")
+            .map(|fd| (UnixStream(fd, End::Accepted), SocketAddr(None)))
     }
 
     /// Creates a new independently owned handle to the underlying socket.
@@ -601,7 +660,7 @@ impl UnixListener {
     /// ```
     #[stable(feature = "unix_socket_redox", since = "1.29")]
     pub fn local_addr(&self) -> io::Result<SocketAddr> {
-        Err(Error::new(ErrorKind::Other, "UnixListener::local_addr unimplemented on redox"))
+        SocketAddr::from_fd(&self.0)
     }
 
     /// Moves the socket into or out of nonblocking mode.
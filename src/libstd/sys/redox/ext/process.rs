@@ -52,6 +52,21 @@ pub trait CommandExt {
     fn before_exec<F>(&mut self, f: F) -> &mut process::Command
         where F: FnMut() -> io::Result<()> + Send + Sync + 'static;
 
+    /// Schedules a closure to be run just before the `exec` function is
+    /// invoked.
+    ///
+    /// This behaves exactly like [`before_exec`], but is `unsafe` to call
+    /// because the closure runs in the child between `clone` and `fexec`,
+    /// where only a very limited set of operations is sound. The caller
+    /// must make sure the closure doesn't allocate, take locks which might
+    /// be held by other threads of the parent, or otherwise rely on state
+    /// that the `clone` didn't carry over consistently.
+    ///
+    /// [`before_exec`]: #tymethod.before_exec
+    #[unstable(feature = "process_pre_exec", issue = "0")]
+    unsafe fn pre_exec<F>(&mut self, f: F) -> &mut process::Command
+        where F: FnMut() -> io::Result<()> + Send + Sync + 'static;
+
     /// Performs all the required setup by this `Command`, followed by calling
     /// the `execvp` syscall.
     ///
@@ -94,6 +109,13 @@ impl CommandExt for process::Command {
         self
     }
 
+    unsafe fn pre_exec<F>(&mut self, f: F) -> &mut process::Command
+        where F: FnMut() -> io::Result<()> + Send + Sync + 'static
+    {
+        self.as_inner_mut().before_exec(Box::new(f));
+        self
+    }
+
     fn exec(&mut self) -> io::Error {
         self.as_inner_mut().exec(sys::process::Stdio::Inherit)
     }
@@ -175,3 +197,9 @@ impl IntoRawFd for process::ChildStderr {
         self.into_inner().into_fd().into_raw()
     }
 }
+
+/// Returns the OS-assigned process identifier associated with this process's parent.
+#[unstable(feature = "redox_ppid", issue = "0")]
+pub fn parent_id() -> u32 {
+    ::sys::os::getppid()
+}
@@ -9,6 +9,7 @@ use sys::fd::FileDesc;
 use sys::time::SystemTime;
 use sys::{cvt, syscall};
 use sys_common::{AsInner, FromInner};
+use sys_common::fs::{copy_chunks, Progress};
 
 pub struct File(FileDesc);
 
@@ -130,6 +131,11 @@ impl fmt::Debug for ReadDir {
     }
 }
 
+impl ReadDir {
+    // The whole directory has already been read by `readdir`.
+    pub fn set_buffer_size(&mut self, _size: usize) {}
+}
+
 impl Iterator for ReadDir {
     type Item = io::Result<DirEntry>;
 
@@ -271,6 +277,10 @@ impl File {
         Ok(())
     }
 
+    pub fn allocate(&self, _len: u64) -> io::Result<()> {
+        Err(Error::new(ErrorKind::Other, "preallocation is not supported on this platform"))
+    }
+
     pub fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
         self.0.read(buf)
     }
@@ -279,6 +289,34 @@ impl File {
         self.0.write(buf)
     }
 
+    // Redox has no `pread`/`pwrite`, so positional I/O goes through a
+    // duplicate of the handle, which has its own cursor.
+    pub fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        let dup = self.duplicate()?;
+        dup.seek(SeekFrom::Start(offset))?;
+        dup.read(buf)
+    }
+
+    pub fn write_at(&self, buf: &[u8], offset: u64) -> io::Result<usize> {
+        let dup = self.duplicate()?;
+        dup.seek(SeekFrom::Start(offset))?;
+        dup.write(buf)
+    }
+
+    pub fn read_vectored_at(&self, bufs: &mut [&mut [u8]], offset: u64) -> io::Result<usize> {
+        match bufs.iter_mut().find(|buf| !buf.is_empty()) {
+            Some(buf) => self.read_at(buf, offset),
+            None => Ok(0),
+        }
+    }
+
+    pub fn write_vectored_at(&self, bufs: &[&[u8]], offset: u64) -> io::Result<usize> {
+        match bufs.iter().find(|buf| !buf.is_empty()) {
+            Some(buf) => self.write_at(buf, offset),
+            None => Ok(0),
+        }
+    }
+
     pub fn flush(&self) -> io::Result<()> { Ok(()) }
 
     pub fn seek(&self, pos: SeekFrom) -> io::Result<u64> {
@@ -306,6 +344,14 @@ impl File {
         set_perm(&self.path()?, perm)
     }
 
+    pub fn lock(&self, _exclusive: bool, _block: bool) -> io::Result<bool> {
+        Err(Error::new(ErrorKind::Other, "file locking is not supported on this platform"))
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        Err(Error::new(ErrorKind::Other, "file locking is not supported on this platform"))
+    }
+
     pub fn path(&self) -> io::Result<PathBuf> {
         let mut buf: [u8; 4096] = [0; 4096];
         let count = cvt(syscall::fpath(*self.fd().as_inner() as usize, &mut buf))?;
@@ -356,6 +402,47 @@ impl fmt::Debug for File {
     }
 }
 
+// Redox has no `openat` yet.
+pub enum DirHandle {}
+
+impl DirHandle {
+    pub fn open(_path: &Path) -> io::Result<DirHandle> {
+        Err(io::Error::new(io::ErrorKind::Other,
+                           "operations relative to a directory are not supported on this \
+                            platform"))
+    }
+
+    pub fn open_at(&self, _path: &Path, _opts: &OpenOptions) -> io::Result<File> {
+        match *self {}
+    }
+
+    pub fn open_dir_at(&self, _path: &Path) -> io::Result<DirHandle> {
+        match *self {}
+    }
+
+    pub fn read_dir_at(&self, _path: &Path) -> io::Result<ReadDir> {
+        match *self {}
+    }
+
+    pub fn metadata_at(&self, _path: &Path, _follow: bool) -> io::Result<FileAttr> {
+        match *self {}
+    }
+
+    pub fn remove_file_at(&self, _path: &Path) -> io::Result<()> {
+        match *self {}
+    }
+
+    pub fn rename_at(&self, _from: &Path, _to_dir: &DirHandle, _to: &Path) -> io::Result<()> {
+        match *self {}
+    }
+}
+
+impl fmt::Debug for DirHandle {
+    fn fmt(&self, _f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {}
+    }
+}
+
 pub fn readdir(p: &Path) -> io::Result<ReadDir> {
     let root = Arc::new(p.to_path_buf());
 
@@ -392,6 +479,13 @@ pub fn rmdir(p: &Path) -> io::Result<()> {
     Ok(())
 }
 
+pub fn sync_dir(p: &Path) -> io::Result<()> {
+    let flags = syscall::O_CLOEXEC | syscall::O_RDONLY | syscall::O_DIRECTORY;
+    let fd = FileDesc::new(cvt(syscall::open(p.to_str().unwrap(), flags))?);
+    cvt(syscall::fsync(fd.raw()))?;
+    Ok(())
+}
+
 pub fn remove_dir_all(path: &Path) -> io::Result<()> {
     let filetype = lstat(path)?.file_type();
     if filetype.is_symlink() {
@@ -423,6 +517,29 @@ pub fn readlink(p: &Path) -> io::Result<PathBuf> {
     Ok(PathBuf::from(unsafe { String::from_utf8_unchecked(Vec::from(&buf[..count])) }))
 }
 
+pub fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    symlink(&readlink(from)?, to)
+}
+
+pub fn file_id(p: &Path) -> io::Result<(u64, u64)> {
+    let attr = stat(p)?;
+    Ok((attr.stat.st_dev as u64, attr.stat.st_ino as u64))
+}
+
+pub fn copy_ownership(_from: &FileAttr, _to: &Path, _follow: bool) -> io::Result<()> {
+    Err(Error::new(ErrorKind::Other,
+                   "copying file ownership is not supported on this platform"))
+}
+
+pub fn copy_xattrs(_from: &Path, _to: &Path) -> io::Result<()> {
+    Err(Error::new(ErrorKind::Other,
+                   "extended attributes are not supported on this platform"))
+}
+
+pub fn set_times(_p: &Path, _accessed: SystemTime, _modified: SystemTime) -> io::Result<()> {
+    Err(Error::new(ErrorKind::Other, "setting file times is not supported on this platform"))
+}
+
 pub fn symlink(src: &Path, dst: &Path) -> io::Result<()> {
     let fd = cvt(syscall::open(dst.to_str().unwrap(),
                                syscall::O_CLOEXEC | syscall::O_SYMLINK |
@@ -450,6 +567,14 @@ pub fn lstat(p: &Path) -> io::Result<FileAttr> {
     file.file_attr()
 }
 
+pub fn try_exists(p: &Path) -> io::Result<bool> {
+    match stat(p) {
+        Ok(_) => Ok(true),
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
 pub fn canonicalize(p: &Path) -> io::Result<PathBuf> {
     let fd = cvt(syscall::open(p.to_str().unwrap(), syscall::O_CLOEXEC | syscall::O_STAT))?;
     let file = File(FileDesc::new(fd));
@@ -457,6 +582,10 @@ pub fn canonicalize(p: &Path) -> io::Result<PathBuf> {
 }
 
 pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
+    copy_with_progress(from, to, &mut Progress::new(&mut |_, _| Ok(()), u64::max_value()))
+}
+
+pub fn copy_with_progress(from: &Path, to: &Path, progress: &mut Progress) -> io::Result<u64> {
     use fs::{File, set_permissions};
     if !from.is_file() {
         return Err(Error::new(ErrorKind::InvalidInput,
@@ -465,9 +594,13 @@ pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
 
     let mut reader = File::open(from)?;
     let mut writer = File::create(to)?;
-    let perm = reader.metadata()?.permissions();
+    let (perm, len) = {
+        let metadata = reader.metadata()?;
+        (metadata.permissions(), metadata.len())
+    };
 
-    let ret = io::copy(&mut reader, &mut writer)?;
+    let ret = copy_chunks(&mut reader, &mut writer, 0, len, progress)?;
     set_permissions(to, perm)?;
+    progress.finish(ret, len)?;
     Ok(ret)
 }
@@ -35,7 +35,22 @@ pub mod thread_local;
 pub mod time;
 
 #[cfg(not(test))]
-pub fn init() {}
+pub fn init() {
+    // Make sure the standard descriptors are open, opening `null:` in place
+    // of any which aren't. Otherwise a child told to inherit one would get
+    // nothing, and the first file we open would be taken for one of them.
+    for fd in 0..3 {
+        if syscall::fcntl(fd, syscall::F_GETFD, 0) != Err(syscall::Error::new(syscall::EBADF)) {
+            continue
+        }
+        if let Ok(null) = syscall::open("null:", syscall::O_RDWR) {
+            if null != fd {
+                let _ = syscall::dup2(null, fd, &[]);
+                let _ = syscall::close(null);
+            }
+        }
+    }
+}
 
 pub fn decode_error_kind(errno: i32) -> ErrorKind {
     match errno {
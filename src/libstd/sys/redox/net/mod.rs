@@ -1,7 +1,7 @@
 use fs::File;
 use io::{Error, Read, self};
 use iter::Iterator;
-use net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use net::{Ipv4Addr, NameInfoFlag, SocketAddr, SocketAddrV4};
 use str::FromStr;
 use string::{String, ToString};
 use sys::syscall::EINVAL;
@@ -125,6 +125,11 @@ impl<'a> TryFrom<(&'a str, u16)> for LookupHost {
     }
 }
 
+pub fn lookup_name_info(_addr: &SocketAddr, _flags: &[NameInfoFlag])
+                        -> io::Result<(String, String)> {
+    Err(Error::new(io::ErrorKind::Other, "Not implemented"))
+}
+
 fn path_to_peer_addr(path_str: &str) -> SocketAddr {
     let mut parts = path_str.split('/').next().unwrap_or("").split(':').skip(1);
     let host = Ipv4Addr::from_str(parts.next().unwrap_or("")).unwrap_or(Ipv4Addr::new(0, 0, 0, 0));
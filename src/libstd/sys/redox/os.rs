@@ -178,6 +178,13 @@ pub fn env() -> Env {
     }
 }
 
+pub use sys_common::env_snapshot::{EnvSnapshot, EnvSnapshotIter};
+
+/// Returns a snapshot of the environment variables of the current process.
+pub fn env_snapshot() -> EnvSnapshot {
+    EnvSnapshot::new(env())
+}
+
 pub fn getenv(k: &OsStr) -> io::Result<Option<OsString>> {
     // environment variables with a nul byte can't be set, so their value is
     // always None as well
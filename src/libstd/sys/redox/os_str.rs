@@ -2,6 +2,7 @@
 /// a `Vec<u8>`/`[u8]`.
 
 use borrow::Cow;
+use collections::TryReserveError;
 use fmt;
 use str;
 use mem;
@@ -89,6 +90,16 @@ impl Buf {
         self.inner.reserve_exact(additional)
     }
 
+    #[inline]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.inner.try_reserve(additional)
+    }
+
+    #[inline]
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.inner.try_reserve_exact(additional)
+    }
+
     #[inline]
     pub fn shrink_to_fit(&mut self) {
         self.inner.shrink_to_fit()
@@ -103,6 +114,16 @@ impl Buf {
         unsafe { mem::transmute(&*self.inner) }
     }
 
+    #[inline]
+    pub fn into_encoded_bytes(self) -> Vec<u8> {
+        self.inner
+    }
+
+    #[inline]
+    pub unsafe fn from_encoded_bytes_unchecked(bytes: Vec<u8>) -> Buf {
+        Buf { inner: bytes }
+    }
+
     pub fn into_string(self) -> Result<String, Buf> {
         String::from_utf8(self.inner).map_err(|p| Buf { inner: p.into_bytes() } )
     }
@@ -154,6 +175,35 @@ impl Slice {
         Buf { inner: self.inner.to_vec() }
     }
 
+    #[inline]
+    pub fn starts_with(&self, prefix: &Slice) -> bool {
+        self.inner.starts_with(&prefix.inner)
+    }
+
+    #[inline]
+    pub fn ends_with(&self, suffix: &Slice) -> bool {
+        self.inner.ends_with(&suffix.inner)
+    }
+
+    pub fn find(&self, needle: &Slice) -> Option<usize> {
+        if needle.inner.is_empty() {
+            return Some(0);
+        }
+        self.inner.windows(needle.inner.len()).position(|w| w == &needle.inner)
+    }
+
+    pub fn get(&self, begin: usize, end: usize) -> Option<&Slice> {
+        self.inner.get(begin..end).map(Slice::from_u8_slice)
+    }
+
+    pub fn to_ascii_lowercase(&self) -> Buf {
+        Buf { inner: self.inner.to_ascii_lowercase() }
+    }
+
+    pub fn to_ascii_uppercase(&self) -> Buf {
+        Buf { inner: self.inner.to_ascii_uppercase() }
+    }
+
     #[inline]
     pub fn into_box(&self) -> Box<Slice> {
         let boxed: Box<[u8]> = self.inner.into();
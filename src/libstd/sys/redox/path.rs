@@ -1,5 +1,7 @@
+use env;
 use ffi::OsStr;
-use path::Prefix;
+use io;
+use path::{Path, PathBuf, Prefix};
 
 #[inline]
 pub fn is_sep_byte(b: u8) -> bool {
@@ -27,3 +29,14 @@ pub fn parse_prefix(path: &OsStr) -> Option<Prefix> {
 
 pub const MAIN_SEP_STR: &str = "/";
 pub const MAIN_SEP: char = '/';
+
+/// Makes `path` absolute against the current directory, and then normalizes
+/// it lexically, as there's nothing in the system to do that.
+pub fn absolute(path: &Path) -> io::Result<PathBuf> {
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        env::current_dir()?.join(path)
+    };
+    Ok(path.normalize_lexically())
+}
@@ -28,6 +28,14 @@ impl AnonPipe {
         self.0.write(buf)
     }
 
+    pub fn try_clone(&self) -> io::Result<AnonPipe> {
+        self.0.duplicate().map(AnonPipe)
+    }
+
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.0.set_nonblocking(nonblocking)
+    }
+
     pub fn fd(&self) -> &FileDesc { &self.0 }
     pub fn into_fd(self) -> FileDesc { self.0 }
 }
@@ -91,3 +99,26 @@ pub fn read2(p1: AnonPipe,
     }
     */
 }
+
+// Like `read2`, this doesn't multiplex yet, so a child filling the second
+// pipe before closing the first will deadlock.
+pub fn read2_with(p1: AnonPipe,
+                 p2: AnonPipe,
+                 f: &mut dyn FnMut(usize, &[u8]) -> io::Result<()>) -> io::Result<()> {
+    read_chunks(&p1.0, 0, f)?;
+    read_chunks(&p2.0, 1, f)
+}
+
+fn read_chunks(fd: &FileDesc,
+               i: usize,
+               f: &mut dyn FnMut(usize, &[u8]) -> io::Result<()>) -> io::Result<()> {
+    let mut buf = [0; 8 * 1024];
+    loop {
+        match fd.read(&mut buf) {
+            Ok(0) => return Ok(()),
+            Ok(n) => f(i, &buf[..n])?,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use env::{split_paths};
-use ffi::{CStr, OsStr};
+use ffi::{CStr, OsStr, OsString};
 use fmt;
 use fs::File;
 use io::{self, prelude::*, BufReader, Error, ErrorKind, SeekFrom};
@@ -7,6 +7,7 @@ use libc::{EXIT_SUCCESS, EXIT_FAILURE};
 use os::unix::ffi::OsStrExt;
 use path::{Path, PathBuf};
 use ptr;
+use slice;
 use sys::ext::fs::MetadataExt;
 use sys::ext::io::AsRawFd;
 use sys::fd::FileDesc;
@@ -14,7 +15,8 @@ use sys::fs::{File as SysFile, OpenOptions};
 use sys::os::{ENV_LOCK, environ};
 use sys::pipe::{self, AnonPipe};
 use sys::{cvt, syscall};
-use sys_common::process::{CommandEnv, DefaultEnvKey};
+use sys_common::process::{self, CommandEnv, DefaultEnvKey};
+use time::Instant;
 
 ////////////////////////////////////////////////////////////////////////////////
 // Command
@@ -38,11 +40,11 @@ pub struct Command {
     // located. Whenever we add a key we update it in place if it's already
     // present, and whenever we remove a key we update the locations of all
     // other keys.
-    program: String,
-    args: Vec<String>,
+    program: OsString,
+    args: Vec<OsString>,
     env: CommandEnv<DefaultEnvKey>,
 
-    cwd: Option<String>,
+    cwd: Option<OsString>,
     uid: Option<u32>,
     gid: Option<u32>,
     saw_nul: bool,
@@ -50,6 +52,7 @@ pub struct Command {
     stdin: Option<Stdio>,
     stdout: Option<Stdio>,
     stderr: Option<Stdio>,
+    merge_stderr: bool,
 }
 
 // passed back to std::process with the pipes connected to the child, if any
@@ -74,6 +77,37 @@ enum ChildStdio {
     Owned(FileDesc),
 }
 
+pub type CommandEnvs<'a> = process::CommandEnvs<'a, DefaultEnvKey>;
+
+/// How environment variable names are compared, and kept in order.
+pub type EnvKey = DefaultEnvKey;
+
+pub struct CommandArgs<'a> {
+    iter: slice::Iter<'a, OsString>,
+}
+
+impl<'a> Iterator for CommandArgs<'a> {
+    type Item = &'a OsStr;
+    fn next(&mut self) -> Option<&'a OsStr> {
+        self.iter.next().map(|arg| &**arg)
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for CommandArgs<'a> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<'a> fmt::Debug for CommandArgs<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter.clone()).finish()
+    }
+}
+
 pub enum Stdio {
     Inherit,
     Null,
@@ -83,31 +117,47 @@ pub enum Stdio {
 
 impl Command {
     pub fn new(program: &OsStr) -> Command {
+        let mut saw_nul = false;
+        let program = checked_os_string(program, &mut saw_nul);
         Command {
-            program: program.to_str().unwrap().to_owned(),
+            program,
             args: Vec::new(),
             env: Default::default(),
             cwd: None,
             uid: None,
             gid: None,
-            saw_nul: false,
+            saw_nul,
             closures: Vec::new(),
             stdin: None,
             stdout: None,
             stderr: None,
+            merge_stderr: false,
         }
     }
 
     pub fn arg(&mut self, arg: &OsStr) {
-        self.args.push(arg.to_str().unwrap().to_owned());
+        let arg = checked_os_string(arg, &mut self.saw_nul);
+        self.args.push(arg);
     }
 
     pub fn env_mut(&mut self) -> &mut CommandEnv<DefaultEnvKey> {
         &mut self.env
     }
+    pub fn get_program(&self) -> &OsStr {
+        &self.program
+    }
+    pub fn get_args(&self) -> CommandArgs {
+        CommandArgs { iter: self.args.iter() }
+    }
+    pub fn get_envs(&self) -> CommandEnvs {
+        self.env.iter()
+    }
+    pub fn get_current_dir(&self) -> Option<&Path> {
+        self.cwd.as_ref().map(|cwd| Path::new(cwd))
+    }
 
     pub fn cwd(&mut self, dir: &OsStr) {
-        self.cwd = Some(dir.to_str().unwrap().to_owned());
+        self.cwd = Some(checked_os_string(dir, &mut self.saw_nul));
     }
     pub fn uid(&mut self, id: u32) {
         self.uid = Some(id);
@@ -131,6 +181,10 @@ impl Command {
         self.stderr = Some(stderr);
     }
 
+    pub fn merge_stderr(&mut self, merge: bool) {
+        self.merge_stderr = merge;
+    }
+
     pub fn spawn(&mut self, default: Stdio, needs_stdin: bool)
                  -> io::Result<(Process, StdioPipes)> {
          const CLOEXEC_MSG_FOOTER: &[u8] = b"NOEX";
@@ -284,7 +338,7 @@ impl Command {
             t!(cvt(syscall::setreuid(u as usize, u as usize)));
         }
         if let Some(ref cwd) = self.cwd {
-            t!(cvt(syscall::chdir(cwd)));
+            t!(cvt(syscall::chdir(cwd.as_bytes())));
         }
 
         for callback in self.closures.iter_mut() {
@@ -293,7 +347,8 @@ impl Command {
 
         self.env.apply();
 
-        let program = if self.program.contains(':') || self.program.contains('/') {
+        let program_bytes = self.program.as_bytes();
+        let program = if program_bytes.contains(&b':') || program_bytes.contains(&b'/') {
             Some(PathBuf::from(&self.program))
         } else if let Ok(path_env) = ::env::var("PATH") {
             let mut program = None;
@@ -377,8 +432,11 @@ impl Command {
             t!(file.seek(SeekFrom::Start(0)));
         }
 
-        args.push([self.program.as_ptr() as usize, self.program.len()]);
-        args.extend(self.args.iter().map(|arg| [arg.as_ptr() as usize, arg.len()]));
+        args.push([program_bytes.as_ptr() as usize, program_bytes.len()]);
+        args.extend(self.args.iter().map(|arg| {
+            let arg = arg.as_bytes();
+            [arg.as_ptr() as usize, arg.len()]
+        }));
 
         // Push all the variables
         let mut vars: Vec<[usize; 2]> = Vec::new();
@@ -409,7 +467,11 @@ impl Command {
         let stderr = self.stderr.as_ref().unwrap_or(&default);
         let (their_stdin, our_stdin) = stdin.to_child_stdio(true)?;
         let (their_stdout, our_stdout) = stdout.to_child_stdio(false)?;
-        let (their_stderr, our_stderr) = stderr.to_child_stdio(false)?;
+        let (their_stderr, our_stderr) = if self.merge_stderr {
+            (their_stdout.merged()?, None)
+        } else {
+            stderr.to_child_stdio(false)?
+        };
         let ours = StdioPipes {
             stdin: our_stdin,
             stdout: our_stdout,
@@ -424,6 +486,16 @@ impl Command {
     }
 }
 
+// Arguments are handed to `fexec` as byte slices so they don't need to be
+// valid UTF-8, but interior nuls are rejected for parity with Unix, where
+// they can't be represented.
+fn checked_os_string(s: &OsStr, saw_nul: &mut bool) -> OsString {
+    if s.as_bytes().contains(&0) {
+        *saw_nul = true;
+    }
+    s.to_os_string()
+}
+
 impl Stdio {
     fn to_child_stdio(&self, readable: bool)
                       -> io::Result<(ChildStdio, Option<AnonPipe>)> {
@@ -486,6 +558,16 @@ impl ChildStdio {
             ChildStdio::Owned(ref fd) => Some(fd.raw()),
         }
     }
+
+    // A descriptor for the child's stderr referring to whatever its stdout
+    // does, as with `2>&1`. It's a copy rather than the same number so that
+    // the order the two are set up in doesn't matter.
+    fn merged(&self) -> io::Result<ChildStdio> {
+        let fd = FileDesc::new(self.fd().unwrap_or(1));
+        let ret = fd.duplicate();
+        fd.into_raw();
+        Ok(ChildStdio::Owned(ret?))
+    }
 }
 
 impl fmt::Debug for Command {
@@ -498,6 +580,13 @@ impl fmt::Debug for Command {
     }
 }
 
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cwd = self.get_current_dir().map(Path::as_os_str);
+        process::fmt_shell_command(f, self.get_program(), self.get_args(), &self.env, cwd)
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // Processes
 ////////////////////////////////////////////////////////////////////////////////
@@ -608,4 +697,8 @@ impl Process {
             Ok(Some(ExitStatus(status as i32)))
         }
     }
+
+    pub fn wait_deadline(&mut self, deadline: Instant) -> io::Result<Option<ExitStatus>> {
+        process::poll_until(deadline, || self.try_wait())
+    }
 }
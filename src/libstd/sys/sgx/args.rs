@@ -40,6 +40,10 @@ impl Args {
     pub fn inner_debug(&self) -> &[OsString] {
         self.0.as_slice()
     }
+
+    pub fn get(&self, i: usize) -> Option<OsString> {
+        self.0.as_slice().get(i).cloned()
+    }
 }
 
 impl Iterator for Args {
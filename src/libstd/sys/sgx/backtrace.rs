@@ -1,9 +1,10 @@
 use io;
 use error::Error;
 use libc;
-use sys_common::backtrace::Frame;
+use sys_common::backtrace::{Frame, Module};
 use unwind as uw;
 use sys::sgx::abi::mem::image_base;
+use sys::unsupported;
 
 pub struct BacktraceContext;
 
@@ -94,3 +95,7 @@ pub fn foreach_symbol_fileline<F>(_: Frame,
 {
     Ok(false)
 }
+
+pub fn modules() -> io::Result<Vec<Module>> {
+    unsupported()
+}
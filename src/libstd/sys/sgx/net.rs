@@ -1,6 +1,6 @@
 use fmt;
 use io;
-use net::{SocketAddr, Shutdown, Ipv4Addr, Ipv6Addr, ToSocketAddrs};
+use net::{SocketAddr, Shutdown, Ipv4Addr, Ipv6Addr, NameInfoFlag, ToSocketAddrs};
 use time::Duration;
 use sys::{unsupported, Void, sgx_ineffective, AsInner, FromInner, IntoInner, TryIntoInner};
 use sys::fd::FileDesc;
@@ -420,6 +420,11 @@ impl<'a> TryFrom<(&'a str, u16)> for LookupHost {
     }
 }
 
+pub fn lookup_name_info(_addr: &SocketAddr, _flags: &[NameInfoFlag])
+                        -> io::Result<(String, String)> {
+    unsupported()
+}
+
 #[allow(bad_style)]
 pub mod netc {
     pub const AF_INET: u8 = 0;
@@ -103,6 +103,13 @@ pub fn env() -> Env {
         .into_iter()
 }
 
+pub use sys_common::env_snapshot::{EnvSnapshot, EnvSnapshotIter};
+
+/// Returns a snapshot of the environment variables of the current process.
+pub fn env_snapshot() -> EnvSnapshot {
+    EnvSnapshot::new(env())
+}
+
 pub fn getenv(k: &OsStr) -> io::Result<Option<OsString>> {
     Ok(get_env_store().and_then(|s| s.lock().unwrap().get(k).cloned() ))
 }
@@ -1,5 +1,7 @@
-use path::Prefix;
+use env;
+use path::{Path, PathBuf, Prefix};
 use ffi::OsStr;
+use io;
 
 #[inline]
 pub fn is_sep_byte(b: u8) -> bool {
@@ -17,3 +19,14 @@ pub fn parse_prefix(_: &OsStr) -> Option<Prefix> {
 
 pub const MAIN_SEP_STR: &'static str = "/";
 pub const MAIN_SEP: char = '/';
+
+/// Makes `path` absolute against the current directory, and then normalizes
+/// it lexically, as there's nothing in the system to do that.
+pub fn absolute(path: &Path) -> io::Result<PathBuf> {
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        env::current_dir()?.join(path)
+    };
+    Ok(path.normalize_lexically())
+}
@@ -0,0 +1,251 @@
+//! Raw bindings to the parts of the UEFI specification that libstd uses.
+//!
+//! Everything here is reached through the `SystemTable` handed to the image
+//! entry point; there is nothing to link against. Only the table entries we
+//! actually call are typed, the rest are kept as opaque pointer-sized slots
+//! so that the layout of each table still matches the specification.
+//!
+//! All firmware entry points use the Microsoft calling convention on x86_64,
+//! which is currently the only UEFI target.
+
+#![allow(non_camel_case_types, dead_code)]
+
+use os::raw::c_void;
+
+pub type Handle = *mut c_void;
+pub type Event = *mut c_void;
+pub type Status = usize;
+pub type Char16 = u16;
+
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct Guid {
+    pub data1: u32,
+    pub data2: u16,
+    pub data3: u16,
+    pub data4: [u8; 8],
+}
+
+pub const ERROR_BIT: Status = 1 << (::mem::size_of::<Status>() * 8 - 1);
+
+pub const SUCCESS: Status = 0;
+pub const LOAD_ERROR: Status = ERROR_BIT | 1;
+pub const INVALID_PARAMETER: Status = ERROR_BIT | 2;
+pub const UNSUPPORTED: Status = ERROR_BIT | 3;
+pub const BAD_BUFFER_SIZE: Status = ERROR_BIT | 4;
+pub const BUFFER_TOO_SMALL: Status = ERROR_BIT | 5;
+pub const NOT_READY: Status = ERROR_BIT | 6;
+pub const DEVICE_ERROR: Status = ERROR_BIT | 7;
+pub const WRITE_PROTECTED: Status = ERROR_BIT | 8;
+pub const OUT_OF_RESOURCES: Status = ERROR_BIT | 9;
+pub const VOLUME_CORRUPTED: Status = ERROR_BIT | 10;
+pub const VOLUME_FULL: Status = ERROR_BIT | 11;
+pub const NO_MEDIA: Status = ERROR_BIT | 12;
+pub const MEDIA_CHANGED: Status = ERROR_BIT | 13;
+pub const NOT_FOUND: Status = ERROR_BIT | 14;
+pub const ACCESS_DENIED: Status = ERROR_BIT | 15;
+pub const NO_RESPONSE: Status = ERROR_BIT | 16;
+pub const NO_MAPPING: Status = ERROR_BIT | 17;
+pub const TIMEOUT: Status = ERROR_BIT | 18;
+pub const NOT_STARTED: Status = ERROR_BIT | 19;
+pub const ALREADY_STARTED: Status = ERROR_BIT | 20;
+pub const ABORTED: Status = ERROR_BIT | 21;
+pub const ICMP_ERROR: Status = ERROR_BIT | 22;
+pub const TFTP_ERROR: Status = ERROR_BIT | 23;
+pub const PROTOCOL_ERROR: Status = ERROR_BIT | 24;
+pub const INCOMPATIBLE_VERSION: Status = ERROR_BIT | 25;
+pub const SECURITY_VIOLATION: Status = ERROR_BIT | 26;
+pub const CRC_ERROR: Status = ERROR_BIT | 27;
+pub const END_OF_MEDIA: Status = ERROR_BIT | 28;
+pub const END_OF_FILE: Status = ERROR_BIT | 31;
+pub const INVALID_LANGUAGE: Status = ERROR_BIT | 32;
+pub const COMPROMISED_DATA: Status = ERROR_BIT | 33;
+
+/// `EfiLoaderData`, the memory type used for all pool allocations.
+pub const LOADER_DATA: u32 = 2;
+
+/// Value of `Time::time_zone` when the firmware doesn't know its offset.
+pub const UNSPECIFIED_TIMEZONE: i16 = 0x07ff;
+
+pub const LOADED_IMAGE_PROTOCOL_GUID: Guid = Guid {
+    data1: 0x5b1b31a1,
+    data2: 0x9562,
+    data3: 0x11d2,
+    data4: [0x8e, 0x3f, 0x00, 0xa0, 0xc9, 0x69, 0x72, 0x3b],
+};
+
+pub const RNG_PROTOCOL_GUID: Guid = Guid {
+    data1: 0x3152bca5,
+    data2: 0xeade,
+    data3: 0x433d,
+    data4: [0x86, 0x2e, 0xc0, 0x1c, 0xdc, 0x29, 0x1f, 0x44],
+};
+
+#[repr(C)]
+pub struct TableHeader {
+    pub signature: u64,
+    pub revision: u32,
+    pub header_size: u32,
+    pub crc32: u32,
+    pub reserved: u32,
+}
+
+#[repr(C)]
+pub struct SystemTable {
+    pub hdr: TableHeader,
+    pub firmware_vendor: *const Char16,
+    pub firmware_revision: u32,
+    pub console_in_handle: Handle,
+    pub con_in: *mut SimpleTextInputProtocol,
+    pub console_out_handle: Handle,
+    pub con_out: *mut SimpleTextOutputProtocol,
+    pub standard_error_handle: Handle,
+    pub std_err: *mut SimpleTextOutputProtocol,
+    pub runtime_services: *mut RuntimeServices,
+    pub boot_services: *mut BootServices,
+    pub number_of_table_entries: usize,
+    pub configuration_table: *mut c_void,
+}
+
+#[repr(C)]
+pub struct Time {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    pub pad1: u8,
+    pub nanosecond: u32,
+    pub time_zone: i16,
+    pub daylight: u8,
+    pub pad2: u8,
+}
+
+#[repr(C)]
+pub struct RuntimeServices {
+    pub hdr: TableHeader,
+    pub get_time: extern "win64" fn(time: *mut Time, capabilities: *mut c_void) -> Status,
+    pub set_time: usize,
+    pub get_wakeup_time: usize,
+    pub set_wakeup_time: usize,
+    pub set_virtual_address_map: usize,
+    pub convert_pointer: usize,
+    pub get_variable: usize,
+    pub get_next_variable_name: usize,
+    pub set_variable: usize,
+    pub get_next_high_monotonic_count: usize,
+    pub reset_system: usize,
+    pub update_capsule: usize,
+    pub query_capsule_capabilities: usize,
+    pub query_variable_info: usize,
+}
+
+#[repr(C)]
+pub struct BootServices {
+    pub hdr: TableHeader,
+    pub raise_tpl: usize,
+    pub restore_tpl: usize,
+    pub allocate_pages: usize,
+    pub free_pages: usize,
+    pub get_memory_map: usize,
+    pub allocate_pool: extern "win64" fn(pool_type: u32, size: usize,
+                                         buffer: *mut *mut c_void) -> Status,
+    pub free_pool: extern "win64" fn(buffer: *mut c_void) -> Status,
+    pub create_event: usize,
+    pub set_timer: usize,
+    pub wait_for_event: extern "win64" fn(number_of_events: usize, event: *const Event,
+                                          index: *mut usize) -> Status,
+    pub signal_event: usize,
+    pub close_event: usize,
+    pub check_event: usize,
+    pub install_protocol_interface: usize,
+    pub reinstall_protocol_interface: usize,
+    pub uninstall_protocol_interface: usize,
+    pub handle_protocol: extern "win64" fn(handle: Handle, protocol: *const Guid,
+                                           interface: *mut *mut c_void) -> Status,
+    pub reserved: usize,
+    pub register_protocol_notify: usize,
+    pub locate_handle: usize,
+    pub locate_device_path: usize,
+    pub install_configuration_table: usize,
+    pub load_image: usize,
+    pub start_image: usize,
+    pub exit: extern "win64" fn(image_handle: Handle, exit_status: Status,
+                                exit_data_size: usize, exit_data: *mut Char16) -> Status,
+    pub unload_image: usize,
+    pub exit_boot_services: usize,
+    pub get_next_monotonic_count: extern "win64" fn(count: *mut u64) -> Status,
+    pub stall: extern "win64" fn(microseconds: usize) -> Status,
+    pub set_watchdog_timer: usize,
+    pub connect_controller: usize,
+    pub disconnect_controller: usize,
+    pub open_protocol: usize,
+    pub close_protocol: usize,
+    pub open_protocol_information: usize,
+    pub protocols_per_handle: usize,
+    pub locate_handle_buffer: usize,
+    pub locate_protocol: extern "win64" fn(protocol: *const Guid, registration: *mut c_void,
+                                           interface: *mut *mut c_void) -> Status,
+    pub install_multiple_protocol_interfaces: usize,
+    pub uninstall_multiple_protocol_interfaces: usize,
+    pub calculate_crc32: usize,
+    pub copy_mem: usize,
+    pub set_mem: usize,
+    pub create_event_ex: usize,
+}
+
+#[repr(C)]
+pub struct InputKey {
+    pub scan_code: u16,
+    pub unicode_char: Char16,
+}
+
+#[repr(C)]
+pub struct SimpleTextInputProtocol {
+    pub reset: extern "win64" fn(this: *mut SimpleTextInputProtocol,
+                                 extended_verification: bool) -> Status,
+    pub read_key_stroke: extern "win64" fn(this: *mut SimpleTextInputProtocol,
+                                           key: *mut InputKey) -> Status,
+    pub wait_for_key: Event,
+}
+
+#[repr(C)]
+pub struct SimpleTextOutputProtocol {
+    pub reset: extern "win64" fn(this: *mut SimpleTextOutputProtocol,
+                                 extended_verification: bool) -> Status,
+    pub output_string: extern "win64" fn(this: *mut SimpleTextOutputProtocol,
+                                         string: *const Char16) -> Status,
+    pub test_string: usize,
+    pub query_mode: usize,
+    pub set_mode: usize,
+    pub set_attribute: usize,
+    pub clear_screen: usize,
+    pub set_cursor_position: usize,
+    pub enable_cursor: usize,
+    pub mode: *mut c_void,
+}
+
+#[repr(C)]
+pub struct LoadedImageProtocol {
+    pub revision: u32,
+    pub parent_handle: Handle,
+    pub system_table: *mut SystemTable,
+    pub device_handle: Handle,
+    pub file_path: *mut c_void,
+    pub reserved: *mut c_void,
+    pub load_options_size: u32,
+    pub load_options: *mut c_void,
+    pub image_base: *mut c_void,
+    pub image_size: u64,
+    pub image_code_type: u32,
+    pub image_data_type: u32,
+    pub unload: usize,
+}
+
+#[repr(C)]
+pub struct RngProtocol {
+    pub get_info: usize,
+    pub get_rng: extern "win64" fn(this: *mut RngProtocol, algorithm: *const Guid,
+                                   value_length: usize, value: *mut u8) -> Status,
+}
@@ -0,0 +1,71 @@
+//! The system allocator on UEFI is the boot services memory pool.
+//!
+//! `AllocatePool` only guarantees 8-byte alignment, so anything stricter is
+//! over-allocated and the original pointer is stashed just in front of the
+//! aligned block, the same trick the Windows allocator uses for `HeapAlloc`.
+
+use alloc::{GlobalAlloc, Layout, System};
+use ptr;
+use sys::abi;
+use sys::boot_services;
+use sys_common::alloc::realloc_fallback;
+
+const POOL_ALIGN: usize = 8;
+
+#[repr(C)]
+struct Header(*mut u8);
+
+unsafe fn get_header<'a>(ptr: *mut u8) -> &'a mut Header {
+    &mut *(ptr as *mut Header).offset(-1)
+}
+
+unsafe fn align_ptr(ptr: *mut u8, align: usize) -> *mut u8 {
+    let aligned = ptr.add(align - (ptr as usize & (align - 1)));
+    *get_header(aligned) = Header(ptr);
+    aligned
+}
+
+unsafe fn allocate_pool(size: usize) -> *mut u8 {
+    let mut ptr = ptr::null_mut();
+    match (boot_services().allocate_pool)(abi::LOADER_DATA, size, &mut ptr) {
+        abi::SUCCESS => ptr as *mut u8,
+        _ => ptr::null_mut(),
+    }
+}
+
+unsafe fn free_pool(ptr: *mut u8) {
+    let status = (boot_services().free_pool)(ptr as *mut _);
+    debug_assert!(status == abi::SUCCESS, "Failed to free pool memory: {:#x}", status);
+}
+
+#[stable(feature = "alloc_system_type", since = "1.28.0")]
+unsafe impl GlobalAlloc for System {
+    #[inline]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if layout.align() <= POOL_ALIGN {
+            return allocate_pool(layout.size())
+        }
+
+        let ptr = allocate_pool(layout.size() + layout.align());
+        if ptr.is_null() {
+            ptr
+        } else {
+            align_ptr(ptr, layout.align())
+        }
+    }
+
+    #[inline]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if layout.align() <= POOL_ALIGN {
+            free_pool(ptr)
+        } else {
+            free_pool(get_header(ptr).0)
+        }
+    }
+
+    #[inline]
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        // There's no way to grow a pool allocation in place.
+        realloc_fallback(self, ptr, layout, new_size)
+    }
+}
@@ -0,0 +1,153 @@
+use ffi::OsString;
+use marker::PhantomData;
+use ptr;
+use slice;
+use sys::abi;
+use sys::{boot_services, cvt, image_handle};
+use vec;
+
+pub unsafe fn init(_argc: isize, _argv: *const *const u8) {
+    // There's no argc/argv on UEFI, the command line is read from the loaded
+    // image protocol on demand.
+}
+
+pub unsafe fn cleanup() {
+}
+
+pub fn args() -> Args {
+    let v = unsafe { load_options() }.map(|opts| parse(opts)).unwrap_or(Vec::new());
+    Args {
+        iter: v.into_iter(),
+        _dont_send_or_sync_me: PhantomData,
+    }
+}
+
+/// Returns the load options of our image as UCS-2 code units.
+///
+/// When started from the UEFI shell these are the full command line,
+/// including the program name, but a boot manager entry can pass arbitrary
+/// binary data, so anything with an odd length is ignored.
+unsafe fn load_options() -> Option<&'static [u16]> {
+    let mut image = ptr::null_mut();
+    cvt((boot_services().handle_protocol)(image_handle(),
+                                          &abi::LOADED_IMAGE_PROTOCOL_GUID,
+                                          &mut image)).ok()?;
+    let image = &*(image as *const abi::LoadedImageProtocol);
+    let size = image.load_options_size as usize;
+    if image.load_options.is_null() || size % 2 != 0 {
+        return None
+    }
+    let opts = slice::from_raw_parts(image.load_options as *const u16, size / 2);
+    Some(match opts.iter().position(|&c| c == 0) {
+        Some(nul) => &opts[..nul],
+        None => opts,
+    })
+}
+
+/// Splits a command line the way the UEFI shell does.
+///
+/// Arguments are separated by spaces or tabs, double quotes group
+/// whitespace into an argument and `^` escapes the character after it.
+/// Characters which aren't valid UTF-16 are replaced with U+FFFD.
+fn parse(cmdline: &[u16]) -> Vec<OsString> {
+    let mut ret = Vec::new();
+    let mut cur = Vec::new();
+    let mut in_arg = false;
+    let mut in_quotes = false;
+    let mut chars = cmdline.iter().cloned();
+    while let Some(c) = chars.next() {
+        match c {
+            0x5e /* ^ */ => {
+                in_arg = true;
+                if let Some(next) = chars.next() {
+                    cur.push(next);
+                }
+            }
+            0x22 /* " */ => {
+                in_arg = true;
+                in_quotes = !in_quotes;
+            }
+            0x20 | 0x09 if !in_quotes => {
+                if in_arg {
+                    ret.push(String::from_utf16_lossy(&cur).into());
+                    cur.clear();
+                    in_arg = false;
+                }
+            }
+            c => {
+                in_arg = true;
+                cur.push(c);
+            }
+        }
+    }
+    if in_arg {
+        ret.push(String::from_utf16_lossy(&cur).into());
+    }
+    ret
+}
+
+pub struct Args {
+    iter: vec::IntoIter<OsString>,
+    _dont_send_or_sync_me: PhantomData<*mut ()>,
+}
+
+impl Args {
+    pub fn inner_debug(&self) -> &[OsString] {
+        self.iter.as_slice()
+    }
+
+    pub fn get(&self, i: usize) -> Option<OsString> {
+        self.iter.as_slice().get(i).cloned()
+    }
+}
+
+impl Iterator for Args {
+    type Item = OsString;
+    fn next(&mut self) -> Option<OsString> {
+        self.iter.next()
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Args {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl DoubleEndedIterator for Args {
+    fn next_back(&mut self) -> Option<OsString> {
+        self.iter.next_back()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    fn chk(cmdline: &str, expected: &[&str]) {
+        let cmdline: Vec<u16> = cmdline.encode_utf16().collect();
+        let args: Vec<String> = parse(&cmdline).into_iter()
+            .map(|a| a.into_string().unwrap())
+            .collect();
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn simple() {
+        chk("", &[]);
+        chk("  ", &[]);
+        chk("app.efi", &["app.efi"]);
+        chk("app.efi  -v\tfs0:\\foo", &["app.efi", "-v", "fs0:\\foo"]);
+    }
+
+    #[test]
+    fn quotes_and_escapes() {
+        chk("app.efi \"a b\" c", &["app.efi", "a b", "c"]);
+        chk("app.efi \"\"", &["app.efi", ""]);
+        chk("app.efi a^\"b ^^", &["app.efi", "a\"b", "^"]);
+        chk("app.efi a^ b", &["app.efi", "a b"]);
+    }
+}
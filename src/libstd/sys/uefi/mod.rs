@@ -0,0 +1,188 @@
+//! System bindings for UEFI applications
+//!
+//! This module contains the facade (aka platform-specific) implementations of
+//! OS level functionality for `x86_64-unknown-uefi`. There is no operating
+//! system underneath us: the firmware calls `efi_main` below with a handle to
+//! the loaded image and a pointer to its system table, and everything else
+//! (memory, consoles, clocks, the command line) is reached through the
+//! protocols and service tables found there.
+//!
+//! UEFI is single-threaded and has no processes, sockets or environment, so
+//! those parts reuse the stubs from the wasm backend.
+
+use io;
+use os::raw::{c_char, c_int};
+use ptr;
+
+pub mod abi;
+
+pub mod alloc;
+pub mod args;
+#[cfg(feature = "backtrace")]
+#[path = "../wasm/backtrace.rs"]
+pub mod backtrace;
+#[path = "../wasm/cmath.rs"]
+pub mod cmath;
+#[path = "../wasm/condvar.rs"]
+pub mod condvar;
+pub mod env;
+#[path = "../wasm/fs.rs"]
+pub mod fs;
+#[path = "../wasm/memchr.rs"]
+pub mod memchr;
+#[path = "../wasm/mutex.rs"]
+pub mod mutex;
+#[path = "../wasm/net.rs"]
+pub mod net;
+pub mod os;
+#[path = "../wasm/os_str.rs"]
+pub mod os_str;
+#[path = "../wasm/path.rs"]
+pub mod path;
+#[path = "../wasm/pipe.rs"]
+pub mod pipe;
+#[path = "../wasm/process.rs"]
+pub mod process;
+#[path = "../wasm/rwlock.rs"]
+pub mod rwlock;
+#[path = "../wasm/stack_overflow.rs"]
+pub mod stack_overflow;
+pub mod stdio;
+pub mod thread;
+#[path = "../wasm/thread_local.rs"]
+pub mod thread_local;
+pub mod time;
+
+static mut IMAGE_HANDLE: abi::Handle = 0 as abi::Handle;
+static mut SYSTEM_TABLE: *mut abi::SystemTable = 0 as *mut abi::SystemTable;
+
+/// Image entry point, called by the firmware.
+///
+/// This stashes away the image handle and system table and then hands over
+/// to the `main` function generated by the compiler, which goes through the
+/// usual `lang_start` machinery.
+#[cfg(not(test))]
+#[no_mangle]
+pub extern "win64" fn efi_main(image: abi::Handle,
+                               st: *mut abi::SystemTable) -> abi::Status {
+    extern {
+        fn main(argc: c_int, argv: *const *const c_char) -> c_int;
+    }
+
+    unsafe {
+        IMAGE_HANDLE = image;
+        SYSTEM_TABLE = st;
+        match main(0, ptr::null()) {
+            0 => abi::SUCCESS,
+            _ => abi::ABORTED,
+        }
+    }
+}
+
+/// The handle of the image we were loaded as.
+pub fn image_handle() -> abi::Handle {
+    unsafe { IMAGE_HANDLE }
+}
+
+/// The system table passed to `efi_main`.
+pub fn system_table() -> &'static abi::SystemTable {
+    unsafe {
+        assert!(!SYSTEM_TABLE.is_null(), "UEFI system table not initialized");
+        &*SYSTEM_TABLE
+    }
+}
+
+/// The boot services table.
+///
+/// libstd never calls `ExitBootServices`, so this stays valid for the whole
+/// lifetime of the program.
+pub fn boot_services() -> &'static abi::BootServices {
+    unsafe { &*system_table().boot_services }
+}
+
+pub fn runtime_services() -> &'static abi::RuntimeServices {
+    unsafe { &*system_table().runtime_services }
+}
+
+#[cfg(not(test))]
+pub fn init() {
+}
+
+pub fn unsupported<T>() -> io::Result<T> {
+    Err(unsupported_err())
+}
+
+pub fn unsupported_err() -> io::Error {
+    io::Error::new(io::ErrorKind::Other,
+                   "operation not supported on UEFI yet")
+}
+
+/// Converts an `EFI_STATUS` into an `io::Result`.
+///
+/// Warnings (non-zero codes without the error bit) are treated as success,
+/// errors are stored as their code with the error bit stripped.
+pub fn cvt(status: abi::Status) -> io::Result<()> {
+    if status & abi::ERROR_BIT == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::from_raw_os_error((status & !abi::ERROR_BIT) as i32))
+    }
+}
+
+pub fn decode_error_kind(code: i32) -> io::ErrorKind {
+    match code as abi::Status | abi::ERROR_BIT {
+        abi::NOT_FOUND => io::ErrorKind::NotFound,
+        abi::ACCESS_DENIED | abi::WRITE_PROTECTED | abi::SECURITY_VIOLATION =>
+            io::ErrorKind::PermissionDenied,
+        abi::INVALID_PARAMETER => io::ErrorKind::InvalidInput,
+        abi::TIMEOUT => io::ErrorKind::TimedOut,
+        abi::NOT_READY => io::ErrorKind::WouldBlock,
+        abi::ALREADY_STARTED => io::ErrorKind::AlreadyExists,
+        abi::END_OF_FILE | abi::END_OF_MEDIA => io::ErrorKind::UnexpectedEof,
+        abi::COMPROMISED_DATA | abi::CRC_ERROR | abi::VOLUME_CORRUPTED =>
+            io::ErrorKind::InvalidData,
+        _ => io::ErrorKind::Other,
+    }
+}
+
+// This enum is used as the storage for a bunch of types which can't actually
+// exist.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub enum Void {}
+
+pub unsafe fn strlen(mut s: *const c_char) -> usize {
+    let mut n = 0;
+    while *s != 0 {
+        n += 1;
+        s = s.offset(1);
+    }
+    return n
+}
+
+pub unsafe fn abort_internal() -> ! {
+    ::intrinsics::abort()
+}
+
+pub fn hashmap_random_keys() -> (u64, u64) {
+    let mut ret = (0u64, 0u64);
+    unsafe {
+        let base = &mut ret as *mut (u64, u64) as *mut u8;
+        let len = ::mem::size_of_val(&ret);
+
+        // Prefer the firmware's RNG protocol, but plenty of machines don't
+        // implement it, so fall back to the cycle counter rather than failing.
+        let mut rng = ptr::null_mut();
+        let found = (boot_services().locate_protocol)(&abi::RNG_PROTOCOL_GUID,
+                                                      ptr::null_mut(),
+                                                      &mut rng);
+        if cvt(found).is_ok() {
+            let rng = rng as *mut abi::RngProtocol;
+            if cvt(((*rng).get_rng)(rng, ptr::null(), len, base)).is_ok() {
+                return ret
+            }
+        }
+        ret.0 = ::arch::x86_64::_rdtsc();
+        ret.1 = ::arch::x86_64::_rdtsc().rotate_left(32) ^ (base as u64);
+    }
+    return ret
+}
@@ -0,0 +1,234 @@
+use error::Error as StdError;
+use ffi::{OsString, OsStr};
+use fmt;
+use io;
+use iter;
+use marker::PhantomData;
+use path::{self, PathBuf};
+use ptr;
+use slice;
+use sys::abi;
+use sys::os_str::Buf;
+use sys::{boot_services, image_handle, unsupported};
+use sys_common::mutex::{Mutex, MutexGuard};
+use sys_common::{AsInner, FromInner};
+use vec;
+
+pub fn errno() -> i32 {
+    // Firmware calls report their errors through their return value, so
+    // there's no errno to consult.
+    0
+}
+
+pub fn error_string(errno: i32) -> String {
+    let msg = match errno as abi::Status | abi::ERROR_BIT {
+        abi::LOAD_ERROR => "image failed to load",
+        abi::INVALID_PARAMETER => "invalid parameter",
+        abi::UNSUPPORTED => "operation not supported",
+        abi::BAD_BUFFER_SIZE => "bad buffer size",
+        abi::BUFFER_TOO_SMALL => "buffer too small",
+        abi::NOT_READY => "no data pending",
+        abi::DEVICE_ERROR => "device error",
+        abi::WRITE_PROTECTED => "device is write protected",
+        abi::OUT_OF_RESOURCES => "out of resources",
+        abi::VOLUME_CORRUPTED => "file system is corrupted",
+        abi::VOLUME_FULL => "no space left on file system",
+        abi::NO_MEDIA => "no medium in device",
+        abi::MEDIA_CHANGED => "medium changed",
+        abi::NOT_FOUND => "item not found",
+        abi::ACCESS_DENIED => "access denied",
+        abi::NO_RESPONSE => "no response from server",
+        abi::NO_MAPPING => "no mapping to device",
+        abi::TIMEOUT => "timeout expired",
+        abi::NOT_STARTED => "protocol not started",
+        abi::ALREADY_STARTED => "protocol already started",
+        abi::ABORTED => "operation aborted",
+        abi::ICMP_ERROR => "ICMP error",
+        abi::TFTP_ERROR => "TFTP error",
+        abi::PROTOCOL_ERROR => "protocol error",
+        abi::INCOMPATIBLE_VERSION => "incompatible version",
+        abi::SECURITY_VIOLATION => "security violation",
+        abi::CRC_ERROR => "CRC error",
+        abi::END_OF_MEDIA => "end of medium",
+        abi::END_OF_FILE => "end of file",
+        abi::INVALID_LANGUAGE => "invalid language",
+        abi::COMPROMISED_DATA => "compromised data",
+        _ => return format!("unknown error {}", errno),
+    };
+    msg.to_string()
+}
+
+pub fn getcwd() -> io::Result<PathBuf> {
+    unsupported()
+}
+
+pub fn chdir(_: &path::Path) -> io::Result<()> {
+    unsupported()
+}
+
+// The UEFI shell separates `path` entries with semicolons.
+const PATH_SEP: u8 = b';';
+
+pub struct SplitPaths<'a> {
+    iter: iter::Map<slice::Split<'a, u8, fn(&u8) -> bool>,
+                    fn(&'a [u8]) -> PathBuf>,
+}
+
+pub fn split_paths(unparsed: &OsStr) -> SplitPaths {
+    fn bytes_to_path(b: &[u8]) -> PathBuf {
+        PathBuf::from(OsString::from_inner(Buf { inner: b.to_vec() }))
+    }
+    fn is_sep(b: &u8) -> bool { *b == PATH_SEP }
+    SplitPaths {
+        iter: unparsed.as_inner().inner.split(is_sep as fn(&u8) -> bool)
+                      .map(bytes_to_path as fn(&[u8]) -> PathBuf)
+    }
+}
+
+impl<'a> Iterator for SplitPaths<'a> {
+    type Item = PathBuf;
+    fn next(&mut self) -> Option<PathBuf> { self.iter.next() }
+    fn size_hint(&self) -> (usize, Option<usize>) { self.iter.size_hint() }
+}
+
+#[derive(Debug)]
+pub struct JoinPathsError;
+
+pub fn join_paths<I, T>(paths: I) -> Result<OsString, JoinPathsError>
+    where I: Iterator<Item=T>, T: AsRef<OsStr>
+{
+    let mut joined = Vec::new();
+
+    for (i, path) in paths.enumerate() {
+        let path = &path.as_ref().as_inner().inner;
+        if i > 0 { joined.push(PATH_SEP) }
+        if path.contains(&PATH_SEP) {
+            return Err(JoinPathsError)
+        }
+        joined.extend_from_slice(path);
+    }
+    Ok(OsString::from_inner(Buf { inner: joined }))
+}
+
+impl fmt::Display for JoinPathsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        "path segment contains separator `;`".fmt(f)
+    }
+}
+
+impl StdError for JoinPathsError {
+    fn description(&self) -> &str { "failed to join paths" }
+}
+
+pub fn current_exe() -> io::Result<PathBuf> {
+    unsupported()
+}
+
+pub struct Env {
+    iter: vec::IntoIter<(OsString, OsString)>,
+    _dont_send_or_sync_me: PhantomData<*mut ()>,
+}
+
+impl Iterator for Env {
+    type Item = (OsString, OsString);
+    fn next(&mut self) -> Option<(OsString, OsString)> { self.iter.next() }
+    fn size_hint(&self) -> (usize, Option<usize>) { self.iter.size_hint() }
+}
+
+pub unsafe fn env_lock() -> MutexGuard<'static> {
+    // We never call `ENV_LOCK.init()`, so it is UB to attempt to
+    // acquire this mutex reentrantly!
+    static ENV_LOCK: Mutex = Mutex::new();
+    ENV_LOCK.lock()
+}
+
+// UEFI applications don't inherit an environment (shell variables aren't
+// passed to the image), so the process environment starts out empty and
+// lives entirely in our own memory. Only ever accessed with `env_lock` held.
+static mut ENV: *mut Vec<(OsString, OsString)> = 0 as *mut _;
+
+unsafe fn environ() -> &'static mut Vec<(OsString, OsString)> {
+    if ENV.is_null() {
+        ENV = Box::into_raw(Box::new(Vec::new()));
+    }
+    &mut *ENV
+}
+
+/// Returns a vector of (variable, value) byte-vector pairs for all the
+/// environment variables of the current process.
+pub fn env() -> Env {
+    unsafe {
+        let _guard = env_lock();
+        Env {
+            iter: environ().clone().into_iter(),
+            _dont_send_or_sync_me: PhantomData,
+        }
+    }
+}
+
+pub use sys_common::env_snapshot::{EnvSnapshot, EnvSnapshotIter};
+
+/// Returns a snapshot of the environment variables of the current process.
+pub fn env_snapshot() -> EnvSnapshot {
+    EnvSnapshot::new(env())
+}
+
+pub fn getenv(k: &OsStr) -> io::Result<Option<OsString>> {
+    unsafe {
+        let _guard = env_lock();
+        Ok(environ().iter()
+            .find(|&&(ref key, _)| &**key == k)
+            .map(|&(_, ref value)| value.clone()))
+    }
+}
+
+pub fn setenv(k: &OsStr, v: &OsStr) -> io::Result<()> {
+    // Keep the same restrictions as the other platforms so programs don't
+    // start depending on being able to create unrepresentable variables.
+    let key = &k.as_inner().inner;
+    if key.is_empty() || key.contains(&b'=') || key.contains(&0) ||
+        v.as_inner().inner.contains(&0)
+    {
+        return Err(io::Error::from_raw_os_error(
+            (abi::INVALID_PARAMETER & !abi::ERROR_BIT) as i32))
+    }
+
+    unsafe {
+        let _guard = env_lock();
+        let env = environ();
+        match env.iter_mut().find(|&&mut (ref key, _)| &**key == k) {
+            Some(&mut (_, ref mut value)) => *value = v.to_os_string(),
+            None => env.push((k.to_os_string(), v.to_os_string())),
+        }
+        Ok(())
+    }
+}
+
+pub fn unsetenv(n: &OsStr) -> io::Result<()> {
+    unsafe {
+        let _guard = env_lock();
+        environ().retain(|&(ref key, _)| &**key != n);
+        Ok(())
+    }
+}
+
+pub fn temp_dir() -> PathBuf {
+    panic!("no temporary directory on UEFI")
+}
+
+pub fn home_dir() -> Option<PathBuf> {
+    None
+}
+
+pub fn exit(code: i32) -> ! {
+    let status = if code == 0 { abi::SUCCESS } else { abi::ABORTED };
+    unsafe {
+        (boot_services().exit)(image_handle(), status, 0, ptr::null_mut());
+        // `Exit` only returns if the image handle was bogus.
+        ::intrinsics::abort()
+    }
+}
+
+pub fn getpid() -> u32 {
+    panic!("no pids on UEFI")
+}
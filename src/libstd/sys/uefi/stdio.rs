@@ -0,0 +1,136 @@
+use char;
+use io;
+use str;
+use sys::abi;
+use sys::{boot_services, cvt, system_table};
+
+pub struct Stdin(());
+pub struct Stdout(());
+pub struct Stderr(());
+
+const REPLACEMENT: u16 = 0xfffd;
+
+/// Writes UTF-8 `data` to a Simple Text Output protocol.
+///
+/// The console takes NUL-terminated UCS-2 strings and needs `\r\n` line
+/// endings, so the data is converted in small chunks on the stack.
+/// Characters outside the basic multilingual plane can't be displayed and
+/// are written as U+FFFD.
+fn write(out: *mut abi::SimpleTextOutputProtocol, data: &[u8]) -> io::Result<usize> {
+    let s = match str::from_utf8(data) {
+        Ok(s) => s,
+        Err(e) if e.valid_up_to() > 0 => unsafe {
+            str::from_utf8_unchecked(&data[..e.valid_up_to()])
+        },
+        Err(e) => {
+            output(out, &[REPLACEMENT, 0])?;
+            return Ok(e.error_len().unwrap_or(data.len()))
+        }
+    };
+
+    let mut buf = [0u16; 128];
+    let mut len = 0;
+    for c in s.chars() {
+        if c == '\n' {
+            buf[len] = '\r' as u16;
+            len += 1;
+        }
+        buf[len] = if (c as u32) < 0x10000 { c as u16 } else { REPLACEMENT };
+        len += 1;
+        if len >= buf.len() - 3 {
+            buf[len] = 0;
+            output(out, &buf[..len + 1])?;
+            len = 0;
+        }
+    }
+    if len > 0 {
+        buf[len] = 0;
+        output(out, &buf[..len + 1])?;
+    }
+    Ok(s.len())
+}
+
+fn output(out: *mut abi::SimpleTextOutputProtocol, s: &[u16]) -> io::Result<()> {
+    debug_assert_eq!(s.last(), Some(&0));
+    unsafe { cvt(((*out).output_string)(out, s.as_ptr())) }
+}
+
+impl Stdin {
+    pub fn new() -> io::Result<Stdin> { Ok(Stdin(())) }
+
+    /// Blocks until a key is pressed and returns it UTF-8 encoded.
+    ///
+    /// Keys without a character (arrows, function keys, ...) are skipped and
+    /// the enter key, which the console reports as `\r`, reads as `\n`.
+    pub fn read(&self, data: &mut [u8]) -> io::Result<usize> {
+        if data.len() < 4 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "buffer too small to hold a character"))
+        }
+        let con_in = system_table().con_in;
+        loop {
+            let mut key = abi::InputKey { scan_code: 0, unicode_char: 0 };
+            let status = unsafe { ((*con_in).read_key_stroke)(con_in, &mut key) };
+            if status == abi::NOT_READY {
+                let mut index = 0;
+                unsafe {
+                    cvt((boot_services().wait_for_event)(1, &(*con_in).wait_for_key,
+                                                         &mut index))?;
+                }
+                continue
+            }
+            cvt(status)?;
+
+            let c = match key.unicode_char {
+                0 => continue,
+                0x0d => '\n',
+                c => char::from_u32(c as u32).unwrap_or(char::REPLACEMENT_CHARACTER),
+            };
+            return Ok(c.encode_utf8(data).len())
+        }
+    }
+}
+
+impl Stdout {
+    pub fn new() -> io::Result<Stdout> { Ok(Stdout(())) }
+
+    pub fn write(&self, data: &[u8]) -> io::Result<usize> {
+        write(system_table().con_out, data)
+    }
+
+    pub fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Stderr {
+    pub fn new() -> io::Result<Stderr> { Ok(Stderr(())) }
+
+    pub fn write(&self, data: &[u8]) -> io::Result<usize> {
+        write(system_table().std_err, data)
+    }
+
+    pub fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl io::Write for Stderr {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        Stderr::write(self, data)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Stderr::flush(self)
+    }
+}
+
+pub fn is_ebadf(_err: &io::Error) -> bool {
+    false
+}
+
+pub const STDIN_BUF_SIZE: usize = ::sys_common::io::DEFAULT_BUF_SIZE;
+
+pub fn panic_output() -> Option<impl io::Write> {
+    Stderr::new().ok()
+}
@@ -0,0 +1,131 @@
+use arch::x86_64::_rdtsc;
+use mem;
+use sync::Once;
+use sys::{boot_services, cvt, runtime_services};
+use sys::abi;
+use time::Duration;
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub struct Instant(Duration);
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub struct SystemTime(Duration);
+
+pub const UNIX_EPOCH: SystemTime = SystemTime(Duration::from_secs(0));
+
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+/// Frequency of the time stamp counter in ticks per second.
+///
+/// Boot services have no clock with a known rate, only `Stall`, so the TSC
+/// is calibrated against a short stall the first time it's needed.
+fn tsc_frequency() -> u64 {
+    static INIT: Once = Once::new();
+    static mut FREQ: u64 = 0;
+
+    const CALIBRATION_US: u64 = 10_000;
+
+    unsafe {
+        INIT.call_once(|| {
+            let start = _rdtsc();
+            cvt((boot_services().stall)(CALIBRATION_US as usize)).unwrap();
+            let end = _rdtsc();
+            FREQ = (end - start) * (1_000_000 / CALIBRATION_US);
+        });
+        FREQ
+    }
+}
+
+impl Instant {
+    pub fn now() -> Instant {
+        let freq = tsc_frequency();
+        let ticks = unsafe { _rdtsc() };
+        Instant(Duration::new(
+            ticks / freq,
+            ((ticks % freq) * NANOS_PER_SEC / freq) as u32,
+        ))
+    }
+
+    pub const fn zero() -> Instant {
+        Instant(Duration::from_secs(0))
+    }
+
+    pub fn actually_monotonic() -> bool {
+        // The TSC isn't necessarily synchronized across cores, but UEFI only
+        // ever runs us on the boot processor.
+        true
+    }
+
+    pub fn sub_instant(&self, other: &Instant) -> Duration {
+        self.0 - other.0
+    }
+
+    pub fn checked_add_duration(&self, other: &Duration) -> Option<Instant> {
+        Some(Instant(self.0.checked_add(*other)?))
+    }
+
+    pub fn checked_sub_duration(&self, other: &Duration) -> Option<Instant> {
+        Some(Instant(self.0.checked_sub(*other)?))
+    }
+}
+
+/// Number of days between 1970-01-01 and the given date in the proleptic
+/// Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = (if y >= 0 { y } else { y - 399 }) / 400;
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+impl SystemTime {
+    pub fn now() -> SystemTime {
+        let mut t: abi::Time = unsafe { mem::zeroed() };
+        cvt((runtime_services().get_time)(&mut t, 0 as *mut _)).unwrap();
+        SystemTime::from_uefi_time(&t)
+    }
+
+    fn from_uefi_time(t: &abi::Time) -> SystemTime {
+        let days = days_from_civil(t.year as i64, t.month as i64, t.day as i64);
+        let mut secs = days * 86400 +
+            t.hour as i64 * 3600 + t.minute as i64 * 60 + t.second as i64;
+        // The firmware keeps local time, with `Localtime = UTC - TimeZone`.
+        if t.time_zone != abi::UNSPECIFIED_TIMEZONE {
+            secs += t.time_zone as i64 * 60;
+        }
+        // Anything before the epoch is a firmware clock that was never set.
+        if secs < 0 {
+            return UNIX_EPOCH
+        }
+        SystemTime(Duration::new(secs as u64, t.nanosecond))
+    }
+
+    pub fn sub_time(&self, other: &SystemTime)
+                    -> Result<Duration, Duration> {
+        self.0.checked_sub(other.0).ok_or_else(|| other.0 - self.0)
+    }
+
+    pub fn checked_add_duration(&self, other: &Duration) -> Option<SystemTime> {
+        Some(SystemTime(self.0.checked_add(*other)?))
+    }
+
+    pub fn checked_sub_duration(&self, other: &Duration) -> Option<SystemTime> {
+        Some(SystemTime(self.0.checked_sub(*other)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::days_from_civil;
+
+    #[test]
+    fn civil() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11017);
+        assert_eq!(days_from_civil(2019, 1, 24), 17920);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+    }
+}
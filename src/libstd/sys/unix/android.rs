@@ -18,10 +18,14 @@
 
 #![cfg(target_os = "android")]
 
-use libc::{c_int, c_void, sighandler_t, size_t, ssize_t};
+use libc::{c_char, c_int, c_void, sighandler_t, size_t, ssize_t};
 use libc::{ftruncate, pread, pwrite};
 
+use cmp;
+use ffi::CStr;
 use io;
+use slice;
+use sync::atomic::{AtomicUsize, Ordering};
 use super::{cvt, cvt_r};
 
 // The `log2` and `log2f` functions apparently appeared in android-18, or at
@@ -158,3 +162,47 @@ pub unsafe fn cvt_pwrite64(fd: c_int, buf: *const c_void, count: size_t, offset:
 {
     cvt(pwrite(fd, buf, count, offset))
 }
+
+// System properties have been readable with `__system_property_get` since the
+// very first NDK. It's deprecated in favor of `__system_property_find` and
+// `__system_property_read_callback` (android-26), which are only needed for
+// read-only properties longer than `PROP_VALUE_MAX`, something none of the
+// properties we care about are.
+extern {
+    fn __system_property_get(name: *const c_char, value: *mut c_char) -> c_int;
+}
+
+const PROP_VALUE_MAX: usize = 92;
+
+/// Reads a system property, returning `None` if it's unset or empty (which
+/// the old API doesn't distinguish).
+pub fn system_property(name: &CStr) -> Option<Vec<u8>> {
+    let mut value = [0 as c_char; PROP_VALUE_MAX];
+    let len = unsafe { __system_property_get(name.as_ptr(), value.as_mut_ptr()) };
+    if len <= 0 {
+        return None
+    }
+    let len = cmp::min(len as usize, PROP_VALUE_MAX - 1);
+    Some(unsafe { slice::from_raw_parts(value.as_ptr() as *const u8, len) }.to_vec())
+}
+
+/// The API level of the running system, or 0 if it can't be determined.
+///
+/// This is the device's level, which can be newer than the one we were
+/// compiled for, so it tells which libc functions and syscalls can be used.
+pub fn api_level() -> u32 {
+    // Cached as `level + 1` so that 0 means "not read yet".
+    static LEVEL: AtomicUsize = AtomicUsize::new(0);
+
+    match LEVEL.load(Ordering::Relaxed) {
+        0 => {}
+        n => return (n - 1) as u32,
+    }
+    let name = CStr::from_bytes_with_nul(b"ro.build.version.sdk\0").unwrap();
+    let level = system_property(name)
+        .and_then(|v| String::from_utf8(v).ok())
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(0);
+    LEVEL.store(level as usize + 1, Ordering::Relaxed);
+    level
+}
@@ -5,7 +5,11 @@
 
 #![allow(dead_code)] // runtime init functions not used during testing
 
-use ffi::OsString;
+use cmp;
+use ffi::{CStr, OsStr, OsString};
+use fmt;
+use io;
+use libc;
 use marker::PhantomData;
 use vec;
 
@@ -20,29 +24,158 @@ pub fn args() -> Args {
     imp::args()
 }
 
+/// Returns the `argc` and `argv` the process was started with, or `(0, null)`
+/// where they aren't known.
+pub fn raw() -> (isize, *const *const u8) {
+    imp::raw()
+}
+
+/// Returns the ELF auxiliary vector the process was started with, without
+/// its terminating `AT_NULL` entry, or an empty slice where it isn't known.
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "l4re"))]
+pub fn auxv() -> &'static [[usize; 2]] {
+    imp::auxv()
+}
+
+/// Overwrites the memory the original arguments were in with `name`, for
+/// tools like `ps` which show it as the process' command line.
+pub fn set_name(name: &OsStr) -> io::Result<()> {
+    imp::set_name(name)
+}
+
 pub struct Args {
-    iter: vec::IntoIter<OsString>,
+    inner: Inner,
     _dont_send_or_sync_me: PhantomData<*mut ()>,
 }
 
+enum Inner {
+    /// The arguments in `front..back` of `argv`, read as they're asked for.
+    Argv { argv: *const *const u8, front: usize, back: usize },
+    /// Arguments copied out up front, where there's no `argv` to index.
+    Owned(vec::IntoIter<OsString>),
+}
+
+unsafe fn arg_at(argv: *const *const u8, i: usize) -> OsString {
+    use os::unix::ffi::OsStringExt;
+    let _guard = imp::lock();
+    let cstr = CStr::from_ptr(*argv.add(i) as *const libc::c_char);
+    OsStringExt::from_vec(cstr.to_bytes().to_vec())
+}
+
 impl Args {
-    pub fn inner_debug(&self) -> &[OsString] {
-        self.iter.as_slice()
+    fn from_argv(argv: *const *const u8, argc: usize) -> Args {
+        Args {
+            inner: Inner::Argv { argv, front: 0, back: argc },
+            _dont_send_or_sync_me: PhantomData,
+        }
+    }
+
+    fn owned(args: Vec<OsString>) -> Args {
+        Args { inner: Inner::Owned(args.into_iter()), _dont_send_or_sync_me: PhantomData }
+    }
+
+    /// Returns the argument `i` places after the next one, without advancing.
+    pub fn get(&self, i: usize) -> Option<OsString> {
+        match self.inner {
+            Inner::Argv { argv, front, back } => {
+                if i < back - front {
+                    Some(unsafe { arg_at(argv, front + i) })
+                } else {
+                    None
+                }
+            }
+            Inner::Owned(ref iter) => iter.as_slice().get(i).cloned(),
+        }
+    }
+
+    pub fn inner_debug(&self) -> ArgsInnerDebug {
+        ArgsInnerDebug { args: self }
+    }
+}
+
+/// Formats the remaining arguments.
+pub struct ArgsInnerDebug<'a> {
+    args: &'a Args,
+}
+
+impl<'a> fmt::Debug for ArgsInnerDebug<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.args.inner {
+            Inner::Argv { argv, front, back } => {
+                // The lock can't be held while the formatter runs, which may
+                // well look at the arguments itself.
+                let args = (front..back).map(|i| unsafe { arg_at(argv, i) }).collect::<Vec<_>>();
+                args.fmt(f)
+            }
+            Inner::Owned(ref iter) => iter.as_slice().fmt(f),
+        }
     }
 }
 
 impl Iterator for Args {
     type Item = OsString;
-    fn next(&mut self) -> Option<OsString> { self.iter.next() }
-    fn size_hint(&self) -> (usize, Option<usize>) { self.iter.size_hint() }
+
+    fn next(&mut self) -> Option<OsString> {
+        match self.inner {
+            Inner::Argv { argv, ref mut front, back } => {
+                if *front < back {
+                    *front += 1;
+                    Some(unsafe { arg_at(argv, *front - 1) })
+                } else {
+                    None
+                }
+            }
+            Inner::Owned(ref mut iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<OsString> {
+        match self.inner {
+            Inner::Argv { ref mut front, back, .. } => {
+                *front = cmp::min(front.saturating_add(n), back);
+            }
+            Inner::Owned(ref mut iter) => return iter.nth(n),
+        }
+        self.next()
+    }
+
+    fn last(mut self) -> Option<OsString> {
+        self.next_back()
+    }
+
+    fn count(self) -> usize {
+        self.len()
+    }
 }
 
 impl ExactSizeIterator for Args {
-    fn len(&self) -> usize { self.iter.len() }
+    fn len(&self) -> usize {
+        match self.inner {
+            Inner::Argv { front, back, .. } => back - front,
+            Inner::Owned(ref iter) => iter.len(),
+        }
+    }
 }
 
 impl DoubleEndedIterator for Args {
-    fn next_back(&mut self) -> Option<OsString> { self.iter.next_back() }
+    fn next_back(&mut self) -> Option<OsString> {
+        match self.inner {
+            Inner::Argv { argv, front, ref mut back } => {
+                if front < *back {
+                    *back -= 1;
+                    Some(unsafe { arg_at(argv, *back) })
+                } else {
+                    None
+                }
+            }
+            Inner::Owned(ref mut iter) => iter.next_back(),
+        }
+    }
 }
 
 #[cfg(any(target_os = "linux",
@@ -57,27 +190,175 @@ impl DoubleEndedIterator for Args {
           target_os = "haiku",
           target_os = "l4re",
           target_os = "fuchsia",
-          target_os = "hermit"))]
+          target_os = "hermit",
+          target_os = "espidf",
+          target_os = "nto"))]
 mod imp {
     use os::unix::prelude::*;
+    use cmp;
     use ptr;
-    use ffi::{CStr, OsString};
-    use marker::PhantomData;
+    use ffi::{CStr, CString, OsStr};
+    use io;
     use libc;
+    use slice;
     use super::Args;
 
-    use sys_common::mutex::Mutex;
+    use sys_common::mutex::{Mutex, MutexGuard};
 
     static mut ARGC: isize = 0;
     static mut ARGV: *const *const u8 = ptr::null();
+    // Unlike the arguments this is never replaced or cleared, the kernel's
+    // copy stays where it is for the life of the process.
+    static mut AUXV: *const [usize; 2] = ptr::null();
+    // The `argv` given to `init`, and the contiguous memory its strings were
+    // found in, which `set_name` overwrites. `ORIG_ARGV`'s own entries are
+    // pointed at copies of the strings first, with `ORIG_MOVED` set, so
+    // anything reading through an `argv` holds `LOCK` while it does.
+    static mut ORIG_ARGC: isize = 0;
+    static mut ORIG_ARGV: *const *const u8 = ptr::null();
+    static mut ORIG_AREA: (*mut u8, usize) = (ptr::null_mut(), 0);
+    static mut ORIG_MOVED: bool = false;
     // We never call `ENV_LOCK.init()`, so it is UB to attempt to
     // acquire this mutex reentrantly!
     static LOCK: Mutex = Mutex::new();
 
     pub unsafe fn init(argc: isize, argv: *const *const u8) {
+        let (argc, argv) = startup_args(argc, argv);
         let _guard = LOCK.lock();
         ARGC = argc;
         ARGV = argv;
+        AUXV = find_auxv(argc, argv);
+        ORIG_ARGC = argc;
+        ORIG_ARGV = argv;
+        ORIG_AREA = find_area(argc, argv);
+    }
+
+    // The kernel copies the argument strings one after another, so the area
+    // runs from the first to the end of the last one that directly follows
+    // its predecessor.
+    unsafe fn find_area(argc: isize, argv: *const *const u8) -> (*mut u8, usize) {
+        if argc <= 0 || argv.is_null() || (*argv).is_null() {
+            return (ptr::null_mut(), 0);
+        }
+        let start = *argv as *mut u8;
+        let mut end = start;
+        for i in 0..argc {
+            let arg = *argv.offset(i) as *mut u8;
+            if arg != end {
+                break;
+            }
+            end = end.add(libc::strlen(arg as *const libc::c_char) + 1);
+        }
+        (start, end as usize - start as usize)
+    }
+
+    pub fn set_name(name: &OsStr) -> io::Result<()> {
+        let name = CString::new(name.as_bytes())?;
+        unsafe {
+            {
+                let _guard = LOCK.lock();
+                let (start, len) = ORIG_AREA;
+                if start.is_null() {
+                    return Err(io::Error::new(io::ErrorKind::Other,
+                                              "the original arguments aren't known"));
+                }
+                if !ORIG_MOVED {
+                    // Anything reading the arguments through `argv` from now
+                    // on gets the copies. They're never freed, since there's
+                    // no telling who else holds the pointers.
+                    for i in 0..ORIG_ARGC {
+                        let arg = ORIG_ARGV.offset(i) as *mut *const u8;
+                        let copy = CString::from(CStr::from_ptr(*arg as *const libc::c_char));
+                        *arg = copy.into_raw() as *const u8;
+                    }
+                    ORIG_MOVED = true;
+                }
+                let area = slice::from_raw_parts_mut(start, len);
+                let bytes = name.as_bytes();
+                // Keep the last byte as the terminating nul.
+                let n = cmp::min(bytes.len(), len - 1);
+                area[..n].copy_from_slice(&bytes[..n]);
+                for b in &mut area[n..] {
+                    *b = 0;
+                }
+            }
+            set_comm(&name);
+        }
+        Ok(())
+    }
+
+    // HermitCore's kernel is handed the arguments by the proxy which boots it,
+    // and keeps them for newlib's startup code, which doesn't always pass
+    // them on to `main`.
+    #[cfg(target_os = "hermit")]
+    unsafe fn startup_args(argc: isize, argv: *const *const u8) -> (isize, *const *const u8) {
+        extern {
+            static libc_argc: libc::c_int;
+            static libc_argv: *const *const u8;
+        }
+        if argc == 0 && !libc_argv.is_null() {
+            (libc_argc as isize, libc_argv)
+        } else {
+            (argc, argv)
+        }
+    }
+
+    #[cfg(not(target_os = "hermit"))]
+    unsafe fn startup_args(argc: isize, argv: *const *const u8) -> (isize, *const *const u8) {
+        (argc, argv)
+    }
+
+    // Linux also keeps a short name for each thread; the main thread's is
+    // what `top` and `ps -o comm` show as the process name. It's set through
+    // procfs, as `prctl` would only rename the calling thread. The main
+    // thread's id is the pid.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn set_comm(name: &CStr) {
+        let path = format!("/proc/self/task/{}/comm", unsafe { libc::getpid() });
+        let _ = ::fs::write(path, name.to_bytes());
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    fn set_comm(_name: &CStr) {
+    }
+
+    // The kernel lays out the start of the stack as `argv`, then `envp`,
+    // then the auxiliary vector, each ended by a null entry; the `argv`
+    // given to `main` points into it. L4Re's loader sets up the initial
+    // stack of its programs the same way, with its own entries in the
+    // auxiliary vector for the environment and kernel info page.
+    #[cfg(any(target_os = "linux", target_os = "android", target_os = "l4re"))]
+    unsafe fn find_auxv(argc: isize, argv: *const *const u8) -> *const [usize; 2] {
+        if argv.is_null() {
+            return ptr::null();
+        }
+        let mut envp = argv.offset(argc + 1);
+        while !(*envp).is_null() {
+            envp = envp.add(1);
+        }
+        envp.add(1) as *const [usize; 2]
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "android", target_os = "l4re")))]
+    unsafe fn find_auxv(_argc: isize, _argv: *const *const u8) -> *const [usize; 2] {
+        ptr::null()
+    }
+
+    pub fn auxv() -> &'static [[usize; 2]] {
+        unsafe {
+            let auxv = {
+                let _guard = LOCK.lock();
+                AUXV
+            };
+            if auxv.is_null() {
+                return &[];
+            }
+            let mut len = 0;
+            while (*auxv.add(len))[0] != 0 {
+                len += 1;
+            }
+            slice::from_raw_parts(auxv, len)
+        }
     }
 
     pub unsafe fn cleanup() {
@@ -87,28 +368,40 @@ mod imp {
     }
 
     pub fn args() -> Args {
-        Args {
-            iter: clone().into_iter(),
-            _dont_send_or_sync_me: PhantomData
+        unsafe {
+            let _guard = LOCK.lock();
+            // A runtime started without calling `main` the usual way (say,
+            // through `callMain()` with no arguments) leaves `argv` empty,
+            // but the page's arguments are still on the module object.
+            #[cfg(target_os = "emscripten")]
+            {
+                if ARGC == 0 {
+                    return Args::owned(::sys::emscripten::module_arguments())
+                }
+            }
+            Args::from_argv(ARGV, ARGC as usize)
         }
     }
 
-    fn clone() -> Vec<OsString> {
+    pub fn raw() -> (isize, *const *const u8) {
         unsafe {
             let _guard = LOCK.lock();
-            (0..ARGC).map(|i| {
-                let cstr = CStr::from_ptr(*ARGV.offset(i) as *const libc::c_char);
-                OsStringExt::from_vec(cstr.to_bytes().to_vec())
-            }).collect()
+            (ARGC, ARGV)
         }
     }
+
+    /// Held while reading the strings an `Args` points at, which `set_name`
+    /// may otherwise be moving and overwriting at the same time.
+    pub fn lock() -> MutexGuard<'static> {
+        unsafe { LOCK.lock() }
+    }
 }
 
 #[cfg(any(target_os = "macos",
-          target_os = "ios"))]
+          target_os = "ios",
+          target_os = "tvos",
+          target_os = "watchos"))]
 mod imp {
-    use ffi::CStr;
-    use marker::PhantomData;
     use libc;
     use super::Args;
 
@@ -118,27 +411,37 @@ mod imp {
     pub fn cleanup() {
     }
 
+    /// Returns the `argc` and `argv` dyld passed to the executable. They live
+    /// for the whole process, and aren't copied out.
     #[cfg(target_os = "macos")]
-    pub fn args() -> Args {
-        use os::unix::prelude::*;
+    pub fn raw() -> (isize, *const *const u8) {
         extern {
             // These functions are in crt_externs.h.
             fn _NSGetArgc() -> *mut libc::c_int;
             fn _NSGetArgv() -> *mut *mut *mut libc::c_char;
         }
+        unsafe { (*_NSGetArgc() as isize, *_NSGetArgv() as *const *const u8) }
+    }
 
-        let vec = unsafe {
-            let (argc, argv) = (*_NSGetArgc() as isize,
-                                *_NSGetArgv() as *const *const libc::c_char);
-            (0.. argc as isize).map(|i| {
-                let bytes = CStr::from_ptr(*argv.offset(i)).to_bytes().to_vec();
-                OsStringExt::from_vec(bytes)
-            }).collect::<Vec<_>>()
-        };
-        Args {
-            iter: vec.into_iter(),
-            _dont_send_or_sync_me: PhantomData,
-        }
+    // The arguments only come from the Objective C runtime, see `args`.
+    #[cfg(any(target_os = "ios", target_os = "tvos", target_os = "watchos"))]
+    pub fn raw() -> (isize, *const *const u8) {
+        (0, ::ptr::null())
+    }
+
+    // Nothing rewrites the arguments here, see `set_name`.
+    pub fn lock() {
+    }
+
+    pub fn set_name(_name: &::ffi::OsStr) -> ::io::Result<()> {
+        Err(::io::Error::new(::io::ErrorKind::Other,
+                             "setting the process name is not supported on this platform"))
+    }
+
+    #[cfg(target_os = "macos")]
+    pub fn args() -> Args {
+        let (argc, argv) = raw();
+        Args::from_argv(argv, argc as usize)
     }
 
     // As _NSGetArgc and _NSGetArgv aren't mentioned in iOS docs
@@ -153,11 +456,15 @@ mod imp {
     // for i in (0..[args count])
     //      res.push([args objectAtIndex:i])
     // res
-    #[cfg(target_os = "ios")]
+    //
+    // tvOS and watchOS share this path since, like iOS, they don't pass
+    // argc/argv to the library initializers.
+    #[cfg(any(target_os = "ios", target_os = "tvos", target_os = "watchos"))]
     pub fn args() -> Args {
-        use ffi::OsString;
+        use ffi::CStr;
         use mem;
-        use str;
+        use os::unix::ffi::OsStringExt;
+        use sys_common::wtf8::Wtf8Buf;
 
         extern {
             fn sel_registerName(name: *const libc::c_uchar) -> Sel;
@@ -189,6 +496,8 @@ mod imp {
             let utf8_sel = sel_registerName("UTF8String\0".as_ptr());
             let count_sel = sel_registerName("count\0".as_ptr());
             let object_at_sel = sel_registerName("objectAtIndex:\0".as_ptr());
+            let length_sel = sel_registerName("length\0".as_ptr());
+            let character_at_sel = sel_registerName("characterAtIndex:\0".as_ptr());
 
             let klass = objc_getClass("NSProcessInfo\0".as_ptr());
             let info = objc_msgSend(klass, process_info_sel);
@@ -199,11 +508,26 @@ mod imp {
                 let tmp = objc_msgSend_ul(args, object_at_sel, i as libc::c_ulong);
                 let utf_c_str: *const libc::c_char =
                     mem::transmute(objc_msgSend(tmp, utf8_sel));
-                let bytes = CStr::from_ptr(utf_c_str).to_bytes();
-                res.push(OsString::from(str::from_utf8(bytes).unwrap()))
+                // `UTF8String` gives null for strings with unpaired
+                // surrogates, which UTF-8 can't hold. Rather than lose such an
+                // argument, its UTF-16 code units are encoded the way
+                // `OsString` holds them on Windows, which keeps those too.
+                // Otherwise the bytes are taken as they are, like argv on
+                // other Unix platforms.
+                if utf_c_str.is_null() {
+                    let len: usize = mem::transmute(objc_msgSend(tmp, length_sel));
+                    let units = (0..len).map(|j| {
+                        let unit = objc_msgSend_ul(tmp, character_at_sel, j as libc::c_ulong);
+                        unit as usize as u16
+                    }).collect::<Vec<u16>>();
+                    res.push(OsStringExt::from_vec(Wtf8Buf::from_wide(&units).into_bytes()));
+                } else {
+                    let bytes = CStr::from_ptr(utf_c_str).to_bytes();
+                    res.push(OsStringExt::from_vec(bytes.to_vec()));
+                }
             }
         }
 
-        Args { iter: res.into_iter(), _dont_send_or_sync_me: PhantomData }
+        Args::owned(res)
     }
 }
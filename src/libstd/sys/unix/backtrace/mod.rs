@@ -75,11 +75,14 @@
 
 pub use self::tracing::unwind_backtrace;
 pub use self::printing::{foreach_symbol_fileline, resolve_symname};
+pub use self::modules::modules;
 
 // tracing impls:
 mod tracing;
 // symbol resolvers:
 mod printing;
+// loaded module listing:
+mod modules;
 
 #[cfg(not(target_os = "emscripten"))]
 pub mod gnu {
@@ -87,12 +90,15 @@ pub mod gnu {
     use fs;
     use libc::c_char;
 
-    #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+    #[cfg(not(any(target_os = "macos",
+                  target_os = "ios",
+                  target_os = "tvos",
+                  target_os = "watchos")))]
     pub fn get_executable_filename() -> io::Result<(Vec<c_char>, fs::File)> {
         Err(io::Error::new(io::ErrorKind::Other, "Not implemented"))
     }
 
-    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    #[cfg(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))]
     pub fn get_executable_filename() -> io::Result<(Vec<c_char>, fs::File)> {
         use env;
         use os::unix::ffi::OsStrExt;
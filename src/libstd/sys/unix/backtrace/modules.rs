@@ -0,0 +1,250 @@
+//! Listing of the executable and shared libraries loaded into the process.
+//!
+//! ELF platforms report every loaded object through `dl_iterate_phdr`, along
+//! with its program headers, from which the extent of its `PT_LOAD` segments
+//! gives the mapped range. On macOS dyld keeps a list of images, and the
+//! extent of the segments in each image's load commands gives the range.
+
+pub use self::imp::modules;
+
+#[cfg(any(target_os = "linux",
+          target_os = "android",
+          target_os = "freebsd",
+          target_os = "dragonfly",
+          target_os = "netbsd",
+          target_os = "openbsd",
+          target_os = "solaris",
+          target_os = "fuchsia"))]
+mod imp {
+    use cmp;
+    use env;
+    use ffi::{CStr, OsStr};
+    use io;
+    use libc::{c_char, c_int, c_void, size_t};
+    use os::unix::ffi::OsStrExt;
+    use path::PathBuf;
+    use slice;
+    use sys_common::backtrace::Module;
+
+    const PT_LOAD: u32 = 1;
+
+    #[cfg(target_pointer_width = "64")]
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct Phdr {
+        p_type: u32,
+        p_flags: u32,
+        p_offset: u64,
+        p_vaddr: u64,
+        p_paddr: u64,
+        p_filesz: u64,
+        p_memsz: u64,
+        p_align: u64,
+    }
+
+    #[cfg(target_pointer_width = "32")]
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct Phdr {
+        p_type: u32,
+        p_offset: u32,
+        p_vaddr: u32,
+        p_paddr: u32,
+        p_filesz: u32,
+        p_memsz: u32,
+        p_flags: u32,
+        p_align: u32,
+    }
+
+    // Only the leading fields, which every implementation shares; later ones
+    // were added over time.
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct DlPhdrInfo {
+        dlpi_addr: usize,
+        dlpi_name: *const c_char,
+        dlpi_phdr: *const Phdr,
+        dlpi_phnum: u16,
+    }
+
+    extern {
+        fn dl_iterate_phdr(callback: extern fn(*mut DlPhdrInfo, size_t, *mut c_void) -> c_int,
+                           data: *mut c_void) -> c_int;
+    }
+
+    extern fn callback(info: *mut DlPhdrInfo, _size: size_t, data: *mut c_void) -> c_int {
+        unsafe {
+            let modules = &mut *(data as *mut Vec<Module>);
+            let info = &*info;
+            let phdrs = slice::from_raw_parts(info.dlpi_phdr, info.dlpi_phnum as usize);
+
+            let mut start = usize::max_value();
+            let mut end = 0;
+            for phdr in phdrs.iter().filter(|phdr| phdr.p_type == PT_LOAD) {
+                start = cmp::min(start, phdr.p_vaddr as usize);
+                end = cmp::max(end, phdr.p_vaddr as usize + phdr.p_memsz as usize);
+            }
+            if start >= end {
+                return 0;
+            }
+
+            // The executable itself is reported without a name.
+            let name = if info.dlpi_name.is_null() {
+                &[][..]
+            } else {
+                CStr::from_ptr(info.dlpi_name).to_bytes()
+            };
+            let path = if name.is_empty() {
+                match env::current_exe() {
+                    Ok(path) => path,
+                    Err(_) => PathBuf::new(),
+                }
+            } else {
+                PathBuf::from(OsStr::from_bytes(name))
+            };
+
+            modules.push(Module {
+                path,
+                base: info.dlpi_addr.wrapping_add(start),
+                size: end - start,
+            });
+            0
+        }
+    }
+
+    pub fn modules() -> io::Result<Vec<Module>> {
+        let mut modules = Vec::new();
+        unsafe {
+            dl_iterate_phdr(callback, &mut modules as *mut Vec<Module> as *mut c_void);
+        }
+        Ok(modules)
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+mod imp {
+    use cmp;
+    use ffi::{CStr, OsStr};
+    use io;
+    use libc::c_char;
+    use os::unix::ffi::OsStrExt;
+    use path::PathBuf;
+    use sys_common::backtrace::Module;
+
+    // Only the leading fields of `mach_header`, which `mach_header_64`
+    // extends.
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct MachHeader {
+        magic: u32,
+        cputype: i32,
+        cpusubtype: i32,
+        filetype: u32,
+        ncmds: u32,
+        sizeofcmds: u32,
+        flags: u32,
+    }
+
+    #[repr(C)]
+    struct LoadCommand {
+        cmd: u32,
+        cmdsize: u32,
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    const LC_SEGMENT: u32 = 0x19;
+    #[cfg(target_pointer_width = "64")]
+    const HEADER_SIZE: usize = 32;
+    #[cfg(target_pointer_width = "64")]
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct SegmentCommand {
+        cmd: u32,
+        cmdsize: u32,
+        segname: [u8; 16],
+        vmaddr: u64,
+        vmsize: u64,
+    }
+
+    #[cfg(target_pointer_width = "32")]
+    const LC_SEGMENT: u32 = 0x1;
+    #[cfg(target_pointer_width = "32")]
+    const HEADER_SIZE: usize = 28;
+    #[cfg(target_pointer_width = "32")]
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct SegmentCommand {
+        cmd: u32,
+        cmdsize: u32,
+        segname: [u8; 16],
+        vmaddr: u32,
+        vmsize: u32,
+    }
+
+    extern {
+        fn _dyld_image_count() -> u32;
+        fn _dyld_get_image_header(image_index: u32) -> *const MachHeader;
+        fn _dyld_get_image_vmaddr_slide(image_index: u32) -> isize;
+        fn _dyld_get_image_name(image_index: u32) -> *const c_char;
+    }
+
+    pub fn modules() -> io::Result<Vec<Module>> {
+        let mut modules = Vec::new();
+        unsafe {
+            for i in 0.._dyld_image_count() {
+                let header = _dyld_get_image_header(i);
+                let name = _dyld_get_image_name(i);
+                if header.is_null() || name.is_null() {
+                    continue;
+                }
+                let slide = _dyld_get_image_vmaddr_slide(i) as usize;
+
+                let mut start = usize::max_value();
+                let mut end = 0;
+                let mut cmd = (header as *const u8).add(HEADER_SIZE);
+                for _ in 0..(*header).ncmds {
+                    let command = &*(cmd as *const LoadCommand);
+                    if command.cmd == LC_SEGMENT {
+                        let segment = &*(cmd as *const SegmentCommand);
+                        // __PAGEZERO reserves the bottom of the address space
+                        // without being part of the image.
+                        if &segment.segname[..11] != b"__PAGEZERO\0" {
+                            start = cmp::min(start, segment.vmaddr as usize);
+                            end = cmp::max(end, (segment.vmaddr + segment.vmsize) as usize);
+                        }
+                    }
+                    cmd = cmd.add(command.cmdsize as usize);
+                }
+                if start >= end {
+                    continue;
+                }
+
+                modules.push(Module {
+                    path: PathBuf::from(OsStr::from_bytes(CStr::from_ptr(name).to_bytes())),
+                    base: start.wrapping_add(slide),
+                    size: end - start,
+                });
+            }
+        }
+        Ok(modules)
+    }
+}
+
+#[cfg(not(any(target_os = "linux",
+              target_os = "android",
+              target_os = "freebsd",
+              target_os = "dragonfly",
+              target_os = "netbsd",
+              target_os = "openbsd",
+              target_os = "solaris",
+              target_os = "fuchsia",
+              target_os = "macos",
+              target_os = "ios")))]
+mod imp {
+    use io;
+    use sys_common::backtrace::Module;
+
+    pub fn modules() -> io::Result<Vec<Module>> {
+        Err(io::Error::new(io::ErrorKind::Other, "Not implemented"))
+    }
+}
@@ -30,6 +30,8 @@ impl Condvar {
 
     #[cfg(any(target_os = "macos",
               target_os = "ios",
+              target_os = "tvos",
+              target_os = "watchos",
               target_os = "l4re",
               target_os = "android",
               target_os = "hermit"))]
@@ -37,6 +39,8 @@ impl Condvar {
 
     #[cfg(not(any(target_os = "macos",
                   target_os = "ios",
+                  target_os = "tvos",
+                  target_os = "watchos",
                   target_os = "l4re",
                   target_os = "android",
                   target_os = "hermit")))]
@@ -77,6 +81,8 @@ impl Condvar {
     // from changes made to the system time.
     #[cfg(not(any(target_os = "macos",
                   target_os = "ios",
+                  target_os = "tvos",
+                  target_os = "watchos",
                   target_os = "android",
                   target_os = "hermit")))]
     pub unsafe fn wait_timeout(&self, mutex: &Mutex, dur: Duration) -> bool {
@@ -108,7 +114,12 @@ impl Condvar {
     // This implementation is modeled after libcxx's condition_variable
     // https://github.com/llvm-mirror/libcxx/blob/release_35/src/condition_variable.cpp#L46
     // https://github.com/llvm-mirror/libcxx/blob/release_35/include/__mutex_base#L367
-    #[cfg(any(target_os = "macos", target_os = "ios", target_os = "android", target_os = "hermit"))]
+    #[cfg(any(target_os = "macos",
+              target_os = "ios",
+              target_os = "tvos",
+              target_os = "watchos",
+              target_os = "android",
+              target_os = "hermit"))]
     pub unsafe fn wait_timeout(&self, mutex: &Mutex, mut dur: Duration) -> bool {
         use ptr;
         use time::Instant;
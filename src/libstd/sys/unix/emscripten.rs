@@ -0,0 +1,92 @@
+//! Emscripten JavaScript-glue module
+//!
+//! A program compiled with Emscripten doesn't get its command line or
+//! environment from a kernel but from the JavaScript `Module` object the page
+//! (or node) sets up around it. Most of the time that's hidden behind the
+//! usual `argv` and `environ`, but not always: `main` gets an empty `argv`
+//! when the runtime is started without one, and edits to the `ENV` object
+//! made after startup never make it into `environ`. The helpers here read
+//! those objects directly.
+//!
+//! They go through `emscripten_run_script_*`, which evaluates a snippet of
+//! JavaScript inside the module. Returned strings come back UTF-8 encoded in
+//! a buffer that the next call overwrites, so they're copied out right away.
+
+#![cfg(target_os = "emscripten")]
+
+use ffi::{CStr, CString, OsStr, OsString};
+use libc::{c_char, c_int};
+use os::unix::prelude::*;
+use sys::os;
+
+extern {
+    fn emscripten_run_script_int(script: *const c_char) -> c_int;
+    fn emscripten_run_script_string(script: *const c_char) -> *const c_char;
+    fn emscripten_is_main_browser_thread() -> c_int;
+    fn emscripten_thread_sleep(msecs: f64);
+}
+
+fn run_int(script: &str) -> c_int {
+    let script = CString::new(script).unwrap();
+    unsafe { emscripten_run_script_int(script.as_ptr()) }
+}
+
+fn run_string(script: &str) -> Vec<u8> {
+    let script = CString::new(script).unwrap();
+    unsafe {
+        let ret = emscripten_run_script_string(script.as_ptr());
+        if ret.is_null() {
+            Vec::new()
+        } else {
+            CStr::from_ptr(ret).to_bytes().to_vec()
+        }
+    }
+}
+
+/// Returns the program name followed by `Module['arguments']`, the way
+/// Emscripten's own startup code would have passed them to `main`.
+///
+/// Arguments containing a nul byte are truncated at it.
+pub fn module_arguments() -> Vec<OsString> {
+    const ARGS: &str = "(typeof Module === 'object' && Module['arguments'] || [])";
+
+    let mut ret = vec![OsString::from("./this.program")];
+    let len = run_int(&format!("{}.length", ARGS));
+    for i in 0..len {
+        let arg = run_string(&format!("String({}[{}])", ARGS, i));
+        ret.push(OsStringExt::from_vec(arg));
+    }
+    ret
+}
+
+/// Copies every variable of the JavaScript `ENV` object into `environ`,
+/// overwriting what was there, so that changes made to it before `main`
+/// runs (in a `preRun` hook, say) are visible to `env::var`.
+///
+/// Keys which are empty or contain `=` are skipped.
+pub fn import_env() {
+    const KEYS: &str = "(typeof ENV === 'object' && ENV ? Object.keys(ENV) : [])";
+
+    let len = run_int(&format!("{}.length", KEYS));
+    for i in 0..len {
+        let key = run_string(&format!("{}[{}]", KEYS, i));
+        let value = run_string(&format!("String(ENV[{}[{}]])", KEYS, i));
+        if key.is_empty() || key.contains(&b'=') {
+            continue
+        }
+        let _ = os::setenv(OsStr::from_bytes(&key), OsStr::from_bytes(&value));
+    }
+}
+
+/// Whether blocking here would block the page.
+///
+/// Only the main browser thread runs the event loop which feeds input to
+/// the TTY; syscalls on every other thread are proxied to it and may wait.
+pub fn on_main_browser_thread() -> bool {
+    unsafe { emscripten_is_main_browser_thread() != 0 }
+}
+
+/// Sleeps a worker thread while the main thread gets on with its work.
+pub fn sleep_ms(ms: f64) {
+    unsafe { emscripten_thread_sleep(ms) }
+}
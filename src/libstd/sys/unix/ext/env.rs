@@ -0,0 +1,147 @@
+//! Unix-specific extensions to primitives in the `std::env` module.
+
+#![unstable(feature = "unix_raw_args", issue = "0")]
+
+use fmt;
+use path::PathBuf;
+use sys;
+
+/// Returns the `argc` and `argv` the process was started with, for C
+/// libraries which want to parse or rewrite the original arguments.
+///
+/// `argv` points to `argc` pointers to nul-terminated strings, followed by
+/// a null pointer. These are the runtime's own copy of the pointers handed to
+/// `main`, not a copy of the strings; anything written through them is seen
+/// by the C code sharing them, but not by [`env::args`], which may already
+/// have copied the strings.
+///
+/// Returns `(0, null)` when the arguments aren't known. That's the case
+/// before the runtime starts and after it shuts down, when Rust code isn't
+/// started through the standard `main`, and on iOS, tvOS and watchOS, where
+/// the arguments only come from `NSProcessInfo`.
+///
+/// [`env::args`]: ../../../env/fn.args.html
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(unix_raw_args)]
+/// use std::os::unix::env;
+///
+/// let (argc, argv) = env::raw_args();
+/// println!("{} arguments at {:p}", argc, argv);
+/// ```
+pub fn raw_args() -> (isize, *const *const u8) {
+    sys::args::raw()
+}
+
+/// Returns the current user's home directory.
+///
+/// Unlike the deprecated [`env::home_dir`], an empty `HOME` counts as unset.
+/// Without `HOME`, the home directory recorded for the user in the password
+/// database is looked up with `getpwuid_r`, on platforms which have one.
+///
+/// [`env::home_dir`]: ../../../env/fn.home_dir.html
+///
+/// # Examples
+///
+/// ```
+/// #![feature(user_home_dir)]
+/// use std::os::unix::env;
+///
+/// match env::user_home_dir() {
+///     Some(path) => println!("Your home directory, probably: {}", path.display()),
+///     None => println!("Impossible to get your home dir!"),
+/// }
+/// ```
+#[unstable(feature = "user_home_dir", issue = "0")]
+pub fn user_home_dir() -> Option<PathBuf> {
+    sys::os::user_home_dir()
+}
+
+/// A guard holding the lock which the standard library takes around every
+/// access to the environment.
+///
+/// This structure is created by the [`try_lock_environment`] function. See
+/// its documentation for more.
+///
+/// [`try_lock_environment`]: fn.try_lock_environment.html
+#[unstable(feature = "env_lock", issue = "0")]
+#[must_use]
+pub struct EnvironmentLock {
+    _inner: sys::os::EnvLockHandle,
+}
+
+/// Takes the lock the standard library holds while it reads or changes the
+/// environment, if no other thread holds it.
+///
+/// C functions such as `getpwnam` or `localtime` read `environ` without
+/// taking any lock, so calling them while another thread calls
+/// [`env::set_var`] is a data race. While the returned guard is alive, other
+/// threads block in [`env::set_var`], [`env::var`] and the rest of
+/// [`std::env`], as well as when spawning processes, so such functions can be
+/// called safely. The thread holding the guard can still use [`std::env`]
+/// itself.
+///
+/// Returns `None` if another thread, or this one through an earlier guard,
+/// already holds the lock. The guard should be dropped quickly, as every
+/// other thread touching the environment waits for it.
+///
+/// [`env::set_var`]: ../../../env/fn.set_var.html
+/// [`env::var`]: ../../../env/fn.var.html
+/// [`std::env`]: ../../../env/index.html
+///
+/// # Examples
+///
+/// ```
+/// #![feature(env_lock)]
+/// use std::os::unix::env;
+///
+/// if let Some(_lock) = env::try_lock_environment() {
+///     // call C functions which read `environ` here
+/// }
+/// ```
+#[unstable(feature = "env_lock", issue = "0")]
+pub fn try_lock_environment() -> Option<EnvironmentLock> {
+    sys::os::try_env_lock().map(|inner| EnvironmentLock { _inner: inner })
+}
+
+#[unstable(feature = "env_lock", issue = "0")]
+impl fmt::Debug for EnvironmentLock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad("EnvironmentLock { .. }")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn raw_args_consistent() {
+        let (argc, argv) = super::raw_args();
+        assert!(argc >= 0);
+        assert_eq!(argc == 0, argv.is_null());
+    }
+
+    #[test]
+    fn user_home_dir_prefers_home() {
+        match ::env::var_os("HOME") {
+            Some(ref home) if !home.is_empty() => {
+                assert_eq!(super::user_home_dir().as_ref().map(|p| p.as_os_str()),
+                           Some(&home[..]));
+            }
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn environment_lock_allows_own_env_access() {
+        let lock = super::try_lock_environment();
+        if lock.is_some() {
+            assert!(super::try_lock_environment().is_none());
+            ::env::set_var("TEST_ENV_LOCK_HELD", "1");
+            assert_eq!(::env::var("TEST_ENV_LOCK_HELD").as_ref().map(|s| &s[..]), Ok("1"));
+            ::env::remove_var("TEST_ENV_LOCK_HELD");
+        }
+        drop(lock);
+    }
+}
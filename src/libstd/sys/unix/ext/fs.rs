@@ -2,12 +2,15 @@
 
 #![stable(feature = "rust1", since = "1.0.0")]
 
+use ffi::{OsStr, OsString};
 use fs::{self, Permissions, OpenOptions};
 use io;
 use libc;
+use ops::{Bound, RangeBounds};
 use path::Path;
 use sys;
 use sys_common::{FromInner, AsInner, AsInnerMut};
+use sys::fs::XattrTarget;
 use sys::platform::fs::MetadataExt as UnixMetadataExt;
 
 /// Unix-specific extensions to [`File`].
@@ -208,6 +211,257 @@ pub trait FileExt {
         }
         Ok(())
     }
+
+    /// Returns the value of the file's extended attribute `name`, or `None`
+    /// if it has no such attribute.
+    ///
+    /// Extended attributes are pieces of data which the filesystem keeps
+    /// with a file, such as SELinux labels and file capabilities on Linux,
+    /// or the quarantine flag on macOS. On Linux, names have a namespace
+    /// prefix, such as `user.` for those which any program may use.
+    ///
+    /// As this works on the open file, it can't be raced by the file being
+    /// renamed or replaced, unlike [`get_xattr`] on a path.
+    ///
+    /// This is currently supported on Linux, Android, macOS and iOS, and
+    /// fails on other platforms. It also fails on filesystems which don't
+    /// support extended attributes.
+    ///
+    /// [`get_xattr`]: fn.get_xattr.html
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(unix_xattr)]
+    /// use std::fs::File;
+    /// use std::io;
+    /// use std::os::unix::fs::FileExt;
+    ///
+    /// fn main() -> io::Result<()> {
+    ///     let file = File::open("foo.txt")?;
+    ///     if let Some(value) = file.get_xattr("user.origin")? {
+    ///         println!("from {}", String::from_utf8_lossy(&value));
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "unix_xattr", issue = "0")]
+    fn get_xattr<N: AsRef<OsStr>>(&self, name: N) -> io::Result<Option<Vec<u8>>>
+        where Self: Sized
+    {
+        let _ = name;
+        Err(xattr_unimplemented())
+    }
+
+    /// Sets the file's extended attribute `name` to `value`, creating it if
+    /// it doesn't exist.
+    ///
+    /// See [`get_xattr`] for more about extended attributes.
+    ///
+    /// [`get_xattr`]: #method.get_xattr
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(unix_xattr)]
+    /// use std::fs::File;
+    /// use std::io;
+    /// use std::os::unix::fs::FileExt;
+    ///
+    /// fn main() -> io::Result<()> {
+    ///     let file = File::create("foo.txt")?;
+    ///     file.set_xattr("user.origin", b"https://example.com/")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "unix_xattr", issue = "0")]
+    fn set_xattr<N: AsRef<OsStr>>(&self, name: N, value: &[u8]) -> io::Result<()>
+        where Self: Sized
+    {
+        let _ = (name, value);
+        Err(xattr_unimplemented())
+    }
+
+    /// Returns the names of the file's extended attributes.
+    ///
+    /// On Linux, only the attributes which the process may read are listed.
+    /// See [`get_xattr`] for more about extended attributes.
+    ///
+    /// [`get_xattr`]: #method.get_xattr
+    #[unstable(feature = "unix_xattr", issue = "0")]
+    fn list_xattr(&self) -> io::Result<Vec<OsString>> {
+        Err(xattr_unimplemented())
+    }
+
+    /// Removes the file's extended attribute `name`.
+    ///
+    /// This fails if there's no such attribute. See [`get_xattr`] for more
+    /// about extended attributes.
+    ///
+    /// [`get_xattr`]: #method.get_xattr
+    #[unstable(feature = "unix_xattr", issue = "0")]
+    fn remove_xattr<N: AsRef<OsStr>>(&self, name: N) -> io::Result<()>
+        where Self: Sized
+    {
+        let _ = name;
+        Err(xattr_unimplemented())
+    }
+
+    /// Tells the kernel how the given range of the file is going to be
+    /// accessed, so that it can cache it accordingly.
+    ///
+    /// A streaming reader can ask for aggressive readahead with
+    /// [`Advice::Sequential`], and a backup tool which reads everything once
+    /// can drop what it's read from the page cache with
+    /// [`Advice::DontNeed`], rather than pushing out everything else.
+    ///
+    /// The range is of byte offsets in the file, and one which is unbounded
+    /// at the end covers the rest of the file, however large it grows.
+    /// This is only a hint, and the kernel is free to ignore it.
+    ///
+    /// This function currently corresponds to `posix_fadvise`, and is
+    /// supported on Linux, Android and FreeBSD. It fails on other platforms.
+    ///
+    /// [`Advice::Sequential`]: enum.Advice.html#variant.Sequential
+    /// [`Advice::DontNeed`]: enum.Advice.html#variant.DontNeed
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(unix_file_advise)]
+    /// use std::fs::File;
+    /// use std::io::{self, Read};
+    /// use std::os::unix::fs::{Advice, FileExt};
+    ///
+    /// fn main() -> io::Result<()> {
+    ///     let mut file = File::open("foo.iso")?;
+    ///     file.advise(.., Advice::Sequential)?;
+    ///     let mut buf = vec![0; 1 << 20];
+    ///     let mut offset = 0;
+    ///     loop {
+    ///         let n = file.read(&mut buf)?;
+    ///         if n == 0 {
+    ///             break
+    ///         }
+    ///         // ...
+    ///         file.advise(offset..offset + n as u64, Advice::DontNeed)?;
+    ///         offset += n as u64;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "unix_file_advise", issue = "0")]
+    fn advise<R: RangeBounds<u64>>(&self, range: R, advice: Advice) -> io::Result<()>
+        where Self: Sized
+    {
+        let _ = (range, advice);
+        Err(io::Error::new(io::ErrorKind::Other, "advice is not supported by this type"))
+    }
+
+    /// Moves the file's cursor to the start of the first data at or after
+    /// the given offset, and returns its offset.
+    ///
+    /// A sparse file has holes, ranges which were never written and which
+    /// read as zeros without taking up any space. A backup or copy tool can
+    /// alternate between this and [`seek_hole`] to read only the data.
+    ///
+    /// Returns `None`, leaving the cursor where it was, if there's no data
+    /// at or after `offset`, because it's in a hole which runs to the end of
+    /// the file, or at or past the end. Filesystems which don't keep track
+    /// of holes report the whole file as data.
+    ///
+    /// This function currently corresponds to `lseek` with `SEEK_DATA`, and
+    /// is supported on Linux, Android, FreeBSD, Solaris and macOS. It fails
+    /// on other platforms.
+    ///
+    /// [`seek_hole`]: #method.seek_hole
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(unix_file_seek_hole)]
+    /// use std::fs::File;
+    /// use std::io::{self, Read};
+    /// use std::os::unix::fs::FileExt;
+    ///
+    /// fn main() -> io::Result<()> {
+    ///     let file = File::open("disk.img")?;
+    ///     let mut pos = 0;
+    ///     while let Some(data) = file.seek_data(pos)? {
+    ///         let hole = file.seek_hole(data)?.expect("data runs past the end");
+    ///         let mut buf = Vec::new();
+    ///         (&file).take(hole - data).read_to_end(&mut buf)?;
+    ///         // ...
+    ///         pos = hole;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "unix_file_seek_hole", issue = "0")]
+    fn seek_data(&self, offset: u64) -> io::Result<Option<u64>> {
+        let _ = offset;
+        Err(seek_hole_unimplemented())
+    }
+
+    /// Moves the file's cursor to the start of the first hole at or after
+    /// the given offset, and returns its offset.
+    ///
+    /// There's always a hole at the end of the file, so this returns the
+    /// length of the file if there's no hole after `offset` before that.
+    /// Returns `None`, leaving the cursor where it was, if `offset` is at or
+    /// past the end. See [`seek_data`] for more.
+    ///
+    /// This function currently corresponds to `lseek` with `SEEK_HOLE`.
+    ///
+    /// [`seek_data`]: #method.seek_data
+    #[unstable(feature = "unix_file_seek_hole", issue = "0")]
+    fn seek_hole(&self, offset: u64) -> io::Result<Option<u64>> {
+        let _ = offset;
+        Err(seek_hole_unimplemented())
+    }
+}
+
+fn seek_hole_unimplemented() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "finding holes is not supported by this type")
+}
+
+// The extended attribute methods are provided so that adding them didn't
+// break other implementations of `FileExt`, which they don't apply to, and
+// the generic ones need `Self: Sized` to keep `dyn FileExt` usable.
+fn xattr_unimplemented() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "extended attributes are not supported by this type")
+}
+
+/// How a range of a file is going to be accessed, for
+/// [`FileExt::advise`].
+///
+/// [`FileExt::advise`]: trait.FileExt.html#method.advise
+#[unstable(feature = "unix_file_advise", issue = "0")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Advice {
+    /// There's nothing special about the access pattern. This undoes any
+    /// other advice.
+    #[unstable(feature = "unix_file_advise", issue = "0")]
+    Normal,
+    /// The range will be read from start to end, so it's worth reading
+    /// further ahead.
+    #[unstable(feature = "unix_file_advise", issue = "0")]
+    Sequential,
+    /// The range will be accessed in no particular order, so reading ahead
+    /// is wasted.
+    #[unstable(feature = "unix_file_advise", issue = "0")]
+    Random,
+    /// The range will be accessed only once.
+    #[unstable(feature = "unix_file_advise", issue = "0")]
+    NoReuse,
+    /// The range will be needed soon, so it's worth starting to read it
+    /// into the page cache now.
+    #[unstable(feature = "unix_file_advise", issue = "0")]
+    WillNeed,
+    /// The range won't be needed again soon, so clean pages of it can be
+    /// dropped from the page cache.
+    #[unstable(feature = "unix_file_advise", issue = "0")]
+    DontNeed,
 }
 
 #[stable(feature = "file_offset", since = "1.15.0")]
@@ -218,6 +472,41 @@ impl FileExt for fs::File {
     fn write_at(&self, buf: &[u8], offset: u64) -> io::Result<usize> {
         self.as_inner().write_at(buf, offset)
     }
+    fn get_xattr<N: AsRef<OsStr>>(&self, name: N) -> io::Result<Option<Vec<u8>>> {
+        sys::fs::get_xattr(XattrTarget::File(self.as_inner()), name.as_ref())
+    }
+    fn set_xattr<N: AsRef<OsStr>>(&self, name: N, value: &[u8]) -> io::Result<()> {
+        sys::fs::set_xattr(XattrTarget::File(self.as_inner()), name.as_ref(), value)
+    }
+    fn list_xattr(&self) -> io::Result<Vec<OsString>> {
+        sys::fs::list_xattr(XattrTarget::File(self.as_inner()))
+    }
+    fn remove_xattr<N: AsRef<OsStr>>(&self, name: N) -> io::Result<()> {
+        sys::fs::remove_xattr(XattrTarget::File(self.as_inner()), name.as_ref())
+    }
+    fn advise<R: RangeBounds<u64>>(&self, range: R, advice: Advice) -> io::Result<()> {
+        let start = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        // A length of 0 means "to the end of the file".
+        let len = match range.end_bound() {
+            Bound::Included(&n) => n.saturating_add(1).saturating_sub(start),
+            Bound::Excluded(&n) => n.saturating_sub(start),
+            Bound::Unbounded => return self.as_inner().advise(start, 0, advice),
+        };
+        if len == 0 {
+            return Ok(())
+        }
+        self.as_inner().advise(start, len, advice)
+    }
+    fn seek_data(&self, offset: u64) -> io::Result<Option<u64>> {
+        self.as_inner().seek_data(offset)
+    }
+    fn seek_hole(&self, offset: u64) -> io::Result<Option<u64>> {
+        self.as_inner().seek_hole(offset)
+    }
 }
 
 /// Unix-specific extensions to [`fs::Permissions`].
@@ -827,6 +1116,67 @@ pub fn symlink<P: AsRef<Path>, Q: AsRef<Path>>(src: P, dst: Q) -> io::Result<()>
     sys::fs::symlink(src.as_ref(), dst.as_ref())
 }
 
+/// Returns the value of the extended attribute `name` of the file at
+/// `path`, or `None` if it has no such attribute.
+///
+/// Symbolic links are followed. To avoid races with the file being renamed
+/// or replaced, open it and use [`FileExt::get_xattr`] instead.
+///
+/// [`FileExt::get_xattr`]: trait.FileExt.html#method.get_xattr
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(unix_xattr)]
+/// use std::os::unix::fs;
+///
+/// fn main() -> std::io::Result<()> {
+///     let label = fs::get_xattr("/etc/passwd", "security.selinux")?;
+///     println!("{:?}", label);
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "unix_xattr", issue = "0")]
+pub fn get_xattr<P: AsRef<Path>, N: AsRef<OsStr>>(path: P, name: N)
+                                                  -> io::Result<Option<Vec<u8>>> {
+    sys::fs::get_xattr(XattrTarget::Path(path.as_ref()), name.as_ref())
+}
+
+/// Sets the extended attribute `name` of the file at `path` to `value`,
+/// creating it if it doesn't exist.
+///
+/// Symbolic links are followed. See [`FileExt::set_xattr`] for the same on
+/// an open file.
+///
+/// [`FileExt::set_xattr`]: trait.FileExt.html#method.set_xattr
+#[unstable(feature = "unix_xattr", issue = "0")]
+pub fn set_xattr<P: AsRef<Path>, N: AsRef<OsStr>>(path: P, name: N, value: &[u8])
+                                                  -> io::Result<()> {
+    sys::fs::set_xattr(XattrTarget::Path(path.as_ref()), name.as_ref(), value)
+}
+
+/// Returns the names of the extended attributes of the file at `path`.
+///
+/// Symbolic links are followed. See [`FileExt::list_xattr`] for the same
+/// on an open file.
+///
+/// [`FileExt::list_xattr`]: trait.FileExt.html#method.list_xattr
+#[unstable(feature = "unix_xattr", issue = "0")]
+pub fn list_xattr<P: AsRef<Path>>(path: P) -> io::Result<Vec<OsString>> {
+    sys::fs::list_xattr(XattrTarget::Path(path.as_ref()))
+}
+
+/// Removes the extended attribute `name` of the file at `path`.
+///
+/// Symbolic links are followed. See [`FileExt::remove_xattr`] for the same
+/// on an open file.
+///
+/// [`FileExt::remove_xattr`]: trait.FileExt.html#method.remove_xattr
+#[unstable(feature = "unix_xattr", issue = "0")]
+pub fn remove_xattr<P: AsRef<Path>, N: AsRef<OsStr>>(path: P, name: N) -> io::Result<()> {
+    sys::fs::remove_xattr(XattrTarget::Path(path.as_ref()), name.as_ref())
+}
+
 /// Unix-specific extensions to [`fs::DirBuilder`].
 ///
 /// [`fs::DirBuilder`]: ../../../../std/fs/struct.DirBuilder.html
@@ -28,11 +28,14 @@
 #![allow(missing_docs)]
 
 pub mod io;
+pub mod env;
 pub mod ffi;
 pub mod fs;
 pub mod process;
 pub mod raw;
+pub mod stack_overflow;
 pub mod thread;
+#[cfg(not(target_os = "espidf"))]
 pub mod net;
 
 /// A prelude for conveniently writing platform-specific code.
@@ -2,9 +2,12 @@
 
 #![stable(feature = "rust1", since = "1.0.0")]
 
+use ffi::OsStr;
 use io;
+use libc;
 use os::unix::io::{FromRawFd, RawFd, AsRawFd, IntoRawFd};
 use process;
+use sync::mpsc;
 use sys;
 use sys_common::{AsInnerMut, AsInner, FromInner, IntoInner};
 
@@ -24,6 +27,29 @@ pub trait CommandExt {
     #[stable(feature = "rust1", since = "1.0.0")]
     fn gid(&mut self, id: u32) -> &mut process::Command;
 
+    /// Sets the supplementary group IDs of the child process, as `setgroups`
+    /// would. Failure in the `setgroups` call will cause the spawn to fail.
+    ///
+    /// Without this, a child given a [`uid`] is left with no supplementary
+    /// groups if we're privileged enough to drop them, and keeps ours
+    /// otherwise. In the child the groups are set first, then the group ID,
+    /// then the user ID, since each step needs privileges the next one may
+    /// give up.
+    ///
+    /// [`uid`]: #tymethod.uid
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(setgroups)]
+    /// use std::os::unix::process::CommandExt;
+    /// use std::process::Command;
+    ///
+    /// Command::new("id").uid(1000).gid(1000).groups(&[1000, 27]).status().unwrap();
+    /// ```
+    #[unstable(feature = "setgroups", issue = "0")]
+    fn groups(&mut self, groups: &[u32]) -> &mut process::Command;
+
     /// Schedules a closure to be run just before the `exec` function is
     /// invoked.
     ///
@@ -83,6 +109,120 @@ pub trait CommandExt {
     /// cross-platform `spawn` instead.
     #[stable(feature = "process_exec2", since = "1.9.0")]
     fn exec(&mut self) -> io::Error;
+
+    /// Gives the child copies of some of our file descriptors at chosen
+    /// numbers, replacing any mappings set before.
+    ///
+    /// Each `(ours, theirs)` pair makes descriptor `theirs` in the child a
+    /// copy of descriptor `ours` in this process, as `dup2` would, without
+    /// the close-on-exec flag. The mappings are all made at once, so a
+    /// descriptor can be moved to a number which is itself the source of
+    /// another mapping, and two descriptors can even be swapped. A mapping
+    /// to 0, 1 or 2 takes precedence over the [`Stdio`] configuration.
+    ///
+    /// Spawning fails with [`InvalidInput`] if a descriptor is negative or
+    /// if the same descriptor in the child is given more than once, and
+    /// with the error from `dup2` if one of ours isn't open.
+    ///
+    /// [`Stdio`]: ../../../../std/process/struct.Stdio.html
+    /// [`InvalidInput`]: ../../../../std/io/enum.ErrorKind.html#variant.InvalidInput
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(process_fd_mappings)]
+    /// use std::fs::File;
+    /// use std::os::unix::io::AsRawFd;
+    /// use std::os::unix::process::CommandExt;
+    /// use std::process::Command;
+    ///
+    /// let log = File::create("child.log").unwrap();
+    /// Command::new("sh").arg("-c").arg("echo hello >&3")
+    ///     .fd_mappings(&[(log.as_raw_fd(), 3)])
+    ///     .status().unwrap();
+    /// ```
+    #[unstable(feature = "process_fd_mappings", issue = "0")]
+    fn fd_mappings(&mut self, mappings: &[(RawFd, RawFd)]) -> &mut process::Command;
+
+    /// Sets the first process argument, `argv[0]`, to something other than
+    /// the program being run.
+    ///
+    /// By default `argv[0]` is the program as passed to [`Command::new`].
+    /// Shells and multi-call binaries behave differently depending on it,
+    /// and it's what the child shows up as in `ps`. The program is still
+    /// looked up and executed as given to `Command::new`.
+    ///
+    /// [`Command::new`]: ../../../../std/process/struct.Command.html#method.new
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(process_set_argv0)]
+    /// use std::os::unix::process::CommandExt;
+    /// use std::process::Command;
+    ///
+    /// // Run busybox as `ls`.
+    /// Command::new("/bin/busybox").arg0("ls").arg("/").status().unwrap();
+    /// ```
+    #[unstable(feature = "process_set_argv0", issue = "0")]
+    fn arg0<S>(&mut self, arg: S) -> &mut process::Command
+        where S: AsRef<OsStr>;
+
+    /// Moves the child into the process group `pgroup`, as `setpgid(0,
+    /// pgroup)` would.
+    ///
+    /// A `pgroup` of 0 puts the child in a new group of its own, whose id is
+    /// the child's pid. Otherwise the group has to exist and be in the same
+    /// session. Keyboard signals such as `SIGINT` from a terminal go to the
+    /// whole foreground process group, so this is how a shell keeps jobs
+    /// apart, and with [`Child::id`] a whole group can be signalled.
+    ///
+    /// [`Child::id`]: ../../../../std/process/struct.Child.html#method.id
+    #[unstable(feature = "process_session", issue = "0")]
+    fn process_group(&mut self, pgroup: i32) -> &mut process::Command;
+
+    /// Sets whether the child starts a new session, as `setsid` would.
+    ///
+    /// The child then leads both the session and a new process group, and
+    /// has no controlling terminal, so it isn't affected when the terminal
+    /// we're running in goes away. This is what daemons do. A session
+    /// leader can't be moved to another process group, so spawning fails if
+    /// this is combined with [`process_group`].
+    ///
+    /// [`process_group`]: #tymethod.process_group
+    #[unstable(feature = "process_session", issue = "0")]
+    fn new_session(&mut self, val: bool) -> &mut process::Command;
+
+    /// Has `signal` sent to the child when the thread which spawned it
+    /// exits, with `PR_SET_PDEATHSIG` on Linux and Android and
+    /// `PROC_PDEATHSIG_CTL` on FreeBSD.
+    ///
+    /// This is usually `SIGKILL`, so that a child can't outlive its
+    /// supervisor even when the supervisor is killed without a chance to
+    /// clean up. If we exit before the child has set this up, the child
+    /// sends itself the signal. Note that on Linux it's the exit of the
+    /// spawning *thread* which counts, not of the whole process, so this is
+    /// best done from a thread which lives as long as the child should.
+    ///
+    /// The setting is kept across `exec`, except for set-user-ID and
+    /// set-group-ID programs. Spawning fails on other platforms.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(parent_death_signal, rustc_private)]
+    /// extern crate libc;
+    ///
+    /// use std::os::unix::process::CommandExt;
+    /// use std::process::Command;
+    ///
+    /// let worker = Command::new("worker")
+    ///     .parent_death_signal(libc::SIGKILL)
+    ///     .spawn()
+    ///     .unwrap();
+    /// ```
+    #[unstable(feature = "parent_death_signal", issue = "0")]
+    fn parent_death_signal(&mut self, signal: i32) -> &mut process::Command;
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -97,6 +237,11 @@ impl CommandExt for process::Command {
         self
     }
 
+    fn groups(&mut self, groups: &[u32]) -> &mut process::Command {
+        self.as_inner_mut().groups(groups);
+        self
+    }
+
     fn before_exec<F>(&mut self, f: F) -> &mut process::Command
         where F: FnMut() -> io::Result<()> + Send + Sync + 'static
     {
@@ -107,6 +252,151 @@ impl CommandExt for process::Command {
     fn exec(&mut self) -> io::Error {
         self.as_inner_mut().exec(sys::process::Stdio::Inherit)
     }
+
+    fn fd_mappings(&mut self, mappings: &[(RawFd, RawFd)]) -> &mut process::Command {
+        self.as_inner_mut().fd_mappings(mappings);
+        self
+    }
+
+    fn arg0<S>(&mut self, arg: S) -> &mut process::Command
+        where S: AsRef<OsStr>
+    {
+        self.as_inner_mut().set_arg_0(arg.as_ref());
+        self
+    }
+
+    fn process_group(&mut self, pgroup: i32) -> &mut process::Command {
+        self.as_inner_mut().pgroup(pgroup);
+        self
+    }
+
+    fn new_session(&mut self, val: bool) -> &mut process::Command {
+        self.as_inner_mut().new_session(val);
+        self
+    }
+
+    fn parent_death_signal(&mut self, signal: i32) -> &mut process::Command {
+        self.as_inner_mut().parent_death_signal(signal);
+        self
+    }
+}
+
+/// Unix-specific extensions to [`process::Child`].
+///
+/// [`process::Child`]: ../../../../std/process/struct.Child.html
+#[unstable(feature = "child_send_signal", issue = "0")]
+pub trait ChildExt {
+    /// Sends `signal` to the child.
+    ///
+    /// Like [`Child::kill`], this fails with [`InvalidInput`] once the child
+    /// has been waited for, as its pid may have been given to another
+    /// process by then. On Linux, a child spawned with a pidfd is signalled
+    /// through it, which can't reach any other process even if the child
+    /// has already exited without us waiting for it.
+    ///
+    /// [`Child::kill`]: ../../../../std/process/struct.Child.html#method.kill
+    /// [`InvalidInput`]: ../../../../std/io/enum.ErrorKind.html#variant.InvalidInput
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(child_send_signal, rustc_private)]
+    /// extern crate libc;
+    ///
+    /// use std::os::unix::process::ChildExt;
+    /// use std::process::Command;
+    ///
+    /// let mut child = Command::new("server").spawn().unwrap();
+    /// // Ask the server to reload its configuration.
+    /// child.send_signal(libc::SIGHUP).unwrap();
+    /// ```
+    fn send_signal(&mut self, signal: i32) -> io::Result<()>;
+
+    /// Asks the child to exit by sending it `SIGTERM`.
+    ///
+    /// Unlike [`Child::kill`], this gives the child a chance to clean up,
+    /// and it may ignore the request.
+    ///
+    /// [`Child::kill`]: ../../../../std/process/struct.Child.html#method.kill
+    fn terminate(&mut self) -> io::Result<()>;
+
+    /// Waits for the child to exit, be stopped or be continued, as
+    /// `waitpid` with `WUNTRACED` and `WCONTINUED` would.
+    ///
+    /// This is for tools doing job control: use [`stopped_signal`] and
+    /// [`continued`] on the returned status to tell the events apart. Once
+    /// the child has exited this returns that status again, like
+    /// [`Child::wait`], which still only returns when the child exits.
+    ///
+    /// [`stopped_signal`]: trait.ExitStatusExt.html#tymethod.stopped_signal
+    /// [`continued`]: trait.ExitStatusExt.html#tymethod.continued
+    /// [`Child::wait`]: ../../../../std/process/struct.Child.html#method.wait
+    #[unstable(feature = "unix_process_wait_more", issue = "0")]
+    fn wait_state_change(&mut self) -> io::Result<process::ExitStatus>;
+
+    /// Gives the child to std to reap, calling `f` with its exit status
+    /// once it exits.
+    ///
+    /// The first call starts a thread which waits for the children given to
+    /// it whenever `SIGCHLD` arrives, so there's no need to install a
+    /// handler racing std's own calls to `waitpid`. Any handler already
+    /// installed is still called. The `Child` is consumed so that nothing
+    /// else can wait for it, and its `stdin`, `stdout` and `stderr` handles
+    /// are dropped; take them first if they're needed.
+    ///
+    /// `f` runs on the reaper's thread, so it shouldn't block for long. A
+    /// panic in it is caught and doesn't affect other children.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(child_on_exit)]
+    /// use std::os::unix::process::ChildExt;
+    /// use std::process::Command;
+    ///
+    /// let child = Command::new("make").spawn().unwrap();
+    /// child.on_exit(|status| println!("make finished: {:?}", status));
+    /// ```
+    #[unstable(feature = "child_on_exit", issue = "0")]
+    fn on_exit<F>(self, f: F)
+        where F: FnOnce(io::Result<process::ExitStatus>) + Send + 'static, Self: Sized;
+
+    /// Gives the child to std to reap, like [`on_exit`], returning a
+    /// channel which receives its exit status.
+    ///
+    /// [`on_exit`]: #tymethod.on_exit
+    #[unstable(feature = "child_on_exit", issue = "0")]
+    fn exit_receiver(self) -> mpsc::Receiver<io::Result<process::ExitStatus>>
+        where Self: Sized;
+}
+
+#[unstable(feature = "child_send_signal", issue = "0")]
+impl ChildExt for process::Child {
+    fn send_signal(&mut self, signal: i32) -> io::Result<()> {
+        self.as_inner_mut().send_signal(signal)
+    }
+
+    fn terminate(&mut self) -> io::Result<()> {
+        self.send_signal(libc::SIGTERM)
+    }
+
+    fn wait_state_change(&mut self) -> io::Result<process::ExitStatus> {
+        self.as_inner_mut().wait_state_change().map(process::ExitStatus::from_inner)
+    }
+
+    fn on_exit<F>(self, f: F)
+        where F: FnOnce(io::Result<process::ExitStatus>) + Send + 'static
+    {
+        self.into_inner().on_exit(Box::new(move |status| {
+            f(status.map(process::ExitStatus::from_inner))
+        }))
+    }
+
+    fn exit_receiver(self) -> mpsc::Receiver<io::Result<process::ExitStatus>> {
+        let (tx, rx) = mpsc::channel();
+        self.on_exit(move |status| { let _ = tx.send(status); });
+        rx
+    }
 }
 
 /// Unix-specific extensions to [`process::ExitStatus`].
@@ -122,6 +412,28 @@ pub trait ExitStatusExt {
     /// If the process was terminated by a signal, returns that signal.
     #[stable(feature = "rust1", since = "1.0.0")]
     fn signal(&self) -> Option<i32>;
+
+    /// Returns whether the process was terminated by a signal and dumped
+    /// core.
+    #[unstable(feature = "unix_process_wait_more", issue = "0")]
+    fn core_dumped(&self) -> bool;
+
+    /// If the process was stopped by a signal, returns that signal.
+    ///
+    /// Such a status only comes from [`ChildExt::wait_state_change`], since
+    /// `wait` doesn't return until the process has terminated.
+    ///
+    /// [`ChildExt::wait_state_change`]: trait.ChildExt.html#tymethod.wait_state_change
+    #[unstable(feature = "unix_process_wait_more", issue = "0")]
+    fn stopped_signal(&self) -> Option<i32>;
+
+    /// Returns whether the process was continued after being stopped.
+    ///
+    /// Such a status only comes from [`ChildExt::wait_state_change`].
+    ///
+    /// [`ChildExt::wait_state_change`]: trait.ChildExt.html#tymethod.wait_state_change
+    #[unstable(feature = "unix_process_wait_more", issue = "0")]
+    fn continued(&self) -> bool;
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -133,6 +445,18 @@ impl ExitStatusExt for process::ExitStatus {
     fn signal(&self) -> Option<i32> {
         self.as_inner().signal()
     }
+
+    fn core_dumped(&self) -> bool {
+        self.as_inner().core_dumped()
+    }
+
+    fn stopped_signal(&self) -> Option<i32> {
+        self.as_inner().stopped_signal()
+    }
+
+    fn continued(&self) -> bool {
+        self.as_inner().continued()
+    }
 }
 
 #[stable(feature = "process_extensions", since = "1.2.0")]
@@ -191,3 +515,32 @@ impl IntoRawFd for process::ChildStderr {
 pub fn parent_id() -> u32 {
     ::sys::os::getppid()
 }
+
+/// Sets the name of the current process as shown by tools like `ps`.
+///
+/// The memory which held the original command line arguments is overwritten
+/// with `name`, which is cut short if it doesn't fit there; the arguments are
+/// copied elsewhere first, so [`env::args`] is unaffected. On Linux the main
+/// thread's name is set too, whichever thread this is called from, as that's
+/// the name `top` and `ps -o comm` show, cut short to 15 bytes.
+///
+/// This returns an error if `name` contains a nul byte, and when the runtime
+/// doesn't know where the original arguments are: on platforms which don't
+/// pass them to the runtime at startup, or when Rust code isn't started
+/// through the standard `main`.
+///
+/// [`env::args`]: ../../../env/fn.args.html
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(process_set_name)]
+/// use std::os::unix::process;
+///
+/// process::set_name("worker: idle").unwrap();
+/// ```
+#[unstable(feature = "process_set_name", issue = "0")]
+pub fn set_name<S: AsRef<OsStr>>(name: S) -> io::Result<()> {
+    sys::args::set_name(name.as_ref())
+}
+
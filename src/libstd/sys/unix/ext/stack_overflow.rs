@@ -0,0 +1,141 @@
+//! Unix-specific configuration of how stack overflows are handled.
+//!
+//! Every Rust thread has a guard region below its stack, and the runtime
+//! installs a `SIGSEGV`/`SIGBUS` handler which reports an overflow when a
+//! thread faults inside its guard region, then aborts the process. That
+//! handler runs on an alternate signal stack, one per thread. This module
+//! lets applications observe overflows before the abort and size those
+//! alternate stacks.
+
+#![unstable(feature = "stack_overflow_handler", issue = "0")]
+
+use ops::Range;
+use sys;
+use sys_common::thread_info;
+
+/// Information about a stack overflow, passed to the handler registered
+/// with [`set_handler`].
+///
+/// [`set_handler`]: fn.set_handler.html
+#[derive(Debug)]
+pub struct OverflowInfo<'a> {
+    fault_address: usize,
+    guard: Range<usize>,
+    thread_name: Option<&'a str>,
+}
+
+impl<'a> OverflowInfo<'a> {
+    pub(crate) fn new(fault_address: usize,
+                      guard: Range<usize>,
+                      thread_name: Option<&'a str>) -> OverflowInfo<'a> {
+        OverflowInfo { fault_address, guard, thread_name }
+    }
+
+    /// Returns the address whose access faulted.
+    pub fn fault_address(&self) -> usize {
+        self.fault_address
+    }
+
+    /// Returns the guard region of the overflowing thread, which contains
+    /// the fault address.
+    pub fn guard(&self) -> Range<usize> {
+        self.guard.clone()
+    }
+
+    /// Returns the name of the overflowing thread, if it has one.
+    pub fn thread_name(&self) -> Option<&str> {
+        self.thread_name
+    }
+}
+
+/// Registers a function to call when a thread overflows its stack.
+///
+/// The handler runs inside the signal handler, on the overflowing thread's
+/// alternate signal stack, before the runtime prints its usual message and
+/// aborts the process. It can't prevent the abort. It must restrict itself
+/// to what is safe in a signal handler: it shouldn't allocate, take locks,
+/// or use more stack than [`alt_stack_size`] leaves it. Writing a crash
+/// report to an already open file descriptor is fine.
+///
+/// The handler is a global resource; setting it replaces any handler set
+/// before. Platforms which don't detect stack overflows never call it.
+///
+/// [`alt_stack_size`]: fn.alt_stack_size.html
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(stack_overflow_handler)]
+/// use std::os::unix::stack_overflow::{self, OverflowInfo};
+///
+/// fn report(info: &OverflowInfo) {
+///     // write `info.fault_address()` to a crash log that is already open
+/// #   let _ = info;
+/// }
+///
+/// stack_overflow::set_handler(report);
+/// ```
+pub fn set_handler(handler: fn(&OverflowInfo)) {
+    sys::stack_overflow::set_overflow_handler(handler)
+}
+
+/// Sets the size of the alternate signal stacks made for threads started
+/// after this call.
+///
+/// Threads that run deep signal handlers, or a handler registered with
+/// [`set_handler`] that needs more room, may want more than the platform's
+/// `SIGSTKSZ`. Sizes below `SIGSTKSZ` are rounded up to it. The main
+/// thread's alternate stack is made before `main` runs, so this doesn't
+/// affect it.
+///
+/// [`set_handler`]: fn.set_handler.html
+pub fn set_alt_stack_size(size: usize) {
+    sys::stack_overflow::set_alt_stack_size(size)
+}
+
+/// Returns the size of the alternate signal stacks made for new threads, as
+/// requested with [`set_alt_stack_size`] or else `SIGSTKSZ`.
+///
+/// Platforms which don't detect stack overflows make no alternate stacks, and
+/// return 0 unless a size was requested.
+///
+/// [`set_alt_stack_size`]: fn.set_alt_stack_size.html
+pub fn alt_stack_size() -> usize {
+    sys::stack_overflow::alt_stack_size()
+}
+
+/// Returns the address range of the current thread's stack guard region, if
+/// the runtime knows it.
+///
+/// Accesses within this range are reported as stack overflows. Threads not
+/// started by the standard library, and platforms without guard regions,
+/// return `None`.
+pub fn current_guard() -> Option<Range<usize>> {
+    thread_info::stack_guard()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alt_stack_size_round_trip() {
+        let default = alt_stack_size();
+        set_alt_stack_size(default * 2);
+        assert_eq!(alt_stack_size(), default * 2);
+        set_alt_stack_size(0);
+        assert_eq!(alt_stack_size(), default);
+    }
+
+    #[test]
+    fn spawned_thread_has_guard() {
+        ::thread::spawn(|| {
+            if let Some(guard) = current_guard() {
+                let local = 0u8;
+                let addr = &local as *const u8 as usize;
+                assert!(guard.start <= guard.end);
+                assert!(addr < guard.start || addr >= guard.end);
+            }
+        }).join().unwrap();
+    }
+}
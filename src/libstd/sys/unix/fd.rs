@@ -29,6 +29,10 @@ fn max_len() -> usize {
     }
 }
 
+// `UIO_MAXIOV`, the most buffers which can be passed to a vectored call.
+#[cfg(target_os = "linux")]
+const MAX_IOV: usize = 1024;
+
 impl FileDesc {
     pub fn new(fd: c_int) -> FileDesc {
         FileDesc { fd }
@@ -96,6 +100,52 @@ impl FileDesc {
         }
     }
 
+    // `preadv` and `pwritev` are called directly, as `libc` doesn't have
+    // them for every Linux target yet. The offset is passed in two halves
+    // on every architecture, and the kernel ignores the high one on 64-bit
+    // ones.
+    #[cfg(target_os = "linux")]
+    pub fn read_vectored_at(&self, bufs: &mut [&mut [u8]], offset: u64) -> io::Result<usize> {
+        let iovecs = bufs.iter_mut().take(MAX_IOV).map(|buf| libc::iovec {
+            iov_base: buf.as_mut_ptr() as *mut c_void,
+            iov_len: buf.len(),
+        }).collect::<Vec<_>>();
+        let ret = cvt(unsafe {
+            libc::syscall(libc::SYS_preadv, self.fd, iovecs.as_ptr(), iovecs.len(),
+                          offset as libc::c_ulong, (offset >> 32) as libc::c_ulong)
+        })?;
+        Ok(ret as usize)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn read_vectored_at(&self, bufs: &mut [&mut [u8]], offset: u64) -> io::Result<usize> {
+        match bufs.iter_mut().find(|buf| !buf.is_empty()) {
+            Some(buf) => self.read_at(buf, offset),
+            None => Ok(0),
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn write_vectored_at(&self, bufs: &[&[u8]], offset: u64) -> io::Result<usize> {
+        let iovecs = bufs.iter().take(MAX_IOV).map(|buf| libc::iovec {
+            iov_base: buf.as_ptr() as *mut c_void,
+            iov_len: buf.len(),
+        }).collect::<Vec<_>>();
+        let ret = cvt(unsafe {
+            libc::syscall(libc::SYS_pwritev, self.fd, iovecs.as_ptr(), iovecs.len(),
+                          offset as libc::c_ulong, (offset >> 32) as libc::c_ulong)
+        })?;
+        Ok(ret as usize)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn write_vectored_at(&self, bufs: &[&[u8]], offset: u64) -> io::Result<usize> {
+        match bufs.iter().find(|buf| !buf.is_empty()) {
+            Some(buf) => self.write_at(buf, offset),
+            None => Ok(0),
+        }
+    }
+
     pub fn write(&self, buf: &[u8]) -> io::Result<usize> {
         let ret = cvt(unsafe {
             libc::write(self.fd,
@@ -156,19 +206,23 @@ impl FileDesc {
                   target_os = "emscripten",
                   target_os = "fuchsia",
                   target_os = "l4re",
-                  target_os = "haiku")))]
+                  target_os = "haiku",
+                  target_os = "vxworks",
+                  target_os = "nto")))]
     pub fn set_cloexec(&self) -> io::Result<()> {
         unsafe {
             cvt(libc::ioctl(self.fd, libc::FIOCLEX))?;
             Ok(())
         }
     }
-    #[cfg(any(target_env = "newlib",
+    #[cfg(any(all(target_env = "newlib", not(target_os = "espidf")),
               target_os = "solaris",
               target_os = "emscripten",
               target_os = "fuchsia",
               target_os = "l4re",
-              target_os = "haiku"))]
+              target_os = "haiku",
+              target_os = "vxworks",
+              target_os = "nto"))]
     pub fn set_cloexec(&self) -> io::Result<()> {
         unsafe {
             let previous = cvt(libc::fcntl(self.fd, libc::F_GETFD))?;
@@ -179,6 +233,12 @@ impl FileDesc {
             Ok(())
         }
     }
+    // Nothing can ever exec on ESP-IDF, and the VFS drivers (lwIP sockets in
+    // particular) reject `F_SETFD`.
+    #[cfg(target_os = "espidf")]
+    pub fn set_cloexec(&self) -> io::Result<()> {
+        Ok(())
+    }
 
     #[cfg(target_os = "linux")]
     pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
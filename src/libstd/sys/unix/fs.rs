@@ -8,15 +8,27 @@ use mem;
 use path::{Path, PathBuf};
 use ptr;
 use sync::Arc;
+use sys::ext::fs::Advice;
 use sys::fd::FileDesc;
 use sys::time::SystemTime;
 use sys::{cvt, cvt_r};
 use sys_common::{AsInner, FromInner};
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+use sys_common::fs::copy_chunks;
+use sys_common::fs::Progress;
 
 #[cfg(any(target_os = "linux", target_os = "emscripten", target_os = "l4re"))]
 use libc::{stat64, fstat64, lstat64, off64_t, ftruncate64, lseek64, dirent64, readdir64_r, open64};
 #[cfg(any(target_os = "linux", target_os = "emscripten"))]
 use libc::fstatat64;
+#[cfg(any(target_os = "macos",
+          target_os = "ios",
+          target_os = "freebsd",
+          target_os = "dragonfly",
+          target_os = "netbsd",
+          target_os = "openbsd",
+          target_os = "solaris"))]
+use libc::fstatat as fstatat64;
 #[cfg(any(target_os = "linux", target_os = "emscripten", target_os = "android"))]
 use libc::dirfd;
 #[cfg(target_os = "android")]
@@ -40,6 +52,174 @@ pub struct File(FileDesc);
 #[derive(Clone)]
 pub struct FileAttr {
     stat: stat64,
+    #[cfg(target_os = "linux")]
+    statx_extra_fields: Option<StatxExtraFields>,
+}
+
+// The parts of a `statx` result which don't fit in a `stat64`.
+#[cfg(target_os = "linux")]
+#[derive(Clone)]
+struct StatxExtraFields {
+    stx_mask: u32,
+    stx_btime: statx_imp::statx_timestamp,
+    stx_attributes: u64,
+    stx_attributes_mask: u64,
+    stx_mnt_id: u64,
+}
+
+// `statx` was added in Linux 4.11. Besides everything in `stat`, it gives
+// the birth time, the mount id (since Linux 5.8) and the `STATX_ATTR_*`
+// flags. Where it's missing, or a seccomp filter which doesn't know about it
+// refuses it with `EPERM`, the callers go back to the `stat` family for the
+// rest of the process.
+#[cfg(target_os = "linux")]
+mod statx_imp {
+    use io;
+    use libc::{self, c_char, c_int};
+    use mem;
+    use ptr;
+    use sync::atomic::{AtomicBool, Ordering};
+    use sys::cvt;
+
+    use super::{stat64, FileAttr, StatxExtraFields};
+
+    // The libc crate doesn't know about `statx` yet.
+    #[cfg(target_arch = "x86")]
+    const SYS_STATX: Option<libc::c_long> = Some(383);
+    #[cfg(target_arch = "x86_64")]
+    const SYS_STATX: Option<libc::c_long> = Some(332);
+    #[cfg(target_arch = "arm")]
+    const SYS_STATX: Option<libc::c_long> = Some(397);
+    #[cfg(target_arch = "aarch64")]
+    const SYS_STATX: Option<libc::c_long> = Some(291);
+    #[cfg(any(target_arch = "powerpc", target_arch = "powerpc64"))]
+    const SYS_STATX: Option<libc::c_long> = Some(383);
+    #[cfg(target_arch = "s390x")]
+    const SYS_STATX: Option<libc::c_long> = Some(379);
+    #[cfg(not(any(target_arch = "x86",
+                  target_arch = "x86_64",
+                  target_arch = "arm",
+                  target_arch = "aarch64",
+                  target_arch = "powerpc",
+                  target_arch = "powerpc64",
+                  target_arch = "s390x")))]
+    const SYS_STATX: Option<libc::c_long> = None;
+
+    pub const AT_STATX_SYNC_AS_STAT: c_int = 0;
+    pub const STATX_BASIC_STATS: u32 = 0x7ff;
+    pub const STATX_BTIME: u32 = 0x800;
+    pub const STATX_MNT_ID: u32 = 0x1000;
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    #[allow(non_camel_case_types)]
+    pub struct statx_timestamp {
+        pub tv_sec: i64,
+        pub tv_nsec: u32,
+        __reserved: i32,
+    }
+
+    #[repr(C)]
+    #[allow(non_camel_case_types)]
+    struct statx {
+        stx_mask: u32,
+        stx_blksize: u32,
+        stx_attributes: u64,
+        stx_nlink: u32,
+        stx_uid: u32,
+        stx_gid: u32,
+        stx_mode: u16,
+        __spare0: u16,
+        stx_ino: u64,
+        stx_size: u64,
+        stx_blocks: u64,
+        stx_attributes_mask: u64,
+        stx_atime: statx_timestamp,
+        stx_btime: statx_timestamp,
+        stx_ctime: statx_timestamp,
+        stx_mtime: statx_timestamp,
+        stx_rdev_major: u32,
+        stx_rdev_minor: u32,
+        stx_dev_major: u32,
+        stx_dev_minor: u32,
+        stx_mnt_id: u64,
+        __spare2: u64,
+        __spare3: [u64; 12],
+    }
+
+    static UNAVAILABLE: AtomicBool = AtomicBool::new(false);
+
+    /// Calls `statx(dirfd, path, flags)`, or returns `None` if the caller
+    /// should use the `stat` family instead.
+    pub fn try_statx(dirfd: c_int, path: *const c_char, flags: c_int)
+                     -> Option<io::Result<FileAttr>> {
+        let nr = match SYS_STATX {
+            Some(nr) => nr,
+            None => return None,
+        };
+        if UNAVAILABLE.load(Ordering::Relaxed) {
+            return None
+        }
+
+        let mut buf: statx = unsafe { mem::zeroed() };
+        let mask = STATX_BASIC_STATS | STATX_BTIME | STATX_MNT_ID;
+        let res = cvt(unsafe {
+            libc::syscall(nr, dirfd, path, flags, mask, &mut buf as *mut statx)
+        });
+        if let Err(err) = res {
+            match err.raw_os_error() {
+                Some(libc::ENOSYS) => {}
+                // `EPERM` may be genuine, so check that `statx` gets as far
+                // as looking at its arguments, which it doesn't if it's been
+                // filtered out.
+                Some(libc::EPERM) if unsafe {
+                    libc::syscall(nr, 0, ptr::null::<c_char>(), 0, mask,
+                                  ptr::null_mut::<statx>()) == -1 &&
+                        io::Error::last_os_error().raw_os_error() == Some(libc::EFAULT)
+                } => return Some(Err(err)),
+                Some(libc::EPERM) => {}
+                _ => return Some(Err(err)),
+            }
+            UNAVAILABLE.store(true, Ordering::Relaxed);
+            return None
+        }
+
+        let mut stat: stat64 = unsafe { mem::zeroed() };
+        stat.st_dev = makedev(buf.stx_dev_major, buf.stx_dev_minor) as _;
+        stat.st_ino = buf.stx_ino as _;
+        stat.st_nlink = buf.stx_nlink as _;
+        stat.st_mode = buf.stx_mode as _;
+        stat.st_uid = buf.stx_uid as _;
+        stat.st_gid = buf.stx_gid as _;
+        stat.st_rdev = makedev(buf.stx_rdev_major, buf.stx_rdev_minor) as _;
+        stat.st_size = buf.stx_size as _;
+        stat.st_blksize = buf.stx_blksize as _;
+        stat.st_blocks = buf.stx_blocks as _;
+        stat.st_atime = buf.stx_atime.tv_sec as _;
+        stat.st_atime_nsec = buf.stx_atime.tv_nsec as _;
+        stat.st_mtime = buf.stx_mtime.tv_sec as _;
+        stat.st_mtime_nsec = buf.stx_mtime.tv_nsec as _;
+        stat.st_ctime = buf.stx_ctime.tv_sec as _;
+        stat.st_ctime_nsec = buf.stx_ctime.tv_nsec as _;
+
+        Some(Ok(FileAttr {
+            stat,
+            statx_extra_fields: Some(StatxExtraFields {
+                stx_mask: buf.stx_mask,
+                stx_btime: buf.stx_btime,
+                stx_attributes: buf.stx_attributes,
+                stx_attributes_mask: buf.stx_attributes_mask,
+                stx_mnt_id: buf.stx_mnt_id,
+            }),
+        }))
+    }
+
+    // The same encoding as glibc's `makedev`.
+    fn makedev(major: u32, minor: u32) -> u64 {
+        let (major, minor) = (major as u64, minor as u64);
+        ((major & 0xfffff000) << 32) | ((major & 0x00000fff) << 8) |
+            ((minor & 0xffffff00) << 12) | (minor & 0x000000ff)
+    }
 }
 
 // all DirEntry's will have a reference to this struct
@@ -48,12 +228,27 @@ struct InnerReadDir {
     root: PathBuf,
 }
 
-#[derive(Clone)]
 pub struct ReadDir {
     inner: Arc<InnerReadDir>,
     end_of_stream: bool,
+    #[cfg(target_os = "linux")]
+    dirents: Dirents,
 }
 
+// On Linux the entries are read with `getdents64` directly, so that the
+// caller can choose how many are fetched with each call. The records are
+// `dirent64`s, but only as long as their names need.
+#[cfg(target_os = "linux")]
+struct Dirents {
+    buf: Vec<u8>,
+    pos: usize,
+    len: usize,
+    size: usize,
+}
+
+#[cfg(target_os = "linux")]
+const DIRENTS_BUF_SIZE: usize = 64 * 1024;
+
 struct Dir(*mut libc::DIR);
 
 unsafe impl Send for Dir {}
@@ -61,7 +256,7 @@ unsafe impl Sync for Dir {}
 
 pub struct DirEntry {
     entry: dirent64,
-    dir: ReadDir,
+    dir: Arc<InnerReadDir>,
     // We need to store an owned copy of the entry name
     // on Solaris and Fuchsia because a) it uses a zero-length
     // array to store the name, b) its lifetime between readdir
@@ -94,6 +289,14 @@ pub struct FileType { mode: mode_t }
 pub struct DirBuilder { mode: mode_t }
 
 impl FileAttr {
+    fn from_stat64(stat: stat64) -> FileAttr {
+        FileAttr {
+            stat,
+            #[cfg(target_os = "linux")]
+            statx_extra_fields: None,
+        }
+    }
+
     pub fn size(&self) -> u64 { self.stat.st_size as u64 }
     pub fn perm(&self) -> FilePermissions {
         FilePermissions { mode: (self.stat.st_mode as mode_t) }
@@ -128,7 +331,58 @@ impl FileAttr {
     }
 }
 
-#[cfg(not(target_os = "netbsd"))]
+// VxWorks and the ESP-IDF file systems only keep whole seconds.
+#[cfg(any(target_os = "vxworks", target_os = "espidf"))]
+impl FileAttr {
+    pub fn modified(&self) -> io::Result<SystemTime> {
+        Ok(SystemTime::from(libc::timespec {
+            tv_sec: self.stat.st_mtime as libc::time_t,
+            tv_nsec: 0,
+        }))
+    }
+
+    pub fn accessed(&self) -> io::Result<SystemTime> {
+        Ok(SystemTime::from(libc::timespec {
+            tv_sec: self.stat.st_atime as libc::time_t,
+            tv_nsec: 0,
+        }))
+    }
+
+    pub fn created(&self) -> io::Result<SystemTime> {
+        Err(io::Error::new(io::ErrorKind::Other,
+                           "creation time is not available on this platform \
+                            currently"))
+    }
+}
+
+// QNX only has the POSIX 2008 `timespec` fields, and no birth time.
+#[cfg(target_os = "nto")]
+impl FileAttr {
+    pub fn modified(&self) -> io::Result<SystemTime> {
+        Ok(SystemTime::from(libc::timespec {
+            tv_sec: self.stat.st_mtim.tv_sec as libc::time_t,
+            tv_nsec: self.stat.st_mtim.tv_nsec as _,
+        }))
+    }
+
+    pub fn accessed(&self) -> io::Result<SystemTime> {
+        Ok(SystemTime::from(libc::timespec {
+            tv_sec: self.stat.st_atim.tv_sec as libc::time_t,
+            tv_nsec: self.stat.st_atim.tv_nsec as _,
+        }))
+    }
+
+    pub fn created(&self) -> io::Result<SystemTime> {
+        Err(io::Error::new(io::ErrorKind::Other,
+                           "creation time is not available on this platform \
+                            currently"))
+    }
+}
+
+#[cfg(not(any(target_os = "netbsd",
+              target_os = "vxworks",
+              target_os = "espidf",
+              target_os = "nto")))]
 impl FileAttr {
     pub fn modified(&self) -> io::Result<SystemTime> {
         Ok(SystemTime::from(libc::timespec {
@@ -148,7 +402,9 @@ impl FileAttr {
               target_os = "freebsd",
               target_os = "openbsd",
               target_os = "macos",
-              target_os = "ios"))]
+              target_os = "ios",
+              target_os = "tvos",
+              target_os = "watchos"))]
     pub fn created(&self) -> io::Result<SystemTime> {
         Ok(SystemTime::from(libc::timespec {
             tv_sec: self.stat.st_birthtime as libc::time_t,
@@ -156,11 +412,36 @@ impl FileAttr {
         }))
     }
 
+    #[cfg(target_os = "linux")]
+    pub fn created(&self) -> io::Result<SystemTime> {
+        match self.statx_extra_fields {
+            Some(ref ext) if ext.stx_mask & statx_imp::STATX_BTIME != 0 => {
+                Ok(SystemTime::from(libc::timespec {
+                    tv_sec: ext.stx_btime.tv_sec as libc::time_t,
+                    tv_nsec: ext.stx_btime.tv_nsec as _,
+                }))
+            }
+            Some(_) => {
+                Err(io::Error::new(io::ErrorKind::Other,
+                                   "creation time is not available for the \
+                                    filesystem"))
+            }
+            None => {
+                Err(io::Error::new(io::ErrorKind::Other,
+                                   "creation time is not available on this \
+                                    kernel"))
+            }
+        }
+    }
+
     #[cfg(not(any(target_os = "bitrig",
                   target_os = "freebsd",
                   target_os = "openbsd",
                   target_os = "macos",
-                  target_os = "ios")))]
+                  target_os = "ios",
+                  target_os = "tvos",
+                  target_os = "watchos",
+                  target_os = "linux")))]
     pub fn created(&self) -> io::Result<SystemTime> {
         Err(io::Error::new(io::ErrorKind::Other,
                            "creation time is not available on this platform \
@@ -168,6 +449,31 @@ impl FileAttr {
     }
 }
 
+#[cfg(target_os = "linux")]
+impl FileAttr {
+    pub fn mount_id(&self) -> Option<u64> {
+        match self.statx_extra_fields {
+            Some(ref ext) if ext.stx_mask & statx_imp::STATX_MNT_ID != 0 => Some(ext.stx_mnt_id),
+            _ => None,
+        }
+    }
+
+    pub fn attributes(&self) -> Option<u64> {
+        self.statx_extra_fields.as_ref().map(|ext| ext.stx_attributes)
+    }
+
+    pub fn attributes_mask(&self) -> Option<u64> {
+        self.statx_extra_fields.as_ref().map(|ext| ext.stx_attributes_mask)
+    }
+}
+
+#[cfg(target_os = "l4re")]
+impl FileAttr {
+    pub fn mount_id(&self) -> Option<u64> { None }
+    pub fn attributes(&self) -> Option<u64> { None }
+    pub fn attributes_mask(&self) -> Option<u64> { None }
+}
+
 impl AsInner<stat64> for FileAttr {
     fn as_inner(&self) -> &stat64 { &self.stat }
 }
@@ -241,7 +547,7 @@ impl Iterator for ReadDir {
                     entry: *entry_ptr,
                     name: ::slice::from_raw_parts(name as *const u8,
                                                   namelen as usize).to_owned().into_boxed_slice(),
-                    dir: self.clone()
+                    dir: self.inner.clone()
                 };
                 if ret.name_bytes() != b"." && ret.name_bytes() != b".." {
                     return Some(Ok(ret))
@@ -250,7 +556,50 @@ impl Iterator for ReadDir {
         }
     }
 
-    #[cfg(not(any(target_os = "solaris", target_os = "fuchsia")))]
+    #[cfg(target_os = "linux")]
+    fn next(&mut self) -> Option<io::Result<DirEntry>> {
+        if self.end_of_stream {
+            return None;
+        }
+
+        loop {
+            if self.dirents.pos == self.dirents.len {
+                if let Err(e) = self.fill_dirents() {
+                    // Reading the same entries again would only fail again.
+                    self.end_of_stream = true;
+                    return Some(Err(e))
+                }
+                if self.dirents.len == 0 {
+                    self.end_of_stream = true;
+                    return None
+                }
+            }
+
+            let mut entry: dirent64 = unsafe { mem::zeroed() };
+            let is_dot = {
+                let rec = &self.dirents.buf[self.dirents.pos..self.dirents.len];
+                unsafe {
+                    entry.d_ino = ptr::read_unaligned(rec.as_ptr() as *const u64) as _;
+                    entry.d_off = ptr::read_unaligned(rec.as_ptr().add(8) as *const i64) as _;
+                    entry.d_reclen = ptr::read_unaligned(rec.as_ptr().add(16) as *const u16);
+                }
+                entry.d_type = rec[18];
+                let name = &rec[19..entry.d_reclen as usize];
+                let name = &name[..name.iter().position(|&b| b == 0).unwrap_or(name.len())];
+                for (dst, &src) in entry.d_name.iter_mut().zip(name) {
+                    *dst = src as libc::c_char;
+                }
+                name == b"." || name == b".."
+            };
+            self.dirents.pos += entry.d_reclen as usize;
+
+            if !is_dot {
+                return Some(Ok(DirEntry { entry, dir: self.inner.clone() }))
+            }
+        }
+    }
+
+    #[cfg(not(any(target_os = "solaris", target_os = "fuchsia", target_os = "linux")))]
     fn next(&mut self) -> Option<io::Result<DirEntry>> {
         if self.end_of_stream {
             return None;
@@ -259,7 +608,7 @@ impl Iterator for ReadDir {
         unsafe {
             let mut ret = DirEntry {
                 entry: mem::zeroed(),
-                dir: self.clone(),
+                dir: self.inner.clone(),
             };
             let mut entry_ptr = ptr::null_mut();
             loop {
@@ -284,6 +633,47 @@ impl Iterator for ReadDir {
     }
 }
 
+impl ReadDir {
+    fn new(dirp: Dir, root: PathBuf) -> ReadDir {
+        ReadDir {
+            inner: Arc::new(InnerReadDir { dirp, root }),
+            end_of_stream: false,
+            #[cfg(target_os = "linux")]
+            dirents: Dirents {
+                buf: Vec::new(),
+                pos: 0,
+                len: 0,
+                size: DIRENTS_BUF_SIZE,
+            },
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn set_buffer_size(&mut self, size: usize) {
+        // The buffer has to hold at least one entry, however long its name.
+        self.dirents.size = size.max(mem::size_of::<dirent64>());
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn set_buffer_size(&mut self, _size: usize) {}
+
+    #[cfg(target_os = "linux")]
+    fn fill_dirents(&mut self) -> io::Result<()> {
+        let dirents = &mut self.dirents;
+        if dirents.buf.len() != dirents.size {
+            dirents.buf = vec![0; dirents.size];
+        }
+        let fd = cvt(unsafe { dirfd(self.inner.dirp.0) })?;
+        let n = cvt_r(|| unsafe {
+            libc::syscall(libc::SYS_getdents64, fd,
+                          dirents.buf.as_mut_ptr(), dirents.buf.len())
+        })?;
+        dirents.pos = 0;
+        dirents.len = n as usize;
+        Ok(())
+    }
+}
+
 impl Drop for Dir {
     fn drop(&mut self) {
         let r = unsafe { libc::closedir(self.0) };
@@ -293,7 +683,7 @@ impl Drop for Dir {
 
 impl DirEntry {
     pub fn path(&self) -> PathBuf {
-        self.dir.inner.root.join(OsStr::from_bytes(self.name_bytes()))
+        self.dir.root.join(OsStr::from_bytes(self.name_bytes()))
     }
 
     pub fn file_name(&self) -> OsString {
@@ -302,12 +692,19 @@ impl DirEntry {
 
     #[cfg(any(target_os = "linux", target_os = "emscripten", target_os = "android"))]
     pub fn metadata(&self) -> io::Result<FileAttr> {
-        let fd = cvt(unsafe {dirfd(self.dir.inner.dirp.0)})?;
+        let fd = cvt(unsafe {dirfd(self.dir.dirp.0)})?;
+        #[cfg(target_os = "linux")]
+        {
+            if let Some(ret) = statx_imp::try_statx(fd, self.entry.d_name.as_ptr(),
+                                                    libc::AT_SYMLINK_NOFOLLOW) {
+                return ret;
+            }
+        }
         let mut stat: stat64 = unsafe { mem::zeroed() };
         cvt(unsafe {
             fstatat64(fd, self.entry.d_name.as_ptr(), &mut stat, libc::AT_SYMLINK_NOFOLLOW)
         })?;
-        Ok(FileAttr { stat })
+        Ok(FileAttr::from_stat64(stat))
     }
 
     #[cfg(not(any(target_os = "linux", target_os = "emscripten", target_os = "android")))]
@@ -315,12 +712,22 @@ impl DirEntry {
         lstat(&self.path())
     }
 
-    #[cfg(any(target_os = "solaris", target_os = "haiku", target_os = "hermit"))]
+    #[cfg(any(target_os = "solaris",
+              target_os = "haiku",
+              target_os = "hermit",
+              target_os = "vxworks",
+              target_os = "espidf",
+              target_os = "nto"))]
     pub fn file_type(&self) -> io::Result<FileType> {
         lstat(&self.path()).map(|m| m.file_type())
     }
 
-    #[cfg(not(any(target_os = "solaris", target_os = "haiku", target_os = "hermit")))]
+    #[cfg(not(any(target_os = "solaris",
+                  target_os = "haiku",
+                  target_os = "hermit",
+                  target_os = "vxworks",
+                  target_os = "espidf",
+                  target_os = "nto")))]
     pub fn file_type(&self) -> io::Result<FileType> {
         match self.entry.d_type {
             libc::DT_CHR => Ok(FileType { mode: libc::S_IFCHR }),
@@ -336,6 +743,8 @@ impl DirEntry {
 
     #[cfg(any(target_os = "macos",
               target_os = "ios",
+              target_os = "tvos",
+              target_os = "watchos",
               target_os = "linux",
               target_os = "emscripten",
               target_os = "android",
@@ -343,7 +752,10 @@ impl DirEntry {
               target_os = "haiku",
               target_os = "l4re",
               target_os = "fuchsia",
-              target_os = "hermit"))]
+              target_os = "hermit",
+              target_os = "vxworks",
+              target_os = "espidf",
+              target_os = "nto"))]
     pub fn ino(&self) -> u64 {
         self.entry.d_ino as u64
     }
@@ -359,6 +771,8 @@ impl DirEntry {
 
     #[cfg(any(target_os = "macos",
               target_os = "ios",
+              target_os = "tvos",
+              target_os = "watchos",
               target_os = "netbsd",
               target_os = "openbsd",
               target_os = "freebsd",
@@ -375,7 +789,10 @@ impl DirEntry {
               target_os = "emscripten",
               target_os = "l4re",
               target_os = "haiku",
-              target_os = "hermit"))]
+              target_os = "hermit",
+              target_os = "vxworks",
+              target_os = "espidf",
+              target_os = "nto"))]
     fn name_bytes(&self) -> &[u8] {
         unsafe {
             CStr::from_ptr(self.entry.d_name.as_ptr()).to_bytes()
@@ -512,11 +929,20 @@ impl File {
     }
 
     pub fn file_attr(&self) -> io::Result<FileAttr> {
+        #[cfg(target_os = "linux")]
+        {
+            let empty = b"\0";
+            if let Some(ret) = statx_imp::try_statx(self.0.raw(),
+                                                    empty.as_ptr() as *const libc::c_char,
+                                                    libc::AT_EMPTY_PATH) {
+                return ret;
+            }
+        }
         let mut stat: stat64 = unsafe { mem::zeroed() };
         cvt(unsafe {
             fstat64(self.0.raw(), &mut stat)
         })?;
-        Ok(FileAttr { stat })
+        Ok(FileAttr::from_stat64(stat))
     }
 
     pub fn fsync(&self) -> io::Result<()> {
@@ -528,7 +954,10 @@ impl File {
         cvt_r(|| unsafe { os_datasync(self.0.raw()) })?;
         return Ok(());
 
-        #[cfg(any(target_os = "macos", target_os = "ios"))]
+        #[cfg(any(target_os = "macos",
+                  target_os = "ios",
+                  target_os = "tvos",
+                  target_os = "watchos"))]
         unsafe fn os_datasync(fd: c_int) -> c_int {
             libc::fcntl(fd, libc::F_FULLFSYNC)
         }
@@ -536,6 +965,8 @@ impl File {
         unsafe fn os_datasync(fd: c_int) -> c_int { libc::fdatasync(fd) }
         #[cfg(not(any(target_os = "macos",
                       target_os = "ios",
+                      target_os = "tvos",
+                      target_os = "watchos",
                       target_os = "linux")))]
         unsafe fn os_datasync(fd: c_int) -> c_int { libc::fsync(fd) }
     }
@@ -550,6 +981,150 @@ impl File {
         }).map(|_| ());
     }
 
+    // Like `posix_fallocate`, this makes the file at least `len` bytes long,
+    // but never shrinks it. glibc's `posix_fallocate` writes to each block
+    // itself on filesystems without `fallocate`. It returns the error rather
+    // than setting `errno`.
+    #[cfg(any(target_os = "linux", target_os = "freebsd"))]
+    pub fn allocate(&self, len: u64) -> io::Result<()> {
+        if len > libc::off_t::max_value() as u64 {
+            return Err(Error::new(ErrorKind::InvalidInput,
+                                  "allocation length is too large"))
+        }
+        loop {
+            match unsafe { libc::posix_fallocate(self.0.raw(), 0, len as libc::off_t) } {
+                0 => return Ok(()),
+                libc::EINTR => {}
+                err => return Err(Error::from_raw_os_error(err)),
+            }
+        }
+    }
+
+    #[cfg(target_os = "android")]
+    pub fn allocate(&self, len: u64) -> io::Result<()> {
+        if len > libc::off_t::max_value() as u64 {
+            return Err(Error::new(ErrorKind::InvalidInput,
+                                  "allocation length is too large"))
+        }
+        cvt_r(|| unsafe {
+            libc::fallocate(self.0.raw(), 0, 0, len as libc::off_t)
+        })?;
+        Ok(())
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    pub fn allocate(&self, len: u64) -> io::Result<()> {
+        let size = self.file_attr()?.size();
+        if len <= size {
+            return Ok(())
+        }
+        if len > libc::off_t::max_value() as u64 {
+            return Err(Error::new(ErrorKind::InvalidInput,
+                                  "allocation length is too large"))
+        }
+        // `F_PREALLOCATE` allocates past the end of the file without
+        // changing its size, so it's extended separately afterwards. One
+        // contiguous extent is best, but any will do.
+        let mut store = libc::fstore_t {
+            fst_flags: libc::F_ALLOCATECONTIG as _,
+            fst_posmode: libc::F_PEOFPOSMODE as _,
+            fst_offset: 0,
+            fst_length: (len - size) as libc::off_t,
+            fst_bytesalloc: 0,
+        };
+        if unsafe { libc::fcntl(self.0.raw(), libc::F_PREALLOCATE, &mut store) } == -1 {
+            store.fst_flags = libc::F_ALLOCATEALL as _;
+            cvt(unsafe { libc::fcntl(self.0.raw(), libc::F_PREALLOCATE, &mut store) })?;
+        }
+        self.truncate(len)
+    }
+
+    #[cfg(not(any(target_os = "linux",
+                  target_os = "android",
+                  target_os = "freebsd",
+                  target_os = "macos",
+                  target_os = "ios")))]
+    pub fn allocate(&self, _len: u64) -> io::Result<()> {
+        Err(io::Error::new(ErrorKind::Other, "preallocation is not supported on this platform"))
+    }
+
+    // `posix_fadvise` returns the error rather than setting `errno`.
+    #[cfg(any(target_os = "linux", target_os = "android", target_os = "freebsd"))]
+    pub fn advise(&self, offset: u64, len: u64, advice: Advice) -> io::Result<()> {
+        let max = libc::off_t::max_value() as u64;
+        if offset > max || len > max {
+            return Err(Error::new(ErrorKind::InvalidInput, "advice range is too large"))
+        }
+        let advice = match advice {
+            Advice::Normal => libc::POSIX_FADV_NORMAL,
+            Advice::Sequential => libc::POSIX_FADV_SEQUENTIAL,
+            Advice::Random => libc::POSIX_FADV_RANDOM,
+            Advice::NoReuse => libc::POSIX_FADV_NOREUSE,
+            Advice::WillNeed => libc::POSIX_FADV_WILLNEED,
+            Advice::DontNeed => libc::POSIX_FADV_DONTNEED,
+        };
+        match unsafe {
+            libc::posix_fadvise(self.0.raw(), offset as libc::off_t, len as libc::off_t, advice)
+        } {
+            0 => Ok(()),
+            err => Err(Error::from_raw_os_error(err)),
+        }
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "android", target_os = "freebsd")))]
+    pub fn advise(&self, _offset: u64, _len: u64, _advice: Advice) -> io::Result<()> {
+        Err(io::Error::new(ErrorKind::Other, "file advice is not supported on this platform"))
+    }
+
+    pub fn seek_data(&self, offset: u64) -> io::Result<Option<u64>> {
+        self.seek_sparse(offset, true)
+    }
+
+    pub fn seek_hole(&self, offset: u64) -> io::Result<Option<u64>> {
+        self.seek_sparse(offset, false)
+    }
+
+    // Moves the offset to the first data or hole at or after `offset`, if
+    // `offset` is before the end of the file.
+    #[cfg(any(target_os = "linux",
+              target_os = "android",
+              target_os = "freebsd",
+              target_os = "solaris",
+              target_os = "macos",
+              target_os = "ios"))]
+    fn seek_sparse(&self, offset: u64, data: bool) -> io::Result<Option<u64>> {
+        // These are missing from `libc` for now, and macOS numbers them the
+        // other way around.
+        #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+        const SEEK_DATA: c_int = 3;
+        #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+        const SEEK_HOLE: c_int = 4;
+        #[cfg(any(target_os = "macos", target_os = "ios"))]
+        const SEEK_HOLE: c_int = 3;
+        #[cfg(any(target_os = "macos", target_os = "ios"))]
+        const SEEK_DATA: c_int = 4;
+
+        if offset > i64::max_value() as u64 {
+            return Ok(None)
+        }
+        let whence = if data { SEEK_DATA } else { SEEK_HOLE };
+        match cvt(unsafe { lseek64(self.0.raw(), offset as i64, whence) }) {
+            Ok(n) => Ok(Some(n as u64)),
+            Err(ref e) if e.raw_os_error() == Some(libc::ENXIO) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    #[cfg(not(any(target_os = "linux",
+              target_os = "android",
+              target_os = "freebsd",
+              target_os = "solaris",
+              target_os = "macos",
+              target_os = "ios")))]
+    fn seek_sparse(&self, _offset: u64, _data: bool) -> io::Result<Option<u64>> {
+        Err(io::Error::new(ErrorKind::Other, "finding holes is not supported on this platform"))
+    }
+
     pub fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
         self.0.read(buf)
     }
@@ -558,6 +1133,10 @@ impl File {
         self.0.read_at(buf, offset)
     }
 
+    pub fn read_vectored_at(&self, bufs: &mut [&mut [u8]], offset: u64) -> io::Result<usize> {
+        self.0.read_vectored_at(bufs, offset)
+    }
+
     pub fn write(&self, buf: &[u8]) -> io::Result<usize> {
         self.0.write(buf)
     }
@@ -566,6 +1145,10 @@ impl File {
         self.0.write_at(buf, offset)
     }
 
+    pub fn write_vectored_at(&self, bufs: &[&[u8]], offset: u64) -> io::Result<usize> {
+        self.0.write_vectored_at(bufs, offset)
+    }
+
     pub fn flush(&self) -> io::Result<()> { Ok(()) }
 
     pub fn seek(&self, pos: SeekFrom) -> io::Result<u64> {
@@ -594,6 +1177,116 @@ impl File {
         cvt_r(|| unsafe { libc::fchmod(self.0.raw(), perm.mode) })?;
         Ok(())
     }
+
+    // `flock` locks belong to the open file description, like locks on
+    // Windows belong to the handle, rather than to the process as `fcntl`
+    // locks do, so they don't go away when another `File` for the same file
+    // is closed.
+    #[cfg(any(target_os = "linux",
+              target_os = "android",
+              target_os = "macos",
+              target_os = "ios",
+              target_os = "freebsd",
+              target_os = "dragonfly",
+              target_os = "netbsd",
+              target_os = "openbsd"))]
+    pub fn lock(&self, exclusive: bool, block: bool) -> io::Result<bool> {
+        let mut operation = if exclusive { libc::LOCK_EX } else { libc::LOCK_SH };
+        if !block {
+            operation |= libc::LOCK_NB;
+        }
+        match cvt_r(|| unsafe { libc::flock(self.0.raw(), operation) }) {
+            Ok(_) => Ok(true),
+            Err(ref e) if e.raw_os_error() == Some(libc::EWOULDBLOCK) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    #[cfg(any(target_os = "linux",
+              target_os = "android",
+              target_os = "macos",
+              target_os = "ios",
+              target_os = "freebsd",
+              target_os = "dragonfly",
+              target_os = "netbsd",
+              target_os = "openbsd"))]
+    pub fn unlock(&self) -> io::Result<()> {
+        cvt_r(|| unsafe { libc::flock(self.0.raw(), libc::LOCK_UN) })?;
+        Ok(())
+    }
+
+    #[cfg(not(any(target_os = "linux",
+                  target_os = "android",
+                  target_os = "macos",
+                  target_os = "ios",
+                  target_os = "freebsd",
+                  target_os = "dragonfly",
+                  target_os = "netbsd",
+                  target_os = "openbsd")))]
+    pub fn lock(&self, _exclusive: bool, _block: bool) -> io::Result<bool> {
+        Err(io::Error::new(ErrorKind::Other, "file locking is not supported on this platform"))
+    }
+
+    #[cfg(not(any(target_os = "linux",
+                  target_os = "android",
+                  target_os = "macos",
+                  target_os = "ios",
+                  target_os = "freebsd",
+                  target_os = "dragonfly",
+                  target_os = "netbsd",
+                  target_os = "openbsd")))]
+    pub fn unlock(&self) -> io::Result<()> {
+        Err(io::Error::new(ErrorKind::Other, "file locking is not supported on this platform"))
+    }
+
+    // Opens an unnamed file in the filesystem of `dir`, which only the
+    // access mode, mode and custom flags of `opts` apply to.
+    #[cfg(target_os = "linux")]
+    pub fn open_tmpfile(dir: &Path, opts: &OpenOptions) -> io::Result<File> {
+        let dir = cstr(dir)?;
+        let flags = libc::O_CLOEXEC |
+                    libc::O_TMPFILE |
+                    opts.get_access_mode()? |
+                    (opts.custom_flags as c_int & !libc::O_ACCMODE);
+        let fd = cvt_r(|| unsafe {
+            open64(dir.as_ptr(), flags, opts.mode as c_int)
+        })?;
+        Ok(File(FileDesc::new(fd)))
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn link_at(&self, path: &Path) -> io::Result<()> {
+        let path = cstr(path)?;
+        let empty = b"\0";
+        let res = cvt(unsafe {
+            libc::linkat(self.0.raw(), empty.as_ptr() as *const libc::c_char,
+                         libc::AT_FDCWD, path.as_ptr(), libc::AT_EMPTY_PATH)
+        });
+        match res {
+            Ok(_) => Ok(()),
+            // `AT_EMPTY_PATH` needs `CAP_DAC_READ_SEARCH`, but the same can
+            // be done through /proc by anyone who can get at the file.
+            Err(ref e) if e.raw_os_error() == Some(libc::ENOENT) => {
+                let fd_path = CString::new(format!("/proc/self/fd/{}", self.0.raw()))?;
+                cvt(unsafe {
+                    libc::linkat(libc::AT_FDCWD, fd_path.as_ptr(),
+                                 libc::AT_FDCWD, path.as_ptr(), libc::AT_SYMLINK_FOLLOW)
+                })?;
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn open_tmpfile(_dir: &Path, _opts: &OpenOptions) -> io::Result<File> {
+        Err(io::Error::new(ErrorKind::Other, "unnamed files are not supported on this platform"))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn link_at(&self, _path: &Path) -> io::Result<()> {
+        Err(io::Error::new(ErrorKind::Other, "unnamed files are not supported on this platform"))
+    }
 }
 
 impl DirBuilder {
@@ -696,12 +1389,167 @@ pub fn readdir(p: &Path) -> io::Result<ReadDir> {
         if ptr.is_null() {
             Err(Error::last_os_error())
         } else {
-            let inner = InnerReadDir { dirp: Dir(ptr), root };
-            Ok(ReadDir{
-                inner: Arc::new(inner),
-                end_of_stream: false,
-            })
+            Ok(ReadDir::new(Dir(ptr), root))
+        }
+    }
+}
+
+// A directory which other files can be opened and managed relative to, with
+// `openat` and friends. The path it was opened with is only kept for
+// `Debug` and for the paths of the entries of `read_dir_at`.
+pub struct DirHandle {
+    fd: FileDesc,
+    path: PathBuf,
+}
+
+#[cfg(any(target_os = "linux",
+          target_os = "android",
+          target_os = "macos",
+          target_os = "ios",
+          target_os = "freebsd",
+          target_os = "dragonfly",
+          target_os = "netbsd",
+          target_os = "openbsd",
+          target_os = "solaris"))]
+impl DirHandle {
+    pub fn open(path: &Path) -> io::Result<DirHandle> {
+        let p = cstr(path)?;
+        let fd = cvt_r(|| unsafe {
+            open64(p.as_ptr(), libc::O_RDONLY | libc::O_DIRECTORY | libc::O_CLOEXEC, 0)
+        })?;
+        Ok(DirHandle { fd: FileDesc::new(fd), path: path.to_path_buf() })
+    }
+
+    pub fn open_at(&self, path: &Path, opts: &OpenOptions) -> io::Result<File> {
+        let p = cstr(path)?;
+        let flags = libc::O_CLOEXEC |
+                    opts.get_access_mode()? |
+                    opts.get_creation_mode()? |
+                    (opts.custom_flags as c_int & !libc::O_ACCMODE);
+        // `open64` adds this itself, but there's no `openat64`.
+        #[cfg(target_os = "linux")]
+        let flags = flags | libc::O_LARGEFILE;
+        let fd = cvt_r(|| unsafe {
+            libc::openat(self.fd.raw(), p.as_ptr(), flags, opts.mode as c_int)
+        })?;
+        Ok(File(FileDesc::new(fd)))
+    }
+
+    pub fn open_dir_at(&self, path: &Path) -> io::Result<DirHandle> {
+        let p = cstr(path)?;
+        let fd = cvt_r(|| unsafe {
+            libc::openat(self.fd.raw(), p.as_ptr(),
+                         libc::O_RDONLY | libc::O_DIRECTORY | libc::O_CLOEXEC, 0)
+        })?;
+        Ok(DirHandle { fd: FileDesc::new(fd), path: self.path.join(path) })
+    }
+
+    pub fn read_dir_at(&self, path: &Path) -> io::Result<ReadDir> {
+        let dir = self.open_dir_at(path)?;
+        let ptr = unsafe { libc::fdopendir(dir.fd.raw()) };
+        if ptr.is_null() {
+            return Err(Error::last_os_error())
+        }
+        // The `DIR` owns the descriptor now.
+        dir.fd.into_raw();
+        Ok(ReadDir::new(Dir(ptr), dir.path))
+    }
+
+    pub fn metadata_at(&self, path: &Path, follow: bool) -> io::Result<FileAttr> {
+        let p = cstr(path)?;
+        let flags = if follow { 0 } else { libc::AT_SYMLINK_NOFOLLOW };
+        #[cfg(target_os = "linux")]
+        {
+            if let Some(ret) = statx_imp::try_statx(self.fd.raw(), p.as_ptr(), flags) {
+                return ret;
+            }
         }
+        let mut stat: stat64 = unsafe { mem::zeroed() };
+        cvt(unsafe { fstatat64(self.fd.raw(), p.as_ptr(), &mut stat, flags) })?;
+        Ok(FileAttr::from_stat64(stat))
+    }
+
+    pub fn remove_file_at(&self, path: &Path) -> io::Result<()> {
+        let p = cstr(path)?;
+        cvt(unsafe { libc::unlinkat(self.fd.raw(), p.as_ptr(), 0) })?;
+        Ok(())
+    }
+
+    pub fn rename_at(&self, from: &Path, to_dir: &DirHandle, to: &Path) -> io::Result<()> {
+        let from = cstr(from)?;
+        let to = cstr(to)?;
+        cvt(unsafe {
+            libc::renameat(self.fd.raw(), from.as_ptr(), to_dir.fd.raw(), to.as_ptr())
+        })?;
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "linux",
+          target_os = "android",
+          target_os = "macos",
+          target_os = "ios",
+          target_os = "freebsd",
+          target_os = "dragonfly",
+          target_os = "netbsd",
+          target_os = "openbsd",
+          target_os = "solaris")))]
+impl DirHandle {
+    pub fn open(_path: &Path) -> io::Result<DirHandle> {
+        Err(dir_handle_unsupported())
+    }
+
+    pub fn open_at(&self, _path: &Path, _opts: &OpenOptions) -> io::Result<File> {
+        Err(dir_handle_unsupported())
+    }
+
+    pub fn open_dir_at(&self, _path: &Path) -> io::Result<DirHandle> {
+        Err(dir_handle_unsupported())
+    }
+
+    pub fn read_dir_at(&self, _path: &Path) -> io::Result<ReadDir> {
+        Err(dir_handle_unsupported())
+    }
+
+    pub fn metadata_at(&self, _path: &Path, _follow: bool) -> io::Result<FileAttr> {
+        Err(dir_handle_unsupported())
+    }
+
+    pub fn remove_file_at(&self, _path: &Path) -> io::Result<()> {
+        Err(dir_handle_unsupported())
+    }
+
+    pub fn rename_at(&self, _from: &Path, _to_dir: &DirHandle, _to: &Path) -> io::Result<()> {
+        Err(dir_handle_unsupported())
+    }
+}
+
+#[cfg(not(any(target_os = "linux",
+          target_os = "android",
+          target_os = "macos",
+          target_os = "ios",
+          target_os = "freebsd",
+          target_os = "dragonfly",
+          target_os = "netbsd",
+          target_os = "openbsd",
+          target_os = "solaris")))]
+fn dir_handle_unsupported() -> io::Error {
+    io::Error::new(ErrorKind::Other,
+                   "operations relative to a directory are not supported on this platform")
+}
+
+impl DirHandle {
+    pub fn fd(&self) -> &FileDesc { &self.fd }
+
+    pub fn into_fd(self) -> FileDesc { self.fd }
+}
+
+impl fmt::Debug for DirHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Dir")
+            .field("fd", &self.fd.raw())
+            .field("path", &self.path)
+            .finish()
     }
 }
 
@@ -730,6 +1578,22 @@ pub fn rmdir(p: &Path) -> io::Result<()> {
     Ok(())
 }
 
+// Makes changes to the entries of directory `p`, such as a file having been
+// renamed into it, durable.
+pub fn sync_dir(p: &Path) -> io::Result<()> {
+    let p = cstr(p)?;
+    let fd = cvt_r(|| unsafe {
+        open64(p.as_ptr(), libc::O_RDONLY | libc::O_CLOEXEC | libc::O_DIRECTORY, 0)
+    })?;
+    let fd = FileDesc::new(fd);
+    match cvt_r(|| unsafe { libc::fsync(fd.raw()) }) {
+        Ok(_) => Ok(()),
+        // Some filesystems can't sync directories, and don't need to.
+        Err(ref e) if e.raw_os_error() == Some(libc::EINVAL) => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
 pub fn remove_dir_all(path: &Path) -> io::Result<()> {
     let filetype = lstat(path)?.file_type();
     if filetype.is_symlink() {
@@ -739,6 +1603,72 @@ pub fn remove_dir_all(path: &Path) -> io::Result<()> {
     }
 }
 
+// Every directory is opened relative to its parent with `O_NOFOLLOW`, and
+// everything in it is removed relative to it, so that replacing a directory
+// in the tree with a symlink while this runs can't redirect the removal
+// outside of the tree; at worst it fails. Misjudging the type of an entry
+// because it's been replaced is just as safe, as `openat` then fails with
+// `ENOTDIR` or `unlinkat` with `EISDIR`.
+#[cfg(any(target_os = "linux",
+          target_os = "android",
+          target_os = "macos",
+          target_os = "ios",
+          target_os = "freebsd",
+          target_os = "dragonfly",
+          target_os = "netbsd",
+          target_os = "openbsd",
+          target_os = "solaris"))]
+fn remove_dir_all_recursive(path: &Path) -> io::Result<()> {
+    let name = cstr(path)?;
+    remove_dir_all_at(libc::AT_FDCWD, &name, path)
+}
+
+// Removes the directory `name` in the directory `parent`, which is at `path`.
+#[cfg(any(target_os = "linux",
+          target_os = "android",
+          target_os = "macos",
+          target_os = "ios",
+          target_os = "freebsd",
+          target_os = "dragonfly",
+          target_os = "netbsd",
+          target_os = "openbsd",
+          target_os = "solaris"))]
+fn remove_dir_all_at(parent: c_int, name: &CStr, path: &Path) -> io::Result<()> {
+    let fd = cvt_r(|| unsafe {
+        libc::openat(parent, name.as_ptr(),
+                     libc::O_RDONLY | libc::O_DIRECTORY | libc::O_NOFOLLOW | libc::O_CLOEXEC,
+                     0)
+    })?;
+    let fd = FileDesc::new(fd);
+    let ptr = unsafe { libc::fdopendir(fd.raw()) };
+    if ptr.is_null() {
+        return Err(Error::last_os_error())
+    }
+    // The `DIR` owns the descriptor now, and keeps it open until the loop
+    // is done with it.
+    let fd = fd.into_raw();
+    for child in ReadDir::new(Dir(ptr), path.to_path_buf()) {
+        let child = child?;
+        let child_name = CString::new(child.name_bytes())?;
+        if child.file_type()?.is_dir() {
+            remove_dir_all_at(fd, &child_name, &child.path())?;
+        } else {
+            cvt(unsafe { libc::unlinkat(fd, child_name.as_ptr(), 0) })?;
+        }
+    }
+    cvt(unsafe { libc::unlinkat(parent, name.as_ptr(), libc::AT_REMOVEDIR) })?;
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux",
+          target_os = "android",
+          target_os = "macos",
+          target_os = "ios",
+          target_os = "freebsd",
+          target_os = "dragonfly",
+          target_os = "netbsd",
+          target_os = "openbsd",
+          target_os = "solaris")))]
 fn remove_dir_all_recursive(path: &Path) -> io::Result<()> {
     for child in readdir(path)? {
         let child = child?;
@@ -793,20 +1723,106 @@ pub fn link(src: &Path, dst: &Path) -> io::Result<()> {
 
 pub fn stat(p: &Path) -> io::Result<FileAttr> {
     let p = cstr(p)?;
+    #[cfg(target_os = "linux")]
+    {
+        if let Some(ret) = statx_imp::try_statx(libc::AT_FDCWD, p.as_ptr(),
+                                                statx_imp::AT_STATX_SYNC_AS_STAT) {
+            return ret;
+        }
+    }
     let mut stat: stat64 = unsafe { mem::zeroed() };
     cvt(unsafe {
         stat64(p.as_ptr(), &mut stat)
     })?;
-    Ok(FileAttr { stat })
+    Ok(FileAttr::from_stat64(stat))
 }
 
 pub fn lstat(p: &Path) -> io::Result<FileAttr> {
     let p = cstr(p)?;
+    #[cfg(target_os = "linux")]
+    {
+        if let Some(ret) = statx_imp::try_statx(libc::AT_FDCWD, p.as_ptr(),
+                                                libc::AT_SYMLINK_NOFOLLOW) {
+            return ret;
+        }
+    }
     let mut stat: stat64 = unsafe { mem::zeroed() };
     cvt(unsafe {
         lstat64(p.as_ptr(), &mut stat)
     })?;
-    Ok(FileAttr { stat })
+    Ok(FileAttr::from_stat64(stat))
+}
+
+pub fn try_exists(p: &Path) -> io::Result<bool> {
+    match stat(p) {
+        Ok(_) => Ok(true),
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+// Makes `to` a symlink with the same target as the symlink `from`.
+pub fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    symlink(&readlink(from)?, to)
+}
+
+// Identifies the file `p` leads to, following symlinks, by its device and
+// inode numbers.
+pub fn file_id(p: &Path) -> io::Result<(u64, u64)> {
+    let attr = stat(p)?;
+    Ok((attr.stat.st_dev as u64, attr.stat.st_ino as u64))
+}
+
+pub fn copy_ownership(from: &FileAttr, to: &Path, follow: bool) -> io::Result<()> {
+    let to = cstr(to)?;
+    let (uid, gid) = (from.stat.st_uid, from.stat.st_gid);
+    cvt(unsafe {
+        if follow {
+            libc::chown(to.as_ptr(), uid, gid)
+        } else {
+            libc::lchown(to.as_ptr(), uid, gid)
+        }
+    })?;
+    Ok(())
+}
+
+pub fn copy_xattrs(from: &Path, to: &Path) -> io::Result<()> {
+    let names = match list_xattr(XattrTarget::Path(from)) {
+        Ok(names) => names,
+        // The filesystem has no extended attributes to copy.
+        Err(ref e) if e.raw_os_error() == Some(libc::ENOTSUP) => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    for name in names {
+        // An attribute removed since it was listed isn't there to copy.
+        if let Some(value) = get_xattr(XattrTarget::Path(from), &name)? {
+            set_xattr(XattrTarget::Path(to), &name, &value)?;
+        }
+    }
+    Ok(())
+}
+
+// `utimensat` keeps nanoseconds, but isn't available everywhere, unlike
+// `utimes`, which only keeps microseconds.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn set_times(p: &Path, accessed: SystemTime, modified: SystemTime) -> io::Result<()> {
+    let p = cstr(p)?;
+    let times = [libc::timespec::from(accessed), libc::timespec::from(modified)];
+    cvt(unsafe { libc::utimensat(libc::AT_FDCWD, p.as_ptr(), times.as_ptr(), 0) })?;
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+pub fn set_times(p: &Path, accessed: SystemTime, modified: SystemTime) -> io::Result<()> {
+    fn timeval(t: SystemTime) -> libc::timeval {
+        let t = libc::timespec::from(t);
+        libc::timeval { tv_sec: t.tv_sec, tv_usec: (t.tv_nsec / 1000) as _ }
+    }
+
+    let p = cstr(p)?;
+    let times = [timeval(accessed), timeval(modified)];
+    cvt(unsafe { libc::utimes(p.as_ptr(), times.as_ptr()) })?;
+    Ok(())
 }
 
 pub fn canonicalize(p: &Path) -> io::Result<PathBuf> {
@@ -823,8 +1839,15 @@ pub fn canonicalize(p: &Path) -> io::Result<PathBuf> {
     Ok(PathBuf::from(OsString::from_vec(buf)))
 }
 
-#[cfg(not(any(target_os = "linux", target_os = "android")))]
 pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
+    copy_with_progress(from, to, &mut Progress::new(&mut |_, _| Ok(()), u64::max_value()))
+}
+
+#[cfg(not(any(target_os = "linux",
+              target_os = "android",
+              target_os = "macos",
+              target_os = "ios")))]
+pub fn copy_with_progress(from: &Path, to: &Path, progress: &mut Progress) -> io::Result<u64> {
     use fs::File;
     if !from.is_file() {
         return Err(Error::new(ErrorKind::InvalidInput,
@@ -833,15 +1856,19 @@ pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
 
     let mut reader = File::open(from)?;
     let mut writer = File::create(to)?;
-    let perm = reader.metadata()?.permissions();
+    let (perm, len) = {
+        let metadata = reader.metadata()?;
+        (metadata.permissions(), metadata.len())
+    };
 
-    let ret = io::copy(&mut reader, &mut writer)?;
+    let ret = copy_chunks(&mut reader, &mut writer, 0, len, progress)?;
     writer.set_permissions(perm)?;
+    progress.finish(ret, len)?;
     Ok(ret)
 }
 
 #[cfg(any(target_os = "linux", target_os = "android"))]
-pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
+pub fn copy_with_progress(from: &Path, to: &Path, progress: &mut Progress) -> io::Result<u64> {
     use cmp;
     use fs::File;
     use sync::atomic::{AtomicBool, Ordering};
@@ -850,6 +1877,21 @@ pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
     // We store the availability in a global to avoid unnecessary syscalls
     static HAS_COPY_FILE_RANGE: AtomicBool = AtomicBool::new(true);
 
+    // `_IOW(0x94, 9, int)`, which makes a file share the data of another
+    // on filesystems which support it, such as btrfs and XFS.
+    #[cfg(any(target_arch = "mips",
+              target_arch = "mips64",
+              target_arch = "powerpc",
+              target_arch = "powerpc64",
+              target_arch = "sparc64"))]
+    const FICLONE: u32 = 0x80049409;
+    #[cfg(not(any(target_arch = "mips",
+                  target_arch = "mips64",
+                  target_arch = "powerpc",
+                  target_arch = "powerpc64",
+                  target_arch = "sparc64")))]
+    const FICLONE: u32 = 0x40049409;
+
     unsafe fn copy_file_range(
         fd_in: libc::c_int,
         off_in: *mut libc::loff_t,
@@ -876,60 +1918,470 @@ pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
 
     let mut reader = File::open(from)?;
     let mut writer = File::create(to)?;
-    let (perm, len) = {
+    let (perm, len, blocks) = {
         let metadata = reader.metadata()?;
-        (metadata.permissions(), metadata.size())
+        (metadata.permissions(), metadata.size(), metadata.blocks())
     };
 
+    // A reflink copies the whole file without copying any data. Any error
+    // just means it isn't possible here, and nothing has been changed.
+    if unsafe { libc::ioctl(writer.as_raw_fd(), FICLONE as _, reader.as_raw_fd()) } == 0 {
+        writer.set_permissions(perm)?;
+        progress.finish(len, len)?;
+        return Ok(len)
+    }
+
+    // A file which takes up less space than its length has holes, which
+    // the copy should have too rather than being filled in with zeros.
+    if blocks.saturating_mul(512) < len {
+        if let Some(written) = copy_sparse(&mut reader, &mut writer, len, progress)? {
+            writer.set_permissions(perm)?;
+            progress.finish(written, len)?;
+            return Ok(written)
+        }
+    }
+
+    // Each of the following steps carries on from where the last one
+    // stopped, as they all move the files' offsets.
     let has_copy_file_range = HAS_COPY_FILE_RANGE.load(Ordering::Relaxed);
+    let chunk_size = cmp::min(progress.chunk_size(), usize::max_value() as u64);
     let mut written = 0u64;
-    while written < len {
-        let copy_result = if has_copy_file_range {
-            let bytes_to_copy = cmp::min(len - written, usize::max_value() as u64) as usize;
-            let copy_result = unsafe {
-                // We actually don't have to adjust the offsets,
-                // because copy_file_range adjusts the file offset automatically
-                cvt(copy_file_range(reader.as_raw_fd(),
-                                    ptr::null_mut(),
-                                    writer.as_raw_fd(),
-                                    ptr::null_mut(),
-                                    bytes_to_copy,
-                                    0)
-                    )
-            };
-            if let Err(ref copy_err) = copy_result {
-                match copy_err.raw_os_error() {
+    while has_copy_file_range && written < len {
+        let bytes_to_copy = cmp::min(len - written, chunk_size) as usize;
+        let copy_result = unsafe {
+            cvt(copy_file_range(reader.as_raw_fd(),
+                                ptr::null_mut(),
+                                writer.as_raw_fd(),
+                                ptr::null_mut(),
+                                bytes_to_copy,
+                                0)
+                )
+        };
+        match copy_result {
+            // The file has shrunk since we looked at it.
+            Ok(0) => break,
+            Ok(ret) => {
+                written += ret as u64;
+                progress.update(written, len)?;
+            }
+            Err(err) => {
+                match err.raw_os_error() {
                     Some(libc::ENOSYS) | Some(libc::EPERM) => {
+                        // - Kernel version is < 4.5 (ENOSYS)
+                        // - copy_file_range is disallowed, for example by seccomp (EPERM)
                         HAS_COPY_FILE_RANGE.store(false, Ordering::Relaxed);
+                        break
                     }
-                    _ => {}
+                    // - Files are mounted on different fs (EXDEV)
+                    // - The filesystem doesn't support it (EINVAL, EOPNOTSUPP)
+                    Some(libc::EXDEV) | Some(libc::EINVAL) | Some(libc::EOPNOTSUPP) => break,
+                    _ => return Err(err),
                 }
             }
-            copy_result
-        } else {
-            Err(io::Error::from_raw_os_error(libc::ENOSYS))
-        };
-        match copy_result {
-            Ok(ret) => written += ret as u64,
+        }
+    }
+
+    // Files such as those in /proc claim to be empty, so the rest is copied
+    // until the end is read, which is usually straight away. `sendfile`
+    // works across filesystems, but not for every kind of file, and not
+    // before Linux 2.6.33.
+    let chunk_size = cmp::min(chunk_size, 0x7fff_f000) as usize;
+    loop {
+        let ret = cvt_r(|| unsafe {
+            libc::sendfile(writer.as_raw_fd(), reader.as_raw_fd(), ptr::null_mut(), chunk_size)
+        });
+        match ret {
+            Ok(0) => {
+                writer.set_permissions(perm)?;
+                progress.finish(written, len)?;
+                return Ok(written)
+            }
+            Ok(ret) => {
+                written += ret as u64;
+                progress.update(written, len)?;
+            }
             Err(err) => {
                 match err.raw_os_error() {
-                    Some(os_err) if os_err == libc::ENOSYS
-                                 || os_err == libc::EXDEV
-                                 || os_err == libc::EPERM => {
-                        // Try fallback io::copy if either:
-                        // - Kernel version is < 4.5 (ENOSYS)
-                        // - Files are mounted on different fs (EXDEV)
-                        // - copy_file_range is disallowed, for example by seccomp (EPERM)
-                        assert_eq!(written, 0);
-                        let ret = io::copy(&mut reader, &mut writer)?;
-                        writer.set_permissions(perm)?;
-                        return Ok(ret)
-                    },
+                    Some(libc::EINVAL) | Some(libc::ENOSYS) | Some(libc::EPERM) => break,
                     _ => return Err(err),
                 }
             }
         }
     }
+
+    let written = copy_chunks(&mut reader, &mut writer, written, len, progress)?;
     writer.set_permissions(perm)?;
+    progress.finish(written, len)?;
     Ok(written)
 }
+
+// Copies only the data of `reader`, seeking past its holes in both files
+// and leaving holes in `writer` there. Holes count as copied. Returns
+// `None`, without having copied anything, if the kernel can't find holes.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn copy_sparse(reader: &mut ::fs::File, writer: &mut ::fs::File, len: u64,
+               progress: &mut Progress) -> io::Result<Option<u64>> {
+    use cmp;
+    use io::{Read, Seek};
+
+    let mut end = len;
+    let mut pos = 0;
+    while pos < end {
+        let data = match reader.as_inner().seek_data(pos) {
+            Ok(Some(data)) => cmp::min(data, end),
+            Ok(None) => break,
+            // Linux before 3.1 doesn't know `SEEK_DATA`.
+            Err(ref e) if pos == 0 && e.raw_os_error() == Some(libc::EINVAL) => {
+                return Ok(None)
+            }
+            Err(e) => return Err(e),
+        };
+        let hole = cmp::min(reader.as_inner().seek_hole(data)?.unwrap_or(end), end);
+        reader.seek(SeekFrom::Start(data))?;
+        writer.seek(SeekFrom::Start(data))?;
+        pos = copy_chunks(&mut reader.by_ref().take(hole - data), &mut *writer, data, end,
+                          progress)?;
+        // The file has shrunk since we looked at it.
+        if pos < hole {
+            end = pos;
+        }
+    }
+    writer.set_len(end)?;
+    Ok(Some(end))
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub fn copy_with_progress(from: &Path, to: &Path, progress: &mut Progress) -> io::Result<u64> {
+    use fs::File;
+    use panic::{self, AssertUnwindSafe};
+    use thread::Result as ThreadResult;
+
+    const COPYFILE_DATA: u32 = 1 << 3;
+    const COPYFILE_STATE_STATUS_CB: u32 = 6;
+    const COPYFILE_STATE_STATUS_CTX: u32 = 7;
+    const COPYFILE_STATE_COPIED: u32 = 8;
+    const COPYFILE_COPY_DATA: c_int = 4;
+    const COPYFILE_PROGRESS: c_int = 4;
+    const COPYFILE_CONTINUE: c_int = 0;
+    const COPYFILE_QUIT: c_int = 2;
+
+    #[allow(non_camel_case_types)]
+    type copyfile_state_t = *mut libc::c_void;
+    #[allow(non_camel_case_types)]
+    type copyfile_callback_t = extern "C" fn(c_int, c_int, copyfile_state_t,
+                                             *const libc::c_char, *const libc::c_char,
+                                             *mut libc::c_void) -> c_int;
+
+    extern {
+        fn fcopyfile(from: c_int, to: c_int, state: copyfile_state_t, flags: u32) -> c_int;
+        fn copyfile_state_alloc() -> copyfile_state_t;
+        fn copyfile_state_free(state: copyfile_state_t) -> c_int;
+        fn copyfile_state_get(state: copyfile_state_t, flag: u32, dst: *mut libc::c_void)
+                              -> c_int;
+        fn copyfile_state_set(state: copyfile_state_t, flag: u32, src: *const libc::c_void)
+                              -> c_int;
+    }
+
+    struct FreeOnDrop(copyfile_state_t);
+    impl Drop for FreeOnDrop {
+        fn drop(&mut self) {
+            unsafe { copyfile_state_free(self.0); }
+        }
+    }
+
+    struct Context<'a, 'b: 'a> {
+        progress: &'a mut Progress<'b>,
+        total: u64,
+        result: ThreadResult<io::Result<()>>,
+    }
+
+    fn copied(state: copyfile_state_t) -> io::Result<u64> {
+        let mut copied: libc::off_t = 0;
+        cvt(unsafe {
+            copyfile_state_get(state,
+                               COPYFILE_STATE_COPIED,
+                               &mut copied as *mut libc::off_t as *mut libc::c_void)
+        })?;
+        Ok(copied as u64)
+    }
+
+    // Stops the copy on the first error or panic, which `fcopyfile` then
+    // fails with `ECANCELED`, for them to be passed on.
+    extern "C" fn status(what: c_int,
+                         stage: c_int,
+                         state: copyfile_state_t,
+                         _src: *const libc::c_char,
+                         _dst: *const libc::c_char,
+                         ctx: *mut libc::c_void) -> c_int {
+        if what != COPYFILE_COPY_DATA || stage != COPYFILE_PROGRESS {
+            return COPYFILE_CONTINUE
+        }
+        let ctx = unsafe { &mut *(ctx as *mut Context) };
+        let total = ctx.total;
+        let progress = &mut *ctx.progress;
+        ctx.result = panic::catch_unwind(AssertUnwindSafe(|| {
+            progress.update(copied(state)?, total)
+        }));
+        match ctx.result {
+            Ok(Ok(())) => COPYFILE_CONTINUE,
+            _ => COPYFILE_QUIT,
+        }
+    }
+
+    if !from.is_file() {
+        return Err(Error::new(ErrorKind::InvalidInput,
+                              "the source path is not an existing regular file"))
+    }
+
+    let reader = File::open(from)?;
+    let writer = File::create(to)?;
+    let (perm, len) = {
+        let metadata = reader.metadata()?;
+        (metadata.permissions(), metadata.len())
+    };
+
+    // `fcopyfile` copies in the kernel, and clones the data on APFS. Only
+    // the data is copied, so that the permissions are set as elsewhere. It
+    // picks the size of each chunk itself, so `chunk_size` is only used to
+    // decide how often to report progress.
+    let state = unsafe {
+        let state = copyfile_state_alloc();
+        if state.is_null() {
+            return Err(io::Error::last_os_error())
+        }
+        FreeOnDrop(state)
+    };
+    let mut ctx = Context { progress, total: len, result: Ok(Ok(())) };
+    let ret = unsafe {
+        let callback: copyfile_callback_t = status;
+        cvt(copyfile_state_set(state.0,
+                               COPYFILE_STATE_STATUS_CB,
+                               callback as *const libc::c_void))?;
+        cvt(copyfile_state_set(state.0,
+                               COPYFILE_STATE_STATUS_CTX,
+                               &mut ctx as *mut Context as *const libc::c_void))?;
+        cvt(fcopyfile(reader.as_raw_fd(), writer.as_raw_fd(), state.0, COPYFILE_DATA))
+    };
+    match ctx.result {
+        Ok(Ok(())) => { ret?; }
+        Ok(Err(e)) => return Err(e),
+        Err(panic) => panic::resume_unwind(panic),
+    }
+    let copied = copied(state.0)?;
+
+    writer.set_permissions(perm)?;
+    ctx.progress.finish(copied, len)?;
+    Ok(copied)
+}
+
+/// What the extended attribute functions act on: a path, whose symlinks
+/// are followed, or an open file.
+pub enum XattrTarget<'a> {
+    Path(&'a Path),
+    File(&'a File),
+}
+
+// The Linux and Darwin functions, which only differ in Darwin's extra
+// `position` and `options` arguments, and in what a missing attribute's
+// error is called.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod xattr_imp {
+    use libc::{self, c_char, c_int, c_void, size_t, ssize_t};
+
+    pub const ENOATTR: c_int = libc::ENODATA;
+
+    pub unsafe fn get(path: *const c_char, name: *const c_char, value: *mut c_void,
+                      size: size_t) -> ssize_t {
+        libc::getxattr(path, name, value, size)
+    }
+    pub unsafe fn fget(fd: c_int, name: *const c_char, value: *mut c_void,
+                       size: size_t) -> ssize_t {
+        libc::fgetxattr(fd, name, value, size)
+    }
+    pub unsafe fn set(path: *const c_char, name: *const c_char, value: *const c_void,
+                      size: size_t) -> c_int {
+        libc::setxattr(path, name, value, size, 0)
+    }
+    pub unsafe fn fset(fd: c_int, name: *const c_char, value: *const c_void,
+                       size: size_t) -> c_int {
+        libc::fsetxattr(fd, name, value, size, 0)
+    }
+    pub unsafe fn list(path: *const c_char, list: *mut c_void, size: size_t) -> ssize_t {
+        libc::listxattr(path, list as *mut c_char, size)
+    }
+    pub unsafe fn flist(fd: c_int, list: *mut c_void, size: size_t) -> ssize_t {
+        libc::flistxattr(fd, list as *mut c_char, size)
+    }
+    pub unsafe fn remove(path: *const c_char, name: *const c_char) -> c_int {
+        libc::removexattr(path, name)
+    }
+    pub unsafe fn fremove(fd: c_int, name: *const c_char) -> c_int {
+        libc::fremovexattr(fd, name)
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+mod xattr_imp {
+    use libc::{self, c_char, c_int, c_void, size_t, ssize_t};
+
+    pub const ENOATTR: c_int = libc::ENOATTR;
+
+    pub unsafe fn get(path: *const c_char, name: *const c_char, value: *mut c_void,
+                      size: size_t) -> ssize_t {
+        libc::getxattr(path, name, value, size, 0, 0)
+    }
+    pub unsafe fn fget(fd: c_int, name: *const c_char, value: *mut c_void,
+                       size: size_t) -> ssize_t {
+        libc::fgetxattr(fd, name, value, size, 0, 0)
+    }
+    pub unsafe fn set(path: *const c_char, name: *const c_char, value: *const c_void,
+                      size: size_t) -> c_int {
+        libc::setxattr(path, name, value, size, 0, 0)
+    }
+    pub unsafe fn fset(fd: c_int, name: *const c_char, value: *const c_void,
+                       size: size_t) -> c_int {
+        libc::fsetxattr(fd, name, value, size, 0, 0)
+    }
+    pub unsafe fn list(path: *const c_char, list: *mut c_void, size: size_t) -> ssize_t {
+        libc::listxattr(path, list as *mut c_char, size, 0)
+    }
+    pub unsafe fn flist(fd: c_int, list: *mut c_void, size: size_t) -> ssize_t {
+        libc::flistxattr(fd, list as *mut c_char, size, 0)
+    }
+    pub unsafe fn remove(path: *const c_char, name: *const c_char) -> c_int {
+        libc::removexattr(path, name, 0)
+    }
+    pub unsafe fn fremove(fd: c_int, name: *const c_char) -> c_int {
+        libc::fremovexattr(fd, name, 0)
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "macos", target_os = "ios"))]
+mod xattr {
+    use ffi::{CString, OsStr, OsString};
+    use io;
+    use libc::{c_void, size_t, ssize_t, ERANGE};
+    use os::unix::prelude::*;
+    use sys::cvt;
+    use super::{cstr, xattr_imp, XattrTarget};
+
+    // Calls one of the functions which fill in a buffer with a value of
+    // unknown size: first to ask for the size, and then with a buffer that
+    // big, starting again if the value has grown in between.
+    fn read<F>(mut f: F) -> io::Result<Vec<u8>>
+        where F: FnMut(*mut c_void, size_t) -> ssize_t
+    {
+        let mut buf = Vec::new();
+        loop {
+            let size = cvt(f(buf.as_mut_ptr() as *mut c_void, 0))? as usize;
+            buf.reserve(size);
+            match cvt(f(buf.as_mut_ptr() as *mut c_void, buf.capacity())) {
+                Ok(n) => {
+                    unsafe { buf.set_len(n as usize) };
+                    return Ok(buf)
+                }
+                Err(ref e) if e.raw_os_error() == Some(ERANGE) => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    pub fn get(target: XattrTarget, name: &OsStr) -> io::Result<Option<Vec<u8>>> {
+        let name = CString::new(name.as_bytes())?;
+        let value = match target {
+            XattrTarget::Path(path) => {
+                let path = cstr(path)?;
+                read(|buf, size| unsafe {
+                    xattr_imp::get(path.as_ptr(), name.as_ptr(), buf, size)
+                })
+            }
+            XattrTarget::File(file) => {
+                read(|buf, size| unsafe {
+                    xattr_imp::fget(file.fd().raw(), name.as_ptr(), buf, size)
+                })
+            }
+        };
+        match value {
+            Ok(value) => Ok(Some(value)),
+            Err(ref e) if e.raw_os_error() == Some(xattr_imp::ENOATTR) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn set(target: XattrTarget, name: &OsStr, value: &[u8]) -> io::Result<()> {
+        let name = CString::new(name.as_bytes())?;
+        let ptr = value.as_ptr() as *const c_void;
+        cvt(match target {
+            XattrTarget::Path(path) => {
+                let path = cstr(path)?;
+                unsafe { xattr_imp::set(path.as_ptr(), name.as_ptr(), ptr, value.len()) }
+            }
+            XattrTarget::File(file) => unsafe {
+                xattr_imp::fset(file.fd().raw(), name.as_ptr(), ptr, value.len())
+            },
+        })?;
+        Ok(())
+    }
+
+    pub fn list(target: XattrTarget) -> io::Result<Vec<OsString>> {
+        let names = match target {
+            XattrTarget::Path(path) => {
+                let path = cstr(path)?;
+                read(|buf, size| unsafe { xattr_imp::list(path.as_ptr(), buf, size) })?
+            }
+            XattrTarget::File(file) => {
+                read(|buf, size| unsafe { xattr_imp::flist(file.fd().raw(), buf, size) })?
+            }
+        };
+        // Each name is followed by a nul.
+        Ok(names.split(|&b| b == 0)
+                .filter(|name| !name.is_empty())
+                .map(|name| OsString::from_vec(name.to_vec()))
+                .collect())
+    }
+
+    pub fn remove(target: XattrTarget, name: &OsStr) -> io::Result<()> {
+        let name = CString::new(name.as_bytes())?;
+        cvt(match target {
+            XattrTarget::Path(path) => {
+                let path = cstr(path)?;
+                unsafe { xattr_imp::remove(path.as_ptr(), name.as_ptr()) }
+            }
+            XattrTarget::File(file) => unsafe {
+                xattr_imp::fremove(file.fd().raw(), name.as_ptr())
+            },
+        })?;
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "linux",
+              target_os = "android",
+              target_os = "macos",
+              target_os = "ios")))]
+mod xattr {
+    use ffi::{OsStr, OsString};
+    use io;
+    use super::XattrTarget;
+
+    fn unsupported<T>() -> io::Result<T> {
+        Err(io::Error::new(io::ErrorKind::Other,
+                           "extended attributes are not supported on this platform"))
+    }
+
+    pub fn get(_target: XattrTarget, _name: &OsStr) -> io::Result<Option<Vec<u8>>> {
+        unsupported()
+    }
+    pub fn set(_target: XattrTarget, _name: &OsStr, _value: &[u8]) -> io::Result<()> {
+        unsupported()
+    }
+    pub fn list(_target: XattrTarget) -> io::Result<Vec<OsString>> {
+        unsupported()
+    }
+    pub fn remove(_target: XattrTarget, _name: &OsStr) -> io::Result<()> {
+        unsupported()
+    }
+}
+
+pub use self::xattr::{get as get_xattr, set as set_xattr};
+pub use self::xattr::{list as list_xattr, remove as remove_xattr};
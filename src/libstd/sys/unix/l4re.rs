@@ -7,7 +7,7 @@ pub mod net {
     use fmt;
     use io;
     use libc;
-    use net::{SocketAddr, Shutdown, Ipv4Addr, Ipv6Addr};
+    use net::{SocketAddr, Shutdown, Ipv4Addr, Ipv6Addr, NameInfoFlag};
     use sys_common::{AsInner, FromInner, IntoInner};
     use sys::fd::FileDesc;
     use time::Duration;
@@ -446,5 +446,10 @@ pub mod net {
             unimpl!();
         }
     }
+
+    pub fn lookup_name_info(_addr: &SocketAddr, _flags: &[NameInfoFlag])
+                            -> io::Result<(String, String)> {
+        unimpl!();
+    }
 }
 
@@ -11,6 +11,8 @@ use libc;
 #[cfg(all(not(rustdoc), target_os = "freebsd"))]   pub use os::freebsd as platform;
 #[cfg(all(not(rustdoc), target_os = "haiku"))]     pub use os::haiku as platform;
 #[cfg(all(not(rustdoc), target_os = "ios"))]       pub use os::ios as platform;
+#[cfg(all(not(rustdoc), target_os = "tvos"))]      pub use os::ios as platform;
+#[cfg(all(not(rustdoc), target_os = "watchos"))]   pub use os::ios as platform;
 #[cfg(all(not(rustdoc), target_os = "macos"))]     pub use os::macos as platform;
 #[cfg(all(not(rustdoc), target_os = "netbsd"))]    pub use os::netbsd as platform;
 #[cfg(all(not(rustdoc), target_os = "openbsd"))]   pub use os::openbsd as platform;
@@ -19,6 +21,7 @@ use libc;
 #[cfg(all(not(rustdoc), target_os = "fuchsia"))]   pub use os::fuchsia as platform;
 #[cfg(all(not(rustdoc), target_os = "l4re"))]      pub use os::linux as platform;
 #[cfg(all(not(rustdoc), target_os = "hermit"))]    pub use os::hermit as platform;
+#[cfg(all(not(rustdoc), target_os = "nto"))]       pub use os::nto as platform;
 
 pub use self::rand::hashmap_random_keys;
 pub use libc::strlen;
@@ -33,6 +36,7 @@ pub mod android;
 pub mod backtrace;
 pub mod cmath;
 pub mod condvar;
+pub mod emscripten;
 pub mod env;
 pub mod ext;
 pub mod fast_thread_local;
@@ -72,6 +76,10 @@ pub fn init() {
         reset_sigpipe();
     }
 
+    // Pick up whatever the page put in `ENV` before starting us.
+    #[cfg(target_os = "emscripten")]
+    emscripten::import_env();
+
     #[cfg(not(any(target_os = "emscripten", target_os = "fuchsia")))]
     unsafe fn reset_sigpipe() {
         assert!(signal(libc::SIGPIPE, libc::SIG_IGN) != libc::SIG_ERR);
@@ -33,6 +33,33 @@ use libc::SO_NOSIGPIPE;
 #[cfg(not(target_vendor = "apple"))]
 const SO_NOSIGPIPE: c_int = 0;
 
+// Flags and buffer sizes for getnameinfo. glibc and musl number the flags
+// differently from the BSDs, and not every libc binding defines them.
+pub const NI_MAXHOST: usize = 1025;
+pub const NI_MAXSERV: usize = 32;
+
+#[cfg(any(target_os = "linux", target_os = "emscripten", target_os = "fuchsia"))]
+mod ni {
+    use libc::c_int;
+    pub const NI_NUMERICHOST: c_int = 1;
+    pub const NI_NUMERICSERV: c_int = 2;
+    pub const NI_NOFQDN: c_int = 4;
+    pub const NI_NAMEREQD: c_int = 8;
+    pub const NI_DGRAM: c_int = 16;
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "emscripten", target_os = "fuchsia")))]
+mod ni {
+    use libc::c_int;
+    pub const NI_NOFQDN: c_int = 0x1;
+    pub const NI_NUMERICHOST: c_int = 0x2;
+    pub const NI_NAMEREQD: c_int = 0x4;
+    pub const NI_NUMERICSERV: c_int = 0x8;
+    pub const NI_DGRAM: c_int = 0x10;
+}
+
+pub use self::ni::*;
+
 pub struct Socket(FileDesc);
 
 pub fn init() {}
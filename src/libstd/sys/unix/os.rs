@@ -4,6 +4,7 @@
 
 use os::unix::prelude::*;
 
+use cell::Cell;
 use error::Error as StdError;
 use ffi::{CString, CStr, OsString, OsStr};
 use fmt;
@@ -42,9 +43,12 @@ extern {
     #[cfg_attr(target_os = "solaris", link_name = "___errno")]
     #[cfg_attr(any(target_os = "macos",
                    target_os = "ios",
+                   target_os = "tvos",
+                   target_os = "watchos",
                    target_os = "freebsd"),
                link_name = "__error")]
     #[cfg_attr(target_os = "haiku", link_name = "_errnop")]
+    #[cfg_attr(target_os = "nto", link_name = "__get_errno_ptr")]
     fn errno_location() -> *mut c_int;
 }
 
@@ -57,8 +61,7 @@ pub fn errno() -> i32 {
 }
 
 /// Sets the platform-specific value of errno
-#[cfg(all(not(target_os = "linux"),
-          not(target_os = "dragonfly")))] // needed for readdir and syscall!
+#[cfg(not(target_os = "dragonfly"))]
 pub fn set_errno(e: i32) {
     unsafe {
         *errno_location() = e as c_int
@@ -297,7 +300,7 @@ pub fn current_exe() -> io::Result<PathBuf> {
     }
 }
 
-#[cfg(any(target_os = "macos", target_os = "ios"))]
+#[cfg(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))]
 pub fn current_exe() -> io::Result<PathBuf> {
     extern {
         fn _NSGetExecutablePath(buf: *mut libc::c_char,
@@ -388,6 +391,30 @@ pub fn current_exe() -> io::Result<PathBuf> {
     Err(io::Error::new(ErrorKind::Other, "Not yet implemented!"))
 }
 
+#[cfg(target_os = "nto")]
+pub fn current_exe() -> io::Result<PathBuf> {
+    // There's no reliable procfs to read the link from, but libc remembers
+    // the full path it loaded us from.
+    extern {
+        fn _cmdname(buf: *mut c_char) -> *mut c_char;
+    }
+    let mut buf = vec![0 as c_char; libc::PATH_MAX as usize];
+    unsafe {
+        if _cmdname(buf.as_mut_ptr()).is_null() {
+            return Err(io::Error::last_os_error())
+        }
+        let name = CStr::from_ptr(buf.as_ptr()).to_bytes();
+        Ok(PathBuf::from(OsStr::from_bytes(name)))
+    }
+}
+
+#[cfg(target_os = "espidf")]
+pub fn current_exe() -> io::Result<PathBuf> {
+    use io::ErrorKind;
+    Err(io::Error::new(ErrorKind::Other,
+                       "the application runs from flash, not from a file"))
+}
+
 pub struct Env {
     iter: vec::IntoIter<(OsString, OsString)>,
     _dont_send_or_sync_me: PhantomData<*mut ()>,
@@ -411,11 +438,53 @@ pub unsafe fn environ() -> *mut *const *const c_char {
     &mut environ
 }
 
-pub unsafe fn env_lock() -> MutexGuard<'static> {
-    // We never call `ENV_LOCK.init()`, so it is UB to attempt to
-    // acquire this mutex reentrantly!
-    static ENV_LOCK: Mutex = Mutex::new();
-    ENV_LOCK.lock()
+// We never call `ENV_LOCK.init()`, so it is UB to attempt to
+// acquire this mutex reentrantly!
+static ENV_LOCK: Mutex = Mutex::new();
+
+thread_local! {
+    // Whether this thread holds `ENV_LOCK` through `try_env_lock`, in which
+    // case the environment functions it calls mustn't lock it again.
+    static ENV_LOCK_HELD: Cell<bool> = Cell::new(false)
+}
+
+fn env_lock_held() -> bool {
+    ENV_LOCK_HELD.try_with(|held| held.get()).unwrap_or(false)
+}
+
+pub struct EnvLockGuard {
+    _guard: Option<MutexGuard<'static>>,
+}
+
+pub unsafe fn env_lock() -> EnvLockGuard {
+    if env_lock_held() {
+        EnvLockGuard { _guard: None }
+    } else {
+        EnvLockGuard { _guard: Some(ENV_LOCK.lock()) }
+    }
+}
+
+/// Holds the environment lock for code outside the standard library, which
+/// the environment functions called on the same thread meanwhile skip.
+pub struct EnvLockHandle {
+    _not_send_or_sync: PhantomData<*mut ()>,
+}
+
+pub fn try_env_lock() -> Option<EnvLockHandle> {
+    unsafe {
+        if env_lock_held() || !ENV_LOCK.try_lock() {
+            return None;
+        }
+        let _ = ENV_LOCK_HELD.try_with(|held| held.set(true));
+        Some(EnvLockHandle { _not_send_or_sync: PhantomData })
+    }
+}
+
+impl Drop for EnvLockHandle {
+    fn drop(&mut self) {
+        let _ = ENV_LOCK_HELD.try_with(|held| held.set(false));
+        unsafe { ENV_LOCK.raw_unlock() }
+    }
 }
 
 /// Returns a vector of (variable, value) byte-vector pairs for all the
@@ -453,6 +522,67 @@ pub fn env() -> Env {
     }
 }
 
+/// A copy of the environment of the process, taken under the environment
+/// lock into a single buffer, so that its variables can be borrowed without
+/// allocating for each of them.
+pub struct EnvSnapshot {
+    buf: Vec<u8>,
+    // For each variable, where its key starts, where the `=` after it is,
+    // and where its value ends.
+    vars: Vec<(usize, usize, usize)>,
+}
+
+impl EnvSnapshot {
+    pub fn iter(&self) -> EnvSnapshotIter {
+        EnvSnapshotIter { buf: &self.buf, vars: self.vars.iter() }
+    }
+}
+
+pub struct EnvSnapshotIter<'a> {
+    buf: &'a [u8],
+    vars: slice::Iter<'a, (usize, usize, usize)>,
+}
+
+impl<'a> Iterator for EnvSnapshotIter<'a> {
+    type Item = (&'a OsStr, &'a OsStr);
+    fn next(&mut self) -> Option<(&'a OsStr, &'a OsStr)> {
+        self.vars.next().map(|&(start, eq, end)| {
+            (OsStr::from_bytes(&self.buf[start..eq]), OsStr::from_bytes(&self.buf[eq + 1..end]))
+        })
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) { self.vars.size_hint() }
+}
+
+/// Returns a snapshot of the environment variables of the current process.
+pub fn env_snapshot() -> EnvSnapshot {
+    unsafe {
+        let _guard = env_lock();
+        let mut entries = Vec::new();
+        let mut len = 0;
+        let mut environ = *environ();
+        while environ != ptr::null() && *environ != ptr::null() {
+            let entry = CStr::from_ptr(*environ).to_bytes();
+            // Skip malformed entries, as `env` does.
+            if !entry.is_empty() {
+                if let Some(eq) = memchr::memchr(b'=', &entry[1..]) {
+                    entries.push((entry, eq + 1));
+                    len += entry.len();
+                }
+            }
+            environ = environ.offset(1);
+        }
+
+        let mut buf = Vec::with_capacity(len);
+        let mut vars = Vec::with_capacity(entries.len());
+        for (entry, eq) in entries {
+            let start = buf.len();
+            buf.extend_from_slice(entry);
+            vars.push((start, start + eq, buf.len()));
+        }
+        EnvSnapshot { buf, vars }
+    }
+}
+
 pub fn getenv(k: &OsStr) -> io::Result<Option<OsString>> {
     // environment variables with a nul byte can't be set, so their value is
     // always None as well
@@ -499,28 +629,85 @@ pub fn temp_dir() -> PathBuf {
         if cfg!(target_os = "android") {
             PathBuf::from("/data/local/tmp")
         } else {
-            PathBuf::from("/tmp")
+            default_temp_dir()
         }
     })
 }
 
+// Sandboxed apps can't write to /tmp, the per-user directory is the one
+// that's always allowed (and what TMPDIR normally points to anyway).
+#[cfg(target_os = "macos")]
+fn default_temp_dir() -> PathBuf {
+    darwin_user_dir(_CS_DARWIN_USER_TEMP_DIR).unwrap_or_else(|_| PathBuf::from("/tmp"))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn default_temp_dir() -> PathBuf {
+    PathBuf::from("/tmp")
+}
+
+#[cfg(target_os = "macos")]
+pub const _CS_DARWIN_USER_TEMP_DIR: c_int = 65537;
+#[cfg(target_os = "macos")]
+pub const _CS_DARWIN_USER_CACHE_DIR: c_int = 65538;
+
+/// Reads one of the per-user directories from `confstr`, which creates it
+/// if needed and reports the location inside the app container when
+/// sandboxed.
+#[cfg(target_os = "macos")]
+pub fn darwin_user_dir(name: c_int) -> io::Result<PathBuf> {
+    extern {
+        fn confstr(name: c_int, buf: *mut c_char, len: libc::size_t) -> libc::size_t;
+    }
+
+    unsafe {
+        let len = confstr(name, ptr::null_mut(), 0);
+        if len == 0 {
+            return Err(io::Error::last_os_error())
+        }
+        let mut buf: Vec<u8> = Vec::with_capacity(len);
+        let len = confstr(name, buf.as_mut_ptr() as *mut c_char, buf.capacity());
+        if len == 0 || len > buf.capacity() {
+            return Err(io::Error::last_os_error())
+        }
+        // `len` includes the terminating nul.
+        buf.set_len(len - 1);
+        Ok(PathBuf::from(OsString::from_vec(buf)))
+    }
+}
+
 pub fn home_dir() -> Option<PathBuf> {
-    return ::env::var_os("HOME").or_else(|| unsafe {
-        fallback()
-    }).map(PathBuf::from);
+    ::env::var_os("HOME").or_else(|| unsafe {
+        passwd_home_dir()
+    }).map(PathBuf::from)
+}
 
-    #[cfg(any(target_os = "android",
+pub fn user_home_dir() -> Option<PathBuf> {
+    match ::env::var_os("HOME") {
+        Some(ref home) if !home.is_empty() => Some(PathBuf::from(home)),
+        _ => unsafe { passwd_home_dir() }.map(PathBuf::from),
+    }
+}
+
+#[cfg(any(target_os = "android",
+          target_os = "ios",
+          target_os = "tvos",
+          target_os = "watchos",
+          target_os = "emscripten",
+          target_os = "espidf"))]
+unsafe fn passwd_home_dir() -> Option<OsString> { None }
+#[cfg(not(any(target_os = "android",
               target_os = "ios",
-              target_os = "emscripten"))]
-    unsafe fn fallback() -> Option<OsString> { None }
-    #[cfg(not(any(target_os = "android",
-                  target_os = "ios",
-                  target_os = "emscripten")))]
-    unsafe fn fallback() -> Option<OsString> {
-        let amt = match libc::sysconf(libc::_SC_GETPW_R_SIZE_MAX) {
-            n if n < 0 => 512 as usize,
-            n => n as usize,
-        };
+              target_os = "tvos",
+              target_os = "watchos",
+              target_os = "emscripten",
+              target_os = "espidf")))]
+unsafe fn passwd_home_dir() -> Option<OsString> {
+    let mut amt = match libc::sysconf(libc::_SC_GETPW_R_SIZE_MAX) {
+        n if n < 0 => 512 as usize,
+        n => n as usize,
+    };
+    loop {
         let mut buf = Vec::with_capacity(amt);
         let mut passwd: libc::passwd = mem::zeroed();
         let mut result = ptr::null_mut();
@@ -529,9 +716,11 @@ pub fn home_dir() -> Option<PathBuf> {
             0 if !result.is_null() => {
                 let ptr = passwd.pw_dir as *const _;
                 let bytes = CStr::from_ptr(ptr).to_bytes().to_vec();
-                Some(OsStringExt::from_vec(bytes))
+                return Some(OsStringExt::from_vec(bytes))
             },
-            _ => None,
+            // `_SC_GETPW_R_SIZE_MAX` is only a suggestion.
+            libc::ERANGE if amt < 1 << 20 => amt *= 2,
+            _ => return None,
         }
     }
 }
@@ -63,6 +63,14 @@ impl AnonPipe {
         self.0.write(buf)
     }
 
+    pub fn try_clone(&self) -> io::Result<AnonPipe> {
+        self.0.duplicate().map(AnonPipe)
+    }
+
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.0.set_nonblocking(nonblocking)
+    }
+
     pub fn fd(&self) -> &FileDesc { &self.0 }
     pub fn into_fd(self) -> FileDesc { self.0 }
 }
@@ -117,3 +125,43 @@ pub fn read2(p1: AnonPipe,
         }
     }
 }
+
+/// Reads both pipes until they're closed, passing each chunk read to `f`
+/// along with the index of the pipe it came from. An error from `f` stops
+/// the reading and is returned.
+pub fn read2_with(p1: AnonPipe,
+                 p2: AnonPipe,
+                 f: &mut dyn FnMut(usize, &[u8]) -> io::Result<()>) -> io::Result<()> {
+    let p = [p1.into_fd(), p2.into_fd()];
+    p[0].set_nonblocking(true)?;
+    p[1].set_nonblocking(true)?;
+
+    let mut fds: [libc::pollfd; 2] = unsafe { mem::zeroed() };
+    for (pfd, fd) in fds.iter_mut().zip(p.iter()) {
+        pfd.fd = fd.raw();
+        pfd.events = libc::POLLIN;
+    }
+    let mut buf = [0; 8 * 1024];
+    let mut open = 2;
+    while open > 0 {
+        cvt_r(|| unsafe { libc::poll(fds.as_mut_ptr(), 2, -1) })?;
+        for i in 0..2 {
+            if fds[i].revents == 0 {
+                continue
+            }
+            match p[i].read(&mut buf) {
+                // `poll` skips negative descriptors, so a closed pipe is
+                // left out from now on.
+                Ok(0) => {
+                    fds[i].fd = -1;
+                    open -= 1;
+                }
+                Ok(n) => f(i, &buf[..n])?,
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock ||
+                              e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+    Ok(())
+}
@@ -1,5 +1,8 @@
-pub use self::process_common::{Command, ExitStatus, ExitCode, Stdio, StdioPipes};
+pub use self::process_common::{Command, CommandArgs, CommandEnvs, ExitStatus, ExitCode};
+pub use self::process_common::{EnvKey, Stdio, StdioPipes};
 pub use self::process_inner::Process;
+#[cfg(not(target_os = "fuchsia"))]
+pub use self::process_inner::pidfd;
 
 mod process_common;
 #[cfg(not(target_os = "fuchsia"))]
@@ -10,3 +13,5 @@ mod process_inner;
 mod process_inner;
 #[cfg(target_os = "fuchsia")]
 mod zircon;
+#[cfg(not(target_os = "fuchsia"))]
+mod reaper;
@@ -1,14 +1,21 @@
 use os::unix::prelude::*;
 
+use cmp;
 use ffi::{OsString, OsStr, CString, CStr};
 use fmt;
 use io;
-use libc::{self, c_int, gid_t, uid_t, c_char, EXIT_SUCCESS, EXIT_FAILURE};
+use libc::{self, c_int, gid_t, pid_t, uid_t, c_char, EXIT_SUCCESS, EXIT_FAILURE};
+#[cfg(target_os = "fuchsia")]
+use mem;
+use path::Path;
 use ptr;
+use slice;
 use sys::fd::FileDesc;
 use sys::fs::{File, OpenOptions};
 use sys::pipe::{self, AnonPipe};
-use sys_common::process::{CommandEnv, DefaultEnvKey};
+#[cfg(target_os = "fuchsia")]
+use sys::process::zircon::{Handle, zx_handle_t};
+use sys_common::process::{self, CommandEnv, DefaultEnvKey};
 use collections::BTreeMap;
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -34,6 +41,8 @@ pub struct Command {
     // present, and whenever we remove a key we update the locations of all
     // other keys.
     program: CString,
+    // Replaces `program` as the first element of `argv`, if set.
+    arg0: Option<CString>,
     args: Vec<CString>,
     argv: Argv,
     env: CommandEnv<DefaultEnvKey>,
@@ -41,11 +50,64 @@ pub struct Command {
     cwd: Option<CString>,
     uid: Option<uid_t>,
     gid: Option<gid_t>,
+    groups: Option<Box<[gid_t]>>,
+    pgroup: Option<pid_t>,
+    new_session: bool,
+    pdeathsig: Option<c_int>,
+    // `CLONE_NEW*` flags for the namespaces the child gets of its own, and
+    // the directory it's confined to; both Linux only.
+    unshare: c_int,
+    chroot: Option<CString>,
     saw_nul: bool,
     closures: Vec<Box<dyn FnMut() -> io::Result<()> + Send + Sync>>,
+    create_pidfd: bool,
+    // Descriptors to copy into the child, as `(ours, theirs)` pairs, and
+    // room for the temporary copies made while remapping them, allocated
+    // up front so that the child doesn't have to.
+    fd_mappings: Vec<(c_int, c_int)>,
+    fd_scratch: Vec<c_int>,
     stdin: Option<Stdio>,
     stdout: Option<Stdio>,
     stderr: Option<Stdio>,
+    merge_stderr: bool,
+
+    // Startup handles and namespace entries passed to `fdio_spawn_etc`. The
+    // handles are owned by us until they're transferred to a child.
+    #[cfg(target_os = "fuchsia")]
+    handles: Vec<(u32, Handle)>,
+    #[cfg(target_os = "fuchsia")]
+    namespace: Vec<(CString, Handle)>,
+}
+
+pub type CommandEnvs<'a> = process::CommandEnvs<'a, DefaultEnvKey>;
+
+/// How environment variable names are compared, and kept in order.
+pub type EnvKey = DefaultEnvKey;
+
+pub struct CommandArgs<'a> {
+    iter: slice::Iter<'a, CString>,
+}
+
+impl<'a> Iterator for CommandArgs<'a> {
+    type Item = &'a OsStr;
+    fn next(&mut self) -> Option<&'a OsStr> {
+        self.iter.next().map(|arg| OsStr::from_bytes(arg.as_bytes()))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for CommandArgs<'a> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<'a> fmt::Debug for CommandArgs<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter.clone()).finish()
+    }
 }
 
 // Create a new type for argv, so that we can make it `Send`
@@ -90,16 +152,31 @@ impl Command {
         Command {
             argv: Argv(vec![program.as_ptr(), ptr::null()]),
             program,
+            arg0: None,
             args: Vec::new(),
             env: Default::default(),
             cwd: None,
             uid: None,
             gid: None,
+            groups: None,
+            pgroup: None,
+            new_session: false,
+            pdeathsig: None,
+            unshare: 0,
+            chroot: None,
             saw_nul,
             closures: Vec::new(),
+            create_pidfd: false,
+            fd_mappings: Vec::new(),
+            fd_scratch: Vec::new(),
             stdin: None,
             stdout: None,
             stderr: None,
+            merge_stderr: false,
+            #[cfg(target_os = "fuchsia")]
+            handles: Vec::new(),
+            #[cfg(target_os = "fuchsia")]
+            namespace: Vec::new(),
         }
     }
 
@@ -115,6 +192,12 @@ impl Command {
         self.args.push(arg);
     }
 
+    pub fn set_arg_0(&mut self, arg: &OsStr) {
+        let arg = os2c(arg, &mut self.saw_nul);
+        self.argv.0[0] = arg.as_ptr();
+        self.arg0 = Some(arg);
+    }
+
     pub fn cwd(&mut self, dir: &OsStr) {
         self.cwd = Some(os2c(dir, &mut self.saw_nul));
     }
@@ -124,10 +207,50 @@ impl Command {
     pub fn gid(&mut self, id: gid_t) {
         self.gid = Some(id);
     }
+    pub fn groups(&mut self, groups: &[gid_t]) {
+        self.groups = Some(Box::from(groups));
+    }
+    pub fn pgroup(&mut self, pgroup: pid_t) {
+        self.pgroup = Some(pgroup);
+    }
+    pub fn new_session(&mut self, val: bool) {
+        self.new_session = val;
+    }
+    pub fn parent_death_signal(&mut self, signal: c_int) {
+        self.pdeathsig = Some(signal);
+    }
+    pub fn create_pidfd(&mut self, val: bool) {
+        self.create_pidfd = val;
+    }
+    pub fn unshare(&mut self, flags: c_int) {
+        self.unshare = flags;
+    }
+    pub fn chroot(&mut self, dir: &OsStr) {
+        self.chroot = Some(os2c(dir, &mut self.saw_nul));
+    }
+    pub fn fd_mappings(&mut self, mappings: &[(c_int, c_int)]) {
+        self.fd_mappings = mappings.to_vec();
+        self.fd_scratch = vec![-1; mappings.len()];
+    }
 
     pub fn saw_nul(&self) -> bool {
         self.saw_nul
     }
+    pub fn get_program(&self) -> &OsStr {
+        OsStr::from_bytes(self.program.as_bytes())
+    }
+    pub fn get_program_cstr(&self) -> &CStr {
+        &self.program
+    }
+    pub fn get_args(&self) -> CommandArgs {
+        CommandArgs { iter: self.args.iter() }
+    }
+    pub fn get_envs(&self) -> CommandEnvs {
+        self.env.iter()
+    }
+    pub fn get_current_dir(&self) -> Option<&Path> {
+        self.cwd.as_ref().map(|cwd| Path::new(OsStr::from_bytes(cwd.as_bytes())))
+    }
     pub fn get_argv(&self) -> &Vec<*const c_char> {
         &self.argv.0
     }
@@ -144,6 +267,64 @@ impl Command {
     pub fn get_gid(&self) -> Option<gid_t> {
         self.gid
     }
+    #[allow(dead_code)]
+    pub fn get_groups(&self) -> Option<&[gid_t]> {
+        self.groups.as_ref().map(|groups| &**groups)
+    }
+    #[allow(dead_code)]
+    pub fn get_pgroup(&self) -> Option<pid_t> {
+        self.pgroup
+    }
+    #[allow(dead_code)]
+    pub fn get_new_session(&self) -> bool {
+        self.new_session
+    }
+    #[allow(dead_code)]
+    pub fn get_parent_death_signal(&self) -> Option<c_int> {
+        self.pdeathsig
+    }
+    #[allow(dead_code)]
+    pub fn get_unshare(&self) -> c_int {
+        self.unshare
+    }
+    #[allow(dead_code)]
+    pub fn get_chroot(&self) -> Option<&CStr> {
+        self.chroot.as_ref().map(|dir| &**dir)
+    }
+    #[allow(dead_code)]
+    pub fn get_create_pidfd(&self) -> bool {
+        self.create_pidfd
+    }
+    #[allow(dead_code)]
+    pub fn get_fd_mappings(&mut self) -> (&[(c_int, c_int)], &mut [c_int]) {
+        (&self.fd_mappings[..], &mut self.fd_scratch[..])
+    }
+
+    // The lowest descriptor above stdio and every number named by a mapping,
+    // so anything at or above it is safe from being overwritten by them.
+    #[allow(dead_code)]
+    pub fn fd_mappings_floor(&self) -> c_int {
+        self.fd_mappings.iter().fold(libc::STDERR_FILENO, |m, &(fd, target)| {
+            cmp::max(m, cmp::max(fd, target))
+        }) + 1
+    }
+
+    // Every descriptor has to be valid and each one in the child can only
+    // be given once.
+    #[allow(dead_code)]
+    pub fn check_fd_mappings(&self) -> io::Result<()> {
+        for (i, &(fd, target)) in self.fd_mappings.iter().enumerate() {
+            if fd < 0 || target < 0 {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                          "negative file descriptor in fd mappings"));
+            }
+            if self.fd_mappings[..i].iter().any(|&(_, t)| t == target) {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                          "file descriptor mapped more than once in the child"));
+            }
+        }
+        Ok(())
+    }
 
     pub fn get_closures(&mut self) -> &mut Vec<Box<dyn FnMut() -> io::Result<()> + Send + Sync>> {
         &mut self.closures
@@ -166,6 +347,10 @@ impl Command {
         self.stderr = Some(stderr);
     }
 
+    pub fn merge_stderr(&mut self, merge: bool) {
+        self.merge_stderr = merge;
+    }
+
     pub fn env_mut(&mut self) -> &mut CommandEnv<DefaultEnvKey> {
         &mut self.env
     }
@@ -188,7 +373,11 @@ impl Command {
         let stderr = self.stderr.as_ref().unwrap_or(&default);
         let (their_stdin, our_stdin) = stdin.to_child_stdio(true)?;
         let (their_stdout, our_stdout) = stdout.to_child_stdio(false)?;
-        let (their_stderr, our_stderr) = stderr.to_child_stdio(false)?;
+        let (their_stderr, our_stderr) = if self.merge_stderr {
+            (their_stdout.merged()?, None)
+        } else {
+            stderr.to_child_stdio(false)?
+        };
         let ours = StdioPipes {
             stdin: our_stdin,
             stdout: our_stdout,
@@ -203,6 +392,29 @@ impl Command {
     }
 }
 
+#[cfg(target_os = "fuchsia")]
+impl Command {
+    pub unsafe fn add_handle(&mut self, id: u32, handle: zx_handle_t) {
+        self.handles.push((id, Handle::new(handle)));
+    }
+
+    pub unsafe fn add_namespace_entry(&mut self, prefix: &OsStr, handle: zx_handle_t) {
+        let prefix = os2c(prefix, &mut self.saw_nul);
+        self.namespace.push((prefix, Handle::new(handle)));
+    }
+
+    pub fn has_namespace(&self) -> bool {
+        !self.namespace.is_empty()
+    }
+
+    // Handles can only be transferred once, so they go to the first child
+    // spawned from this command.
+    pub fn take_handles(&mut self) -> (Vec<(u32, Handle)>, Vec<(CString, Handle)>) {
+        (mem::replace(&mut self.handles, Vec::new()),
+         mem::replace(&mut self.namespace, Vec::new()))
+    }
+}
+
 fn os2c(s: &OsStr, saw_nul: &mut bool) -> CString {
     CString::new(s.as_bytes()).unwrap_or_else(|_e| {
         *saw_nul = true;
@@ -324,11 +536,25 @@ impl ChildStdio {
             ChildStdio::Owned(ref fd) => Some(fd.raw()),
         }
     }
+
+    // A descriptor for the child's stderr referring to whatever its stdout
+    // does, as with `2>&1`. It's a copy rather than the same number so that
+    // the order the two are set up in doesn't matter.
+    pub fn merged(&self) -> io::Result<ChildStdio> {
+        let fd = FileDesc::new(self.fd().unwrap_or(libc::STDOUT_FILENO));
+        let ret = fd.duplicate();
+        fd.into_raw();
+        Ok(ChildStdio::Owned(ret?))
+    }
 }
 
 impl fmt::Debug for Command {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self.program)?;
+        if let Some(ref arg0) = self.arg0 {
+            write!(f, "[{:?}] {:?}", self.program, arg0)?;
+        } else {
+            write!(f, "{:?}", self.program)?;
+        }
         for arg in &self.args {
             write!(f, " {:?}", arg)?;
         }
@@ -336,6 +562,13 @@ impl fmt::Debug for Command {
     }
 }
 
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cwd = self.get_current_dir().map(Path::as_os_str);
+        process::fmt_shell_command(f, self.get_program(), self.get_args(), &self.env, cwd)
+    }
+}
+
 /// Unix exit statuses
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub struct ExitStatus(c_int);
@@ -362,12 +595,28 @@ impl ExitStatus {
     }
 
     pub fn signal(&self) -> Option<i32> {
-        if !self.exited() {
+        if unsafe { libc::WIFSIGNALED(self.0) } {
             Some(unsafe { libc::WTERMSIG(self.0) })
         } else {
             None
         }
     }
+
+    pub fn core_dumped(&self) -> bool {
+        unsafe { libc::WIFSIGNALED(self.0) && libc::WCOREDUMP(self.0) }
+    }
+
+    pub fn stopped_signal(&self) -> Option<i32> {
+        if unsafe { libc::WIFSTOPPED(self.0) } {
+            Some(unsafe { libc::WSTOPSIG(self.0) })
+        } else {
+            None
+        }
+    }
+
+    pub fn continued(&self) -> bool {
+        unsafe { libc::WIFCONTINUED(self.0) }
+    }
 }
 
 impl From<c_int> for ExitStatus {
@@ -380,9 +629,18 @@ impl fmt::Display for ExitStatus {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         if let Some(code) = self.code() {
             write!(f, "exit code: {}", code)
+        } else if let Some(signal) = self.signal() {
+            if self.core_dumped() {
+                write!(f, "signal: {} (core dumped)", signal)
+            } else {
+                write!(f, "signal: {}", signal)
+            }
+        } else if let Some(signal) = self.stopped_signal() {
+            write!(f, "stopped (not terminated) by signal: {}", signal)
+        } else if self.continued() {
+            write!(f, "continued")
         } else {
-            let signal = self.signal().unwrap();
-            write!(f, "signal: {}", signal)
+            write!(f, "unrecognised wait status: {:#x}", self.0)
         }
     }
 }
@@ -400,7 +658,9 @@ impl ExitCode {
     }
 }
 
-#[cfg(all(test, not(target_os = "emscripten")))]
+#[cfg(all(test, not(any(target_os = "emscripten",
+                        target_os = "vxworks",
+                        target_os = "espidf"))))]
 mod tests {
     use super::*;
 
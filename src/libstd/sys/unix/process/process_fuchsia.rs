@@ -1,10 +1,12 @@
 use io;
-use libc::{self, size_t};
+use libc::{self, c_int, size_t};
 use mem;
 use ptr;
 
 use sys::process::zircon::{Handle, zx_handle_t};
 use sys::process::process_common::*;
+use sys_common::process;
+use time::Instant;
 
 ////////////////////////////////////////////////////////////////////////////////
 // Command
@@ -19,6 +21,15 @@ impl Command {
             return Err(io::Error::new(io::ErrorKind::InvalidInput,
                                       "nul byte found in provided data"));
         }
+        self.check_fd_mappings()?;
+        if self.get_pgroup().is_some() || self.get_new_session() {
+            return Err(io::Error::new(io::ErrorKind::Other,
+                                      "Fuchsia has no process groups or sessions"));
+        }
+        if self.get_parent_death_signal().is_some() {
+            return Err(io::Error::new(io::ErrorKind::Other,
+                                      "Fuchsia processes can't be signalled"));
+        }
 
         let (ours, theirs) = self.setup_io(default, needs_stdin)?;
 
@@ -52,40 +63,58 @@ impl Command {
         };
 
         let transfer_or_clone = |opt_fd, target_fd| if let Some(local_fd) = opt_fd {
-            fdio_spawn_action_t {
-                action: FDIO_SPAWN_ACTION_TRANSFER_FD,
-                local_fd,
-                target_fd,
-                ..Default::default()
-            }
+            fdio_spawn_action_t::fd(FDIO_SPAWN_ACTION_TRANSFER_FD, local_fd, target_fd)
         } else {
-            fdio_spawn_action_t {
-                action: FDIO_SPAWN_ACTION_CLONE_FD,
-                local_fd: target_fd,
-                target_fd,
-                ..Default::default()
-            }
+            fdio_spawn_action_t::fd(FDIO_SPAWN_ACTION_CLONE_FD, target_fd, target_fd)
         };
 
+        for callback in self.get_closures().iter_mut() {
+            callback()?;
+        }
+
         // Clone stdin, stdout, and stderr
-        let action1 = transfer_or_clone(stdio.stdin.fd(), 0);
-        let action2 = transfer_or_clone(stdio.stdout.fd(), 1);
-        let action3 = transfer_or_clone(stdio.stderr.fd(), 2);
-        let actions = [action1, action2, action3];
+        let mut actions = vec![
+            transfer_or_clone(stdio.stdin.fd(), 0),
+            transfer_or_clone(stdio.stdout.fd(), 1),
+            transfer_or_clone(stdio.stderr.fd(), 2),
+        ];
+
+        // fdio_spawn_etc copies the mapped descriptors out of our table, so
+        // unlike after a fork there's nothing to be careful about.
+        for &(fd, target) in self.get_fd_mappings().0 {
+            actions.push(fdio_spawn_action_t::fd(FDIO_SPAWN_ACTION_CLONE_FD, fd, target));
+        }
 
         // We don't want FileDesc::drop to be called on any stdio. fdio_spawn_etc
         // always consumes transferred file descriptors.
         mem::forget(stdio);
 
-        for callback in self.get_closures().iter_mut() {
-            callback()?;
+        // A child given explicit namespace entries only sees those, rather
+        // than a copy of ours with the new entries layered on top.
+        let mut flags = FDIO_SPAWN_CLONE_JOB | FDIO_SPAWN_CLONE_LDSVC;
+        if !self.has_namespace() {
+            flags |= FDIO_SPAWN_CLONE_NAMESPACE;
+        }
+
+        // Startup handles are consumed by fdio_spawn_etc whether or not the
+        // spawn succeeds, so ownership is given up right here.
+        let (handles, namespace) = self.take_handles();
+        for (id, handle) in handles {
+            actions.push(fdio_spawn_action_t::handle(id, handle.into_raw()));
+        }
+        // The prefix strings have to stay alive until the call returns.
+        let mut prefixes = Vec::with_capacity(namespace.len());
+        for (prefix, handle) in namespace {
+            actions.push(fdio_spawn_action_t::ns_entry(prefix.as_ptr(), handle.into_raw()));
+            prefixes.push(prefix);
         }
 
         let mut process_handle: zx_handle_t = 0;
         zx_cvt(fdio_spawn_etc(
             0,
-            FDIO_SPAWN_CLONE_JOB | FDIO_SPAWN_CLONE_LDSVC | FDIO_SPAWN_CLONE_NAMESPACE,
-            self.get_argv()[0], self.get_argv().as_ptr(), envp, 3, actions.as_ptr(),
+            flags,
+            self.get_program_cstr().as_ptr(), self.get_argv().as_ptr(), envp,
+            actions.len() as u64, actions.as_ptr(),
             &mut process_handle,
             ptr::null_mut(),
         ))?;
@@ -116,6 +145,10 @@ impl Process {
         Ok(())
     }
 
+    pub fn send_signal(&mut self, _signal: c_int) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::Other, "Fuchsia processes have no signals"))
+    }
+
     pub fn wait(&mut self) -> io::Result<ExitStatus> {
         use default::Default;
         use sys::process::zircon::*;
@@ -125,8 +158,17 @@ impl Process {
         let mut avail: size_t = 0;
 
         unsafe {
-            zx_cvt(zx_object_wait_one(self.handle.raw(), ZX_TASK_TERMINATED,
-                                      ZX_TIME_INFINITE, ptr::null_mut()))?;
+            // Register an async wait for termination on a private port and
+            // block on the port for the resulting packet.
+            let mut port: zx_handle_t = ZX_HANDLE_INVALID;
+            zx_cvt(zx_port_create(0, &mut port))?;
+            let port = Handle::new(port);
+            zx_cvt(zx_object_wait_async(self.handle.raw(), port.raw(), 0,
+                                        ZX_TASK_TERMINATED, ZX_WAIT_ASYNC_ONCE))?;
+            let mut packet: zx_port_packet_t = mem::zeroed();
+            zx_cvt(zx_port_wait(port.raw(), ZX_TIME_INFINITE, &mut packet))?;
+            zx_cvt(packet.status)?;
+
             zx_cvt(zx_object_get_info(self.handle.raw(), ZX_INFO_PROCESS,
                                       &mut proc_info as *mut _ as *mut libc::c_void,
                                       mem::size_of::<zx_info_process_t>(), &mut actual,
@@ -139,6 +181,17 @@ impl Process {
         Ok(ExitStatus::new(proc_info.rec.return_code))
     }
 
+    // Without SIGCHLD there's nothing to share, so each child simply gets
+    // a thread of its own waiting for it.
+    pub fn on_exit(mut self, f: Box<dyn FnOnce(io::Result<ExitStatus>) + Send>) {
+        ::thread::spawn(move || f(self.wait()));
+    }
+
+    // There's no job control, so the only change is the exit.
+    pub fn wait_state_change(&mut self) -> io::Result<ExitStatus> {
+        self.wait()
+    }
+
     pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
         use default::Default;
         use sys::process::zircon::*;
@@ -155,7 +208,7 @@ impl Process {
                 x if x == ERR_TIMED_OUT => {
                     return Ok(None);
                 },
-                _ => { panic!("Failed to wait on process handle: {}", status); },
+                _ => { zx_cvt(status)?; },
             }
             zx_cvt(zx_object_get_info(self.handle.raw(), ZX_INFO_PROCESS,
                                       &mut proc_info as *mut _ as *mut libc::c_void,
@@ -168,4 +221,8 @@ impl Process {
         }
         Ok(Some(ExitStatus::new(proc_info.rec.return_code)))
     }
+
+    pub fn wait_deadline(&mut self, deadline: Instant) -> io::Result<Option<ExitStatus>> {
+        process::poll_until(deadline, || self.try_wait())
+    }
 }
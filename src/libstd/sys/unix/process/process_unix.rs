@@ -2,18 +2,38 @@ use io::{self, Error, ErrorKind};
 use libc::{self, c_int, gid_t, pid_t, uid_t};
 use ptr;
 use sys::cvt;
+use sys::fd::FileDesc;
 use sys::process::process_common::*;
 use sys;
+use process::SpawnStage;
+use sys_common::process::{self, StageError};
+use time::Instant;
 
 ////////////////////////////////////////////////////////////////////////////////
 // Command
 ////////////////////////////////////////////////////////////////////////////////
 
+// tvOS and watchOS forbid creating new processes: `fork`, `exec*` and
+// `posix_spawn` are all marked unavailable in the SDKs, and calling them
+// gets the app killed by the sandbox.
+fn no_fork_exec() -> bool {
+    cfg!(any(target_os = "tvos", target_os = "watchos"))
+}
+
+fn fork_exec_unsupported() -> io::Error {
+    io::Error::new(ErrorKind::Other,
+                   "spawning processes is not supported on this platform")
+}
+
 impl Command {
     pub fn spawn(&mut self, default: Stdio, needs_stdin: bool)
                  -> io::Result<(Process, StdioPipes)> {
         const CLOEXEC_MSG_FOOTER: &[u8] = b"NOEX";
 
+        if no_fork_exec() {
+            return Err(fork_exec_unsupported());
+        }
+
         let envp = self.capture_env();
 
         if self.saw_nul() {
@@ -21,13 +41,27 @@ impl Command {
                                       "nul byte found in provided data"));
         }
 
+        self.check_fd_mappings()?;
+        check_parent_death_signal(self)?;
+        check_clone3(self)?;
+
         let (ours, theirs) = self.setup_io(default, needs_stdin)?;
 
         if let Some(ret) = self.posix_spawn(&theirs, envp.as_ref())? {
             return Ok((ret, ours))
         }
 
+        // QNX's `fork` fails outright in a multithreaded process, and we have
+        // no way of knowing that we're single threaded, so anything
+        // `posix_spawn` can't express can't be spawned at all.
+        if cfg!(target_os = "nto") {
+            return Err(io::Error::new(ErrorKind::Other,
+                                      "QNX can only spawn processes through posix_spawn, \
+                                       which doesn't support this command's settings"));
+        }
+
         let (input, output) = sys::pipe::anon_pipe()?;
+        let ppid = unsafe { libc::getpid() };
 
         // Whatever happens after the fork is almost for sure going to touch or
         // look at the environment in one way or another (PATH in `execvp` or
@@ -37,22 +71,41 @@ impl Command {
         // Note that as soon as we're done with the fork there's no need to hold
         // a lock any more because the parent won't do anything and the child is
         // in its own process.
+        let mut pidfd = -1;
         let result = unsafe {
             let _env_lock = sys::os::env_lock();
-            cvt(libc::fork())?
+            if self.get_unshare() != 0 || self.get_create_pidfd() {
+                let pidfd = if self.get_create_pidfd() { Some(&mut pidfd) } else { None };
+                clone3::fork(self.get_unshare(), pidfd)?
+            } else {
+                cvt(libc::fork())?
+            }
         };
 
         let pid = unsafe {
             match result {
                 0 => {
                     drop(input);
-                    let err = self.do_exec(theirs, envp.as_ref());
+                    // A mapping onto the error pipe's number would overwrite
+                    // it, and an exec failure would then go unreported, so
+                    // it's moved above everything the mappings touch first.
+                    let mut output = output.into_fd();
+                    let (stage, err) = match raise_fd(&output, self.fd_mappings_floor()) {
+                        Ok(raised) => {
+                            if let Some(raised) = raised {
+                                output = raised;
+                            }
+                            self.do_exec(theirs, envp.as_ref(), ppid)
+                        }
+                        Err(e) => (SpawnStage::Stdio, e),
+                    };
                     let errno = err.raw_os_error().unwrap_or(libc::EINVAL) as u32;
                     let bytes = [
                         (errno >> 24) as u8,
                         (errno >> 16) as u8,
                         (errno >>  8) as u8,
                         (errno >>  0) as u8,
+                        stage_to_byte(stage),
                         CLOEXEC_MSG_FOOTER[0], CLOEXEC_MSG_FOOTER[1],
                         CLOEXEC_MSG_FOOTER[2], CLOEXEC_MSG_FOOTER[3]
                     ];
@@ -66,21 +119,25 @@ impl Command {
             }
         };
 
-        let mut p = Process { pid: pid, status: None };
+        // The pidfd is only ever set in the parent, and by then it refers to
+        // the child, as the child can't have been reaped yet.
+        let pidfd = if pidfd >= 0 { Some(FileDesc::new(pidfd)) } else { None };
+        let mut p = Process { pid: pid, status: None, pidfd: pidfd };
         drop(output);
-        let mut bytes = [0; 8];
+        let mut bytes = [0; 9];
 
         // loop to handle EINTR
         loop {
             match input.read(&mut bytes) {
                 Ok(0) => return Ok((p, ours)),
-                Ok(8) => {
-                    assert!(combine(CLOEXEC_MSG_FOOTER) == combine(&bytes[4.. 8]),
+                Ok(9) => {
+                    assert!(combine(CLOEXEC_MSG_FOOTER) == combine(&bytes[5.. 9]),
                             "Validation on the CLOEXEC pipe failed: {:?}", bytes);
                     let errno = combine(&bytes[0.. 4]);
                     assert!(p.wait().is_ok(),
                             "wait() should either return Ok or panic");
-                    return Err(Error::from_raw_os_error(errno))
+                    return Err(StageError::new(byte_to_stage(bytes[4]),
+                                               Error::from_raw_os_error(errno)))
                 }
                 Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
                 Err(e) => {
@@ -96,6 +153,17 @@ impl Command {
             }
         }
 
+        // Copies `fd` to a close-on-exec descriptor at or above `min`, unless
+        // it's there already.
+        unsafe fn raise_fd(fd: &FileDesc, min: c_int) -> io::Result<Option<FileDesc>> {
+            if fd.raw() >= min {
+                return Ok(None)
+            }
+            let raised = FileDesc::new(cvt(libc::fcntl(fd.raw(), libc::F_DUPFD, min))?);
+            raised.set_cloexec()?;
+            Ok(Some(raised))
+        }
+
         fn combine(arr: &[u8]) -> i32 {
             let a = arr[0] as u32;
             let b = arr[1] as u32;
@@ -104,15 +172,49 @@ impl Command {
 
             ((a << 24) | (b << 16) | (c << 8) | (d << 0)) as i32
         }
+
+        fn stage_to_byte(stage: SpawnStage) -> u8 {
+            match stage {
+                SpawnStage::Stdio => 1,
+                SpawnStage::User => 2,
+                SpawnStage::Chdir => 3,
+                SpawnStage::PreExec => 4,
+                SpawnStage::Exec => 5,
+                SpawnStage::Session => 6,
+                _ => 0,
+            }
+        }
+
+        fn byte_to_stage(byte: u8) -> SpawnStage {
+            match byte {
+                1 => SpawnStage::Stdio,
+                2 => SpawnStage::User,
+                3 => SpawnStage::Chdir,
+                4 => SpawnStage::PreExec,
+                5 => SpawnStage::Exec,
+                6 => SpawnStage::Session,
+                _ => SpawnStage::Other,
+            }
+        }
     }
 
     pub fn exec(&mut self, default: Stdio) -> io::Error {
+        if no_fork_exec() {
+            return fork_exec_unsupported();
+        }
+
         let envp = self.capture_env();
 
         if self.saw_nul() {
             return io::Error::new(ErrorKind::InvalidInput,
                                   "nul byte found in provided data")
         }
+        if let Err(e) = self.check_fd_mappings() {
+            return e
+        }
+        if let Err(e) = check_parent_death_signal(self) {
+            return e
+        }
 
         match self.setup_io(default, true) {
             Ok((_, theirs)) => {
@@ -122,7 +224,7 @@ impl Command {
                     // environment lock before we try to exec.
                     let _lock = sys::os::env_lock();
 
-                    self.do_exec(theirs, envp.as_ref())
+                    self.do_exec(theirs, envp.as_ref(), libc::getppid()).1
                 }
             }
             Err(e) => e,
@@ -162,32 +264,81 @@ impl Command {
     unsafe fn do_exec(
         &mut self,
         stdio: ChildPipes,
-        maybe_envp: Option<&CStringArray>
-    ) -> io::Error {
+        maybe_envp: Option<&CStringArray>,
+        ppid: pid_t,
+    ) -> (SpawnStage, io::Error) {
         use sys::{self, cvt_r};
 
         macro_rules! t {
-            ($e:expr) => (match $e {
+            ($stage:ident, $e:expr) => (match $e {
                 Ok(e) => e,
-                Err(e) => return e,
+                Err(e) => return (SpawnStage::$stage, e),
             })
         }
 
+        // Before any descriptor is overwritten, every one to be mapped is
+        // copied above all of the numbers involved, stdio included. That way
+        // no mapping can clobber the source of another, even when they form
+        // a cycle, and the parent's own stdio can be mapped too.
+        {
+            let min = self.fd_mappings_floor();
+            let (mappings, scratch) = self.get_fd_mappings();
+            for (&(fd, _), tmp) in mappings.iter().zip(scratch.iter_mut()) {
+                *tmp = t!(Stdio, cvt(libc::fcntl(fd, libc::F_DUPFD, min)));
+            }
+        }
+
         if let Some(fd) = stdio.stdin.fd() {
-            t!(cvt_r(|| libc::dup2(fd, libc::STDIN_FILENO)));
+            t!(Stdio, cvt_r(|| libc::dup2(fd, libc::STDIN_FILENO)));
         }
         if let Some(fd) = stdio.stdout.fd() {
-            t!(cvt_r(|| libc::dup2(fd, libc::STDOUT_FILENO)));
+            t!(Stdio, cvt_r(|| libc::dup2(fd, libc::STDOUT_FILENO)));
         }
         if let Some(fd) = stdio.stderr.fd() {
-            t!(cvt_r(|| libc::dup2(fd, libc::STDERR_FILENO)));
+            t!(Stdio, cvt_r(|| libc::dup2(fd, libc::STDERR_FILENO)));
         }
 
-        if cfg!(not(any(target_os = "l4re"))) {
-            if let Some(u) = self.get_gid() {
-                t!(cvt(libc::setgid(u as gid_t)));
+        // Mappings come after stdio so that they win if they name the same
+        // descriptor. The copies are all above every target, so closing them
+        // as we go can't close a mapped descriptor.
+        {
+            let (mappings, scratch) = self.get_fd_mappings();
+            for (&(_, target), &tmp) in mappings.iter().zip(scratch.iter()) {
+                t!(Stdio, cvt_r(|| libc::dup2(tmp, target)));
+                libc::close(tmp);
             }
-            if let Some(u) = self.get_uid() {
+        }
+
+        // A new session has to come first: a session leader can't then be
+        // moved to another process group, so asking for both fails here.
+        if self.get_new_session() {
+            t!(Session, cvt(libc::setsid()));
+        }
+        if let Some(pgroup) = self.get_pgroup() {
+            t!(Session, cvt(libc::setpgid(0, pgroup)));
+        }
+
+        // The new root has to be entered while we're still privileged, and
+        // before `cwd` is looked up, which is relative to it. Without a
+        // `cwd` the child starts at the new root, as it could otherwise
+        // still reach the old one through its working directory.
+        #[cfg(target_os = "linux")]
+        {
+            if let Some(root) = self.get_chroot() {
+                t!(Chdir, cvt(libc::chroot(root.as_ptr())));
+                if self.get_cwd().is_none() {
+                    t!(Chdir, cvt(libc::chdir(b"/\0".as_ptr() as *const libc::c_char)));
+                }
+            }
+        }
+
+        // Each step needs the privileges the next one gives up: the groups
+        // can only be changed while we're still root, and the gid while
+        // our uid is still allowed to.
+        if cfg!(not(any(target_os = "l4re"))) {
+            if let Some(groups) = self.get_groups() {
+                t!(User, cvt(libc::setgroups(groups.len() as _, groups.as_ptr())));
+            } else if self.get_uid().is_some() {
                 // When dropping privileges from root, the `setgroups` call
                 // will remove any extraneous groups. If we don't call this,
                 // then even though our uid has dropped, we may still have
@@ -196,12 +347,28 @@ impl Command {
                 // return value, this is just done as an optimistic
                 // privilege dropping function.
                 let _ = libc::setgroups(0, ptr::null());
-
-                t!(cvt(libc::setuid(u as uid_t)));
+            }
+            if let Some(u) = self.get_gid() {
+                t!(User, cvt(libc::setgid(u as gid_t)));
+            }
+            if let Some(u) = self.get_uid() {
+                t!(User, cvt(libc::setuid(u as uid_t)));
             }
         }
         if let Some(ref cwd) = *self.get_cwd() {
-            t!(cvt(libc::chdir(cwd.as_ptr())));
+            t!(Chdir, cvt(libc::chdir(cwd.as_ptr())));
+        }
+
+        // Changing our credentials resets the signal, so it has to come
+        // after. If the parent has already gone, we've been reparented and
+        // the signal will never come, so it's sent now instead. In a new pid
+        // namespace the parent is outside of it and can't be checked for.
+        if let Some(signal) = self.get_parent_death_signal() {
+            t!(Other, set_parent_death_signal(signal));
+            let parent = libc::getppid();
+            if parent != ppid && parent != 0 {
+                t!(Other, cvt(libc::kill(libc::getpid(), signal)));
+            }
         }
 
         // emscripten has no signal support.
@@ -224,18 +391,18 @@ impl Command {
                              0,
                              mem::size_of::<libc::sigset_t>());
             } else {
-                t!(cvt(libc::sigemptyset(&mut set)));
+                t!(Other, cvt(libc::sigemptyset(&mut set)));
             }
-            t!(cvt(libc::pthread_sigmask(libc::SIG_SETMASK, &set,
-                                         ptr::null_mut())));
+            t!(Other, cvt(libc::pthread_sigmask(libc::SIG_SETMASK, &set,
+                                                ptr::null_mut())));
             let ret = sys::signal(libc::SIGPIPE, libc::SIG_DFL);
             if ret == libc::SIG_ERR {
-                return io::Error::last_os_error()
+                return (SpawnStage::Other, io::Error::last_os_error())
             }
         }
 
         for callback in self.get_closures().iter_mut() {
-            t!(callback());
+            t!(PreExec, callback());
         }
 
         // Although we're performing an exec here we may also return with an
@@ -259,12 +426,13 @@ impl Command {
             *sys::os::environ() = envp.as_ptr();
         }
 
-        libc::execvp(self.get_argv()[0], self.get_argv().as_ptr());
-        io::Error::last_os_error()
+        libc::execvp(self.get_program_cstr().as_ptr(), self.get_argv().as_ptr());
+        (SpawnStage::Exec, io::Error::last_os_error())
     }
 
-    #[cfg(not(any(target_os = "macos", target_os = "freebsd",
-                  all(target_os = "linux", target_env = "gnu"))))]
+    #[cfg(not(any(target_os = "macos", target_os = "freebsd", target_os = "nto",
+                  all(target_os = "linux", target_env = "gnu"),
+                  all(target_os = "linux", target_env = "musl"))))]
     fn posix_spawn(&mut self, _: &ChildPipes, _: Option<&CStringArray>)
         -> io::Result<Option<Process>>
     {
@@ -272,20 +440,43 @@ impl Command {
     }
 
     // Only support platforms for which posix_spawn() can return ENOENT
-    // directly.
-    #[cfg(any(target_os = "macos", target_os = "freebsd",
-              all(target_os = "linux", target_env = "gnu")))]
+    // directly. musl's always has, reporting the exec failure from the child
+    // over a pipe.
+    #[cfg(any(target_os = "macos", target_os = "freebsd", target_os = "nto",
+              all(target_os = "linux", target_env = "gnu"),
+              all(target_os = "linux", target_env = "musl")))]
     fn posix_spawn(&mut self, stdio: &ChildPipes, envp: Option<&CStringArray>)
         -> io::Result<Option<Process>>
     {
         use mem;
         use sys;
 
-        if self.get_cwd().is_some() ||
-            self.get_gid().is_some() ||
+        // Changing directory needs a file action which only newer C
+        // libraries have (glibc 2.29, macOS 10.15), so it's looked up at
+        // runtime, falling back to fork/exec without it.
+        weak! {
+            fn posix_spawn_file_actions_addchdir_np(*mut libc::posix_spawn_file_actions_t,
+                                                    *const libc::c_char) -> libc::c_int
+        }
+        let addchdir = match self.get_cwd() {
+            Some(cwd) => match posix_spawn_file_actions_addchdir_np.get() {
+                Some(f) => Some((f, cwd)),
+                None => return Ok(None),
+            },
+            None => None,
+        };
+
+        if self.get_gid().is_some() ||
             self.get_uid().is_some() ||
+            self.get_groups().is_some() ||
             self.env_saw_path() ||
-            self.get_closures().len() != 0 {
+            self.get_new_session() ||
+            self.get_parent_death_signal().is_some() ||
+            self.get_unshare() != 0 ||
+            self.get_create_pidfd() ||
+            self.get_chroot().is_some() ||
+            self.get_closures().len() != 0 ||
+            self.get_fd_mappings().0.len() != 0 {
             return Ok(None)
         }
 
@@ -301,7 +492,7 @@ impl Command {
             }
         }
 
-        let mut p = Process { pid: 0, status: None };
+        let mut p = Process { pid: 0, status: None, pidfd: None };
 
         struct PosixSpawnFileActions(libc::posix_spawn_file_actions_t);
 
@@ -345,6 +536,12 @@ impl Command {
                                                            fd,
                                                            libc::STDERR_FILENO))?;
             }
+            if let Some((f, cwd)) = addchdir {
+                match f(&mut file_actions.0, cwd.as_ptr()) {
+                    0 => {}
+                    e => return Err(io::Error::from_raw_os_error(e)),
+                }
+            }
 
             let mut set: libc::sigset_t = mem::uninitialized();
             cvt(libc::sigemptyset(&mut set))?;
@@ -354,8 +551,12 @@ impl Command {
             cvt(libc::posix_spawnattr_setsigdefault(&mut attrs.0,
                                                     &set))?;
 
-            let flags = libc::POSIX_SPAWN_SETSIGDEF |
+            let mut flags = libc::POSIX_SPAWN_SETSIGDEF |
                 libc::POSIX_SPAWN_SETSIGMASK;
+            if let Some(pgroup) = self.get_pgroup() {
+                cvt(libc::posix_spawnattr_setpgroup(&mut attrs.0, pgroup))?;
+                flags |= libc::POSIX_SPAWN_SETPGROUP;
+            }
             cvt(libc::posix_spawnattr_setflags(&mut attrs.0, flags as _))?;
 
             // Make sure we synchronize access to the global `environ` resource
@@ -364,7 +565,7 @@ impl Command {
                 .unwrap_or_else(|| *sys::os::environ() as *const _);
             let ret = libc::posix_spawnp(
                 &mut p.pid,
-                self.get_argv()[0],
+                self.get_program_cstr().as_ptr(),
                 &file_actions.0,
                 &attrs.0,
                 self.get_argv().as_ptr() as *const _,
@@ -372,8 +573,11 @@ impl Command {
             );
             if ret == 0 {
                 Ok(Some(p))
-            } else {
+            } else if addchdir.is_some() {
+                // The directory change is reported the same way as the exec.
                 Err(io::Error::from_raw_os_error(ret))
+            } else {
+                Err(StageError::new(SpawnStage::Exec, io::Error::from_raw_os_error(ret)))
             }
         }
     }
@@ -387,6 +591,7 @@ impl Command {
 pub struct Process {
     pid: pid_t,
     status: Option<ExitStatus>,
+    pidfd: Option<FileDesc>,
 }
 
 impl Process {
@@ -402,7 +607,20 @@ impl Process {
             Err(Error::new(ErrorKind::InvalidInput,
                            "invalid argument: can't kill an exited process"))
         } else {
-            cvt(unsafe { libc::kill(self.pid, libc::SIGKILL) }).map(|_| ())
+            self.send_signal(libc::SIGKILL)
+        }
+    }
+
+    pub fn send_signal(&mut self, signal: c_int) -> io::Result<()> {
+        // As with `kill`, the pid may belong to someone else once we've
+        // waited. A pidfd can't, so it's used when there is one.
+        if self.status.is_some() {
+            return Err(Error::new(ErrorKind::InvalidInput,
+                                  "invalid argument: can't signal an exited process"))
+        }
+        match self.pidfd {
+            Some(ref fd) => pidfd::send_signal(fd, signal),
+            None => cvt(unsafe { libc::kill(self.pid, signal) }).map(|_| ()),
         }
     }
 
@@ -417,6 +635,28 @@ impl Process {
         Ok(ExitStatus::new(status))
     }
 
+    pub fn on_exit(self, f: Box<dyn FnOnce(io::Result<ExitStatus>) + Send>) {
+        super::reaper::register(self, f)
+    }
+
+    // Also returns when the child is stopped or continued, which unlike
+    // its exit isn't remembered.
+    pub fn wait_state_change(&mut self) -> io::Result<ExitStatus> {
+        use sys::cvt_r;
+        if let Some(status) = self.status {
+            return Ok(status)
+        }
+        let mut status = 0 as c_int;
+        cvt_r(|| unsafe {
+            libc::waitpid(self.pid, &mut status, libc::WUNTRACED | libc::WCONTINUED)
+        })?;
+        let status = ExitStatus::new(status);
+        if status.stopped_signal().is_none() && !status.continued() {
+            self.status = Some(status);
+        }
+        Ok(status)
+    }
+
     pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
         if let Some(status) = self.status {
             return Ok(Some(status))
@@ -432,4 +672,357 @@ impl Process {
             Ok(Some(ExitStatus::new(status)))
         }
     }
+
+    pub fn pidfd(&self) -> Option<&FileDesc> {
+        self.pidfd.as_ref()
+    }
+
+    pub fn take_pidfd(&mut self) -> Option<FileDesc> {
+        self.pidfd.take()
+    }
+
+    pub fn wait_deadline(&mut self, deadline: Instant) -> io::Result<Option<ExitStatus>> {
+        if let Some(status) = self.try_wait()? {
+            return Ok(Some(status))
+        }
+        // Block on something which is notified when the child exits where
+        // the platform has it, and poll `waitpid` otherwise.
+        if exit_notify::wait(self.pid, self.pidfd.as_ref(), deadline)? {
+            return self.try_wait()
+        }
+        process::poll_until(deadline, || self.try_wait())
+    }
+}
+
+/// Process file descriptors, which refer to one process for as long as
+/// they're open, where pids may be reused once a process has been reaped.
+#[cfg(all(target_os = "linux",
+          any(target_arch = "x86",
+              target_arch = "x86_64",
+              target_arch = "arm",
+              target_arch = "aarch64",
+              target_arch = "powerpc",
+              target_arch = "powerpc64",
+              target_arch = "s390x")))]
+pub mod pidfd {
+    use io;
+    use libc::{self, c_int, pid_t};
+    use ptr;
+    use sys::cvt;
+    use sys::fd::FileDesc;
+
+    // These are numbered the same on all of the above, and aren't known to
+    // the libc crate yet.
+    const SYS_PIDFD_SEND_SIGNAL: libc::c_long = 424;
+    const SYS_PIDFD_OPEN: libc::c_long = 434;
+
+    /// Opens a pidfd for `pid`, which fails on kernels before 5.3.
+    pub fn open(pid: pid_t) -> io::Result<FileDesc> {
+        let fd = cvt(unsafe { libc::syscall(SYS_PIDFD_OPEN, pid, 0) })?;
+        Ok(FileDesc::new(fd as c_int))
+    }
+
+    pub fn send_signal(fd: &FileDesc, signal: c_int) -> io::Result<()> {
+        cvt(unsafe {
+            libc::syscall(SYS_PIDFD_SEND_SIGNAL, fd.raw(), signal,
+                          ptr::null::<libc::siginfo_t>(), 0)
+        }).map(|_| ())
+    }
+}
+
+#[cfg(not(all(target_os = "linux",
+              any(target_arch = "x86",
+                  target_arch = "x86_64",
+                  target_arch = "arm",
+                  target_arch = "aarch64",
+                  target_arch = "powerpc",
+                  target_arch = "powerpc64",
+                  target_arch = "s390x"))))]
+pub mod pidfd {
+    use io;
+    use libc::c_int;
+    use sys::fd::FileDesc;
+
+    pub fn send_signal(_fd: &FileDesc, _signal: c_int) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::Other, "pidfds are not supported on this platform"))
+    }
+}
+
+// A child started with `clone3` can't run arbitrary code before it execs,
+// see `clone3`.
+fn check_clone3(cmd: &mut Command) -> io::Result<()> {
+    if (cmd.get_unshare() != 0 || cmd.get_create_pidfd()) && !cmd.get_closures().is_empty() {
+        return Err(io::Error::new(ErrorKind::InvalidInput,
+                                  "before_exec closures can't be combined with \
+                                   unshare or create_pidfd"))
+    }
+    Ok(())
+}
+
+fn check_parent_death_signal(cmd: &Command) -> io::Result<()> {
+    if cmd.get_parent_death_signal().is_some() &&
+        !cfg!(any(target_os = "linux", target_os = "android", target_os = "freebsd")) {
+        return Err(io::Error::new(ErrorKind::Other,
+                                  "a parent death signal is not supported on this platform"))
+    }
+    Ok(())
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+unsafe fn set_parent_death_signal(signal: c_int) -> io::Result<()> {
+    cvt(libc::prctl(libc::PR_SET_PDEATHSIG, signal as libc::c_ulong)).map(|_| ())
+}
+
+#[cfg(target_os = "freebsd")]
+unsafe fn set_parent_death_signal(mut signal: c_int) -> io::Result<()> {
+    use libc::c_void;
+
+    // FreeBSD 11.2 and later; `idtype_t` is an `int` sized enum.
+    const P_PID: c_int = 0;
+    const PROC_PDEATHSIG_CTL: c_int = 11;
+    extern "C" {
+        fn procctl(idtype: c_int, id: libc::id_t, cmd: c_int, data: *mut c_void) -> c_int;
+    }
+    cvt(procctl(P_PID, 0, PROC_PDEATHSIG_CTL, &mut signal as *mut c_int as *mut c_void))
+        .map(|_| ())
+}
+
+// Never called, as `check_parent_death_signal` fails first.
+#[cfg(not(any(target_os = "linux", target_os = "android", target_os = "freebsd")))]
+unsafe fn set_parent_death_signal(_signal: c_int) -> io::Result<()> {
+    Err(io::Error::new(ErrorKind::Other,
+                       "a parent death signal is not supported on this platform"))
+}
+
+/// Starting a child in new namespaces, or with a pidfd.
+///
+/// `fork` clones the calling process with `clone3`, which unlike `unshare`
+/// in the child also puts the child itself into a new pid namespace, as its
+/// init, and which can create a pidfd for the child as it starts.
+///
+/// It's called in place of `libc::fork`, so the C library isn't told about
+/// the new process: its atfork handlers don't run, and the locks it resets
+/// after a real `fork`, such as malloc's, may still be held in the child.
+/// That's only sound as long as the child makes nothing but async-signal-safe
+/// calls before it execs, so it can't be combined with `before_exec` closures,
+/// see `check_clone3`.
+#[cfg(all(target_os = "linux",
+          any(target_arch = "x86",
+              target_arch = "x86_64",
+              target_arch = "arm",
+              target_arch = "aarch64",
+              target_arch = "powerpc",
+              target_arch = "powerpc64",
+              target_arch = "s390x")))]
+mod clone3 {
+    use io;
+    use libc::{self, c_int, pid_t};
+    use mem;
+    use sys::cvt;
+
+    // Numbered the same on all of the above, like the pidfd calls.
+    const SYS_CLONE3: libc::c_long = 435;
+
+    // The first version of the kernel's `struct clone_args`.
+    #[repr(C)]
+    struct CloneArgs {
+        flags: u64,
+        pidfd: u64,
+        child_tid: u64,
+        parent_tid: u64,
+        exit_signal: u64,
+        stack: u64,
+        stack_size: u64,
+        tls: u64,
+    }
+
+    // Not known to the libc crate yet.
+    const CLONE_PIDFD: c_int = 0x1000;
+
+    /// Like `fork`, with the child in new namespaces for each of the
+    /// `CLONE_NEW*` flags in `flags`, and with a pidfd for it stored in
+    /// `pidfd` if that's given. Fails on kernels before 5.3.
+    pub unsafe fn fork(flags: c_int, pidfd: Option<&mut c_int>) -> io::Result<pid_t> {
+        // Anything else could have the child share our memory or files.
+        const NAMESPACES: c_int = libc::CLONE_NEWNS | libc::CLONE_NEWUTS |
+            libc::CLONE_NEWIPC | libc::CLONE_NEWUSER | libc::CLONE_NEWPID |
+            libc::CLONE_NEWNET | libc::CLONE_NEWCGROUP;
+        if flags & !NAMESPACES != 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "only namespace flags can be given to unshare"))
+        }
+        let (flags, pidfd) = match pidfd {
+            Some(pidfd) => (flags | CLONE_PIDFD, pidfd as *mut c_int as u64),
+            None => (flags, 0),
+        };
+        let args = CloneArgs {
+            flags: flags as u32 as u64,
+            pidfd: pidfd,
+            child_tid: 0,
+            parent_tid: 0,
+            exit_signal: libc::SIGCHLD as u64,
+            stack: 0,
+            stack_size: 0,
+            tls: 0,
+        };
+        cvt(libc::syscall(SYS_CLONE3, &args as *const CloneArgs,
+                          mem::size_of::<CloneArgs>())).map(|pid| pid as pid_t)
+    }
+}
+
+#[cfg(not(all(target_os = "linux",
+              any(target_arch = "x86",
+                  target_arch = "x86_64",
+                  target_arch = "arm",
+                  target_arch = "aarch64",
+                  target_arch = "powerpc",
+                  target_arch = "powerpc64",
+                  target_arch = "s390x"))))]
+mod clone3 {
+    use io;
+    use libc::{c_int, pid_t};
+
+    pub unsafe fn fork(_flags: c_int, _pidfd: Option<&mut c_int>) -> io::Result<pid_t> {
+        Err(io::Error::new(io::ErrorKind::Other,
+                           "namespaces and pidfds are not supported on this platform"))
+    }
+}
+
+/// Blocking until a child exits or a deadline passes.
+///
+/// `wait` returns `Ok(false)` if it can't, and otherwise `Ok(true)` once the
+/// child has exited or the deadline has passed, without reaping the child.
+#[cfg(all(target_os = "linux",
+          any(target_arch = "x86",
+              target_arch = "x86_64",
+              target_arch = "arm",
+              target_arch = "aarch64",
+              target_arch = "powerpc",
+              target_arch = "powerpc64",
+              target_arch = "s390x")))]
+mod exit_notify {
+    use io;
+    use libc::{self, c_int, pid_t};
+    use sys::fd::FileDesc;
+    use sys_common::process::timeout_ms;
+    use time::Instant;
+    use super::pidfd;
+
+    pub fn wait(pid: pid_t, pidfd: Option<&FileDesc>, deadline: Instant) -> io::Result<bool> {
+        // The child's own pidfd is used when it has one.
+        let opened;
+        let fd = match pidfd {
+            Some(fd) => fd,
+            None => match pidfd::open(pid) {
+                Ok(fd) => {
+                    opened = fd;
+                    &opened
+                }
+                Err(_) => return Ok(false),
+            },
+        };
+        // A pidfd is readable once its process has exited.
+        let mut pollfd = libc::pollfd { fd: fd.raw(), events: libc::POLLIN, revents: 0 };
+        loop {
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(true)
+            }
+            let timeout = timeout_ms(deadline - now);
+            let timeout = if timeout > c_int::max_value() as u64 {
+                c_int::max_value()
+            } else {
+                timeout as c_int
+            };
+            match unsafe { libc::poll(&mut pollfd, 1, timeout) } {
+                -1 => {
+                    let err = io::Error::last_os_error();
+                    if err.kind() != io::ErrorKind::Interrupted {
+                        return Err(err)
+                    }
+                }
+                0 => {}
+                _ => return Ok(true),
+            }
+        }
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "freebsd"))]
+mod exit_notify {
+    use io;
+    use libc::{self, pid_t};
+    use mem;
+    use sys::fd::FileDesc;
+    use time::Instant;
+
+    pub fn wait(pid: pid_t, _pidfd: Option<&FileDesc>, deadline: Instant) -> io::Result<bool> {
+        let kq = unsafe { libc::kqueue() };
+        if kq < 0 {
+            return Ok(false)
+        }
+        let kq = FileDesc::new(kq);
+        let mut change: libc::kevent = unsafe { mem::zeroed() };
+        change.ident = pid as _;
+        change.filter = libc::EVFILT_PROC;
+        change.flags = libc::EV_ADD | libc::EV_ONESHOT;
+        change.fflags = libc::NOTE_EXIT;
+        loop {
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(true)
+            }
+            let remaining = deadline - now;
+            let timeout = libc::timespec {
+                tv_sec: if remaining.as_secs() > libc::time_t::max_value() as u64 {
+                    libc::time_t::max_value()
+                } else {
+                    remaining.as_secs() as libc::time_t
+                },
+                tv_nsec: remaining.subsec_nanos() as _,
+            };
+            let mut event: libc::kevent = unsafe { mem::zeroed() };
+            match unsafe { libc::kevent(kq.raw(), &change, 1, &mut event, 1, &timeout) } {
+                -1 => {
+                    let err = io::Error::last_os_error();
+                    if err.kind() != io::ErrorKind::Interrupted {
+                        return Err(err)
+                    }
+                }
+                0 => {}
+                // A child which already exited can't be registered; the
+                // caller reaps it.
+                _ if event.flags & libc::EV_ERROR != 0 => {
+                    if event.data as i32 == libc::ESRCH {
+                        return Ok(true)
+                    }
+                    return Err(io::Error::from_raw_os_error(event.data as i32))
+                }
+                _ => return Ok(true),
+            }
+        }
+    }
+}
+
+#[cfg(not(any(all(target_os = "linux",
+                  any(target_arch = "x86",
+                      target_arch = "x86_64",
+                      target_arch = "arm",
+                      target_arch = "aarch64",
+                      target_arch = "powerpc",
+                      target_arch = "powerpc64",
+                      target_arch = "s390x")),
+              target_os = "macos",
+              target_os = "freebsd")))]
+mod exit_notify {
+    use io;
+    use libc::pid_t;
+    use sys::fd::FileDesc;
+    use time::Instant;
+
+    pub fn wait(_pid: pid_t, _pidfd: Option<&FileDesc>, _deadline: Instant)
+        -> io::Result<bool>
+    {
+        Ok(false)
+    }
 }
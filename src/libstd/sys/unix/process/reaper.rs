@@ -0,0 +1,151 @@
+//! The reaper behind `ChildExt::on_exit`.
+//!
+//! Children handed over to it are kept in a list, which a thread started on
+//! first use goes through whenever a `SIGCHLD` arrives, reaping the ones
+//! which have exited with `waitpid(pid, WNOHANG)` and running their
+//! callbacks. It only ever waits for the pids it was given, so it can't reap
+//! a child the program waits for some other way, and as the `Child` itself
+//! is given up, nothing else in std can wait for one of its children either.
+//!
+//! The `SIGCHLD` handler only writes a byte to a pipe the thread is polling,
+//! and then calls the handler which was there before, if any. The thread
+//! also looks every second, in case the handler is replaced later by one
+//! which doesn't chain to ours. If `SIGCHLD` is ignored it's left that way,
+//! but then the kernel reaps children itself and their callbacks get the
+//! `ECHILD` error.
+
+use io;
+use libc::{self, c_int, c_void};
+use mem;
+use panic::{self, AssertUnwindSafe};
+use ptr;
+use sync::{Mutex, Once};
+use sys::cvt;
+use sys::fd::FileDesc;
+use sys::os;
+use sys::pipe;
+use thread;
+
+use super::{ExitStatus, Process};
+
+pub type Callback = Box<dyn FnOnce(io::Result<ExitStatus>) + Send>;
+
+struct Reaper {
+    children: Mutex<Vec<(Process, Callback)>>,
+    wake_read: FileDesc,
+    wake_write: FileDesc,
+}
+
+static INIT: Once = Once::new();
+static mut REAPER: *const Reaper = ptr::null();
+// Only written before the handler is installed.
+static mut WAKE_FD: c_int = -1;
+static mut PREVIOUS: Option<libc::sigaction> = None;
+
+/// Calls `f` with the exit status of `p` from the reaper's thread once it
+/// exits.
+pub fn register(p: Process, f: Callback) {
+    let reaper = reaper();
+    reaper.children.lock().unwrap().push((p, f));
+    // The child may have exited before it was in the list.
+    wake(reaper.wake_write.raw());
+}
+
+fn reaper() -> &'static Reaper {
+    INIT.call_once(|| unsafe {
+        let (read, write) = pipe::anon_pipe().expect("failed to create the reaper's pipe");
+        let (read, write) = (read.into_fd(), write.into_fd());
+        read.set_nonblocking(true).expect("failed to set up the reaper's pipe");
+        write.set_nonblocking(true).expect("failed to set up the reaper's pipe");
+        WAKE_FD = write.raw();
+        REAPER = Box::into_raw(Box::new(Reaper {
+            children: Mutex::new(Vec::new()),
+            wake_read: read,
+            wake_write: write,
+        }));
+        install_handler();
+        thread::Builder::new()
+            .name("child reaper".to_string())
+            .spawn(|| run(&*REAPER))
+            .expect("failed to spawn the reaper thread");
+    });
+    unsafe { &*REAPER }
+}
+
+unsafe fn install_handler() {
+    let mut previous: libc::sigaction = mem::zeroed();
+    if libc::sigaction(libc::SIGCHLD, ptr::null(), &mut previous) != 0 ||
+        previous.sa_sigaction == libc::SIG_IGN {
+        return
+    }
+    PREVIOUS = Some(previous);
+    let mut action: libc::sigaction = mem::zeroed();
+    action.sa_sigaction = on_sigchld as libc::sighandler_t;
+    action.sa_flags = libc::SA_SIGINFO | libc::SA_RESTART | libc::SA_NOCLDSTOP;
+    libc::sigaction(libc::SIGCHLD, &action, ptr::null_mut());
+}
+
+extern "C" fn on_sigchld(signum: c_int, info: *mut libc::siginfo_t, context: *mut c_void) {
+    unsafe {
+        let errno = os::errno();
+        wake(WAKE_FD);
+        os::set_errno(errno);
+        if let Some(ref previous) = PREVIOUS {
+            let handler = previous.sa_sigaction;
+            if handler == libc::SIG_DFL || handler == libc::SIG_IGN {
+                return
+            }
+            if previous.sa_flags & libc::SA_SIGINFO != 0 {
+                let f: extern "C" fn(c_int, *mut libc::siginfo_t, *mut c_void) =
+                    mem::transmute(handler);
+                f(signum, info, context);
+            } else {
+                let f: extern "C" fn(c_int) = mem::transmute(handler);
+                f(signum);
+            }
+        }
+    }
+}
+
+// A full pipe already has a wakeup pending, so failures don't matter.
+fn wake(fd: c_int) {
+    unsafe {
+        libc::write(fd, b"\0".as_ptr() as *const c_void, 1);
+    }
+}
+
+fn run(reaper: &Reaper) {
+    let mut buf = [0; 64];
+    let mut waiting = false;
+    loop {
+        let mut pfd = libc::pollfd { fd: reaper.wake_read.raw(), events: libc::POLLIN, revents: 0 };
+        let timeout = if waiting { 1000 } else { -1 };
+        let _ = cvt(unsafe { libc::poll(&mut pfd, 1, timeout) });
+        while let Ok(n) = reaper.wake_read.read(&mut buf) {
+            if n < buf.len() {
+                break
+            }
+        }
+
+        let mut done = Vec::new();
+        {
+            let mut children = reaper.children.lock().unwrap();
+            let mut i = 0;
+            while i < children.len() {
+                let result = match children[i].0.try_wait() {
+                    Ok(None) => { i += 1; continue }
+                    Ok(Some(status)) => Ok(status),
+                    Err(e) => Err(e),
+                };
+                let (_, f) = children.swap_remove(i);
+                done.push((f, result));
+            }
+            waiting = !children.is_empty();
+        }
+
+        // A panicking callback mustn't take the others down with it.
+        for (f, result) in done {
+            let _ = panic::catch_unwind(AssertUnwindSafe(move || f(result)));
+        }
+    }
+}
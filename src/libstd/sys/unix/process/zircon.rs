@@ -2,6 +2,7 @@
 
 use convert::TryInto;
 use io;
+use mem;
 use os::raw::c_char;
 use u64;
 
@@ -56,6 +57,14 @@ impl Handle {
     pub fn raw(&self) -> zx_handle_t {
         self.raw
     }
+
+    /// Gives up ownership of the handle without closing it, e.g. because it
+    /// has been transferred to another process.
+    pub fn into_raw(self) -> zx_handle_t {
+        let raw = self.raw;
+        mem::forget(self);
+        raw
+    }
 }
 
 impl Drop for Handle {
@@ -107,14 +116,86 @@ extern {
                               avail: *mut size_t) -> zx_status_t;
 }
 
-#[derive(Default)]
+pub const ZX_WAIT_ASYNC_ONCE: u32 = 0;
+
+#[repr(C)]
+pub struct zx_port_packet_t {
+    pub key: u64,
+    pub packet_type: u32,
+    pub status: zx_status_t,
+    pub payload: [u8; 32],
+}
+
+extern {
+    pub fn zx_port_create(options: u32, out: *mut zx_handle_t) -> zx_status_t;
+
+    pub fn zx_port_wait(handle: zx_handle_t, deadline: zx_time_t,
+                        packet: *mut zx_port_packet_t) -> zx_status_t;
+
+    pub fn zx_object_wait_async(handle: zx_handle_t, port: zx_handle_t, key: u64,
+                                signals: zx_signals_t, options: u32) -> zx_status_t;
+}
+
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct fdio_spawn_fd_t {
+    pub local_fd: i32,
+    pub target_fd: i32,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct fdio_spawn_ns_t {
+    pub prefix: *const c_char,
+    pub handle: zx_handle_t,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct fdio_spawn_handle_t {
+    pub id: u32,
+    pub handle: zx_handle_t,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub union fdio_spawn_action_union_t {
+    pub fd: fdio_spawn_fd_t,
+    pub ns: fdio_spawn_ns_t,
+    pub h: fdio_spawn_handle_t,
+}
+
 #[repr(C)]
 pub struct fdio_spawn_action_t {
     pub action: u32,
     pub reserved0: u32,
-    pub local_fd: i32,
-    pub target_fd: i32,
-    pub reserved1: u64,
+    pub u: fdio_spawn_action_union_t,
+}
+
+impl fdio_spawn_action_t {
+    pub fn fd(action: u32, local_fd: i32, target_fd: i32) -> fdio_spawn_action_t {
+        fdio_spawn_action_t {
+            action,
+            reserved0: 0,
+            u: fdio_spawn_action_union_t { fd: fdio_spawn_fd_t { local_fd, target_fd } },
+        }
+    }
+
+    pub fn ns_entry(prefix: *const c_char, handle: zx_handle_t) -> fdio_spawn_action_t {
+        fdio_spawn_action_t {
+            action: FDIO_SPAWN_ACTION_ADD_NS_ENTRY,
+            reserved0: 0,
+            u: fdio_spawn_action_union_t { ns: fdio_spawn_ns_t { prefix, handle } },
+        }
+    }
+
+    pub fn handle(id: u32, handle: zx_handle_t) -> fdio_spawn_action_t {
+        fdio_spawn_action_t {
+            action: FDIO_SPAWN_ACTION_ADD_HANDLE,
+            reserved0: 0,
+            u: fdio_spawn_action_union_t { h: fdio_spawn_handle_t { id, handle } },
+        }
+    }
 }
 
 extern {
@@ -137,6 +218,8 @@ pub const FDIO_SPAWN_CLONE_ALL: u32 = 0xFFFF;
 
 pub const FDIO_SPAWN_ACTION_CLONE_FD: u32 = 0x0001;
 pub const FDIO_SPAWN_ACTION_TRANSFER_FD: u32 = 0x0002;
+pub const FDIO_SPAWN_ACTION_ADD_NS_ENTRY: u32 = 0x0003;
+pub const FDIO_SPAWN_ACTION_ADD_HANDLE: u32 = 0x0004;
 
 // Errors
 
@@ -13,6 +13,8 @@ pub fn hashmap_random_keys() -> (u64, u64) {
 
 #[cfg(all(unix,
           not(target_os = "ios"),
+          not(target_os = "tvos"),
+          not(target_os = "watchos"),
           not(target_os = "openbsd"),
           not(target_os = "freebsd"),
           not(target_os = "fuchsia")))]
@@ -42,6 +44,16 @@ mod imp {
             return false;
         }
 
+        // Before android-28 the syscall may not be allowed by the seccomp
+        // filter apps run under (which kills the process rather than failing
+        // with ENOSYS), so only use it where bionic itself does.
+        #[cfg(target_os = "android")]
+        {
+            if ::sys::android::api_level() < 28 {
+                return false;
+            }
+        }
+
         let mut read = 0;
         while read < v.len() {
             let result = getrandom(&mut v[read..]);
@@ -102,7 +114,7 @@ mod imp {
     }
 }
 
-#[cfg(target_os = "ios")]
+#[cfg(any(target_os = "ios", target_os = "tvos", target_os = "watchos"))]
 mod imp {
     use io;
     use libc::{c_int, size_t};
@@ -1,13 +1,48 @@
 #![cfg_attr(test, allow(dead_code))]
 
 use libc;
+use mem;
+use sync::atomic::{AtomicUsize, Ordering};
+use sys::ext::stack_overflow::OverflowInfo;
 use self::imp::{make_handler, drop_handler};
 
 pub use self::imp::cleanup;
 pub use self::imp::init;
 
 pub struct Handler {
-    _data: *mut libc::c_void
+    _data: *mut libc::c_void,
+    _size: usize,
+}
+
+// The `fn(&OverflowInfo)` to call on a stack overflow, or 0 for none.
+static OVERFLOW_HANDLER: AtomicUsize = AtomicUsize::new(0);
+
+// The size of the alternate signal stacks made from now on, or 0 for
+// `SIGSTKSZ`.
+static ALT_STACK_SIZE: AtomicUsize = AtomicUsize::new(0);
+
+pub fn set_overflow_handler(handler: fn(&OverflowInfo)) {
+    OVERFLOW_HANDLER.store(handler as usize, Ordering::SeqCst);
+}
+
+#[allow(dead_code)] // not every platform detects overflows
+fn run_overflow_handler(info: &OverflowInfo) {
+    let handler = OVERFLOW_HANDLER.load(Ordering::SeqCst);
+    if handler != 0 {
+        let handler: fn(&OverflowInfo) = unsafe { mem::transmute(handler) };
+        handler(info);
+    }
+}
+
+pub fn set_alt_stack_size(size: usize) {
+    ALT_STACK_SIZE.store(size, Ordering::SeqCst);
+}
+
+pub fn alt_stack_size() -> usize {
+    match ALT_STACK_SIZE.load(Ordering::SeqCst) {
+        0 => imp::DEFAULT_ALT_STACK_SIZE,
+        size => size,
+    }
 }
 
 impl Handler {
@@ -34,6 +69,7 @@ impl Drop for Handler {
           target_os = "openbsd"))]
 mod imp {
     use super::Handler;
+    use cmp;
     use mem;
     use ptr;
     use libc::{sigaltstack, SIGSTKSZ, SS_DISABLE};
@@ -44,9 +80,9 @@ mod imp {
     use libc::{SIGSEGV, PROT_READ, PROT_WRITE, MAP_PRIVATE, MAP_ANON};
     use libc::MAP_FAILED;
 
+    use sys::ext::stack_overflow::OverflowInfo;
     use sys_common::thread_info;
 
-
     #[cfg(any(target_os = "linux", target_os = "android"))]
     unsafe fn siginfo_si_addr(info: *mut libc::siginfo_t) -> usize {
         #[repr(C)]
@@ -92,9 +128,12 @@ mod imp {
         let guard = thread_info::stack_guard().unwrap_or(0..0);
         let addr = siginfo_si_addr(info);
 
-        // If the faulting address is within the guard page, then we print a
-        // message saying so and abort.
+        // If the faulting address is within the guard page, then we give the
+        // application's handler a look, print a message saying so and abort.
         if guard.start <= addr && addr < guard.end {
+            let thread = thread_info::current_thread();
+            let name = thread.as_ref().and_then(|t| t.name());
+            super::run_overflow_handler(&OverflowInfo::new(addr, guard.clone(), name));
             report_overflow();
             rtabort!("stack overflow");
         } else {
@@ -107,7 +146,10 @@ mod imp {
         }
     }
 
+    pub const DEFAULT_ALT_STACK_SIZE: usize = SIGSTKSZ;
+
     static mut MAIN_ALTSTACK: *mut libc::c_void = ptr::null_mut();
+    static mut MAIN_ALTSTACK_SIZE: usize = 0;
 
     pub unsafe fn init() {
         let mut action: sigaction = mem::zeroed();
@@ -118,16 +160,17 @@ mod imp {
 
         let handler = make_handler();
         MAIN_ALTSTACK = handler._data;
+        MAIN_ALTSTACK_SIZE = handler._size;
         mem::forget(handler);
     }
 
     pub unsafe fn cleanup() {
-        Handler { _data: MAIN_ALTSTACK };
+        Handler { _data: MAIN_ALTSTACK, _size: MAIN_ALTSTACK_SIZE };
     }
 
-    unsafe fn get_stackp() -> *mut libc::c_void {
+    unsafe fn get_stackp(size: usize) -> *mut libc::c_void {
         let stackp = mmap(ptr::null_mut(),
-                          SIGSTKSZ,
+                          size,
                           PROT_READ | PROT_WRITE,
                           MAP_PRIVATE | MAP_ANON,
                           -1,
@@ -144,14 +187,14 @@ mod imp {
               target_os = "netbsd",
               target_os = "openbsd",
               target_os = "solaris"))]
-    unsafe fn get_stack() -> libc::stack_t {
-        libc::stack_t { ss_sp: get_stackp(), ss_flags: 0, ss_size: SIGSTKSZ }
+    unsafe fn get_stack(size: usize) -> libc::stack_t {
+        libc::stack_t { ss_sp: get_stackp(size), ss_flags: 0, ss_size: size }
     }
 
     #[cfg(any(target_os = "freebsd",
               target_os = "dragonfly"))]
-    unsafe fn get_stack() -> libc::stack_t {
-        libc::stack_t { ss_sp: get_stackp() as *mut i8, ss_flags: 0, ss_size: SIGSTKSZ }
+    unsafe fn get_stack(size: usize) -> libc::stack_t {
+        libc::stack_t { ss_sp: get_stackp(size) as *mut i8, ss_flags: 0, ss_size: size }
     }
 
     pub unsafe fn make_handler() -> Handler {
@@ -159,11 +202,13 @@ mod imp {
         sigaltstack(ptr::null(), &mut stack);
         // Configure alternate signal stack, if one is not already set.
         if stack.ss_flags & SS_DISABLE != 0 {
-            stack = get_stack();
+            // Never go below what the platform deems enough for a handler.
+            let size = cmp::max(super::alt_stack_size(), SIGSTKSZ);
+            stack = get_stack(size);
             sigaltstack(&stack, ptr::null_mut());
-            Handler { _data: stack.ss_sp as *mut libc::c_void }
+            Handler { _data: stack.ss_sp as *mut libc::c_void, _size: size }
         } else {
-            Handler { _data: ptr::null_mut() }
+            Handler { _data: ptr::null_mut(), _size: 0 }
         }
     }
 
@@ -179,7 +224,7 @@ mod imp {
                 ss_size: SIGSTKSZ,
             };
             sigaltstack(&stack, ptr::null_mut());
-            munmap(handler._data, SIGSTKSZ);
+            munmap(handler._data, handler._size);
         }
     }
 }
@@ -195,6 +240,9 @@ mod imp {
 mod imp {
     use ptr;
 
+    // No alternate signal stacks are made.
+    pub const DEFAULT_ALT_STACK_SIZE: usize = 0;
+
     pub unsafe fn init() {
     }
 
@@ -202,7 +250,7 @@ mod imp {
     }
 
     pub unsafe fn make_handler() -> super::Handler {
-        super::Handler { _data: ptr::null_mut() }
+        super::Handler { _data: ptr::null_mut(), _size: 0 }
     }
 
     pub unsafe fn drop_handler(_handler: &mut super::Handler) {
@@ -9,12 +9,36 @@ pub struct Stderr(());
 impl Stdin {
     pub fn new() -> io::Result<Stdin> { Ok(Stdin(())) }
 
+    #[cfg(not(target_os = "emscripten"))]
     pub fn read(&self, data: &mut [u8]) -> io::Result<usize> {
         let fd = FileDesc::new(libc::STDIN_FILENO);
         let ret = fd.read(data);
         fd.into_raw(); // do not close this FD
         ret
     }
+
+    // Emscripten's TTY reports "nothing typed yet" as EAGAIN. The main
+    // browser thread can't wait for input without freezing the page, so it
+    // gets the error, but on any other thread the read is proxied to the main
+    // thread and we can simply sleep until the page has fed the TTY more
+    // input. That's what makes blocking, interactive reads work in a worker.
+    #[cfg(target_os = "emscripten")]
+    pub fn read(&self, data: &mut [u8]) -> io::Result<usize> {
+        use sys::emscripten;
+
+        let fd = FileDesc::new(libc::STDIN_FILENO);
+        let ret = loop {
+            match fd.read(data) {
+                Err(ref e) if e.raw_os_error() == Some(libc::EAGAIN) &&
+                              !emscripten::on_main_browser_thread() => {
+                    emscripten::sleep_ms(10.0);
+                }
+                ret => break ret,
+            }
+        };
+        fd.into_raw(); // do not close this FD
+        ret
+    }
 }
 
 impl Stdout {
@@ -109,13 +109,20 @@ impl Thread {
         }
     }
 
-    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    #[cfg(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))]
     pub fn set_name(name: &CStr) {
         unsafe {
             libc::pthread_setname_np(name.as_ptr());
         }
     }
 
+    #[cfg(target_os = "nto")]
+    pub fn set_name(name: &CStr) {
+        unsafe {
+            libc::pthread_setname_np(libc::pthread_self(), name.as_ptr());
+        }
+    }
+
     #[cfg(target_os = "netbsd")]
     pub fn set_name(name: &CStr) {
         use ffi::CString;
@@ -196,6 +203,8 @@ impl Drop for Thread {
           not(target_os = "openbsd"),
           not(target_os = "solaris")))]
 #[cfg_attr(test, allow(dead_code))]
+// QNX does map guard pages below thread stacks, but has no
+// `pthread_getattr_np` to tell us where they are.
 pub mod guard {
     use ops::Range;
     pub type Guard = Range<usize>;
@@ -417,11 +426,19 @@ fn min_stack_size(attr: *const libc::pthread_attr_t) -> usize {
 // No point in looking up __pthread_get_minstack() on non-glibc
 // platforms.
 #[cfg(all(not(target_os = "linux"),
-          not(target_os = "netbsd")))]
+          not(target_os = "netbsd"),
+          not(target_os = "nto")))]
 fn min_stack_size(_: *const libc::pthread_attr_t) -> usize {
     libc::PTHREAD_STACK_MIN
 }
 
+// QNX's PTHREAD_STACK_MIN is a mere 256 bytes, which doesn't leave room for
+// the thread's TLS block, let alone any of our code.
+#[cfg(target_os = "nto")]
+fn min_stack_size(_: *const libc::pthread_attr_t) -> usize {
+    ::cmp::max(libc::PTHREAD_STACK_MIN, 4096)
+}
+
 #[cfg(target_os = "netbsd")]
 fn min_stack_size(_: *const libc::pthread_attr_t) -> usize {
     2048 // just a guess
@@ -4,6 +4,8 @@ use time::Duration;
 use core::hash::{Hash, Hasher};
 
 pub use self::inner::{Instant, SystemTime, UNIX_EPOCH};
+#[cfg(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))]
+pub use self::inner::mach_timebase;
 use convert::TryInto;
 
 const NSEC_PER_SEC: u64 = 1_000_000_000;
@@ -111,7 +113,7 @@ impl Hash for Timespec {
     }
 }
 
-#[cfg(any(target_os = "macos", target_os = "ios"))]
+#[cfg(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))]
 mod inner {
     use fmt;
     use libc;
@@ -214,6 +216,12 @@ mod inner {
         }
     }
 
+    impl From<SystemTime> for libc::timespec {
+        fn from(t: SystemTime) -> libc::timespec {
+            t.t.t
+        }
+    }
+
     impl fmt::Debug for SystemTime {
         fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
             f.debug_struct("SystemTime")
@@ -231,6 +239,13 @@ mod inner {
         Some(mul_div_u64(nanos, info.denom as u64, info.numer as u64))
     }
 
+    /// The `(numer, denom)` ratio converting `mach_absolute_time` ticks to
+    /// nanoseconds.
+    pub fn mach_timebase() -> (u32, u32) {
+        let info = info();
+        (info.numer, info.denom)
+    }
+
     fn info() -> &'static libc::mach_timebase_info {
         static mut INFO: libc::mach_timebase_info = libc::mach_timebase_info {
             numer: 0,
@@ -247,7 +262,7 @@ mod inner {
     }
 }
 
-#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+#[cfg(not(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos")))]
 mod inner {
     use fmt;
     use libc;
@@ -271,10 +286,28 @@ mod inner {
     };
 
     impl Instant {
+        #[cfg(not(target_os = "espidf"))]
         pub fn now() -> Instant {
             Instant { t: now(libc::CLOCK_MONOTONIC) }
         }
 
+        // Everything on ESP-IDF that keeps time is built on esp_timer, a
+        // 64-bit microsecond counter which starts at boot and never wraps.
+        #[cfg(target_os = "espidf")]
+        pub fn now() -> Instant {
+            extern { fn esp_timer_get_time() -> i64; }
+
+            let us = unsafe { esp_timer_get_time() };
+            Instant {
+                t: Timespec {
+                    t: libc::timespec {
+                        tv_sec: (us / 1_000_000) as libc::time_t,
+                        tv_nsec: ((us % 1_000_000) * 1_000) as _,
+                    },
+                },
+            }
+        }
+
         pub const fn zero() -> Instant {
             Instant {
                 t: Timespec::zero(),
@@ -284,6 +317,7 @@ mod inner {
         pub fn actually_monotonic() -> bool {
             (cfg!(target_os = "linux") && cfg!(target_arch = "x86_64")) ||
             (cfg!(target_os = "linux") && cfg!(target_arch = "x86")) ||
+            cfg!(target_os = "espidf") ||
             false // last clause, used so `||` is always trailing above
         }
 
@@ -336,6 +370,12 @@ mod inner {
         }
     }
 
+    impl From<SystemTime> for libc::timespec {
+        fn from(t: SystemTime) -> libc::timespec {
+            t.t.t
+        }
+    }
+
     impl fmt::Debug for SystemTime {
         fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
             f.debug_struct("SystemTime")
@@ -0,0 +1,99 @@
+//! Global initialization and retrieval of command line arguments.
+//!
+//! The RTP loader builds the startup block of a new process (argc, argv,
+//! envp and the auxiliary vector) from the arguments given to `rtpSpawn`,
+//! and the C runtime's `_start` hands argc and argv on to `main`. We keep a
+//! copy of those pointers when the runtime is initialized. The environment
+//! half of the block is published by the C runtime as `environ`, see
+//! `sys::os`.
+
+#![allow(dead_code)] // runtime init functions not used during testing
+
+use ffi::{CStr, OsStr, OsString};
+use io;
+use libc;
+use marker::PhantomData;
+use os::unix::prelude::*;
+use ptr;
+use sys_common::mutex::Mutex;
+use vec;
+
+static mut ARGC: isize = 0;
+static mut ARGV: *const *const u8 = ptr::null();
+// We never call `LOCK.init()`, so it is UB to attempt to
+// acquire this mutex reentrantly!
+static LOCK: Mutex = Mutex::new();
+
+/// One-time global initialization.
+pub unsafe fn init(argc: isize, argv: *const *const u8) {
+    let _guard = LOCK.lock();
+    ARGC = argc;
+    ARGV = argv;
+}
+
+/// One-time global cleanup.
+pub unsafe fn cleanup() {
+    let _guard = LOCK.lock();
+    ARGC = 0;
+    ARGV = ptr::null();
+}
+
+/// Returns the command line arguments
+pub fn args() -> Args {
+    Args {
+        iter: clone().into_iter(),
+        _dont_send_or_sync_me: PhantomData
+    }
+}
+
+/// Returns the `argc` and `argv` the process was started with.
+pub fn raw() -> (isize, *const *const u8) {
+    unsafe {
+        let _guard = LOCK.lock();
+        (ARGC, ARGV)
+    }
+}
+
+pub fn set_name(_name: &OsStr) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Other,
+                       "setting the process name is not supported on this platform"))
+}
+
+fn clone() -> Vec<OsString> {
+    unsafe {
+        let _guard = LOCK.lock();
+        (0..ARGC).map(|i| {
+            let cstr = CStr::from_ptr(*ARGV.offset(i) as *const libc::c_char);
+            OsStringExt::from_vec(cstr.to_bytes().to_vec())
+        }).collect()
+    }
+}
+
+pub struct Args {
+    iter: vec::IntoIter<OsString>,
+    _dont_send_or_sync_me: PhantomData<*mut ()>,
+}
+
+impl Args {
+    pub fn inner_debug(&self) -> &[OsString] {
+        self.iter.as_slice()
+    }
+
+    pub fn get(&self, i: usize) -> Option<OsString> {
+        self.iter.as_slice().get(i).cloned()
+    }
+}
+
+impl Iterator for Args {
+    type Item = OsString;
+    fn next(&mut self) -> Option<OsString> { self.iter.next() }
+    fn size_hint(&self) -> (usize, Option<usize>) { self.iter.size_hint() }
+}
+
+impl ExactSizeIterator for Args {
+    fn len(&self) -> usize { self.iter.len() }
+}
+
+impl DoubleEndedIterator for Args {
+    fn next_back(&mut self) -> Option<OsString> { self.iter.next_back() }
+}
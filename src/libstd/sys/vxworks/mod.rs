@@ -0,0 +1,145 @@
+//! System bindings for VxWorks real-time processes (RTPs)
+//!
+//! An RTP is a POSIX-flavoured user-mode process, so most of this module is
+//! shared with the unix backend: files, sockets, pipes, locks and clocks all
+//! go through the same libc calls. What differs lives here. Threads are
+//! native tasks created through taskLib, child processes are started with
+//! `rtpSpawn` since there's no `fork`, and errno is reached through
+//! `errnoGet`/`errnoSet`.
+
+#![allow(missing_docs, nonstandard_style)]
+
+use io::{self, ErrorKind};
+use libc;
+
+#[cfg(rustdoc)]      pub use os::linux as platform;
+#[cfg(not(rustdoc))] pub use os::vxworks as platform;
+
+pub use self::rand::hashmap_random_keys;
+pub use libc::strlen;
+
+#[macro_use]
+#[path = "../unix/weak.rs"]
+pub mod weak;
+
+#[path = "../unix/alloc.rs"]
+pub mod alloc;
+pub mod args;
+#[cfg(feature = "backtrace")]
+#[path = "../unix/backtrace/mod.rs"]
+pub mod backtrace;
+#[path = "../unix/cmath.rs"]
+pub mod cmath;
+#[path = "../unix/condvar.rs"]
+pub mod condvar;
+pub mod env;
+#[path = "../unix/ext/mod.rs"]
+pub mod ext;
+#[path = "../unix/fast_thread_local.rs"]
+pub mod fast_thread_local;
+#[path = "../unix/fd.rs"]
+pub mod fd;
+#[path = "../unix/fs.rs"]
+pub mod fs;
+#[path = "../unix/memchr.rs"]
+pub mod memchr;
+#[path = "../unix/mutex.rs"]
+pub mod mutex;
+#[path = "../unix/net.rs"]
+pub mod net;
+pub mod os;
+#[path = "../unix/os_str.rs"]
+pub mod os_str;
+#[path = "../unix/path.rs"]
+pub mod path;
+#[path = "../unix/pipe.rs"]
+pub mod pipe;
+pub mod process;
+pub mod rand;
+#[path = "../unix/rwlock.rs"]
+pub mod rwlock;
+#[path = "../unix/stack_overflow.rs"]
+pub mod stack_overflow;
+pub mod thread;
+#[path = "../unix/thread_local.rs"]
+pub mod thread_local;
+#[path = "../unix/time.rs"]
+pub mod time;
+#[path = "../unix/stdio.rs"]
+pub mod stdio;
+
+#[cfg(not(test))]
+pub fn init() {
+    // Same as on unix: the default action for SIGPIPE would kill the RTP
+    // when writing to a closed socket, so ignore it and get EPIPE instead.
+    unsafe {
+        assert!(signal(libc::SIGPIPE, libc::SIG_IGN) != libc::SIG_ERR);
+    }
+}
+
+pub use libc::signal;
+
+pub fn decode_error_kind(errno: i32) -> ErrorKind {
+    match errno as libc::c_int {
+        libc::ECONNREFUSED => ErrorKind::ConnectionRefused,
+        libc::ECONNRESET => ErrorKind::ConnectionReset,
+        libc::EPERM | libc::EACCES => ErrorKind::PermissionDenied,
+        libc::EPIPE => ErrorKind::BrokenPipe,
+        libc::ENOTCONN => ErrorKind::NotConnected,
+        libc::ECONNABORTED => ErrorKind::ConnectionAborted,
+        libc::EADDRNOTAVAIL => ErrorKind::AddrNotAvailable,
+        libc::EADDRINUSE => ErrorKind::AddrInUse,
+        libc::ENOENT => ErrorKind::NotFound,
+        libc::EINTR => ErrorKind::Interrupted,
+        libc::EINVAL => ErrorKind::InvalidInput,
+        libc::ETIMEDOUT => ErrorKind::TimedOut,
+        libc::EEXIST => ErrorKind::AlreadyExists,
+
+        // These two constants can have the same value on some systems,
+        // but different values on others, so we can't use a match
+        // clause
+        x if x == libc::EAGAIN || x == libc::EWOULDBLOCK =>
+            ErrorKind::WouldBlock,
+
+        _ => ErrorKind::Other,
+    }
+}
+
+#[doc(hidden)]
+pub trait IsMinusOne {
+    fn is_minus_one(&self) -> bool;
+}
+
+macro_rules! impl_is_minus_one {
+    ($($t:ident)*) => ($(impl IsMinusOne for $t {
+        fn is_minus_one(&self) -> bool {
+            *self == -1
+        }
+    })*)
+}
+
+impl_is_minus_one! { i8 i16 i32 i64 isize }
+
+pub fn cvt<T: IsMinusOne>(t: T) -> io::Result<T> {
+    if t.is_minus_one() {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(t)
+    }
+}
+
+pub fn cvt_r<T, F>(mut f: F) -> io::Result<T>
+    where T: IsMinusOne,
+          F: FnMut() -> T
+{
+    loop {
+        match cvt(f()) {
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+            other => return other,
+        }
+    }
+}
+
+pub unsafe fn abort_internal() -> ! {
+    ::libc::abort()
+}
@@ -0,0 +1,338 @@
+//! Implementation of `std::os` functionality for VxWorks RTPs
+
+use os::unix::prelude::*;
+
+use cell::Cell;
+use error::Error as StdError;
+use ffi::{CString, CStr, OsString, OsStr};
+use fmt;
+use io;
+use iter;
+use libc::{self, c_int, c_char};
+use marker::PhantomData;
+use memchr;
+use path::{self, PathBuf};
+use ptr;
+use slice;
+use sys_common::mutex::{Mutex, MutexGuard};
+use sys::cvt;
+use vec;
+
+const TMPBUF_SZ: usize = 128;
+
+extern {
+    // errno is a per-task variable on VxWorks, these are the supported
+    // way of getting at it from an RTP.
+    fn errnoGet() -> c_int;
+    fn errnoSet(errno: c_int) -> c_int;
+}
+
+/// Returns the platform-specific value of errno
+pub fn errno() -> i32 {
+    unsafe { errnoGet() as i32 }
+}
+
+/// Sets the platform-specific value of errno
+pub fn set_errno(e: i32) {
+    unsafe {
+        errnoSet(e as c_int);
+    }
+}
+
+/// Gets a detailed string description for the given error number.
+pub fn error_string(errno: i32) -> String {
+    extern {
+        fn strerror_r(errnum: c_int, buf: *mut c_char,
+                      buflen: libc::size_t) -> c_int;
+    }
+
+    let mut buf = [0 as c_char; TMPBUF_SZ];
+
+    let p = buf.as_mut_ptr();
+    unsafe {
+        if strerror_r(errno as c_int, p, buf.len()) < 0 {
+            panic!("strerror_r failure");
+        }
+
+        let p = p as *const _;
+        String::from_utf8_lossy(CStr::from_ptr(p).to_bytes()).into_owned()
+    }
+}
+
+pub fn getcwd() -> io::Result<PathBuf> {
+    let mut buf = Vec::with_capacity(512);
+    loop {
+        unsafe {
+            let ptr = buf.as_mut_ptr() as *mut libc::c_char;
+            if !libc::getcwd(ptr, buf.capacity()).is_null() {
+                let len = CStr::from_ptr(buf.as_ptr() as *const libc::c_char).to_bytes().len();
+                buf.set_len(len);
+                buf.shrink_to_fit();
+                return Ok(PathBuf::from(OsString::from_vec(buf)));
+            } else {
+                let error = io::Error::last_os_error();
+                if error.raw_os_error() != Some(libc::ERANGE) {
+                    return Err(error);
+                }
+            }
+
+            // Trigger the internal buffer resizing logic of `Vec` by requiring
+            // more space than the current capacity.
+            let cap = buf.capacity();
+            buf.set_len(cap);
+            buf.reserve(1);
+        }
+    }
+}
+
+pub fn chdir(p: &path::Path) -> io::Result<()> {
+    let p: &OsStr = p.as_ref();
+    let p = CString::new(p.as_bytes())?;
+    unsafe {
+        cvt(libc::chdir(p.as_ptr())).map(|_| ())
+    }
+}
+
+pub struct SplitPaths<'a> {
+    iter: iter::Map<slice::Split<'a, u8, fn(&u8) -> bool>,
+                    fn(&'a [u8]) -> PathBuf>,
+}
+
+pub fn split_paths(unparsed: &OsStr) -> SplitPaths {
+    fn bytes_to_path(b: &[u8]) -> PathBuf {
+        PathBuf::from(<OsStr as OsStrExt>::from_bytes(b))
+    }
+    fn is_colon(b: &u8) -> bool { *b == b':' }
+    let unparsed = unparsed.as_bytes();
+    SplitPaths {
+        iter: unparsed.split(is_colon as fn(&u8) -> bool)
+                      .map(bytes_to_path as fn(&[u8]) -> PathBuf)
+    }
+}
+
+impl<'a> Iterator for SplitPaths<'a> {
+    type Item = PathBuf;
+    fn next(&mut self) -> Option<PathBuf> { self.iter.next() }
+    fn size_hint(&self) -> (usize, Option<usize>) { self.iter.size_hint() }
+}
+
+#[derive(Debug)]
+pub struct JoinPathsError;
+
+pub fn join_paths<I, T>(paths: I) -> Result<OsString, JoinPathsError>
+    where I: Iterator<Item=T>, T: AsRef<OsStr>
+{
+    let mut joined = Vec::new();
+    let sep = b':';
+
+    for (i, path) in paths.enumerate() {
+        let path = path.as_ref().as_bytes();
+        if i > 0 { joined.push(sep) }
+        if path.contains(&sep) {
+            return Err(JoinPathsError)
+        }
+        joined.extend_from_slice(path);
+    }
+    Ok(OsStringExt::from_vec(joined))
+}
+
+impl fmt::Display for JoinPathsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        "path segment contains separator `:`".fmt(f)
+    }
+}
+
+impl StdError for JoinPathsError {
+    fn description(&self) -> &str { "failed to join paths" }
+}
+
+/// There's no way to ask the kernel which file an RTP was loaded from, but
+/// `rtpSpawn` has no `PATH` search either, so `argv[0]` is the path that was
+/// actually loaded.
+pub fn current_exe() -> io::Result<PathBuf> {
+    match super::args::args().next() {
+        Some(exe) => ::fs::canonicalize(exe),
+        None => Err(io::Error::new(io::ErrorKind::Other,
+                                   "no program name in the RTP arguments")),
+    }
+}
+
+pub struct Env {
+    iter: vec::IntoIter<(OsString, OsString)>,
+    _dont_send_or_sync_me: PhantomData<*mut ()>,
+}
+
+impl Iterator for Env {
+    type Item = (OsString, OsString);
+    fn next(&mut self) -> Option<(OsString, OsString)> { self.iter.next() }
+    fn size_hint(&self) -> (usize, Option<usize>) { self.iter.size_hint() }
+}
+
+/// The environment half of the RTP startup block, as set up by the C
+/// runtime from the `envp` passed to `rtpSpawn`.
+pub unsafe fn environ() -> *mut *const *const c_char {
+    extern { static mut environ: *const *const c_char; }
+    &mut environ
+}
+
+// We never call `ENV_LOCK.init()`, so it is UB to attempt to
+// acquire this mutex reentrantly!
+static ENV_LOCK: Mutex = Mutex::new();
+
+thread_local! {
+    // Whether this thread holds `ENV_LOCK` through `try_env_lock`, in which
+    // case the environment functions it calls mustn't lock it again.
+    static ENV_LOCK_HELD: Cell<bool> = Cell::new(false)
+}
+
+fn env_lock_held() -> bool {
+    ENV_LOCK_HELD.try_with(|held| held.get()).unwrap_or(false)
+}
+
+pub struct EnvLockGuard {
+    _guard: Option<MutexGuard<'static>>,
+}
+
+pub unsafe fn env_lock() -> EnvLockGuard {
+    if env_lock_held() {
+        EnvLockGuard { _guard: None }
+    } else {
+        EnvLockGuard { _guard: Some(ENV_LOCK.lock()) }
+    }
+}
+
+/// Holds the environment lock for code outside the standard library, which
+/// the environment functions called on the same thread meanwhile skip.
+pub struct EnvLockHandle {
+    _not_send_or_sync: PhantomData<*mut ()>,
+}
+
+pub fn try_env_lock() -> Option<EnvLockHandle> {
+    unsafe {
+        if env_lock_held() || !ENV_LOCK.try_lock() {
+            return None;
+        }
+        let _ = ENV_LOCK_HELD.try_with(|held| held.set(true));
+        Some(EnvLockHandle { _not_send_or_sync: PhantomData })
+    }
+}
+
+impl Drop for EnvLockHandle {
+    fn drop(&mut self) {
+        let _ = ENV_LOCK_HELD.try_with(|held| held.set(false));
+        unsafe { ENV_LOCK.raw_unlock() }
+    }
+}
+
+/// Returns a vector of (variable, value) byte-vector pairs for all the
+/// environment variables of the current process.
+pub fn env() -> Env {
+    unsafe {
+        let _guard = env_lock();
+        let mut environ = *environ();
+        let mut result = Vec::new();
+        while environ != ptr::null() && *environ != ptr::null() {
+            if let Some(key_value) = parse(CStr::from_ptr(*environ).to_bytes()) {
+                result.push(key_value);
+            }
+            environ = environ.offset(1);
+        }
+        return Env {
+            iter: result.into_iter(),
+            _dont_send_or_sync_me: PhantomData,
+        }
+    }
+
+    fn parse(input: &[u8]) -> Option<(OsString, OsString)> {
+        // Strategy (copied from glibc): Variable name and value are separated
+        // by an ASCII equals sign '='. Since a variable name must not be
+        // empty, allow variable names starting with an equals sign. Skip all
+        // malformed lines.
+        if input.is_empty() {
+            return None;
+        }
+        let pos = memchr::memchr(b'=', &input[1..]).map(|p| p + 1);
+        pos.map(|p| (
+            OsStringExt::from_vec(input[..p].to_vec()),
+            OsStringExt::from_vec(input[p+1..].to_vec()),
+        ))
+    }
+}
+
+pub use sys_common::env_snapshot::{EnvSnapshot, EnvSnapshotIter};
+
+/// Returns a snapshot of the environment variables of the current process.
+pub fn env_snapshot() -> EnvSnapshot {
+    EnvSnapshot::new(env())
+}
+
+pub fn getenv(k: &OsStr) -> io::Result<Option<OsString>> {
+    // environment variables with a nul byte can't be set, so their value is
+    // always None as well
+    let k = CString::new(k.as_bytes())?;
+    unsafe {
+        let _guard = env_lock();
+        let s = libc::getenv(k.as_ptr()) as *const libc::c_char;
+        let ret = if s.is_null() {
+            None
+        } else {
+            Some(OsStringExt::from_vec(CStr::from_ptr(s).to_bytes().to_vec()))
+        };
+        Ok(ret)
+    }
+}
+
+pub fn setenv(k: &OsStr, v: &OsStr) -> io::Result<()> {
+    let k = CString::new(k.as_bytes())?;
+    let v = CString::new(v.as_bytes())?;
+
+    unsafe {
+        let _guard = env_lock();
+        cvt(libc::setenv(k.as_ptr(), v.as_ptr(), 1)).map(|_| ())
+    }
+}
+
+pub fn unsetenv(n: &OsStr) -> io::Result<()> {
+    let nbuf = CString::new(n.as_bytes())?;
+
+    unsafe {
+        let _guard = env_lock();
+        cvt(libc::unsetenv(nbuf.as_ptr())).map(|_| ())
+    }
+}
+
+pub fn page_size() -> usize {
+    unsafe {
+        libc::sysconf(libc::_SC_PAGESIZE) as usize
+    }
+}
+
+pub fn temp_dir() -> PathBuf {
+    ::env::var_os("TMPDIR").map(PathBuf::from).unwrap_or_else(|| {
+        PathBuf::from("/tmp")
+    })
+}
+
+pub fn home_dir() -> Option<PathBuf> {
+    // There's no password database to fall back on.
+    ::env::var_os("HOME").map(PathBuf::from)
+}
+
+pub fn user_home_dir() -> Option<PathBuf> {
+    match ::env::var_os("HOME") {
+        Some(ref home) if !home.is_empty() => Some(PathBuf::from(home)),
+        _ => None,
+    }
+}
+
+pub fn exit(code: i32) -> ! {
+    unsafe { libc::exit(code as c_int) }
+}
+
+pub fn getpid() -> u32 {
+    unsafe { libc::getpid() as u32 }
+}
+
+pub fn getppid() -> u32 {
+    unsafe { libc::getppid() as u32 }
+}
@@ -0,0 +1,231 @@
+use io::{self, Error, ErrorKind};
+use libc::{self, c_char, c_int, pid_t, size_t};
+use os::unix::prelude::*;
+use sys::cvt;
+use sys::process::process_common::*;
+use sys::thread;
+use sys;
+use sys_common::process;
+use time::Instant;
+
+type RTP_ID = c_int;
+
+const RTP_ID_ERROR: RTP_ID = -1;
+const VX_FP_TASK: c_int = 0x0100_0000;
+
+extern {
+    fn rtpSpawn(path: *const c_char, argv: *const *const c_char,
+                envp: *const *const c_char, priority: c_int,
+                stack_size: size_t, options: c_int,
+                task_options: c_int) -> RTP_ID;
+    fn taskIdSelf() -> c_int;
+    fn taskPriorityGet(tid: c_int, priority: *mut c_int) -> c_int;
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Command
+////////////////////////////////////////////////////////////////////////////////
+
+impl Command {
+    /// Starts the program in a new RTP.
+    ///
+    /// There's no `fork`: `rtpSpawn` loads the executable straight into a
+    /// fresh process which inherits our file descriptors and current
+    /// directory. The child's stdio and cwd are therefore set up by
+    /// temporarily changing our own, with the environment lock held so no
+    /// other thread observes (or spawns in) that window.
+    pub fn spawn(&mut self, default: Stdio, needs_stdin: bool)
+                 -> io::Result<(Process, StdioPipes)> {
+        let envp = self.capture_env();
+
+        if self.saw_nul() {
+            return Err(io::Error::new(ErrorKind::InvalidInput,
+                                      "nul byte found in provided data"));
+        }
+        if self.get_uid().is_some() || self.get_gid().is_some() || self.get_groups().is_some() {
+            return Err(io::Error::new(ErrorKind::Other,
+                                      "changing the user of a child RTP is not supported"));
+        }
+        if !self.get_closures().is_empty() {
+            return Err(io::Error::new(ErrorKind::Other,
+                                      "pre-exec closures can't run in a spawned RTP"));
+        }
+        if !self.get_fd_mappings().0.is_empty() {
+            return Err(io::Error::new(ErrorKind::Other,
+                                      "mapping file descriptors into an RTP is not supported"));
+        }
+        if self.get_pgroup().is_some() || self.get_new_session() {
+            return Err(io::Error::new(ErrorKind::Other,
+                                      "RTPs have no process groups or sessions"));
+        }
+        if self.get_parent_death_signal().is_some() {
+            return Err(io::Error::new(ErrorKind::Other,
+                                      "RTPs can't be signalled when their parent exits"));
+        }
+
+        let (ours, theirs) = self.setup_io(default, needs_stdin)?;
+
+        let mut priority = 0;
+        cvt(unsafe { taskPriorityGet(taskIdSelf(), &mut priority) })?;
+
+        let rtp = unsafe {
+            let _lock = sys::os::env_lock();
+            let _stdio = Redirect::new(&theirs)?;
+            let _cwd = match *self.get_cwd() {
+                Some(ref cwd) => Some(Chdir::new(cwd.as_ptr())?),
+                None => None,
+            };
+            let envp = match envp {
+                Some(ref envp) => envp.as_ptr(),
+                None => *sys::os::environ(),
+            };
+            rtpSpawn(self.get_program_cstr().as_ptr(), self.get_argv().as_ptr(), envp,
+                     priority, thread::DEFAULT_MIN_STACK_SIZE, 0, VX_FP_TASK)
+        };
+        if rtp == RTP_ID_ERROR {
+            return Err(io::Error::last_os_error())
+        }
+
+        Ok((Process { pid: rtp as pid_t, status: None }, ours))
+    }
+
+    pub fn exec(&mut self, _default: Stdio) -> io::Error {
+        io::Error::new(ErrorKind::Other,
+                       "an RTP can't replace its own program image")
+    }
+}
+
+/// Points our stdio at the child's for as long as it's alive, restoring the
+/// original descriptors on drop.
+struct Redirect {
+    saved: [Option<c_int>; 3],
+}
+
+impl Redirect {
+    unsafe fn new(stdio: &ChildPipes) -> io::Result<Redirect> {
+        let mut ret = Redirect { saved: [None; 3] };
+        let fds = [stdio.stdin.fd(), stdio.stdout.fd(), stdio.stderr.fd()];
+        for (target, fd) in fds.iter().enumerate() {
+            if let Some(fd) = *fd {
+                let target = target as c_int;
+                ret.saved[target as usize] = Some(cvt(libc::dup(target))?);
+                cvt(libc::dup2(fd, target))?;
+            }
+        }
+        Ok(ret)
+    }
+}
+
+impl Drop for Redirect {
+    fn drop(&mut self) {
+        for (target, saved) in self.saved.iter().enumerate() {
+            if let Some(saved) = *saved {
+                unsafe {
+                    libc::dup2(saved, target as c_int);
+                    libc::close(saved);
+                }
+            }
+        }
+    }
+}
+
+/// Changes our working directory for as long as it's alive.
+struct Chdir {
+    saved: Vec<u8>,
+}
+
+impl Chdir {
+    unsafe fn new(dir: *const c_char) -> io::Result<Chdir> {
+        let mut saved = sys::os::getcwd()?.into_os_string().into_vec();
+        saved.push(0);
+        cvt(libc::chdir(dir))?;
+        Ok(Chdir { saved })
+    }
+}
+
+impl Drop for Chdir {
+    fn drop(&mut self) {
+        unsafe {
+            libc::chdir(self.saved.as_ptr() as *const c_char);
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Processes
+////////////////////////////////////////////////////////////////////////////////
+
+/// The RTP ID of the child, which doubles as its pid.
+pub struct Process {
+    pid: pid_t,
+    status: Option<ExitStatus>,
+}
+
+impl Process {
+    pub fn id(&self) -> u32 {
+        self.pid as u32
+    }
+
+    pub fn kill(&mut self) -> io::Result<()> {
+        // If we've already waited on this process then the pid can be recycled
+        // and used for another process, and we probably shouldn't be killing
+        // random processes, so just return an error.
+        if self.status.is_some() {
+            Err(Error::new(ErrorKind::InvalidInput,
+                           "invalid argument: can't kill an exited process"))
+        } else {
+            cvt(unsafe { libc::kill(self.pid, libc::SIGKILL) }).map(|_| ())
+        }
+    }
+
+    pub fn send_signal(&mut self, signal: c_int) -> io::Result<()> {
+        if self.status.is_some() {
+            Err(Error::new(ErrorKind::InvalidInput,
+                           "invalid argument: can't signal an exited process"))
+        } else {
+            cvt(unsafe { libc::kill(self.pid, signal) }).map(|_| ())
+        }
+    }
+
+    pub fn wait(&mut self) -> io::Result<ExitStatus> {
+        use sys::cvt_r;
+        if let Some(status) = self.status {
+            return Ok(status)
+        }
+        let mut status = 0 as c_int;
+        cvt_r(|| unsafe { libc::waitpid(self.pid, &mut status, 0) })?;
+        self.status = Some(ExitStatus::new(status));
+        Ok(ExitStatus::new(status))
+    }
+
+    // Without SIGCHLD there's nothing to share, so each child simply gets
+    // a thread of its own waiting for it.
+    pub fn on_exit(mut self, f: Box<dyn FnOnce(io::Result<ExitStatus>) + Send>) {
+        ::thread::spawn(move || f(self.wait()));
+    }
+
+    // There's no job control, so the only change is the exit.
+    pub fn wait_state_change(&mut self) -> io::Result<ExitStatus> {
+        self.wait()
+    }
+
+    pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
+        if let Some(status) = self.status {
+            return Ok(Some(status))
+        }
+        let mut status = 0 as c_int;
+        let pid = cvt(unsafe {
+            libc::waitpid(self.pid, &mut status, libc::WNOHANG)
+        })?;
+        if pid == 0 {
+            Ok(None)
+        } else {
+            self.status = Some(ExitStatus::new(status));
+            Ok(Some(ExitStatus::new(status)))
+        }
+    }
+
+    pub fn wait_deadline(&mut self, deadline: Instant) -> io::Result<Option<ExitStatus>> {
+        process::poll_until(deadline, || self.try_wait())
+    }
+}
@@ -0,0 +1,46 @@
+use io;
+use libc::{self, c_int, c_uchar};
+use mem;
+use slice;
+use sync::atomic::{AtomicBool, Ordering};
+
+extern {
+    fn randSecure() -> c_int;
+    fn randABytes(buf: *mut c_uchar, len: c_int) -> c_int;
+}
+
+pub fn hashmap_random_keys() -> (u64, u64) {
+    let mut v = (0, 0);
+    unsafe {
+        let view = slice::from_raw_parts_mut(&mut v as *mut _ as *mut u8,
+                                             mem::size_of_val(&v));
+        fill_bytes(view);
+    }
+    return v
+}
+
+/// Fills `v` from the kernel's entropy pool.
+///
+/// `randABytes` doesn't block, it just hands out weaker bytes until the pool
+/// has been seeded, so wait for `randSecure` to say it's ready first. Once
+/// it has been seeded it stays that way.
+fn fill_bytes(v: &mut [u8]) {
+    static SEEDED: AtomicBool = AtomicBool::new(false);
+
+    while !SEEDED.load(Ordering::Relaxed) {
+        match unsafe { randSecure() } {
+            n if n < 0 => panic!("couldn't check the random pool: {}",
+                                 io::Error::last_os_error()),
+            0 => unsafe { libc::usleep(10); },
+            _ => SEEDED.store(true, Ordering::Relaxed),
+        }
+    }
+
+    for chunk in v.chunks_mut(c_int::max_value() as usize) {
+        let ret = unsafe { randABytes(chunk.as_mut_ptr(), chunk.len() as c_int) };
+        if ret < 0 {
+            panic!("couldn't generate random bytes: {}",
+                   io::Error::last_os_error());
+        }
+    }
+}
@@ -0,0 +1,174 @@
+//! Threads as native VxWorks tasks.
+//!
+//! taskLib has no notion of joining a task, so every thread we spawn gets a
+//! binary semaphore which the task gives once the closure has returned. The
+//! semaphore is shared between the task and its `Thread` handle and deleted
+//! by whichever of the two lets go of it last, which makes detaching a
+//! thread (dropping the handle) free.
+
+use boxed::FnBox;
+use cmp;
+use ffi::CStr;
+use io;
+use libc::{self, c_char, c_int, size_t};
+use ptr;
+use sync::Arc;
+use sys::os;
+use time::Duration;
+
+use sys_common::thread::*;
+
+pub const DEFAULT_MIN_STACK_SIZE: usize = 256 * 1024;
+
+// Task stacks are allocated up front and never grow, so don't let callers
+// ask for something which can't even hold the task's own bookkeeping.
+const MIN_TASK_STACK_SIZE: usize = 16 * 1024;
+
+type TASK_ID = c_int;
+type SEM_ID = c_int;
+type STATUS = c_int;
+
+const OK: STATUS = 0;
+const TASK_ID_ERROR: TASK_ID = -1;
+const SEM_ID_NULL: SEM_ID = 0;
+const VX_FP_TASK: c_int = 0x0100_0000;
+const SEM_Q_FIFO: c_int = 0;
+const SEM_EMPTY: c_int = 0;
+const WAIT_FOREVER: c_int = -1;
+
+extern {
+    fn taskSpawn(name: *const c_char, priority: c_int, options: c_int,
+                 stack_size: size_t, entry: extern fn(usize) -> c_int,
+                 arg1: usize, arg2: usize, arg3: usize, arg4: usize,
+                 arg5: usize, arg6: usize, arg7: usize, arg8: usize,
+                 arg9: usize, arg10: usize) -> TASK_ID;
+    fn taskIdSelf() -> TASK_ID;
+    fn taskPriorityGet(tid: TASK_ID, priority: *mut c_int) -> STATUS;
+    fn taskDelay(ticks: c_int) -> STATUS;
+
+    fn semBCreate(options: c_int, initial: c_int) -> SEM_ID;
+    fn semTake(sem: SEM_ID, timeout: c_int) -> STATUS;
+    fn semGive(sem: SEM_ID) -> STATUS;
+    fn semDelete(sem: SEM_ID) -> STATUS;
+}
+
+/// Signalled by the task once its closure has run.
+struct Exited(SEM_ID);
+
+impl Drop for Exited {
+    fn drop(&mut self) {
+        let ret = unsafe { semDelete(self.0) };
+        debug_assert_eq!(ret, OK);
+    }
+}
+
+struct Packet {
+    main: Box<dyn FnBox()>,
+    exited: Arc<Exited>,
+}
+
+pub struct Thread {
+    id: TASK_ID,
+    exited: Arc<Exited>,
+}
+
+unsafe impl Send for Thread {}
+unsafe impl Sync for Thread {}
+
+impl Thread {
+    // unsafe: see thread::Builder::spawn_unchecked for safety requirements
+    pub unsafe fn new(stack: usize, p: Box<dyn FnBox()>)
+                          -> io::Result<Thread> {
+        let sem = semBCreate(SEM_Q_FIFO, SEM_EMPTY);
+        if sem == SEM_ID_NULL {
+            return Err(io::Error::last_os_error())
+        }
+        let exited = Arc::new(Exited(sem));
+
+        // New tasks run at the priority of the one spawning them, the same
+        // as a thread created with default attributes would.
+        let mut priority = 0;
+        if taskPriorityGet(taskIdSelf(), &mut priority) != OK {
+            return Err(io::Error::last_os_error())
+        }
+
+        let stack_size = cmp::max(stack, MIN_TASK_STACK_SIZE);
+        let packet = Box::into_raw(Box::new(Packet {
+            main: p,
+            exited: exited.clone(),
+        }));
+
+        let id = taskSpawn(ptr::null(), priority, VX_FP_TASK, stack_size,
+                           task_start, packet as usize,
+                           0, 0, 0, 0, 0, 0, 0, 0, 0);
+        return if id == TASK_ID_ERROR {
+            let err = io::Error::last_os_error();
+            drop(Box::from_raw(packet));
+            Err(err)
+        } else {
+            Ok(Thread { id, exited })
+        };
+
+        extern fn task_start(packet: usize) -> c_int {
+            unsafe {
+                let Packet { main, exited } = *Box::from_raw(packet as *mut Packet);
+                start_thread(Box::into_raw(Box::new(main)) as *mut u8);
+                semGive(exited.0);
+            }
+            0
+        }
+    }
+
+    pub fn yield_now() {
+        // A delay of zero ticks moves us to the back of the ready queue for
+        // our priority, which is how VxWorks spells "yield".
+        let ret = unsafe { taskDelay(0) };
+        debug_assert_eq!(ret, OK);
+    }
+
+    pub fn set_name(_name: &CStr) {
+        // Task names are fixed when the task is spawned.
+    }
+
+    pub fn sleep(dur: Duration) {
+        let mut secs = dur.as_secs();
+        let mut nsecs = dur.subsec_nanos() as _;
+
+        // If we're awoken with a signal then the return value will be -1 and
+        // nanosleep will fill in `ts` with the remaining time.
+        unsafe {
+            while secs > 0 || nsecs > 0 {
+                let mut ts = libc::timespec {
+                    tv_sec: cmp::min(libc::time_t::max_value() as u64, secs) as libc::time_t,
+                    tv_nsec: nsecs,
+                };
+                secs -= ts.tv_sec as u64;
+                if libc::nanosleep(&ts, &mut ts) == -1 {
+                    assert_eq!(os::errno(), libc::EINTR);
+                    secs += ts.tv_sec as u64;
+                    nsecs = ts.tv_nsec;
+                } else {
+                    nsecs = 0;
+                }
+            }
+        }
+    }
+
+    pub fn join(self) {
+        let ret = unsafe { semTake(self.exited.0, WAIT_FOREVER) };
+        assert!(ret == OK,
+                "failed to join thread: {}", io::Error::last_os_error());
+    }
+
+    pub fn id(&self) -> TASK_ID { self.id }
+
+    pub fn into_id(self) -> TASK_ID { self.id }
+}
+
+#[cfg_attr(test, allow(dead_code))]
+pub mod guard {
+    use ops::Range;
+    pub type Guard = Range<usize>;
+    pub unsafe fn current() -> Option<Guard> { None }
+    pub unsafe fn init() -> Option<Guard> { None }
+}
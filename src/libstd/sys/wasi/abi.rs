@@ -0,0 +1,330 @@
+//! Raw bindings to the `wasi_unstable` host interface.
+//!
+//! These mirror the definitions in `wasi/core.h` of the WASI SDK. We don't
+//! have a `libc` to lean on for this target, so the types and imports are
+//! declared here directly and wrapped by the rest of `sys::wasi`.
+
+#![allow(dead_code, non_camel_case_types)]
+
+pub type errno = u16;
+pub type fd = u32;
+pub type filesize = u64;
+pub type filedelta = i64;
+pub type timestamp = u64;
+pub type clockid = u32;
+pub type rights = u64;
+pub type oflags = u16;
+pub type fdflags = u16;
+pub type lookupflags = u32;
+pub type whence = u8;
+pub type filetype = u8;
+pub type dircookie = u64;
+pub type device = u64;
+pub type inode = u64;
+pub type linkcount = u32;
+pub type advice = u8;
+pub type exitcode = u32;
+
+pub const ESUCCESS: errno = 0;
+pub const E2BIG: errno = 1;
+pub const EACCES: errno = 2;
+pub const EADDRINUSE: errno = 3;
+pub const EADDRNOTAVAIL: errno = 4;
+pub const EAFNOSUPPORT: errno = 5;
+pub const EAGAIN: errno = 6;
+pub const EALREADY: errno = 7;
+pub const EBADF: errno = 8;
+pub const EBADMSG: errno = 9;
+pub const EBUSY: errno = 10;
+pub const ECANCELED: errno = 11;
+pub const ECHILD: errno = 12;
+pub const ECONNABORTED: errno = 13;
+pub const ECONNREFUSED: errno = 14;
+pub const ECONNRESET: errno = 15;
+pub const EDEADLK: errno = 16;
+pub const EDESTADDRREQ: errno = 17;
+pub const EDOM: errno = 18;
+pub const EDQUOT: errno = 19;
+pub const EEXIST: errno = 20;
+pub const EFAULT: errno = 21;
+pub const EFBIG: errno = 22;
+pub const EHOSTUNREACH: errno = 23;
+pub const EIDRM: errno = 24;
+pub const EILSEQ: errno = 25;
+pub const EINPROGRESS: errno = 26;
+pub const EINTR: errno = 27;
+pub const EINVAL: errno = 28;
+pub const EIO: errno = 29;
+pub const EISCONN: errno = 30;
+pub const EISDIR: errno = 31;
+pub const ELOOP: errno = 32;
+pub const EMFILE: errno = 33;
+pub const EMLINK: errno = 34;
+pub const EMSGSIZE: errno = 35;
+pub const EMULTIHOP: errno = 36;
+pub const ENAMETOOLONG: errno = 37;
+pub const ENETDOWN: errno = 38;
+pub const ENETRESET: errno = 39;
+pub const ENETUNREACH: errno = 40;
+pub const ENFILE: errno = 41;
+pub const ENOBUFS: errno = 42;
+pub const ENODEV: errno = 43;
+pub const ENOENT: errno = 44;
+pub const ENOEXEC: errno = 45;
+pub const ENOLCK: errno = 46;
+pub const ENOLINK: errno = 47;
+pub const ENOMEM: errno = 48;
+pub const ENOMSG: errno = 49;
+pub const ENOPROTOOPT: errno = 50;
+pub const ENOSPC: errno = 51;
+pub const ENOSYS: errno = 52;
+pub const ENOTCONN: errno = 53;
+pub const ENOTDIR: errno = 54;
+pub const ENOTEMPTY: errno = 55;
+pub const ENOTRECOVERABLE: errno = 56;
+pub const ENOTSOCK: errno = 57;
+pub const ENOTSUP: errno = 58;
+pub const ENOTTY: errno = 59;
+pub const ENXIO: errno = 60;
+pub const EOVERFLOW: errno = 61;
+pub const EOWNERDEAD: errno = 62;
+pub const EPERM: errno = 63;
+pub const EPIPE: errno = 64;
+pub const EPROTO: errno = 65;
+pub const EPROTONOSUPPORT: errno = 66;
+pub const EPROTOTYPE: errno = 67;
+pub const ERANGE: errno = 68;
+pub const EROFS: errno = 69;
+pub const ESPIPE: errno = 70;
+pub const ESRCH: errno = 71;
+pub const ESTALE: errno = 72;
+pub const ETIMEDOUT: errno = 73;
+pub const ETXTBSY: errno = 74;
+pub const EXDEV: errno = 75;
+pub const ENOTCAPABLE: errno = 76;
+
+pub const CLOCK_REALTIME: clockid = 0;
+pub const CLOCK_MONOTONIC: clockid = 1;
+pub const CLOCK_PROCESS_CPUTIME_ID: clockid = 2;
+pub const CLOCK_THREAD_CPUTIME_ID: clockid = 3;
+
+pub const WHENCE_CUR: whence = 0;
+pub const WHENCE_END: whence = 1;
+pub const WHENCE_SET: whence = 2;
+
+pub const O_CREAT: oflags = 0x0001;
+pub const O_DIRECTORY: oflags = 0x0002;
+pub const O_EXCL: oflags = 0x0004;
+pub const O_TRUNC: oflags = 0x0008;
+
+pub const FDFLAG_APPEND: fdflags = 0x0001;
+pub const FDFLAG_DSYNC: fdflags = 0x0002;
+pub const FDFLAG_NONBLOCK: fdflags = 0x0004;
+pub const FDFLAG_RSYNC: fdflags = 0x0008;
+pub const FDFLAG_SYNC: fdflags = 0x0010;
+
+pub const LOOKUP_SYMLINK_FOLLOW: lookupflags = 0x0000_0001;
+
+pub const FILETYPE_UNKNOWN: filetype = 0;
+pub const FILETYPE_BLOCK_DEVICE: filetype = 1;
+pub const FILETYPE_CHARACTER_DEVICE: filetype = 2;
+pub const FILETYPE_DIRECTORY: filetype = 3;
+pub const FILETYPE_REGULAR_FILE: filetype = 4;
+pub const FILETYPE_SOCKET_DGRAM: filetype = 5;
+pub const FILETYPE_SOCKET_STREAM: filetype = 6;
+pub const FILETYPE_SYMBOLIC_LINK: filetype = 7;
+
+pub const PREOPENTYPE_DIR: u8 = 0;
+
+pub const ADVICE_NORMAL: advice = 0;
+pub const ADVICE_SEQUENTIAL: advice = 1;
+pub const ADVICE_RANDOM: advice = 2;
+pub const ADVICE_WILLNEED: advice = 3;
+pub const ADVICE_DONTNEED: advice = 4;
+pub const ADVICE_NOREUSE: advice = 5;
+
+pub const RIGHT_FD_DATASYNC: rights = 1 << 0;
+pub const RIGHT_FD_READ: rights = 1 << 1;
+pub const RIGHT_FD_SEEK: rights = 1 << 2;
+pub const RIGHT_FD_FDSTAT_SET_FLAGS: rights = 1 << 3;
+pub const RIGHT_FD_SYNC: rights = 1 << 4;
+pub const RIGHT_FD_TELL: rights = 1 << 5;
+pub const RIGHT_FD_WRITE: rights = 1 << 6;
+pub const RIGHT_FD_ADVISE: rights = 1 << 7;
+pub const RIGHT_FD_ALLOCATE: rights = 1 << 8;
+pub const RIGHT_PATH_CREATE_DIRECTORY: rights = 1 << 9;
+pub const RIGHT_PATH_CREATE_FILE: rights = 1 << 10;
+pub const RIGHT_PATH_LINK_SOURCE: rights = 1 << 11;
+pub const RIGHT_PATH_LINK_TARGET: rights = 1 << 12;
+pub const RIGHT_PATH_OPEN: rights = 1 << 13;
+pub const RIGHT_FD_READDIR: rights = 1 << 14;
+pub const RIGHT_PATH_READLINK: rights = 1 << 15;
+pub const RIGHT_PATH_RENAME_SOURCE: rights = 1 << 16;
+pub const RIGHT_PATH_RENAME_TARGET: rights = 1 << 17;
+pub const RIGHT_PATH_FILESTAT_GET: rights = 1 << 18;
+pub const RIGHT_PATH_FILESTAT_SET_SIZE: rights = 1 << 19;
+pub const RIGHT_PATH_FILESTAT_SET_TIMES: rights = 1 << 20;
+pub const RIGHT_FD_FILESTAT_GET: rights = 1 << 21;
+pub const RIGHT_FD_FILESTAT_SET_SIZE: rights = 1 << 22;
+pub const RIGHT_FD_FILESTAT_SET_TIMES: rights = 1 << 23;
+pub const RIGHT_PATH_SYMLINK: rights = 1 << 24;
+pub const RIGHT_PATH_REMOVE_DIRECTORY: rights = 1 << 25;
+pub const RIGHT_PATH_UNLINK_FILE: rights = 1 << 26;
+pub const RIGHT_POLL_FD_READWRITE: rights = 1 << 27;
+pub const RIGHT_SOCK_SHUTDOWN: rights = 1 << 28;
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct ciovec {
+    pub buf: *const u8,
+    pub buf_len: usize,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct iovec {
+    pub buf: *mut u8,
+    pub buf_len: usize,
+}
+
+/// `__wasi_prestat_t`; directories are the only kind of preopen today so the
+/// union is flattened into its one variant.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct prestat {
+    pub pr_type: u8,
+    pub pr_name_len: usize,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct fdstat {
+    pub fs_filetype: filetype,
+    pub fs_flags: fdflags,
+    pub fs_rights_base: rights,
+    pub fs_rights_inheriting: rights,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct filestat {
+    pub st_dev: device,
+    pub st_ino: inode,
+    pub st_filetype: filetype,
+    pub st_nlink: linkcount,
+    pub st_size: filesize,
+    pub st_atim: timestamp,
+    pub st_mtim: timestamp,
+    pub st_ctim: timestamp,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct dirent {
+    pub d_next: dircookie,
+    pub d_ino: inode,
+    pub d_namlen: u32,
+    pub d_type: filetype,
+}
+
+#[link(wasm_import_module = "wasi_unstable")]
+extern {
+    pub fn args_get(argv: *mut *mut u8, argv_buf: *mut u8) -> errno;
+    pub fn args_sizes_get(argc: *mut usize, argv_buf_size: *mut usize) -> errno;
+
+    pub fn environ_get(environ: *mut *mut u8, environ_buf: *mut u8) -> errno;
+    pub fn environ_sizes_get(environ_count: *mut usize,
+                             environ_buf_size: *mut usize) -> errno;
+
+    pub fn clock_res_get(clock_id: clockid, resolution: *mut timestamp) -> errno;
+    pub fn clock_time_get(clock_id: clockid,
+                          precision: timestamp,
+                          time: *mut timestamp) -> errno;
+
+    pub fn fd_advise(fd: fd, offset: filesize, len: filesize, advice: advice) -> errno;
+    pub fn fd_allocate(fd: fd, offset: filesize, len: filesize) -> errno;
+    pub fn fd_close(fd: fd) -> errno;
+    pub fn fd_datasync(fd: fd) -> errno;
+    pub fn fd_fdstat_get(fd: fd, buf: *mut fdstat) -> errno;
+    pub fn fd_fdstat_set_flags(fd: fd, flags: fdflags) -> errno;
+    pub fn fd_filestat_get(fd: fd, buf: *mut filestat) -> errno;
+    pub fn fd_filestat_set_size(fd: fd, st_size: filesize) -> errno;
+    pub fn fd_pread(fd: fd,
+                    iovs: *const iovec,
+                    iovs_len: usize,
+                    offset: filesize,
+                    nread: *mut usize) -> errno;
+    pub fn fd_prestat_get(fd: fd, buf: *mut prestat) -> errno;
+    pub fn fd_prestat_dir_name(fd: fd, path: *mut u8, path_len: usize) -> errno;
+    pub fn fd_pwrite(fd: fd,
+                     iovs: *const ciovec,
+                     iovs_len: usize,
+                     offset: filesize,
+                     nwritten: *mut usize) -> errno;
+    pub fn fd_read(fd: fd,
+                   iovs: *const iovec,
+                   iovs_len: usize,
+                   nread: *mut usize) -> errno;
+    pub fn fd_readdir(fd: fd,
+                      buf: *mut u8,
+                      buf_len: usize,
+                      cookie: dircookie,
+                      bufused: *mut usize) -> errno;
+    pub fn fd_renumber(from: fd, to: fd) -> errno;
+    pub fn fd_seek(fd: fd,
+                   offset: filedelta,
+                   whence: whence,
+                   newoffset: *mut filesize) -> errno;
+    pub fn fd_sync(fd: fd) -> errno;
+    pub fn fd_tell(fd: fd, newoffset: *mut filesize) -> errno;
+    pub fn fd_write(fd: fd,
+                    iovs: *const ciovec,
+                    iovs_len: usize,
+                    nwritten: *mut usize) -> errno;
+
+    pub fn path_create_directory(fd: fd, path: *const u8, path_len: usize) -> errno;
+    pub fn path_filestat_get(fd: fd,
+                             flags: lookupflags,
+                             path: *const u8,
+                             path_len: usize,
+                             buf: *mut filestat) -> errno;
+    pub fn path_link(old_fd: fd,
+                     old_flags: lookupflags,
+                     old_path: *const u8,
+                     old_path_len: usize,
+                     new_fd: fd,
+                     new_path: *const u8,
+                     new_path_len: usize) -> errno;
+    pub fn path_open(dirfd: fd,
+                     dirflags: lookupflags,
+                     path: *const u8,
+                     path_len: usize,
+                     oflags: oflags,
+                     fs_rights_base: rights,
+                     fs_rights_inheriting: rights,
+                     fs_flags: fdflags,
+                     fd: *mut fd) -> errno;
+    pub fn path_readlink(fd: fd,
+                         path: *const u8,
+                         path_len: usize,
+                         buf: *mut u8,
+                         buf_len: usize,
+                         bufused: *mut usize) -> errno;
+    pub fn path_remove_directory(fd: fd, path: *const u8, path_len: usize) -> errno;
+    pub fn path_rename(old_fd: fd,
+                       old_path: *const u8,
+                       old_path_len: usize,
+                       new_fd: fd,
+                       new_path: *const u8,
+                       new_path_len: usize) -> errno;
+    pub fn path_symlink(old_path: *const u8,
+                        old_path_len: usize,
+                        fd: fd,
+                        new_path: *const u8,
+                        new_path_len: usize) -> errno;
+    pub fn path_unlink_file(fd: fd, path: *const u8, path_len: usize) -> errno;
+
+    pub fn proc_exit(rval: exitcode) -> !;
+    pub fn random_get(buf: *mut u8, buf_len: usize) -> errno;
+    pub fn sched_yield() -> errno;
+}
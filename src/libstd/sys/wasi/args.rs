@@ -0,0 +1,128 @@
+use ffi::{CStr, OsStr, OsString};
+use fmt;
+use io;
+use marker::PhantomData;
+use os::raw::c_char;
+use os::wasi::ffi::{OsStrExt, OsStringExt};
+use ptr;
+use slice;
+use sync::Once;
+use sys::abi;
+use sys::cvt;
+
+pub unsafe fn init(_argc: isize, _argv: *const *const u8) {
+    // On wasi the arguments are fetched from the host on demand, so there's
+    // nothing for us to do here
+}
+
+pub unsafe fn cleanup() {
+}
+
+pub fn args() -> Args {
+    Args {
+        iter: stored().iter(),
+        _dont_send_or_sync_me: PhantomData,
+    }
+}
+
+/// Returns the arguments, fetched from the host on the first call.
+///
+/// They can't change while the program runs, so the buffer the host fills in
+/// is kept for the rest of the program and every `Args` borrows from it. If
+/// the host can't provide them, there are none.
+fn stored() -> &'static [&'static [u8]] {
+    static INIT: Once = Once::new();
+    static mut ARGS: &'static [&'static [u8]] = &[];
+    unsafe {
+        INIT.call_once(|| {
+            if let Ok(args) = fetch() {
+                ARGS = args;
+            }
+        });
+        ARGS
+    }
+}
+
+/// Reads the whole argument list via `args_sizes_get` and `args_get`.
+///
+/// The host writes all arguments NUL-terminated into one buffer and fills in
+/// a table of pointers into it, so both are sized up front.
+fn fetch() -> io::Result<&'static [&'static [u8]]> {
+    unsafe {
+        let mut argc = 0;
+        let mut argv_buf_size = 0;
+        cvt(abi::args_sizes_get(&mut argc, &mut argv_buf_size))?;
+
+        let mut argv = vec![ptr::null_mut::<u8>(); argc];
+        let argv_buf = Box::leak(vec![0u8; argv_buf_size].into_boxed_slice());
+        cvt(abi::args_get(argv.as_mut_ptr(), argv_buf.as_mut_ptr()))?;
+
+        let args = argv.iter().map(|&arg| {
+            slice::from_raw_parts(arg as *const u8,
+                                  CStr::from_ptr(arg as *const c_char).to_bytes().len())
+        }).collect::<Vec<&'static [u8]>>();
+        Ok(Box::leak(args.into_boxed_slice()))
+    }
+}
+
+pub struct Args {
+    iter: slice::Iter<'static, &'static [u8]>,
+    _dont_send_or_sync_me: PhantomData<*mut ()>,
+}
+
+impl Args {
+    pub fn inner_debug(&self) -> ArgsInnerDebug {
+        ArgsInnerDebug { args: self }
+    }
+
+    pub fn get(&self, i: usize) -> Option<OsString> {
+        self.iter.as_slice().get(i).map(|arg| to_os_string(arg))
+    }
+}
+
+fn to_os_string(arg: &[u8]) -> OsString {
+    OsString::from_vec(arg.to_vec())
+}
+
+pub struct ArgsInnerDebug<'a> {
+    args: &'a Args,
+}
+
+impl<'a> fmt::Debug for ArgsInnerDebug<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list()
+            .entries(self.args.iter.as_slice().iter().map(|arg| OsStr::from_bytes(arg)))
+            .finish()
+    }
+}
+
+impl Iterator for Args {
+    type Item = OsString;
+    fn next(&mut self) -> Option<OsString> {
+        self.iter.next().map(|arg| to_os_string(arg))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+    fn nth(&mut self, n: usize) -> Option<OsString> {
+        self.iter.nth(n).map(|arg| to_os_string(arg))
+    }
+    fn last(mut self) -> Option<OsString> {
+        self.next_back()
+    }
+    fn count(self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl ExactSizeIterator for Args {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl DoubleEndedIterator for Args {
+    fn next_back(&mut self) -> Option<OsString> {
+        self.iter.next_back().map(|arg| to_os_string(arg))
+    }
+}
@@ -0,0 +1,225 @@
+//! WASI-specific extensions to primitives in the `std::fs` module.
+
+#![unstable(feature = "wasi_ext", issue = "0")]
+
+use fs::{self, File, Metadata, OpenOptions};
+use io;
+use path::Path;
+use sys_common::{AsInner, AsInnerMut, FromInner};
+
+/// WASI-specific extensions to [`File`].
+///
+/// [`File`]: ../../../../std/fs/struct.File.html
+pub trait FileExt {
+    /// Reads a number of bytes starting from a given offset.
+    ///
+    /// Returns the number of bytes read.
+    ///
+    /// The offset is relative to the start of the file and thus independent
+    /// from the current cursor.
+    ///
+    /// The current file cursor is not affected by this function.
+    ///
+    /// Note that similar to [`File::read`], it is not an error to return with a
+    /// short read.
+    ///
+    /// [`File::read`]: ../../../../std/fs/struct.File.html#method.read
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize>;
+
+    /// Writes a number of bytes starting from a given offset.
+    ///
+    /// Returns the number of bytes written.
+    ///
+    /// The offset is relative to the start of the file and thus independent
+    /// from the current cursor.
+    ///
+    /// The current file cursor is not affected by this function.
+    ///
+    /// When writing beyond the end of the file, the file is appropriately
+    /// extended and the intermediate bytes are initialized with the value 0.
+    ///
+    /// Note that similar to [`File::write`], it is not an error to return a
+    /// short write.
+    ///
+    /// [`File::write`]: ../../../../std/fs/struct.File.html#write.v
+    fn write_at(&self, buf: &[u8], offset: u64) -> io::Result<usize>;
+
+    /// Opens a file relative to this directory handle.
+    ///
+    /// This is how capability-oriented code opens paths without going
+    /// through the preopened directories: `self` must be a directory opened
+    /// with enough rights to look up and open `path`.
+    fn open_at<P: AsRef<Path>>(&self, path: P, opts: &OpenOptions) -> io::Result<File>;
+
+    /// Queries the metadata of `path` relative to this directory handle,
+    /// without following a trailing symlink.
+    fn metadata_at<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata>;
+}
+
+impl FileExt for fs::File {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        self.as_inner().read_at(buf, offset)
+    }
+
+    fn write_at(&self, buf: &[u8], offset: u64) -> io::Result<usize> {
+        self.as_inner().write_at(buf, offset)
+    }
+
+    fn open_at<P: AsRef<Path>>(&self, path: P, opts: &OpenOptions) -> io::Result<File> {
+        let inner = self.as_inner().open_at(path.as_ref(), opts.as_inner())?;
+        Ok(File::from_inner(inner))
+    }
+
+    fn metadata_at<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
+        let attr = self.as_inner().metadata_at(0, path.as_ref())?;
+        Ok(Metadata::from_inner(attr))
+    }
+}
+
+/// WASI-specific extensions to [`fs::OpenOptions`].
+///
+/// [`fs::OpenOptions`]: ../../../../std/fs/struct.OpenOptions.html
+pub trait OpenOptionsExt {
+    /// Pass custom `dirflags` argument to `path_open`.
+    ///
+    /// This option configures the `dirflags` argument to the
+    /// `path_open` syscall which `OpenOptions` will eventually call. The
+    /// `dirflags` argument configures how the file is looked up, currently
+    /// primarily affecting whether symlinks are followed or not.
+    ///
+    /// By default this value is `LOOKUP_SYMLINK_FOLLOW`, or symlinks are
+    /// followed. You can call this method with 0 to disable following
+    /// symlinks and then use `open` to see if there's a symlink at the end of
+    /// a path.
+    fn lookup_flags(&mut self, flags: u32) -> &mut Self;
+
+    /// Indicates whether `OpenOptions` must open a directory or not.
+    ///
+    /// This method will configure whether the `O_DIRECTORY` flag is passed
+    /// when opening a file. When passed it will require that the opened file
+    /// is a directory.
+    ///
+    /// This option is by default `false`
+    fn directory(&mut self, dir: bool) -> &mut Self;
+
+    /// Pass custom `fs_flags` (the `FDFLAG_*` constants) to `path_open`.
+    ///
+    /// Note that the append flag is additionally set when
+    /// `OpenOptions::append` is requested.
+    fn fs_flags(&mut self, flags: u16) -> &mut Self;
+
+    /// Pass custom `fs_rights_base` argument to `path_open`.
+    ///
+    /// This option configures the `fs_rights_base` argument to the
+    /// `path_open` syscall which `OpenOptions` will eventually call. If this
+    /// is not set then a reasonable set of rights is derived from whether
+    /// the file is opened for reading and/or writing.
+    fn rights_base(&mut self, rights: u64) -> &mut Self;
+
+    /// Pass custom `fs_rights_inheriting` argument to `path_open`.
+    ///
+    /// The default, if not set, is the same as `fs_rights_base`.
+    fn rights_inheriting(&mut self, rights: u64) -> &mut Self;
+}
+
+impl OpenOptionsExt for OpenOptions {
+    fn lookup_flags(&mut self, flags: u32) -> &mut OpenOptions {
+        self.as_inner_mut().lookup_flags(flags);
+        self
+    }
+
+    fn directory(&mut self, dir: bool) -> &mut OpenOptions {
+        self.as_inner_mut().directory(dir);
+        self
+    }
+
+    fn fs_flags(&mut self, flags: u16) -> &mut OpenOptions {
+        self.as_inner_mut().fs_flags(flags);
+        self
+    }
+
+    fn rights_base(&mut self, rights: u64) -> &mut OpenOptions {
+        self.as_inner_mut().rights_base(rights);
+        self
+    }
+
+    fn rights_inheriting(&mut self, rights: u64) -> &mut OpenOptions {
+        self.as_inner_mut().rights_inheriting(rights);
+        self
+    }
+}
+
+/// WASI-specific extensions to [`fs::Metadata`].
+///
+/// [`fs::Metadata`]: ../../../../std/fs/struct.Metadata.html
+pub trait MetadataExt {
+    /// Returns the `st_dev` field of the internal `filestat_t`
+    fn dev(&self) -> u64;
+    /// Returns the `st_ino` field of the internal `filestat_t`
+    fn ino(&self) -> u64;
+    /// Returns the `st_nlink` field of the internal `filestat_t`
+    fn nlink(&self) -> u32;
+    /// Returns the `st_ctim` field of the internal `filestat_t`
+    fn ctim(&self) -> u64;
+}
+
+impl MetadataExt for fs::Metadata {
+    fn dev(&self) -> u64 {
+        self.as_inner().as_wasi().st_dev
+    }
+    fn ino(&self) -> u64 {
+        self.as_inner().as_wasi().st_ino
+    }
+    fn nlink(&self) -> u32 {
+        self.as_inner().as_wasi().st_nlink
+    }
+    fn ctim(&self) -> u64 {
+        self.as_inner().as_wasi().st_ctim
+    }
+}
+
+/// WASI-specific extensions for [`FileType`].
+///
+/// Adds support for special WASI file types such as block/character devices,
+/// pipes, and sockets.
+///
+/// [`FileType`]: ../../../../std/fs/struct.FileType.html
+pub trait FileTypeExt {
+    /// Returns `true` if this file type is a block device.
+    fn is_block_device(&self) -> bool;
+    /// Returns `true` if this file type is a character device.
+    fn is_character_device(&self) -> bool;
+    /// Returns `true` if this file type is a socket datagram.
+    fn is_socket_dgram(&self) -> bool;
+    /// Returns `true` if this file type is a socket stream.
+    fn is_socket_stream(&self) -> bool;
+}
+
+impl FileTypeExt for fs::FileType {
+    fn is_block_device(&self) -> bool {
+        self.as_inner().bits() == ::sys::abi::FILETYPE_BLOCK_DEVICE
+    }
+    fn is_character_device(&self) -> bool {
+        self.as_inner().bits() == ::sys::abi::FILETYPE_CHARACTER_DEVICE
+    }
+    fn is_socket_dgram(&self) -> bool {
+        self.as_inner().bits() == ::sys::abi::FILETYPE_SOCKET_DGRAM
+    }
+    fn is_socket_stream(&self) -> bool {
+        self.as_inner().bits() == ::sys::abi::FILETYPE_SOCKET_STREAM
+    }
+}
+
+/// WASI-specific extension methods for [`fs::DirEntry`].
+///
+/// [`fs::DirEntry`]: ../../../../std/fs/struct.DirEntry.html
+pub trait DirEntryExt {
+    /// Returns the underlying `d_ino` field of the `dirent_t`
+    fn ino(&self) -> u64;
+}
+
+impl DirEntryExt for fs::DirEntry {
+    fn ino(&self) -> u64 {
+        self.as_inner().ino()
+    }
+}
@@ -0,0 +1,24 @@
+//! Platform-specific extensions to `std` for WASI.
+//!
+//! Provides access to the raw file descriptors behind `std` types and to the
+//! WASI-specific parts of the filesystem, such as opening files relative to a
+//! directory handle and inspecting inode-level metadata.
+
+#![unstable(feature = "wasi_ext", issue = "0")]
+
+pub mod ffi;
+pub mod fs;
+pub mod io;
+
+/// A prelude for conveniently writing platform-specific code.
+///
+/// Includes all extension traits, and some important type definitions.
+#[unstable(feature = "wasi_ext", issue = "0")]
+pub mod prelude {
+    #[doc(no_inline)]
+    pub use super::ffi::{OsStrExt, OsStringExt};
+    #[doc(no_inline)]
+    pub use super::fs::{FileExt, DirEntryExt, MetadataExt, OpenOptionsExt};
+    #[doc(no_inline)]
+    pub use super::io::{RawFd, AsRawFd, FromRawFd, IntoRawFd};
+}
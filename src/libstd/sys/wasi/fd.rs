@@ -0,0 +1,237 @@
+#![allow(dead_code)]
+
+use io::{self, SeekFrom};
+use mem;
+use sys::abi;
+use sys::cvt;
+
+/// An owned WASI file descriptor, closed on drop.
+#[derive(Debug)]
+pub struct WasiFd {
+    fd: abi::fd,
+}
+
+fn iovec(a: &mut [u8]) -> abi::iovec {
+    abi::iovec { buf: a.as_mut_ptr(), buf_len: a.len() }
+}
+
+fn ciovec(a: &[u8]) -> abi::ciovec {
+    abi::ciovec { buf: a.as_ptr(), buf_len: a.len() }
+}
+
+impl WasiFd {
+    pub unsafe fn from_raw(fd: abi::fd) -> WasiFd {
+        WasiFd { fd }
+    }
+
+    pub fn as_raw(&self) -> abi::fd {
+        self.fd
+    }
+
+    /// Extracts the actual file descriptor without closing it.
+    pub fn into_raw(self) -> abi::fd {
+        let fd = self.fd;
+        mem::forget(self);
+        fd
+    }
+
+    pub fn datasync(&self) -> io::Result<()> {
+        cvt(unsafe { abi::fd_datasync(self.fd) })
+    }
+
+    pub fn pread(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        let iov = [iovec(buf)];
+        let mut nread = 0;
+        cvt(unsafe {
+            abi::fd_pread(self.fd, iov.as_ptr(), iov.len(), offset, &mut nread)
+        })?;
+        Ok(nread)
+    }
+
+    pub fn pread_vectored(&self, bufs: &mut [&mut [u8]], offset: u64) -> io::Result<usize> {
+        let iovs = bufs.iter_mut().map(|buf| iovec(buf)).collect::<Vec<_>>();
+        let mut nread = 0;
+        cvt(unsafe {
+            abi::fd_pread(self.fd, iovs.as_ptr(), iovs.len(), offset, &mut nread)
+        })?;
+        Ok(nread)
+    }
+
+    pub fn pwrite(&self, buf: &[u8], offset: u64) -> io::Result<usize> {
+        let iov = [ciovec(buf)];
+        let mut nwritten = 0;
+        cvt(unsafe {
+            abi::fd_pwrite(self.fd, iov.as_ptr(), iov.len(), offset, &mut nwritten)
+        })?;
+        Ok(nwritten)
+    }
+
+    pub fn pwrite_vectored(&self, bufs: &[&[u8]], offset: u64) -> io::Result<usize> {
+        let iovs = bufs.iter().map(|buf| ciovec(buf)).collect::<Vec<_>>();
+        let mut nwritten = 0;
+        cvt(unsafe {
+            abi::fd_pwrite(self.fd, iovs.as_ptr(), iovs.len(), offset, &mut nwritten)
+        })?;
+        Ok(nwritten)
+    }
+
+    pub fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
+        let iov = [iovec(buf)];
+        let mut nread = 0;
+        cvt(unsafe { abi::fd_read(self.fd, iov.as_ptr(), iov.len(), &mut nread) })?;
+        Ok(nread)
+    }
+
+    pub fn write(&self, buf: &[u8]) -> io::Result<usize> {
+        let iov = [ciovec(buf)];
+        let mut nwritten = 0;
+        cvt(unsafe {
+            abi::fd_write(self.fd, iov.as_ptr(), iov.len(), &mut nwritten)
+        })?;
+        Ok(nwritten)
+    }
+
+    pub fn seek(&self, pos: SeekFrom) -> io::Result<u64> {
+        let (whence, offset) = match pos {
+            SeekFrom::Start(pos) => (abi::WHENCE_SET, pos as i64),
+            SeekFrom::End(pos) => (abi::WHENCE_END, pos),
+            SeekFrom::Current(pos) => (abi::WHENCE_CUR, pos),
+        };
+        let mut pos = 0;
+        cvt(unsafe { abi::fd_seek(self.fd, offset, whence, &mut pos) })?;
+        Ok(pos)
+    }
+
+    pub fn tell(&self) -> io::Result<u64> {
+        let mut pos = 0;
+        cvt(unsafe { abi::fd_tell(self.fd, &mut pos) })?;
+        Ok(pos)
+    }
+
+    pub fn fdstat(&self) -> io::Result<abi::fdstat> {
+        let mut stat: abi::fdstat = unsafe { mem::zeroed() };
+        cvt(unsafe { abi::fd_fdstat_get(self.fd, &mut stat) })?;
+        Ok(stat)
+    }
+
+    pub fn set_flags(&self, flags: abi::fdflags) -> io::Result<()> {
+        cvt(unsafe { abi::fd_fdstat_set_flags(self.fd, flags) })
+    }
+
+    pub fn sync(&self) -> io::Result<()> {
+        cvt(unsafe { abi::fd_sync(self.fd) })
+    }
+
+    pub fn advise(&self, offset: u64, len: u64, advice: abi::advice) -> io::Result<()> {
+        cvt(unsafe { abi::fd_advise(self.fd, offset, len, advice) })
+    }
+
+    pub fn allocate(&self, offset: u64, len: u64) -> io::Result<()> {
+        cvt(unsafe { abi::fd_allocate(self.fd, offset, len) })
+    }
+
+    pub fn create_directory(&self, path: &[u8]) -> io::Result<()> {
+        cvt(unsafe { abi::path_create_directory(self.fd, path.as_ptr(), path.len()) })
+    }
+
+    pub fn link(
+        &self,
+        old_flags: abi::lookupflags,
+        old_path: &[u8],
+        new_fd: &WasiFd,
+        new_path: &[u8],
+    ) -> io::Result<()> {
+        cvt(unsafe {
+            abi::path_link(self.fd, old_flags, old_path.as_ptr(), old_path.len(),
+                           new_fd.fd, new_path.as_ptr(), new_path.len())
+        })
+    }
+
+    pub fn open(
+        &self,
+        dirflags: abi::lookupflags,
+        path: &[u8],
+        oflags: abi::oflags,
+        fs_rights_base: abi::rights,
+        fs_rights_inheriting: abi::rights,
+        fs_flags: abi::fdflags,
+    ) -> io::Result<WasiFd> {
+        let mut fd = 0;
+        cvt(unsafe {
+            abi::path_open(self.fd, dirflags, path.as_ptr(), path.len(), oflags,
+                           fs_rights_base, fs_rights_inheriting, fs_flags, &mut fd)
+        })?;
+        Ok(WasiFd { fd })
+    }
+
+    pub fn readdir(&self, buf: &mut [u8], cookie: abi::dircookie) -> io::Result<usize> {
+        let mut used = 0;
+        cvt(unsafe {
+            abi::fd_readdir(self.fd, buf.as_mut_ptr(), buf.len(), cookie, &mut used)
+        })?;
+        Ok(used)
+    }
+
+    pub fn readlink(&self, path: &[u8], buf: &mut [u8]) -> io::Result<usize> {
+        let mut used = 0;
+        cvt(unsafe {
+            abi::path_readlink(self.fd, path.as_ptr(), path.len(),
+                               buf.as_mut_ptr(), buf.len(), &mut used)
+        })?;
+        Ok(used)
+    }
+
+    pub fn rename(&self, old_path: &[u8], new_fd: &WasiFd, new_path: &[u8]) -> io::Result<()> {
+        cvt(unsafe {
+            abi::path_rename(self.fd, old_path.as_ptr(), old_path.len(),
+                             new_fd.fd, new_path.as_ptr(), new_path.len())
+        })
+    }
+
+    pub fn filestat_get(&self) -> io::Result<abi::filestat> {
+        let mut stat: abi::filestat = unsafe { mem::zeroed() };
+        cvt(unsafe { abi::fd_filestat_get(self.fd, &mut stat) })?;
+        Ok(stat)
+    }
+
+    pub fn filestat_set_size(&self, size: u64) -> io::Result<()> {
+        cvt(unsafe { abi::fd_filestat_set_size(self.fd, size) })
+    }
+
+    pub fn path_filestat_get(
+        &self,
+        flags: abi::lookupflags,
+        path: &[u8],
+    ) -> io::Result<abi::filestat> {
+        let mut stat: abi::filestat = unsafe { mem::zeroed() };
+        cvt(unsafe {
+            abi::path_filestat_get(self.fd, flags, path.as_ptr(), path.len(), &mut stat)
+        })?;
+        Ok(stat)
+    }
+
+    pub fn symlink(&self, old_path: &[u8], new_path: &[u8]) -> io::Result<()> {
+        cvt(unsafe {
+            abi::path_symlink(old_path.as_ptr(), old_path.len(),
+                              self.fd, new_path.as_ptr(), new_path.len())
+        })
+    }
+
+    pub fn unlink_file(&self, path: &[u8]) -> io::Result<()> {
+        cvt(unsafe { abi::path_unlink_file(self.fd, path.as_ptr(), path.len()) })
+    }
+
+    pub fn remove_directory(&self, path: &[u8]) -> io::Result<()> {
+        cvt(unsafe { abi::path_remove_directory(self.fd, path.as_ptr(), path.len()) })
+    }
+}
+
+impl Drop for WasiFd {
+    fn drop(&mut self) {
+        // Errors are ignored when closing a file descriptor, there's nothing
+        // sensible to do with them here.
+        unsafe {
+            let _ = abi::fd_close(self.fd);
+        }
+    }
+}
@@ -0,0 +1,817 @@
+use cmp;
+use ffi::{OsStr, OsString};
+use fmt;
+use io::{self, SeekFrom};
+use mem;
+use os::wasi::ffi::{OsStrExt, OsStringExt};
+use path::{Path, PathBuf, Component};
+use ptr;
+use sync::{Arc, Once};
+use sys::abi;
+use sys::fd::WasiFd;
+use sys::time::SystemTime;
+use sys::unsupported;
+use sys_common::FromInner;
+use sys_common::fs::{copy_chunks, Progress};
+
+pub struct File {
+    fd: WasiFd,
+}
+
+#[derive(Clone)]
+pub struct FileAttr {
+    meta: abi::filestat,
+}
+
+pub struct ReadDir {
+    inner: Arc<ReadDirInner>,
+    cookie: abi::dircookie,
+    buf: Vec<u8>,
+    offset: usize,
+    end_of_stream: bool,
+}
+
+struct ReadDirInner {
+    root: PathBuf,
+    dir: File,
+}
+
+pub struct DirEntry {
+    meta: abi::dirent,
+    name: Vec<u8>,
+    inner: Arc<ReadDirInner>,
+}
+
+#[derive(Clone, Debug)]
+pub struct OpenOptions {
+    read: bool,
+    write: bool,
+    append: bool,
+    truncate: bool,
+    create: bool,
+    create_new: bool,
+    // system-specific
+    directory: bool,
+    dirflags: abi::lookupflags,
+    fdflags: abi::fdflags,
+    rights_base: Option<abi::rights>,
+    rights_inheriting: Option<abi::rights>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FilePermissions {
+    readonly: bool,
+}
+
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+pub struct FileType {
+    bits: abi::filetype,
+}
+
+#[derive(Debug)]
+pub struct DirBuilder {}
+
+// Size of the buffer handed to `fd_readdir`, comfortably larger than any
+// single entry a host will produce.
+const READ_DIR_BUF_SIZE: usize = 4096;
+
+impl FileAttr {
+    fn zero() -> FileAttr {
+        FileAttr {
+            meta: unsafe { mem::zeroed() },
+        }
+    }
+
+    pub fn size(&self) -> u64 {
+        self.meta.st_size
+    }
+
+    pub fn perm(&self) -> FilePermissions {
+        // not currently implemented in wasi yet
+        FilePermissions { readonly: false }
+    }
+
+    pub fn file_type(&self) -> FileType {
+        FileType {
+            bits: self.meta.st_filetype,
+        }
+    }
+
+    pub fn modified(&self) -> io::Result<SystemTime> {
+        Ok(SystemTime::from_wasi_timestamp(self.meta.st_mtim))
+    }
+
+    pub fn accessed(&self) -> io::Result<SystemTime> {
+        Ok(SystemTime::from_wasi_timestamp(self.meta.st_atim))
+    }
+
+    pub fn created(&self) -> io::Result<SystemTime> {
+        // WASI only exposes the status change time, which is not the same
+        // thing as a creation time.
+        Err(io::Error::new(io::ErrorKind::Other,
+                           "creation time is not available on this platform \
+                            currently"))
+    }
+
+    pub fn as_wasi(&self) -> &abi::filestat {
+        &self.meta
+    }
+}
+
+impl FilePermissions {
+    pub fn readonly(&self) -> bool {
+        self.readonly
+    }
+
+    pub fn set_readonly(&mut self, readonly: bool) {
+        self.readonly = readonly;
+    }
+}
+
+impl FileType {
+    pub fn is_dir(&self) -> bool {
+        self.bits == abi::FILETYPE_DIRECTORY
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.bits == abi::FILETYPE_REGULAR_FILE
+    }
+
+    pub fn is_symlink(&self) -> bool {
+        self.bits == abi::FILETYPE_SYMBOLIC_LINK
+    }
+
+    pub fn bits(&self) -> abi::filetype {
+        self.bits
+    }
+}
+
+impl fmt::Debug for ReadDir {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ReadDir").finish()
+    }
+}
+
+impl ReadDir {
+    fn new(dir: File, root: PathBuf) -> ReadDir {
+        ReadDir {
+            cookie: 0,
+            buf: Vec::new(),
+            offset: 0,
+            end_of_stream: false,
+            inner: Arc::new(ReadDirInner { dir, root }),
+        }
+    }
+
+    pub fn set_buffer_size(&mut self, _size: usize) {}
+}
+
+impl Iterator for ReadDir {
+    type Item = io::Result<DirEntry>;
+
+    fn next(&mut self) -> Option<io::Result<DirEntry>> {
+        loop {
+            // First try to pull a complete entry out of what's left in our
+            // buffer. Entries are a fixed-size header immediately followed by
+            // the (not NUL-terminated) name.
+            let header = mem::size_of::<abi::dirent>();
+            let data = &self.buf[self.offset..];
+            if data.len() >= header {
+                let dirent = unsafe {
+                    ptr::read_unaligned(data.as_ptr() as *const abi::dirent)
+                };
+                let name_end = header + dirent.d_namlen as usize;
+                if data.len() >= name_end {
+                    let name = data[header..name_end].to_vec();
+                    self.offset += name_end;
+                    self.cookie = dirent.d_next;
+                    if name == b"." || name == b".." {
+                        continue
+                    }
+                    return Some(Ok(DirEntry {
+                        meta: dirent,
+                        name,
+                        inner: self.inner.clone(),
+                    }))
+                }
+            }
+
+            // Otherwise we've either run out of entries or the host truncated
+            // the last one, so refill the buffer starting at the last cookie
+            // we fully consumed. A short read means there's nothing more.
+            if self.end_of_stream {
+                return None
+            }
+            // If the last refill didn't hold even one whole entry, the buffer
+            // is too small for it, and refilling at the same size would just
+            // truncate it again.
+            let size = if self.offset == 0 && !self.buf.is_empty() {
+                self.buf.len() * 2
+            } else {
+                cmp::max(self.buf.len(), READ_DIR_BUF_SIZE)
+            };
+            self.buf.resize(size, 0);
+            self.offset = 0;
+            let n = match self.inner.dir.fd.readdir(&mut self.buf, self.cookie) {
+                Ok(n) => n,
+                Err(e) => {
+                    self.buf.truncate(0);
+                    self.end_of_stream = true;
+                    return Some(Err(e))
+                }
+            };
+            self.buf.truncate(n);
+            self.end_of_stream = n < size;
+        }
+    }
+}
+
+impl DirEntry {
+    pub fn path(&self) -> PathBuf {
+        let name = OsStr::from_bytes(&self.name);
+        self.inner.root.join(name)
+    }
+
+    pub fn file_name(&self) -> OsString {
+        OsString::from_vec(self.name.clone())
+    }
+
+    pub fn metadata(&self) -> io::Result<FileAttr> {
+        metadata_at(&self.inner.dir.fd, 0, Path::new(OsStr::from_bytes(&self.name)))
+    }
+
+    pub fn file_type(&self) -> io::Result<FileType> {
+        Ok(FileType {
+            bits: self.meta.d_type,
+        })
+    }
+
+    pub fn ino(&self) -> abi::inode {
+        self.meta.d_ino
+    }
+}
+
+impl OpenOptions {
+    pub fn new() -> OpenOptions {
+        OpenOptions {
+            read: false,
+            write: false,
+            append: false,
+            truncate: false,
+            create: false,
+            create_new: false,
+            directory: false,
+            dirflags: abi::LOOKUP_SYMLINK_FOLLOW,
+            fdflags: 0,
+            rights_base: None,
+            rights_inheriting: None,
+        }
+    }
+
+    pub fn read(&mut self, read: bool) { self.read = read; }
+    pub fn write(&mut self, write: bool) { self.write = write; }
+    pub fn append(&mut self, append: bool) { self.append = append; }
+    pub fn truncate(&mut self, truncate: bool) { self.truncate = truncate; }
+    pub fn create(&mut self, create: bool) { self.create = create; }
+    pub fn create_new(&mut self, create_new: bool) { self.create_new = create_new; }
+
+    pub fn directory(&mut self, directory: bool) { self.directory = directory; }
+    pub fn lookup_flags(&mut self, flags: abi::lookupflags) { self.dirflags = flags; }
+    pub fn fs_flags(&mut self, flags: abi::fdflags) { self.fdflags = flags; }
+    pub fn rights_base(&mut self, rights: abi::rights) { self.rights_base = Some(rights); }
+    pub fn rights_inheriting(&mut self, rights: abi::rights) {
+        self.rights_inheriting = Some(rights);
+    }
+
+    fn oflags(&self) -> abi::oflags {
+        let mut ret = 0;
+        if self.create {
+            ret |= abi::O_CREAT;
+        }
+        if self.directory {
+            ret |= abi::O_DIRECTORY;
+        }
+        if self.create_new {
+            ret |= abi::O_CREAT | abi::O_EXCL;
+        }
+        if self.truncate {
+            ret |= abi::O_TRUNC;
+        }
+        ret
+    }
+
+    fn fdflags(&self) -> abi::fdflags {
+        let mut ret = self.fdflags;
+        if self.append {
+            ret |= abi::FDFLAG_APPEND;
+        }
+        ret
+    }
+
+    fn get_rights_base(&self) -> abi::rights {
+        if let Some(rights) = self.rights_base {
+            return rights
+        }
+
+        // If rights haven't otherwise been specified try to pick a reasonable
+        // set. This can always be overridden by users via extension traits,
+        // and implementations may give us fewer rights silently than we ask
+        // for. So given that, just look at `read` and `write` and bucket
+        // interesting rights into those categories.
+        let mut base = 0;
+        if self.read {
+            base |= abi::RIGHT_FD_READ | abi::RIGHT_FD_READDIR;
+        }
+        if self.write || self.append {
+            base |= abi::RIGHT_FD_WRITE |
+                abi::RIGHT_FD_DATASYNC |
+                abi::RIGHT_FD_SYNC |
+                abi::RIGHT_FD_ALLOCATE |
+                abi::RIGHT_FD_FILESTAT_SET_SIZE;
+        }
+
+        // FIXME: some of these should probably be read-only or write-only...
+        base |= abi::RIGHT_FD_ADVISE |
+            abi::RIGHT_FD_FDSTAT_SET_FLAGS |
+            abi::RIGHT_FD_FILESTAT_GET |
+            abi::RIGHT_FD_SEEK |
+            abi::RIGHT_FD_TELL |
+            abi::RIGHT_POLL_FD_READWRITE;
+
+        base
+    }
+
+    fn get_rights_inheriting(&self) -> abi::rights {
+        self.rights_inheriting.unwrap_or_else(|| self.get_rights_base())
+    }
+}
+
+impl File {
+    pub fn open(path: &Path, opts: &OpenOptions) -> io::Result<File> {
+        let (dir, file) = open_parent(path)?;
+        open_at(dir, &file, opts)
+    }
+
+    pub fn open_at(&self, path: &Path, opts: &OpenOptions) -> io::Result<File> {
+        open_at(&self.fd, path, opts)
+    }
+
+    pub fn file_attr(&self) -> io::Result<FileAttr> {
+        self.fd.filestat_get().map(|meta| FileAttr { meta })
+    }
+
+    pub fn metadata_at(&self, flags: abi::lookupflags, path: &Path) -> io::Result<FileAttr> {
+        metadata_at(&self.fd, flags, path)
+    }
+
+    pub fn fsync(&self) -> io::Result<()> {
+        self.fd.sync()
+    }
+
+    pub fn datasync(&self) -> io::Result<()> {
+        self.fd.datasync()
+    }
+
+    pub fn truncate(&self, size: u64) -> io::Result<()> {
+        self.fd.filestat_set_size(size)
+    }
+
+    pub fn allocate(&self, len: u64) -> io::Result<()> {
+        self.fd.allocate(0, len)
+    }
+
+    pub fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.fd.read(buf)
+    }
+
+    pub fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        self.fd.pread(buf, offset)
+    }
+
+    pub fn read_vectored_at(&self, bufs: &mut [&mut [u8]], offset: u64) -> io::Result<usize> {
+        self.fd.pread_vectored(bufs, offset)
+    }
+
+    pub fn write(&self, buf: &[u8]) -> io::Result<usize> {
+        self.fd.write(buf)
+    }
+
+    pub fn write_at(&self, buf: &[u8], offset: u64) -> io::Result<usize> {
+        self.fd.pwrite(buf, offset)
+    }
+
+    pub fn write_vectored_at(&self, bufs: &[&[u8]], offset: u64) -> io::Result<usize> {
+        self.fd.pwrite_vectored(bufs, offset)
+    }
+
+    pub fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    pub fn seek(&self, pos: SeekFrom) -> io::Result<u64> {
+        self.fd.seek(pos)
+    }
+
+    pub fn duplicate(&self) -> io::Result<File> {
+        // https://github.com/CraneStation/wasmtime/blob/master/docs/WASI-rationale.md#why-no-dup
+        unsupported()
+    }
+
+    pub fn set_permissions(&self, _perm: FilePermissions) -> io::Result<()> {
+        // Permissions haven't been fully figured out in wasi yet, so this is
+        // likely temporary
+        unsupported()
+    }
+
+    pub fn lock(&self, _exclusive: bool, _block: bool) -> io::Result<bool> {
+        unsupported()
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        unsupported()
+    }
+
+    pub fn fd(&self) -> &WasiFd {
+        &self.fd
+    }
+
+    pub fn into_fd(self) -> WasiFd {
+        self.fd
+    }
+
+    pub fn read_link(&self, file: &Path) -> io::Result<PathBuf> {
+        read_link(&self.fd, file)
+    }
+}
+
+impl FromInner<abi::fd> for File {
+    fn from_inner(fd: abi::fd) -> File {
+        unsafe {
+            File {
+                fd: WasiFd::from_raw(fd),
+            }
+        }
+    }
+}
+
+impl DirBuilder {
+    pub fn new() -> DirBuilder {
+        DirBuilder {}
+    }
+
+    pub fn mkdir(&self, p: &Path) -> io::Result<()> {
+        let (dir, file) = open_parent(p)?;
+        dir.create_directory(file.as_os_str().as_bytes())
+    }
+}
+
+impl fmt::Debug for File {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("File")
+            .field("fd", &self.fd.as_raw())
+            .finish()
+    }
+}
+
+pub fn readdir(p: &Path) -> io::Result<ReadDir> {
+    let dir = DirHandle::open(p)?;
+    Ok(ReadDir::new(dir.dir, dir.path))
+}
+
+// Everything in WASI is relative to a directory descriptor anyway.
+pub struct DirHandle {
+    dir: File,
+    path: PathBuf,
+}
+
+impl DirHandle {
+    pub fn open(path: &Path) -> io::Result<DirHandle> {
+        let (dir, file) = open_parent(path)?;
+        Ok(DirHandle {
+            dir: open_at(dir, &file, &DirHandle::open_options())?,
+            path: path.to_path_buf(),
+        })
+    }
+
+    fn open_options() -> OpenOptions {
+        let mut opts = OpenOptions::new();
+        opts.directory(true);
+        opts.read(true);
+        opts
+    }
+
+    pub fn open_at(&self, path: &Path, opts: &OpenOptions) -> io::Result<File> {
+        self.dir.open_at(path, opts)
+    }
+
+    pub fn open_dir_at(&self, path: &Path) -> io::Result<DirHandle> {
+        Ok(DirHandle {
+            dir: self.dir.open_at(path, &DirHandle::open_options())?,
+            path: self.path.join(path),
+        })
+    }
+
+    pub fn read_dir_at(&self, path: &Path) -> io::Result<ReadDir> {
+        let dir = self.open_dir_at(path)?;
+        Ok(ReadDir::new(dir.dir, dir.path))
+    }
+
+    pub fn metadata_at(&self, path: &Path, follow: bool) -> io::Result<FileAttr> {
+        let flags = if follow { abi::LOOKUP_SYMLINK_FOLLOW } else { 0 };
+        self.dir.metadata_at(flags, path)
+    }
+
+    pub fn remove_file_at(&self, path: &Path) -> io::Result<()> {
+        self.dir.fd().unlink_file(path.as_os_str().as_bytes())
+    }
+
+    pub fn rename_at(&self, from: &Path, to_dir: &DirHandle, to: &Path) -> io::Result<()> {
+        self.dir.fd().rename(from.as_os_str().as_bytes(),
+                             to_dir.dir.fd(),
+                             to.as_os_str().as_bytes())
+    }
+}
+
+impl fmt::Debug for DirHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Dir")
+            .field("fd", &self.dir.fd().as_raw())
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+pub fn unlink(p: &Path) -> io::Result<()> {
+    let (dir, file) = open_parent(p)?;
+    dir.unlink_file(file.as_os_str().as_bytes())
+}
+
+pub fn rename(old: &Path, new: &Path) -> io::Result<()> {
+    let (old, old_file) = open_parent(old)?;
+    let (new, new_file) = open_parent(new)?;
+    old.rename(old_file.as_os_str().as_bytes(), new, new_file.as_os_str().as_bytes())
+}
+
+pub fn set_perm(_p: &Path, _perm: FilePermissions) -> io::Result<()> {
+    // Permissions haven't been fully figured out in wasi yet, so this is
+    // likely temporary
+    unsupported()
+}
+
+pub fn rmdir(p: &Path) -> io::Result<()> {
+    let (dir, file) = open_parent(p)?;
+    dir.remove_directory(file.as_os_str().as_bytes())
+}
+
+pub fn sync_dir(p: &Path) -> io::Result<()> {
+    DirHandle::open(p)?.dir.fsync()
+}
+
+pub fn remove_dir_all(path: &Path) -> io::Result<()> {
+    let filetype = lstat(path)?.file_type();
+    if filetype.is_symlink() {
+        unlink(path)
+    } else {
+        remove_dir_all_recursive(path)
+    }
+}
+
+fn remove_dir_all_recursive(path: &Path) -> io::Result<()> {
+    for child in readdir(path)? {
+        let child = child?;
+        if child.file_type()?.is_dir() {
+            remove_dir_all_recursive(&child.path())?;
+        } else {
+            unlink(&child.path())?;
+        }
+    }
+    rmdir(path)
+}
+
+pub fn readlink(p: &Path) -> io::Result<PathBuf> {
+    let (dir, file) = open_parent(p)?;
+    read_link(dir, &file)
+}
+
+fn read_link(fd: &WasiFd, file: &Path) -> io::Result<PathBuf> {
+    // Try to get a best effort initial capacity for the vector we're going to
+    // fill. Note that if it's not a symlink we don't use a file to avoid
+    // allocating gigabytes if you read_link a huge movie file by accident.
+    // Additionally we add 1 to the initial size so if it doesn't change until
+    // when we call `readlink` the returned length will be less than the
+    // capacity, guaranteeing that we got all the data.
+    let meta = metadata_at(fd, 0, file)?;
+    let initial_size = if meta.file_type().is_symlink() {
+        (meta.size() as usize).saturating_add(1)
+    } else {
+        1 // this'll fail in just a moment
+    };
+
+    // Now that we have an initial guess of how big to make our buffer, call
+    // `readlink` in a loop until it fails or reports it filled fewer bytes than
+    // we asked for, indicating we got everything.
+    let file = file.as_os_str().as_bytes();
+    let mut destination = vec![0u8; initial_size];
+    loop {
+        let len = fd.readlink(file, &mut destination)?;
+        if len < destination.len() {
+            destination.truncate(len);
+            destination.shrink_to_fit();
+            return Ok(PathBuf::from(OsString::from_vec(destination)));
+        }
+        let amt_to_add = destination.len();
+        destination.extend(vec![0u8; amt_to_add]);
+    }
+}
+
+pub fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    symlink(&readlink(from)?, to)
+}
+
+pub fn file_id(p: &Path) -> io::Result<(u64, u64)> {
+    let attr = stat(p)?;
+    Ok((attr.meta.st_dev as u64, attr.meta.st_ino as u64))
+}
+
+pub fn copy_ownership(_from: &FileAttr, _to: &Path, _follow: bool) -> io::Result<()> {
+    unsupported()
+}
+
+pub fn copy_xattrs(_from: &Path, _to: &Path) -> io::Result<()> {
+    unsupported()
+}
+
+pub fn set_times(_p: &Path, _accessed: SystemTime, _modified: SystemTime) -> io::Result<()> {
+    unsupported()
+}
+
+pub fn symlink(src: &Path, dst: &Path) -> io::Result<()> {
+    let (dst, dst_file) = open_parent(dst)?;
+    dst.symlink(src.as_os_str().as_bytes(), dst_file.as_os_str().as_bytes())
+}
+
+pub fn link(src: &Path, dst: &Path) -> io::Result<()> {
+    let (src, src_file) = open_parent(src)?;
+    let (dst, dst_file) = open_parent(dst)?;
+    src.link(
+        abi::LOOKUP_SYMLINK_FOLLOW,
+        src_file.as_os_str().as_bytes(),
+        dst,
+        dst_file.as_os_str().as_bytes(),
+    )
+}
+
+pub fn stat(p: &Path) -> io::Result<FileAttr> {
+    let (dir, file) = open_parent(p)?;
+    metadata_at(dir, abi::LOOKUP_SYMLINK_FOLLOW, &file)
+}
+
+pub fn lstat(p: &Path) -> io::Result<FileAttr> {
+    let (dir, file) = open_parent(p)?;
+    metadata_at(dir, 0, &file)
+}
+
+pub fn try_exists(p: &Path) -> io::Result<bool> {
+    match stat(p) {
+        Ok(_) => Ok(true),
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+fn metadata_at(
+    fd: &WasiFd,
+    flags: abi::lookupflags,
+    path: &Path,
+) -> io::Result<FileAttr> {
+    let mut ret = FileAttr::zero();
+    ret.meta = fd.path_filestat_get(flags, path.as_os_str().as_bytes())?;
+    Ok(ret)
+}
+
+pub fn canonicalize(_p: &Path) -> io::Result<PathBuf> {
+    // This seems to not be in wasi's API yet, and we may need to end up
+    // emulating it ourselves. For now just return an error.
+    unsupported()
+}
+
+fn open_at(fd: &WasiFd, path: &Path, opts: &OpenOptions) -> io::Result<File> {
+    let fd = fd.open(
+        opts.dirflags,
+        path.as_os_str().as_bytes(),
+        opts.oflags(),
+        opts.get_rights_base(),
+        opts.get_rights_inheriting(),
+        opts.fdflags(),
+    )?;
+    Ok(File { fd })
+}
+
+/// A directory handed to us by the host at startup.
+///
+/// WASI is capability based: there is no ambient filesystem namespace and no
+/// working directory, so every path-based operation has to be performed
+/// relative to one of these preopened directory descriptors.
+struct Preopen {
+    path: PathBuf,
+    fd: WasiFd,
+}
+
+static mut PREOPENS: *const Vec<Preopen> = 0 as *const _;
+
+/// Returns the table of preopened directories, querying the host on first
+/// use.
+///
+/// Preopens are numbered consecutively from fd 3, so we probe upwards until
+/// `fd_prestat_get` reports `EBADF`. The descriptors are owned by the table
+/// for the rest of the program, which is never torn down.
+fn preopens() -> &'static [Preopen] {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| unsafe {
+        let mut ret = Vec::new();
+        let mut fd = 3;
+        loop {
+            let mut prestat: abi::prestat = mem::zeroed();
+            match abi::fd_prestat_get(fd, &mut prestat) {
+                abi::ESUCCESS => {}
+                _ => break,
+            }
+            if prestat.pr_type == abi::PREOPENTYPE_DIR {
+                let mut name = vec![0u8; prestat.pr_name_len];
+                if abi::fd_prestat_dir_name(fd, name.as_mut_ptr(), name.len()) ==
+                    abi::ESUCCESS
+                {
+                    // Some hosts include a trailing NUL in the name length.
+                    if name.last() == Some(&0) {
+                        name.pop();
+                    }
+                    ret.push(Preopen {
+                        path: PathBuf::from(OsString::from_vec(name)),
+                        fd: WasiFd::from_raw(fd),
+                    });
+                }
+            }
+            fd += 1;
+        }
+        PREOPENS = Box::into_raw(Box::new(ret));
+    });
+    unsafe { &*PREOPENS }
+}
+
+fn is_cwd(path: &Path) -> bool {
+    path.components().all(|c| c == Component::CurDir)
+}
+
+/// Resolves `p` to a preopened directory and a path relative to it.
+///
+/// The preopen whose name is the longest component-wise prefix of `p` wins,
+/// mirroring what the WASI libc does. Relative paths are resolved against a
+/// preopen named `.` if the host provided one. Escaping the chosen directory
+/// through `..` or symlinks is rejected by the host itself with
+/// `ENOTCAPABLE`.
+fn open_parent(p: &Path) -> io::Result<(&'static WasiFd, PathBuf)> {
+    let mut best: Option<(&'static Preopen, PathBuf, usize)> = None;
+    for preopen in preopens() {
+        let rel = if is_cwd(&preopen.path) {
+            if p.is_relative() { Some(p.to_path_buf()) } else { None }
+        } else {
+            p.strip_prefix(&preopen.path).ok().map(|r| r.to_path_buf())
+        };
+        let rel = match rel {
+            Some(rel) => rel,
+            None => continue,
+        };
+        let depth = preopen.path.components().count();
+        if best.as_ref().map(|b| depth > b.2).unwrap_or(true) {
+            best = Some((preopen, rel, depth));
+        }
+    }
+
+    match best {
+        Some((preopen, rel, _)) => {
+            let rel = if rel.as_os_str().is_empty() { PathBuf::from(".") } else { rel };
+            Ok((&preopen.fd, rel))
+        }
+        None => {
+            let msg = format!("failed to find a preopened file descriptor \
+                               through which {:?} could be opened", p);
+            Err(io::Error::new(io::ErrorKind::PermissionDenied, msg))
+        }
+    }
+}
+
+pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
+    copy_with_progress(from, to, &mut Progress::new(&mut |_, _| Ok(()), u64::max_value()))
+}
+
+pub fn copy_with_progress(from: &Path, to: &Path, progress: &mut Progress) -> io::Result<u64> {
+    use fs::File;
+
+    let mut reader = File::open(from)?;
+    let mut writer = File::create(to)?;
+    let len = reader.metadata()?.len();
+
+    let ret = copy_chunks(&mut reader, &mut writer, 0, len, progress)?;
+    progress.finish(ret, len)?;
+    Ok(ret)
+}
@@ -0,0 +1,124 @@
+//! System bindings for the WASI platform
+//!
+//! This module contains the facade (aka platform-specific) implementations of
+//! OS level functionality for `wasm32-unknown-wasi`. Unlike the bare wasm
+//! target there's an actual host interface here, so arguments, the
+//! environment, clocks and (capability-based) filesystem access are all
+//! implemented on top of the `wasi_unstable` imports in `abi`.
+//!
+//! Everything that WASI has no notion of yet (threads, sockets, processes)
+//! reuses the stubs from the wasm backend.
+
+use io;
+use os::raw::c_char;
+
+pub mod abi;
+
+#[path = "../wasm/alloc.rs"]
+pub mod alloc;
+pub mod args;
+#[cfg(feature = "backtrace")]
+#[path = "../wasm/backtrace.rs"]
+pub mod backtrace;
+#[path = "../wasm/cmath.rs"]
+pub mod cmath;
+#[path = "../wasm/condvar.rs"]
+pub mod condvar;
+pub mod env;
+pub mod ext;
+pub mod fd;
+pub mod fs;
+#[path = "../wasm/memchr.rs"]
+pub mod memchr;
+#[path = "../wasm/mutex.rs"]
+pub mod mutex;
+#[path = "../wasm/net.rs"]
+pub mod net;
+pub mod os;
+#[path = "../wasm/os_str.rs"]
+pub mod os_str;
+#[path = "../wasm/path.rs"]
+pub mod path;
+#[path = "../wasm/pipe.rs"]
+pub mod pipe;
+pub mod process;
+#[path = "../wasm/rwlock.rs"]
+pub mod rwlock;
+#[path = "../wasm/stack_overflow.rs"]
+pub mod stack_overflow;
+pub mod stdio;
+#[path = "../wasm/thread.rs"]
+pub mod thread;
+#[path = "../wasm/thread_local.rs"]
+pub mod thread_local;
+pub mod time;
+
+#[cfg(not(test))]
+pub fn init() {
+}
+
+pub fn unsupported<T>() -> io::Result<T> {
+    Err(unsupported_err())
+}
+
+pub fn unsupported_err() -> io::Error {
+    io::Error::new(io::ErrorKind::Other,
+                   "operation not supported on wasi yet")
+}
+
+/// Converts a WASI errno return value into an `io::Result`.
+pub fn cvt(err: abi::errno) -> io::Result<()> {
+    if err == abi::ESUCCESS {
+        Ok(())
+    } else {
+        Err(io::Error::from_raw_os_error(err as i32))
+    }
+}
+
+pub fn decode_error_kind(errno: i32) -> io::ErrorKind {
+    match errno as abi::errno {
+        abi::ECONNREFUSED => io::ErrorKind::ConnectionRefused,
+        abi::ECONNRESET => io::ErrorKind::ConnectionReset,
+        abi::EPERM | abi::EACCES | abi::ENOTCAPABLE => io::ErrorKind::PermissionDenied,
+        abi::EPIPE => io::ErrorKind::BrokenPipe,
+        abi::ENOTCONN => io::ErrorKind::NotConnected,
+        abi::ECONNABORTED => io::ErrorKind::ConnectionAborted,
+        abi::EADDRNOTAVAIL => io::ErrorKind::AddrNotAvailable,
+        abi::EADDRINUSE => io::ErrorKind::AddrInUse,
+        abi::ENOENT => io::ErrorKind::NotFound,
+        abi::EINTR => io::ErrorKind::Interrupted,
+        abi::EINVAL => io::ErrorKind::InvalidInput,
+        abi::ETIMEDOUT => io::ErrorKind::TimedOut,
+        abi::EEXIST => io::ErrorKind::AlreadyExists,
+        abi::EAGAIN => io::ErrorKind::WouldBlock,
+        _ => io::ErrorKind::Other,
+    }
+}
+
+// This enum is used as the storage for a bunch of types which can't actually
+// exist.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub enum Void {}
+
+pub unsafe fn strlen(mut s: *const c_char) -> usize {
+    let mut n = 0;
+    while *s != 0 {
+        n += 1;
+        s = s.offset(1);
+    }
+    return n
+}
+
+pub unsafe fn abort_internal() -> ! {
+    ::intrinsics::abort()
+}
+
+pub fn hashmap_random_keys() -> (u64, u64) {
+    let mut ret = (0u64, 0u64);
+    unsafe {
+        let base = &mut ret as *mut (u64, u64) as *mut u8;
+        let len = ::mem::size_of_val(&ret);
+        cvt(abi::random_get(base, len)).expect("random_get failure");
+    }
+    return ret
+}
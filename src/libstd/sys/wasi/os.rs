@@ -0,0 +1,263 @@
+use error::Error as StdError;
+use ffi::{CStr, OsString, OsStr};
+use fmt;
+use io;
+use iter;
+use marker::PhantomData;
+use memchr;
+use os::raw::c_char;
+use os::wasi::prelude::*;
+use path::{self, PathBuf};
+use ptr;
+use slice;
+use sys::abi;
+use sys::{cvt, unsupported};
+use sys_common::mutex::{Mutex, MutexGuard};
+use vec;
+
+pub fn errno() -> i32 {
+    // All WASI calls report their errors through their return value, so
+    // there's no thread-local errno to consult.
+    0
+}
+
+pub fn error_string(errno: i32) -> String {
+    let msg = match errno as abi::errno {
+        abi::ESUCCESS => "no error",
+        abi::E2BIG => "argument list too long",
+        abi::EACCES => "permission denied",
+        abi::EADDRINUSE => "address in use",
+        abi::EADDRNOTAVAIL => "address not available",
+        abi::EAFNOSUPPORT => "address family not supported",
+        abi::EAGAIN => "resource unavailable, try again",
+        abi::EALREADY => "connection already in progress",
+        abi::EBADF => "bad file descriptor",
+        abi::EBUSY => "device or resource busy",
+        abi::ECONNABORTED => "connection aborted",
+        abi::ECONNREFUSED => "connection refused",
+        abi::ECONNRESET => "connection reset",
+        abi::EEXIST => "file exists",
+        abi::EFAULT => "bad address",
+        abi::EFBIG => "file too large",
+        abi::EINTR => "interrupted function",
+        abi::EINVAL => "invalid argument",
+        abi::EIO => "I/O error",
+        abi::EISDIR => "is a directory",
+        abi::ELOOP => "too many levels of symbolic links",
+        abi::EMFILE => "file descriptor value too large",
+        abi::ENAMETOOLONG => "filename too long",
+        abi::ENOENT => "no such file or directory",
+        abi::ENOMEM => "not enough space",
+        abi::ENOSPC => "no space left on device",
+        abi::ENOSYS => "function not supported",
+        abi::ENOTCONN => "the socket is not connected",
+        abi::ENOTDIR => "not a directory or a symbolic link to a directory",
+        abi::ENOTEMPTY => "directory not empty",
+        abi::ENOTSUP => "not supported",
+        abi::EPERM => "operation not permitted",
+        abi::EPIPE => "broken pipe",
+        abi::EROFS => "read-only file system",
+        abi::ESPIPE => "invalid seek",
+        abi::ETIMEDOUT => "connection timed out",
+        abi::EXDEV => "cross-device link",
+        abi::ENOTCAPABLE => "extension: capabilities insufficient",
+        _ => return format!("unknown error {}", errno),
+    };
+    msg.to_string()
+}
+
+pub fn getcwd() -> io::Result<PathBuf> {
+    unsupported()
+}
+
+pub fn chdir(_: &path::Path) -> io::Result<()> {
+    unsupported()
+}
+
+pub struct SplitPaths<'a> {
+    iter: iter::Map<slice::Split<'a, u8, fn(&u8) -> bool>,
+                    fn(&'a [u8]) -> PathBuf>,
+}
+
+pub fn split_paths(unparsed: &OsStr) -> SplitPaths {
+    fn bytes_to_path(b: &[u8]) -> PathBuf {
+        PathBuf::from(<OsStr as OsStrExt>::from_bytes(b))
+    }
+    fn is_colon(b: &u8) -> bool { *b == b':' }
+    let unparsed = unparsed.as_bytes();
+    SplitPaths {
+        iter: unparsed.split(is_colon as fn(&u8) -> bool)
+                      .map(bytes_to_path as fn(&[u8]) -> PathBuf)
+    }
+}
+
+impl<'a> Iterator for SplitPaths<'a> {
+    type Item = PathBuf;
+    fn next(&mut self) -> Option<PathBuf> { self.iter.next() }
+    fn size_hint(&self) -> (usize, Option<usize>) { self.iter.size_hint() }
+}
+
+#[derive(Debug)]
+pub struct JoinPathsError;
+
+pub fn join_paths<I, T>(paths: I) -> Result<OsString, JoinPathsError>
+    where I: Iterator<Item=T>, T: AsRef<OsStr>
+{
+    let mut joined = Vec::new();
+    let sep = b':';
+
+    for (i, path) in paths.enumerate() {
+        let path = path.as_ref().as_bytes();
+        if i > 0 { joined.push(sep) }
+        if path.contains(&sep) {
+            return Err(JoinPathsError)
+        }
+        joined.extend_from_slice(path);
+    }
+    Ok(OsStringExt::from_vec(joined))
+}
+
+impl fmt::Display for JoinPathsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        "path segment contains separator `:`".fmt(f)
+    }
+}
+
+impl StdError for JoinPathsError {
+    fn description(&self) -> &str { "failed to join paths" }
+}
+
+pub fn current_exe() -> io::Result<PathBuf> {
+    unsupported()
+}
+
+pub struct Env {
+    iter: vec::IntoIter<(OsString, OsString)>,
+    _dont_send_or_sync_me: PhantomData<*mut ()>,
+}
+
+impl Iterator for Env {
+    type Item = (OsString, OsString);
+    fn next(&mut self) -> Option<(OsString, OsString)> { self.iter.next() }
+    fn size_hint(&self) -> (usize, Option<usize>) { self.iter.size_hint() }
+}
+
+pub unsafe fn env_lock() -> MutexGuard<'static> {
+    // We never call `ENV_LOCK.init()`, so it is UB to attempt to
+    // acquire this mutex reentrantly!
+    static ENV_LOCK: Mutex = Mutex::new();
+    ENV_LOCK.lock()
+}
+
+// WASI hands us the environment once at startup and has no notion of
+// modifying it, so we keep our own copy which `setenv` and `unsetenv` edit.
+// Only ever accessed with `env_lock` held.
+static mut ENV: *mut Vec<(OsString, OsString)> = 0 as *mut _;
+
+unsafe fn environ() -> &'static mut Vec<(OsString, OsString)> {
+    if ENV.is_null() {
+        ENV = Box::into_raw(Box::new(load_environ().unwrap_or(Vec::new())));
+    }
+    &mut *ENV
+}
+
+unsafe fn load_environ() -> io::Result<Vec<(OsString, OsString)>> {
+    let mut count = 0;
+    let mut buf_size = 0;
+    cvt(abi::environ_sizes_get(&mut count, &mut buf_size))?;
+
+    let mut environ = vec![ptr::null_mut::<u8>(); count];
+    let mut buf = vec![0u8; buf_size];
+    cvt(abi::environ_get(environ.as_mut_ptr(), buf.as_mut_ptr()))?;
+
+    Ok(environ.iter().filter_map(|&entry| {
+        parse(CStr::from_ptr(entry as *const c_char).to_bytes())
+    }).collect())
+}
+
+fn parse(input: &[u8]) -> Option<(OsString, OsString)> {
+    // Strategy (copied from glibc): Variable name and value are separated
+    // by an ASCII equals sign '='. Since a variable name must not be
+    // empty, allow variable names starting with an equals sign. Skip all
+    // malformed lines.
+    if input.is_empty() {
+        return None;
+    }
+    let pos = memchr::memchr(b'=', &input[1..]).map(|p| p + 1);
+    pos.map(|p| (
+        OsStringExt::from_vec(input[..p].to_vec()),
+        OsStringExt::from_vec(input[p+1..].to_vec()),
+    ))
+}
+
+/// Returns a vector of (variable, value) byte-vector pairs for all the
+/// environment variables of the current process.
+pub fn env() -> Env {
+    unsafe {
+        let _guard = env_lock();
+        Env {
+            iter: environ().clone().into_iter(),
+            _dont_send_or_sync_me: PhantomData,
+        }
+    }
+}
+
+pub use sys_common::env_snapshot::{EnvSnapshot, EnvSnapshotIter};
+
+/// Returns a snapshot of the environment variables of the current process.
+pub fn env_snapshot() -> EnvSnapshot {
+    EnvSnapshot::new(env())
+}
+
+pub fn getenv(k: &OsStr) -> io::Result<Option<OsString>> {
+    unsafe {
+        let _guard = env_lock();
+        Ok(environ().iter()
+            .find(|&&(ref key, _)| &**key == k)
+            .map(|&(_, ref value)| value.clone()))
+    }
+}
+
+pub fn setenv(k: &OsStr, v: &OsStr) -> io::Result<()> {
+    // Keep the same restrictions as the other platforms so programs don't
+    // start depending on being able to create unrepresentable variables.
+    if k.is_empty() || k.as_bytes().contains(&b'=') || k.as_bytes().contains(&0) ||
+        v.as_bytes().contains(&0)
+    {
+        return Err(io::Error::from_raw_os_error(abi::EINVAL as i32))
+    }
+
+    unsafe {
+        let _guard = env_lock();
+        let env = environ();
+        match env.iter_mut().find(|&&mut (ref key, _)| &**key == k) {
+            Some(&mut (_, ref mut value)) => *value = v.to_os_string(),
+            None => env.push((k.to_os_string(), v.to_os_string())),
+        }
+        Ok(())
+    }
+}
+
+pub fn unsetenv(n: &OsStr) -> io::Result<()> {
+    unsafe {
+        let _guard = env_lock();
+        environ().retain(|&(ref key, _)| &**key != n);
+        Ok(())
+    }
+}
+
+pub fn temp_dir() -> PathBuf {
+    panic!("no temporary directory on wasi")
+}
+
+pub fn home_dir() -> Option<PathBuf> {
+    None
+}
+
+pub fn exit(code: i32) -> ! {
+    unsafe { abi::proc_exit(code as abi::exitcode) }
+}
+
+pub fn getpid() -> u32 {
+    panic!("unsupported");
+}
@@ -1,6 +1,6 @@
 use io;
 use sys::unsupported;
-use sys_common::backtrace::Frame;
+use sys_common::backtrace::{Frame, Module};
 
 pub struct BacktraceContext;
 
@@ -25,3 +25,7 @@ pub fn foreach_symbol_fileline<F>(_: Frame,
 {
     unsupported()
 }
+
+pub fn modules() -> io::Result<Vec<Module>> {
+    unsupported()
+}
@@ -5,6 +5,7 @@ use io::{self, SeekFrom};
 use path::{Path, PathBuf};
 use sys::time::SystemTime;
 use sys::{unsupported, Void};
+use sys_common::fs::Progress;
 
 pub struct File(Void);
 
@@ -136,6 +137,12 @@ impl fmt::Debug for ReadDir {
     }
 }
 
+impl ReadDir {
+    pub fn set_buffer_size(&mut self, _size: usize) {
+        match self.0 {}
+    }
+}
+
 impl Iterator for ReadDir {
     type Item = io::Result<DirEntry>;
 
@@ -196,14 +203,34 @@ impl File {
         match self.0 {}
     }
 
+    pub fn allocate(&self, _len: u64) -> io::Result<()> {
+        match self.0 {}
+    }
+
     pub fn read(&self, _buf: &mut [u8]) -> io::Result<usize> {
         match self.0 {}
     }
 
+    pub fn read_at(&self, _buf: &mut [u8], _offset: u64) -> io::Result<usize> {
+        match self.0 {}
+    }
+
+    pub fn read_vectored_at(&self, _bufs: &mut [&mut [u8]], _offset: u64) -> io::Result<usize> {
+        match self.0 {}
+    }
+
     pub fn write(&self, _buf: &[u8]) -> io::Result<usize> {
         match self.0 {}
     }
 
+    pub fn write_at(&self, _buf: &[u8], _offset: u64) -> io::Result<usize> {
+        match self.0 {}
+    }
+
+    pub fn write_vectored_at(&self, _bufs: &[&[u8]], _offset: u64) -> io::Result<usize> {
+        match self.0 {}
+    }
+
     pub fn flush(&self) -> io::Result<()> {
         match self.0 {}
     }
@@ -220,6 +247,14 @@ impl File {
         match self.0 {}
     }
 
+    pub fn lock(&self, _exclusive: bool, _block: bool) -> io::Result<bool> {
+        match self.0 {}
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        match self.0 {}
+    }
+
     pub fn diverge(&self) -> ! {
         match self.0 {}
     }
@@ -241,6 +276,44 @@ impl fmt::Debug for File {
     }
 }
 
+pub struct DirHandle(Void);
+
+impl DirHandle {
+    pub fn open(_path: &Path) -> io::Result<DirHandle> {
+        unsupported()
+    }
+
+    pub fn open_at(&self, _path: &Path, _opts: &OpenOptions) -> io::Result<File> {
+        match self.0 {}
+    }
+
+    pub fn open_dir_at(&self, _path: &Path) -> io::Result<DirHandle> {
+        match self.0 {}
+    }
+
+    pub fn read_dir_at(&self, _path: &Path) -> io::Result<ReadDir> {
+        match self.0 {}
+    }
+
+    pub fn metadata_at(&self, _path: &Path, _follow: bool) -> io::Result<FileAttr> {
+        match self.0 {}
+    }
+
+    pub fn remove_file_at(&self, _path: &Path) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn rename_at(&self, _from: &Path, _to_dir: &DirHandle, _to: &Path) -> io::Result<()> {
+        match self.0 {}
+    }
+}
+
+impl fmt::Debug for DirHandle {
+    fn fmt(&self, _f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {}
+    }
+}
+
 pub fn readdir(_p: &Path) -> io::Result<ReadDir> {
     unsupported()
 }
@@ -261,6 +334,10 @@ pub fn rmdir(_p: &Path) -> io::Result<()> {
     unsupported()
 }
 
+pub fn sync_dir(_p: &Path) -> io::Result<()> {
+    unsupported()
+}
+
 pub fn remove_dir_all(_path: &Path) -> io::Result<()> {
     unsupported()
 }
@@ -269,6 +346,26 @@ pub fn readlink(_p: &Path) -> io::Result<PathBuf> {
     unsupported()
 }
 
+pub fn copy_symlink(_from: &Path, _to: &Path) -> io::Result<()> {
+    unsupported()
+}
+
+pub fn file_id(_p: &Path) -> io::Result<(u64, u64)> {
+    unsupported()
+}
+
+pub fn copy_ownership(_from: &FileAttr, _to: &Path, _follow: bool) -> io::Result<()> {
+    unsupported()
+}
+
+pub fn copy_xattrs(_from: &Path, _to: &Path) -> io::Result<()> {
+    unsupported()
+}
+
+pub fn set_times(_p: &Path, _accessed: SystemTime, _modified: SystemTime) -> io::Result<()> {
+    unsupported()
+}
+
 pub fn symlink(_src: &Path, _dst: &Path) -> io::Result<()> {
     unsupported()
 }
@@ -285,6 +382,10 @@ pub fn lstat(_p: &Path) -> io::Result<FileAttr> {
     unsupported()
 }
 
+pub fn try_exists(_p: &Path) -> io::Result<bool> {
+    unsupported()
+}
+
 pub fn canonicalize(_p: &Path) -> io::Result<PathBuf> {
     unsupported()
 }
@@ -292,3 +393,8 @@ pub fn canonicalize(_p: &Path) -> io::Result<PathBuf> {
 pub fn copy(_from: &Path, _to: &Path) -> io::Result<u64> {
     unsupported()
 }
+
+pub fn copy_with_progress(_from: &Path, _to: &Path, _progress: &mut Progress)
+                          -> io::Result<u64> {
+    unsupported()
+}
@@ -1,6 +1,6 @@
 use fmt;
 use io;
-use net::{SocketAddr, Shutdown, Ipv4Addr, Ipv6Addr};
+use net::{SocketAddr, Shutdown, Ipv4Addr, Ipv6Addr, NameInfoFlag};
 use time::Duration;
 use sys::{unsupported, Void};
 use convert::TryFrom;
@@ -306,6 +306,11 @@ impl<'a> TryFrom<(&'a str, u16)> for LookupHost {
     }
 }
 
+pub fn lookup_name_info(_addr: &SocketAddr, _flags: &[NameInfoFlag])
+                        -> io::Result<(String, String)> {
+    unsupported()
+}
+
 #[allow(nonstandard_style)]
 pub mod netc {
     pub const AF_INET: u8 = 0;
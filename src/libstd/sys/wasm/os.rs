@@ -73,6 +73,13 @@ pub fn env() -> Env {
     panic!("not supported on web assembly")
 }
 
+pub use sys_common::env_snapshot::{EnvSnapshot, EnvSnapshotIter};
+
+/// Returns a snapshot of the environment variables of the current process.
+pub fn env_snapshot() -> EnvSnapshot {
+    EnvSnapshot::new(env())
+}
+
 pub fn getenv(k: &OsStr) -> io::Result<Option<OsString>> {
     Ok(GetEnvSysCall::perform(k))
 }
@@ -27,20 +27,28 @@ pub fn args() -> Args {
 
 /// Implements the Windows command-line argument parsing algorithm.
 ///
-/// Microsoft's documentation for the Windows CLI argument format can be found at
-/// <https://docs.microsoft.com/en-us/previous-versions//17w5ykft(v=vs.85)>.
+/// This follows the rules used by the MSVC C runtime since Visual Studio
+/// 2008, documented at
+/// <https://docs.microsoft.com/en-us/cpp/cpp/parsing-cpp-command-line-arguments>,
+/// which is how the vast majority of programs split their command line:
 ///
-/// Windows includes a function to do this in shell32.dll,
-/// but linking with that DLL causes the process to be registered as a GUI application.
-/// GUI applications add a bunch of overhead, even if no windows are drawn. See
-/// <https://randomascii.wordpress.com/2018/12/03/a-not-called-function-can-cause-a-5x-slowdown/>.
+/// * The executable name at the start is special: it ends at the first
+///   space or tab outside of quotes, and quotes only toggle quoting, they
+///   can't be escaped (they can't appear in file names anyway).
+/// * Arguments are separated by runs of spaces and tabs outside of quotes.
+/// * Backslashes are literal unless they're followed by a quote, in which
+///   case each pair becomes one backslash and an odd one left over escapes
+///   the quote.
+/// * An unescaped quote toggles quoting, except that inside quotes two
+///   consecutive quotes are a literal quote and quoting continues.
 ///
-/// This function was tested for equivalence to the shell32.dll implementation in
-/// Windows 10 Pro v1803, using an exhaustive test suite available at
-/// <https://gist.github.com/notriddle/dde431930c392e428055b2dc22e638f5> or
-/// <https://paste.gg/p/anonymous/47d6ed5f5bd549168b1c69c799825223>.
-unsafe fn parse_lp_cmd_line<F: Fn() -> OsString>(lp_cmd_line: *const u16, exe_name: F)
-                                                 -> Vec<OsString> {
+/// The last rule is where this differs from `CommandLineToArgvW` in
+/// shell32.dll, which ends the quoted section after such a pair. Linking
+/// with shell32 also causes the process to be registered as a GUI
+/// application, which has a lot of overhead even if no windows are drawn, see
+/// <https://randomascii.wordpress.com/2018/12/03/a-not-called-function-can-cause-a-5x-slowdown/>.
+pub unsafe fn parse_lp_cmd_line<F: Fn() -> OsString>(lp_cmd_line: *const u16, exe_name: F)
+                                                     -> Vec<OsString> {
     const BACKSLASH: u16 = '\\' as u16;
     const QUOTE: u16 = '"' as u16;
     const TAB: u16 = '\t' as u16;
@@ -50,106 +58,84 @@ unsafe fn parse_lp_cmd_line<F: Fn() -> OsString>(lp_cmd_line: *const u16, exe_na
         ret_val.push(exe_name());
         return ret_val;
     }
-    let mut cmd_line = {
+    let cmd_line = {
         let mut end = 0;
         while *lp_cmd_line.offset(end) != 0 {
             end += 1;
         }
         slice::from_raw_parts(lp_cmd_line, end as usize)
     };
-    // The executable name at the beginning is special.
-    cmd_line = match cmd_line[0] {
-        // The executable name ends at the next quote mark,
-        // no matter what.
-        QUOTE => {
-            let args = {
-                let mut cut = cmd_line[1..].splitn(2, |&c| c == QUOTE);
-                if let Some(exe) = cut.next() {
-                    ret_val.push(OsString::from_wide(exe));
-                }
-                cut.next()
-            };
-            if let Some(args) = args {
-                args
-            } else {
-                return ret_val;
-            }
+    let mut code_units = cmd_line.iter().cloned().peekable();
+
+    // The executable name at the beginning is special: there are no escapes,
+    // a quote always toggles `in_quotes` and is dropped.
+    let mut in_quotes = false;
+    let mut cur = Vec::new();
+    while let Some(w) = code_units.next() {
+        match w {
+            QUOTE => in_quotes = !in_quotes,
+            SPACE | TAB if !in_quotes => break,
+            _ => cur.push(w),
         }
-        // Implement quirk: when they say whitespace here,
-        // they include the entire ASCII control plane:
-        // "However, if lpCmdLine starts with any amount of whitespace, CommandLineToArgvW
-        // will consider the first argument to be an empty string. Excess whitespace at the
-        // end of lpCmdLine is ignored."
-        0...SPACE => {
-            ret_val.push(OsString::new());
-            &cmd_line[1..]
-        },
-        // The executable name ends at the next whitespace,
-        // no matter what.
-        _ => {
-            let args = {
-                let mut cut = cmd_line.splitn(2, |&c| c > 0 && c <= SPACE);
-                if let Some(exe) = cut.next() {
-                    ret_val.push(OsString::from_wide(exe));
-                }
-                cut.next()
-            };
-            if let Some(args) = args {
-                args
-            } else {
-                return ret_val;
-            }
+    }
+    ret_val.push(OsString::from_wide(&cur));
+
+    // Skip the whitespace separating the executable name from the arguments.
+    while let Some(&w) = code_units.peek() {
+        if w != SPACE && w != TAB {
+            break
         }
-    };
-    let mut cur = Vec::new();
+        code_units.next();
+    }
+
+    // Parse the arguments proper.
+    let mut in_arg = false;
     let mut in_quotes = false;
-    let mut was_in_quotes = false;
-    let mut backslash_count: usize = 0;
-    for &c in cmd_line {
-        match c {
-            // backslash
+    let mut cur = Vec::new();
+    while let Some(w) = code_units.next() {
+        match w {
+            SPACE | TAB if !in_quotes => {
+                if in_arg {
+                    ret_val.push(OsString::from_wide(&cur));
+                    cur.truncate(0);
+                    in_arg = false;
+                }
+            }
             BACKSLASH => {
-                backslash_count += 1;
-                was_in_quotes = false;
-            },
-            QUOTE if backslash_count % 2 == 0 => {
-                cur.extend(iter::repeat(b'\\' as u16).take(backslash_count / 2));
-                backslash_count = 0;
-                if was_in_quotes {
-                    cur.push('"' as u16);
-                    was_in_quotes = false;
+                in_arg = true;
+                let mut backslash_count = 1;
+                while code_units.peek() == Some(&BACKSLASH) {
+                    code_units.next();
+                    backslash_count += 1;
+                }
+                if code_units.peek() == Some(&QUOTE) {
+                    cur.extend(iter::repeat(BACKSLASH).take(backslash_count / 2));
+                    // An odd number of backslashes escapes the quote.
+                    if backslash_count % 2 == 1 {
+                        code_units.next();
+                        cur.push(QUOTE);
+                    }
                 } else {
-                    was_in_quotes = in_quotes;
-                    in_quotes = !in_quotes;
+                    cur.extend(iter::repeat(BACKSLASH).take(backslash_count));
                 }
             }
-            QUOTE if backslash_count % 2 != 0 => {
-                cur.extend(iter::repeat(b'\\' as u16).take(backslash_count / 2));
-                backslash_count = 0;
-                was_in_quotes = false;
-                cur.push(b'"' as u16);
+            QUOTE if in_quotes && code_units.peek() == Some(&QUOTE) => {
+                // Two quotes inside a quoted section are a literal quote.
+                code_units.next();
+                cur.push(QUOTE);
             }
-            SPACE | TAB if !in_quotes => {
-                cur.extend(iter::repeat(b'\\' as u16).take(backslash_count));
-                if !cur.is_empty() || was_in_quotes {
-                    ret_val.push(OsString::from_wide(&cur[..]));
-                    cur.truncate(0);
-                }
-                backslash_count = 0;
-                was_in_quotes = false;
+            QUOTE => {
+                in_arg = true;
+                in_quotes = !in_quotes;
             }
             _ => {
-                cur.extend(iter::repeat(b'\\' as u16).take(backslash_count));
-                backslash_count = 0;
-                was_in_quotes = false;
-                cur.push(c);
+                in_arg = true;
+                cur.push(w);
             }
         }
     }
-    cur.extend(iter::repeat(b'\\' as u16).take(backslash_count));
-    // include empty quoted strings at the end of the arguments list
-    if !cur.is_empty() || was_in_quotes || in_quotes {
-        ret_val.push(OsString::from_wide(&cur[..]));
+    if in_arg {
+        ret_val.push(OsString::from_wide(&cur));
     }
     ret_val
 }
@@ -174,6 +160,10 @@ impl Args {
             args: self
         }
     }
+
+    pub fn get(&self, i: usize) -> Option<OsString> {
+        self.parsed_args_list.as_slice().get(i).cloned()
+    }
 }
 
 impl Iterator for Args {
@@ -240,18 +230,31 @@ mod tests {
 
     #[test]
     fn genius_quotes() {
-        chk(r#"EXE "" """#, &["EXE", "", ""]);
+        chk(r#"EXE "" """#, &["EXE", "", "\""]);
         chk(r#"EXE "" """"#, &["EXE", "", "\""]);
         chk(
             r#"EXE "this is """all""" in the same argument""#,
             &["EXE", "this is \"all\" in the same argument"]
         );
         chk(r#"EXE "a"""#, &["EXE", "a\""]);
-        chk(r#"EXE "a"" a"#, &["EXE", "a\"", "a"]);
+        chk(r#"EXE "a"" a"#, &["EXE", "a\" a"]);
+        chk(r#"EXE "a""b"c"#, &["EXE", "a\"bc"]);
+        chk(r#"EXE a""b"#, &["EXE", "ab"]);
         // quotes cannot be escaped in command names
         chk(r#""EXE" check"#, &["EXE", "check"]);
         chk(r#""EXE check""#, &["EXE check"]);
-        chk(r#""EXE """for""" check"#, &["EXE ", r#"for""#, "check"]);
-        chk(r#""EXE \"for\" check"#, &[r#"EXE \"#, r#"for""#,  "check"]);
+        chk(r#""EXE """for""" check"#, &["EXE for check"]);
+        chk(r#""EXE \"for\" check"#, &[r#"EXE \for\ check"#]);
+        chk(r#""C:\dir\" a"#, &[r#"C:\dir\"#, "a"]);
+    }
+
+    #[test]
+    fn backslashes() {
+        chk(r#"EXE a\"#, &["EXE", r#"a\"#]);
+        chk(r#"EXE a\\ b"#, &["EXE", r#"a\\"#, "b"]);
+        chk(r#"EXE "a\\" b"#, &["EXE", r#"a\"#, "b"]);
+        chk(r#"EXE "a\" b"#, &["EXE", r#"a" b"#]);
+        chk(r#"EXE \\\"a"#, &["EXE", r#"\"a"#]);
+        chk(r#"EXE \\\\"a"#, &["EXE", r#"\\a"#]);
     }
 }
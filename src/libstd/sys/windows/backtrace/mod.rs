@@ -20,7 +20,7 @@ use mem;
 use ptr;
 use sys::c;
 use sys::dynamic_lib::DynamicLibrary;
-use sys_common::backtrace::Frame;
+use sys_common::backtrace::{Frame, Module};
 
 macro_rules! sym {
     ($lib:expr, $e:expr, $t:ident) => (
@@ -87,6 +87,56 @@ pub fn unwind_backtrace(frames: &mut [Frame]) -> io::Result<(usize, BacktraceCon
     }
 }
 
+pub fn modules() -> io::Result<Vec<Module>> {
+    let process = unsafe { c::GetCurrentProcess() };
+
+    // The module list can change between the calls, so retry until the
+    // buffer was big enough.
+    let mut handles: Vec<c::HMODULE> = Vec::new();
+    loop {
+        let mut needed = 0;
+        let ret = unsafe {
+            c::K32EnumProcessModules(process,
+                                     handles.as_mut_ptr(),
+                                     (handles.len() * mem::size_of::<c::HMODULE>()) as c::DWORD,
+                                     &mut needed)
+        };
+        if ret == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let count = needed as usize / mem::size_of::<c::HMODULE>();
+        if count <= handles.len() {
+            handles.truncate(count);
+            break;
+        }
+        handles.resize(count, ptr::null_mut());
+    }
+
+    let mut modules = Vec::with_capacity(handles.len());
+    for &handle in &handles {
+        let mut info: c::MODULEINFO = unsafe { mem::zeroed() };
+        let ret = unsafe {
+            c::K32GetModuleInformation(process,
+                                       handle,
+                                       &mut info,
+                                       mem::size_of::<c::MODULEINFO>() as c::DWORD)
+        };
+        if ret == 0 {
+            // The module was unloaded since it was listed.
+            continue;
+        }
+        let path = super::fill_utf16_buf(|buf, sz| unsafe {
+            c::GetModuleFileNameW(handle, buf, sz)
+        }, super::os2path)?;
+        modules.push(Module {
+            path,
+            base: info.lpBaseOfDll as usize,
+            size: info.SizeOfImage as usize,
+        });
+    }
+    Ok(modules)
+}
+
 fn set_frames<W: StackWalker>(StackWalk: W, frames: &mut [Frame]) -> io::Result<usize> {
     let process = unsafe { c::GetCurrentProcess() };
     let thread = unsafe { c::GetCurrentProcess() };
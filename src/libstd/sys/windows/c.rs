@@ -167,6 +167,7 @@ pub const STD_ERROR_HANDLE: DWORD = -12i32 as DWORD;
 pub const HANDLE_FLAG_INHERIT: DWORD = 0x00000001;
 
 pub const PROGRESS_CONTINUE: DWORD = 0;
+pub const PROGRESS_CANCEL: DWORD = 1;
 
 pub const ERROR_FILE_NOT_FOUND: DWORD = 2;
 pub const ERROR_PATH_NOT_FOUND: DWORD = 3;
@@ -179,8 +180,11 @@ pub const ERROR_INVALID_PARAMETER: DWORD = 87;
 pub const ERROR_BROKEN_PIPE: DWORD = 109;
 pub const ERROR_CALL_NOT_IMPLEMENTED: DWORD = 120;
 pub const ERROR_INSUFFICIENT_BUFFER: DWORD = 122;
+pub const ERROR_SHARING_VIOLATION: DWORD = 32;
+pub const ERROR_LOCK_VIOLATION: DWORD = 33;
 pub const ERROR_ALREADY_EXISTS: DWORD = 183;
 pub const ERROR_NO_DATA: DWORD = 232;
+pub const ERROR_DIRECTORY: DWORD = 267;
 pub const ERROR_ENVVAR_NOT_FOUND: DWORD = 203;
 pub const ERROR_OPERATION_ABORTED: DWORD = 995;
 pub const ERROR_IO_PENDING: DWORD = 997;
@@ -188,6 +192,9 @@ pub const ERROR_TIMEOUT: DWORD = 0x5B4;
 
 pub const E_NOTIMPL: HRESULT = 0x80004001u32 as HRESULT;
 
+pub const LOCKFILE_FAIL_IMMEDIATELY: DWORD = 0x00000001;
+pub const LOCKFILE_EXCLUSIVE_LOCK: DWORD = 0x00000002;
+
 pub const INVALID_HANDLE_VALUE: HANDLE = !0 as HANDLE;
 
 pub const FACILITY_NT_BIT: DWORD = 0x1000_0000;
@@ -213,8 +220,19 @@ pub const SRWLOCK_INIT: SRWLOCK = SRWLOCK { ptr: ptr::null_mut() };
 pub const DETACHED_PROCESS: DWORD = 0x00000008;
 pub const CREATE_NEW_PROCESS_GROUP: DWORD = 0x00000200;
 pub const CREATE_UNICODE_ENVIRONMENT: DWORD = 0x00000400;
+pub const CREATE_SUSPENDED: DWORD = 0x00000004;
+pub const CREATE_BREAKAWAY_FROM_JOB: DWORD = 0x01000000;
 pub const STARTF_USESTDHANDLES: DWORD = 0x00000100;
 
+pub const JOB_OBJECT_LIMIT_JOB_TIME: DWORD = 0x00000004;
+pub const JOB_OBJECT_LIMIT_PROCESS_MEMORY: DWORD = 0x00000100;
+pub const JOB_OBJECT_LIMIT_JOB_MEMORY: DWORD = 0x00000200;
+pub const JOB_OBJECT_LIMIT_BREAKAWAY_OK: DWORD = 0x00000800;
+pub const JOB_OBJECT_LIMIT_SILENT_BREAKAWAY_OK: DWORD = 0x00001000;
+pub const JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE: DWORD = 0x00002000;
+pub const JobObjectExtendedLimitInformation: c_int = 9;
+
+pub const AF_UNIX: c_int = 1;
 pub const AF_INET: c_int = 2;
 pub const AF_INET6: c_int = 23;
 pub const SD_BOTH: c_int = 2;
@@ -414,6 +432,11 @@ pub struct FILE_END_OF_FILE_INFO {
     pub EndOfFile: LARGE_INTEGER,
 }
 
+#[repr(C)]
+pub struct FILE_ALLOCATION_INFO {
+    pub AllocationSize: LARGE_INTEGER,
+}
+
 #[repr(C)]
 pub struct REPARSE_DATA_BUFFER {
     pub ReparseTag: c_uint,
@@ -505,6 +528,13 @@ pub struct GUID {
     pub Data4: [BYTE; 8],
 }
 
+pub const FOLDERID_Profile: GUID = GUID {
+    Data1: 0x5E6C858F,
+    Data2: 0x0E22,
+    Data3: 0x4760,
+    Data4: [0x9A, 0xFE, 0xEA, 0x33, 0x17, 0xB6, 0x71, 0x73],
+};
+
 #[repr(C)]
 pub struct WSAPROTOCOLCHAIN {
     pub ChainLen: c_int,
@@ -673,6 +703,14 @@ pub struct KDHELP64 {
     pub Reserved: [u64; 5],
 }
 
+#[repr(C)]
+#[cfg(feature = "backtrace")]
+pub struct MODULEINFO {
+    pub lpBaseOfDll: LPVOID,
+    pub SizeOfImage: DWORD,
+    pub EntryPoint: LPVOID,
+}
+
 #[cfg(target_arch = "x86")]
 #[repr(C)]
 pub struct CONTEXT {
@@ -888,6 +926,39 @@ pub struct CONTEXT {
     pub Wvr: [DWORD; ARM64_MAX_WATCHPOINTS],
 }
 
+#[repr(C)]
+pub struct JOBOBJECT_BASIC_LIMIT_INFORMATION {
+    pub PerProcessUserTimeLimit: LARGE_INTEGER,
+    pub PerJobUserTimeLimit: LARGE_INTEGER,
+    pub LimitFlags: DWORD,
+    pub MinimumWorkingSetSize: SIZE_T,
+    pub MaximumWorkingSetSize: SIZE_T,
+    pub ActiveProcessLimit: DWORD,
+    pub Affinity: ULONG_PTR,
+    pub PriorityClass: DWORD,
+    pub SchedulingClass: DWORD,
+}
+
+#[repr(C)]
+pub struct IO_COUNTERS {
+    pub ReadOperationCount: u64,
+    pub WriteOperationCount: u64,
+    pub OtherOperationCount: u64,
+    pub ReadTransferCount: u64,
+    pub WriteTransferCount: u64,
+    pub OtherTransferCount: u64,
+}
+
+#[repr(C)]
+pub struct JOBOBJECT_EXTENDED_LIMIT_INFORMATION {
+    pub BasicLimitInformation: JOBOBJECT_BASIC_LIMIT_INFORMATION,
+    pub IoInfo: IO_COUNTERS,
+    pub ProcessMemoryLimit: SIZE_T,
+    pub JobMemoryLimit: SIZE_T,
+    pub PeakProcessMemoryUsed: SIZE_T,
+    pub PeakJobMemoryUsed: SIZE_T,
+}
+
 #[repr(C)]
 pub struct SOCKADDR_STORAGE_LH {
     pub ss_family: ADDRESS_FAMILY,
@@ -927,6 +998,19 @@ pub struct sockaddr_in6 {
     pub sin6_scope_id: c_ulong,
 }
 
+pub const UNIX_PATH_MAX: usize = 108;
+
+#[repr(C)]
+#[derive(Copy)]
+pub struct sockaddr_un {
+    pub sun_family: ADDRESS_FAMILY,
+    pub sun_path: [CHAR; UNIX_PATH_MAX],
+}
+
+impl Clone for sockaddr_un {
+    fn clone(&self) -> sockaddr_un { *self }
+}
+
 #[repr(C)]
 #[derive(Copy, Clone)]
 pub struct in_addr {
@@ -1019,6 +1103,10 @@ extern "system" {
     pub fn RemoveDirectoryW(lpPathName: LPCWSTR) -> BOOL;
     pub fn SetFileAttributesW(lpFileName: LPCWSTR,
                               dwFileAttributes: DWORD) -> BOOL;
+    pub fn SetFileTime(hFile: HANDLE,
+                       lpCreationTime: *const FILETIME,
+                       lpLastAccessTime: *const FILETIME,
+                       lpLastWriteTime: *const FILETIME) -> BOOL;
     pub fn GetFileInformationByHandle(hFile: HANDLE,
                             lpFileInformation: LPBY_HANDLE_FILE_INFORMATION)
                             -> BOOL;
@@ -1086,6 +1174,15 @@ extern "system" {
                                    -> BOOL;
     pub fn GetExitCodeProcess(hProcess: HANDLE, lpExitCode: LPDWORD) -> BOOL;
     pub fn TerminateProcess(hProcess: HANDLE, uExitCode: UINT) -> BOOL;
+    pub fn ResumeThread(hThread: HANDLE) -> DWORD;
+    pub fn CreateJobObjectW(lpJobAttributes: LPSECURITY_ATTRIBUTES,
+                            lpName: LPCWSTR) -> HANDLE;
+    pub fn SetInformationJobObject(hJob: HANDLE,
+                                   JobObjectInformationClass: c_int,
+                                   lpJobObjectInformation: LPVOID,
+                                   cbJobObjectInformationLength: DWORD) -> BOOL;
+    pub fn AssignProcessToJobObject(hJob: HANDLE, hProcess: HANDLE) -> BOOL;
+    pub fn TerminateJobObject(hJob: HANDLE, uExitCode: UINT) -> BOOL;
     pub fn CreateProcessW(lpApplicationName: LPCWSTR,
                           lpCommandLine: LPWSTR,
                           lpProcessAttributes: LPSECURITY_ATTRIBUTES,
@@ -1111,6 +1208,10 @@ extern "system" {
     pub fn DeleteFileW(lpPathName: LPCWSTR) -> BOOL;
     pub fn GetCurrentDirectoryW(nBufferLength: DWORD, lpBuffer: LPWSTR) -> DWORD;
     pub fn SetCurrentDirectoryW(lpPathName: LPCWSTR) -> BOOL;
+    pub fn GetFullPathNameW(lpFileName: LPCWSTR,
+                            nBufferLength: DWORD,
+                            lpBuffer: LPWSTR,
+                            lpFilePart: *mut LPWSTR) -> DWORD;
     pub fn WideCharToMultiByte(CodePage: UINT,
                                dwFlags: DWORD,
                                lpWideCharStr: LPCWSTR,
@@ -1165,6 +1266,19 @@ extern "system" {
                      lpOverlapped: LPOVERLAPPED)
                      -> BOOL;
     pub fn CloseHandle(hObject: HANDLE) -> BOOL;
+    pub fn LockFileEx(hFile: HANDLE,
+                      dwFlags: DWORD,
+                      dwReserved: DWORD,
+                      nNumberOfBytesToLockLow: DWORD,
+                      nNumberOfBytesToLockHigh: DWORD,
+                      lpOverlapped: LPOVERLAPPED)
+                      -> BOOL;
+    pub fn UnlockFile(hFile: HANDLE,
+                      dwFileOffsetLow: DWORD,
+                      dwFileOffsetHigh: DWORD,
+                      nNumberOfBytesToUnlockLow: DWORD,
+                      nNumberOfBytesToUnlockHigh: DWORD)
+                      -> BOOL;
     pub fn CreateHardLinkW(lpSymlinkFileName: LPCWSTR,
                            lpTargetFileName: LPCWSTR,
                            lpSecurityAttributes: LPSECURITY_ATTRIBUTES)
@@ -1225,8 +1339,11 @@ extern "system" {
                        hints: *const ADDRINFOA,
                        res: *mut *mut ADDRINFOA) -> c_int;
     pub fn freeaddrinfo(res: *mut ADDRINFOA);
+    pub fn getnameinfo(sa: *const SOCKADDR, salen: socklen_t,
+                       host: *mut c_char, hostlen: DWORD,
+                       serv: *mut c_char, servlen: DWORD,
+                       flags: c_int) -> c_int;
 
-    #[cfg(feature = "backtrace")]
     pub fn LoadLibraryW(name: LPCWSTR) -> HMODULE;
     #[cfg(feature = "backtrace")]
     pub fn FreeLibrary(handle: HMODULE) -> BOOL;
@@ -1336,6 +1453,26 @@ compat_fn! {
     }
 }
 
+// The psapi functions kernel32 exports from Windows 7 on, for listing the
+// modules in a backtrace.
+#[cfg(feature = "backtrace")]
+compat_fn! {
+    kernel32:
+
+    pub fn K32EnumProcessModules(_hProcess: HANDLE,
+                                 _lphModule: *mut HMODULE,
+                                 _cb: DWORD,
+                                 _lpcbNeeded: LPDWORD) -> BOOL {
+        SetLastError(ERROR_CALL_NOT_IMPLEMENTED as DWORD); 0
+    }
+    pub fn K32GetModuleInformation(_hProcess: HANDLE,
+                                   _hModule: HMODULE,
+                                   _lpmodinfo: *mut MODULEINFO,
+                                   _cb: DWORD) -> BOOL {
+        SetLastError(ERROR_CALL_NOT_IMPLEMENTED as DWORD); 0
+    }
+}
+
 #[cfg(all(target_env = "gnu", feature = "backtrace"))]
 mod gnu {
     use super::*;
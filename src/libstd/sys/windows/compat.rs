@@ -28,6 +28,24 @@ pub fn lookup(module: &str, symbol: &str) -> Option<usize> {
     }
 }
 
+/// Like `lookup`, but loads `module` first if it isn't already. The module
+/// stays loaded.
+pub fn load(module: &str, symbol: &str) -> Option<usize> {
+    let mut module: Vec<u16> = module.encode_utf16().collect();
+    module.push(0);
+    let symbol = CString::new(symbol).unwrap();
+    unsafe {
+        let handle = c::LoadLibraryW(module.as_ptr());
+        if handle.is_null() {
+            return None;
+        }
+        match c::GetProcAddress(handle, symbol.as_ptr()) as usize {
+            0 => None,
+            n => Some(n),
+        }
+    }
+}
+
 pub fn store_func(ptr: &AtomicUsize, module: &str, symbol: &str,
                   fallback: usize) -> usize {
     let value = lookup(module, symbol).unwrap_or(fallback);
@@ -0,0 +1,30 @@
+//! Windows-specific extensions to primitives in the `std::env` module.
+
+#![unstable(feature = "user_home_dir", issue = "0")]
+
+use path::PathBuf;
+use sys;
+
+/// Returns the current user's home directory.
+///
+/// Unlike the deprecated [`env::home_dir`], this doesn't consult `HOME`,
+/// which Windows doesn't set. An empty `USERPROFILE` counts as unset, and
+/// without it the profile folder is asked for with `SHGetKnownFolderPath`.
+/// That loads shell32.dll into the process if it isn't already.
+///
+/// [`env::home_dir`]: ../../../env/fn.home_dir.html
+///
+/// # Examples
+///
+/// ```
+/// #![feature(user_home_dir)]
+/// use std::os::windows::env;
+///
+/// match env::user_home_dir() {
+///     Some(path) => println!("Your home directory, probably: {}", path.display()),
+///     None => println!("Impossible to get your home dir!"),
+/// }
+/// ```
+pub fn user_home_dir() -> Option<PathBuf> {
+    sys::os::user_home_dir()
+}
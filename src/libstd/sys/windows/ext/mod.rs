@@ -10,9 +10,11 @@
 #![doc(cfg(windows))]
 #![allow(missing_docs)]
 
+pub mod env;
 pub mod ffi;
 pub mod fs;
 pub mod io;
+pub mod net;
 pub mod raw;
 pub mod process;
 pub mod thread;
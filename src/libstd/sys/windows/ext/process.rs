@@ -2,10 +2,13 @@
 
 #![stable(feature = "process_extensions", since = "1.2.0")]
 
+use ffi::{OsStr, OsString};
+use io;
 use os::windows::io::{FromRawHandle, RawHandle, AsRawHandle, IntoRawHandle};
 use process;
 use sys;
 use sys_common::{AsInnerMut, AsInner, FromInner, IntoInner};
+use time::Duration;
 
 #[stable(feature = "process_extensions", since = "1.2.0")]
 impl FromRawHandle for process::Stdio {
@@ -102,6 +105,109 @@ pub trait CommandExt {
     /// [1]: https://msdn.microsoft.com/en-us/library/windows/desktop/ms684863(v=vs.85).aspx
     #[stable(feature = "windows_process_extensions", since = "1.16.0")]
     fn creation_flags(&mut self, flags: u32) -> &mut process::Command;
+
+    /// Appends a literal argument to the command line of the process.
+    ///
+    /// Unlike [`arg`], the argument is not quoted or escaped in any way, it's
+    /// added to the command line as is, separated from the preceding one by a
+    /// single space. This is needed for programs which don't split their
+    /// command line the way the C runtime does, such as `cmd.exe /C` or
+    /// `msiexec`, and it's up to the caller to quote the text the way the
+    /// child expects.
+    ///
+    /// [`arg`]: ../../../../std/process/struct.Command.html#method.arg
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(windows_process_extensions_raw_arg)]
+    /// use std::os::windows::process::CommandExt;
+    /// use std::process::Command;
+    ///
+    /// let status = Command::new("cmd.exe")
+    ///     .arg("/C")
+    ///     .raw_arg(r#""echo "hello world" & pause""#)
+    ///     .status()
+    ///     .expect("failed to run cmd.exe");
+    /// ```
+    #[unstable(feature = "windows_process_extensions_raw_arg", issue = "0")]
+    fn raw_arg<S: AsRef<OsStr>>(&mut self, raw_text: S) -> &mut process::Command;
+
+    /// Returns the command line that would be passed to `CreateProcess`.
+    ///
+    /// This is the program name and arguments exactly as they will be seen
+    /// by the child, which is useful to check how arguments end up quoted.
+    /// The program name is the one given to [`Command::new`]; the search of
+    /// a `PATH` passed in the environment of the child only happens when it
+    /// is spawned.
+    ///
+    /// # Errors
+    ///
+    /// Fails in the same cases as spawning would because of the arguments,
+    /// for example if they contain a nul character.
+    ///
+    /// [`Command::new`]: ../../../../std/process/struct.Command.html#method.new
+    #[unstable(feature = "windows_process_extensions_raw_arg", issue = "0")]
+    fn raw_command_line(&self) -> io::Result<OsString>;
+
+    /// Spawns the process into a new [job object] with the given limits.
+    ///
+    /// Every process the child starts is put in the same job, unless the
+    /// limits allow it to break away. The job belongs to the returned
+    /// [`Child`], which can terminate all of them at once with
+    /// [`ChildExt::kill_tree`].
+    ///
+    /// [job object]: https://docs.microsoft.com/en-us/windows/desktop/procthread/job-objects
+    /// [`Child`]: ../../../../std/process/struct.Child.html
+    /// [`ChildExt::kill_tree`]: trait.ChildExt.html#tymethod.kill_tree
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(windows_process_job_object)]
+    /// use std::os::windows::process::{ChildExt, CommandExt, JobLimits};
+    /// use std::process::Command;
+    ///
+    /// let mut limits = JobLimits::new();
+    /// limits.kill_on_close(true).process_memory_limit(512 * 1024 * 1024);
+    /// let mut child = Command::new("build.bat")
+    ///     .job_object(limits)
+    ///     .spawn()
+    ///     .expect("failed to spawn");
+    /// child.kill_tree().expect("failed to kill the build");
+    /// ```
+    #[unstable(feature = "windows_process_job_object", issue = "0")]
+    fn job_object(&mut self, limits: JobLimits) -> &mut process::Command;
+
+    /// Sets whether the child and every process it starts are terminated
+    /// when the [`Child`] is dropped, or when this process exits.
+    ///
+    /// This spawns the child into a job object as [`job_object`] does, with
+    /// [`JobLimits::kill_on_close`] set. The job handle isn't inherited, so
+    /// ours is the only one, and Windows closes it even if we crash. If
+    /// `job_object` has already been called this only changes that limit;
+    /// calling `job_object` afterwards replaces all of the limits.
+    ///
+    /// [`Child`]: ../../../../std/process/struct.Child.html
+    /// [`job_object`]: #tymethod.job_object
+    /// [`JobLimits::kill_on_close`]: struct.JobLimits.html#method.kill_on_close
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(windows_process_job_object)]
+    /// use std::os::windows::process::CommandExt;
+    /// use std::process::Command;
+    ///
+    /// let server = Command::new("server.exe")
+    ///     .kill_on_drop(true)
+    ///     .spawn()
+    ///     .expect("failed to spawn");
+    /// // ...
+    /// drop(server); // The server and its workers are gone.
+    /// ```
+    #[unstable(feature = "windows_process_job_object", issue = "0")]
+    fn kill_on_drop(&mut self, kill: bool) -> &mut process::Command;
 }
 
 #[stable(feature = "windows_process_extensions", since = "1.16.0")]
@@ -110,4 +216,108 @@ impl CommandExt for process::Command {
         self.as_inner_mut().creation_flags(flags);
         self
     }
+
+    fn raw_arg<S: AsRef<OsStr>>(&mut self, raw_text: S) -> &mut process::Command {
+        self.as_inner_mut().raw_arg(raw_text.as_ref());
+        self
+    }
+
+    fn raw_command_line(&self) -> io::Result<OsString> {
+        self.as_inner().raw_command_line()
+    }
+
+    fn job_object(&mut self, limits: JobLimits) -> &mut process::Command {
+        self.as_inner_mut().job_object(limits.0);
+        self
+    }
+
+    fn kill_on_drop(&mut self, kill: bool) -> &mut process::Command {
+        self.as_inner_mut().kill_on_drop(kill);
+        self
+    }
+}
+
+/// Limits for the job object a process is spawned into by
+/// [`CommandExt::job_object`].
+///
+/// By default none of the limits is enabled: the processes in the job keep
+/// running when the job is closed, and they can't break away from it.
+///
+/// [`CommandExt::job_object`]: trait.CommandExt.html#tymethod.job_object
+#[unstable(feature = "windows_process_job_object", issue = "0")]
+#[derive(Clone, Debug, Default)]
+pub struct JobLimits(sys::process::JobLimits);
+
+#[unstable(feature = "windows_process_job_object", issue = "0")]
+impl JobLimits {
+    /// Creates a set of limits with nothing enabled.
+    pub fn new() -> JobLimits {
+        JobLimits::default()
+    }
+
+    /// Terminates all processes in the job when the last handle to it is
+    /// closed, which happens when the [`Child`] is dropped.
+    ///
+    /// [`Child`]: ../../../../std/process/struct.Child.html
+    pub fn kill_on_close(&mut self, kill: bool) -> &mut JobLimits {
+        self.0.kill_on_close = kill;
+        self
+    }
+
+    /// Limits the memory each process in the job can commit, in bytes.
+    pub fn process_memory_limit(&mut self, bytes: usize) -> &mut JobLimits {
+        self.0.process_memory = Some(bytes);
+        self
+    }
+
+    /// Limits the memory all processes in the job can commit together, in
+    /// bytes.
+    pub fn job_memory_limit(&mut self, bytes: usize) -> &mut JobLimits {
+        self.0.job_memory = Some(bytes);
+        self
+    }
+
+    /// Limits the user-mode CPU time all processes in the job can use
+    /// together. Once it's used up, all of them are terminated.
+    pub fn cpu_time_limit(&mut self, limit: Duration) -> &mut JobLimits {
+        self.0.cpu_time = Some(limit);
+        self
+    }
+
+    /// Allows processes in the job to start children outside of it by
+    /// passing `CREATE_BREAKAWAY_FROM_JOB` to `CreateProcess`.
+    pub fn breakaway_ok(&mut self, ok: bool) -> &mut JobLimits {
+        self.0.breakaway_ok = ok;
+        self
+    }
+
+    /// Starts all children of processes in the job outside of it, as if
+    /// they passed `CREATE_BREAKAWAY_FROM_JOB`.
+    pub fn silent_breakaway_ok(&mut self, ok: bool) -> &mut JobLimits {
+        self.0.silent_breakaway_ok = ok;
+        self
+    }
+}
+
+/// Windows-specific extensions to [`process::Child`].
+///
+/// [`process::Child`]: ../../../../std/process/struct.Child.html
+#[unstable(feature = "windows_process_job_object", issue = "0")]
+pub trait ChildExt {
+    /// Terminates the child and every process it started.
+    ///
+    /// This needs the job object of a child spawned with
+    /// [`CommandExt::job_object`], and fails with [`InvalidInput`] for other
+    /// children. Processes which broke away from the job are not affected.
+    ///
+    /// [`CommandExt::job_object`]: trait.CommandExt.html#tymethod.job_object
+    /// [`InvalidInput`]: ../../../../std/io/enum.ErrorKind.html#variant.InvalidInput
+    fn kill_tree(&mut self) -> io::Result<()>;
+}
+
+#[unstable(feature = "windows_process_job_object", issue = "0")]
+impl ChildExt for process::Child {
+    fn kill_tree(&mut self) -> io::Result<()> {
+        self.as_inner().kill_tree()
+    }
 }
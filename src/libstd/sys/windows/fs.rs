@@ -12,7 +12,9 @@ use sys::handle::Handle;
 use sys::time::SystemTime;
 use sys::{c, cvt};
 use sys_common::FromInner;
+use sys_common::fs::Progress;
 
+use super::path::maybe_verbatim;
 use super::to_u16s;
 
 pub struct File { handle: Handle }
@@ -81,6 +83,11 @@ impl fmt::Debug for ReadDir {
     }
 }
 
+impl ReadDir {
+    // `FindNextFileW` returns one entry at a time whatever we do.
+    pub fn set_buffer_size(&mut self, _size: usize) {}
+}
+
 impl Iterator for ReadDir {
     type Item = io::Result<DirEntry>;
     fn next(&mut self) -> Option<io::Result<DirEntry>> {
@@ -246,7 +253,7 @@ impl OpenOptions {
 
 impl File {
     pub fn open(path: &Path, opts: &OpenOptions) -> io::Result<File> {
-        let path = to_u16s(path)?;
+        let path = maybe_verbatim(path)?;
         let handle = unsafe {
             c::CreateFileW(path.as_ptr(),
                            opts.get_access_mode()?,
@@ -284,6 +291,25 @@ impl File {
         Ok(())
     }
 
+    pub fn allocate(&self, len: u64) -> io::Result<()> {
+        // An allocation size below the end of the file would truncate it.
+        if len <= self.file_attr()?.size() {
+            return Ok(())
+        }
+        let mut info = c::FILE_ALLOCATION_INFO {
+            AllocationSize: len as c::LARGE_INTEGER,
+        };
+        let size = mem::size_of_val(&info);
+        cvt(unsafe {
+            c::SetFileInformationByHandle(self.handle.raw(),
+                                          c::FileAllocationInfo,
+                                          &mut info as *mut _ as *mut _,
+                                          size as c::DWORD)
+        })?;
+        // Allocating doesn't move the end of the file.
+        self.truncate(len)
+    }
+
     pub fn file_attr(&self) -> io::Result<FileAttr> {
         unsafe {
             let mut info: c::BY_HANDLE_FILE_INFORMATION = mem::zeroed();
@@ -315,6 +341,13 @@ impl File {
         self.handle.read_at(buf, offset)
     }
 
+    pub fn read_vectored_at(&self, bufs: &mut [&mut [u8]], offset: u64) -> io::Result<usize> {
+        match bufs.iter_mut().find(|buf| !buf.is_empty()) {
+            Some(buf) => self.read_at(buf, offset),
+            None => Ok(0),
+        }
+    }
+
     pub fn write(&self, buf: &[u8]) -> io::Result<usize> {
         self.handle.write(buf)
     }
@@ -323,6 +356,13 @@ impl File {
         self.handle.write_at(buf, offset)
     }
 
+    pub fn write_vectored_at(&self, bufs: &[&[u8]], offset: u64) -> io::Result<usize> {
+        match bufs.iter().find(|buf| !buf.is_empty()) {
+            Some(buf) => self.write_at(buf, offset),
+            None => Ok(0),
+        }
+    }
+
     pub fn flush(&self) -> io::Result<()> { Ok(()) }
 
     pub fn seek(&self, pos: SeekFrom) -> io::Result<u64> {
@@ -423,6 +463,35 @@ impl File {
         })?;
         Ok(())
     }
+
+    // Locks the whole file, however large it grows.
+    pub fn lock(&self, exclusive: bool, block: bool) -> io::Result<bool> {
+        let mut flags = 0;
+        if exclusive {
+            flags |= c::LOCKFILE_EXCLUSIVE_LOCK;
+        }
+        if !block {
+            flags |= c::LOCKFILE_FAIL_IMMEDIATELY;
+        }
+        let mut overlapped: c::OVERLAPPED = unsafe { mem::zeroed() };
+        let res = cvt(unsafe {
+            c::LockFileEx(self.handle.raw(), flags, 0,
+                          c::DWORD::max_value(), c::DWORD::max_value(),
+                          &mut overlapped)
+        });
+        match res {
+            Ok(_) => Ok(true),
+            Err(ref e) if e.raw_os_error() == Some(c::ERROR_LOCK_VIOLATION as i32) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        cvt(unsafe {
+            c::UnlockFile(self.handle.raw(), 0, 0, c::DWORD::max_value(), c::DWORD::max_value())
+        })?;
+        Ok(())
+    }
 }
 
 impl FromInner<c::HANDLE> for File {
@@ -542,7 +611,7 @@ impl DirBuilder {
     pub fn new() -> DirBuilder { DirBuilder }
 
     pub fn mkdir(&self, p: &Path) -> io::Result<()> {
-        let p = to_u16s(p)?;
+        let p = maybe_verbatim(p)?;
         cvt(unsafe {
             c::CreateDirectoryW(p.as_ptr(), ptr::null_mut())
         })?;
@@ -550,10 +619,52 @@ impl DirBuilder {
     }
 }
 
+// Opening files relative to a directory handle needs `NtCreateFile`, which
+// isn't bound yet.
+pub enum DirHandle {}
+
+impl DirHandle {
+    pub fn open(_path: &Path) -> io::Result<DirHandle> {
+        Err(io::Error::new(io::ErrorKind::Other,
+                           "operations relative to a directory are not supported on this \
+                            platform"))
+    }
+
+    pub fn open_at(&self, _path: &Path, _opts: &OpenOptions) -> io::Result<File> {
+        match *self {}
+    }
+
+    pub fn open_dir_at(&self, _path: &Path) -> io::Result<DirHandle> {
+        match *self {}
+    }
+
+    pub fn read_dir_at(&self, _path: &Path) -> io::Result<ReadDir> {
+        match *self {}
+    }
+
+    pub fn metadata_at(&self, _path: &Path, _follow: bool) -> io::Result<FileAttr> {
+        match *self {}
+    }
+
+    pub fn remove_file_at(&self, _path: &Path) -> io::Result<()> {
+        match *self {}
+    }
+
+    pub fn rename_at(&self, _from: &Path, _to_dir: &DirHandle, _to: &Path) -> io::Result<()> {
+        match *self {}
+    }
+}
+
+impl fmt::Debug for DirHandle {
+    fn fmt(&self, _f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {}
+    }
+}
+
 pub fn readdir(p: &Path) -> io::Result<ReadDir> {
     let root = p.to_path_buf();
     let star = p.join("*");
-    let path = to_u16s(&star)?;
+    let path = maybe_verbatim(&star)?;
 
     unsafe {
         let mut wfd = mem::zeroed();
@@ -571,14 +682,14 @@ pub fn readdir(p: &Path) -> io::Result<ReadDir> {
 }
 
 pub fn unlink(p: &Path) -> io::Result<()> {
-    let p_u16s = to_u16s(p)?;
+    let p_u16s = maybe_verbatim(p)?;
     cvt(unsafe { c::DeleteFileW(p_u16s.as_ptr()) })?;
     Ok(())
 }
 
 pub fn rename(old: &Path, new: &Path) -> io::Result<()> {
-    let old = to_u16s(old)?;
-    let new = to_u16s(new)?;
+    let old = maybe_verbatim(old)?;
+    let new = maybe_verbatim(new)?;
     cvt(unsafe {
         c::MoveFileExW(old.as_ptr(), new.as_ptr(), c::MOVEFILE_REPLACE_EXISTING)
     })?;
@@ -586,11 +697,22 @@ pub fn rename(old: &Path, new: &Path) -> io::Result<()> {
 }
 
 pub fn rmdir(p: &Path) -> io::Result<()> {
-    let p = to_u16s(p)?;
+    let p = maybe_verbatim(p)?;
     cvt(unsafe { c::RemoveDirectoryW(p.as_ptr()) })?;
     Ok(())
 }
 
+// `MoveFileExW` and friends commit the directory change along with the
+// operation, and directory handles can't be flushed, so just make sure that
+// the directory is there.
+pub fn sync_dir(p: &Path) -> io::Result<()> {
+    if stat(p)?.file_type().is_dir() {
+        Ok(())
+    } else {
+        Err(io::Error::from_raw_os_error(c::ERROR_DIRECTORY as i32))
+    }
+}
+
 pub fn remove_dir_all(path: &Path) -> io::Result<()> {
     let filetype = lstat(path)?.file_type();
     if filetype.is_symlink() {
@@ -633,9 +755,55 @@ pub fn symlink(src: &Path, dst: &Path) -> io::Result<()> {
     symlink_inner(src, dst, false)
 }
 
+// Makes `to` a symlink with the same target as the symlink `from`, and to
+// a directory if that one is.
+pub fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    let dir = lstat(from)?.file_type().is_symlink_dir();
+    symlink_inner(&readlink(from)?, to, dir)
+}
+
+// Identifies the file `p` leads to, following symlinks, by the serial
+// number of its volume and its index on it.
+pub fn file_id(p: &Path) -> io::Result<(u64, u64)> {
+    let mut opts = OpenOptions::new();
+    opts.access_mode(0);
+    opts.custom_flags(c::FILE_FLAG_BACKUP_SEMANTICS);
+    let file = File::open(p, &opts)?;
+    unsafe {
+        let mut info: c::BY_HANDLE_FILE_INFORMATION = mem::zeroed();
+        cvt(c::GetFileInformationByHandle(file.handle.raw(), &mut info))?;
+        Ok((info.dwVolumeSerialNumber as u64,
+            ((info.nFileIndexHigh as u64) << 32) | (info.nFileIndexLow as u64)))
+    }
+}
+
+pub fn copy_ownership(_from: &FileAttr, _to: &Path, _follow: bool) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Other,
+                       "copying file ownership is not supported on this platform"))
+}
+
+pub fn copy_xattrs(_from: &Path, _to: &Path) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Other,
+                       "extended attributes are not supported on this platform"))
+}
+
+pub fn set_times(p: &Path, accessed: SystemTime, modified: SystemTime) -> io::Result<()> {
+    let mut opts = OpenOptions::new();
+    opts.access_mode(c::FILE_WRITE_ATTRIBUTES);
+    // This flag is so we can open directories too
+    opts.custom_flags(c::FILE_FLAG_BACKUP_SEMANTICS);
+    let file = File::open(p, &opts)?;
+    let accessed = c::FILETIME::from(accessed);
+    let modified = c::FILETIME::from(modified);
+    cvt(unsafe { c::SetFileTime(file.handle.raw(), ptr::null(), &accessed, &modified) })?;
+    Ok(())
+}
+
 pub fn symlink_inner(src: &Path, dst: &Path, dir: bool) -> io::Result<()> {
+    // The target is stored in the link as given, only the link itself is a
+    // path to open.
     let src = to_u16s(src)?;
-    let dst = to_u16s(dst)?;
+    let dst = maybe_verbatim(dst)?;
     let flags = if dir { c::SYMBOLIC_LINK_FLAG_DIRECTORY } else { 0 };
     // Formerly, symlink creation required the SeCreateSymbolicLink privilege. For the Windows 10
     // Creators Update, Microsoft loosened this to allow unprivileged symlink creation if the
@@ -660,8 +828,8 @@ pub fn symlink_inner(src: &Path, dst: &Path, dir: bool) -> io::Result<()> {
 }
 
 pub fn link(src: &Path, dst: &Path) -> io::Result<()> {
-    let src = to_u16s(src)?;
-    let dst = to_u16s(dst)?;
+    let src = maybe_verbatim(src)?;
+    let dst = maybe_verbatim(dst)?;
     cvt(unsafe {
         c::CreateHardLinkW(dst.as_ptr(), src.as_ptr(), ptr::null_mut())
     })?;
@@ -687,8 +855,19 @@ pub fn lstat(path: &Path) -> io::Result<FileAttr> {
     file.file_attr()
 }
 
+pub fn try_exists(path: &Path) -> io::Result<bool> {
+    match stat(path) {
+        Ok(_) => Ok(true),
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        // Files which are in use without sharing, such as the page file,
+        // can't be opened at all, even to read their attributes.
+        Err(ref e) if e.raw_os_error() == Some(c::ERROR_SHARING_VIOLATION as i32) => Ok(true),
+        Err(e) => Err(e),
+    }
+}
+
 pub fn set_perm(p: &Path, perm: FilePermissions) -> io::Result<()> {
-    let p = to_u16s(p)?;
+    let p = maybe_verbatim(p)?;
     unsafe {
         cvt(c::SetFileAttributesW(p.as_ptr(), perm.attrs))?;
         Ok(())
@@ -715,10 +894,28 @@ pub fn canonicalize(p: &Path) -> io::Result<PathBuf> {
 }
 
 pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
+    copy_with_progress(from, to, &mut Progress::new(&mut |_, _| Ok(()), u64::max_value()))
+}
+
+pub fn copy_with_progress(from: &Path, to: &Path, progress: &mut Progress) -> io::Result<u64> {
+    use panic::{self, AssertUnwindSafe};
+    use thread::Result as ThreadResult;
+
+    struct Context<'a, 'b: 'a> {
+        progress: &'a mut Progress<'b>,
+        size: i64,
+        total: i64,
+        result: ThreadResult<io::Result<()>>,
+    }
+
+    // Only the main stream is counted, as only its size is returned.
+    // `CopyFileEx` picks the size of each chunk itself, so `chunk_size` is
+    // only used to decide how often to report progress. The first error or
+    // panic cancels the copy, and is passed on once `CopyFileEx` returns.
     unsafe extern "system" fn callback(
         _TotalFileSize: c::LARGE_INTEGER,
         _TotalBytesTransferred: c::LARGE_INTEGER,
-        _StreamSize: c::LARGE_INTEGER,
+        StreamSize: c::LARGE_INTEGER,
         StreamBytesTransferred: c::LARGE_INTEGER,
         dwStreamNumber: c::DWORD,
         _dwCallbackReason: c::DWORD,
@@ -726,17 +923,35 @@ pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
         _hDestinationFile: c::HANDLE,
         lpData: c::LPVOID,
     ) -> c::DWORD {
-        if dwStreamNumber == 1 {*(lpData as *mut i64) = StreamBytesTransferred;}
-        c::PROGRESS_CONTINUE
+        if dwStreamNumber != 1 {
+            return c::PROGRESS_CONTINUE
+        }
+        let ctx = &mut *(lpData as *mut Context);
+        ctx.size = StreamBytesTransferred;
+        ctx.total = StreamSize;
+        let progress = &mut *ctx.progress;
+        ctx.result = panic::catch_unwind(AssertUnwindSafe(|| {
+            progress.update(StreamBytesTransferred as u64, StreamSize as u64)
+        }));
+        match ctx.result {
+            Ok(Ok(())) => c::PROGRESS_CONTINUE,
+            _ => c::PROGRESS_CANCEL,
+        }
     }
-    let pfrom = to_u16s(from)?;
-    let pto = to_u16s(to)?;
-    let mut size = 0i64;
-    cvt(unsafe {
+    let pfrom = maybe_verbatim(from)?;
+    let pto = maybe_verbatim(to)?;
+    let mut ctx = Context { progress, size: 0, total: 0, result: Ok(Ok(())) };
+    let ret = cvt(unsafe {
         c::CopyFileExW(pfrom.as_ptr(), pto.as_ptr(), Some(callback),
-                       &mut size as *mut _ as *mut _, ptr::null_mut(), 0)
-    })?;
-    Ok(size as u64)
+                       &mut ctx as *mut Context as *mut _, ptr::null_mut(), 0)
+    });
+    match ctx.result {
+        Ok(Ok(())) => { ret?; }
+        Ok(Err(e)) => return Err(e),
+        Err(panic) => panic::resume_unwind(panic),
+    }
+    ctx.progress.finish(ctx.size as u64, ctx.total as u64)?;
+    Ok(ctx.size as u64)
 }
 
 #[allow(dead_code)]
@@ -23,6 +23,15 @@ pub mod netc {
     pub use sys::c::ADDRESS_FAMILY as sa_family_t;
 }
 
+// Flags and buffer sizes for getnameinfo.
+pub const NI_MAXHOST: usize = 1025;
+pub const NI_MAXSERV: usize = 32;
+pub const NI_NOFQDN: c_int = 0x1;
+pub const NI_NUMERICHOST: c_int = 0x2;
+pub const NI_NAMEREQD: c_int = 0x4;
+pub const NI_NUMERICSERV: c_int = 0x8;
+pub const NI_DGRAM: c_int = 0x10;
+
 pub struct Socket(c::SOCKET);
 
 /// Checks whether the Windows socket interface has been started already, and
@@ -94,6 +103,10 @@ impl Socket {
             SocketAddr::V4(..) => c::AF_INET,
             SocketAddr::V6(..) => c::AF_INET6,
         };
+        Socket::new_raw(fam, ty)
+    }
+
+    pub fn new_raw(fam: c_int, ty: c_int) -> io::Result<Socket> {
         let socket = unsafe {
             match c::WSASocketW(fam, ty, 0, ptr::null_mut(), 0,
                                 c::WSA_FLAG_OVERLAPPED) {
@@ -207,6 +220,14 @@ impl Socket {
         self.recv_with_flags(buf, 0)
     }
 
+    pub fn write(&self, buf: &[u8]) -> io::Result<usize> {
+        let len = cmp::min(buf.len(), i32::max_value() as usize) as i32;
+        let ret = cvt(unsafe {
+            c::send(self.0, buf.as_ptr() as *const c_void, len, 0)
+        })?;
+        Ok(ret as usize)
+    }
+
     pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
         self.recv_with_flags(buf, c::MSG_PEEK)
     }
@@ -8,11 +8,13 @@ use error::Error as StdError;
 use ffi::{OsString, OsStr};
 use fmt;
 use io;
+use mem;
+use os::raw::c_void;
 use os::windows::ffi::EncodeWide;
 use path::{self, PathBuf};
 use ptr;
 use slice;
-use sys::{c, cvt};
+use sys::{c, compat, cvt};
 use sys::handle::Handle;
 
 use super::to_u16s;
@@ -131,6 +133,13 @@ pub fn env() -> Env {
     }
 }
 
+pub use sys_common::env_snapshot::{EnvSnapshot, EnvSnapshotIter};
+
+/// Returns a snapshot of the environment variables of the current process.
+pub fn env_snapshot() -> EnvSnapshot {
+    EnvSnapshot::new(env())
+}
+
 pub struct SplitPaths<'a> {
     data: EncodeWide<'a>,
     must_yield: bool,
@@ -287,7 +296,18 @@ pub fn temp_dir() -> PathBuf {
 pub fn home_dir() -> Option<PathBuf> {
     ::env::var_os("HOME").or_else(|| {
         ::env::var_os("USERPROFILE")
-    }).map(PathBuf::from).or_else(|| unsafe {
+    }).map(PathBuf::from).or_else(profile_dir)
+}
+
+pub fn user_home_dir() -> Option<PathBuf> {
+    match ::env::var_os("USERPROFILE") {
+        Some(ref dir) if !dir.is_empty() => Some(PathBuf::from(dir)),
+        _ => unsafe { known_folder_path(&c::FOLDERID_Profile) }.or_else(profile_dir),
+    }
+}
+
+fn profile_dir() -> Option<PathBuf> {
+    unsafe {
         let me = c::GetCurrentProcess();
         let mut token = ptr::null_mut();
         if c::OpenProcessToken(me, c::TOKEN_READ, &mut token) == 0 {
@@ -301,7 +321,34 @@ pub fn home_dir() -> Option<PathBuf> {
                 _ => sz - 1, // sz includes the null terminator
             }
         }, super::os2path).ok()
-    })
+    }
+}
+
+// shell32 isn't linked, see `args::parse_lp_cmd_line`, so it's only loaded
+// once something asks for a known folder.
+unsafe fn known_folder_path(id: &c::GUID) -> Option<PathBuf> {
+    type GetPath = unsafe extern "system" fn(*const c::GUID, c::DWORD, c::HANDLE,
+                                             *mut c::LPWSTR) -> c::HRESULT;
+    type Free = unsafe extern "system" fn(*mut c_void);
+
+    let get_path = compat::load("shell32.dll", "SHGetKnownFolderPath")?;
+    let free = compat::load("ole32.dll", "CoTaskMemFree")?;
+    let get_path = mem::transmute::<usize, GetPath>(get_path);
+    let free = mem::transmute::<usize, Free>(free);
+
+    let mut path = ptr::null_mut();
+    let ret = if get_path(id, 0, ptr::null_mut(), &mut path) >= 0 && !path.is_null() {
+        let mut len = 0;
+        while *path.offset(len) != 0 {
+            len += 1;
+        }
+        Some(super::os2path(slice::from_raw_parts(path, len as usize)))
+    } else {
+        None
+    };
+    // The buffer has to be freed even when the call fails.
+    free(path as *mut c_void);
+    ret
 }
 
 pub fn exit(code: i32) -> ! {
@@ -2,6 +2,7 @@
 /// wrapper around the "WTF-8" encoding; see the `wtf8` module for more.
 
 use borrow::Cow;
+use collections::TryReserveError;
 use fmt;
 use sys_common::wtf8::{Wtf8, Wtf8Buf};
 use mem;
@@ -83,6 +84,14 @@ impl Buf {
         unsafe { mem::transmute(self.inner.as_slice()) }
     }
 
+    pub fn into_encoded_bytes(self) -> Vec<u8> {
+        self.inner.into_bytes()
+    }
+
+    pub unsafe fn from_encoded_bytes_unchecked(bytes: Vec<u8>) -> Buf {
+        Buf { inner: Wtf8Buf::from_bytes_unchecked(bytes) }
+    }
+
     pub fn into_string(self) -> Result<String, Buf> {
         self.inner.into_string().map_err(|buf| Buf { inner: buf })
     }
@@ -99,6 +108,14 @@ impl Buf {
         self.inner.reserve_exact(additional)
     }
 
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.inner.try_reserve(additional)
+    }
+
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.inner.try_reserve_exact(additional)
+    }
+
     pub fn shrink_to_fit(&mut self) {
         self.inner.shrink_to_fit()
     }
@@ -149,6 +166,36 @@ impl Slice {
         Buf { inner: buf }
     }
 
+    fn from_wtf8(s: &Wtf8) -> &Slice {
+        unsafe { mem::transmute(s) }
+    }
+
+    #[inline]
+    pub fn starts_with(&self, prefix: &Slice) -> bool {
+        self.inner.starts_with(&prefix.inner)
+    }
+
+    #[inline]
+    pub fn ends_with(&self, suffix: &Slice) -> bool {
+        self.inner.ends_with(&suffix.inner)
+    }
+
+    pub fn find(&self, needle: &Slice) -> Option<usize> {
+        self.inner.find(&needle.inner)
+    }
+
+    pub fn get(&self, begin: usize, end: usize) -> Option<&Slice> {
+        self.inner.get(begin, end).map(Slice::from_wtf8)
+    }
+
+    pub fn to_ascii_lowercase(&self) -> Buf {
+        Buf { inner: self.inner.to_ascii_lowercase() }
+    }
+
+    pub fn to_ascii_uppercase(&self) -> Buf {
+        Buf { inner: self.inner.to_ascii_uppercase() }
+    }
+
     #[inline]
     pub fn into_box(&self) -> Box<Slice> {
         unsafe { mem::transmute(self.inner.into_box()) }
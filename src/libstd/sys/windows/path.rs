@@ -1,6 +1,11 @@
-use path::Prefix;
-use ffi::OsStr;
+use path::{Path, PathBuf, Prefix};
+use ffi::{OsStr, OsString};
+use os::windows::ffi::OsStringExt;
+use io;
 use mem;
+use ptr;
+use sys::c;
+use super::{fill_utf16_buf, to_u16s};
 
 fn os_str_as_u8_slice(s: &OsStr) -> &[u8] {
     unsafe { mem::transmute(s) }
@@ -91,3 +96,118 @@ pub fn parse_prefix<'a>(path: &'a OsStr) -> Option<Prefix> {
 
 pub const MAIN_SEP_STR: &str = "\\";
 pub const MAIN_SEP: char = '\\';
+
+/// Makes `path` absolute with `GetFullPathNameW`, which normalizes it the
+/// way Win32 does for every non-verbatim path anyway.
+pub fn absolute(path: &Path) -> io::Result<PathBuf> {
+    let path = to_u16s(path)?;
+    fill_utf16_buf(|buf, sz| unsafe {
+        c::GetFullPathNameW(path.as_ptr(), sz, buf, ptr::null_mut())
+    }, |buf| {
+        PathBuf::from(OsString::from_wide(buf))
+    })
+}
+
+/// Converts `path` to a nul-terminated wide string for the file system APIs,
+/// switching to a verbatim `\\?\` path when the plain one would run into
+/// the `MAX_PATH` limit.
+///
+/// Verbatim paths skip the normalization Win32 applies to other paths, so the
+/// path is made absolute with `GetFullPathNameW` first. That resolves `.`
+/// and `..` relative to the current directory and turns `/` into `\`,
+/// exactly as would happen without the prefix. Short paths are passed through
+/// as they are, as are device paths such as `\\.\COM1` (which
+/// `GetFullPathNameW` also produces for reserved names like `NUL`).
+pub fn maybe_verbatim(path: &Path) -> io::Result<Vec<u16>> {
+    get_long_path(to_u16s(path)?)
+}
+
+fn get_long_path(path: Vec<u16>) -> io::Result<Vec<u16>> {
+    // Normally the limit is 260 code units including the nul, but some APIs
+    // such as `CreateDirectoryW` stop at 248 to leave room for an 8.3 name.
+    const LEGACY_MAX_PATH: usize = 248;
+    const SEP: u16 = b'\\' as u16;
+    const ALT_SEP: u16 = b'/' as u16;
+    const COLON: u16 = b':' as u16;
+    const VERBATIM_PREFIX: &[u16] = &[SEP, SEP, b'?' as u16, SEP];
+    const NT_PREFIX: &[u16] = &[SEP, b'?' as u16, b'?' as u16, SEP];
+    const DEVICE_PREFIX: &[u16] = &[SEP, SEP, b'.' as u16, SEP];
+    const UNC_PREFIX: &[u16] = &[SEP, SEP, b'?' as u16, SEP,
+                                 b'U' as u16, b'N' as u16, b'C' as u16, SEP];
+
+    let is_sep = |c: u16| c == SEP || c == ALT_SEP;
+    if path.starts_with(VERBATIM_PREFIX) || path.starts_with(NT_PREFIX) || path == [0] {
+        return Ok(path)
+    }
+    if path.len() < LEGACY_MAX_PATH {
+        // `C:\...` and `\\server\...` can't grow when made absolute, every
+        // other short path (relative, `\foo` or `C:foo`) depends on the
+        // current directory.
+        let absolute_disk = path.len() > 2 && path[1] == COLON && is_sep(path[2]);
+        let unc = path.len() > 1 && is_sep(path[0]) && is_sep(path[1]);
+        if absolute_disk || unc {
+            return Ok(path)
+        }
+    }
+
+    fill_utf16_buf(|buf, sz| unsafe {
+        c::GetFullPathNameW(path.as_ptr(), sz, buf, ptr::null_mut())
+    }, |absolute| {
+        let (prefix, rest): (&[u16], &[u16]) = if absolute.len() + 1 < LEGACY_MAX_PATH ||
+            absolute.starts_with(DEVICE_PREFIX) ||
+            absolute.starts_with(VERBATIM_PREFIX) ||
+            absolute.starts_with(NT_PREFIX)
+        {
+            (&[], absolute)
+        } else if absolute.len() > 2 && absolute[1] == COLON && absolute[2] == SEP {
+            // C:\foo => \\?\C:\foo
+            (VERBATIM_PREFIX, absolute)
+        } else if absolute.starts_with(&[SEP, SEP]) {
+            // \\server\share\foo => \\?\UNC\server\share\foo
+            (UNC_PREFIX, &absolute[2..])
+        } else {
+            (&[], absolute)
+        };
+        let mut long = Vec::with_capacity(prefix.len() + rest.len() + 1);
+        long.extend_from_slice(prefix);
+        long.extend_from_slice(rest);
+        long.push(0);
+        long
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::maybe_verbatim;
+    use path::Path;
+
+    fn verbatim(path: &str) -> String {
+        let mut wide = maybe_verbatim(Path::new(path)).unwrap();
+        assert_eq!(wide.pop(), Some(0));
+        String::from_utf16(&wide).unwrap()
+    }
+
+    #[test]
+    fn short_paths_unchanged() {
+        assert_eq!(verbatim(r"C:\foo\bar.txt"), r"C:\foo\bar.txt");
+        assert_eq!(verbatim(r"\\server\share\a"), r"\\server\share\a");
+        assert_eq!(verbatim(r"\\?\C:\a"), r"\\?\C:\a");
+        assert_eq!(verbatim(""), "");
+    }
+
+    #[test]
+    fn long_paths() {
+        let long = "a".repeat(300);
+        assert_eq!(verbatim(&format!(r"C:\{}\..\b/c", long)), r"C:\b\c");
+        assert_eq!(verbatim(&format!(r"C:\{}\.\b/c", long)),
+                   format!(r"\\?\C:\{}\b\c", long));
+        assert_eq!(verbatim(&format!(r"\\server\share\{}", long)),
+                   format!(r"\\?\UNC\server\share\{}", long));
+        assert_eq!(verbatim(&format!(r"\\?\C:\{}\..", long)),
+                   format!(r"\\?\C:\{}\..", long));
+
+        let relative = verbatim(&long);
+        assert!(relative.starts_with(r"\\?\"), "{}", relative);
+        assert!(relative.ends_with(&format!(r"\{}", long)), "{}", relative);
+    }
+}
@@ -169,6 +169,23 @@ impl AnonPipe {
     pub fn write(&self, buf: &[u8]) -> io::Result<usize> {
         self.inner.write(buf)
     }
+
+    pub fn try_clone(&self) -> io::Result<AnonPipe> {
+        let inner = self.inner.duplicate(0, false, c::DUPLICATE_SAME_ACCESS)?;
+        Ok(AnonPipe { inner })
+    }
+
+    // `PIPE_NOWAIT` exists, but an empty pipe then fails reads with
+    // `ERROR_NO_DATA`, which is also what a closed one gives, so it can't
+    // be told apart from the end of the stream.
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        if nonblocking {
+            Err(io::Error::new(io::ErrorKind::Other,
+                               "non-blocking pipes are not supported on Windows"))
+        } else {
+            Ok(())
+        }
+    }
 }
 
 pub fn read2(p1: AnonPipe,
@@ -208,6 +225,56 @@ pub fn read2(p1: AnonPipe,
     }
 }
 
+/// Reads both pipes until they're closed, passing each chunk read to `f`
+/// along with the index of the pipe it came from. An error from `f` stops
+/// the reading and is returned.
+pub fn read2_with(p1: AnonPipe,
+                 p2: AnonPipe,
+                 f: &mut dyn FnMut(usize, &[u8]) -> io::Result<()>) -> io::Result<()> {
+    let p1 = p1.into_handle();
+    let p2 = p2.into_handle();
+
+    let (mut v1, mut v2) = (Vec::with_capacity(8 * 1024), Vec::with_capacity(8 * 1024));
+    let mut p1 = AsyncPipe::new(p1, &mut v1)?;
+    let mut p2 = AsyncPipe::new(p2, &mut v2)?;
+    let objs = [p1.event.raw(), p2.event.raw()];
+
+    // This is `read2`'s loop, except that each pipe's buffer is handed to
+    // `f` and emptied after every read, so it never grows.
+    loop {
+        let res = unsafe {
+            c::WaitForMultipleObjects(2, objs.as_ptr(), c::FALSE, c::INFINITE)
+        };
+        if res == c::WAIT_OBJECT_0 {
+            if !step(&mut p1, 0, f)? {
+                while step(&mut p2, 1, f)? {}
+                return Ok(())
+            }
+        } else if res == c::WAIT_OBJECT_0 + 1 {
+            if !step(&mut p2, 1, f)? {
+                while step(&mut p1, 0, f)? {}
+                return Ok(())
+            }
+        } else {
+            return Err(io::Error::last_os_error())
+        }
+    }
+
+    // Completes the pipe's pending read, if any, and schedules the next one,
+    // returning whether the pipe is still open.
+    fn step(pipe: &mut AsyncPipe,
+            i: usize,
+            f: &mut dyn FnMut(usize, &[u8]) -> io::Result<()>) -> io::Result<bool> {
+        let open = pipe.result()?;
+        if !pipe.dst.is_empty() {
+            let res = f(i, &pipe.dst[..]);
+            pipe.dst.clear();
+            res?;
+        }
+        Ok(open && pipe.schedule_read()?)
+    }
+}
+
 struct AsyncPipe<'a> {
     pipe: Handle,
     event: Handle,
@@ -9,7 +9,7 @@ use fs;
 use io::{self, Error, ErrorKind};
 use libc::{c_void, EXIT_SUCCESS, EXIT_FAILURE};
 use mem;
-use os::windows::ffi::OsStrExt;
+use os::windows::ffi::{OsStrExt, OsStringExt};
 use path::Path;
 use ptr;
 use sys::mutex::Mutex;
@@ -20,8 +20,11 @@ use sys::pipe::{self, AnonPipe};
 use sys::stdio;
 use sys::cvt;
 use sys_common::{AsInner, FromInner, IntoInner};
-use sys_common::process::{CommandEnv, EnvKey};
+use slice;
+use sys_common::process::{self as common, timeout_ms, CommandEnv};
 use borrow::Borrow;
+use cmp;
+use time::{Duration, Instant};
 
 ////////////////////////////////////////////////////////////////////////////////
 // Command
@@ -51,7 +54,7 @@ impl AsRef<OsStr> for WindowsEnvKey {
     fn as_ref(&self) -> &OsStr { &self.0 }
 }
 
-impl EnvKey for WindowsEnvKey {}
+impl common::EnvKey for WindowsEnvKey {}
 
 
 fn ensure_no_nuls<T: AsRef<OsStr>>(str: T) -> io::Result<T> {
@@ -64,7 +67,7 @@ fn ensure_no_nuls<T: AsRef<OsStr>>(str: T) -> io::Result<T> {
 
 pub struct Command {
     program: OsString,
-    args: Vec<OsString>,
+    args: Vec<Arg>,
     env: CommandEnv<WindowsEnvKey>,
     cwd: Option<OsString>,
     flags: u32,
@@ -72,6 +75,117 @@ pub struct Command {
     stdin: Option<Stdio>,
     stdout: Option<Stdio>,
     stderr: Option<Stdio>,
+    merge_stderr: bool,
+    job: Option<JobLimits>,
+}
+
+/// Limits of the job object a child is spawned into.
+#[derive(Clone, Debug, Default)]
+pub struct JobLimits {
+    pub kill_on_close: bool,
+    pub process_memory: Option<usize>,
+    pub job_memory: Option<usize>,
+    pub cpu_time: Option<Duration>,
+    pub breakaway_ok: bool,
+    pub silent_breakaway_ok: bool,
+}
+
+impl JobLimits {
+    fn create_job(&self) -> io::Result<Handle> {
+        let job = unsafe { c::CreateJobObjectW(ptr::null_mut(), ptr::null()) };
+        if job.is_null() {
+            return Err(Error::last_os_error())
+        }
+        let job = Handle::new(job);
+
+        let mut info: c::JOBOBJECT_EXTENDED_LIMIT_INFORMATION = unsafe { mem::zeroed() };
+        let mut flags = 0;
+        if self.kill_on_close {
+            flags |= c::JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+        }
+        if let Some(limit) = self.process_memory {
+            flags |= c::JOB_OBJECT_LIMIT_PROCESS_MEMORY;
+            info.ProcessMemoryLimit = limit;
+        }
+        if let Some(limit) = self.job_memory {
+            flags |= c::JOB_OBJECT_LIMIT_JOB_MEMORY;
+            info.JobMemoryLimit = limit;
+        }
+        if let Some(limit) = self.cpu_time {
+            // The limit is counted in 100ns intervals.
+            let ticks = limit.as_secs()
+                .saturating_mul(10_000_000)
+                .saturating_add(limit.subsec_nanos() as u64 / 100);
+            flags |= c::JOB_OBJECT_LIMIT_JOB_TIME;
+            info.BasicLimitInformation.PerJobUserTimeLimit =
+                cmp::min(ticks, i64::max_value() as u64) as c::LARGE_INTEGER;
+        }
+        if self.breakaway_ok {
+            flags |= c::JOB_OBJECT_LIMIT_BREAKAWAY_OK;
+        }
+        if self.silent_breakaway_ok {
+            flags |= c::JOB_OBJECT_LIMIT_SILENT_BREAKAWAY_OK;
+        }
+        info.BasicLimitInformation.LimitFlags = flags;
+
+        cvt(unsafe {
+            c::SetInformationJobObject(job.raw(),
+                                       c::JobObjectExtendedLimitInformation,
+                                       &mut info as *mut _ as c::LPVOID,
+                                       mem::size_of_val(&info) as c::DWORD)
+        })?;
+        Ok(job)
+    }
+}
+
+/// An argument as given to the `Command`, either to be quoted so the child
+/// parses it back unchanged or to be inserted in the command line verbatim.
+pub enum Arg {
+    Regular(OsString),
+    Raw(OsString),
+}
+
+impl fmt::Debug for Arg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Arg::Regular(ref arg) => arg.fmt(f),
+            Arg::Raw(ref arg) => write!(f, "{}", arg.to_string_lossy()),
+        }
+    }
+}
+
+pub type CommandEnvs<'a> = common::CommandEnvs<'a, WindowsEnvKey>;
+
+/// How environment variable names are compared, and kept in order.
+/// Windows ignores their case.
+pub type EnvKey = WindowsEnvKey;
+
+pub struct CommandArgs<'a> {
+    iter: slice::Iter<'a, Arg>,
+}
+
+impl<'a> Iterator for CommandArgs<'a> {
+    type Item = &'a OsStr;
+    fn next(&mut self) -> Option<&'a OsStr> {
+        self.iter.next().map(|arg| match *arg {
+            Arg::Regular(ref arg) | Arg::Raw(ref arg) => &**arg,
+        })
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for CommandArgs<'a> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<'a> fmt::Debug for CommandArgs<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter.clone()).finish()
+    }
 }
 
 pub enum Stdio {
@@ -103,15 +217,36 @@ impl Command {
             stdin: None,
             stdout: None,
             stderr: None,
+            merge_stderr: false,
+            job: None,
         }
     }
 
     pub fn arg(&mut self, arg: &OsStr) {
-        self.args.push(arg.to_os_string())
+        self.args.push(Arg::Regular(arg.to_os_string()))
+    }
+    pub fn raw_arg(&mut self, arg: &OsStr) {
+        self.args.push(Arg::Raw(arg.to_os_string()))
+    }
+    pub fn raw_command_line(&self) -> io::Result<OsString> {
+        let cmd = make_command_line(&self.program, &self.args)?;
+        Ok(OsString::from_wide(&cmd))
     }
     pub fn env_mut(&mut self) -> &mut CommandEnv<WindowsEnvKey> {
         &mut self.env
     }
+    pub fn get_program(&self) -> &OsStr {
+        &self.program
+    }
+    pub fn get_args(&self) -> CommandArgs {
+        CommandArgs { iter: self.args.iter() }
+    }
+    pub fn get_envs(&self) -> CommandEnvs {
+        self.env.iter()
+    }
+    pub fn get_current_dir(&self) -> Option<&Path> {
+        self.cwd.as_ref().map(|cwd| Path::new(cwd))
+    }
     pub fn cwd(&mut self, dir: &OsStr) {
         self.cwd = Some(dir.to_os_string())
     }
@@ -124,9 +259,24 @@ impl Command {
     pub fn stderr(&mut self, stderr: Stdio) {
         self.stderr = Some(stderr);
     }
+    pub fn merge_stderr(&mut self, merge: bool) {
+        self.merge_stderr = merge;
+    }
     pub fn creation_flags(&mut self, flags: u32) {
         self.flags = flags;
     }
+    pub fn job_object(&mut self, limits: JobLimits) {
+        self.job = Some(limits);
+    }
+    pub fn kill_on_drop(&mut self, kill: bool) {
+        match self.job {
+            Some(ref mut limits) => limits.kill_on_close = kill,
+            None if kill => {
+                self.job = Some(JobLimits { kill_on_close: true, ..JobLimits::default() })
+            }
+            None => {}
+        }
+    }
 
     pub fn spawn(&mut self, default: Stdio, needs_stdin: bool)
                  -> io::Result<(Process, StdioPipes)> {
@@ -162,6 +312,15 @@ impl Command {
         if self.detach {
             flags |= c::DETACHED_PROCESS | c::CREATE_NEW_PROCESS_GROUP;
         }
+        // The child is started suspended so it can't spawn anything of its
+        // own before it's been put into the job.
+        let job = match self.job {
+            Some(ref limits) => Some(limits.create_job()?),
+            None => None,
+        };
+        if job.is_some() {
+            flags |= c::CREATE_SUSPENDED;
+        }
 
         let (envp, _data) = make_envp(maybe_env)?;
         let (dirp, _data) = make_dirp(self.cwd.as_ref())?;
@@ -192,11 +351,15 @@ impl Command {
         let stdin = stdin.to_handle(c::STD_INPUT_HANDLE, &mut pipes.stdin)?;
         let stdout = stdout.to_handle(c::STD_OUTPUT_HANDLE,
                                       &mut pipes.stdout)?;
-        let stderr = stderr.to_handle(c::STD_ERROR_HANDLE,
-                                      &mut pipes.stderr)?;
+        // Merging just hands the child the same handle twice, as `2>&1`.
+        let stderr = if self.merge_stderr {
+            None
+        } else {
+            Some(stderr.to_handle(c::STD_ERROR_HANDLE, &mut pipes.stderr)?)
+        };
         si.hStdInput = stdin.raw();
         si.hStdOutput = stdout.raw();
-        si.hStdError = stderr.raw();
+        si.hStdError = stderr.as_ref().unwrap_or(&stdout).raw();
 
         unsafe {
             cvt(c::CreateProcessW(ptr::null(),
@@ -207,12 +370,30 @@ impl Command {
                                   &mut si, &mut pi))
         }?;
 
+        let thread = Handle::new(pi.hThread);
+        let handle = Handle::new(pi.hProcess);
+        if let Some(ref job) = job {
+            let assigned = cvt(unsafe {
+                c::AssignProcessToJobObject(job.raw(), handle.raw())
+            }).and_then(|_| {
+                match unsafe { c::ResumeThread(thread.raw()) } {
+                    0xffffffff => Err(Error::last_os_error()),
+                    _ => Ok(()),
+                }
+            });
+            if let Err(e) = assigned {
+                // Don't leave the child behind, suspended and outside the job.
+                unsafe { c::TerminateProcess(handle.raw(), 1); }
+                return Err(e)
+            }
+        }
+
         // We close the thread handle because we don't care about keeping
         // the thread id valid, and we aren't keeping the thread handle
         // around to be able to close it later.
-        drop(Handle::new(pi.hThread));
+        drop(thread);
 
-        Ok((Process { handle: Handle::new(pi.hProcess) }, pipes))
+        Ok((Process { handle, job }, pipes))
     }
 
 }
@@ -227,6 +408,27 @@ impl fmt::Debug for Command {
     }
 }
 
+// Written as a `cmd.exe` line, with the command line quoted exactly as it'll
+// be passed to `CreateProcess`. There's no way for `cmd.exe` to clear the
+// environment, so that isn't shown.
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(ref cwd) = self.cwd {
+            write!(f, "cd /d \"{}\" && ", cwd.to_string_lossy())?;
+        }
+        for (key, value) in self.env.iter() {
+            write!(f, "set \"{}={}\" && ",
+                   key.to_string_lossy(),
+                   value.map_or("".into(), |v| v.to_string_lossy()))?;
+        }
+        match make_command_line(&self.program, &self.args) {
+            Ok(cmd) => f.write_str(&String::from_utf16_lossy(&cmd)),
+            // Nothing could be spawned from this, so just show what it has.
+            Err(_) => fmt::Debug::fmt(self, f),
+        }
+    }
+}
+
 impl<'a> DropGuard<'a> {
     fn new(lock: &'a Mutex) -> DropGuard<'a> {
         unsafe {
@@ -325,6 +527,7 @@ impl From<File> for Stdio {
 /// for the process to terminate.
 pub struct Process {
     handle: Handle,
+    job: Option<Handle>,
 }
 
 impl Process {
@@ -335,6 +538,17 @@ impl Process {
         Ok(())
     }
 
+    pub fn kill_tree(&self) -> io::Result<()> {
+        match self.job {
+            Some(ref job) => {
+                cvt(unsafe { c::TerminateJobObject(job.raw(), 1) })?;
+                Ok(())
+            }
+            None => Err(io::Error::new(ErrorKind::InvalidInput,
+                                       "process was not spawned in a job object")),
+        }
+    }
+
     pub fn id(&self) -> u32 {
         unsafe {
             c::GetProcessId(self.handle.raw()) as u32
@@ -368,9 +582,33 @@ impl Process {
         }
     }
 
+    pub fn wait_deadline(&mut self, deadline: Instant) -> io::Result<Option<ExitStatus>> {
+        loop {
+            let now = Instant::now();
+            let timeout = if now >= deadline {
+                0
+            } else {
+                cmp::min(timeout_ms(deadline - now), (c::INFINITE - 1) as u64) as c::DWORD
+            };
+            match unsafe { c::WaitForSingleObject(self.handle.raw(), timeout) } {
+                c::WAIT_OBJECT_0 => return self.try_wait(),
+                c::WAIT_TIMEOUT if timeout == 0 => return Ok(None),
+                c::WAIT_TIMEOUT => {}
+                _ => return Err(Error::last_os_error()),
+            }
+        }
+    }
+
     pub fn handle(&self) -> &Handle { &self.handle }
 
-    pub fn into_handle(self) -> Handle { self.handle }
+    pub fn into_handle(self) -> Handle {
+        // Closing the job could kill the process we're handing out, so the job
+        // stays open for as long as we run.
+        if let Some(job) = self.job {
+            job.into_raw();
+        }
+        self.handle
+    }
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
@@ -453,34 +691,59 @@ fn zeroed_process_information() -> c::PROCESS_INFORMATION {
 
 // Produces a wide string *without terminating null*; returns an error if
 // `prog` or any of the `args` contain a nul.
-fn make_command_line(prog: &OsStr, args: &[OsString]) -> io::Result<Vec<u16>> {
-    // Encode the command and arguments in a command line string such
-    // that the spawned process may recover them using CommandLineToArgvW.
+fn make_command_line(prog: &OsStr, args: &[Arg]) -> io::Result<Vec<u16>> {
+    // Encode the command and arguments in a command line string such that
+    // the spawned process recovers them exactly when it parses its command
+    // line with the MSVC CRT rules, see `sys::args::parse_lp_cmd_line`.
     let mut cmd: Vec<u16> = Vec::new();
-    // Always quote the program name so CreateProcess doesn't interpret args as
-    // part of the name if the binary wasn't found first time.
-    append_arg(&mut cmd, prog, true)?;
+    append_program(&mut cmd, prog)?;
     for arg in args {
         cmd.push(' ' as u16);
-        append_arg(&mut cmd, arg, false)?;
+        match *arg {
+            Arg::Regular(ref arg) => append_arg(&mut cmd, arg)?,
+            Arg::Raw(ref arg) => {
+                // Raw arguments are the caller's responsibility, they're
+                // only checked for the nul that would truncate the line.
+                cmd.extend(ensure_no_nuls(arg)?.encode_wide());
+            }
+        }
     }
     return Ok(cmd);
 
-    fn append_arg(cmd: &mut Vec<u16>, arg: &OsStr, force_quotes: bool) -> io::Result<()> {
+    fn append_program(cmd: &mut Vec<u16>, prog: &OsStr) -> io::Result<()> {
+        // Always quote the program name so CreateProcess doesn't interpret args
+        // as part of the name if the binary wasn't found first time.
+        //
+        // The program name is parsed differently from the arguments: there are
+        // no escapes, everything up to the next quote is taken literally. So
+        // backslashes must not be doubled (a path like `C:\dir\` would grow
+        // an extra backslash), and a quote can't be represented at all,
+        // although it can't appear in a file name anyway.
+        ensure_no_nuls(prog)?;
+        if prog.encode_wide().any(|c| c == '"' as u16) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "program path has embedded quote"));
+        }
+        cmd.push('"' as u16);
+        cmd.extend(prog.encode_wide());
+        cmd.push('"' as u16);
+        Ok(())
+    }
+
+    fn append_arg(cmd: &mut Vec<u16>, arg: &OsStr) -> io::Result<()> {
         // If an argument has 0 characters then we need to quote it to ensure
         // that it actually gets passed through on the command line or otherwise
         // it will be dropped entirely when parsed on the other end.
         ensure_no_nuls(arg)?;
         let arg_bytes = &arg.as_inner().inner.as_inner();
-        let quote = force_quotes || arg_bytes.iter().any(|c| *c == b' ' || *c == b'\t')
+        let quote = arg_bytes.iter().any(|c| *c == b' ' || *c == b'\t')
             || arg_bytes.is_empty();
         if quote {
             cmd.push('"' as u16);
         }
 
-        let mut iter = arg.encode_wide();
         let mut backslashes: usize = 0;
-        while let Some(x) = iter.next() {
+        for x in arg.encode_wide() {
             if x == '\\' as u16 {
                 backslashes += 1;
             } else {
@@ -538,15 +801,16 @@ fn make_dirp(d: Option<&OsString>) -> io::Result<(*const u16, Vec<u16>)> {
 #[cfg(test)]
 mod tests {
     use ffi::{OsStr, OsString};
-    use super::make_command_line;
+    use sys::args::parse_lp_cmd_line;
+    use super::{make_command_line, Arg};
 
     #[test]
     fn test_make_command_line() {
         fn test_wrapper(prog: &str, args: &[&str]) -> String {
             let command_line = &make_command_line(OsStr::new(prog),
                                                   &args.iter()
-                                                       .map(|a| OsString::from(a))
-                                                       .collect::<Vec<OsString>>())
+                                                       .map(|a| Arg::Regular(a.into()))
+                                                       .collect::<Vec<Arg>>())
                                     .unwrap();
             String::from_utf16(command_line).unwrap()
         }
@@ -576,5 +840,73 @@ mod tests {
             test_wrapper("\u{03c0}\u{042f}\u{97f3}\u{00e6}\u{221e}", &[]),
             "\"\u{03c0}\u{042f}\u{97f3}\u{00e6}\u{221e}\""
         );
+        assert_eq!(
+            test_wrapper("C:\\dir\\", &["a\\"]),
+            "\"C:\\dir\\\" a\\"
+        );
+        assert_eq!(
+            test_wrapper("echo", &["", "a\\ b\\"]),
+            "\"echo\" \"\" \"a\\ b\\\\\""
+        );
+    }
+
+    #[test]
+    fn test_make_command_line_rejects_quoted_program() {
+        let err = make_command_line(OsStr::new("a\"b"), &[]).unwrap_err();
+        assert_eq!(err.kind(), ::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_make_command_line_raw() {
+        let args = [
+            Arg::Regular("/C".into()),
+            Arg::Raw(r#""echo "a b" ^& exit""#.into()),
+            Arg::Regular("a\"b".into()),
+        ];
+        let cmd = make_command_line(OsStr::new("cmd.exe"), &args).unwrap();
+        assert_eq!(String::from_utf16(&cmd).unwrap(),
+                   r#""cmd.exe" /C "echo "a b" ^& exit" a\"b"#);
+
+        let args = [Arg::Raw("a\0b".into())];
+        assert!(make_command_line(OsStr::new("cmd.exe"), &args).is_err());
+    }
+
+    #[test]
+    fn test_command_line_round_trip() {
+        fn round_trip(prog: &str, args: &[&str]) {
+            let args: Vec<OsString> = args.iter().map(OsString::from).collect();
+            let regular: Vec<Arg> = args.iter().cloned().map(Arg::Regular).collect();
+            let mut cmd = make_command_line(OsStr::new(prog), &regular).unwrap();
+            cmd.push(0);
+            let parsed = unsafe {
+                parse_lp_cmd_line(cmd.as_ptr(), || OsString::from("UNUSED"))
+            };
+            let mut expected = vec![OsString::from(prog)];
+            expected.extend(args);
+            assert_eq!(parsed, expected, "command line {:?}",
+                       String::from_utf16_lossy(&cmd[..cmd.len() - 1]));
+        }
+
+        let corpus = [
+            "", " ", "\t", "a", "a b", "a\tb", "  a  ", "\"", "\"\"", "\"\"\"",
+            "\\", "\\\\", "a\\", "a\\\\", "\\a", "a\\b", "a\\ b\\",
+            "\\\"", "\\\\\"", "a\"b", "a\"\"b", "\"a b\"", "a \\\"b\\\" c",
+            "\\\" \\\\\"", "^a", "%PATH%", "\u{3c0}\u{42f}", "\u{1f605} \\",
+            "C:\\Program Files\\", "\\\\server\\share\\",
+        ];
+        let progs = [
+            "prog", "C:\\Program Files\\prog.exe", "C:\\dir\\", "\\\\server\\a b\\",
+            "\u{3c0}.exe",
+        ];
+
+        for prog in &progs {
+            round_trip(prog, &[]);
+            for a in &corpus {
+                round_trip(prog, &[a]);
+                for b in &corpus {
+                    round_trip(prog, &[a, b]);
+                }
+            }
+        }
     }
 }
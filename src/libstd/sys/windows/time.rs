@@ -151,6 +151,12 @@ impl From<c::FILETIME> for SystemTime {
     }
 }
 
+impl From<SystemTime> for c::FILETIME {
+    fn from(t: SystemTime) -> c::FILETIME {
+        t.t
+    }
+}
+
 impl Hash for SystemTime {
     fn hash<H : Hasher>(&self, state: &mut H) {
         self.intervals().hash(state)
@@ -3,6 +3,8 @@
 //! Documentation can be found on the `rt::at_exit` function.
 
 use boxed::FnBox;
+use cell::Cell;
+use panic::{self, AssertUnwindSafe};
 use ptr;
 use mem;
 use sys_common::mutex::Mutex;
@@ -17,9 +19,22 @@ type Queue = Vec<Box<dyn FnBox()>>;
 // acquire this mutex reentrantly!
 static LOCK: Mutex = Mutex::new();
 static mut QUEUE: *mut Queue = ptr::null_mut();
+// Teardown registered through `process::on_shutdown`, which runs in reverse
+// order of registration before anything in `QUEUE`. It shares `LOCK`.
+static mut SHUTDOWN: *mut Queue = ptr::null_mut();
 
 const DONE: *mut Queue = 1_usize as *mut _;
 
+// Held for as long as the teardown in `SHUTDOWN` is running, so that other
+// threads shutting down wait for it to finish instead of exiting under it.
+static RUNNING: Mutex = Mutex::new();
+
+thread_local! {
+    // Set on the thread running the teardown, which mustn't wait for itself
+    // when a closure calls `process::exit`.
+    static RUNNING_HERE: Cell<bool> = Cell::new(false)
+}
+
 // The maximum number of times the cleanup routines will be run. While running
 // the at_exit closures new ones may be registered, and this count is the number
 // of times the new closures will be allowed to register successfully. After
@@ -74,3 +89,47 @@ pub fn push(f: Box<dyn FnBox()>) -> bool {
         }
     }
 }
+
+pub fn push_shutdown(f: Box<dyn FnBox()>) -> bool {
+    unsafe {
+        let _guard = LOCK.lock();
+        if SHUTDOWN.is_null() {
+            let state: Box<Queue> = box Vec::new();
+            SHUTDOWN = Box::into_raw(state);
+        } else if SHUTDOWN == DONE {
+            return false
+        }
+        (*SHUTDOWN).push(f);
+        true
+    }
+}
+
+pub fn run_shutdown() {
+    // A teardown closure reentering, for example by calling `process::exit`,
+    // finds nothing left to run.
+    if RUNNING_HERE.try_with(|here| here.get()).unwrap_or(false) {
+        return
+    }
+
+    unsafe {
+        // Whoever gets here first runs the teardown, and anyone else blocks
+        // here until it's done.
+        let _running = RUNNING.lock();
+        let queue = {
+            let _guard = LOCK.lock();
+            mem::replace(&mut SHUTDOWN, DONE)
+        };
+
+        if !queue.is_null() && queue != DONE {
+            let _ = RUNNING_HERE.try_with(|here| here.set(true));
+            // A panic has already been reported by the hook, and unwinding
+            // any further would skip the rest of the teardown and escape
+            // from `main`'s caller or `process::exit`.
+            let queue: Box<Queue> = Box::from_raw(queue);
+            for to_run in queue.into_iter().rev() {
+                let _ = panic::catch_unwind(AssertUnwindSafe(move || to_run()));
+            }
+            let _ = RUNNING_HERE.try_with(|here| here.set(false));
+        }
+    }
+}
@@ -4,7 +4,7 @@
 use env;
 use io::prelude::*;
 use io;
-use path::{self, Path};
+use path::{self, Path, PathBuf};
 use ptr;
 use rustc_demangle::demangle;
 use str;
@@ -15,6 +15,7 @@ pub use sys::backtrace::{
     unwind_backtrace,
     resolve_symname,
     foreach_symbol_fileline,
+    modules,
     BacktraceContext
 };
 
@@ -36,13 +37,25 @@ pub struct Frame {
     pub inline_context: u32,
 }
 
+/// An executable or shared library loaded into the process. See `modules` for
+/// how these are listed.
+pub struct Module {
+    /// Path the module was loaded from.
+    pub path: PathBuf,
+    /// Lowest address the module is mapped at.
+    pub base: usize,
+    /// Number of bytes from `base` to the end of the module's last segment.
+    pub size: usize,
+}
+
 /// Max number of frames to print.
 const MAX_NB_FRAMES: usize = 100;
 
+/// Serializes everything that walks the stack or resolves symbols.
+static LOCK: Mutex = Mutex::new();
+
 /// Prints the current backtrace.
 pub fn print(w: &mut dyn Write, format: PrintFormat) -> io::Result<()> {
-    static LOCK: Mutex = Mutex::new();
-
     // There are issues currently linking libbacktrace into tests, and in
     // general during libstd's own unit tests we're not testing this path. In
     // test mode immediately return here to optimize away any references to the
@@ -126,6 +139,38 @@ fn filter_frames(frames: &[Frame],
     (skipped_before, skipped_after)
 }
 
+/// Captures the current stack without resolving any symbols, for
+/// `std::backtrace` to resolve later through `resolve`.
+pub fn capture() -> io::Result<Vec<Frame>> {
+    let mut frames = vec![Frame {
+        exact_position: ptr::null(),
+        symbol_addr: ptr::null(),
+        inline_context: 0,
+    }; MAX_NB_FRAMES];
+    unsafe {
+        LOCK.lock();
+        let res = unwind_backtrace(&mut frames);
+        LOCK.unlock();
+        let (nb_frames, _context) = res?;
+        frames.truncate(nb_frames);
+    }
+    Ok(frames)
+}
+
+/// Runs `f` with a context suitable for `resolve_symname` and
+/// `foreach_symbol_fileline` on frames returned by an earlier `capture`.
+pub fn resolve<F, T>(f: F) -> io::Result<T>
+    where F: FnOnce(&BacktraceContext) -> T
+{
+    unsafe {
+        LOCK.lock();
+        // Unwinding into an empty buffer stops straight away; all we want
+        // here is the context the backend sets up for resolution.
+        let res = unwind_backtrace(&mut []).map(|(_, context)| f(&context));
+        LOCK.unlock();
+        res
+    }
+}
 
 /// Fixed frame used to clean the backtrace with `RUST_BACKTRACE=1`.
 #[inline(never)]
@@ -144,10 +189,13 @@ pub enum PrintFormat {
     Full = 3,
 }
 
+// 0 until `RUST_BACKTRACE` has been read, then 1 for `None` or the
+// `PrintFormat` discriminant.
+static ENABLED: atomic::AtomicIsize = atomic::AtomicIsize::new(0);
+
 // For now logging is turned off by default, and this function checks to see
 // whether the magical environment variable is present to see if it's turned on.
 pub fn log_enabled() -> Option<PrintFormat> {
-    static ENABLED: atomic::AtomicIsize = atomic::AtomicIsize::new(0);
     match ENABLED.load(Ordering::SeqCst) {
         0 => {}
         1 => return None,
@@ -171,6 +219,14 @@ pub fn log_enabled() -> Option<PrintFormat> {
     val
 }
 
+/// Overrides whatever `RUST_BACKTRACE` says for later calls to `log_enabled`.
+pub fn set_log_enabled(format: Option<PrintFormat>) {
+    ENABLED.store(match format {
+        Some(v) => v as isize,
+        None => 1,
+    }, Ordering::SeqCst);
+}
+
 /// Prints the symbol of the backtrace frame.
 ///
 /// These output functions should now be used everywhere to ensure consistency.
@@ -0,0 +1,34 @@
+//! A snapshot of the environment for platforms which can only list their
+//! variables as owned strings.
+//!
+//! Platforms which can copy the whole environment into one buffer provide
+//! their own `EnvSnapshot` instead, with the same interface.
+
+use ffi::{OsStr, OsString};
+use slice;
+
+pub struct EnvSnapshot {
+    vars: Vec<(OsString, OsString)>,
+}
+
+impl EnvSnapshot {
+    pub fn new<I: Iterator<Item = (OsString, OsString)>>(env: I) -> EnvSnapshot {
+        EnvSnapshot { vars: env.collect() }
+    }
+
+    pub fn iter(&self) -> EnvSnapshotIter {
+        EnvSnapshotIter { iter: self.vars.iter() }
+    }
+}
+
+pub struct EnvSnapshotIter<'a> {
+    iter: slice::Iter<'a, (OsString, OsString)>,
+}
+
+impl<'a> Iterator for EnvSnapshotIter<'a> {
+    type Item = (&'a OsStr, &'a OsStr);
+    fn next(&mut self) -> Option<(&'a OsStr, &'a OsStr)> {
+        self.iter.next().map(|(key, value)| (&key[..], &value[..]))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) { self.iter.size_hint() }
+}
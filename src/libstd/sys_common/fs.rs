@@ -0,0 +1,69 @@
+//! Platform-independent parts of copying files.
+
+#![allow(dead_code)]
+
+use io::{self, ErrorKind, Read, Write};
+use sys_common::io::DEFAULT_BUF_SIZE;
+
+/// Calls the callback given to `fs::copy_with_progress` as a copy goes on:
+/// each time at least `chunk_size` more bytes have been copied since it was
+/// last called, and then once more when the copy is done.
+pub struct Progress<'a> {
+    f: &'a mut dyn FnMut(u64, u64) -> io::Result<()>,
+    chunk_size: u64,
+    reported: Option<u64>,
+}
+
+impl<'a> Progress<'a> {
+    pub fn new(f: &'a mut dyn FnMut(u64, u64) -> io::Result<()>, chunk_size: u64)
+               -> Progress<'a> {
+        Progress { f, chunk_size, reported: None }
+    }
+
+    /// How much to copy at once, where the platform allows it, so that the
+    /// callback is called about as often as it wants.
+    pub fn chunk_size(&self) -> u64 {
+        self.chunk_size
+    }
+
+    /// Reports that `copied` bytes of `total` have been copied, if enough
+    /// have been since the last report. An error cancels the copy.
+    pub fn update(&mut self, copied: u64, total: u64) -> io::Result<()> {
+        if copied - self.reported.unwrap_or(0) < self.chunk_size {
+            return Ok(())
+        }
+        self.reported = Some(copied);
+        (self.f)(copied, total)
+    }
+
+    /// Reports that the copy is done, after `copied` bytes of `total`.
+    pub fn finish(&mut self, copied: u64, total: u64) -> io::Result<()> {
+        if self.reported == Some(copied) {
+            return Ok(())
+        }
+        self.reported = Some(copied);
+        (self.f)(copied, total)
+    }
+}
+
+/// Copies the rest of `reader` to `writer`, for when the platform can't
+/// copy the data itself. `copied` is what's been copied already, which the
+/// result includes.
+pub fn copy_chunks(reader: &mut dyn Read,
+                   writer: &mut dyn Write,
+                   mut copied: u64,
+                   total: u64,
+                   progress: &mut Progress) -> io::Result<u64> {
+    let mut buf = [0; DEFAULT_BUF_SIZE];
+    loop {
+        let len = match reader.read(&mut buf) {
+            Ok(0) => return Ok(copied),
+            Ok(len) => len,
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        writer.write_all(&buf[..len])?;
+        copied += len as u64;
+        progress.update(copied, total)?;
+    }
+}
@@ -0,0 +1,33 @@
+//! Collection of the functions registered with `register_init!`.
+//!
+//! Each registration is a `#[used]` static placed in a dedicated linker
+//! section, see `sys::init_hooks`, so the linker gathers the registrations of
+//! every crate in the program into a single array.
+//!
+//! Only the object files the linker actually includes contribute to it. An
+//! rlib is an archive, and linkers only pull the members out of an archive
+//! which define a symbol something else refers to, so a hook in an object
+//! file of a library which nothing else in the program uses may be dropped
+//! along with that object file, and never run.
+
+#![allow(dead_code)] // not used in libstd's own tests
+
+use mem;
+use rt::InitHook;
+use slice;
+use sys;
+
+/// Returns every `InitHook` linked into the program, in no particular order.
+pub fn hooks() -> &'static [InitHook] {
+    match sys::init_hooks::section() {
+        Some((start, end)) => unsafe { between(start, end) },
+        None => &[],
+    }
+}
+
+/// Returns the hooks laid out from `start` up to, but not including, `end`.
+unsafe fn between(start: &'static InitHook, end: &'static InitHook) -> &'static [InitHook] {
+    let start = start as *const InitHook;
+    let len = (end as *const InitHook as usize - start as usize) / mem::size_of::<InitHook>();
+    slice::from_raw_parts(start, len)
+}
@@ -33,6 +33,9 @@ pub mod at_exit_imp;
 #[cfg(feature = "backtrace")]
 pub mod backtrace;
 pub mod condvar;
+pub mod env_snapshot;
+pub mod fs;
+pub mod init_hooks;
 pub mod io;
 pub mod mutex;
 pub mod poison;
@@ -50,6 +53,7 @@ cfg_if! {
     if #[cfg(any(target_os = "cloudabi",
                  target_os = "l4re",
                  target_os = "redox",
+                 target_os = "uefi",
                  all(target_arch = "wasm32", not(target_os = "emscripten")),
                  all(target_vendor = "fortanix", target_env = "sgx")))] {
         pub use sys::net;
@@ -104,8 +108,21 @@ pub fn at_exit<F: FnOnce() + Send + 'static>(f: F) -> Result<(), ()> {
     if at_exit_imp::push(Box::new(f)) {Ok(())} else {Err(())}
 }
 
+/// Enqueues a procedure to run when the process shuts down, before any
+/// `at_exit` handler. These run in reverse order of registration.
+///
+/// Returns `Err` if shutdown has already begun, in which case the closure
+/// will never run.
+pub fn on_shutdown<F: FnOnce() + Send + 'static>(f: F) -> Result<(), ()> {
+    if at_exit_imp::push_shutdown(Box::new(f)) {Ok(())} else {Err(())}
+}
+
 /// One-time runtime cleanup.
+///
+/// The teardown registered with `on_shutdown` runs first, and outside of the
+/// one-time guard so that it may itself call `process::exit`.
 pub fn cleanup() {
+    at_exit_imp::run_shutdown();
     static CLEANUP: Once = Once::new();
     CLEANUP.call_once(|| unsafe {
         sys::args::cleanup();
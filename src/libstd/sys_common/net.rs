@@ -1,12 +1,14 @@
 use cmp;
-use ffi::CString;
+use ffi::{CStr, CString};
 use fmt;
 use io::{self, Error, ErrorKind};
-use libc::{c_int, c_void};
+use libc::{c_char, c_int, c_void};
 use mem;
-use net::{SocketAddr, Shutdown, Ipv4Addr, Ipv6Addr};
+use net::{SocketAddr, Shutdown, Ipv4Addr, Ipv6Addr, NameInfoFlag};
 use ptr;
 use sys::net::{cvt, cvt_r, cvt_gai, Socket, init, wrlen_t};
+use sys::net::{NI_DGRAM, NI_MAXHOST, NI_MAXSERV, NI_NAMEREQD, NI_NOFQDN, NI_NUMERICHOST,
+               NI_NUMERICSERV};
 use sys::net::netc as c;
 use sys_common::{AsInner, FromInner, IntoInner};
 use time::Duration;
@@ -14,23 +16,31 @@ use convert::{TryFrom, TryInto};
 
 #[cfg(any(target_os = "dragonfly", target_os = "freebsd",
           target_os = "ios", target_os = "macos",
+          target_os = "tvos", target_os = "watchos",
           target_os = "openbsd", target_os = "netbsd",
-          target_os = "solaris", target_os = "haiku", target_os = "l4re"))]
+          target_os = "solaris", target_os = "haiku", target_os = "l4re",
+          target_os = "nto"))]
 use sys::net::netc::IPV6_JOIN_GROUP as IPV6_ADD_MEMBERSHIP;
 #[cfg(not(any(target_os = "dragonfly", target_os = "freebsd",
               target_os = "ios", target_os = "macos",
+              target_os = "tvos", target_os = "watchos",
               target_os = "openbsd", target_os = "netbsd",
-              target_os = "solaris", target_os = "haiku", target_os = "l4re")))]
+              target_os = "solaris", target_os = "haiku", target_os = "l4re",
+              target_os = "nto")))]
 use sys::net::netc::IPV6_ADD_MEMBERSHIP;
 #[cfg(any(target_os = "dragonfly", target_os = "freebsd",
           target_os = "ios", target_os = "macos",
+          target_os = "tvos", target_os = "watchos",
           target_os = "openbsd", target_os = "netbsd",
-          target_os = "solaris", target_os = "haiku", target_os = "l4re"))]
+          target_os = "solaris", target_os = "haiku", target_os = "l4re",
+          target_os = "nto"))]
 use sys::net::netc::IPV6_LEAVE_GROUP as IPV6_DROP_MEMBERSHIP;
 #[cfg(not(any(target_os = "dragonfly", target_os = "freebsd",
               target_os = "ios", target_os = "macos",
+              target_os = "tvos", target_os = "watchos",
               target_os = "openbsd", target_os = "netbsd",
-              target_os = "solaris", target_os = "haiku", target_os = "l4re")))]
+              target_os = "solaris", target_os = "haiku", target_os = "l4re",
+              target_os = "nto")))]
 use sys::net::netc::IPV6_DROP_MEMBERSHIP;
 
 #[cfg(any(target_os = "linux", target_os = "android",
@@ -198,6 +208,40 @@ impl<'a> TryFrom<(&'a str, u16)> for LookupHost {
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////
+// get_name_info
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn lookup_name_info(addr: &SocketAddr, flags: &[NameInfoFlag])
+                        -> io::Result<(String, String)> {
+    init();
+
+    let flags = flags.iter().fold(0, |bits, flag| bits | match *flag {
+        NameInfoFlag::NoFqdn => NI_NOFQDN,
+        NameInfoFlag::NumericHost => NI_NUMERICHOST,
+        NameInfoFlag::NumericService => NI_NUMERICSERV,
+        NameInfoFlag::NameRequired => NI_NAMEREQD,
+        NameInfoFlag::Datagram => NI_DGRAM,
+    });
+
+    let (addrp, len) = addr.into_inner();
+    let mut host = [0 as c_char; NI_MAXHOST];
+    let mut service = [0 as c_char; NI_MAXSERV];
+    unsafe {
+        cvt_gai(c::getnameinfo(addrp, len,
+                               host.as_mut_ptr(), host.len() as _,
+                               service.as_mut_ptr(), service.len() as _,
+                               flags))?;
+    }
+
+    let to_string = |buf: &[c_char]| unsafe {
+        CStr::from_ptr(buf.as_ptr()).to_str().map(|s| s.to_owned()).map_err(|_| {
+            io::Error::new(ErrorKind::InvalidData, "name is not valid UTF-8")
+        })
+    };
+    Ok((to_string(&host)?, to_string(&service)?))
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // TCP streams
 ////////////////////////////////////////////////////////////////////////////////
@@ -647,6 +691,18 @@ mod tests {
     use super::*;
     use collections::HashMap;
 
+    #[test]
+    fn lookup_numeric_name_info() {
+        let addr: SocketAddr = "127.0.0.1:80".parse().unwrap();
+        let flags = [NameInfoFlag::NumericHost, NameInfoFlag::NumericService];
+        assert_eq!(lookup_name_info(&addr, &flags).unwrap(),
+                   ("127.0.0.1".to_string(), "80".to_string()));
+
+        let addr: SocketAddr = "[::1]:8080".parse().unwrap();
+        assert_eq!(lookup_name_info(&addr, &flags).unwrap(),
+                   ("::1".to_string(), "8080".to_string()));
+    }
+
     #[test]
     fn no_lookup_host_duplicates() {
         let mut addrs = HashMap::new();
@@ -3,8 +3,16 @@
 
 use ffi::{OsStr, OsString};
 use env;
+use cmp;
 use collections::BTreeMap;
+use collections::btree_map;
 use borrow::Borrow;
+use error::Error;
+use fmt;
+use io;
+use process::SpawnStage;
+use thread;
+use time::{Duration, Instant};
 
 pub trait EnvKey:
     From<OsString> + Into<OsString> +
@@ -90,6 +98,10 @@ impl<K: EnvKey> CommandEnv<K> {
         !self.clear && self.vars.is_empty()
     }
 
+    pub fn is_cleared(&self) -> bool {
+        self.clear
+    }
+
     pub fn capture_if_changed(&self) -> Option<BTreeMap<K, OsString>> {
         if self.is_unchanged() {
             None
@@ -115,6 +127,9 @@ impl<K: EnvKey> CommandEnv<K> {
         self.clear = true;
         self.vars.clear();
     }
+    pub fn iter(&self) -> CommandEnvs<K> {
+        CommandEnvs { iter: self.vars.iter() }
+    }
     pub fn have_changed_path(&self) -> bool {
         self.saw_path || self.clear
     }
@@ -124,3 +139,187 @@ impl<K: EnvKey> CommandEnv<K> {
         }
     }
 }
+
+/// The changes to the environment, for `process::CommandEnvs`.
+#[derive(Debug)]
+pub struct CommandEnvs<'a, K: 'a> {
+    iter: btree_map::Iter<'a, K, Option<OsString>>,
+}
+
+impl<'a, K: EnvKey> Iterator for CommandEnvs<'a, K> {
+    type Item = (&'a OsStr, Option<&'a OsStr>);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(key, value)| (key.as_ref(), value.as_ref().map(|v| &**v)))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, K: EnvKey> ExactSizeIterator for CommandEnvs<'a, K> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+/// Writes a command as a line of POSIX shell which would run it, for the
+/// platforms whose `Command` is displayed that way.
+///
+/// Environment changes are written as assignments before the program, or
+/// with `env` if any variable is removed, the environment is cleared, or a
+/// name couldn't be assigned by the shell. Non-UTF-8 data is lossily
+/// converted.
+pub fn fmt_shell_command<'a, K, I>(f: &mut fmt::Formatter,
+                                   program: &OsStr,
+                                   args: I,
+                                   env: &CommandEnv<K>,
+                                   cwd: Option<&OsStr>) -> fmt::Result
+    where K: EnvKey, I: Iterator<Item = &'a OsStr>
+{
+    if let Some(cwd) = cwd {
+        f.write_str("cd ")?;
+        write_shell_word(f, cwd, false)?;
+        f.write_str(" && ")?;
+    }
+
+    let use_env = env.clear || env.iter().any(|(key, value)| {
+        value.is_none() || !is_shell_name(key)
+    });
+    if use_env {
+        f.write_str("env ")?;
+        if env.clear {
+            f.write_str("-i ")?;
+        }
+        // `env` stops looking for options at the first assignment.
+        for (key, _) in env.iter().filter(|&(_, value)| value.is_none()) {
+            f.write_str("-u ")?;
+            write_shell_word(f, key, false)?;
+            f.write_str(" ")?;
+        }
+    }
+    for (key, value) in env.iter() {
+        let value = match value {
+            Some(value) => value,
+            None => continue,
+        };
+        if use_env {
+            let mut assignment = key.to_os_string();
+            assignment.push("=");
+            assignment.push(value);
+            write_shell_word(f, &assignment, false)?;
+        } else {
+            write!(f, "{}=", key.to_string_lossy())?;
+            write_shell_word(f, value, false)?;
+        }
+        f.write_str(" ")?;
+    }
+
+    // A program containing `=` would be taken for another assignment. The
+    // shell only needs it quoted, but `env` takes any such word for one, even
+    // with a `/` in it, so it has a shell run the program instead.
+    if use_env && program.to_string_lossy().contains('=') {
+        f.write_str("sh -c 'exec \"$0\" \"$@\"' ")?;
+    }
+    write_shell_word(f, program, true)?;
+    for arg in args {
+        f.write_str(" ")?;
+        write_shell_word(f, arg, false)?;
+    }
+    Ok(())
+}
+
+// Writes `word` as-is if the shell would leave it alone, or else in single
+// quotes, inside which only a single quote itself needs escaping.
+fn write_shell_word(f: &mut fmt::Formatter, word: &OsStr, quote_eq: bool) -> fmt::Result {
+    let word = word.to_string_lossy();
+    let plain = !word.is_empty() && word.bytes().all(|b| match b {
+        b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' => true,
+        b'_' | b'@' | b'%' | b'+' | b':' | b',' | b'.' | b'/' | b'-' => true,
+        b'=' => !quote_eq,
+        _ => false,
+    });
+    if plain {
+        return f.write_str(&word)
+    }
+    f.write_str("'")?;
+    for (i, part) in word.split('\'').enumerate() {
+        if i > 0 {
+            f.write_str("'\\''")?;
+        }
+        f.write_str(part)?;
+    }
+    f.write_str("'")
+}
+
+fn is_shell_name(name: &OsStr) -> bool {
+    let name = match name.to_str() {
+        Some(name) => name.as_bytes(),
+        None => return false,
+    };
+    match name.first().cloned() {
+        Some(b'a'..=b'z') | Some(b'A'..=b'Z') | Some(b'_') => {}
+        _ => return false,
+    }
+    name.iter().all(|b| b.is_ascii_alphanumeric() || *b == b'_')
+}
+
+/// Calls `try_wait` until it returns a status or `deadline` passes, sleeping
+/// for increasing intervals between calls, for platforms which can't block
+/// on a child with a timeout.
+pub fn poll_until<T, F>(deadline: Instant, mut try_wait: F) -> io::Result<Option<T>>
+    where F: FnMut() -> io::Result<Option<T>>
+{
+    let mut interval = Duration::from_millis(1);
+    loop {
+        if let Some(status) = try_wait()? {
+            return Ok(Some(status))
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return Ok(None)
+        }
+        thread::sleep(cmp::min(interval, deadline - now));
+        interval = cmp::min(interval * 2, Duration::from_millis(100));
+    }
+}
+
+/// Returns `timeout` in whole milliseconds, rounded up so that waiting that
+/// long doesn't wake before it's over.
+pub fn timeout_ms(timeout: Duration) -> u64 {
+    timeout.as_secs()
+        .saturating_mul(1_000)
+        .saturating_add((timeout.subsec_nanos() as u64 + 999_999) / 1_000_000)
+}
+
+// How a backend reports which step of a child's setup failed: the error
+// from that step is wrapped in this, and `Command` unwraps it again with
+// `split_spawn_error`, so that `spawn` still returns the error as it was.
+#[derive(Debug)]
+pub struct StageError {
+    pub stage: SpawnStage,
+    pub error: io::Error,
+}
+
+impl StageError {
+    pub fn new(stage: SpawnStage, error: io::Error) -> io::Error {
+        io::Error::new(error.kind(), StageError { stage, error })
+    }
+}
+
+impl fmt::Display for StageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl Error for StageError {
+    fn description(&self) -> &str { self.error.description() }
+}
+
+pub fn split_spawn_error(error: io::Error) -> (SpawnStage, io::Error) {
+    if !error.get_ref().map_or(false, |e| e.is::<StageError>()) {
+        return (SpawnStage::Other, error)
+    }
+    let inner = error.into_inner().unwrap().downcast::<StageError>().unwrap();
+    (inner.stage, inner.error)
+}
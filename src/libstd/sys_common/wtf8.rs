@@ -19,6 +19,7 @@ use core::str::next_code_point;
 
 use borrow::Cow;
 use char;
+use collections::TryReserveError;
 use fmt;
 use hash::{Hash, Hasher};
 use iter::FromIterator;
@@ -238,6 +239,16 @@ impl Wtf8Buf {
         self.bytes.reserve_exact(additional)
     }
 
+    #[inline]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.bytes.try_reserve(additional)
+    }
+
+    #[inline]
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.bytes.try_reserve_exact(additional)
+    }
+
     #[inline]
     pub fn shrink_to_fit(&mut self) {
         self.bytes.shrink_to_fit()
@@ -248,6 +259,21 @@ impl Wtf8Buf {
         self.bytes.shrink_to(min_capacity)
     }
 
+    /// Creates a WTF-8 string from a WTF-8 byte vector.
+    ///
+    /// Since the byte vector is not checked for valid WTF-8, this function is
+    /// marked unsafe.
+    #[inline]
+    pub unsafe fn from_bytes_unchecked(value: Vec<u8>) -> Wtf8Buf {
+        Wtf8Buf { bytes: value }
+    }
+
+    /// Consumes the string, returning its WTF-8 bytes.
+    #[inline]
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
     /// Returns the number of bytes that this string buffer can hold without reallocating.
     #[inline]
     pub fn capacity(&self) -> usize {
@@ -867,6 +893,58 @@ impl Hash for Wtf8 {
 
 impl Wtf8 {
     pub fn make_ascii_uppercase(&mut self) { self.bytes.make_ascii_uppercase() }
+
+    /// Returns a copy of the string with ASCII letters mapped to lowercase.
+    pub fn to_ascii_lowercase(&self) -> Wtf8Buf {
+        Wtf8Buf { bytes: self.bytes.to_ascii_lowercase() }
+    }
+
+    /// Returns a copy of the string with ASCII letters mapped to uppercase.
+    pub fn to_ascii_uppercase(&self) -> Wtf8Buf {
+        Wtf8Buf { bytes: self.bytes.to_ascii_uppercase() }
+    }
+
+    /// Returns whether `prefix` is a prefix of the string.
+    ///
+    /// Surrogates are compared as code points: a lone lead surrogate is not a
+    /// prefix of a string starting with the supplementary code point it is
+    /// half of.
+    #[inline]
+    pub fn starts_with(&self, prefix: &Wtf8) -> bool {
+        self.bytes.starts_with(&prefix.bytes)
+    }
+
+    /// Returns whether `suffix` is a suffix of the string, comparing
+    /// surrogates as code points like `starts_with`.
+    #[inline]
+    pub fn ends_with(&self, suffix: &Wtf8) -> bool {
+        self.bytes.ends_with(&suffix.bytes)
+    }
+
+    /// Returns the byte index of the first occurrence of `needle`, comparing
+    /// surrogates as code points like `starts_with`.
+    ///
+    /// The index is always on a code point boundary.
+    pub fn find(&self, needle: &Wtf8) -> Option<usize> {
+        if needle.is_empty() {
+            return Some(0);
+        }
+        self.bytes.windows(needle.len()).position(|w| w == &needle.bytes)
+    }
+
+    /// Returns the slice for the byte range [`begin`..`end`), or `None` if
+    /// either end is not on a code point boundary or `begin > end`.
+    #[inline]
+    pub fn get(&self, begin: usize, end: usize) -> Option<&Wtf8> {
+        // is_code_point_boundary checks that the index is in [0, .len()]
+        if begin <= end &&
+           is_code_point_boundary(self, begin) &&
+           is_code_point_boundary(self, end) {
+            Some(unsafe { slice_unchecked(self, begin, end) })
+        } else {
+            None
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1254,4 +1332,26 @@ mod tests {
         assert_eq!(string.encode_wide().collect::<Vec<_>>(),
                    vec![0x61, 0xE9, 0x20, 0xD83D, 0xD83D, 0xDCA9]);
     }
+
+    #[test]
+    fn wtf8_find_and_get() {
+        let mut string = Wtf8Buf::from_str("a\u{1F4A9}");
+        string.push(CodePoint::from_u32(0xD83D).unwrap());
+        string.push_str("b");
+        let lead = Wtf8Buf::from_wide(&[0xD83D]);
+        let trail = Wtf8Buf::from_wide(&[0xDCA9]);
+
+        assert_eq!(string.find(&lead), Some(5));
+        assert_eq!(string.find(&trail), None);
+        assert!(!string[1..].starts_with(&lead));
+        assert!(string[..8].ends_with(&lead));
+
+        assert_eq!(string.get(5, 8).map(|s| s.to_string_lossy()), Some("\u{FFFD}".into()));
+        assert!(string.get(2, 8).is_none());
+        assert!(string.get(0, 10).is_none());
+        assert!(string.get(8, 5).is_none());
+
+        assert_eq!(Wtf8::from_str("AbC").to_ascii_lowercase(), Wtf8Buf::from_str("abc"));
+        assert_eq!(Wtf8::from_str("AbC").to_ascii_uppercase(), Wtf8Buf::from_str("ABC"));
+    }
 }
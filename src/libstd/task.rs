@@ -0,0 +1,137 @@
+//! Types and Traits for working with asynchronous tasks.
+
+use future::Future;
+use pin::Pin;
+use thread::{self, Thread};
+
+#[doc(inline)]
+pub use core::task::*;
+
+/// Runs a future to completion on the current thread.
+///
+/// The future is polled with a waker which unparks this thread, and the
+/// thread parks whenever the future returns `Pending`. There is no reactor:
+/// whatever the future waits on must itself call `wake` once progress can be
+/// made, for example from another thread.
+///
+/// This is meant for the boundary between synchronous and asynchronous code,
+/// such as tests and small tools, not as a replacement for an executor. The
+/// future can't rely on the current thread's park token for anything else.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(futures_api, futures_block_on)]
+/// use std::future::Future;
+/// use std::pin::Pin;
+/// use std::task::{self, Poll, Waker};
+///
+/// struct Ready(u32);
+///
+/// impl Future for Ready {
+///     type Output = u32;
+///     fn poll(self: Pin<&mut Self>, _waker: &Waker) -> Poll<u32> {
+///         Poll::Ready(self.0)
+///     }
+/// }
+///
+/// assert_eq!(task::block_on(Ready(7)), 7);
+/// ```
+#[unstable(feature = "futures_block_on", issue = "0")]
+pub fn block_on<F: Future>(mut future: F) -> F::Output {
+    let waker = Waker::from(thread::current());
+    // Safe because `future` is a local which is shadowed here, so it is
+    // never moved again.
+    let mut future = unsafe { Pin::new_unchecked(&mut future) };
+    loop {
+        match future.as_mut().poll(&waker) {
+            Poll::Ready(output) => return output,
+            // A wakeup that came before this leaves the park token set, so it
+            // isn't lost; a spurious return from `park` just polls again.
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+/// Creates a `Waker` which wakes a task by unparking `thread`.
+///
+/// This is the waker [`block_on`] uses.
+///
+/// [`block_on`]: fn.block_on.html
+#[unstable(feature = "futures_block_on", issue = "0")]
+impl From<Thread> for Waker {
+    fn from(thread: Thread) -> Waker {
+        unsafe { Waker::new_unchecked(thread_raw_waker(thread)) }
+    }
+}
+
+static THREAD_WAKER_VTABLE: RawWakerVTable = RawWakerVTable {
+    clone: clone_thread_waker,
+    wake: wake_thread_waker,
+    drop: drop_thread_waker,
+};
+
+fn thread_raw_waker(thread: Thread) -> RawWaker {
+    RawWaker::new(Box::into_raw(Box::new(thread)) as *const (), &THREAD_WAKER_VTABLE)
+}
+
+unsafe fn clone_thread_waker(data: *const ()) -> RawWaker {
+    thread_raw_waker((*(data as *const Thread)).clone())
+}
+
+unsafe fn wake_thread_waker(data: *const ()) {
+    (*(data as *const Thread)).unpark()
+}
+
+unsafe fn drop_thread_waker(data: *const ()) {
+    drop(Box::from_raw(data as *mut Thread))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sync::Arc;
+    use sync::atomic::{AtomicBool, Ordering};
+
+    // Pending until another thread, spawned on the first poll, sets `done`.
+    struct WokenFromThread {
+        done: Arc<AtomicBool>,
+        spawned: bool,
+    }
+
+    impl Future for WokenFromThread {
+        type Output = u32;
+
+        fn poll(mut self: Pin<&mut Self>, waker: &Waker) -> Poll<u32> {
+            if self.done.load(Ordering::SeqCst) {
+                return Poll::Ready(42);
+            }
+            if !self.spawned {
+                self.spawned = true;
+                let done = self.done.clone();
+                let waker = waker.clone();
+                thread::spawn(move || {
+                    done.store(true, Ordering::SeqCst);
+                    waker.wake();
+                });
+            }
+            Poll::Pending
+        }
+    }
+
+    #[test]
+    fn block_on_woken_from_other_thread() {
+        let future = WokenFromThread { done: Arc::new(AtomicBool::new(false)), spawned: false };
+        assert_eq!(block_on(future), 42);
+    }
+
+    #[test]
+    fn thread_waker_unparks() {
+        let waker = Waker::from(thread::current());
+        let clone = waker.clone();
+        drop(waker);
+        // The token set by `wake` makes `park` return immediately.
+        clone.wake();
+        thread::park();
+    }
+}
@@ -553,9 +553,96 @@ impl FromInner<time::SystemTime> for SystemTime {
     }
 }
 
+/// A timer for microbenchmarks which accounts for the cost of reading the
+/// clock.
+///
+/// Timing a very short piece of code with [`Instant`] also measures the time
+/// spent reading the clock, which can be a good part of the result. A
+/// `BenchTimer` measures that cost once per process, by reading the clock
+/// repeatedly and keeping the fastest read, and subtracts it from the
+/// elapsed time it reports.
+///
+/// The clock is the same monotonic clock as [`Instant`]'s, with the same
+/// resolution. Pair this with [`hint::black_box`] to stop the optimizer from
+/// removing the code being measured.
+///
+/// [`Instant`]: struct.Instant.html
+/// [`hint::black_box`]: ../hint/fn.black_box.html
+///
+/// # Examples
+///
+/// ```
+/// #![feature(bench_timer, bench_black_box)]
+/// use std::hint::black_box;
+/// use std::time::BenchTimer;
+///
+/// let timer = BenchTimer::start();
+/// black_box((0..1000u64).sum::<u64>());
+/// println!("summing took {:?}", timer.elapsed_minus_overhead());
+/// ```
+#[unstable(feature = "bench_timer", issue = "0")]
+#[derive(Copy, Clone, Debug)]
+pub struct BenchTimer {
+    start: Instant,
+}
+
+#[unstable(feature = "bench_timer", issue = "0")]
+impl BenchTimer {
+    /// Starts a timer at the current instant.
+    ///
+    /// The first timer started in a process calibrates the clock overhead
+    /// before reading the clock, so that the calibration isn't measured.
+    pub fn start() -> BenchTimer {
+        BenchTimer::overhead();
+        BenchTimer { start: Instant::now() }
+    }
+
+    /// Returns the time elapsed since the timer started, including the cost
+    /// of reading the clock.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Returns the time elapsed since the timer started, minus the cost of
+    /// reading the clock.
+    ///
+    /// This saturates at zero when the measured code took less time than a
+    /// clock read is estimated to.
+    pub fn elapsed_minus_overhead(&self) -> Duration {
+        self.elapsed().checked_sub(BenchTimer::overhead()).unwrap_or(Duration::from_secs(0))
+    }
+
+    /// Returns the estimated cost of reading the clock, measuring it if this
+    /// is the first call in the process.
+    pub fn overhead() -> Duration {
+        use sync::atomic::{AtomicUsize, Ordering};
+
+        // The overhead in nanoseconds plus one, or zero before calibration.
+        static OVERHEAD: AtomicUsize = AtomicUsize::new(0);
+        const ROUNDS: usize = 1000;
+
+        let overhead = match OVERHEAD.load(Ordering::Relaxed) {
+            0 => {
+                let mut fastest = Duration::from_secs(1);
+                for _ in 0..ROUNDS {
+                    let start = Instant::now();
+                    fastest = cmp::min(fastest, start.elapsed());
+                }
+                let nanos = fastest.subsec_nanos() as usize;
+                // Racing calibrations agree closely enough; keep whichever
+                // finished last.
+                OVERHEAD.store(nanos + 1, Ordering::Relaxed);
+                nanos
+            }
+            n => n - 1,
+        };
+        Duration::new(0, overhead as u32)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{Instant, SystemTime, Duration, UNIX_EPOCH};
+    use super::{BenchTimer, Instant, SystemTime, Duration, UNIX_EPOCH};
 
     macro_rules! assert_almost_eq {
         ($a:expr, $b:expr) => ({
@@ -575,6 +662,17 @@ mod tests {
         assert!(b >= a);
     }
 
+    #[test]
+    fn bench_timer_subtracts_overhead() {
+        let overhead = BenchTimer::overhead();
+        assert!(overhead < Duration::from_secs(1));
+        assert_eq!(BenchTimer::overhead(), overhead);
+
+        let timer = BenchTimer::start();
+        let minus_overhead = timer.elapsed_minus_overhead();
+        assert!(minus_overhead <= timer.elapsed());
+    }
+
     #[test]
     fn instant_elapsed() {
         let a = Instant::now();
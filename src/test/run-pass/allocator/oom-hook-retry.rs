@@ -0,0 +1,71 @@
+// run-pass
+// no-prefer-dynamic
+
+#![feature(oom_hook, try_reserve)]
+
+use std::alloc::{self, GlobalAlloc, Layout, OomAction, System};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+// Allocations of this size fail while `CACHE_FULL` is set.
+const BIG: usize = 12345;
+
+static CACHE_FULL: AtomicBool = AtomicBool::new(false);
+static HOOK_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+struct A;
+
+unsafe impl GlobalAlloc for A {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if layout.size() == BIG && CACHE_FULL.load(Ordering::SeqCst) {
+            return std::ptr::null_mut();
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: A = A;
+
+fn drop_cache(layout: Layout, retries: u32) -> OomAction {
+    assert_eq!(layout.size(), BIG);
+    assert_eq!(retries, 0);
+    HOOK_CALLS.fetch_add(1, Ordering::SeqCst);
+    CACHE_FULL.store(false, Ordering::SeqCst);
+    OomAction::Retry
+}
+
+// Unregisters itself, which must stick even though it's running.
+fn take_self(_layout: Layout, _retries: u32) -> OomAction {
+    assert!(alloc::take_oom_hook().is_some());
+    HOOK_CALLS.fetch_add(1, Ordering::SeqCst);
+    CACHE_FULL.store(false, Ordering::SeqCst);
+    OomAction::Retry
+}
+
+fn main() {
+    alloc::set_oom_hook(drop_cache);
+
+    // Fallible allocations report the failure without calling the hook.
+    CACHE_FULL.store(true, Ordering::SeqCst);
+    assert!(Vec::<u8>::new().try_reserve_exact(BIG).is_err());
+    assert_eq!(HOOK_CALLS.load(Ordering::SeqCst), 0);
+
+    // Infallible ones let the hook free memory and try again.
+    let v = Vec::<u8>::with_capacity(BIG);
+    assert_eq!(v.capacity(), BIG);
+    assert_eq!(HOOK_CALLS.load(Ordering::SeqCst), 1);
+
+    assert!(alloc::take_oom_hook().is_some());
+    assert!(alloc::take_oom_hook().is_none());
+
+    alloc::set_oom_hook(take_self);
+    CACHE_FULL.store(true, Ordering::SeqCst);
+    let v = Vec::<u8>::with_capacity(BIG);
+    assert_eq!(v.capacity(), BIG);
+    assert_eq!(HOOK_CALLS.load(Ordering::SeqCst), 2);
+    assert!(alloc::take_oom_hook().is_none());
+}
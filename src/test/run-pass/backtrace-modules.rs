@@ -0,0 +1,27 @@
+// run-pass
+// only-linux
+// compile-flags:-g
+
+#![feature(backtrace)]
+
+use std::backtrace;
+use std::env;
+
+fn marker() -> usize {
+    marker as fn() -> usize as usize
+}
+
+fn main() {
+    let modules = backtrace::modules().unwrap();
+    let exe = env::current_exe().unwrap();
+
+    // The executable is one of the loaded modules, and contains its own code.
+    let ip = marker();
+    let module = modules.iter().find(|m| m.path() == exe.as_path()).unwrap();
+    let base = module.base_address() as usize;
+    assert!(base <= ip && ip < base + module.size());
+
+    let symbols = backtrace::resolve(ip as *mut _);
+    assert!(symbols.iter().any(|s| s.name().map_or(false, |n| n.ends_with("marker"))),
+            "{:?}", symbols);
+}
@@ -0,0 +1,25 @@
+// run-pass
+
+#![feature(set_args)]
+
+use std::env;
+use std::ffi::OsString;
+
+fn main() {
+    let original: Vec<OsString> = env::args_os().collect();
+
+    let guard = unsafe { env::set_args(vec!["prog".into(), "--replaced".into()]) };
+    let mut args = env::args();
+    assert_eq!(env::args().collect::<Vec<_>>(), ["prog", "--replaced"]);
+
+    // Nested replacements put back the one before them.
+    let inner = unsafe { env::set_args(vec!["nul\0byte".into()]) };
+    assert_eq!(env::args_os().collect::<Vec<_>>(), ["nul\0byte"]);
+    drop(inner);
+    assert_eq!(env::args().collect::<Vec<_>>(), ["prog", "--replaced"]);
+
+    drop(guard);
+    assert_eq!(env::args_os().collect::<Vec<_>>(), original);
+    // An iterator made meanwhile keeps the replacement.
+    assert_eq!(args.nth(1).unwrap(), "--replaced");
+}
@@ -0,0 +1,18 @@
+// run-pass
+// only-linux
+
+#![feature(linux_auxv)]
+
+use std::os::linux::env;
+
+fn main() {
+    assert!(env::auxv().len() > 0);
+    assert!(env::auxv().all(|(key, _)| key != env::AT_NULL));
+
+    let page_size = env::page_size().unwrap();
+    assert!(page_size >= 4096 && page_size.is_power_of_two());
+    assert_eq!(env::getauxval(env::AT_PAGESZ), Some(page_size));
+
+    assert!(env::random_bytes().is_some());
+    assert_eq!(env::getauxval(!0), None);
+}
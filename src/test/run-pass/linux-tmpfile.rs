@@ -0,0 +1,39 @@
+// run-pass
+// only-linux
+
+#![feature(linux_tmpfile)]
+
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Read, Write};
+use std::os::linux::fs::{FileExt, OpenOptionsExt};
+use std::process;
+
+fn main() {
+    let dir = env::temp_dir().join(format!("linux-tmpfile-{}", process::id()));
+    fs::create_dir(&dir).unwrap();
+
+    let mut file = match OpenOptions::new().read(true).write(true).tmpfile(&dir) {
+        Ok(file) => file,
+        // The kernel is older than 3.11, or the filesystem doesn't support it.
+        Err(_) => {
+            fs::remove_dir(&dir).unwrap();
+            return
+        }
+    };
+    // It has no name, so there's nothing to see yet.
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+
+    file.write_all(b"published").unwrap();
+    let path = dir.join("published");
+    file.link_at(&path).unwrap();
+    let mut contents = String::new();
+    fs::File::open(&path).unwrap().read_to_string(&mut contents).unwrap();
+    assert_eq!(contents, "published");
+
+    // Linking over an existing name fails.
+    assert_eq!(file.link_at(&path).unwrap_err().kind(), ErrorKind::AlreadyExists);
+
+    fs::remove_file(&path).unwrap();
+    fs::remove_dir(&dir).unwrap();
+}
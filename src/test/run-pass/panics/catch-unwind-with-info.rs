@@ -0,0 +1,44 @@
+// run-pass
+// ignore-emscripten no processes
+// ignore-wasm32-bare no unwinding
+
+#![feature(catch_unwind_with_info)]
+
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static HOOK_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+fn main() {
+    assert_eq!(panic::catch_unwind_with_info(|| 1).unwrap(), 1);
+
+    let line = line!() + 2;
+    let caught = panic::catch_unwind_with_info(|| {
+        panic!("formatted {}", 42);
+    }).unwrap_err();
+    assert_eq!(caught.message(), Some("formatted 42"));
+    let location = caught.location().unwrap();
+    assert_eq!(location.file(), file!());
+    assert_eq!(location.line(), line);
+    assert!(caught.backtrace().is_some());
+    assert_eq!(caught.payload().downcast_ref::<String>().unwrap(), "formatted 42");
+
+    // Panics started by `resume_unwind` skip the hook, so only the payload is
+    // known.
+    let caught = panic::catch_unwind_with_info(|| {
+        panic::resume_unwind(Box::new("resumed"));
+    }).unwrap_err();
+    assert_eq!(caught.message(), Some("resumed"));
+    assert!(caught.location().is_none());
+    assert!(caught.backtrace().is_none());
+
+    // Custom hooks still run.
+    panic::set_hook(Box::new(|_| { HOOK_CALLS.fetch_add(1, Ordering::SeqCst); }));
+    let caught = panic::catch_unwind_with_info(|| {
+        let inner = panic::catch_unwind_with_info(|| panic!("inner")).unwrap_err();
+        assert_eq!(inner.message(), Some("inner"));
+        panic!("outer");
+    }).unwrap_err();
+    assert_eq!(caught.message(), Some("outer"));
+    assert_eq!(HOOK_CALLS.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,35 @@
+// run-pass
+// ignore-emscripten no processes
+// ignore-wasm32-bare no unwinding
+
+#![feature(panic_double_panic_policy)]
+
+use std::panic::{self, DoublePanicPolicy};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static HOOK_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+struct Bomb;
+
+impl Drop for Bomb {
+    fn drop(&mut self) {
+        panic!("second");
+    }
+}
+
+fn main() {
+    assert_eq!(panic::double_panic_policy(), DoublePanicPolicy::Abort);
+    panic::set_double_panic_policy(DoublePanicPolicy::Unwind);
+    panic::set_hook(Box::new(|_| { HOOK_CALLS.fetch_add(1, Ordering::SeqCst); }));
+
+    let result = panic::catch_unwind(|| {
+        let _bomb = Bomb;
+        panic!("first");
+    });
+
+    // The hook saw both panics, but only the second one was caught.
+    assert_eq!(HOOK_CALLS.load(Ordering::SeqCst), 2);
+    let payload = result.unwrap_err();
+    assert_eq!(*payload.downcast_ref::<&'static str>().unwrap(), "second");
+    assert!(!std::thread::panicking());
+}
@@ -0,0 +1,21 @@
+// run-pass
+// only-linux
+
+#![feature(process_set_name)]
+
+use std::env;
+use std::fs;
+use std::os::unix::process;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    process::set_name("renamed").unwrap();
+    assert_eq!(env::args().collect::<Vec<_>>(), args);
+
+    let cmdline = fs::read("/proc/self/cmdline").unwrap();
+    assert!(cmdline.starts_with(b"renamed\0"));
+    assert_eq!(fs::read_to_string("/proc/self/comm").unwrap(), "renamed\n");
+
+    assert!(process::set_name("nul\0byte").is_err());
+}
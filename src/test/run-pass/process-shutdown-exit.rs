@@ -0,0 +1,41 @@
+// run-pass
+// ignore-cloudabi no processes
+// ignore-emscripten no processes
+
+#![feature(process_shutdown)]
+
+use std::env;
+use std::process::{self, Command};
+
+fn register() {
+    process::on_shutdown(|| println!("logger flushed")).unwrap();
+    process::on_shutdown(|| println!("profiler flushed")).unwrap();
+}
+
+fn main() {
+    match env::args().nth(1).as_ref().map(|arg| &arg[..]) {
+        Some("return") => {
+            register();
+            println!("main returning");
+            return;
+        }
+        Some("exit") => {
+            register();
+            println!("exiting");
+            process::exit(3);
+        }
+        _ => {}
+    }
+
+    let this = env::args().next().unwrap();
+
+    let output = Command::new(&this).arg("return").output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(),
+               "main returning\nprofiler flushed\nlogger flushed\n");
+
+    let output = Command::new(&this).arg("exit").output().unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(),
+               "exiting\nprofiler flushed\nlogger flushed\n");
+}
@@ -0,0 +1,36 @@
+// run-pass
+// ignore-cloudabi no processes
+// ignore-emscripten no processes
+
+#![feature(process_shutdown)]
+
+use std::env;
+use std::process::{self, Command};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+fn child() {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        // Exiting while main's teardown is still running waits for it.
+        rx.recv().unwrap();
+        process::exit(0);
+    });
+    process::on_shutdown(move || {
+        tx.send(()).unwrap();
+        thread::sleep(Duration::from_millis(200));
+        println!("teardown finished");
+    }).unwrap();
+}
+
+fn main() {
+    if env::args().nth(1).map_or(false, |arg| arg == "child") {
+        return child();
+    }
+
+    let this = env::args().next().unwrap();
+    let output = Command::new(this).arg("child").output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "teardown finished\n");
+}
@@ -0,0 +1,39 @@
+// run-pass
+// ignore-cloudabi no processes
+// ignore-emscripten no processes
+
+#![feature(process_exitcode_placeholder, process_shutdown)]
+
+use std::env;
+use std::process::{self, Command, ExitCode};
+
+fn child() {
+    // Registered teardown runs last-registered first.
+    process::on_shutdown(|| {
+        println!("2");
+        // Too late to register more.
+        let late = process::on_shutdown(|| println!("registered during shutdown"));
+        println!("late registration refused: {}", late.is_err());
+    }).unwrap();
+    process::on_shutdown(|| println!("1")).unwrap();
+    // A panicking closure doesn't stop the rest.
+    process::on_shutdown(|| panic!("panicked during shutdown")).unwrap();
+    process::on_shutdown(|| println!("0")).unwrap();
+
+    ExitCode::FAILURE.exit_process();
+}
+
+fn main() {
+    if env::args().nth(1).map_or(false, |arg| arg == "child") {
+        return child();
+    }
+
+    let this = env::args().next().unwrap();
+    let output = Command::new(this).arg("child").output().unwrap();
+    // The code passed to `exit_process` is still the one the process exits
+    // with once the closures have run.
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(),
+               "0\n1\n2\nlate registration refused: true\n");
+    assert!(String::from_utf8(output.stderr).unwrap().contains("panicked during shutdown"));
+}
@@ -0,0 +1,52 @@
+// run-pass
+// ignore-cloudabi no processes
+// ignore-emscripten no processes
+// ignore-windows
+// ignore-vxworks no fd mappings
+
+#![feature(process_fd_mappings)]
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{ErrorKind, Write};
+use std::os::unix::io::AsRawFd;
+use std::os::unix::process::CommandExt;
+use std::process::Command;
+
+fn create(name: &str, contents: &str) -> File {
+    let path = env::temp_dir().join(format!("process-fd-mappings-{}-{}", std::process::id(), name));
+    File::create(&path).unwrap().write_all(contents.as_bytes()).unwrap();
+    let file = File::open(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    file
+}
+
+fn main() {
+    let a = create("a", "a");
+    let b = create("b", "b");
+    let (fa, fb) = (a.as_raw_fd(), b.as_raw_fd());
+
+    // Swap the two descriptors in the child.
+    let out = Command::new("sh").arg("-c").arg(format!("cat <&{}; cat <&{}", fa, fb))
+        .fd_mappings(&[(fa, fb), (fb, fa)])
+        .output().unwrap();
+    assert!(out.status.success());
+    assert_eq!(out.stdout, b"ba");
+
+    // A mapping onto stdin wins over the stdio configuration.
+    let c = create("c", "c");
+    let out = Command::new("cat").fd_mappings(&[(c.as_raw_fd(), 0)]).output().unwrap();
+    assert!(out.status.success());
+    assert_eq!(out.stdout, b"c");
+
+    // Mapping over every low descriptor, whichever one the spawn error pipe
+    // is using, still lets an exec failure be reported.
+    let low = (3..64).map(|fd| (fa, fd)).collect::<Vec<_>>();
+    let err = Command::new("/nonexistent/program").fd_mappings(&low).spawn().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+
+    let err = Command::new("true").fd_mappings(&[(fa, 5), (fb, 5)]).spawn().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = Command::new("true").fd_mappings(&[(-1, 5)]).spawn().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
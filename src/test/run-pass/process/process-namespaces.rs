@@ -0,0 +1,37 @@
+// run-pass
+// only-linux
+
+#![feature(linux_namespaces, rustc_private)]
+
+extern crate libc;
+
+use std::io::ErrorKind;
+use std::os::linux::process::CommandExt;
+use std::process::Command;
+
+fn main() {
+    let err = Command::new("true").unshare(libc::CLONE_VM).spawn().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    // The child is the init of its new pid namespace.
+    let output = Command::new("sh")
+        .arg("-c").arg("echo $$")
+        .unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWPID)
+        .output();
+    match output {
+        Ok(output) => assert_eq!(output.stdout, b"1\n"),
+        // The kernel is older than 5.3, or doesn't let us have user
+        // namespaces.
+        Err(e) => match e.raw_os_error() {
+            Some(libc::ENOSYS) | Some(libc::EPERM) | Some(libc::EINVAL) |
+            Some(libc::ENOSPC) => {}
+            _ => panic!("unexpected error: {}", e),
+        },
+    }
+
+    // Without the privileges to change root, it's the child which fails.
+    if unsafe { libc::geteuid() } != 0 {
+        let err = Command::new("true").chroot("/").status().unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::EPERM));
+    }
+}
@@ -0,0 +1,48 @@
+// run-pass
+// only-linux
+// ignore-android
+
+#![feature(parent_death_signal, rustc_private)]
+
+extern crate libc;
+
+use std::env;
+use std::io::Read;
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+fn main() {
+    if env::args().nth(1).as_ref().map(|s| &**s) == Some("middle") {
+        // Leave behind a child which should be killed as we exit.
+        let child = Command::new("sleep")
+            .arg("100")
+            .stdout(Stdio::null())
+            .parent_death_signal(libc::SIGKILL)
+            .spawn()
+            .unwrap();
+        print!("{}", child.id());
+        return
+    }
+
+    let mut middle = Command::new(env::current_exe().unwrap())
+        .arg("middle")
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut pid = String::new();
+    middle.stdout.take().unwrap().read_to_string(&mut pid).unwrap();
+    assert!(middle.wait().unwrap().success());
+    let pid: libc::pid_t = pid.parse().unwrap();
+
+    // Once killed, the orphan is reaped by init, after which it's gone.
+    for _ in 0..100 {
+        if unsafe { libc::kill(pid, 0) } != 0 {
+            return
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    unsafe { libc::kill(pid, libc::SIGKILL); }
+    panic!("the orphaned child was not killed");
+}
@@ -0,0 +1,31 @@
+// run-pass
+// only-linux
+
+#![feature(linux_pidfd)]
+
+use std::io::ErrorKind;
+use std::os::linux::process::{ChildExt, CommandExt};
+use std::os::unix::process::{CommandExt as UnixCommandExt, ExitStatusExt};
+use std::process::Command;
+
+fn main() {
+    let mut child = Command::new("sleep").arg("100").spawn().unwrap();
+    assert!(child.pidfd().is_err());
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    // The child is started with clone3, where it can't run closures.
+    let err = Command::new("true").create_pidfd(true).before_exec(|| Ok(())).spawn().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    let mut child = match Command::new("sleep").arg("100").create_pidfd(true).spawn() {
+        Ok(child) => child,
+        // The kernel is older than 5.3.
+        Err(_) => return,
+    };
+    let pidfd = child.take_pidfd().unwrap();
+    assert!(child.pidfd().is_err());
+    pidfd.kill().unwrap();
+    assert_eq!(child.wait().unwrap().signal(), Some(9));
+    assert!(pidfd.kill().is_err());
+}
@@ -0,0 +1,32 @@
+// run-pass
+// ignore-cloudabi no processes
+// ignore-emscripten no processes
+// ignore-windows
+// ignore-fuchsia no process groups
+// ignore-vxworks no process groups
+
+#![feature(process_session, rustc_private)]
+
+extern crate libc;
+
+use std::os::unix::process::CommandExt;
+use std::process::Command;
+
+fn main() {
+    let mut child = Command::new("sleep").arg("100").process_group(0).spawn().unwrap();
+    let pid = child.id() as libc::pid_t;
+    assert_eq!(unsafe { libc::getpgid(pid) }, pid);
+    assert_eq!(unsafe { libc::getsid(pid) }, unsafe { libc::getsid(0) });
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    let mut child = Command::new("sleep").arg("100").new_session(true).spawn().unwrap();
+    let pid = child.id() as libc::pid_t;
+    assert_eq!(unsafe { libc::getsid(pid) }, pid);
+    assert_eq!(unsafe { libc::getpgid(pid) }, pid);
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    // A session leader can't join another group.
+    assert!(Command::new("true").new_session(true).process_group(0).spawn().is_err());
+}
@@ -0,0 +1,26 @@
+// run-pass
+// ignore-cloudabi no processes
+// ignore-emscripten no processes
+// ignore-windows
+
+#![feature(process_set_argv0)]
+
+use std::os::unix::process::CommandExt;
+use std::process::Command;
+
+fn main() {
+    // `sh -c` sets `$0` to its own argv[0] when given no more arguments.
+    let out = Command::new("/bin/sh").arg0("custom-name").arg("-c").arg("echo $0")
+        .output().unwrap();
+    assert!(out.status.success());
+    assert_eq!(out.stdout, b"custom-name\n");
+
+    // The program is still found through PATH under its own name.
+    let out = Command::new("sh").arg0("/nonexistent/sh").arg("-c").arg("echo $0")
+        .output().unwrap();
+    assert!(out.status.success());
+    assert_eq!(out.stdout, b"/nonexistent/sh\n");
+
+    let cmd = format!("{:?}", Command::new("sh").arg0("custom-name").arg("x"));
+    assert_eq!(cmd, r#"["sh"] "custom-name" "x""#);
+}
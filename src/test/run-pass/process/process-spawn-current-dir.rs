@@ -0,0 +1,23 @@
+// run-pass
+// ignore-cloudabi no processes
+// ignore-emscripten no processes
+// ignore-windows
+
+use std::env;
+use std::io::ErrorKind;
+use std::process::Command;
+
+fn main() {
+    let out = Command::new("pwd").current_dir("/").output().unwrap();
+    assert!(out.status.success());
+    assert_eq!(out.stdout, b"/\n");
+
+    // A relative program is found relative to the new directory.
+    let sh = Command::new("bin/sh").current_dir("/").arg("-c").arg("exit 3")
+        .status().unwrap();
+    assert_eq!(sh.code(), Some(3));
+
+    let missing = env::temp_dir().join("process-spawn-current-dir-missing");
+    let err = Command::new("pwd").current_dir(&missing).spawn().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+}
@@ -0,0 +1,36 @@
+// run-pass
+// only-linux
+
+#![feature(rt_init_hooks)]
+
+use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT: AtomicUsize = AtomicUsize::new(1);
+static EARLY: AtomicUsize = AtomicUsize::new(0);
+static DEFAULT: AtomicUsize = AtomicUsize::new(0);
+static LATE: AtomicUsize = AtomicUsize::new(0);
+
+fn late() {
+    LATE.store(NEXT.fetch_add(1, Ordering::SeqCst), Ordering::SeqCst);
+}
+
+fn early() {
+    // The runtime is already set up, so arguments are available.
+    assert!(env::args().next().is_some());
+    EARLY.store(NEXT.fetch_add(1, Ordering::SeqCst), Ordering::SeqCst);
+}
+
+fn default_priority() {
+    DEFAULT.store(NEXT.fetch_add(1, Ordering::SeqCst), Ordering::SeqCst);
+}
+
+std::register_init!(late, 10);
+std::register_init!(early, -10);
+std::register_init!(default_priority);
+
+fn main() {
+    assert_eq!(EARLY.load(Ordering::SeqCst), 1);
+    assert_eq!(DEFAULT.load(Ordering::SeqCst), 2);
+    assert_eq!(LATE.load(Ordering::SeqCst), 3);
+}
@@ -0,0 +1,44 @@
+// run-pass
+// only-linux
+
+#![feature(rustc_private, unix_file_advise)]
+
+extern crate libc;
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::os::unix::fs::{Advice, FileExt};
+use std::os::unix::io::FromRawFd;
+use std::process;
+
+fn main() {
+    let path = env::temp_dir().join(format!("unix-file-advise-{}", process::id()));
+    let data = vec![0x5a; 1 << 16];
+    File::create(&path).unwrap().write_all(&data).unwrap();
+
+    let mut file = File::open(&path).unwrap();
+    for &advice in &[Advice::Normal, Advice::Sequential, Advice::Random,
+                     Advice::NoReuse, Advice::WillNeed, Advice::DontNeed] {
+        file.advise(.., advice).unwrap();
+        file.advise(4096..8192, advice).unwrap();
+        file.advise(4096..=8191, advice).unwrap();
+        file.advise(1 << 20.., advice).unwrap();
+    }
+    // An empty range is no advice at all, rather than advice for the rest
+    // of the file.
+    file.advise(10..10, Advice::WillNeed).unwrap();
+
+    // It's only a hint, so the data is still all there.
+    let mut contents = Vec::new();
+    file.read_to_end(&mut contents).unwrap();
+    assert!(contents == data);
+
+    let mut fds = [0; 2];
+    assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+    let (read, _write) = unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) };
+    let err = read.advise(.., Advice::Sequential).unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::ESPIPE));
+
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,48 @@
+// run-pass
+// only-linux
+
+#![feature(unix_file_seek_hole)]
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{Seek, SeekFrom, Write};
+use std::os::unix::fs::{FileExt, MetadataExt};
+use std::process;
+
+fn main() {
+    let dir = env::temp_dir().join(format!("unix-file-seek-hole-{}", process::id()));
+    fs::create_dir(&dir).unwrap();
+    let path = dir.join("sparse");
+    let len = 4 << 20;
+
+    {
+        let mut file = File::create(&path).unwrap();
+        file.set_len(len).unwrap();
+        file.seek(SeekFrom::Start(1 << 20)).unwrap();
+        file.write_all(&[0x5a; 4096]).unwrap();
+    }
+
+    let mut file = File::open(&path).unwrap();
+    let data = file.seek_data(0).unwrap().unwrap();
+    assert!(data <= 1 << 20);
+    assert_eq!(file.seek(SeekFrom::Current(0)).unwrap(), data);
+    let hole = file.seek_hole(data).unwrap().unwrap();
+    assert!(hole >= (1 << 20) + 4096 && hole <= len);
+    // The last byte is in a hole, unless the filesystem doesn't keep track
+    // of them, and there's nothing from the end on.
+    let last = file.seek_hole(len - 1).unwrap().unwrap();
+    assert!(last == len - 1 || last == len);
+    assert_eq!(file.seek_data(len).unwrap(), None);
+    assert_eq!(file.seek_hole(len).unwrap(), None);
+    assert_eq!(file.seek_data(u64::max_value()).unwrap(), None);
+
+    let copy = dir.join("copy");
+    assert_eq!(fs::copy(&path, &copy).unwrap(), len);
+    assert_eq!(fs::read(&path).unwrap(), fs::read(&copy).unwrap());
+    // Where the filesystem keeps the holes, the copy should too.
+    if fs::metadata(&path).unwrap().blocks() * 512 < len {
+        assert!(fs::metadata(&copy).unwrap().blocks() * 512 < len);
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
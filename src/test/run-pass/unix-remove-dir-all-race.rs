@@ -0,0 +1,53 @@
+// run-pass
+// only-linux
+
+// Swapping a directory for a symlink while `remove_dir_all` is removing it
+// mustn't make it remove anything the symlink points to.
+
+use std::env;
+use std::fs::{self, File};
+use std::os::unix::fs::symlink;
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+fn main() {
+    let base = env::temp_dir().join(format!("unix-remove-dir-all-race-{}", process::id()));
+    let outside = base.join("outside");
+    let canary = outside.join("canary");
+    fs::create_dir_all(&outside).unwrap();
+    File::create(&canary).unwrap();
+
+    for i in 0..100 {
+        let root = base.join(format!("root{}", i));
+        let victim = root.join("victim");
+        fs::create_dir_all(&victim).unwrap();
+        for j in 0..100 {
+            File::create(victim.join(format!("file{}", j))).unwrap();
+        }
+
+        let done = Arc::new(AtomicBool::new(false));
+        let swapper = {
+            let done = done.clone();
+            let outside = outside.clone();
+            let saved = root.with_file_name(format!("saved{}", i));
+            thread::spawn(move || {
+                while !done.load(Ordering::SeqCst) {
+                    if fs::rename(&victim, &saved).is_ok() {
+                        let _ = symlink(&outside, &victim);
+                        break;
+                    }
+                }
+            })
+        };
+        // Failing is fine, as long as nothing outside the tree goes.
+        let _ = fs::remove_dir_all(&root);
+        done.store(true, Ordering::SeqCst);
+        swapper.join().unwrap();
+
+        assert!(canary.exists(), "removed the target of a symlink on iteration {}", i);
+    }
+
+    fs::remove_dir_all(&base).unwrap();
+}
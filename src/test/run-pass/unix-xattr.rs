@@ -0,0 +1,47 @@
+// run-pass
+// only-linux
+
+#![feature(rustc_private, unix_xattr)]
+
+extern crate libc;
+
+use std::env;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::os::unix::fs::{self as unix_fs, FileExt};
+use std::process;
+
+fn main() {
+    let path = env::temp_dir().join(format!("unix-xattr-{}", process::id()));
+    let file = File::create(&path).unwrap();
+
+    match file.set_xattr("user.test", b"value") {
+        Ok(()) => {}
+        // The filesystem doesn't support user attributes.
+        Err(ref e) if e.raw_os_error() == Some(libc::EOPNOTSUPP) => {
+            fs::remove_file(&path).unwrap();
+            return
+        }
+        Err(e) => panic!("unexpected error: {}", e),
+    }
+    assert_eq!(file.get_xattr("user.test").unwrap(), Some(b"value".to_vec()));
+    assert_eq!(unix_fs::get_xattr(&path, "user.test").unwrap(), Some(b"value".to_vec()));
+    assert_eq!(file.get_xattr("user.missing").unwrap(), None);
+
+    // A value bigger than a first guess at the size would be.
+    let big = vec![b'x'; 3000];
+    unix_fs::set_xattr(&path, "user.big", &big).unwrap();
+    assert_eq!(file.get_xattr("user.big").unwrap(), Some(big));
+
+    let names = file.list_xattr().unwrap();
+    assert!(names.contains(&OsString::from("user.test")));
+    assert!(names.contains(&OsString::from("user.big")));
+
+    file.remove_xattr("user.test").unwrap();
+    unix_fs::remove_xattr(&path, "user.big").unwrap();
+    assert_eq!(unix_fs::get_xattr(&path, "user.test").unwrap(), None);
+    assert!(file.remove_xattr("user.test").is_err());
+    assert!(!unix_fs::list_xattr(&path).unwrap().contains(&OsString::from("user.big")));
+
+    fs::remove_file(&path).unwrap();
+}